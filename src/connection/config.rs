@@ -2,8 +2,15 @@
 //!
 //! DFC server configuration data structures and persistence.
 
+use super::{
+    ProxyConfig, PulsarAuthMode, PulsarSecurityConfig, SafetyLevel, ServerEnvironment,
+    SshTunnelConfig,
+};
 use crate::error::{Error, Result};
-use crate::helpers::{decrypt, encrypt, get_or_create_config_dir};
+use crate::helpers::{
+    StagedFile, decrypt, encrypt, encrypt_for_vault, get_or_create_config_dir,
+    is_legacy_cipher_text,
+};
 use serde::{Deserialize, Serialize};
 use smol::fs;
 use std::collections::hash_map::DefaultHasher;
@@ -11,6 +18,9 @@ use std::hash::{Hash, Hasher};
 use std::path::PathBuf;
 use tracing::info;
 
+/// `(host, port)` pairs
+pub type HostPorts = Vec<(String, u16)>;
+
 fn normalize_optional(value: Option<&str>) -> Option<&str> {
     value.map(str::trim).filter(|v| !v.is_empty())
 }
@@ -36,6 +46,49 @@ pub struct DfcServerConfig {
     pub pulsar_token: Option<String>,
     /// Last update timestamp (RFC3339)
     pub updated_at: Option<String>,
+    /// How writes and publishing against the server are guarded
+    #[serde(default)]
+    pub safety: SafetyLevel,
+    /// Redis logical database index (ignored in cluster mode)
+    #[serde(default)]
+    pub db: u8,
+    /// Connect to Redis over TLS
+    #[serde(default)]
+    pub tls: bool,
+    /// PEM file of a custom CA (system roots otherwise)
+    pub tls_ca_file: Option<String>,
+    /// PEM file of the client certificate for mutual TLS
+    pub tls_cert_file: Option<String>,
+    /// PEM file of the client private key for mutual TLS
+    pub tls_key_file: Option<String>,
+    /// Accept any server certificate (lab setups only)
+    #[serde(default)]
+    pub tls_skip_verify: bool,
+    /// Sentinel master name; host/port then address a Sentinel
+    pub sentinel_master: Option<String>,
+    /// Additional Sentinel nodes (`host:port`, comma or whitespace separated)
+    pub sentinel_nodes: Option<String>,
+    /// Connect directly even when a global proxy is set
+    #[serde(default)]
+    pub proxy_direct: bool,
+    /// SSH tunnel carrying the Redis and Pulsar connections
+    #[serde(default)]
+    pub ssh_tunnel: Option<SshTunnelConfig>,
+    /// Proxy for this server, used instead of the global proxy
+    #[serde(default)]
+    pub proxy: Option<ProxyConfig>,
+    /// Pulsar authentication mode and TLS options
+    #[serde(default)]
+    pub pulsar_security: Option<PulsarSecurityConfig>,
+    /// Group (folder) the server is listed under on the home page
+    #[serde(default)]
+    pub group: Option<String>,
+    /// Free-form tags for filtering
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Deployment environment label
+    #[serde(default)]
+    pub environment: Option<ServerEnvironment>,
 }
 
 /// TOML wrapper structure for server list
@@ -71,6 +124,36 @@ impl DfcServerConfig {
         }
     }
 
+    /// Sentinel master name and Sentinel nodes, `host:port` first, when the
+    /// server is reached through Sentinel
+    pub fn sentinel(&self) -> Result<Option<(String, HostPorts)>> {
+        let Some(master) = normalize_optional(self.sentinel_master.as_deref()) else {
+            return Ok(None);
+        };
+        let mut nodes = vec![(self.host.trim().to_string(), self.port)];
+        for node in parse_host_list(self.sentinel_nodes.as_deref().unwrap_or_default())? {
+            if !nodes.contains(&node) {
+                nodes.push(node);
+            }
+        }
+        Ok(Some((master.to_string(), nodes)))
+    }
+
+    /// Proxy used to reach `host`: the server's own proxy, else the global
+    /// one unless `proxy_direct`, and `None` when the host is bypassed
+    pub fn effective_proxy<'a>(
+        &'a self,
+        global: Option<&'a ProxyConfig>,
+        host: &str,
+    ) -> Option<&'a ProxyConfig> {
+        let proxy = match &self.proxy {
+            Some(proxy) if !proxy.url.trim().is_empty() => proxy,
+            _ if self.proxy_direct => return None,
+            _ => global.filter(|proxy| !proxy.url.trim().is_empty())?,
+        };
+        (!proxy.bypasses(host)).then_some(proxy)
+    }
+
     /// Compare server configurations by uniqueness fields only.
     pub fn same_config_for_uniqueness(&self, other: &Self) -> bool {
         self.host.trim() == other.host.trim()
@@ -81,7 +164,44 @@ impl DfcServerConfig {
                 == normalize_optional(other.device_filter.as_deref())
             && normalize_optional(self.pulsar_token.as_deref())
                 == normalize_optional(other.pulsar_token.as_deref())
+            && self.db == other.db
+            && normalize_optional(self.sentinel_master.as_deref())
+                == normalize_optional(other.sentinel_master.as_deref())
+            && self.pulsar_auth_mode() == other.pulsar_auth_mode()
     }
+
+    /// Pulsar authentication mode (static token unless configured)
+    pub fn pulsar_auth_mode(&self) -> PulsarAuthMode {
+        self.pulsar_security
+            .as_ref()
+            .map(|security| security.auth_mode)
+            .unwrap_or_default()
+    }
+}
+
+/// Parse a `host:port` list separated by commas or whitespace; the port
+/// defaults to 26379 (Sentinel)
+pub fn parse_host_list(text: &str) -> Result<HostPorts> {
+    text.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let (host, port) = match part.rsplit_once(':') {
+                Some((host, port)) => {
+                    let port = port.parse().map_err(|_| Error::Invalid {
+                        message: format!("Invalid port in \"{part}\""),
+                    })?;
+                    (host, port)
+                }
+                None => (part, 26379),
+            };
+            if host.is_empty() {
+                return Err(Error::Invalid {
+                    message: format!("Missing host in \"{part}\""),
+                });
+            }
+            Ok((host.to_string(), port))
+        })
+        .collect()
 }
 
 /// Get or create the server configuration file path
@@ -99,8 +219,28 @@ fn get_server_config_path() -> Result<PathBuf> {
     Ok(path)
 }
 
-/// Load all server configurations from file
-pub fn get_servers() -> Result<Vec<DfcServerConfig>> {
+/// Every secret field of `server`, set or not
+pub fn secret_slots_mut(server: &mut DfcServerConfig) -> Vec<&mut Option<String>> {
+    let mut slots = vec![&mut server.password, &mut server.pulsar_token];
+    slots.extend(server.ssh_tunnel.as_mut().map(|ssh| &mut ssh.password));
+    slots.extend(server.proxy.as_mut().map(|proxy| &mut proxy.password));
+    if let Some(security) = server.pulsar_security.as_mut() {
+        slots.push(&mut security.password);
+        slots.push(&mut security.oauth2_client_secret);
+    }
+    slots
+}
+
+/// Secrets of a server that are stored encrypted
+fn secrets_mut(server: &mut DfcServerConfig) -> Vec<&mut String> {
+    secret_slots_mut(server)
+        .into_iter()
+        .filter_map(Option::as_mut)
+        .collect()
+}
+
+/// Server configurations as stored, secrets still encrypted
+fn read_servers() -> Result<Vec<DfcServerConfig>> {
     let path = get_server_config_path()?;
     let value = std::fs::read_to_string(&path)?;
 
@@ -109,44 +249,80 @@ pub fn get_servers() -> Result<Vec<DfcServerConfig>> {
     }
 
     let configs: DfcServers = toml::from_str(&value)?;
-    let mut servers = configs.servers;
+    Ok(configs.servers)
+}
+
+/// Load all server configurations from file
+pub fn get_servers() -> Result<Vec<DfcServerConfig>> {
+    let mut servers = read_servers()?;
 
-    // Decrypt sensitive fields
+    // Decrypt sensitive fields; values that do not decrypt (vault locked,
+    // hand-edited plain text) are kept as stored
     for server in servers.iter_mut() {
-        if let Some(pwd) = &server.password {
-            server.password = Some(decrypt(pwd).unwrap_or_else(|_| pwd.clone()));
-        }
-        if let Some(token) = &server.pulsar_token {
-            server.pulsar_token = Some(decrypt(token).unwrap_or_else(|_| token.clone()));
+        for secret in secrets_mut(server) {
+            *secret = decrypt(secret).unwrap_or_else(|_| secret.clone());
         }
     }
 
     Ok(servers)
 }
 
-/// Save server configurations to file
-pub async fn save_servers(mut servers: Vec<DfcServerConfig>) -> Result<()> {
-    // Encrypt sensitive fields
+/// Whether the server file holds secrets not yet encrypted with the vault key
+pub fn servers_have_legacy_secrets() -> Result<bool> {
+    Ok(read_servers()?.iter_mut().any(|server| {
+        secrets_mut(server)
+            .into_iter()
+            .any(|s| is_legacy_cipher_text(s))
+    }))
+}
+
+/// Serialize servers with their secrets encrypted
+fn encode_servers(servers: Vec<DfcServerConfig>) -> Result<String> {
+    encode_servers_with(servers, encrypt)
+}
+
+/// Serialize servers with their secrets encrypted by `seal`
+fn encode_servers_with(
+    mut servers: Vec<DfcServerConfig>,
+    seal: impl Fn(&str) -> Result<String>,
+) -> Result<String> {
     for server in servers.iter_mut() {
-        if let Some(pwd) = &server.password {
-            if !pwd.is_empty() {
-                server.password = Some(encrypt(pwd)?);
-            }
-        }
-        if let Some(token) = &server.pulsar_token {
-            if !token.is_empty() {
-                server.pulsar_token = Some(encrypt(token)?);
+        for secret in secrets_mut(server) {
+            if !secret.is_empty() {
+                *secret = seal(secret)?;
             }
         }
     }
+    Ok(toml::to_string_pretty(&DfcServers { servers })?)
+}
+
+/// Server file content with secrets encrypted by a vault key that is not
+/// installed yet; stage it with `stage_encoded_servers` before the switch
+pub fn encode_servers_for_key(servers: Vec<DfcServerConfig>, key: &[u8; 32]) -> Result<String> {
+    encode_servers_with(servers, |secret| encrypt_for_vault(key, secret))
+}
 
+/// Stage server file content from `encode_servers_for_key`
+pub fn stage_encoded_servers(content: &str) -> Result<StagedFile> {
+    StagedFile::write(get_server_config_path()?, content)
+}
+
+/// Save server configurations to file
+pub async fn save_servers(servers: Vec<DfcServerConfig>) -> Result<()> {
+    let content = encode_servers(servers)?;
     let path = get_server_config_path()?;
-    let content = toml::to_string_pretty(&DfcServers { servers })?;
     fs::write(&path, content).await?;
 
     Ok(())
 }
 
+/// Save server configurations to file from a synchronous context
+pub fn save_servers_blocking(servers: Vec<DfcServerConfig>) -> Result<()> {
+    let content = encode_servers(servers)?;
+    std::fs::write(get_server_config_path()?, content)?;
+    Ok(())
+}
+
 /// Get a single server configuration by ID
 pub fn get_server_by_id(id: &str) -> Result<DfcServerConfig> {
     let servers = get_servers()?;
@@ -160,7 +336,7 @@ pub fn get_server_by_id(id: &str) -> Result<DfcServerConfig> {
 
 #[cfg(test)]
 mod tests {
-    use super::DfcServerConfig;
+    use super::{DfcServerConfig, ProxyConfig, parse_host_list};
 
     fn sample_server() -> DfcServerConfig {
         DfcServerConfig {
@@ -173,6 +349,7 @@ mod tests {
             device_filter: None,
             pulsar_token: Some("token-a".to_string()),
             updated_at: Some("2026-04-14T12:00:00+08:00".to_string()),
+            ..Default::default()
         }
     }
 
@@ -197,4 +374,55 @@ mod tests {
 
         assert!(!left.same_config_for_uniqueness(&right));
     }
+
+    #[test]
+    fn effective_proxy_prefers_server_proxy_and_honours_bypass() {
+        let global = ProxyConfig {
+            url: "http://proxy.corp:3128".to_string(),
+            bypass: Some("10.15.0.0/16".to_string()),
+            ..Default::default()
+        };
+        let mut server = sample_server();
+        assert_eq!(
+            server.effective_proxy(Some(&global), "10.20.0.1"),
+            Some(&global)
+        );
+        assert_eq!(server.effective_proxy(Some(&global), &server.host), None);
+
+        server.proxy_direct = true;
+        assert_eq!(server.effective_proxy(Some(&global), "10.20.0.1"), None);
+
+        let own = ProxyConfig {
+            url: "socks5://jump.corp:1080".to_string(),
+            ..Default::default()
+        };
+        server.proxy = Some(own.clone());
+        assert_eq!(
+            server.effective_proxy(Some(&global), &server.host),
+            Some(&own)
+        );
+    }
+
+    #[test]
+    fn sentinel_nodes_start_with_host_and_default_port() {
+        let mut server = sample_server();
+        assert_eq!(server.sentinel().expect("no sentinel"), None);
+
+        server.sentinel_master = Some(" mymaster ".to_string());
+        server.sentinel_nodes =
+            Some("10.15.200.122:26380, 10.15.200.123\n10.15.200.121:15000".to_string());
+        assert_eq!(
+            server.sentinel().expect("sentinel"),
+            Some((
+                "mymaster".to_string(),
+                vec![
+                    ("10.15.200.121".to_string(), 15000),
+                    ("10.15.200.122".to_string(), 26380),
+                    ("10.15.200.123".to_string(), 26379),
+                ]
+            ))
+        );
+        assert!(parse_host_list("host:port").is_err());
+        assert!(parse_host_list(":26379").is_err());
+    }
 }
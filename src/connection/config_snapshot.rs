@@ -0,0 +1,342 @@
+//! Config Snapshots
+//!
+//! Timestamped copies of the CMC iothub configs loaded from a server, and the
+//! diff between two of them: added or removed configs, agents and topics,
+//! visibility flags and service URLs.
+
+use super::redis_config::{ConfigItem, TopicDetail};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Configs of one server as loaded at one point in time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigSnapshot {
+    /// Local load time (RFC 3339)
+    pub taken_at: String,
+    pub server_id: String,
+    pub server_name: String,
+    pub configs: Vec<ConfigItem>,
+}
+
+impl ConfigSnapshot {
+    /// Snapshot of configs loaded just now
+    pub fn new(server_id: &str, server_name: &str, configs: Vec<ConfigItem>) -> Self {
+        Self {
+            taken_at: chrono::Local::now().to_rfc3339(),
+            server_id: server_id.to_string(),
+            server_name: server_name.to_string(),
+            configs,
+        }
+    }
+}
+
+/// What a config change affects
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigChangeSubject {
+    /// A whole config key
+    Config,
+    /// Service URL of a config
+    ServiceUrl,
+    /// Topic of a config
+    Topic,
+    /// TopicAgentId of a config
+    Agent,
+    /// Topic of a TopicAgentId
+    AgentTopic,
+    /// Visibility flag of a topic
+    Visibility,
+}
+
+impl ConfigChangeSubject {
+    /// i18n key of the subject label
+    pub fn label_key(self) -> &'static str {
+        match self {
+            Self::Config => "config_history.subject_config",
+            Self::ServiceUrl => "config_history.subject_service_url",
+            Self::Topic => "config_history.subject_topic",
+            Self::Agent => "config_history.subject_agent",
+            Self::AgentTopic => "config_history.subject_agent_topic",
+            Self::Visibility => "config_history.subject_visibility",
+        }
+    }
+}
+
+/// Direction of a config change
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// One difference between two snapshots
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigChange {
+    /// Config key (Redis key path) the change belongs to
+    pub source: String,
+    pub subject: ConfigChangeSubject,
+    pub kind: ConfigChangeKind,
+    /// Topic path, agent ID or `agent topic`; empty for config-level changes
+    pub name: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+/// Diff two config lists, matching configs by source key, topics by path and
+/// agents by ID
+pub fn diff_configs(old: &[ConfigItem], new: &[ConfigItem]) -> Vec<ConfigChange> {
+    let old_by_source: BTreeMap<&str, &ConfigItem> =
+        old.iter().map(|c| (c.source.as_str(), c)).collect();
+    let new_by_source: BTreeMap<&str, &ConfigItem> =
+        new.iter().map(|c| (c.source.as_str(), c)).collect();
+    let mut changes = Vec::new();
+
+    for (source, old_config) in &old_by_source {
+        if !new_by_source.contains_key(source) {
+            changes.push(change(
+                old_config,
+                ConfigChangeSubject::Config,
+                ConfigChangeKind::Removed,
+                String::new(),
+                Some(old_config.service_url.clone()),
+                None,
+            ));
+        }
+    }
+    for (source, new_config) in &new_by_source {
+        let Some(old_config) = old_by_source.get(source) else {
+            changes.push(change(
+                new_config,
+                ConfigChangeSubject::Config,
+                ConfigChangeKind::Added,
+                String::new(),
+                None,
+                Some(new_config.service_url.clone()),
+            ));
+            continue;
+        };
+        diff_config(old_config, new_config, &mut changes);
+    }
+    changes
+}
+
+/// Diff two versions of the same config key
+fn diff_config(old: &ConfigItem, new: &ConfigItem, changes: &mut Vec<ConfigChange>) {
+    if old.service_url != new.service_url {
+        changes.push(change(
+            new,
+            ConfigChangeSubject::ServiceUrl,
+            ConfigChangeKind::Changed,
+            String::new(),
+            Some(old.service_url.clone()),
+            Some(new.service_url.clone()),
+        ));
+    }
+
+    let old_topics = old.details.iter().map(|d| (d.path.as_str(), d.visibility));
+    let new_topics = new.details.iter().map(|d| (d.path.as_str(), d.visibility));
+    diff_topics(
+        new,
+        ConfigChangeSubject::Topic,
+        "",
+        old_topics.collect(),
+        new_topics.collect(),
+        changes,
+    );
+
+    let old_agents: BTreeMap<&str, &[TopicDetail]> = old
+        .topic_agents
+        .iter()
+        .map(|a| (a.agent_id.as_str(), a.topics.as_slice()))
+        .collect();
+    let new_agents: BTreeMap<&str, &[TopicDetail]> = new
+        .topic_agents
+        .iter()
+        .map(|a| (a.agent_id.as_str(), a.topics.as_slice()))
+        .collect();
+    for agent_id in old_agents.keys().filter(|id| !new_agents.contains_key(*id)) {
+        changes.push(change(
+            new,
+            ConfigChangeSubject::Agent,
+            ConfigChangeKind::Removed,
+            agent_id.to_string(),
+            None,
+            None,
+        ));
+    }
+    for (agent_id, new_topics) in &new_agents {
+        let Some(old_topics) = old_agents.get(agent_id) else {
+            changes.push(change(
+                new,
+                ConfigChangeSubject::Agent,
+                ConfigChangeKind::Added,
+                agent_id.to_string(),
+                None,
+                None,
+            ));
+            continue;
+        };
+        diff_topics(
+            new,
+            ConfigChangeSubject::AgentTopic,
+            agent_id,
+            old_topics
+                .iter()
+                .map(|t| (t.path.as_str(), t.visibility))
+                .collect(),
+            new_topics
+                .iter()
+                .map(|t| (t.path.as_str(), t.visibility))
+                .collect(),
+            changes,
+        );
+    }
+}
+
+/// Diff topic paths and their visibility flags; `owner` prefixes the names
+fn diff_topics(
+    config: &ConfigItem,
+    subject: ConfigChangeSubject,
+    owner: &str,
+    old: BTreeMap<&str, bool>,
+    new: BTreeMap<&str, bool>,
+    changes: &mut Vec<ConfigChange>,
+) {
+    let name = |path: &str| {
+        if owner.is_empty() {
+            path.to_string()
+        } else {
+            format!("{owner} {path}")
+        }
+    };
+    for path in old.keys().filter(|path| !new.contains_key(*path)) {
+        changes.push(change(
+            config,
+            subject,
+            ConfigChangeKind::Removed,
+            name(path),
+            None,
+            None,
+        ));
+    }
+    for (path, visibility) in &new {
+        match old.get(path) {
+            None => changes.push(change(
+                config,
+                subject,
+                ConfigChangeKind::Added,
+                name(path),
+                None,
+                None,
+            )),
+            Some(old_visibility) if old_visibility != visibility => changes.push(change(
+                config,
+                ConfigChangeSubject::Visibility,
+                ConfigChangeKind::Changed,
+                name(path),
+                Some(old_visibility.to_string()),
+                Some(visibility.to_string()),
+            )),
+            Some(_) => {}
+        }
+    }
+}
+
+fn change(
+    config: &ConfigItem,
+    subject: ConfigChangeSubject,
+    kind: ConfigChangeKind,
+    name: String,
+    before: Option<String>,
+    after: Option<String>,
+) -> ConfigChange {
+    ConfigChange {
+        source: config.source.clone(),
+        subject,
+        kind,
+        name,
+        before,
+        after,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connection::{DetailItem, TopicAgentItem};
+
+    fn config(source: &str, url: &str, topics: &[(&str, bool)], agents: &[&str]) -> ConfigItem {
+        ConfigItem {
+            group_id: 0,
+            service_url: url.to_string(),
+            source: source.to_string(),
+            details: topics
+                .iter()
+                .enumerate()
+                .map(|(ix, (path, visibility))| DetailItem {
+                    index: ix as i32,
+                    path: path.to_string(),
+                    visibility: *visibility,
+                    group_id: 0,
+                })
+                .collect(),
+            topic_agents: agents
+                .iter()
+                .map(|agent_id| TopicAgentItem {
+                    agent_id: agent_id.to_string(),
+                    topics: Vec::new(),
+                    group_id: 0,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn diffs_configs_topics_agents_and_urls() {
+        let old = vec![
+            config(
+                "CMC_a",
+                "pulsar://a:6650",
+                &[("t1", true), ("t2", true)],
+                &["A1"],
+            ),
+            config("CMC_gone", "pulsar://g:6650", &[], &[]),
+        ];
+        let new = vec![
+            config(
+                "CMC_a",
+                "pulsar://b:6650",
+                &[("t1", false), ("t3", true)],
+                &["A2"],
+            ),
+            config("CMC_new", "pulsar://n:6650", &[], &[]),
+        ];
+
+        let changes: Vec<_> = diff_configs(&old, &new)
+            .into_iter()
+            .map(|c| (c.source, c.subject, c.kind, c.name))
+            .collect();
+        use ConfigChangeKind::*;
+        use ConfigChangeSubject::*;
+        assert_eq!(
+            changes,
+            [
+                ("CMC_gone", Config, Removed, ""),
+                ("CMC_a", ServiceUrl, Changed, ""),
+                ("CMC_a", Topic, Removed, "t2"),
+                ("CMC_a", Visibility, Changed, "t1"),
+                ("CMC_a", Topic, Added, "t3"),
+                ("CMC_a", Agent, Removed, "A1"),
+                ("CMC_a", Agent, Added, "A2"),
+                ("CMC_new", Config, Added, ""),
+            ]
+            .map(|(source, subject, kind, name)| (
+                source.to_string(),
+                subject,
+                kind,
+                name.to_string()
+            ))
+        );
+        assert!(diff_configs(&new, &new).is_empty());
+    }
+}
@@ -0,0 +1,199 @@
+//! Config Watch
+//!
+//! Change detection for the CMC keys behind the config list and the IMID→IMR
+//! mapping, either from keyspace notifications or by polling key fingerprints.
+
+use super::redis_config::REDIS_KEY_PATTERNS;
+use std::collections::BTreeMap;
+
+/// Key of the InfoModel property mapping (IMID → IMR)
+pub const INFOMODEL_KEY_PATTERN: &str = "CMC_*_sg.infomodel.property";
+/// Keys read by `fetch_configs` besides `REDIS_KEY_PATTERNS` (app ID, TopicAgentIds)
+const CONFIG_DEPENDENCY_PATTERNS: &[&str] = &["CMC_*_sg.main", "CMC_*_sg.device"];
+/// Polling interval when keyspace notifications are unavailable
+pub const CONFIG_WATCH_POLL_SECS: u64 = 30;
+/// Fingerprint resync interval while keyspace notifications are used, to
+/// catch events lost during a subscriber reconnect
+pub const CONFIG_WATCH_RESYNC_SECS: u64 = 300;
+
+/// How config changes are detected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigWatchMode {
+    /// Keyspace notifications on a dedicated subscriber connection
+    Notifications,
+    /// Periodic fingerprint comparison
+    Polling,
+}
+
+/// Which loaded data went stale
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConfigWatchChange {
+    /// Config list (`fetch_configs`)
+    pub configs: bool,
+    /// IMID→IMR mapping (`fetch_imid2imr`)
+    pub infomodel: bool,
+}
+
+impl ConfigWatchChange {
+    /// Whether nothing changed
+    pub fn is_empty(&self) -> bool {
+        !self.configs && !self.infomodel
+    }
+
+    /// Combine two changes
+    pub fn merge(&mut self, other: Self) {
+        self.configs |= other.configs;
+        self.infomodel |= other.infomodel;
+    }
+
+    /// Change caused by writing `key`
+    pub fn for_key(key: &str) -> Self {
+        Self {
+            configs: REDIS_KEY_PATTERNS
+                .iter()
+                .chain(CONFIG_DEPENDENCY_PATTERNS)
+                .any(|pattern| glob_match(pattern, key)),
+            infomodel: glob_match(INFOMODEL_KEY_PATTERN, key),
+        }
+    }
+}
+
+/// Events sent by a running config watch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigWatchEvent {
+    /// Detection mode in use
+    Mode(ConfigWatchMode),
+    /// Watched keys changed
+    Changed(ConfigWatchChange),
+}
+
+/// Key patterns to watch, restricted to `cfgid` when the server has one
+pub fn config_watch_patterns(cfgid: Option<&str>) -> Vec<String> {
+    let cfgid = cfgid.map(|cfgid| {
+        if cfgid.starts_with('{') && cfgid.ends_with('}') {
+            cfgid.to_string()
+        } else {
+            format!("{{{cfgid}}}")
+        }
+    });
+    REDIS_KEY_PATTERNS
+        .iter()
+        .chain(CONFIG_DEPENDENCY_PATTERNS)
+        .chain(std::iter::once(&INFOMODEL_KEY_PATTERN))
+        .map(|pattern| match &cfgid {
+            Some(cfgid) => pattern.replace('*', cfgid),
+            None => pattern.to_string(),
+        })
+        .collect()
+}
+
+/// Whether `notify-keyspace-events` publishes keyspace events for writes and
+/// deletes of string, list and hash keys
+pub fn keyspace_notifications_enabled(flags: &str) -> bool {
+    flags.contains('K')
+        && (flags.contains('A') || ['g', '$', 'l', 'h'].iter().all(|c| flags.contains(*c)))
+}
+
+/// Key of a keyspace notification channel (`__keyspace@0__:key`)
+pub fn keyspace_channel_key(channel: &str) -> Option<&str> {
+    let rest = channel.strip_prefix("__keyspace@")?;
+    let (_db, key) = rest.split_once("__:")?;
+    Some(key)
+}
+
+/// Value fingerprints of the watched keys
+pub type ConfigFingerprint = BTreeMap<String, u64>;
+
+/// Stale data between two fingerprints; added and removed keys count as changed
+pub fn fingerprint_change(old: &ConfigFingerprint, new: &ConfigFingerprint) -> ConfigWatchChange {
+    let mut change = ConfigWatchChange::default();
+    for (key, hash) in new {
+        if old.get(key) != Some(hash) {
+            change.merge(ConfigWatchChange::for_key(key));
+        }
+    }
+    for key in old.keys().filter(|key| !new.contains_key(*key)) {
+        change.merge(ConfigWatchChange::for_key(key));
+    }
+    change
+}
+
+/// Match a key against a pattern whose only wildcard is `*`
+fn glob_match(pattern: &str, key: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let Some(first) = parts.next() else {
+        return key.is_empty();
+    };
+    let Some(mut rest) = key.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_keys_and_channels() {
+        assert_eq!(
+            keyspace_channel_key("__keyspace@0__:CMC_{A1}_sg.bus"),
+            Some("CMC_{A1}_sg.bus")
+        );
+        assert_eq!(keyspace_channel_key("news"), None);
+        assert!(keyspace_notifications_enabled("KA"));
+        assert!(keyspace_notifications_enabled("Kg$lh"));
+        assert!(!keyspace_notifications_enabled("EA"));
+        assert!(!keyspace_notifications_enabled(""));
+
+        let bus = ConfigWatchChange::for_key("CMC_{A1}_sg.bus");
+        assert!(bus.configs && !bus.infomodel);
+        let device = ConfigWatchChange::for_key("CMC_{A1}_sg.device");
+        assert!(device.configs);
+        let infomodel = ConfigWatchChange::for_key("CMC_{A1}_sg.infomodel.property");
+        assert!(!infomodel.configs && infomodel.infomodel);
+        assert!(ConfigWatchChange::for_key("CMC_{A1}_sg.other").is_empty());
+        assert!(ConfigWatchChange::for_key("sg.bus").is_empty());
+
+        assert_eq!(
+            config_watch_patterns(Some("A1"))[0],
+            "CMC_{A1}_sg.og.output.iothub"
+        );
+        assert_eq!(
+            config_watch_patterns(None).last().map(String::as_str),
+            Some(INFOMODEL_KEY_PATTERN)
+        );
+    }
+
+    #[test]
+    fn compares_fingerprints() {
+        let old = ConfigFingerprint::from([
+            ("CMC_{A1}_sg.bus".to_string(), 1),
+            ("CMC_{A1}_sg.infomodel.property".to_string(), 2),
+        ]);
+        let mut new = old.clone();
+        assert!(fingerprint_change(&old, &new).is_empty());
+
+        new.insert("CMC_{A1}_sg.infomodel.property".to_string(), 3);
+        assert_eq!(
+            fingerprint_change(&old, &new),
+            ConfigWatchChange {
+                configs: false,
+                infomodel: true,
+            }
+        );
+
+        new.remove("CMC_{A1}_sg.bus");
+        assert!(fingerprint_change(&old, &new).configs);
+    }
+}
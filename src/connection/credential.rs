@@ -1,6 +1,8 @@
 //! Preset Credential Management
 //!
-//! Preset credentials for Redis authentication with optional ACL username support.
+//! Preset credentials for Redis authentication with optional ACL username support,
+//! and the credential ladder tried when connecting: the server password, each
+//! preset credential, then no auth, starting with the one that worked last.
 
 use serde::{Deserialize, Serialize};
 
@@ -65,6 +67,119 @@ pub fn text_to_credentials(text: &str) -> Vec<PresetCredential> {
         .collect()
 }
 
+/// Credential a Redis connection authenticated with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkingCredential {
+    ServerPassword,
+    /// Preset credential at `index`; the ACL user tells a reordered list apart
+    Preset {
+        index: usize,
+        #[serde(default)]
+        username: Option<String>,
+    },
+    NoAuth,
+}
+
+impl WorkingCredential {
+    /// Label without secrets, e.g. "preset credential #2 (user admin)"
+    pub fn label(&self) -> String {
+        match self {
+            Self::ServerPassword => "server password".to_string(),
+            Self::Preset {
+                index,
+                username: Some(user),
+            } if !user.is_empty() => format!("preset credential #{} (user {user})", index + 1),
+            Self::Preset { index, .. } => format!("preset credential #{}", index + 1),
+            Self::NoAuth => "no auth".to_string(),
+        }
+    }
+
+    /// Whether the credential still names the same secret after the preset
+    /// list changed from `previous` to `current`; a preset whose entry at its
+    /// index was edited, removed or moved does not
+    pub fn survives_preset_change(
+        &self,
+        previous: &[PresetCredential],
+        current: &[PresetCredential],
+    ) -> bool {
+        match self {
+            Self::Preset { index, .. } => {
+                previous.get(*index).is_some() && previous.get(*index) == current.get(*index)
+            }
+            Self::ServerPassword | Self::NoAuth => true,
+        }
+    }
+
+    /// Password of the preset credential to store as the server's own password;
+    /// `None` unless this is a preset without an ACL user that still matches
+    /// `presets`, since a server config carries no username
+    pub fn promotable_password<'a>(&self, presets: &'a [PresetCredential]) -> Option<&'a str> {
+        let Self::Preset {
+            index,
+            username: None,
+        } = self
+        else {
+            return None;
+        };
+        presets
+            .get(*index)
+            .filter(|preset| preset.username.is_none())
+            .map(|preset| preset.password.as_str())
+    }
+}
+
+/// One step of the credential ladder
+#[derive(Debug, Clone, PartialEq)]
+pub struct CredentialAttempt<'a> {
+    pub credential: WorkingCredential,
+    pub username: Option<&'a str>,
+    pub password: Option<&'a str>,
+}
+
+/// Credentials to try in order: the server password, each preset credential,
+/// then no auth. `remembered` moves to the front while it still matches the
+/// current settings, so the credential that worked last is tried first.
+pub fn credential_attempts<'a>(
+    server_password: Option<&'a str>,
+    presets: &'a [PresetCredential],
+    remembered: Option<&WorkingCredential>,
+) -> Vec<CredentialAttempt<'a>> {
+    let mut attempts = Vec::with_capacity(presets.len() + 2);
+    if let Some(password) = server_password.filter(|password| !password.is_empty()) {
+        attempts.push(CredentialAttempt {
+            credential: WorkingCredential::ServerPassword,
+            username: None,
+            password: Some(password),
+        });
+    }
+    for (index, preset) in presets.iter().enumerate() {
+        attempts.push(CredentialAttempt {
+            credential: WorkingCredential::Preset {
+                index,
+                username: preset.username.clone(),
+            },
+            username: preset.username.as_deref(),
+            password: Some(preset.password.as_str()),
+        });
+    }
+    attempts.push(CredentialAttempt {
+        credential: WorkingCredential::NoAuth,
+        username: None,
+        password: None,
+    });
+
+    if let Some(position) = remembered.and_then(|remembered| {
+        attempts
+            .iter()
+            .position(|attempt| attempt.credential == *remembered)
+    }) {
+        let attempt = attempts.remove(position);
+        attempts.insert(0, attempt);
+    }
+    attempts
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -114,4 +229,110 @@ mod tests {
         let back = credentials_to_text(&creds);
         assert_eq!(back, "pass1\nadmin:pass2\npass3");
     }
+
+    fn ladder(
+        server_password: Option<&str>,
+        presets: &[PresetCredential],
+        remembered: Option<&WorkingCredential>,
+    ) -> Vec<String> {
+        credential_attempts(server_password, presets, remembered)
+            .iter()
+            .map(|attempt| attempt.credential.label())
+            .collect()
+    }
+
+    #[test]
+    fn credential_attempts_start_with_remembered() {
+        let presets = text_to_credentials("pass1\nadmin:pass2");
+        assert_eq!(
+            ladder(Some("own"), &presets, None),
+            vec![
+                "server password",
+                "preset credential #1",
+                "preset credential #2 (user admin)",
+                "no auth"
+            ]
+        );
+
+        let remembered = WorkingCredential::Preset {
+            index: 1,
+            username: Some("admin".to_string()),
+        };
+        let attempts = credential_attempts(Some(""), &presets, Some(&remembered));
+        assert_eq!(attempts[0].credential, remembered);
+        assert_eq!(attempts[0].username, Some("admin"));
+        assert_eq!(attempts[0].password, Some("pass2"));
+        assert_eq!(attempts.len(), 3);
+
+        // A reordered or shortened list no longer matches and keeps the default order
+        let stale = WorkingCredential::Preset {
+            index: 0,
+            username: Some("admin".to_string()),
+        };
+        assert_eq!(
+            ladder(None, &presets, Some(&stale))[0],
+            "preset credential #1"
+        );
+        assert_eq!(
+            ladder(None, &presets, Some(&WorkingCredential::ServerPassword))[0],
+            "preset credential #1"
+        );
+        assert_eq!(
+            ladder(None, &presets, Some(&WorkingCredential::NoAuth))[0],
+            "no auth"
+        );
+    }
+
+    #[test]
+    fn only_password_presets_are_promotable() {
+        let presets = text_to_credentials("pass1\nadmin:pass2");
+        let plain = WorkingCredential::Preset {
+            index: 0,
+            username: None,
+        };
+        assert_eq!(plain.promotable_password(&presets), Some("pass1"));
+
+        let acl = WorkingCredential::Preset {
+            index: 1,
+            username: Some("admin".to_string()),
+        };
+        assert_eq!(acl.promotable_password(&presets), None);
+        let moved = WorkingCredential::Preset {
+            index: 1,
+            username: None,
+        };
+        assert_eq!(moved.promotable_password(&presets), None);
+        assert_eq!(
+            WorkingCredential::ServerPassword.promotable_password(&presets),
+            None
+        );
+    }
+
+    #[test]
+    fn edited_presets_invalidate_remembered_credentials() {
+        let previous = text_to_credentials("pass1\nadmin:pass2");
+        let first = WorkingCredential::Preset {
+            index: 0,
+            username: None,
+        };
+        let second = WorkingCredential::Preset {
+            index: 1,
+            username: Some("admin".to_string()),
+        };
+
+        let appended = text_to_credentials("pass1\nadmin:pass2\npass3");
+        assert!(first.survives_preset_change(&previous, &appended));
+        assert!(second.survives_preset_change(&previous, &appended));
+
+        let edited = text_to_credentials("changed\nadmin:pass2");
+        assert!(!first.survives_preset_change(&previous, &edited));
+        assert!(second.survives_preset_change(&previous, &edited));
+
+        let removed = text_to_credentials("admin:pass2");
+        assert!(!first.survives_preset_change(&previous, &removed));
+        assert!(!second.survives_preset_change(&previous, &removed));
+
+        assert!(WorkingCredential::ServerPassword.survives_preset_change(&previous, &removed));
+        assert!(WorkingCredential::NoAuth.survives_preset_change(&previous, &[]));
+    }
 }
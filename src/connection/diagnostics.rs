@@ -0,0 +1,184 @@
+//! Connection Diagnostics
+//!
+//! Step-by-step results of a diagnostics run against a server entry, and
+//! the plain-text report users share when a connection fails.
+
+use std::fmt;
+
+/// Outcome of one diagnostics step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticStatus {
+    Pass,
+    Fail,
+    /// Not run because an earlier step decided it
+    Skip,
+}
+
+impl DiagnosticStatus {
+    /// Fixed-width tag used in the text report
+    pub fn tag(self) -> &'static str {
+        match self {
+            Self::Pass => "PASS",
+            Self::Fail => "FAIL",
+            Self::Skip => "SKIP",
+        }
+    }
+}
+
+/// One checked step, e.g. "DNS resolution" or "Auth: preset credential #2"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiagnosticStep {
+    pub name: String,
+    pub status: DiagnosticStatus,
+    pub detail: String,
+}
+
+/// Result of a diagnostics run
+#[derive(Debug, Clone, Default)]
+pub struct DiagnosticReport {
+    /// Server display name
+    pub server: String,
+    /// Local time the run started
+    pub started_at: String,
+    pub steps: Vec<DiagnosticStep>,
+}
+
+impl DiagnosticReport {
+    pub fn new(server: impl Into<String>, started_at: impl Into<String>) -> Self {
+        Self {
+            server: server.into(),
+            started_at: started_at.into(),
+            steps: Vec::new(),
+        }
+    }
+
+    pub fn push(
+        &mut self,
+        name: impl Into<String>,
+        status: DiagnosticStatus,
+        detail: impl Into<String>,
+    ) {
+        self.steps.push(DiagnosticStep {
+            name: name.into(),
+            status,
+            detail: detail.into(),
+        });
+    }
+
+    pub fn pass(&mut self, name: impl Into<String>, detail: impl Into<String>) {
+        self.push(name, DiagnosticStatus::Pass, detail);
+    }
+
+    pub fn fail(&mut self, name: impl Into<String>, detail: impl Into<String>) {
+        self.push(name, DiagnosticStatus::Fail, detail);
+    }
+
+    pub fn skip(&mut self, name: impl Into<String>, detail: impl Into<String>) {
+        self.push(name, DiagnosticStatus::Skip, detail);
+    }
+
+    /// Record `result` as a passing or failing step
+    pub fn record<T, E: fmt::Display>(
+        &mut self,
+        name: impl Into<String>,
+        result: &std::result::Result<T, E>,
+        detail: impl FnOnce(&T) -> String,
+    ) {
+        match result {
+            Ok(value) => self.pass(name, detail(value)),
+            Err(err) => self.fail(name, err.to_string()),
+        }
+    }
+
+    pub fn failures(&self) -> usize {
+        self.steps
+            .iter()
+            .filter(|step| step.status == DiagnosticStatus::Fail)
+            .count()
+    }
+
+    /// Replace every occurrence of `secrets` in step details, so the report
+    /// can be shared even when a driver error echoes a credential
+    pub fn redact<S: AsRef<str>>(&mut self, secrets: &[S]) {
+        let mut secrets: Vec<&str> = secrets
+            .iter()
+            .map(AsRef::as_ref)
+            .filter(|secret| !secret.is_empty())
+            .collect();
+        // Longer secrets first, so one containing a shorter one is hidden whole
+        secrets.sort_by_key(|secret| std::cmp::Reverse(secret.len()));
+        for step in &mut self.steps {
+            for secret in &secrets {
+                if step.detail.contains(secret) {
+                    step.detail = step.detail.replace(secret, "***");
+                }
+            }
+        }
+    }
+
+    /// Shareable report, one line per step
+    pub fn to_text(&self) -> String {
+        let mut text = format!(
+            "DFC-GUI connection diagnostics\nServer: {}\nStarted: {}\n\n",
+            self.server, self.started_at
+        );
+        for step in &self.steps {
+            text.push_str(&format!("[{}] {}", step.status.tag(), step.name));
+            if !step.detail.is_empty() {
+                text.push_str(" - ");
+                // Keep one line per step
+                text.push_str(&step.detail.replace(['\r', '\n'], " "));
+            }
+            text.push('\n');
+        }
+        text.push_str(&format!(
+            "\n{} steps, {} failed\n",
+            self.steps.len(),
+            self.failures()
+        ));
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_lists_one_line_per_step() {
+        let mut report = DiagnosticReport::new("lab", "2026-01-01 10:00:00");
+        report.pass("DNS resolution", "10.0.0.5:6379");
+        report.fail("Auth: server password", "WRONGPASS invalid\npassword");
+        report.skip("Auth: no auth", "");
+        let result: std::result::Result<u32, String> = Ok(3);
+        report.record("Config keys", &result, |count| format!("{count} keys"));
+
+        assert_eq!(report.failures(), 1);
+        let text = report.to_text();
+        assert!(text.contains("Server: lab\n"));
+        assert!(text.contains("[PASS] DNS resolution - 10.0.0.5:6379\n"));
+        assert!(text.contains("[FAIL] Auth: server password - WRONGPASS invalid password\n"));
+        assert!(text.contains("[SKIP] Auth: no auth\n"));
+        assert!(text.contains("[PASS] Config keys - 3 keys\n"));
+        assert!(text.ends_with("4 steps, 1 failed\n"));
+    }
+
+    #[test]
+    fn redact_hides_secrets_in_details() {
+        let mut report = DiagnosticReport::default();
+        report.fail("Auth", "AUTH hunter2 rejected for ops");
+        report.redact(&["hunter2", "x"]);
+        assert_eq!(report.steps[0].detail, "AUTH *** rejected for ops");
+    }
+
+    #[test]
+    fn redact_hides_short_secrets_too() {
+        let mut report = DiagnosticReport::default();
+        report.fail("Auth", "AUTH ab rejected, token abcd expired");
+        report.redact(&["", "ab", "abcd"]);
+        assert_eq!(
+            report.steps[0].detail,
+            "AUTH *** rejected, token *** expired"
+        );
+    }
+}
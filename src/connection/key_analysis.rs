@@ -0,0 +1,324 @@
+//! Keyspace Analysis
+//!
+//! Aggregates sampled keys into a keyspace report: biggest keys by memory and
+//! element count, key counts per prefix and per type, and a TTL histogram.
+
+use super::redis_config::RedisKeyType;
+use std::collections::HashMap;
+
+/// Keys kept in each "largest keys" list
+pub const KEY_ANALYSIS_TOP_N: usize = 50;
+
+/// Upper bounds (exclusive, in seconds) of the TTL histogram buckets; the last
+/// bucket collects everything above
+pub const TTL_BUCKET_BOUNDS: [i64; 4] = [60, 3600, 86_400, 7 * 86_400];
+
+/// Number of TTL histogram buckets for keys with an expiry
+pub const TTL_BUCKET_COUNT: usize = TTL_BUCKET_BOUNDS.len() + 1;
+
+/// Measurements of one key
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeySample {
+    /// Key name
+    pub key: String,
+    /// Key type
+    pub key_type: RedisKeyType,
+    /// TTL in seconds, -1 without expiry
+    pub ttl: i64,
+    /// `MEMORY USAGE` in bytes, if the server reports it
+    pub memory: Option<u64>,
+    /// Element count of collections, byte length of strings
+    pub elements: Option<u64>,
+}
+
+/// Key count and memory of a group of keys
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct KeyGroupStats {
+    /// Number of keys
+    pub keys: u64,
+    /// Sum of `MEMORY USAGE`
+    pub memory: u64,
+}
+
+impl KeyGroupStats {
+    fn add(&mut self, sample: &KeySample) {
+        self.keys += 1;
+        self.memory += sample.memory.unwrap_or(0);
+    }
+}
+
+/// Aggregated keyspace report
+#[derive(Debug, Clone, Default)]
+pub struct KeyspaceReport {
+    /// Keys analyzed
+    pub analyzed: u64,
+    /// Keys and memory per type
+    by_type: Vec<(RedisKeyType, KeyGroupStats)>,
+    /// Keys and memory per top-level prefix
+    by_prefix: HashMap<String, KeyGroupStats>,
+    /// Keys without expiry
+    pub no_expiry: u64,
+    /// Keys with expiry per `TTL_BUCKET_BOUNDS` bucket
+    pub ttl_buckets: [u64; TTL_BUCKET_COUNT],
+    /// Largest keys by memory, descending
+    top_by_memory: Vec<KeySample>,
+    /// Largest keys by element count, descending
+    top_by_elements: Vec<KeySample>,
+}
+
+impl KeyspaceReport {
+    /// Add one key to the report.
+    ///
+    /// Keys that vanished between SCAN and measuring (TTL -2) are skipped.
+    pub fn add(&mut self, sample: KeySample, delimiters: &str) {
+        if sample.ttl == -2 {
+            return;
+        }
+        self.analyzed += 1;
+
+        match self.by_type.iter_mut().find(|(t, _)| *t == sample.key_type) {
+            Some((_, stats)) => stats.add(&sample),
+            None => {
+                let mut stats = KeyGroupStats::default();
+                stats.add(&sample);
+                self.by_type.push((sample.key_type, stats));
+            }
+        }
+        self.by_prefix
+            .entry(top_level_prefix(&sample.key, delimiters))
+            .or_default()
+            .add(&sample);
+
+        if sample.ttl < 0 {
+            self.no_expiry += 1;
+        } else {
+            let bucket = TTL_BUCKET_BOUNDS
+                .iter()
+                .position(|bound| sample.ttl < *bound)
+                .unwrap_or(TTL_BUCKET_BOUNDS.len());
+            self.ttl_buckets[bucket] += 1;
+        }
+
+        if let Some(memory) = sample.memory {
+            insert_top(&mut self.top_by_memory, sample.clone(), memory, |s| {
+                s.memory.unwrap_or(0)
+            });
+        }
+        if let Some(elements) = sample.elements {
+            insert_top(&mut self.top_by_elements, sample, elements, |s| {
+                s.elements.unwrap_or(0)
+            });
+        }
+    }
+
+    /// Keys and memory per type, most memory first
+    pub fn types(&self) -> Vec<(RedisKeyType, KeyGroupStats)> {
+        let mut types = self.by_type.clone();
+        types.sort_by(|a, b| b.1.memory.cmp(&a.1.memory).then(b.1.keys.cmp(&a.1.keys)));
+        types
+    }
+
+    /// Keys and memory per prefix, most memory first
+    pub fn prefixes(&self) -> Vec<(&str, KeyGroupStats)> {
+        let mut prefixes: Vec<_> = self
+            .by_prefix
+            .iter()
+            .map(|(prefix, stats)| (prefix.as_str(), *stats))
+            .collect();
+        prefixes.sort_by(|a, b| {
+            b.1.memory
+                .cmp(&a.1.memory)
+                .then(b.1.keys.cmp(&a.1.keys))
+                .then(a.0.cmp(b.0))
+        });
+        prefixes
+    }
+
+    /// Largest keys by memory, descending
+    pub fn top_by_memory(&self) -> &[KeySample] {
+        &self.top_by_memory
+    }
+
+    /// Largest keys by element count, descending
+    pub fn top_by_elements(&self) -> &[KeySample] {
+        &self.top_by_elements
+    }
+
+    /// Export the report as CSV with one row per type, prefix, TTL bucket and top key
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("section,name,type,keys,memory_bytes,elements,ttl_secs\n");
+        let mut row = |fields: [&str; 7]| {
+            let line: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+            out.push_str(&line.join(","));
+            out.push('\n');
+        };
+
+        for (key_type, stats) in self.types() {
+            let (keys, memory) = (stats.keys.to_string(), stats.memory.to_string());
+            row(["type", key_type.short_name(), "", &keys, &memory, "", ""]);
+        }
+        for (prefix, stats) in self.prefixes() {
+            let (keys, memory) = (stats.keys.to_string(), stats.memory.to_string());
+            row(["prefix", prefix, "", &keys, &memory, "", ""]);
+        }
+        row(["ttl", "none", "", &self.no_expiry.to_string(), "", "", ""]);
+        for (ix, count) in self.ttl_buckets.iter().enumerate() {
+            row([
+                "ttl",
+                &ttl_bucket_label(ix),
+                "",
+                &count.to_string(),
+                "",
+                "",
+                "",
+            ]);
+        }
+        for (section, samples) in [
+            ("top_memory", &self.top_by_memory),
+            ("top_elements", &self.top_by_elements),
+        ] {
+            for sample in samples {
+                let memory = sample.memory.map(|m| m.to_string()).unwrap_or_default();
+                let elements = sample.elements.map(|e| e.to_string()).unwrap_or_default();
+                row([
+                    section,
+                    &sample.key,
+                    sample.key_type.short_name(),
+                    "1",
+                    &memory,
+                    &elements,
+                    &sample.ttl.to_string(),
+                ]);
+            }
+        }
+        out
+    }
+}
+
+/// Insert into a descending top-N list
+fn insert_top(
+    top: &mut Vec<KeySample>,
+    sample: KeySample,
+    size: u64,
+    size_of: impl Fn(&KeySample) -> u64,
+) {
+    let ix = top.partition_point(|s| size_of(s) >= size);
+    if ix < KEY_ANALYSIS_TOP_N {
+        top.insert(ix, sample);
+        top.truncate(KEY_ANALYSIS_TOP_N);
+    }
+}
+
+/// First key segment followed by `*`, e.g. `cmc:*`; empty for keys without a delimiter
+pub fn top_level_prefix(key: &str, delimiters: &str) -> String {
+    match key.find(|c| delimiters.contains(c)) {
+        Some(ix) => {
+            let delimiter_len = key[ix..].chars().next().map_or(1, char::len_utf8);
+            format!("{}*", &key[..ix + delimiter_len])
+        }
+        None => String::new(),
+    }
+}
+
+/// Compact label of a TTL bucket, e.g. `1h-1d`
+pub fn ttl_bucket_label(ix: usize) -> String {
+    fn span(secs: i64) -> String {
+        match secs {
+            s if s % 86_400 == 0 => format!("{}d", s / 86_400),
+            s if s % 3600 == 0 => format!("{}h", s / 3600),
+            s if s % 60 == 0 => format!("{}m", s / 60),
+            s => format!("{s}s"),
+        }
+    }
+    match (
+        ix.checked_sub(1).map(|i| TTL_BUCKET_BOUNDS[i]),
+        TTL_BUCKET_BOUNDS.get(ix),
+    ) {
+        (None, Some(upper)) => format!("<{}", span(*upper)),
+        (Some(lower), Some(upper)) => format!("{}-{}", span(lower), span(*upper)),
+        (Some(lower), None) => format!(">={}", span(lower)),
+        (None, None) => String::new(),
+    }
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(
+        key: &str,
+        key_type: RedisKeyType,
+        ttl: i64,
+        memory: u64,
+        elements: u64,
+    ) -> KeySample {
+        KeySample {
+            key: key.to_string(),
+            key_type,
+            ttl,
+            memory: Some(memory),
+            elements: Some(elements),
+        }
+    }
+
+    #[test]
+    fn aggregates_keys() {
+        let mut report = KeyspaceReport::default();
+        report.add(sample("cmc:a", RedisKeyType::Hash, -1, 500, 10), ":");
+        report.add(sample("cmc:b", RedisKeyType::Hash, 30, 100, 90), ":");
+        report.add(sample("dev:x", RedisKeyType::String, 7200, 900, 5), ":");
+        report.add(sample("gone", RedisKeyType::String, -2, 1, 1), ":");
+
+        assert_eq!(report.analyzed, 3);
+        assert_eq!(report.no_expiry, 1);
+        assert_eq!(report.ttl_buckets, [1, 0, 1, 0, 0]);
+        assert_eq!(
+            report.prefixes(),
+            vec![
+                (
+                    "dev:*",
+                    KeyGroupStats {
+                        keys: 1,
+                        memory: 900
+                    }
+                ),
+                (
+                    "cmc:*",
+                    KeyGroupStats {
+                        keys: 2,
+                        memory: 600
+                    }
+                ),
+            ]
+        );
+        assert_eq!(report.types()[0].0, RedisKeyType::String);
+        let top: Vec<&str> = report
+            .top_by_memory()
+            .iter()
+            .map(|s| s.key.as_str())
+            .collect();
+        assert_eq!(top, ["dev:x", "cmc:a", "cmc:b"]);
+        assert_eq!(report.top_by_elements()[0].key, "cmc:b");
+    }
+
+    #[test]
+    fn exports_csv_and_labels() {
+        let mut report = KeyspaceReport::default();
+        report.add(sample("a,\"b\"", RedisKeyType::List, 10, 64, 3), ":");
+        let csv = report.to_csv();
+        assert!(csv.starts_with("section,name,type,keys,memory_bytes,elements,ttl_secs\n"));
+        assert!(csv.contains("top_memory,\"a,\"\"b\"\"\",LIST,1,64,3,10\n"));
+        assert!(csv.contains("ttl,<1m,,1,,,\n"));
+        assert_eq!(ttl_bucket_label(2), "1h-1d");
+        assert_eq!(ttl_bucket_label(TTL_BUCKET_COUNT - 1), ">=7d");
+    }
+}
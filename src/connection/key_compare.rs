@@ -0,0 +1,366 @@
+//! Cross-Server Key Comparison
+//!
+//! Structural diff of a key's value between two servers: JSON strings per
+//! JSONPath, hashes per field, sets per member, sorted sets per member score
+//! and lists per index.
+
+use super::redis_config::RedisKeyType;
+use super::value_format::{json_child_path, json_summary};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Keys compared by one pattern comparison
+pub const KEY_COMPARE_MAX_KEYS: usize = 200;
+
+/// Elements loaded per collection key; larger values are compared partially
+pub const KEY_COMPARE_MAX_ELEMENTS: usize = 10_000;
+
+/// Characters of a value shown in a diff entry
+const DIFF_TEXT_MAX_CHARS: usize = 200;
+
+/// A key's value loaded for comparison
+#[derive(Debug, Clone, PartialEq)]
+pub enum CompareValue {
+    /// The key does not exist
+    Missing,
+    /// String value as raw bytes
+    String(Vec<u8>),
+    /// Hash fields and values
+    Hash(BTreeMap<String, String>),
+    /// List elements
+    List(Vec<String>),
+    /// Set members
+    Set(BTreeSet<String>),
+    /// Sorted set members and scores
+    ZSet(BTreeMap<String, f64>),
+    /// Streams and unknown types are compared by type only
+    Other(RedisKeyType),
+}
+
+impl CompareValue {
+    /// Type of the value, `None` if the key is missing
+    pub fn key_type(&self) -> Option<RedisKeyType> {
+        match self {
+            Self::Missing => None,
+            Self::String(_) => Some(RedisKeyType::String),
+            Self::Hash(_) => Some(RedisKeyType::Hash),
+            Self::List(_) => Some(RedisKeyType::List),
+            Self::Set(_) => Some(RedisKeyType::Set),
+            Self::ZSet(_) => Some(RedisKeyType::ZSet),
+            Self::Other(key_type) => Some(*key_type),
+        }
+    }
+
+    /// One-line description, e.g. `HASH (12 fields)`
+    pub fn summary(&self) -> String {
+        match self {
+            Self::Missing => "(nil)".to_string(),
+            Self::String(bytes) => shorten(&String::from_utf8_lossy(bytes)),
+            Self::Hash(map) => format!("HASH ({} fields)", map.len()),
+            Self::List(items) => format!("LIST ({} elements)", items.len()),
+            Self::Set(members) => format!("SET ({} members)", members.len()),
+            Self::ZSet(members) => format!("ZSET ({} members)", members.len()),
+            Self::Other(key_type) => key_type.short_name().to_string(),
+        }
+    }
+}
+
+/// Side on which a difference was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffKind {
+    /// Present on the source server only
+    OnlyInSource,
+    /// Present on the target server only
+    OnlyInTarget,
+    /// Present on both with different values
+    Changed,
+}
+
+/// One difference inside a value
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffEntry {
+    /// JSONPath, hash field, set member or list index; empty for whole strings
+    pub path: String,
+    pub kind: DiffKind,
+    /// Value on the source server
+    pub source: Option<String>,
+    /// Value on the target server
+    pub target: Option<String>,
+}
+
+/// Outcome of comparing one key
+#[derive(Debug, Clone, PartialEq)]
+pub enum KeyDiff {
+    /// Both values are equal
+    Equal,
+    /// The key exists on the source server only
+    OnlyInSource,
+    /// The key exists on the target server only
+    OnlyInTarget,
+    /// The key has different types on the two servers
+    TypeMismatch {
+        source: RedisKeyType,
+        target: RedisKeyType,
+    },
+    /// Same type, different content
+    Changed(Vec<DiffEntry>),
+    /// Streams and unknown types are not compared
+    NotCompared(RedisKeyType),
+}
+
+impl KeyDiff {
+    /// Whether the key differs between the servers
+    pub fn is_different(&self) -> bool {
+        !matches!(self, Self::Equal | Self::NotCompared(_))
+    }
+}
+
+/// Comparison result of one key
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyComparison {
+    pub key: String,
+    pub diff: KeyDiff,
+    /// A value exceeded `KEY_COMPARE_MAX_ELEMENTS` and was compared partially
+    pub truncated: bool,
+}
+
+/// Compare the source and target value of a key
+pub fn diff_values(source: &CompareValue, target: &CompareValue) -> KeyDiff {
+    let mut entries = Vec::new();
+    match (source, target) {
+        (CompareValue::Missing, CompareValue::Missing) => return KeyDiff::Equal,
+        (_, CompareValue::Missing) => return KeyDiff::OnlyInSource,
+        (CompareValue::Missing, _) => return KeyDiff::OnlyInTarget,
+        (CompareValue::String(a), CompareValue::String(b)) => {
+            if a == b {
+                return KeyDiff::Equal;
+            }
+            match (parse_json(a), parse_json(b)) {
+                (Some(a), Some(b)) => diff_json("$", &a, &b, &mut entries),
+                _ => entries.push(DiffEntry {
+                    path: String::new(),
+                    kind: DiffKind::Changed,
+                    source: Some(shorten(&String::from_utf8_lossy(a))),
+                    target: Some(shorten(&String::from_utf8_lossy(b))),
+                }),
+            }
+        }
+        (CompareValue::Hash(a), CompareValue::Hash(b)) => diff_maps(a, b, &mut entries),
+        (CompareValue::ZSet(a), CompareValue::ZSet(b)) => diff_maps(a, b, &mut entries),
+        (CompareValue::Set(a), CompareValue::Set(b)) => {
+            entries.extend(a.difference(b).map(|member| DiffEntry {
+                path: member.clone(),
+                kind: DiffKind::OnlyInSource,
+                source: Some(shorten(member)),
+                target: None,
+            }));
+            entries.extend(b.difference(a).map(|member| DiffEntry {
+                path: member.clone(),
+                kind: DiffKind::OnlyInTarget,
+                source: None,
+                target: Some(shorten(member)),
+            }));
+        }
+        (CompareValue::List(a), CompareValue::List(b)) => {
+            for ix in 0..a.len().max(b.len()) {
+                let (a, b) = (a.get(ix), b.get(ix));
+                if a != b {
+                    entries.push(DiffEntry {
+                        path: ix.to_string(),
+                        kind: side_kind(a.is_some(), b.is_some()),
+                        source: a.map(|s| shorten(s)),
+                        target: b.map(|s| shorten(s)),
+                    });
+                }
+            }
+        }
+        (CompareValue::Other(a), CompareValue::Other(b)) if a == b => {
+            return KeyDiff::NotCompared(*a);
+        }
+        _ => {
+            return KeyDiff::TypeMismatch {
+                source: source.key_type().unwrap_or_default(),
+                target: target.key_type().unwrap_or_default(),
+            };
+        }
+    }
+
+    if entries.is_empty() {
+        KeyDiff::Equal
+    } else {
+        KeyDiff::Changed(entries)
+    }
+}
+
+/// Parse a string value as a JSON object or array; scalars are compared as text
+fn parse_json(bytes: &[u8]) -> Option<serde_json::Value> {
+    serde_json::from_slice::<serde_json::Value>(bytes)
+        .ok()
+        .filter(|value| value.is_object() || value.is_array())
+}
+
+/// Diff two JSON documents member by member
+fn diff_json(
+    path: &str,
+    source: &serde_json::Value,
+    target: &serde_json::Value,
+    entries: &mut Vec<DiffEntry>,
+) {
+    use serde_json::Value;
+
+    match (source, target) {
+        (Value::Object(a), Value::Object(b)) => {
+            let keys: BTreeSet<&String> = a.keys().chain(b.keys()).collect();
+            for key in keys {
+                let child = json_child_path(path, key);
+                match (a.get(key), b.get(key)) {
+                    (Some(a), Some(b)) => diff_json(&child, a, b, entries),
+                    (a, b) => entries.push(json_entry(child, a, b)),
+                }
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            for ix in 0..a.len().max(b.len()) {
+                let child = format!("{path}[{ix}]");
+                match (a.get(ix), b.get(ix)) {
+                    (Some(a), Some(b)) => diff_json(&child, a, b, entries),
+                    (a, b) => entries.push(json_entry(child, a, b)),
+                }
+            }
+        }
+        (a, b) if a != b => entries.push(json_entry(path.to_string(), Some(a), Some(b))),
+        _ => {}
+    }
+}
+
+fn json_entry(
+    path: String,
+    source: Option<&serde_json::Value>,
+    target: Option<&serde_json::Value>,
+) -> DiffEntry {
+    DiffEntry {
+        path,
+        kind: side_kind(source.is_some(), target.is_some()),
+        source: source.map(|v| shorten(&json_summary(v))),
+        target: target.map(|v| shorten(&json_summary(v))),
+    }
+}
+
+/// Diff hash fields or sorted set scores
+fn diff_maps<V: PartialEq + ToString>(
+    source: &BTreeMap<String, V>,
+    target: &BTreeMap<String, V>,
+    entries: &mut Vec<DiffEntry>,
+) {
+    let keys: BTreeSet<&String> = source.keys().chain(target.keys()).collect();
+    for key in keys {
+        let (a, b) = (source.get(key), target.get(key));
+        if a != b {
+            entries.push(DiffEntry {
+                path: key.clone(),
+                kind: side_kind(a.is_some(), b.is_some()),
+                source: a.map(|v| shorten(&v.to_string())),
+                target: b.map(|v| shorten(&v.to_string())),
+            });
+        }
+    }
+}
+
+fn side_kind(in_source: bool, in_target: bool) -> DiffKind {
+    match (in_source, in_target) {
+        (true, false) => DiffKind::OnlyInSource,
+        (false, true) => DiffKind::OnlyInTarget,
+        _ => DiffKind::Changed,
+    }
+}
+
+/// Cut long values to `DIFF_TEXT_MAX_CHARS`
+fn shorten(text: &str) -> String {
+    match text.char_indices().nth(DIFF_TEXT_MAX_CHARS) {
+        Some((ix, _)) => format!("{}…", &text[..ix]),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn string(text: &str) -> CompareValue {
+        CompareValue::String(text.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn diffs_json_strings_by_path() {
+        let source = string(r#"{"agents":[{"id":1,"on":true}],"url":"a"}"#);
+        let target = string(r#"{"agents":[{"id":1,"on":false},{"id":2}],"svc":"b","url":"a"}"#);
+        let KeyDiff::Changed(entries) = diff_values(&source, &target) else {
+            panic!("expected a structural diff");
+        };
+        let paths: Vec<(&str, DiffKind)> =
+            entries.iter().map(|e| (e.path.as_str(), e.kind)).collect();
+        assert_eq!(
+            paths,
+            [
+                ("$.agents[0].on", DiffKind::Changed),
+                ("$.agents[1]", DiffKind::OnlyInTarget),
+                ("$.svc", DiffKind::OnlyInTarget),
+            ]
+        );
+        assert_eq!(diff_values(&string("x"), &string("x")), KeyDiff::Equal);
+        assert_eq!(
+            diff_values(&string("1"), &string("2")),
+            KeyDiff::Changed(vec![DiffEntry {
+                path: String::new(),
+                kind: DiffKind::Changed,
+                source: Some("1".to_string()),
+                target: Some("2".to_string()),
+            }])
+        );
+    }
+
+    #[test]
+    fn diffs_collections_and_presence() {
+        let hash = |pairs: &[(&str, &str)]| {
+            CompareValue::Hash(
+                pairs
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+            )
+        };
+        let KeyDiff::Changed(entries) = diff_values(
+            &hash(&[("a", "1"), ("b", "2")]),
+            &hash(&[("b", "3"), ("c", "4")]),
+        ) else {
+            panic!("expected a field diff");
+        };
+        let kinds: Vec<DiffKind> = entries.iter().map(|e| e.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                DiffKind::OnlyInSource,
+                DiffKind::Changed,
+                DiffKind::OnlyInTarget
+            ]
+        );
+
+        let set =
+            |members: &[&str]| CompareValue::Set(members.iter().map(|m| m.to_string()).collect());
+        let KeyDiff::Changed(entries) = diff_values(&set(&["x", "y"]), &set(&["y", "z"])) else {
+            panic!("expected a member diff");
+        };
+        assert_eq!(entries.len(), 2);
+
+        assert_eq!(
+            diff_values(&string("x"), &CompareValue::Missing),
+            KeyDiff::OnlyInSource
+        );
+        assert_eq!(
+            diff_values(&string("x"), &set(&["x"])),
+            KeyDiff::TypeMismatch {
+                source: RedisKeyType::String,
+                target: RedisKeyType::Set,
+            }
+        );
+    }
+}
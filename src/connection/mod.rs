@@ -3,9 +3,41 @@
 //! Server configuration and connection handling for DFC-GUI.
 
 mod config;
+mod config_snapshot;
+mod config_watch;
 mod credential;
+mod diagnostics;
+mod key_analysis;
+mod key_compare;
+mod proxy;
+mod pulsar_security;
+mod redis_command;
 mod redis_config;
+mod redis_info;
+mod redis_write;
+mod server_catalog;
+mod server_safety;
+mod server_transfer;
+mod ssh_tunnel;
+mod value_format;
+mod value_search;
 
 pub use config::*;
+pub use config_snapshot::*;
+pub use config_watch::*;
 pub use credential::*;
+pub use diagnostics::*;
+pub use key_analysis::*;
+pub use key_compare::*;
+pub use proxy::*;
+pub use pulsar_security::*;
+pub use redis_command::*;
 pub use redis_config::*;
+pub use redis_info::*;
+pub use redis_write::*;
+pub use server_catalog::*;
+pub use server_safety::*;
+pub use server_transfer::*;
+pub use ssh_tunnel::*;
+pub use value_format::*;
+pub use value_search::*;
@@ -0,0 +1,247 @@
+//! Proxy Settings
+//!
+//! SOCKS5 and HTTP CONNECT proxies for outbound connections, set globally or
+//! per server, with optional authentication and a bypass list.
+
+use crate::error::{Error, Result};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+
+/// Proxy protocol
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProxyKind {
+    /// SOCKS5 with remote name resolution
+    Socks5,
+    /// HTTP CONNECT
+    Http,
+}
+
+/// Outbound proxy
+#[derive(Debug, Default, Deserialize, Clone, Serialize, Hash, Eq, PartialEq)]
+pub struct ProxyConfig {
+    /// `socks5://host:port` or `http://host:port`
+    pub url: String,
+    pub username: Option<String>,
+    /// Proxy password (encrypted storage)
+    pub password: Option<String>,
+    /// Hosts reached directly, separated by commas or whitespace: `*`, exact
+    /// names, domains (`example.com` or `.example.com`, subdomains included),
+    /// IP addresses and CIDR ranges
+    pub bypass: Option<String>,
+}
+
+impl ProxyConfig {
+    /// Protocol, host and port of the proxy
+    pub fn endpoint(&self) -> Result<(ProxyKind, String, u16)> {
+        let invalid = || Error::Invalid {
+            message: format!("Invalid proxy URL \"{}\"", self.url),
+        };
+        let (scheme, authority) = self.url.trim().split_once("://").ok_or_else(invalid)?;
+        let (kind, default_port) = match scheme.to_ascii_lowercase().as_str() {
+            "socks5" | "socks5h" => (ProxyKind::Socks5, 1080),
+            "http" => (ProxyKind::Http, 8080),
+            _ => return Err(invalid()),
+        };
+        let authority = authority.trim_end_matches('/');
+        let (host, port) = match authority.strip_prefix('[') {
+            Some(rest) => {
+                let (host, rest) = rest.split_once(']').ok_or_else(invalid)?;
+                (host, rest.strip_prefix(':'))
+            }
+            None => match authority.rsplit_once(':') {
+                Some((host, port)) => (host, Some(port)),
+                None => (authority, None),
+            },
+        };
+        if host.is_empty() || host.contains(['/', '@']) {
+            return Err(invalid());
+        }
+        let port = match port {
+            Some(port) => port.parse().map_err(|_| invalid())?,
+            None => default_port,
+        };
+        Ok((kind, host.to_string(), port))
+    }
+
+    /// Whether `host` is reached directly
+    pub fn bypasses(&self, host: &str) -> bool {
+        let host = host
+            .trim()
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_ascii_lowercase();
+        let ip = host.parse::<IpAddr>().ok();
+        self.bypass
+            .as_deref()
+            .unwrap_or_default()
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|entry| !entry.is_empty())
+            .any(|entry| {
+                let entry = entry.to_ascii_lowercase();
+                if entry == "*" {
+                    return true;
+                }
+                if let Some((network, prefix)) = entry.split_once('/') {
+                    return ip.is_some_and(|ip| cidr_contains(network, prefix, ip));
+                }
+                let domain = entry.trim_start_matches('*').trim_start_matches('.');
+                host == domain || host.ends_with(&format!(".{domain}"))
+            })
+    }
+
+    /// `Proxy-Authorization` header value, if credentials are set
+    fn basic_auth(&self) -> Option<String> {
+        let username = self.username.as_deref().filter(|u| !u.is_empty())?;
+        let password = self.password.as_deref().unwrap_or_default();
+        let encoded =
+            base64::engine::general_purpose::STANDARD.encode(format!("{username}:{password}"));
+        Some(format!("Basic {encoded}"))
+    }
+}
+
+fn cidr_contains(network: &str, prefix: &str, ip: IpAddr) -> bool {
+    let (Ok(network), Ok(prefix)) = (network.parse::<IpAddr>(), prefix.parse::<u32>()) else {
+        return false;
+    };
+    match (network, ip) {
+        (IpAddr::V4(network), IpAddr::V4(ip)) if prefix <= 32 => {
+            let mask = u32::MAX.checked_shl(32 - prefix).unwrap_or(0);
+            u32::from(network) & mask == u32::from(ip) & mask
+        }
+        (IpAddr::V6(network), IpAddr::V6(ip)) if prefix <= 128 => {
+            let mask = u128::MAX.checked_shl(128 - prefix).unwrap_or(0);
+            u128::from(network) & mask == u128::from(ip) & mask
+        }
+        _ => false,
+    }
+}
+
+/// HTTP CONNECT request for `host:port`
+pub fn http_connect_request(proxy: &ProxyConfig, host: &str, port: u16) -> String {
+    let authority = if host.contains(':') {
+        format!("[{host}]:{port}")
+    } else {
+        format!("{host}:{port}")
+    };
+    let mut request = format!("CONNECT {authority} HTTP/1.1\r\nHost: {authority}\r\n");
+    if let Some(auth) = proxy.basic_auth() {
+        request.push_str(&format!("Proxy-Authorization: {auth}\r\n"));
+    }
+    request.push_str("\r\n");
+    request
+}
+
+/// SOCKS5 greeting offering no-auth, plus username/password when set
+pub fn socks5_greeting(proxy: &ProxyConfig) -> Vec<u8> {
+    if proxy.username.as_deref().is_some_and(|u| !u.is_empty()) {
+        vec![0x05, 0x02, 0x00, 0x02]
+    } else {
+        vec![0x05, 0x01, 0x00]
+    }
+}
+
+/// SOCKS5 username/password sub-negotiation (RFC 1929)
+pub fn socks5_auth_request(proxy: &ProxyConfig) -> Result<Vec<u8>> {
+    let username = proxy.username.as_deref().unwrap_or_default().as_bytes();
+    let password = proxy.password.as_deref().unwrap_or_default().as_bytes();
+    let (Ok(username_len), Ok(password_len)) =
+        (u8::try_from(username.len()), u8::try_from(password.len()))
+    else {
+        return Err(Error::Invalid {
+            message: "SOCKS5 username and password must be at most 255 bytes".to_string(),
+        });
+    };
+    let mut request = vec![0x01, username_len];
+    request.extend_from_slice(username);
+    request.push(password_len);
+    request.extend_from_slice(password);
+    Ok(request)
+}
+
+/// SOCKS5 CONNECT request; names are resolved by the proxy
+pub fn socks5_connect_request(host: &str, port: u16) -> Result<Vec<u8>> {
+    let mut request = vec![0x05, 0x01, 0x00];
+    match host.parse::<IpAddr>() {
+        Ok(IpAddr::V4(ip)) => {
+            request.push(0x01);
+            request.extend_from_slice(&ip.octets());
+        }
+        Ok(IpAddr::V6(ip)) => {
+            request.push(0x04);
+            request.extend_from_slice(&ip.octets());
+        }
+        Err(_) => {
+            let len = u8::try_from(host.len()).map_err(|_| Error::Invalid {
+                message: format!("Host name too long for SOCKS5: {host}"),
+            })?;
+            request.push(0x03);
+            request.push(len);
+            request.extend_from_slice(host.as_bytes());
+        }
+    }
+    request.extend_from_slice(&port.to_be_bytes());
+    Ok(request)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proxy(url: &str, bypass: &str) -> ProxyConfig {
+        ProxyConfig {
+            url: url.to_string(),
+            username: Some("ops".to_string()),
+            password: Some("secret".to_string()),
+            bypass: Some(bypass.to_string()),
+        }
+    }
+
+    #[test]
+    fn parses_endpoints_and_bypass_lists() {
+        let socks = proxy("socks5://10.0.0.1", "localhost, .corp.example 10.15.0.0/16");
+        assert_eq!(
+            socks.endpoint().expect("socks endpoint"),
+            (ProxyKind::Socks5, "10.0.0.1".to_string(), 1080)
+        );
+        assert_eq!(
+            proxy("http://proxy.example:3128/", "")
+                .endpoint()
+                .expect("http endpoint"),
+            (ProxyKind::Http, "proxy.example".to_string(), 3128)
+        );
+        assert!(proxy("ftp://proxy.example", "").endpoint().is_err());
+        assert!(proxy("http://user@proxy.example", "").endpoint().is_err());
+
+        assert!(socks.bypasses("localhost"));
+        assert!(socks.bypasses("redis.corp.example"));
+        assert!(socks.bypasses("corp.example"));
+        assert!(!socks.bypasses("notcorp.example"));
+        assert!(socks.bypasses("10.15.64.53"));
+        assert!(!socks.bypasses("10.16.64.53"));
+        assert!(proxy("socks5://p", "*").bypasses("anything"));
+    }
+
+    #[test]
+    fn encodes_handshakes() {
+        let http = proxy("http://p", "");
+        assert_eq!(
+            http_connect_request(&http, "broker", 6650),
+            "CONNECT broker:6650 HTTP/1.1\r\nHost: broker:6650\r\n\
+             Proxy-Authorization: Basic b3BzOnNlY3JldA==\r\n\r\n"
+        );
+        assert_eq!(socks5_greeting(&http), [0x05, 0x02, 0x00, 0x02]);
+        assert_eq!(
+            socks5_auth_request(&http).expect("auth request"),
+            b"\x01\x03ops\x06secret"
+        );
+        assert_eq!(
+            socks5_connect_request("10.0.0.2", 6379).expect("ipv4 request"),
+            [0x05, 0x01, 0x00, 0x01, 10, 0, 0, 2, 0x18, 0xEB]
+        );
+        assert_eq!(
+            socks5_connect_request("ab", 80).expect("domain request"),
+            [0x05, 0x01, 0x00, 0x03, 2, b'a', b'b', 0, 80]
+        );
+    }
+}
@@ -0,0 +1,249 @@
+//! Pulsar Security Settings
+//!
+//! Per-server Pulsar authentication (static token, token file or environment
+//! variable, basic auth, OAuth2 client credentials) and TLS options for
+//! `pulsar+ssl://` service URLs.
+
+use crate::error::{Error, Result};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+/// How a server authenticates to Pulsar
+#[derive(Debug, Default, Clone, Copy, Deserialize, Serialize, Hash, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PulsarAuthMode {
+    /// Static JWT from `DfcServerConfig::pulsar_token` (none when empty)
+    #[default]
+    Token,
+    /// JWT read from a file on every connect
+    TokenFile,
+    /// JWT read from an environment variable on every connect
+    TokenEnv,
+    /// Username and password
+    Basic,
+    /// OAuth2 client credentials flow
+    #[serde(rename = "oauth2")]
+    OAuth2,
+}
+
+impl PulsarAuthMode {
+    pub const ALL: [Self; 5] = [
+        Self::Token,
+        Self::TokenFile,
+        Self::TokenEnv,
+        Self::Basic,
+        Self::OAuth2,
+    ];
+
+    /// i18n key suffix
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::Token => "token",
+            Self::TokenFile => "token_file",
+            Self::TokenEnv => "token_env",
+            Self::Basic => "basic",
+            Self::OAuth2 => "oauth2",
+        }
+    }
+}
+
+/// Pulsar authentication and TLS of a server
+#[derive(Debug, Default, Deserialize, Clone, Serialize, Hash, Eq, PartialEq)]
+pub struct PulsarSecurityConfig {
+    #[serde(default)]
+    pub auth_mode: PulsarAuthMode,
+    /// Token file (`TokenFile`)
+    pub token_file: Option<String>,
+    /// Environment variable holding the token (`TokenEnv`)
+    pub token_env: Option<String>,
+    /// Basic auth username
+    pub username: Option<String>,
+    /// Basic auth password (encrypted storage)
+    pub password: Option<String>,
+    /// OAuth2 issuer URL
+    pub oauth2_issuer_url: Option<String>,
+    pub oauth2_client_id: Option<String>,
+    /// OAuth2 client secret (encrypted storage)
+    pub oauth2_client_secret: Option<String>,
+    /// OAuth2 key file (JSON with `client_id` and `client_secret`), used
+    /// instead of the client ID and secret
+    pub oauth2_credentials_file: Option<String>,
+    pub oauth2_audience: Option<String>,
+    pub oauth2_scope: Option<String>,
+    /// PEM file of a custom CA for `pulsar+ssl://` (system roots otherwise)
+    pub tls_ca_file: Option<String>,
+    /// Accept certificates whose name does not match the broker host
+    #[serde(default)]
+    pub tls_skip_hostname_verification: bool,
+    /// Accept untrusted broker certificates (lab setups only)
+    #[serde(default)]
+    pub tls_allow_insecure: bool,
+}
+
+/// Resolved Pulsar credentials for one connect
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PulsarCredentials {
+    None,
+    Token(String),
+    Basic {
+        username: String,
+        password: String,
+    },
+    OAuth2 {
+        issuer_url: String,
+        /// `file://` or `data:` URL of the client credentials JSON
+        credentials_url: String,
+        audience: Option<String>,
+        scope: Option<String>,
+    },
+}
+
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|v| !v.is_empty())
+}
+
+fn required<'a>(value: Option<&'a str>, what: &str) -> Result<&'a str> {
+    non_empty(value).ok_or_else(|| Error::Invalid {
+        message: format!("Pulsar {what} is required"),
+    })
+}
+
+impl PulsarSecurityConfig {
+    /// Credentials to connect with; `static_token` is the server's
+    /// `pulsar_token`. Token files and variables are read here, so changes
+    /// apply on the next connect.
+    pub fn credentials(&self, static_token: Option<&str>) -> Result<PulsarCredentials> {
+        let credentials = match self.auth_mode {
+            PulsarAuthMode::Token => match non_empty(static_token) {
+                Some(token) => PulsarCredentials::Token(token.to_string()),
+                None => PulsarCredentials::None,
+            },
+            PulsarAuthMode::TokenFile => {
+                let path = required(self.token_file.as_deref(), "token file")?;
+                let token = std::fs::read_to_string(path)?;
+                PulsarCredentials::Token(
+                    required(Some(&token), &format!("token in {path}"))?.to_string(),
+                )
+            }
+            PulsarAuthMode::TokenEnv => {
+                let var = required(self.token_env.as_deref(), "token variable")?;
+                let token = std::env::var(var).map_err(|e| Error::Invalid {
+                    message: format!("Pulsar token variable {var}: {e}"),
+                })?;
+                PulsarCredentials::Token(
+                    required(Some(&token), &format!("token in ${var}"))?.to_string(),
+                )
+            }
+            PulsarAuthMode::Basic => PulsarCredentials::Basic {
+                username: required(self.username.as_deref(), "username")?.to_string(),
+                password: self.password.clone().unwrap_or_default(),
+            },
+            PulsarAuthMode::OAuth2 => {
+                let issuer_url = required(self.oauth2_issuer_url.as_deref(), "OAuth2 issuer URL")?;
+                let credentials_url = match non_empty(self.oauth2_credentials_file.as_deref()) {
+                    Some(path) if path.starts_with('/') => format!("file://{path}"),
+                    // Windows drive paths
+                    Some(path) => format!("file:///{}", path.replace('\\', "/")),
+                    None => {
+                        let json = serde_json::json!({
+                            "client_id": required(
+                                self.oauth2_client_id.as_deref(),
+                                "OAuth2 client ID"
+                            )?,
+                            "client_secret": self
+                                .oauth2_client_secret
+                                .as_deref()
+                                .unwrap_or_default(),
+                        });
+                        format!(
+                            "data:application/json;base64,{}",
+                            base64::engine::general_purpose::STANDARD.encode(json.to_string())
+                        )
+                    }
+                };
+                PulsarCredentials::OAuth2 {
+                    issuer_url: issuer_url.to_string(),
+                    credentials_url,
+                    audience: non_empty(self.oauth2_audience.as_deref()).map(str::to_string),
+                    scope: non_empty(self.oauth2_scope.as_deref()).map(str::to_string),
+                }
+            }
+        };
+        Ok(credentials)
+    }
+}
+
+/// Whether `url` uses TLS (`pulsar+ssl://`)
+pub fn is_pulsar_tls_url(url: &str) -> bool {
+    url.trim()
+        .split_once("://")
+        .is_some_and(|(scheme, _)| scheme.eq_ignore_ascii_case("pulsar+ssl"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_credentials_per_mode() {
+        let mut security = PulsarSecurityConfig::default();
+        assert_eq!(
+            security.credentials(Some(" jwt ")).expect("static token"),
+            PulsarCredentials::Token("jwt".to_string())
+        );
+        assert_eq!(
+            security.credentials(Some("")).expect("no token"),
+            PulsarCredentials::None
+        );
+
+        security.auth_mode = PulsarAuthMode::TokenEnv;
+        security.token_env = Some("DFC_GUI_TEST_MISSING_PULSAR_TOKEN".to_string());
+        assert!(security.credentials(None).is_err());
+
+        security.auth_mode = PulsarAuthMode::Basic;
+        assert!(security.credentials(None).is_err());
+        security.username = Some("ops".to_string());
+        security.password = Some("secret".to_string());
+        assert_eq!(
+            security.credentials(None).expect("basic"),
+            PulsarCredentials::Basic {
+                username: "ops".to_string(),
+                password: "secret".to_string(),
+            }
+        );
+
+        security.auth_mode = PulsarAuthMode::OAuth2;
+        security.oauth2_issuer_url = Some("https://auth.example.com".to_string());
+        security.oauth2_client_id = Some("dfc".to_string());
+        security.oauth2_client_secret = Some("s3cret".to_string());
+        let PulsarCredentials::OAuth2 {
+            credentials_url, ..
+        } = security.credentials(None).expect("oauth2")
+        else {
+            panic!("expected OAuth2 credentials");
+        };
+        let encoded = credentials_url
+            .strip_prefix("data:application/json;base64,")
+            .expect("data URL");
+        let decoded = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .expect("base64");
+        let json: serde_json::Value = serde_json::from_slice(&decoded).expect("json");
+        assert_eq!(json["client_id"], "dfc");
+        assert_eq!(json["client_secret"], "s3cret");
+
+        security.oauth2_credentials_file = Some("/etc/dfc/key.json".to_string());
+        assert!(matches!(
+            security.credentials(None),
+            Ok(PulsarCredentials::OAuth2 { credentials_url, .. })
+                if credentials_url == "file:///etc/dfc/key.json"
+        ));
+    }
+
+    #[test]
+    fn detects_tls_urls() {
+        assert!(is_pulsar_tls_url("pulsar+ssl://broker:6651"));
+        assert!(is_pulsar_tls_url(" PULSAR+SSL://broker"));
+        assert!(!is_pulsar_tls_url("pulsar://broker:6650"));
+    }
+}
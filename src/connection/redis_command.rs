@@ -0,0 +1,622 @@
+//! Redis console commands
+//!
+//! Parsing, completion and classification of raw command lines typed into the
+//! console, plus the reply tree the console renders.
+
+/// Command names offered by tab completion (upper case, sorted)
+pub const REDIS_COMMANDS: &[&str] = &[
+    "APPEND",
+    "BITCOUNT",
+    "BITPOS",
+    "CLIENT",
+    "CLUSTER",
+    "CONFIG",
+    "COPY",
+    "DBSIZE",
+    "DECR",
+    "DECRBY",
+    "DEL",
+    "DUMP",
+    "ECHO",
+    "EVAL",
+    "EVALSHA",
+    "EXISTS",
+    "EXPIRE",
+    "EXPIREAT",
+    "EXPIRETIME",
+    "FLUSHALL",
+    "FLUSHDB",
+    "GEOADD",
+    "GEODIST",
+    "GEOPOS",
+    "GEOSEARCH",
+    "GET",
+    "GETDEL",
+    "GETEX",
+    "GETRANGE",
+    "GETSET",
+    "HDEL",
+    "HEXISTS",
+    "HGET",
+    "HGETALL",
+    "HINCRBY",
+    "HINCRBYFLOAT",
+    "HKEYS",
+    "HLEN",
+    "HMGET",
+    "HMSET",
+    "HRANDFIELD",
+    "HSCAN",
+    "HSET",
+    "HSETNX",
+    "HSTRLEN",
+    "HVALS",
+    "INCR",
+    "INCRBY",
+    "INCRBYFLOAT",
+    "INFO",
+    "KEYS",
+    "LASTSAVE",
+    "LATENCY",
+    "LINDEX",
+    "LINSERT",
+    "LLEN",
+    "LMOVE",
+    "LPOP",
+    "LPOS",
+    "LPUSH",
+    "LPUSHX",
+    "LRANGE",
+    "LREM",
+    "LSET",
+    "LTRIM",
+    "MEMORY",
+    "MGET",
+    "MSET",
+    "MSETNX",
+    "OBJECT",
+    "PERSIST",
+    "PEXPIRE",
+    "PEXPIREAT",
+    "PFADD",
+    "PFCOUNT",
+    "PFMERGE",
+    "PING",
+    "PSETEX",
+    "PTTL",
+    "PUBLISH",
+    "PUBSUB",
+    "RANDOMKEY",
+    "RENAME",
+    "RENAMENX",
+    "RESTORE",
+    "ROLE",
+    "RPOP",
+    "RPUSH",
+    "RPUSHX",
+    "SADD",
+    "SCAN",
+    "SCARD",
+    "SCRIPT",
+    "SDIFF",
+    "SDIFFSTORE",
+    "SET",
+    "SETEX",
+    "SETNX",
+    "SETRANGE",
+    "SINTER",
+    "SINTERSTORE",
+    "SISMEMBER",
+    "SLOWLOG",
+    "SMEMBERS",
+    "SMISMEMBER",
+    "SMOVE",
+    "SPOP",
+    "SRANDMEMBER",
+    "SREM",
+    "SSCAN",
+    "STRLEN",
+    "SUNION",
+    "SUNIONSTORE",
+    "TIME",
+    "TOUCH",
+    "TTL",
+    "TYPE",
+    "UNLINK",
+    "XACK",
+    "XADD",
+    "XAUTOCLAIM",
+    "XCLAIM",
+    "XDEL",
+    "XGROUP",
+    "XINFO",
+    "XLEN",
+    "XPENDING",
+    "XRANGE",
+    "XREAD",
+    "XREVRANGE",
+    "XTRIM",
+    "ZADD",
+    "ZCARD",
+    "ZCOUNT",
+    "ZINCRBY",
+    "ZMSCORE",
+    "ZPOPMAX",
+    "ZPOPMIN",
+    "ZRANDMEMBER",
+    "ZRANGE",
+    "ZRANGEBYSCORE",
+    "ZRANK",
+    "ZREM",
+    "ZREMRANGEBYRANK",
+    "ZREMRANGEBYSCORE",
+    "ZREVRANGE",
+    "ZREVRANK",
+    "ZSCAN",
+    "ZSCORE",
+    "ZUNIONSTORE",
+];
+
+/// Commands that only read data or server state.
+///
+/// Classification is an allowlist: anything not listed here (including
+/// commands added by newer servers or modules) is treated as a write.
+const READ_ONLY_COMMANDS: &[&str] = &[
+    "BITCOUNT",
+    "BITFIELD_RO",
+    "BITPOS",
+    "DBSIZE",
+    "DUMP",
+    "ECHO",
+    "EVALSHA_RO",
+    "EVAL_RO",
+    "EXISTS",
+    "EXPIRETIME",
+    "FCALL_RO",
+    "GEODIST",
+    "GEOHASH",
+    "GEOPOS",
+    "GEORADIUSBYMEMBER_RO",
+    "GEORADIUS_RO",
+    "GEOSEARCH",
+    "GET",
+    "GETBIT",
+    "GETRANGE",
+    "HEXISTS",
+    "HGET",
+    "HGETALL",
+    "HKEYS",
+    "HLEN",
+    "HMGET",
+    "HRANDFIELD",
+    "HSCAN",
+    "HSTRLEN",
+    "HVALS",
+    "INFO",
+    "KEYS",
+    "LASTSAVE",
+    "LCS",
+    "LINDEX",
+    "LLEN",
+    "LOLWUT",
+    "LPOS",
+    "LRANGE",
+    "MGET",
+    "PEXPIRETIME",
+    "PFCOUNT",
+    "PING",
+    "PTTL",
+    "RANDOMKEY",
+    "ROLE",
+    "SCAN",
+    "SCARD",
+    "SDIFF",
+    "SINTER",
+    "SINTERCARD",
+    "SISMEMBER",
+    "SMEMBERS",
+    "SMISMEMBER",
+    "SORT_RO",
+    "SRANDMEMBER",
+    "SSCAN",
+    "STRLEN",
+    "SUBSTR",
+    "SUNION",
+    "TIME",
+    "TOUCH",
+    "TTL",
+    "TYPE",
+    "XLEN",
+    "XPENDING",
+    "XRANGE",
+    "XREAD",
+    "XREVRANGE",
+    "ZCARD",
+    "ZCOUNT",
+    "ZDIFF",
+    "ZINTER",
+    "ZINTERCARD",
+    "ZLEXCOUNT",
+    "ZMSCORE",
+    "ZRANDMEMBER",
+    "ZRANGE",
+    "ZRANGEBYLEX",
+    "ZRANGEBYSCORE",
+    "ZRANK",
+    "ZREVRANGE",
+    "ZREVRANGEBYLEX",
+    "ZREVRANGEBYSCORE",
+    "ZREVRANK",
+    "ZSCAN",
+    "ZSCORE",
+    "ZUNION",
+];
+
+/// Container commands whose listed subcommands only read state; any other
+/// subcommand of these is treated as a write
+const READ_ONLY_SUBCOMMANDS: &[(&str, &[&str])] = &[
+    ("ACL", &["CAT", "GETUSER", "LIST", "LOG", "USERS", "WHOAMI"]),
+    ("CLIENT", &["GETNAME", "ID", "INFO", "LIST", "TRACKINGINFO"]),
+    (
+        "CLUSTER",
+        &[
+            "COUNTKEYSINSLOT",
+            "GETKEYSINSLOT",
+            "INFO",
+            "KEYSLOT",
+            "LINKS",
+            "MYID",
+            "MYSHARDID",
+            "NODES",
+            "REPLICAS",
+            "SHARDS",
+            "SLAVES",
+            "SLOTS",
+        ],
+    ),
+    ("COMMAND", &["COUNT", "DOCS", "GETKEYS", "INFO", "LIST"]),
+    ("CONFIG", &["GET"]),
+    ("FUNCTION", &["DUMP", "LIST", "STATS"]),
+    (
+        "LATENCY",
+        &["DOCTOR", "GRAPH", "HISTOGRAM", "HISTORY", "LATEST"],
+    ),
+    ("MEMORY", &["DOCTOR", "MALLOC-STATS", "STATS", "USAGE"]),
+    ("MODULE", &["LIST"]),
+    ("OBJECT", &["ENCODING", "FREQ", "IDLETIME", "REFCOUNT"]),
+    (
+        "PUBSUB",
+        &[
+            "CHANNELS",
+            "NUMPAT",
+            "NUMSUB",
+            "SHARDCHANNELS",
+            "SHARDNUMSUB",
+        ],
+    ),
+    ("SCRIPT", &["EXISTS"]),
+    ("SLOWLOG", &["GET", "LEN"]),
+    ("XINFO", &["CONSUMERS", "GROUPS", "STREAM"]),
+];
+
+/// Commands that block the shared connection or switch it into a push mode
+const UNSUPPORTED_COMMANDS: &[&str] = &[
+    "BLMOVE",
+    "BLPOP",
+    "BRPOP",
+    "BZPOPMAX",
+    "BZPOPMIN",
+    "AUTH",
+    "HELLO",
+    "MONITOR",
+    "MULTI",
+    "PSUBSCRIBE",
+    "PSYNC",
+    "QUIT",
+    "RESET",
+    "SELECT",
+    "SSUBSCRIBE",
+    "SUBSCRIBE",
+    "SYNC",
+    "WAIT",
+];
+
+/// Split a command line into arguments.
+///
+/// Supports double quotes with `\"`, `\\`, `\n`, `\t` and `\xHH` escapes and
+/// single quotes taken literally, like `redis-cli`.
+pub fn parse_command_line(line: &str) -> Result<Vec<String>, String> {
+    let mut args = Vec::new();
+    let mut chars = line.chars().peekable();
+
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        let Some(&first) = chars.peek() else {
+            break;
+        };
+
+        let mut arg = String::new();
+        match first {
+            '"' => {
+                chars.next();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => arg.push('\n'),
+                            Some('r') => arg.push('\r'),
+                            Some('t') => arg.push('\t'),
+                            Some('x') => {
+                                let hex: String = chars.by_ref().take(2).collect();
+                                let byte = u8::from_str_radix(&hex, 16)
+                                    .map_err(|_| format!("Invalid escape: \\x{hex}"))?;
+                                arg.push(char::from(byte));
+                            }
+                            Some(other) => arg.push(other),
+                            None => return Err("Unbalanced quotes".to_string()),
+                        },
+                        Some(c) => arg.push(c),
+                        None => return Err("Unbalanced quotes".to_string()),
+                    }
+                }
+            }
+            '\'' => {
+                chars.next();
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => arg.push(c),
+                        None => return Err("Unbalanced quotes".to_string()),
+                    }
+                }
+            }
+            _ => {
+                while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                    arg.push(c);
+                }
+            }
+        }
+        if chars.peek().is_some_and(|c| !c.is_whitespace()) {
+            return Err("Closing quote must be followed by a space".to_string());
+        }
+        args.push(arg);
+    }
+
+    Ok(args)
+}
+
+/// Command names starting with `prefix` (case-insensitive)
+pub fn complete_command(prefix: &str) -> Vec<&'static str> {
+    let prefix = prefix.to_ascii_uppercase();
+    REDIS_COMMANDS
+        .iter()
+        .copied()
+        .filter(|name| name.starts_with(&prefix))
+        .collect()
+}
+
+/// Longest prefix shared by all candidates
+pub fn common_prefix<'a>(candidates: &[&'a str]) -> &'a str {
+    let Some((first, rest)) = candidates.split_first() else {
+        return "";
+    };
+    let mut len = first.len();
+    for candidate in rest {
+        len = first
+            .bytes()
+            .zip(candidate.bytes())
+            .take(len)
+            .take_while(|(a, b)| a == b)
+            .count();
+    }
+    &first[..len]
+}
+
+/// Whether the command (name plus arguments) may modify data or server state.
+///
+/// Only commands on the read-only allowlist pass; unknown commands count as
+/// writes.
+pub fn is_write_command(args: &[String]) -> bool {
+    let Some(name) = args.first().map(|name| name.to_ascii_uppercase()) else {
+        return false;
+    };
+    if READ_ONLY_COMMANDS.contains(&name.as_str()) {
+        return false;
+    }
+    let sub = args.get(1).map(|sub| sub.to_ascii_uppercase());
+    !READ_ONLY_SUBCOMMANDS.iter().any(|(command, subs)| {
+        *command == name && sub.as_deref().is_some_and(|sub| subs.contains(&sub))
+    })
+}
+
+/// Whether the command carries a credential and must not be kept in the
+/// console history: AUTH, HELLO/MIGRATE with AUTH, ACL SETUSER and
+/// CONFIG SET of a password parameter
+pub fn has_credentials(args: &[String]) -> bool {
+    let Some(name) = args.first().map(|name| name.to_ascii_uppercase()) else {
+        return false;
+    };
+    let sub = args.get(1).map(|sub| sub.to_ascii_uppercase());
+    match name.as_str() {
+        "AUTH" => true,
+        "HELLO" | "MIGRATE" => args[1..]
+            .iter()
+            .any(|arg| arg.eq_ignore_ascii_case("AUTH") || arg.eq_ignore_ascii_case("AUTH2")),
+        "ACL" => sub.as_deref() == Some("SETUSER"),
+        "CONFIG" if sub.as_deref() == Some("SET") => args.iter().skip(2).step_by(2).any(|param| {
+            let param = param.to_ascii_lowercase();
+            param.contains("pass") || param == "masterauth"
+        }),
+        _ => false,
+    }
+}
+
+/// Whether the command (name plus arguments) cannot run on the shared
+/// connection: it blocks, switches protocol or authentication, or stops
+/// replies from coming back
+pub fn is_unsupported_command(args: &[String]) -> bool {
+    let Some(name) = args.first().map(|name| name.to_ascii_uppercase()) else {
+        return false;
+    };
+    if UNSUPPORTED_COMMANDS.contains(&name.as_str()) {
+        return true;
+    }
+    name == "CLIENT"
+        && args
+            .get(1)
+            .is_some_and(|sub| sub.eq_ignore_ascii_case("REPLY"))
+}
+
+/// Reply of a console command, covering RESP2 and RESP3 types
+#[derive(Debug, Clone, PartialEq)]
+pub enum RedisReply {
+    Nil,
+    Integer(i64),
+    Double(f64),
+    Boolean(bool),
+    /// Simple or bulk string
+    String(String),
+    Array(Vec<RedisReply>),
+    /// RESP3 map (or attribute) as ordered pairs
+    Map(Vec<(String, RedisReply)>),
+    /// Error reply from the server, or a command rejected by the console
+    Error(String),
+}
+
+impl RedisReply {
+    /// Whether the reply has children to expand
+    pub fn is_nested(&self) -> bool {
+        matches!(self, Self::Array(items) if !items.is_empty())
+            || matches!(self, Self::Map(pairs) if !pairs.is_empty())
+    }
+
+    /// One-line rendering in `redis-cli` style; nested replies show their size
+    pub fn summary(&self) -> String {
+        match self {
+            Self::Nil => "(nil)".to_string(),
+            Self::Integer(v) => format!("(integer) {v}"),
+            Self::Double(v) => format!("(double) {v}"),
+            Self::Boolean(v) => format!("({v})"),
+            Self::String(v) => format!("\"{}\"", v.escape_debug()),
+            Self::Array(items) if items.is_empty() => "(empty array)".to_string(),
+            Self::Array(items) => format!("(array) {} items", items.len()),
+            Self::Map(pairs) if pairs.is_empty() => "(empty map)".to_string(),
+            Self::Map(pairs) => format!("(map) {} entries", pairs.len()),
+            Self::Error(message) => format!("(error) {message}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        parse_command_line(line).expect("valid command line")
+    }
+
+    #[test]
+    fn credential_commands_are_recognized() {
+        assert!(has_credentials(&args("auth ops hunter2")));
+        assert!(has_credentials(&args("HELLO 3 AUTH ops hunter2")));
+        assert!(has_credentials(&args("ACL SETUSER ops on >hunter2")));
+        assert!(has_credentials(&args(
+            "CONFIG SET maxmemory 1gb requirepass hunter2"
+        )));
+        assert!(has_credentials(&args("config set masterauth hunter2")));
+        assert!(!has_credentials(&args("CONFIG SET maxmemory 1gb")));
+        assert!(!has_credentials(&args("CONFIG GET requirepass")));
+        assert!(!has_credentials(&args("GET auth")));
+        assert!(!has_credentials(&args("HELLO 3")));
+    }
+
+    #[test]
+    fn parse_command_line_handles_quotes_and_escapes() {
+        assert_eq!(args("  GET   cfg:1 "), vec!["GET", "cfg:1"]);
+        assert_eq!(
+            args(r#"SET k "wind farm \"A\"\n""#),
+            vec!["SET", "k", "wind farm \"A\"\n"]
+        );
+        assert_eq!(args(r"SET k 'a\b c'"), vec!["SET", "k", r"a\b c"]);
+        assert_eq!(args(r#"SET k "\x41""#), vec!["SET", "k", "A"]);
+        assert_eq!(args(r#"SET k """#), vec!["SET", "k", ""]);
+        assert!(args("").is_empty());
+        assert!(parse_command_line(r#"GET "open"#).is_err());
+        assert!(parse_command_line(r#"GET "a"b"#).is_err());
+    }
+
+    #[test]
+    fn complete_command_matches_case_insensitively() {
+        assert_eq!(complete_command("hge"), vec!["HGET", "HGETALL"]);
+        assert_eq!(common_prefix(&complete_command("hge")), "HGET");
+        assert_eq!(common_prefix(&complete_command("zrev")), "ZREVRAN");
+        assert!(complete_command("nope").is_empty());
+    }
+
+    #[test]
+    fn write_commands_are_detected_by_name_and_subcommand() {
+        assert!(is_write_command(&args("set k v")));
+        assert!(is_write_command(&args("CONFIG set maxmemory 1")));
+        assert!(!is_write_command(&args("CONFIG GET maxmemory")));
+        assert!(!is_write_command(&args("HGETALL k")));
+        assert!(!is_write_command(&[]));
+        assert!(is_unsupported_command(&args("subscribe news")));
+        assert!(!is_unsupported_command(&args("GET k")));
+    }
+
+    #[test]
+    fn write_commands_missing_from_the_allowlist_are_writes() {
+        for line in [
+            "RPOPLPUSH a b",
+            "SETBIT k 1 1",
+            "BITOP AND dest a b",
+            "BITFIELD k SET u8 0 1",
+            "SORT k STORE dest",
+            "SORT k",
+            "ZINTERSTORE dest 2 a b",
+            "ZDIFFSTORE dest 2 a b",
+            "ZRANGESTORE dest src 0 -1",
+            "GEOSEARCHSTORE dest src FROMMEMBER m BYRADIUS 1 km",
+            "LMPOP 1 k LEFT",
+            "ZMPOP 1 k MIN",
+            "REPLICAOF host 6379",
+            "SLAVEOF NO ONE",
+            "ACL SETUSER bob on",
+            "ACL DELUSER bob",
+            "FUNCTION LOAD code",
+            "FUNCTION DELETE lib",
+            "FUNCTION FLUSH",
+            "FCALL fn 0",
+            "DEBUG SLEEP 0",
+            "MODULE LOAD /tmp/m.so",
+            "CLIENT KILL ID 1",
+            "OBJECT",
+            "SOMEFUTURECOMMAND k",
+        ] {
+            assert!(is_write_command(&args(line)), "{line}");
+        }
+        for line in [
+            "ACL WHOAMI",
+            "CLIENT LIST",
+            "FUNCTION LIST",
+            "MODULE LIST",
+            "SLOWLOG GET 10",
+            "OBJECT ENCODING k",
+            "SORT_RO k",
+            "FCALL_RO fn 0",
+        ] {
+            assert!(!is_write_command(&args(line)), "{line}");
+        }
+    }
+
+    #[test]
+    fn connection_state_commands_are_unsupported() {
+        assert!(is_unsupported_command(&args("HELLO 3")));
+        assert!(is_unsupported_command(&args("auth user secret")));
+        assert!(is_unsupported_command(&args("CLIENT REPLY OFF")));
+        assert!(is_unsupported_command(&args("client reply skip")));
+        assert!(!is_unsupported_command(&args("CLIENT LIST")));
+        assert!(!is_unsupported_command(&[]));
+        assert!(complete_command("HEL").is_empty());
+    }
+}
@@ -2,10 +2,12 @@
 //!
 //! Data structures for storing configuration items loaded from Redis.
 
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::sync::Arc;
 
 /// TopicAgentId item with associated topics
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TopicAgentItem {
     /// The TopicAgentId value
     pub agent_id: String,
@@ -16,7 +18,7 @@ pub struct TopicAgentItem {
 }
 
 /// Topic detail for TopicAgentItem
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TopicDetail {
     /// Index within the topic list (as provided by config, if any)
     pub index: i32,
@@ -29,7 +31,7 @@ pub struct TopicDetail {
 }
 
 /// Configuration item loaded from Redis
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ConfigItem {
     /// Group ID (sequence number)
     pub group_id: i32,
@@ -44,7 +46,7 @@ pub struct ConfigItem {
 }
 
 /// Topic detail item
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DetailItem {
     /// Index within the config group
     pub index: i32,
@@ -164,11 +166,128 @@ impl RedisKeyItem {
     }
 }
 
+/// A single stream entry returned by XRANGE/XREVRANGE
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RedisStreamEntry {
+    /// Entry ID (e.g., "1718000000000-0")
+    pub id: String,
+    /// Field-value pairs in entry order
+    pub fields: Vec<(String, String)>,
+}
+
+/// Stream summary from XINFO STREAM
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RedisStreamInfo {
+    /// Number of entries in the stream
+    pub length: i64,
+    /// Number of consumer groups
+    pub groups: i64,
+    /// Last generated entry ID
+    pub last_generated_id: String,
+    /// ID of the first entry, if any
+    pub first_entry_id: Option<String>,
+    /// ID of the last entry, if any
+    pub last_entry_id: Option<String>,
+}
+
+/// Consumer group summary from XINFO GROUPS
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RedisStreamGroup {
+    /// Group name
+    pub name: String,
+    /// Number of consumers in the group
+    pub consumers: i64,
+    /// Number of pending (delivered but unacknowledged) entries
+    pub pending: i64,
+    /// Last entry ID delivered to the group
+    pub last_delivered_id: String,
+    /// Entries not yet delivered to the group (Redis 7+)
+    pub lag: Option<i64>,
+}
+
+/// Pending entry of a consumer group from XPENDING
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RedisStreamPendingEntry {
+    /// Entry ID
+    pub id: String,
+    /// Consumer owning the entry
+    pub consumer: String,
+    /// Milliseconds since the entry was last delivered
+    pub idle_ms: i64,
+    /// Number of times the entry was delivered
+    pub delivery_count: i64,
+}
+
+/// Stream value: summary, one page of entries, and consumer groups
+#[derive(Debug, Clone, Default)]
+pub struct RedisStreamValue {
+    /// XINFO STREAM summary
+    pub info: RedisStreamInfo,
+    /// Loaded entries, in page order
+    pub entries: Vec<RedisStreamEntry>,
+    /// Whether entries are paged newest-first (XREVRANGE)
+    pub reverse: bool,
+    /// Whether more entries are available after the last loaded one
+    pub has_more: bool,
+    /// Consumer groups
+    pub groups: Vec<RedisStreamGroup>,
+    /// Pending entries per group name
+    pub pending: Vec<(String, Vec<RedisStreamPendingEntry>)>,
+}
+
+impl RedisStreamValue {
+    /// ID of the last loaded entry, used as the exclusive start of the next page
+    pub fn last_entry_id(&self) -> Option<&str> {
+        self.entries.last().map(|entry| entry.id.as_str())
+    }
+}
+
+/// Position of the next page of a collection value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RedisValueCursor {
+    /// HSCAN/SSCAN/ZSCAN cursor (never 0; a finished scan is `Done`)
+    Scan(u64),
+    /// Offset for LRANGE/ZRANGE paging
+    Offset(i64),
+    /// All elements are loaded
+    #[default]
+    Done,
+}
+
+impl RedisValueCursor {
+    /// Whether more elements are available
+    pub fn has_more(&self) -> bool {
+        !matches!(self, Self::Done)
+    }
+
+    /// Build a scan cursor from a SCAN-family reply cursor
+    pub fn from_scan(cursor: u64) -> Self {
+        if cursor == 0 {
+            Self::Done
+        } else {
+            Self::Scan(cursor)
+        }
+    }
+}
+
+/// One page of a collection value
+#[derive(Debug, Clone)]
+pub struct RedisValuePage {
+    /// Elements of this page
+    pub value: RedisKeyValue,
+    /// Where the next page starts
+    pub next: RedisValueCursor,
+    /// Total element count of the key (HLEN/SCARD/LLEN/ZCARD), if known
+    pub total: Option<i64>,
+}
+
 /// Redis key value representation
 #[derive(Debug, Clone)]
 pub enum RedisKeyValue {
     /// String value
     String(String),
+    /// String value that is not valid UTF-8
+    Bytes(Vec<u8>),
     /// Hash value (field-value pairs)
     Hash(Vec<(String, String)>),
     /// List value (ordered elements)
@@ -177,6 +296,8 @@ pub enum RedisKeyValue {
     Set(Vec<String>),
     /// Sorted set value (elements with scores)
     ZSet(Vec<(String, f64)>),
+    /// Stream value (entries, groups and pending lists)
+    Stream(RedisStreamValue),
     /// Loading state
     Loading,
     /// Error state
@@ -185,6 +306,68 @@ pub enum RedisKeyValue {
     Empty,
 }
 
+impl RedisKeyValue {
+    /// Number of loaded elements for collection values
+    pub fn loaded_len(&self) -> usize {
+        match self {
+            Self::Hash(pairs) => pairs.len(),
+            Self::List(items) | Self::Set(items) => items.len(),
+            Self::ZSet(members) => members.len(),
+            Self::Stream(stream) => stream.entries.len(),
+            Self::String(_) | Self::Bytes(_) | Self::Loading | Self::Error(_) | Self::Empty => 0,
+        }
+    }
+
+    /// Key type of a loaded collection value
+    pub fn key_type(&self) -> Option<RedisKeyType> {
+        match self {
+            Self::String(_) | Self::Bytes(_) => Some(RedisKeyType::String),
+            Self::Hash(_) => Some(RedisKeyType::Hash),
+            Self::List(_) => Some(RedisKeyType::List),
+            Self::Set(_) => Some(RedisKeyType::Set),
+            Self::ZSet(_) => Some(RedisKeyType::ZSet),
+            Self::Stream(_) => Some(RedisKeyType::Stream),
+            Self::Loading | Self::Error(_) | Self::Empty => None,
+        }
+    }
+
+    /// Append the elements of a following page of the same type.
+    ///
+    /// HSCAN/SSCAN/ZSCAN may return an element again on a later cursor, so
+    /// fields and members already loaded are skipped. Returns `false` if the
+    /// page type does not match.
+    pub fn append_page(&mut self, page: RedisKeyValue) -> bool {
+        match (self, page) {
+            (Self::Hash(pairs), Self::Hash(more)) => {
+                let mut loaded: HashSet<String> =
+                    pairs.iter().map(|(field, _)| field.clone()).collect();
+                pairs.extend(
+                    more.into_iter()
+                        .filter(|(field, _)| loaded.insert(field.clone())),
+                );
+            }
+            (Self::List(items), Self::List(more)) => items.extend(more),
+            (Self::Set(members), Self::Set(more)) => {
+                let mut loaded: HashSet<String> = members.iter().cloned().collect();
+                members.extend(
+                    more.into_iter()
+                        .filter(|member| loaded.insert(member.clone())),
+                );
+            }
+            (Self::ZSet(members), Self::ZSet(more)) => {
+                let mut loaded: HashSet<String> =
+                    members.iter().map(|(member, _)| member.clone()).collect();
+                members.extend(
+                    more.into_iter()
+                        .filter(|(member, _)| loaded.insert(member.clone())),
+                );
+            }
+            _ => return false,
+        }
+        true
+    }
+}
+
 impl Default for RedisKeyValue {
     fn default() -> Self {
         Self::Empty
@@ -201,3 +384,33 @@ pub struct ConnectedServerInfo {
     /// Selected config source (Redis key)
     pub config_source: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn append_page_skips_elements_a_scan_returned_again() {
+        let mut hash = RedisKeyValue::Hash(vec![("a".to_string(), "1".to_string())]);
+        assert!(hash.append_page(RedisKeyValue::Hash(vec![
+            ("a".to_string(), "1".to_string()),
+            ("b".to_string(), "2".to_string()),
+        ])));
+        assert_eq!(hash.loaded_len(), 2);
+
+        let mut set = RedisKeyValue::Set(vec!["x".to_string()]);
+        assert!(set.append_page(RedisKeyValue::Set(vec!["y".to_string(), "x".to_string()])));
+        assert_eq!(set.loaded_len(), 2);
+
+        let mut zset = RedisKeyValue::ZSet(vec![("m".to_string(), 1.0)]);
+        assert!(zset.append_page(RedisKeyValue::ZSet(vec![("m".to_string(), 1.0)])));
+        assert_eq!(zset.loaded_len(), 1);
+
+        // List pages come from LRANGE and may hold equal values
+        let mut list = RedisKeyValue::List(vec!["v".to_string()]);
+        assert!(list.append_page(RedisKeyValue::List(vec!["v".to_string()])));
+        assert_eq!(list.loaded_len(), 2);
+
+        assert!(!list.append_page(RedisKeyValue::Set(Vec::new())));
+    }
+}
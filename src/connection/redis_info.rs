@@ -0,0 +1,498 @@
+//! Redis server diagnostics
+//!
+//! Data types and text parsers for the server dashboard: `INFO`, `CLIENT LIST`,
+//! `CLUSTER NODES` and `SLOWLOG GET`.
+
+use crate::helpers::format_bytes;
+use std::collections::{BTreeMap, HashMap};
+
+/// Number of hash slots in a Redis Cluster
+pub const CLUSTER_SLOT_COUNT: usize = 16384;
+
+/// Parse an `INFO` reply into `field -> value`, skipping section headers
+pub fn parse_info(text: &str) -> HashMap<String, String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once(':'))
+        .map(|(field, value)| (field.to_string(), value.to_string()))
+        .collect()
+}
+
+/// Parse a comma-separated `k=v` list, e.g. `keys=10,expires=2,avg_ttl=0`
+fn parse_pairs(text: &str) -> HashMap<&str, &str> {
+    text.split(',')
+        .filter_map(|pair| pair.split_once('='))
+        .collect()
+}
+
+/// Key statistics of one logical database (INFO keyspace)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RedisKeyspaceDb {
+    pub db: u32,
+    pub keys: u64,
+    pub expires: u64,
+    /// Average TTL of keys with an expiry, in milliseconds
+    pub avg_ttl_ms: u64,
+}
+
+/// Server summary extracted from `INFO`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RedisServerStats {
+    pub version: String,
+    /// `standalone`, `sentinel` or `cluster`
+    pub mode: String,
+    pub uptime_secs: u64,
+    pub used_memory: u64,
+    pub used_memory_human: String,
+    pub used_memory_peak: u64,
+    pub used_memory_peak_human: String,
+    /// Configured memory limit in bytes (0 means no limit)
+    pub maxmemory: u64,
+    pub maxmemory_human: String,
+    pub ops_per_sec: u64,
+    pub connected_clients: u64,
+    pub blocked_clients: u64,
+    pub keyspace_hits: u64,
+    pub keyspace_misses: u64,
+    /// `master` or `slave`
+    pub role: String,
+    /// `host:port` of the master when this server is a replica
+    pub master: Option<String>,
+    pub master_link_status: Option<String>,
+    /// Replicas of this master as `ip:port (state, lag)`
+    pub replicas: Vec<String>,
+    pub cluster_enabled: bool,
+    pub keyspace: Vec<RedisKeyspaceDb>,
+}
+
+impl RedisServerStats {
+    /// Build the summary from parsed `INFO` fields
+    pub fn from_info(info: &HashMap<String, String>) -> Self {
+        let text = |field: &str| info.get(field).cloned().unwrap_or_default();
+        let number = |field: &str| {
+            info.get(field)
+                .and_then(|v| v.trim().parse::<u64>().ok())
+                .unwrap_or_default()
+        };
+
+        let master = info
+            .get("master_host")
+            .map(|host| format!("{host}:{}", text("master_port")));
+
+        let mut replicas: Vec<(usize, String)> = info
+            .iter()
+            .filter_map(|(field, value)| {
+                let index = field.strip_prefix("slave")?.parse::<usize>().ok()?;
+                let pairs = parse_pairs(value);
+                let addr = format!(
+                    "{}:{}",
+                    pairs.get("ip").copied().unwrap_or("?"),
+                    pairs.get("port").copied().unwrap_or("?")
+                );
+                let state = pairs.get("state").copied().unwrap_or("?");
+                let lag = pairs.get("lag").copied().unwrap_or("?");
+                Some((index, format!("{addr} ({state}, lag {lag})")))
+            })
+            .collect();
+        replicas.sort_by_key(|(index, _)| *index);
+
+        let mut keyspace: Vec<RedisKeyspaceDb> = info
+            .iter()
+            .filter_map(|(field, value)| {
+                let db = field.strip_prefix("db")?.parse::<u32>().ok()?;
+                let pairs = parse_pairs(value);
+                let get = |name: &str| {
+                    pairs
+                        .get(name)
+                        .and_then(|v| v.parse::<u64>().ok())
+                        .unwrap_or_default()
+                };
+                Some(RedisKeyspaceDb {
+                    db,
+                    keys: get("keys"),
+                    expires: get("expires"),
+                    avg_ttl_ms: get("avg_ttl"),
+                })
+            })
+            .collect();
+        keyspace.sort_by_key(|db| db.db);
+
+        Self {
+            version: text("redis_version"),
+            mode: text("redis_mode"),
+            uptime_secs: number("uptime_in_seconds"),
+            used_memory: number("used_memory"),
+            used_memory_human: text("used_memory_human"),
+            used_memory_peak: number("used_memory_peak"),
+            used_memory_peak_human: text("used_memory_peak_human"),
+            maxmemory: number("maxmemory"),
+            maxmemory_human: text("maxmemory_human"),
+            ops_per_sec: number("instantaneous_ops_per_sec"),
+            connected_clients: number("connected_clients"),
+            blocked_clients: number("blocked_clients"),
+            keyspace_hits: number("keyspace_hits"),
+            keyspace_misses: number("keyspace_misses"),
+            role: text("role"),
+            master,
+            master_link_status: info.get("master_link_status").cloned(),
+            replicas: replicas.into_iter().map(|(_, replica)| replica).collect(),
+            cluster_enabled: text("cluster_enabled") == "1" || text("redis_mode") == "cluster",
+            keyspace,
+        }
+    }
+
+    /// Cluster-wide summary from the stats of every primary. Counters,
+    /// memory and key counts add up; version and mode come from the first
+    /// node, uptime from the youngest one.
+    pub fn combine(nodes: &[RedisServerStats]) -> Self {
+        let Some(first) = nodes.first() else {
+            return Self::default();
+        };
+        let sum = |field: fn(&RedisServerStats) -> u64| nodes.iter().map(field).sum::<u64>();
+
+        let used_memory = sum(|node| node.used_memory);
+        let used_memory_peak = sum(|node| node.used_memory_peak);
+        // A single node without a limit leaves the cluster without one
+        let maxmemory = if nodes.iter().any(|node| node.maxmemory == 0) {
+            0
+        } else {
+            sum(|node| node.maxmemory)
+        };
+
+        let mut keyspace: BTreeMap<u32, (u64, u64, u128)> = BTreeMap::new();
+        for db in nodes.iter().flat_map(|node| &node.keyspace) {
+            let (keys, expires, ttl_total) = keyspace.entry(db.db).or_default();
+            *keys += db.keys;
+            *expires += db.expires;
+            *ttl_total += u128::from(db.avg_ttl_ms) * u128::from(db.expires);
+        }
+
+        Self {
+            version: first.version.clone(),
+            mode: first.mode.clone(),
+            uptime_secs: nodes.iter().map(|node| node.uptime_secs).min().unwrap_or(0),
+            used_memory,
+            used_memory_human: format_bytes(used_memory),
+            used_memory_peak,
+            used_memory_peak_human: format_bytes(used_memory_peak),
+            maxmemory,
+            maxmemory_human: format_bytes(maxmemory),
+            ops_per_sec: sum(|node| node.ops_per_sec),
+            connected_clients: sum(|node| node.connected_clients),
+            blocked_clients: sum(|node| node.blocked_clients),
+            keyspace_hits: sum(|node| node.keyspace_hits),
+            keyspace_misses: sum(|node| node.keyspace_misses),
+            role: first.role.clone(),
+            master: None,
+            master_link_status: None,
+            replicas: nodes
+                .iter()
+                .flat_map(|node| node.replicas.iter().cloned())
+                .collect(),
+            cluster_enabled: nodes.iter().any(|node| node.cluster_enabled),
+            keyspace: keyspace
+                .into_iter()
+                .map(|(db, (keys, expires, ttl_total))| RedisKeyspaceDb {
+                    db,
+                    keys,
+                    expires,
+                    avg_ttl_ms: ttl_total
+                        .checked_div(u128::from(expires))
+                        .map_or(0, |avg| avg as u64),
+                })
+                .collect(),
+        }
+    }
+
+    /// Keyspace hit rate in percent, if any lookups happened
+    pub fn hit_rate(&self) -> Option<f64> {
+        let total = self.keyspace_hits + self.keyspace_misses;
+        (total > 0).then(|| self.keyspace_hits as f64 * 100.0 / total as f64)
+    }
+
+    /// Memory use relative to `maxmemory` in percent, if a limit is set
+    pub fn memory_usage(&self) -> Option<f64> {
+        (self.maxmemory > 0).then(|| self.used_memory as f64 * 100.0 / self.maxmemory as f64)
+    }
+}
+
+/// One connection from `CLIENT LIST`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RedisClientInfo {
+    pub id: String,
+    pub addr: String,
+    pub name: String,
+    /// Connection age in seconds
+    pub age_secs: u64,
+    /// Idle time in seconds
+    pub idle_secs: u64,
+    pub db: String,
+    pub flags: String,
+    /// Last command executed
+    pub cmd: String,
+}
+
+/// Parse a `CLIENT LIST` reply (one `k=v k=v ...` line per connection)
+pub fn parse_client_list(text: &str) -> Vec<RedisClientInfo> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let fields: HashMap<&str, &str> = line
+                .split_whitespace()
+                .filter_map(|pair| pair.split_once('='))
+                .collect();
+            let text = |name: &str| fields.get(name).copied().unwrap_or_default().to_string();
+            let number = |name: &str| {
+                fields
+                    .get(name)
+                    .and_then(|v| v.parse::<u64>().ok())
+                    .unwrap_or_default()
+            };
+            RedisClientInfo {
+                id: text("id"),
+                addr: text("addr"),
+                name: text("name"),
+                age_secs: number("age"),
+                idle_secs: number("idle"),
+                db: text("db"),
+                flags: text("flags"),
+                cmd: text("cmd"),
+            }
+        })
+        .collect()
+}
+
+/// One node from `CLUSTER NODES`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RedisClusterNode {
+    pub id: String,
+    /// `ip:port` (cluster bus port and hostname stripped)
+    pub addr: String,
+    /// Comma-separated flags, e.g. `myself,master`
+    pub flags: String,
+    /// Master node ID for replicas
+    pub master_id: Option<String>,
+    /// `connected` or `disconnected`
+    pub link_state: String,
+    /// Owned slot ranges (inclusive)
+    pub slots: Vec<(u16, u16)>,
+}
+
+impl RedisClusterNode {
+    /// Whether the node is a master
+    pub fn is_master(&self) -> bool {
+        self.flags.split(',').any(|flag| flag == "master")
+    }
+
+    /// Whether the node is flagged as failing
+    pub fn is_failing(&self) -> bool {
+        self.flags
+            .split(',')
+            .any(|flag| flag == "fail" || flag == "fail?")
+    }
+
+    /// Number of slots owned by this node
+    pub fn slot_count(&self) -> usize {
+        self.slots
+            .iter()
+            .map(|(start, end)| usize::from(end - start) + 1)
+            .sum()
+    }
+
+    /// Slot ranges formatted as `0-5460 10923`
+    pub fn slots_label(&self) -> String {
+        self.slots
+            .iter()
+            .map(|(start, end)| {
+                if start == end {
+                    start.to_string()
+                } else {
+                    format!("{start}-{end}")
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Parse a `CLUSTER NODES` reply
+pub fn parse_cluster_nodes(text: &str) -> Vec<RedisClusterNode> {
+    let mut nodes: Vec<RedisClusterNode> = text
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() < 8 {
+                return None;
+            }
+            let addr = parts[1].split(['@', ',']).next().unwrap_or_default();
+            let slots = parts[8..]
+                .iter()
+                // Importing/migrating markers look like `[slot->-node]`
+                .filter(|token| !token.starts_with('['))
+                .filter_map(|token| match token.split_once('-') {
+                    Some((start, end)) => Some((start.parse().ok()?, end.parse().ok()?)),
+                    None => {
+                        let slot = token.parse().ok()?;
+                        Some((slot, slot))
+                    }
+                })
+                .collect();
+            Some(RedisClusterNode {
+                id: parts[0].to_string(),
+                addr: addr.to_string(),
+                flags: parts[2].to_string(),
+                master_id: (parts[3] != "-").then(|| parts[3].to_string()),
+                link_state: parts[7].to_string(),
+                slots,
+            })
+        })
+        .collect();
+    nodes.sort_by(|a, b| a.addr.cmp(&b.addr));
+    nodes
+}
+
+/// Number of distinct slots served by healthy masters
+pub fn cluster_slot_coverage(nodes: &[RedisClusterNode]) -> usize {
+    let mut covered = vec![false; CLUSTER_SLOT_COUNT];
+    for node in nodes
+        .iter()
+        .filter(|node| node.is_master() && !node.is_failing())
+    {
+        for (start, end) in &node.slots {
+            let end = usize::from(*end).min(CLUSTER_SLOT_COUNT - 1);
+            if let Some(range) = covered.get_mut(usize::from(*start)..=end) {
+                range.fill(true);
+            }
+        }
+    }
+    covered.iter().filter(|covered| **covered).count()
+}
+
+/// One `SLOWLOG GET` entry
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RedisSlowlogEntry {
+    pub id: i64,
+    /// Unix timestamp (seconds) of the command
+    pub timestamp: i64,
+    pub duration_us: i64,
+    /// Command with arguments, space separated
+    pub command: String,
+    pub client_addr: String,
+    pub client_name: String,
+}
+
+/// Everything shown on the server dashboard
+#[derive(Debug, Clone, Default)]
+pub struct RedisDashboard {
+    pub stats: RedisServerStats,
+    pub slowlog: Vec<RedisSlowlogEntry>,
+    pub clients: Vec<RedisClientInfo>,
+    /// Empty unless the server runs in cluster mode
+    pub cluster_nodes: Vec<RedisClusterNode>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn server_stats_read_memory_replication_and_keyspace() {
+        let info = parse_info(
+            "# Server\r\nredis_version:7.2.4\r\nredis_mode:standalone\r\n\
+             # Memory\r\nused_memory:1048576\r\nused_memory_human:1.00M\r\nmaxmemory:4194304\r\n\
+             # Stats\r\ninstantaneous_ops_per_sec:42\r\nkeyspace_hits:3\r\nkeyspace_misses:1\r\n\
+             # Replication\r\nrole:master\r\nconnected_slaves:2\r\n\
+             slave1:ip=10.0.0.3,port=6380,state=online,offset=10,lag=1\r\n\
+             slave0:ip=10.0.0.2,port=6380,state=online,offset=10,lag=0\r\n\
+             # Keyspace\r\ndb2:keys=5,expires=1,avg_ttl=100\r\ndb0:keys=10,expires=0,avg_ttl=0\r\n",
+        );
+        let stats = RedisServerStats::from_info(&info);
+
+        assert_eq!(stats.version, "7.2.4");
+        assert_eq!(stats.ops_per_sec, 42);
+        assert_eq!(stats.hit_rate(), Some(75.0));
+        assert_eq!(stats.memory_usage(), Some(25.0));
+        assert_eq!(stats.role, "master");
+        assert_eq!(
+            stats.replicas,
+            vec![
+                "10.0.0.2:6380 (online, lag 0)",
+                "10.0.0.3:6380 (online, lag 1)"
+            ]
+        );
+        assert_eq!(
+            stats.keyspace.iter().map(|db| db.db).collect::<Vec<_>>(),
+            vec![0, 2]
+        );
+        assert_eq!(stats.keyspace[1].avg_ttl_ms, 100);
+        assert!(!stats.cluster_enabled);
+    }
+
+    #[test]
+    fn client_list_parses_fields() {
+        let clients = parse_client_list(
+            "id=3 addr=127.0.0.1:52555 laddr=127.0.0.1:6379 fd=8 name=dfc age=12 idle=0 \
+             flags=N db=0 cmd=client|list\n",
+        );
+        assert_eq!(clients.len(), 1);
+        assert_eq!(clients[0].name, "dfc");
+        assert_eq!(clients[0].age_secs, 12);
+        assert_eq!(clients[0].cmd, "client|list");
+    }
+
+    #[test]
+    fn cluster_nodes_parse_slots_and_coverage() {
+        let nodes = parse_cluster_nodes(
+            "a1 10.0.0.1:7000@17000 myself,master - 0 0 1 connected 0-5460\n\
+             b2 10.0.0.2:7000@17000,host-b master - 0 0 2 connected 5461-10922 [5461->-c3]\n\
+             c3 10.0.0.3:7000@17000 master,fail - 0 0 3 disconnected 10923-16383\n\
+             d4 10.0.0.4:7000@17000 slave a1 0 0 1 connected\n",
+        );
+        assert_eq!(nodes.len(), 4);
+        assert_eq!(nodes[1].addr, "10.0.0.2:7000");
+        assert_eq!(nodes[1].slots, vec![(5461, 10922)]);
+        assert_eq!(nodes[0].slots_label(), "0-5460");
+        assert_eq!(nodes[3].master_id.as_deref(), Some("a1"));
+        assert!(nodes[2].is_failing());
+        // The failing master's slots are not served
+        assert_eq!(cluster_slot_coverage(&nodes), 10923);
+    }
+
+    #[test]
+    fn cluster_stats_add_up_across_primaries() {
+        let node = |text: &str| RedisServerStats::from_info(&parse_info(text));
+        let stats = RedisServerStats::combine(&[
+            node(
+                "redis_version:7.2.4\r\nredis_mode:cluster\r\nuptime_in_seconds:900\r\n\
+                 used_memory:1048576\r\nmaxmemory:4194304\r\ninstantaneous_ops_per_sec:10\r\n\
+                 keyspace_hits:3\r\nkeyspace_misses:1\r\nrole:master\r\n\
+                 db0:keys=10,expires=2,avg_ttl=100\r\n",
+            ),
+            node(
+                "redis_version:7.2.4\r\nredis_mode:cluster\r\nuptime_in_seconds:300\r\n\
+                 used_memory:1048576\r\nmaxmemory:4194304\r\ninstantaneous_ops_per_sec:5\r\n\
+                 keyspace_hits:1\r\nkeyspace_misses:3\r\nrole:master\r\n\
+                 db0:keys=20,expires=2,avg_ttl=300\r\n",
+            ),
+        ]);
+
+        assert_eq!(stats.mode, "cluster");
+        assert_eq!(stats.uptime_secs, 300);
+        assert_eq!(stats.used_memory_human, "2.0 MB");
+        assert_eq!(stats.memory_usage(), Some(25.0));
+        assert_eq!(stats.ops_per_sec, 15);
+        assert_eq!(stats.hit_rate(), Some(50.0));
+        assert!(stats.cluster_enabled);
+        assert_eq!(
+            stats.keyspace,
+            vec![RedisKeyspaceDb {
+                db: 0,
+                keys: 30,
+                expires: 4,
+                avg_ttl_ms: 200,
+            }]
+        );
+    }
+}
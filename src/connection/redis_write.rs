@@ -0,0 +1,387 @@
+//! Redis write operations
+//!
+//! Describes every mutation the key browser can issue, so the UI can preview
+//! the change, the repository can execute it and the audit log can record it.
+
+use super::redis_config::RedisKeyValue;
+
+/// Placeholder shown for a missing value in previews
+const NIL: &str = "(nil)";
+
+/// A single guarded write against one key
+#[derive(Debug, Clone, PartialEq)]
+pub enum RedisWriteOp {
+    /// SET key value
+    Set { key: String, value: String },
+    /// HSET key field value
+    HSet {
+        key: String,
+        field: String,
+        value: String,
+    },
+    /// HDEL key field
+    HDel { key: String, field: String },
+    /// LPUSH key value
+    LPush { key: String, value: String },
+    /// LSET key index value
+    LSet {
+        key: String,
+        index: i64,
+        value: String,
+    },
+    /// LSET key index tombstone, then LREM key 1 tombstone.
+    ///
+    /// Removes exactly the element at `index` even when the list holds
+    /// duplicates. Nothing is written unless that element still equals `value`.
+    LDel {
+        key: String,
+        index: i64,
+        value: String,
+        tombstone: String,
+    },
+    /// SADD key member
+    SAdd { key: String, member: String },
+    /// SREM key member
+    SRem { key: String, member: String },
+    /// ZADD key score member
+    ZAdd {
+        key: String,
+        member: String,
+        score: f64,
+    },
+    /// ZREM key member
+    ZRem { key: String, member: String },
+    /// EXPIRE key seconds
+    Expire { key: String, seconds: i64 },
+    /// PERSIST key
+    Persist { key: String },
+    /// RENAMENX key new_key (never overwrites an existing key)
+    Rename { key: String, new_key: String },
+    /// DEL key
+    Del { key: String },
+}
+
+/// Before/after text of a write, shown in the confirmation dialog
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RedisWritePreview {
+    pub before: String,
+    pub after: String,
+}
+
+/// Checks the element at an index, then replaces it with a tombstone and
+/// removes that tombstone, all in one atomic step. Replies -1 when the
+/// element no longer matches.
+const LIST_DELETE_SCRIPT: &str = "\
+if redis.call('LINDEX', KEYS[1], ARGV[1]) ~= ARGV[2] then return -1 end
+redis.call('LSET', KEYS[1], ARGV[1], ARGV[3])
+return redis.call('LREM', KEYS[1], 1, ARGV[3])";
+
+impl RedisWriteOp {
+    /// Delete the list element at `index`, which the caller saw holding `value`
+    pub fn list_delete(key: String, index: i64, value: String) -> Self {
+        Self::LDel {
+            key,
+            index,
+            value,
+            tombstone: format!("__dfc_deleted_{}__", uuid::Uuid::new_v4().simple()),
+        }
+    }
+
+    /// Key the operation targets
+    pub fn key(&self) -> &str {
+        match self {
+            Self::Set { key, .. }
+            | Self::HSet { key, .. }
+            | Self::HDel { key, .. }
+            | Self::LPush { key, .. }
+            | Self::LSet { key, .. }
+            | Self::LDel { key, .. }
+            | Self::SAdd { key, .. }
+            | Self::SRem { key, .. }
+            | Self::ZAdd { key, .. }
+            | Self::ZRem { key, .. }
+            | Self::Expire { key, .. }
+            | Self::Persist { key }
+            | Self::Rename { key, .. }
+            | Self::Del { key } => key,
+        }
+    }
+
+    /// Redis command name
+    pub fn command_name(&self) -> &'static str {
+        match self {
+            Self::Set { .. } => "SET",
+            Self::HSet { .. } => "HSET",
+            Self::HDel { .. } => "HDEL",
+            Self::LPush { .. } => "LPUSH",
+            Self::LSet { .. } => "LSET",
+            Self::LDel { .. } => "EVAL",
+            Self::SAdd { .. } => "SADD",
+            Self::SRem { .. } => "SREM",
+            Self::ZAdd { .. } => "ZADD",
+            Self::ZRem { .. } => "ZREM",
+            Self::Expire { .. } => "EXPIRE",
+            Self::Persist { .. } => "PERSIST",
+            Self::Rename { .. } => "RENAMENX",
+            Self::Del { .. } => "DEL",
+        }
+    }
+
+    /// Command arguments (including the key)
+    pub fn args(&self) -> Vec<String> {
+        let key = self.key().to_string();
+        match self {
+            Self::Set { value, .. } | Self::LPush { value, .. } => vec![key, value.clone()],
+            Self::HSet { field, value, .. } => vec![key, field.clone(), value.clone()],
+            Self::HDel { field, .. } => vec![key, field.clone()],
+            Self::LSet { index, value, .. } => vec![key, index.to_string(), value.clone()],
+            Self::LDel {
+                index,
+                value,
+                tombstone,
+                ..
+            } => vec![
+                LIST_DELETE_SCRIPT.to_string(),
+                "1".to_string(),
+                key,
+                index.to_string(),
+                value.clone(),
+                tombstone.clone(),
+            ],
+            Self::SAdd { member, .. } | Self::SRem { member, .. } | Self::ZRem { member, .. } => {
+                vec![key, member.clone()]
+            }
+            Self::ZAdd { member, score, .. } => vec![key, score.to_string(), member.clone()],
+            Self::Expire { seconds, .. } => vec![key, seconds.to_string()],
+            Self::Rename { new_key, .. } => vec![key, new_key.clone()],
+            Self::Persist { .. } | Self::Del { .. } => vec![key],
+        }
+    }
+
+    /// Printable command line, quoting arguments that contain spaces or quotes
+    pub fn command_line(&self) -> String {
+        if let Self::LDel {
+            key,
+            index,
+            tombstone,
+            ..
+        } = self
+        {
+            // Show the commands the script runs rather than the script itself
+            let (key, tombstone) = (quote_arg(key), quote_arg(tombstone));
+            return format!("LSET {key} {index} {tombstone}; LREM {key} 1 {tombstone}");
+        }
+        let mut parts = vec![self.command_name().to_string()];
+        parts.extend(self.args().iter().map(|arg| quote_arg(arg)));
+        parts.join(" ")
+    }
+
+    /// Build the before/after preview from the loaded value and TTL.
+    ///
+    /// Only the loaded page is known locally, so elements outside it show as `(nil)`.
+    pub fn preview(&self, current: &RedisKeyValue, ttl: i64) -> RedisWritePreview {
+        let (before, after) = match self {
+            Self::Set { value, .. } => {
+                let before = match current {
+                    RedisKeyValue::String(old) => old.clone(),
+                    RedisKeyValue::Bytes(old) => String::from_utf8_lossy(old).into_owned(),
+                    _ => NIL.to_string(),
+                };
+                (before, value.clone())
+            }
+            Self::HSet { field, value, .. } => (
+                hash_field(current, field)
+                    .map(|old| format!("{field}: {old}"))
+                    .unwrap_or_else(|| NIL.to_string()),
+                format!("{field}: {value}"),
+            ),
+            Self::HDel { field, .. } => (
+                hash_field(current, field)
+                    .map(|old| format!("{field}: {old}"))
+                    .unwrap_or_else(|| NIL.to_string()),
+                NIL.to_string(),
+            ),
+            Self::LPush { value, .. } => (NIL.to_string(), format!("[0] {value}")),
+            Self::LSet { index, value, .. } => {
+                let old = match current {
+                    RedisKeyValue::List(items) => usize::try_from(*index)
+                        .ok()
+                        .and_then(|ix| items.get(ix))
+                        .cloned(),
+                    _ => None,
+                };
+                (
+                    old.map(|old| format!("[{index}] {old}"))
+                        .unwrap_or_else(|| NIL.to_string()),
+                    format!("[{index}] {value}"),
+                )
+            }
+            Self::LDel { index, value, .. } => (format!("[{index}] {value}"), NIL.to_string()),
+            Self::SAdd { member, .. } => {
+                let exists =
+                    matches!(current, RedisKeyValue::Set(members) if members.contains(member));
+                (
+                    if exists {
+                        member.clone()
+                    } else {
+                        NIL.to_string()
+                    },
+                    member.clone(),
+                )
+            }
+            Self::SRem { member, .. } => (member.clone(), NIL.to_string()),
+            Self::ZAdd { member, score, .. } => (
+                zset_score(current, member)
+                    .map(|old| format!("{member}: {old}"))
+                    .unwrap_or_else(|| NIL.to_string()),
+                format!("{member}: {score}"),
+            ),
+            Self::ZRem { member, .. } => (
+                zset_score(current, member)
+                    .map(|old| format!("{member}: {old}"))
+                    .unwrap_or_else(|| member.clone()),
+                NIL.to_string(),
+            ),
+            Self::Expire { seconds, .. } => (format_ttl(ttl), format_ttl(*seconds)),
+            Self::Persist { .. } => (format_ttl(ttl), format_ttl(-1)),
+            Self::Rename { key, new_key } => (key.clone(), new_key.clone()),
+            Self::Del { key } => (format!("{key} ({})", summarize(current)), NIL.to_string()),
+        };
+        RedisWritePreview { before, after }
+    }
+}
+
+fn hash_field<'a>(current: &'a RedisKeyValue, field: &str) -> Option<&'a str> {
+    match current {
+        RedisKeyValue::Hash(pairs) => pairs
+            .iter()
+            .find(|(name, _)| name == field)
+            .map(|(_, value)| value.as_str()),
+        _ => None,
+    }
+}
+
+fn zset_score(current: &RedisKeyValue, member: &str) -> Option<f64> {
+    match current {
+        RedisKeyValue::ZSet(members) => members
+            .iter()
+            .find(|(name, _)| name == member)
+            .map(|(_, score)| *score),
+        _ => None,
+    }
+}
+
+fn format_ttl(ttl: i64) -> String {
+    if ttl < 0 {
+        "TTL: none".to_string()
+    } else {
+        format!("TTL: {ttl}s")
+    }
+}
+
+fn summarize(current: &RedisKeyValue) -> String {
+    match current {
+        RedisKeyValue::String(value) => format!("{} bytes", value.len()),
+        RedisKeyValue::Bytes(value) => format!("{} bytes", value.len()),
+        RedisKeyValue::Hash(_)
+        | RedisKeyValue::List(_)
+        | RedisKeyValue::Set(_)
+        | RedisKeyValue::ZSet(_)
+        | RedisKeyValue::Stream(_) => format!("{} loaded elements", current.loaded_len()),
+        RedisKeyValue::Loading | RedisKeyValue::Error(_) | RedisKeyValue::Empty => NIL.to_string(),
+    }
+}
+
+fn quote_arg(arg: &str) -> String {
+    if !arg.is_empty()
+        && !arg
+            .chars()
+            .any(|c| c.is_whitespace() || c == '"' || c == '\'')
+    {
+        return arg.to_string();
+    }
+    format!("\"{}\"", arg.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_line_quotes_arguments_with_spaces() {
+        let op = RedisWriteOp::HSet {
+            key: "cfg:1".to_string(),
+            field: "name".to_string(),
+            value: "wind farm \"A\"".to_string(),
+        };
+        assert_eq!(op.command_line(), r#"HSET cfg:1 name "wind farm \"A\"""#);
+
+        let op = RedisWriteOp::ZAdd {
+            key: "z".to_string(),
+            member: "m".to_string(),
+            score: 1.5,
+        };
+        assert_eq!(op.args(), vec!["z", "1.5", "m"]);
+    }
+
+    #[test]
+    fn preview_uses_loaded_hash_field() {
+        let current = RedisKeyValue::Hash(vec![("host".to_string(), "10.0.0.1".to_string())]);
+        let op = RedisWriteOp::HSet {
+            key: "k".to_string(),
+            field: "host".to_string(),
+            value: "10.0.0.2".to_string(),
+        };
+        assert_eq!(
+            op.preview(&current, -1),
+            RedisWritePreview {
+                before: "host: 10.0.0.1".to_string(),
+                after: "host: 10.0.0.2".to_string(),
+            }
+        );
+
+        let op = RedisWriteOp::HSet {
+            key: "k".to_string(),
+            field: "port".to_string(),
+            value: "6379".to_string(),
+        };
+        assert_eq!(op.preview(&current, -1).before, NIL);
+    }
+
+    #[test]
+    fn list_delete_targets_the_index_through_a_tombstone() {
+        let op = RedisWriteOp::list_delete("queue".to_string(), 2, "job".to_string());
+        let RedisWriteOp::LDel { tombstone, .. } = &op else {
+            panic!("expected LDel");
+        };
+        assert_eq!(
+            op.command_line(),
+            format!("LSET queue 2 {tombstone}; LREM queue 1 {tombstone}")
+        );
+        assert_eq!(op.args()[2..], ["queue", "2", "job", tombstone.as_str()]);
+
+        let other = RedisWriteOp::list_delete("queue".to_string(), 2, "job".to_string());
+        assert_ne!(op, other, "every delete gets its own tombstone");
+
+        let current = RedisKeyValue::List(vec!["job".to_string(); 3]);
+        let preview = op.preview(&current, -1);
+        assert_eq!(preview.before, "[2] job");
+        assert_eq!(preview.after, NIL);
+    }
+
+    #[test]
+    fn preview_formats_ttl_changes() {
+        let op = RedisWriteOp::Expire {
+            key: "k".to_string(),
+            seconds: 60,
+        };
+        let preview = op.preview(&RedisKeyValue::Empty, -1);
+        assert_eq!(preview.before, "TTL: none");
+        assert_eq!(preview.after, "TTL: 60s");
+
+        let op = RedisWriteOp::Persist {
+            key: "k".to_string(),
+        };
+        assert_eq!(op.preview(&RedisKeyValue::Empty, 30).before, "TTL: 30s");
+    }
+}
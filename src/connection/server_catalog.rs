@@ -0,0 +1,321 @@
+//! Server Catalog
+//!
+//! Organisation of the server list on the home page:
+//! - Environment label (dev / test / prod), free-form tags and a group name
+//! - Filtering by keyword, `#tag`, `env:` and `group:` terms
+//! - Sorting, and sections per group
+
+use super::DfcServerConfig;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+
+/// Deployment environment of a server
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ServerEnvironment {
+    Dev,
+    Test,
+    Prod,
+}
+
+impl ServerEnvironment {
+    pub const ALL: [Self; 3] = [Self::Dev, Self::Test, Self::Prod];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Dev => "dev",
+            Self::Test => "test",
+            Self::Prod => "prod",
+        }
+    }
+
+    /// Parse a label such as `prod` or `production` (case-insensitive)
+    pub fn parse(text: &str) -> Option<Self> {
+        match text.trim().to_lowercase().as_str() {
+            "dev" | "development" => Some(Self::Dev),
+            "test" | "testing" | "staging" => Some(Self::Test),
+            "prod" | "production" => Some(Self::Prod),
+            _ => None,
+        }
+    }
+
+    /// Sort rank, production first
+    fn rank(self) -> u8 {
+        match self {
+            Self::Prod => 0,
+            Self::Test => 1,
+            Self::Dev => 2,
+        }
+    }
+}
+
+/// Order of the servers on the home page
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ServerSortOrder {
+    /// Order of `servers.toml`
+    #[default]
+    Manual,
+    Name,
+    Host,
+    /// Production first, then test, dev and unlabelled
+    Environment,
+    /// Most recently updated first
+    Updated,
+}
+
+impl ServerSortOrder {
+    pub const ALL: [Self; 5] = [
+        Self::Manual,
+        Self::Name,
+        Self::Host,
+        Self::Environment,
+        Self::Updated,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Manual => "manual",
+            Self::Name => "name",
+            Self::Host => "host",
+            Self::Environment => "environment",
+            Self::Updated => "updated",
+        }
+    }
+}
+
+/// Tags separated by commas or whitespace, without a leading `#`, first
+/// spelling kept for case-insensitive duplicates
+pub fn parse_tags(text: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in text
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .map(|tag| tag.trim_start_matches('#'))
+        .filter(|tag| !tag.is_empty())
+    {
+        if !tags.iter().any(|known| known.eq_ignore_ascii_case(tag)) {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
+}
+
+impl DfcServerConfig {
+    /// Group name, `None` when blank
+    pub fn group_name(&self) -> Option<&str> {
+        self.group
+            .as_deref()
+            .map(str::trim)
+            .filter(|group| !group.is_empty())
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags
+            .iter()
+            .any(|known| known.eq_ignore_ascii_case(tag))
+    }
+
+    /// Whether the server matches every whitespace separated term of
+    /// `filter`: `#tag` or `tag:name` needs the tag, `env:prod` the
+    /// environment, `group:text` a group containing the text, and any other
+    /// term must appear in the name, host, cfgid, group or a tag
+    pub fn matches_filter(&self, filter: &str) -> bool {
+        let filter = filter.to_lowercase();
+        filter.split_whitespace().all(|term| {
+            if let Some(tag) = term.strip_prefix('#').or_else(|| term.strip_prefix("tag:")) {
+                return tag.is_empty() || self.has_tag(tag);
+            }
+            if let Some(env) = term.strip_prefix("env:") {
+                return env.is_empty()
+                    || self.environment.is_some_and(|environment| {
+                        Some(environment) == ServerEnvironment::parse(env)
+                    });
+            }
+            let group = self.group_name().unwrap_or_default().to_lowercase();
+            if let Some(text) = term.strip_prefix("group:") {
+                return group.contains(text);
+            }
+            self.name.to_lowercase().contains(term)
+                || self.host.to_lowercase().contains(term)
+                || self
+                    .cfgid
+                    .as_ref()
+                    .is_some_and(|cfgid| cfgid.to_lowercase().contains(term))
+                || group.contains(term)
+                || self
+                    .tags
+                    .iter()
+                    .any(|tag| tag.to_lowercase().contains(term))
+        })
+    }
+}
+
+/// Sort `servers` in place; the sort is stable, so ties keep the file order
+pub fn sort_servers(servers: &mut [DfcServerConfig], order: ServerSortOrder) {
+    match order {
+        ServerSortOrder::Manual => {}
+        ServerSortOrder::Name => servers.sort_by_cached_key(|server| server.name.to_lowercase()),
+        ServerSortOrder::Host => {
+            servers.sort_by(|a, b| a.host.trim().cmp(b.host.trim()).then(a.port.cmp(&b.port)))
+        }
+        ServerSortOrder::Environment => servers
+            .sort_by_key(|server| server.environment.map_or(u8::MAX, ServerEnvironment::rank)),
+        ServerSortOrder::Updated => {
+            servers.sort_by(|a, b| Reverse(&a.updated_at).cmp(&Reverse(&b.updated_at)))
+        }
+    }
+}
+
+/// Key that identifies a group; names differing only in case are one group
+pub fn group_key(group: &str) -> String {
+    group.to_lowercase()
+}
+
+/// Split `servers` into sections per group, named groups in case-insensitive
+/// order and ungrouped servers last; the order inside a group is kept.
+///
+/// A group is named after its first server.
+pub fn group_servers(servers: Vec<DfcServerConfig>) -> Vec<(Option<String>, Vec<DfcServerConfig>)> {
+    let mut groups: Vec<(Option<String>, Option<String>, Vec<DfcServerConfig>)> = Vec::new();
+    for server in servers {
+        let group = server.group_name().map(str::to_string);
+        let key = group.as_deref().map(group_key);
+        match groups.iter_mut().find(|(known, _, _)| *known == key) {
+            Some((_, _, members)) => members.push(server),
+            None => groups.push((key, group, vec![server])),
+        }
+    }
+    groups.sort_by(|(a, _, _), (b, _, _)| (a.is_none(), a).cmp(&(b.is_none(), b)));
+    groups
+        .into_iter()
+        .map(|(_, name, members)| (name, members))
+        .collect()
+}
+
+/// Tags used by `servers`, sorted case-insensitively
+pub fn collect_tags(servers: &[DfcServerConfig]) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in servers.iter().flat_map(|server| server.tags.iter()) {
+        if !tags.iter().any(|known| known.eq_ignore_ascii_case(tag)) {
+            tags.push(tag.clone());
+        }
+    }
+    tags.sort_by_cached_key(|tag| tag.to_lowercase());
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(name: &str, host: &str) -> DfcServerConfig {
+        DfcServerConfig {
+            id: name.to_string(),
+            name: name.to_string(),
+            host: host.to_string(),
+            port: 6379,
+            ..Default::default()
+        }
+    }
+
+    fn catalog() -> Vec<DfcServerConfig> {
+        let mut alpha = server("Alpha", "10.0.0.2");
+        alpha.group = Some("North".to_string());
+        alpha.tags = vec!["wind".to_string(), "Beta-Site".to_string()];
+        alpha.environment = Some(ServerEnvironment::Prod);
+        alpha.updated_at = Some("2026-10-01T00:00:00+08:00".to_string());
+        let mut bravo = server("bravo", "10.0.0.1");
+        bravo.group = Some(" ".to_string());
+        bravo.environment = Some(ServerEnvironment::Dev);
+        bravo.updated_at = Some("2026-10-02T00:00:00+08:00".to_string());
+        let mut charlie = server("Charlie", "10.0.0.3");
+        charlie.group = Some("east".to_string());
+        charlie.tags = vec!["solar".to_string()];
+        vec![alpha, bravo, charlie]
+    }
+
+    fn names(servers: &[DfcServerConfig]) -> Vec<&str> {
+        servers.iter().map(|server| server.name.as_str()).collect()
+    }
+
+    #[test]
+    fn parse_tags_trims_and_deduplicates() {
+        assert_eq!(
+            parse_tags(" #wind, solar  Wind,,north "),
+            vec!["wind", "solar", "north"]
+        );
+        assert!(parse_tags(" , ").is_empty());
+    }
+
+    #[test]
+    fn matches_filter_terms() {
+        let servers = catalog();
+        let matching = |filter: &str| -> Vec<&str> {
+            servers
+                .iter()
+                .filter(|server| server.matches_filter(filter))
+                .map(|server| server.name.as_str())
+                .collect()
+        };
+        assert_eq!(matching(""), vec!["Alpha", "bravo", "Charlie"]);
+        assert_eq!(matching("#WIND"), vec!["Alpha"]);
+        assert_eq!(matching("tag:solar"), vec!["Charlie"]);
+        assert_eq!(matching("env:production"), vec!["Alpha"]);
+        assert_eq!(matching("group:ea"), vec!["Charlie"]);
+        assert_eq!(matching("10.0.0 beta"), vec!["Alpha"]);
+        assert_eq!(matching("north"), vec!["Alpha"]);
+        assert!(matching("#wind env:dev").is_empty());
+    }
+
+    #[test]
+    fn sort_orders() {
+        let mut servers = catalog();
+        sort_servers(&mut servers, ServerSortOrder::Name);
+        assert_eq!(names(&servers), vec!["Alpha", "bravo", "Charlie"]);
+        sort_servers(&mut servers, ServerSortOrder::Host);
+        assert_eq!(names(&servers), vec!["bravo", "Alpha", "Charlie"]);
+        sort_servers(&mut servers, ServerSortOrder::Environment);
+        assert_eq!(names(&servers), vec!["Alpha", "bravo", "Charlie"]);
+        sort_servers(&mut servers, ServerSortOrder::Updated);
+        assert_eq!(names(&servers), vec!["bravo", "Alpha", "Charlie"]);
+    }
+
+    #[test]
+    fn group_servers_puts_ungrouped_last() {
+        let groups = group_servers(catalog());
+        let sections: Vec<_> = groups
+            .iter()
+            .map(|(name, members)| (name.as_deref(), names(members)))
+            .collect();
+        assert_eq!(
+            sections,
+            vec![
+                (Some("east"), vec!["Charlie"]),
+                (Some("North"), vec!["Alpha"]),
+                (None, vec!["bravo"]),
+            ]
+        );
+        assert_eq!(collect_tags(&catalog()), vec!["Beta-Site", "solar", "wind"]);
+    }
+
+    #[test]
+    fn group_servers_ignores_the_case_of_group_names() {
+        let mut servers = catalog();
+        let mut delta = server("Delta", "10.0.0.4");
+        delta.group = Some(" north ".to_string());
+        servers.push(delta);
+        let groups = group_servers(servers);
+        let sections: Vec<_> = groups
+            .iter()
+            .map(|(name, members)| (name.as_deref(), names(members)))
+            .collect();
+        assert_eq!(
+            sections,
+            vec![
+                (Some("east"), vec!["Charlie"]),
+                (Some("North"), vec!["Alpha", "Delta"]),
+                (None, vec!["bravo"]),
+            ]
+        );
+    }
+}
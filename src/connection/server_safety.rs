@@ -0,0 +1,187 @@
+//! Server Safety Levels
+//!
+//! Guards every write against a server (key browser edits, console writes,
+//! key copies, service requests and publishing):
+//! - Read-only servers reject writes
+//! - Confirm-every-write servers need the server name typed for each write
+//! - Unrestricted servers accept writes after the usual confirmations
+//!
+//! Servers without a stored level, including those saved before levels
+//! existed, confirm every write.
+//!
+//! Write APIs take a [`WritePermit`], which only [`DfcServerConfig::authorize_write`]
+//! hands out, so a new write path cannot skip the check.
+
+use super::DfcServerConfig;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+/// How writes against a server are guarded
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SafetyLevel {
+    /// Reject every write
+    ReadOnly,
+    /// Require the server name to be typed for every write
+    #[default]
+    ConfirmWrites,
+    Unrestricted,
+}
+
+impl SafetyLevel {
+    pub const ALL: [Self; 3] = [Self::ReadOnly, Self::ConfirmWrites, Self::Unrestricted];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::ReadOnly => "read_only",
+            Self::ConfirmWrites => "confirm_writes",
+            Self::Unrestricted => "unrestricted",
+        }
+    }
+
+    /// Whether the level restricts writes at all
+    pub fn is_protected(self) -> bool {
+        self != Self::Unrestricted
+    }
+}
+
+/// Proof that a write against one server passed its safety level
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WritePermit {
+    server_id: String,
+}
+
+impl WritePermit {
+    /// Id of the server the permit was issued for
+    pub fn server_id(&self) -> &str {
+        &self.server_id
+    }
+
+    /// Fail unless the permit was issued for the server with `server_id`
+    pub fn check_server_id(&self, server_id: &str) -> Result<()> {
+        if self.server_id == server_id {
+            Ok(())
+        } else {
+            Err(Error::Invalid {
+                message: format!("The write was not authorized for server {server_id}"),
+            })
+        }
+    }
+
+    /// Fail unless the permit was issued for `server`
+    pub fn check(&self, server: &DfcServerConfig) -> Result<()> {
+        if self.server_id == server.id {
+            Ok(())
+        } else {
+            Err(Error::Invalid {
+                message: format!("The write was not authorized for {}", server.display_name()),
+            })
+        }
+    }
+}
+
+impl DfcServerConfig {
+    /// Name to type when confirming a write: the server name, or
+    /// `host:port` for unnamed servers
+    pub fn confirmation_name(&self) -> String {
+        let name = self.name.trim();
+        if name.is_empty() {
+            format!("{}:{}", self.host, self.port)
+        } else {
+            name.to_string()
+        }
+    }
+
+    /// Whether writes need the server name typed first
+    pub fn needs_write_confirmation(&self) -> bool {
+        self.safety == SafetyLevel::ConfirmWrites
+    }
+
+    /// Authorize a write against this server; `confirmation` is the text
+    /// typed by the user, required to match [`Self::confirmation_name`] on
+    /// confirm-every-write servers
+    pub fn authorize_write(&self, confirmation: Option<&str>) -> Result<WritePermit> {
+        let expected = self.confirmation_name();
+        match self.safety {
+            SafetyLevel::ReadOnly => Err(Error::Invalid {
+                message: format!("{expected} is read-only"),
+            }),
+            SafetyLevel::ConfirmWrites
+                if confirmation.map(str::trim) != Some(expected.as_str()) =>
+            {
+                Err(Error::Invalid {
+                    message: format!("Type {expected} to confirm the write"),
+                })
+            }
+            _ => Ok(WritePermit {
+                server_id: self.id.clone(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(safety: SafetyLevel) -> DfcServerConfig {
+        DfcServerConfig {
+            id: "id-1".to_string(),
+            name: " Site A ".to_string(),
+            host: "10.0.0.1".to_string(),
+            port: 6379,
+            safety,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn authorize_write_follows_safety_level() {
+        let unrestricted = server(SafetyLevel::Unrestricted);
+        let permit = unrestricted.authorize_write(None).expect("unrestricted");
+        assert_eq!(permit.server_id(), "id-1");
+        assert!(permit.check(&unrestricted).is_ok());
+
+        assert!(
+            server(SafetyLevel::ReadOnly)
+                .authorize_write(Some("Site A"))
+                .is_err()
+        );
+
+        let guarded = server(SafetyLevel::ConfirmWrites);
+        assert!(guarded.needs_write_confirmation());
+        assert!(guarded.authorize_write(None).is_err());
+        assert!(guarded.authorize_write(Some("site a")).is_err());
+        assert!(guarded.authorize_write(Some(" Site A ")).is_ok());
+    }
+
+    #[test]
+    fn servers_without_a_stored_level_confirm_writes() {
+        let stored: DfcServerConfig = toml::from_str(
+            r#"
+            id = "id-1"
+            name = "Site A"
+            host = "10.0.0.1"
+            port = 6379
+            "#,
+        )
+        .expect("parse server");
+        assert_eq!(stored.safety, SafetyLevel::ConfirmWrites);
+        assert!(stored.authorize_write(None).is_err());
+    }
+
+    #[test]
+    fn permit_is_bound_to_its_server() {
+        let permit = server(SafetyLevel::Unrestricted)
+            .authorize_write(None)
+            .expect("unrestricted");
+        let other = DfcServerConfig {
+            id: "id-2".to_string(),
+            ..Default::default()
+        };
+        assert!(permit.check(&other).is_err());
+        assert!(permit.check_server_id("id-1").is_ok());
+        assert!(permit.check_server_id("id-2").is_err());
+        assert_eq!(other.confirmation_name(), ":0");
+    }
+}
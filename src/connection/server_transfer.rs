@@ -0,0 +1,405 @@
+//! Server Import and Export
+//!
+//! Portable file of selected server configurations:
+//! - Secrets left out, in plain text, or encrypted with an export passphrase
+//! - Optional preset credentials
+//! - Import planning: duplicates are found with `same_config_for_uniqueness`
+//!   and resolved per server (skip, replace, or keep both)
+
+use super::{DfcServerConfig, PresetCredential, secret_slots_mut};
+use crate::error::{Error, Result};
+use crate::helpers::{PassphraseKey, VAULT_KEY_LEN, decrypt_with, encrypt_with};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Version written to new export files
+pub const SERVER_EXPORT_VERSION: u32 = 1;
+
+/// How secrets are written to an export file
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportSecrets {
+    /// Passwords and tokens are left out
+    #[default]
+    Omit,
+    /// Passwords and tokens in plain text
+    Plain,
+    /// Passwords and tokens encrypted with a key derived from an export passphrase
+    Encrypted,
+}
+
+/// Contents of an export file
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ServerExport {
+    pub version: u32,
+    pub exported_at: String,
+    pub secrets: ExportSecrets,
+    /// Key settings of an `Encrypted` export
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encryption: Option<PassphraseKey>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub preset_credentials: Vec<PresetCredential>,
+    #[serde(default)]
+    pub servers: Vec<DfcServerConfig>,
+}
+
+impl ServerExport {
+    /// Build an export of `servers` (and `preset_credentials`, dropped when
+    /// secrets are omitted); `passphrase` is required for `Encrypted`
+    pub fn new(
+        servers: &[DfcServerConfig],
+        preset_credentials: &[PresetCredential],
+        secrets: ExportSecrets,
+        passphrase: Option<&str>,
+        exported_at: String,
+    ) -> Result<Self> {
+        let mut export = Self {
+            version: SERVER_EXPORT_VERSION,
+            exported_at,
+            secrets,
+            encryption: None,
+            preset_credentials: preset_credentials.to_vec(),
+            servers: servers.to_vec(),
+        };
+        match secrets {
+            ExportSecrets::Omit => {
+                export.preset_credentials.clear();
+                for server in export.servers.iter_mut() {
+                    for slot in secret_slots_mut(server) {
+                        *slot = None;
+                    }
+                }
+            }
+            ExportSecrets::Plain => {}
+            ExportSecrets::Encrypted => {
+                let passphrase = passphrase.ok_or_else(|| Error::Invalid {
+                    message: "An export passphrase is required to encrypt secrets".to_string(),
+                })?;
+                let (settings, key) = PassphraseKey::generate(passphrase)?;
+                export.map_secrets(|secret| encrypt_with(&key, secret))?;
+                export.encryption = Some(settings);
+            }
+        }
+        Ok(export)
+    }
+
+    /// Parse an export file; encrypted secrets stay encrypted until `decrypt`
+    pub fn parse(content: &str) -> Result<Self> {
+        let export: Self = toml::from_str(content)?;
+        if export.version > SERVER_EXPORT_VERSION {
+            return Err(Error::Invalid {
+                message: format!(
+                    "Export file version {} is newer than supported version {}",
+                    export.version, SERVER_EXPORT_VERSION
+                ),
+            });
+        }
+        if export.secrets == ExportSecrets::Encrypted && export.encryption.is_none() {
+            return Err(Error::Invalid {
+                message: "Encrypted export file has no key settings".to_string(),
+            });
+        }
+        Ok(export)
+    }
+
+    pub fn to_toml(&self) -> Result<String> {
+        Ok(toml::to_string_pretty(self)?)
+    }
+
+    /// Whether `decrypt` must be called before importing
+    pub fn needs_passphrase(&self) -> bool {
+        self.secrets == ExportSecrets::Encrypted
+    }
+
+    /// Decrypt the secrets of an `Encrypted` export; fails on a wrong passphrase
+    pub fn decrypt(&mut self, passphrase: &str) -> Result<()> {
+        let Some(settings) = self.encryption.as_ref() else {
+            return Ok(());
+        };
+        let key: [u8; VAULT_KEY_LEN] = settings.unlock(passphrase)?;
+        self.map_secrets(|secret| decrypt_with(&key, secret))?;
+        self.secrets = ExportSecrets::Plain;
+        self.encryption = None;
+        Ok(())
+    }
+
+    fn map_secrets(&mut self, f: impl Fn(&str) -> Result<String>) -> Result<()> {
+        for server in self.servers.iter_mut() {
+            for secret in secret_slots_mut(server).into_iter().flatten() {
+                if !secret.is_empty() {
+                    *secret = f(secret)?;
+                }
+            }
+        }
+        for cred in self.preset_credentials.iter_mut() {
+            cred.password = f(&cred.password)?;
+        }
+        Ok(())
+    }
+}
+
+/// What to do with one imported server
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportResolution {
+    /// Add as a new server (keeps both when it duplicates an existing one)
+    Add,
+    /// Leave it out
+    Skip,
+    /// Overwrite the duplicated existing server, keeping its ID
+    Replace,
+}
+
+/// Imported server with its duplicate and chosen resolution
+#[derive(Debug, Clone)]
+pub struct ImportCandidate {
+    pub server: DfcServerConfig,
+    /// ID of the existing server with the same configuration
+    pub duplicate_of: Option<String>,
+    pub resolution: ImportResolution,
+}
+
+/// Servers added, replaced and skipped by an import
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportSummary {
+    pub added: usize,
+    pub replaced: usize,
+    pub skipped: usize,
+}
+
+/// Match the servers of `export` against `existing`. Duplicates default to
+/// `Skip`, the others to `Add`. Without secrets in the file, the secrets of
+/// the existing servers are ignored in the comparison.
+pub fn plan_import(existing: &[DfcServerConfig], export: &ServerExport) -> Vec<ImportCandidate> {
+    let comparable: Vec<DfcServerConfig> = existing
+        .iter()
+        .cloned()
+        .map(|mut server| {
+            if export.secrets == ExportSecrets::Omit {
+                for slot in secret_slots_mut(&mut server) {
+                    *slot = None;
+                }
+            }
+            server
+        })
+        .collect();
+    export
+        .servers
+        .iter()
+        .map(|server| {
+            let duplicate_of = comparable
+                .iter()
+                .find(|existing| existing.same_config_for_uniqueness(server))
+                .map(|existing| existing.id.clone());
+            let resolution = if duplicate_of.is_some() {
+                ImportResolution::Skip
+            } else {
+                ImportResolution::Add
+            };
+            ImportCandidate {
+                server: server.clone(),
+                duplicate_of,
+                resolution,
+            }
+        })
+        .collect()
+}
+
+/// Apply the resolutions of `candidates` to `servers`. Added servers get new
+/// IDs; a replaced server keeps its ID and any secret the file left out.
+pub fn apply_import(
+    servers: &mut Vec<DfcServerConfig>,
+    candidates: Vec<ImportCandidate>,
+    updated_at: &str,
+) -> ImportSummary {
+    let mut summary = ImportSummary::default();
+    for candidate in candidates {
+        let mut server = candidate.server;
+        server.updated_at = Some(updated_at.to_string());
+        let target = match (candidate.resolution, candidate.duplicate_of) {
+            (ImportResolution::Skip, _) => None,
+            (ImportResolution::Replace, Some(id)) => {
+                servers.iter_mut().find(|existing| existing.id == id)
+            }
+            (ImportResolution::Add | ImportResolution::Replace, _) => {
+                server.id = Uuid::now_v7().to_string();
+                servers.push(server);
+                summary.added += 1;
+                continue;
+            }
+        };
+        match target {
+            Some(existing) => {
+                server.id = existing.id.clone();
+                keep_missing_secrets(&mut server, existing);
+                *existing = server;
+                summary.replaced += 1;
+            }
+            None => summary.skipped += 1,
+        }
+    }
+    summary
+}
+
+/// Add the imported preset credentials not known yet; returns how many were added
+pub fn merge_preset_credentials(
+    existing: &mut Vec<PresetCredential>,
+    imported: &[PresetCredential],
+) -> usize {
+    let before = existing.len();
+    for cred in imported {
+        if !cred.password.is_empty() && !existing.contains(cred) {
+            existing.push(cred.clone());
+        }
+    }
+    existing.len() - before
+}
+
+/// Fill the secrets `server` has no value for from `existing`
+fn keep_missing_secrets(server: &mut DfcServerConfig, existing: &DfcServerConfig) {
+    fn keep(slot: &mut Option<String>, previous: Option<&String>) {
+        if slot.as_deref().is_none_or(str::is_empty) {
+            *slot = previous.cloned();
+        }
+    }
+    keep(&mut server.password, existing.password.as_ref());
+    keep(&mut server.pulsar_token, existing.pulsar_token.as_ref());
+    if let (Some(ssh), Some(previous)) = (server.ssh_tunnel.as_mut(), &existing.ssh_tunnel) {
+        keep(&mut ssh.password, previous.password.as_ref());
+    }
+    if let (Some(proxy), Some(previous)) = (server.proxy.as_mut(), &existing.proxy) {
+        keep(&mut proxy.password, previous.password.as_ref());
+    }
+    if let (Some(security), Some(previous)) =
+        (server.pulsar_security.as_mut(), &existing.pulsar_security)
+    {
+        keep(&mut security.password, previous.password.as_ref());
+        keep(
+            &mut security.oauth2_client_secret,
+            previous.oauth2_client_secret.as_ref(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(id: &str, host: &str) -> DfcServerConfig {
+        DfcServerConfig {
+            id: id.to_string(),
+            name: format!("{host} server"),
+            host: host.to_string(),
+            port: 6379,
+            password: Some("redis-secret".to_string()),
+            cfgid: Some("{DCC0007}".to_string()),
+            pulsar_token: Some("pulsar-token".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn credentials() -> Vec<PresetCredential> {
+        vec![PresetCredential {
+            username: Some("ops".to_string()),
+            password: "preset-secret".to_string(),
+        }]
+    }
+
+    #[test]
+    fn omitted_secrets_are_left_out_of_the_file() {
+        let export = ServerExport::new(
+            &[server("a", "10.0.0.1")],
+            &credentials(),
+            ExportSecrets::Omit,
+            None,
+            "2026-10-18T10:00:00+08:00".to_string(),
+        )
+        .expect("export");
+        let content = export.to_toml().expect("serialize");
+        assert!(!content.contains("redis-secret"));
+        assert!(!content.contains("pulsar-token"));
+        assert!(!content.contains("preset-secret"));
+
+        let parsed = ServerExport::parse(&content).expect("parse");
+        assert!(!parsed.needs_passphrase());
+        assert_eq!(parsed.servers[0].host, "10.0.0.1");
+        assert_eq!(parsed.servers[0].password, None);
+    }
+
+    #[test]
+    fn encrypted_export_round_trips_with_the_passphrase() {
+        crate::helpers::init_test_vault();
+        let export = ServerExport::new(
+            &[server("a", "10.0.0.1")],
+            &credentials(),
+            ExportSecrets::Encrypted,
+            Some("onboarding pass"),
+            "2026-10-18T10:00:00+08:00".to_string(),
+        )
+        .expect("export");
+        let content = export.to_toml().expect("serialize");
+        assert!(!content.contains("redis-secret"));
+        assert!(!content.contains("preset-secret"));
+
+        let mut parsed = ServerExport::parse(&content).expect("parse");
+        assert!(parsed.needs_passphrase());
+        assert!(parsed.clone().decrypt("wrong pass").is_err());
+        parsed.decrypt("onboarding pass").expect("decrypt");
+        assert_eq!(parsed.servers[0].password.as_deref(), Some("redis-secret"));
+        assert_eq!(parsed.preset_credentials[0].password, "preset-secret");
+        assert!(
+            ServerExport::new(&[], &[], ExportSecrets::Encrypted, None, String::new()).is_err()
+        );
+    }
+
+    #[test]
+    fn import_resolves_duplicates() {
+        let mut existing = vec![server("a", "10.0.0.1"), server("b", "10.0.0.2")];
+        let mut renamed = server("x", "10.0.0.1");
+        renamed.name = "Renamed".to_string();
+        let export = ServerExport::new(
+            &[renamed, server("y", "10.0.0.2"), server("z", "10.0.0.3")],
+            &[],
+            ExportSecrets::Omit,
+            None,
+            String::new(),
+        )
+        .expect("export");
+
+        let mut plan = plan_import(&existing, &export);
+        assert_eq!(plan[0].duplicate_of.as_deref(), Some("a"));
+        assert_eq!(plan[1].duplicate_of.as_deref(), Some("b"));
+        assert_eq!(plan[2].duplicate_of, None);
+        assert_eq!(plan[0].resolution, ImportResolution::Skip);
+        assert_eq!(plan[2].resolution, ImportResolution::Add);
+        plan[0].resolution = ImportResolution::Replace;
+
+        let summary = apply_import(&mut existing, plan, "2026-10-18T10:00:00+08:00");
+        assert_eq!(
+            summary,
+            ImportSummary {
+                added: 1,
+                replaced: 1,
+                skipped: 1,
+            }
+        );
+        assert_eq!(existing.len(), 3);
+        assert_eq!(existing[0].id, "a");
+        assert_eq!(existing[0].name, "Renamed");
+        // The file had no secrets; the replaced server keeps its own
+        assert_eq!(existing[0].password.as_deref(), Some("redis-secret"));
+        assert_ne!(existing[2].id, "z");
+        assert_eq!(existing[2].host, "10.0.0.3");
+    }
+
+    #[test]
+    fn merge_preset_credentials_skips_known_entries() {
+        let mut existing = credentials();
+        let mut imported = credentials();
+        imported.push(PresetCredential {
+            username: None,
+            password: "other".to_string(),
+        });
+        assert_eq!(merge_preset_credentials(&mut existing, &imported), 1);
+        assert_eq!(existing.len(), 2);
+    }
+}
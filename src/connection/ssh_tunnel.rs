@@ -0,0 +1,172 @@
+//! SSH Tunnel Settings
+//!
+//! Per-server SSH tunnel through a bastion, optionally reached over a chain
+//! of jump hosts. Redis and Pulsar connections of the server are then carried
+//! by local port forwards.
+
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+/// Default SSH port
+const DEFAULT_SSH_PORT: u16 = 22;
+
+/// SSH tunnel of a server
+#[derive(Debug, Default, Deserialize, Clone, Serialize, Hash, Eq, PartialEq)]
+pub struct SshTunnelConfig {
+    /// Tunnel host, `user@host[:port]`
+    pub host: String,
+    /// Password, or passphrase of `key_file` (encrypted storage)
+    pub password: Option<String>,
+    /// Private key file (OpenSSH or PEM)
+    pub key_file: Option<String>,
+    /// Jump hosts before `host`, `user@host[:port]` separated by commas or
+    /// whitespace, in connection order; they share the credentials
+    pub jump_hosts: Option<String>,
+}
+
+impl SshTunnelConfig {
+    /// Hops in connection order, ending with the tunnel host
+    pub fn hops(&self) -> Result<Vec<SshHop>> {
+        let jumps = self.jump_hosts.as_deref().unwrap_or_default();
+        jumps
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|part| !part.is_empty())
+            .chain(std::iter::once(self.host.trim()))
+            .map(SshHop::parse)
+            .collect()
+    }
+}
+
+/// One SSH host of a tunnel chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SshHop {
+    pub user: String,
+    pub host: String,
+    pub port: u16,
+}
+
+impl SshHop {
+    /// Parse `user@host[:port]`; IPv6 hosts go in brackets
+    pub fn parse(text: &str) -> Result<Self> {
+        let invalid = |reason: &str| Error::Invalid {
+            message: format!("Invalid SSH host \"{text}\": {reason}"),
+        };
+        let (user, address) = text
+            .trim()
+            .rsplit_once('@')
+            .ok_or_else(|| invalid("missing user"))?;
+        let (host, port) = if let Some(rest) = address.strip_prefix('[') {
+            let (host, rest) = rest
+                .split_once(']')
+                .ok_or_else(|| invalid("unclosed bracket"))?;
+            match rest.strip_prefix(':') {
+                Some(port) => (host, Some(port)),
+                None if rest.is_empty() => (host, None),
+                None => return Err(invalid("unexpected text after host")),
+            }
+        } else {
+            match address.split_once(':') {
+                Some((host, port)) => (host, Some(port)),
+                None => (address, None),
+            }
+        };
+        let port = match port {
+            Some(port) => port.parse().map_err(|_| invalid("invalid port"))?,
+            None => DEFAULT_SSH_PORT,
+        };
+        if user.is_empty() {
+            return Err(invalid("missing user"));
+        }
+        if host.is_empty() {
+            return Err(invalid("missing host"));
+        }
+        Ok(Self {
+            user: user.to_string(),
+            host: host.to_string(),
+            port,
+        })
+    }
+}
+
+/// Scheme, host and port of `scheme://host[:port]`; IPv6 hosts go in brackets
+pub fn url_host_port(url: &str, default_port: u16) -> Option<(&str, &str, u16)> {
+    let (scheme, authority) = url.trim().split_once("://")?;
+    let authority = authority.trim_end_matches('/');
+    let (host, port) = match authority.strip_prefix('[') {
+        Some(rest) => {
+            let (host, rest) = rest.split_once(']')?;
+            (host, rest.strip_prefix(':'))
+        }
+        None => match authority.rsplit_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (authority, None),
+        },
+    };
+    if host.is_empty() || authority.contains('/') || authority.contains(',') {
+        return None;
+    }
+    let port = match port {
+        Some(port) => port.parse().ok()?,
+        None => default_port,
+    };
+    Some((scheme, host, port))
+}
+
+/// State of a server's SSH tunnel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SshTunnelStatus {
+    /// Connecting or reconnecting
+    Connecting,
+    /// Session established
+    Connected,
+    /// Last attempt failed; retried in the background
+    Failed(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_hops_in_connection_order() {
+        let tunnel = SshTunnelConfig {
+            host: "ops@10.15.200.10".to_string(),
+            jump_hosts: Some("jump@bastion.example.com:2222, me@[fd00::1]:22".to_string()),
+            ..Default::default()
+        };
+        let hops = tunnel.hops().expect("valid hops");
+        assert_eq!(
+            hops.iter()
+                .map(|hop| (hop.user.as_str(), hop.host.as_str(), hop.port))
+                .collect::<Vec<_>>(),
+            [
+                ("jump", "bastion.example.com", 2222),
+                ("me", "fd00::1", 22),
+                ("ops", "10.15.200.10", 22),
+            ]
+        );
+
+        assert!(SshHop::parse("10.15.200.10").is_err());
+        assert!(SshHop::parse("ops@").is_err());
+        assert!(SshHop::parse("ops@host:ssh").is_err());
+        assert!(SshHop::parse("ops@[fd00::1").is_err());
+    }
+
+    #[test]
+    fn splits_service_urls() {
+        assert_eq!(
+            url_host_port("pulsar://10.15.64.53:6650", 6650),
+            Some(("pulsar", "10.15.64.53", 6650))
+        );
+        assert_eq!(
+            url_host_port("pulsar+ssl://broker.example.com/", 6651),
+            Some(("pulsar+ssl", "broker.example.com", 6651))
+        );
+        assert_eq!(
+            url_host_port("pulsar://[fd00::1]:6650", 6650),
+            Some(("pulsar", "fd00::1", 6650))
+        );
+        assert_eq!(url_host_port("pulsar://a:6650,b:6650", 6650), None);
+        assert_eq!(url_host_port("10.15.64.53:6650", 6650), None);
+    }
+}
@@ -0,0 +1,850 @@
+//! Value Formatters
+//!
+//! Pluggable formatters that turn raw Redis string values into readable text or
+//! a JSON tree: JSON, iothub protobuf, MessagePack, hex dump and base64.
+
+use crate::proto::iothub::{
+    AnyValue, ClockTime, DataFrame, EnumValue, EventRecord, EventRecordList, HiClockTime,
+    SvrReqRecord, SvrRespRecord,
+};
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+use std::collections::HashMap;
+
+/// Bytes shown in a hex dump before it is truncated
+pub const HEX_DUMP_MAX_BYTES: usize = 64 * 1024;
+
+/// Maximum nesting depth accepted by the MessagePack decoder
+const MSGPACK_MAX_DEPTH: usize = 64;
+
+/// How a string value is displayed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValueFormat {
+    #[default]
+    Text,
+    Json,
+    Protobuf,
+    MessagePack,
+    Hex,
+    Base64,
+}
+
+impl ValueFormat {
+    /// All formats in selector order
+    pub const ALL: [ValueFormat; 6] = [
+        ValueFormat::Text,
+        ValueFormat::Json,
+        ValueFormat::Protobuf,
+        ValueFormat::MessagePack,
+        ValueFormat::Hex,
+        ValueFormat::Base64,
+    ];
+
+    /// i18n key of the format name
+    pub fn label_key(self) -> &'static str {
+        match self {
+            ValueFormat::Text => "value_format.text",
+            ValueFormat::Json => "value_format.json",
+            ValueFormat::Protobuf => "value_format.protobuf",
+            ValueFormat::MessagePack => "value_format.msgpack",
+            ValueFormat::Hex => "value_format.hex",
+            ValueFormat::Base64 => "value_format.base64",
+        }
+    }
+
+    /// Pick the first formatter that recognizes the value
+    pub fn detect(bytes: &[u8]) -> ValueFormat {
+        FORMATTERS
+            .iter()
+            .find(|formatter| formatter.detect(bytes))
+            .map(|formatter| formatter.format())
+            .unwrap_or(ValueFormat::Hex)
+    }
+
+    /// Format a value with this format's formatter
+    pub fn render(self, bytes: &[u8]) -> Result<FormattedValue, String> {
+        formatter(self).render(bytes)
+    }
+}
+
+/// Output of a formatter
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormattedValue {
+    /// Plain text, shown as is
+    Text(String),
+    /// Structured value, shown as a collapsible tree
+    Tree(Value),
+}
+
+/// A formatter for raw string values
+pub trait ValueFormatter: Sync {
+    /// Format this formatter implements
+    fn format(&self) -> ValueFormat;
+
+    /// Whether the value looks like this format; used for auto-detection
+    fn detect(&self, bytes: &[u8]) -> bool;
+
+    /// Format the value, or describe why it cannot be decoded
+    fn render(&self, bytes: &[u8]) -> Result<FormattedValue, String>;
+}
+
+/// Registered formatters in auto-detection order
+static FORMATTERS: &[&dyn ValueFormatter] = &[
+    &JsonFormatter,
+    &MessagePackFormatter,
+    &ProtobufFormatter,
+    &TextFormatter,
+    &HexFormatter,
+    &Base64Formatter,
+];
+
+/// Look up the formatter of a format
+pub fn formatter(format: ValueFormat) -> &'static dyn ValueFormatter {
+    FORMATTERS
+        .iter()
+        .copied()
+        .find(|formatter| formatter.format() == format)
+        .unwrap_or(&TextFormatter)
+}
+
+/// Key pattern a remembered format applies to.
+///
+/// The last segment after any delimiter is replaced by `*`, so
+/// `cmc:device:42` and `cmc:device:43` share `cmc:device:*`.
+pub fn key_pattern(key: &str, delimiters: &str) -> String {
+    match key.rfind(|c| delimiters.contains(c)) {
+        Some(ix) => {
+            let delimiter_len = key[ix..].chars().next().map_or(1, char::len_utf8);
+            format!("{}*", &key[..ix + delimiter_len])
+        }
+        None => key.to_string(),
+    }
+}
+
+/// Whether the bytes are binary rather than printable text
+fn looks_binary(bytes: &[u8]) -> bool {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text
+            .chars()
+            .any(|c| c.is_control() && !matches!(c, '\t' | '\n' | '\r')),
+        Err(_) => true,
+    }
+}
+
+// ==================== Text ====================
+
+struct TextFormatter;
+
+impl ValueFormatter for TextFormatter {
+    fn format(&self) -> ValueFormat {
+        ValueFormat::Text
+    }
+
+    fn detect(&self, bytes: &[u8]) -> bool {
+        !looks_binary(bytes)
+    }
+
+    fn render(&self, bytes: &[u8]) -> Result<FormattedValue, String> {
+        Ok(FormattedValue::Text(
+            String::from_utf8_lossy(bytes).into_owned(),
+        ))
+    }
+}
+
+// ==================== JSON ====================
+
+struct JsonFormatter;
+
+impl ValueFormatter for JsonFormatter {
+    fn format(&self) -> ValueFormat {
+        ValueFormat::Json
+    }
+
+    fn detect(&self, bytes: &[u8]) -> bool {
+        let trimmed = bytes.trim_ascii();
+        matches!(
+            (trimmed.first(), trimmed.last()),
+            (Some(b'{'), Some(b'}')) | (Some(b'['), Some(b']'))
+        ) && serde_json::from_slice::<Value>(trimmed).is_ok()
+    }
+
+    fn render(&self, bytes: &[u8]) -> Result<FormattedValue, String> {
+        serde_json::from_slice(bytes)
+            .map(FormattedValue::Tree)
+            .map_err(|e| e.to_string())
+    }
+}
+
+// ==================== Hex ====================
+
+struct HexFormatter;
+
+impl ValueFormatter for HexFormatter {
+    fn format(&self) -> ValueFormat {
+        ValueFormat::Hex
+    }
+
+    fn detect(&self, _bytes: &[u8]) -> bool {
+        true
+    }
+
+    fn render(&self, bytes: &[u8]) -> Result<FormattedValue, String> {
+        Ok(FormattedValue::Text(hex_dump(bytes)))
+    }
+}
+
+/// Classic 16 bytes per line hex dump with an ASCII column
+pub fn hex_dump(bytes: &[u8]) -> String {
+    let shown = &bytes[..bytes.len().min(HEX_DUMP_MAX_BYTES)];
+    let mut out = String::with_capacity(shown.len() * 4 + 16);
+    for (line, chunk) in shown.chunks(16).enumerate() {
+        out.push_str(&format!("{:08x} ", line * 16));
+        for ix in 0..16 {
+            if ix == 8 {
+                out.push(' ');
+            }
+            match chunk.get(ix) {
+                Some(byte) => out.push_str(&format!(" {byte:02x}")),
+                None => out.push_str("   "),
+            }
+        }
+        out.push_str("  |");
+        out.extend(chunk.iter().map(|&byte| {
+            if byte.is_ascii_graphic() || byte == b' ' {
+                byte as char
+            } else {
+                '.'
+            }
+        }));
+        out.push_str("|\n");
+    }
+    if bytes.len() > shown.len() {
+        out.push_str(&format!("... {} more bytes\n", bytes.len() - shown.len()));
+    }
+    out
+}
+
+// ==================== Base64 ====================
+
+struct Base64Formatter;
+
+impl ValueFormatter for Base64Formatter {
+    fn format(&self) -> ValueFormat {
+        ValueFormat::Base64
+    }
+
+    fn detect(&self, _bytes: &[u8]) -> bool {
+        // Too many plain words are valid base64; only chosen explicitly.
+        false
+    }
+
+    /// Decode and show the payload with the format detected for it
+    fn render(&self, bytes: &[u8]) -> Result<FormattedValue, String> {
+        let decoded = BASE64
+            .decode(bytes.trim_ascii())
+            .map_err(|e| e.to_string())?;
+        ValueFormat::detect(&decoded).render(&decoded)
+    }
+}
+
+// ==================== MessagePack ====================
+
+struct MessagePackFormatter;
+
+impl ValueFormatter for MessagePackFormatter {
+    fn format(&self) -> ValueFormat {
+        ValueFormat::MessagePack
+    }
+
+    /// Only top-level maps and arrays, which cannot be confused with text
+    fn detect(&self, bytes: &[u8]) -> bool {
+        matches!(bytes.first(), Some(0x80..=0x9f | 0xdc..=0xdf)) && decode_msgpack(bytes).is_ok()
+    }
+
+    fn render(&self, bytes: &[u8]) -> Result<FormattedValue, String> {
+        decode_msgpack(bytes).map(FormattedValue::Tree)
+    }
+}
+
+/// Decode a complete MessagePack document into a JSON value.
+///
+/// Binary and extension payloads become base64 strings; non-string map keys
+/// are stringified.
+pub fn decode_msgpack(bytes: &[u8]) -> Result<Value, String> {
+    let mut reader = MsgPackReader { bytes, pos: 0 };
+    let value = reader.read_value(0)?;
+    if reader.pos != bytes.len() {
+        return Err(format!(
+            "{} trailing bytes after MessagePack value",
+            bytes.len() - reader.pos
+        ));
+    }
+    Ok(value)
+}
+
+struct MsgPackReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> MsgPackReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| format!("Unexpected end of MessagePack data at byte {}", self.pos))?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        let mut out = [0u8; N];
+        out.copy_from_slice(self.take(N)?);
+        Ok(out)
+    }
+
+    fn read_len(&mut self, width: usize) -> Result<usize, String> {
+        Ok(match width {
+            1 => u8::from_be_bytes(self.read_array()?) as usize,
+            2 => u16::from_be_bytes(self.read_array()?) as usize,
+            _ => u32::from_be_bytes(self.read_array()?) as usize,
+        })
+    }
+
+    fn read_str(&mut self, len: usize) -> Result<Value, String> {
+        let raw = self.take(len)?;
+        Ok(Value::String(String::from_utf8_lossy(raw).into_owned()))
+    }
+
+    fn read_bin(&mut self, len: usize) -> Result<Value, String> {
+        Ok(Value::String(BASE64.encode(self.take(len)?)))
+    }
+
+    fn read_ext(&mut self, len: usize) -> Result<Value, String> {
+        let [ext_type] = self.read_array::<1>()?;
+        let data = BASE64.encode(self.take(len)?);
+        Ok(json!({ "ext_type": ext_type as i8, "data": data }))
+    }
+
+    fn read_seq(&mut self, len: usize, depth: usize) -> Result<Value, String> {
+        // Every element takes at least one byte, which bounds the allocation.
+        let mut items = Vec::with_capacity(len.min(self.bytes.len() - self.pos));
+        for _ in 0..len {
+            items.push(self.read_value(depth + 1)?);
+        }
+        Ok(Value::Array(items))
+    }
+
+    fn read_map(&mut self, len: usize, depth: usize) -> Result<Value, String> {
+        let mut map = Map::new();
+        for _ in 0..len {
+            let key = match self.read_value(depth + 1)? {
+                Value::String(key) => key,
+                other => other.to_string(),
+            };
+            let value = self.read_value(depth + 1)?;
+            map.insert(key, value);
+        }
+        Ok(Value::Object(map))
+    }
+
+    fn read_value(&mut self, depth: usize) -> Result<Value, String> {
+        if depth > MSGPACK_MAX_DEPTH {
+            return Err("MessagePack value is nested too deeply".to_string());
+        }
+        let [marker] = self.read_array::<1>()?;
+        match marker {
+            0x00..=0x7f => Ok(json!(marker)),
+            0x80..=0x8f => self.read_map((marker & 0x0f) as usize, depth),
+            0x90..=0x9f => self.read_seq((marker & 0x0f) as usize, depth),
+            0xa0..=0xbf => self.read_str((marker & 0x1f) as usize),
+            0xc0 => Ok(Value::Null),
+            0xc2 => Ok(Value::Bool(false)),
+            0xc3 => Ok(Value::Bool(true)),
+            0xc4..=0xc6 => {
+                let len = self.read_len(1 << (marker - 0xc4))?;
+                self.read_bin(len)
+            }
+            0xc7..=0xc9 => {
+                let len = self.read_len(1 << (marker - 0xc7))?;
+                self.read_ext(len)
+            }
+            0xca => Ok(json!(f32::from_be_bytes(self.read_array()?))),
+            0xcb => Ok(json!(f64::from_be_bytes(self.read_array()?))),
+            0xcc => Ok(json!(u8::from_be_bytes(self.read_array()?))),
+            0xcd => Ok(json!(u16::from_be_bytes(self.read_array()?))),
+            0xce => Ok(json!(u32::from_be_bytes(self.read_array()?))),
+            0xcf => Ok(json!(u64::from_be_bytes(self.read_array()?))),
+            0xd0 => Ok(json!(i8::from_be_bytes(self.read_array()?))),
+            0xd1 => Ok(json!(i16::from_be_bytes(self.read_array()?))),
+            0xd2 => Ok(json!(i32::from_be_bytes(self.read_array()?))),
+            0xd3 => Ok(json!(i64::from_be_bytes(self.read_array()?))),
+            0xd4..=0xd8 => self.read_ext(1 << (marker - 0xd4)),
+            0xd9..=0xdb => {
+                let len = self.read_len(1 << (marker - 0xd9))?;
+                self.read_str(len)
+            }
+            0xdc | 0xdd => {
+                let len = self.read_len(if marker == 0xdc { 2 } else { 4 })?;
+                self.read_seq(len, depth)
+            }
+            0xde | 0xdf => {
+                let len = self.read_len(if marker == 0xde { 2 } else { 4 })?;
+                self.read_map(len, depth)
+            }
+            0xe0..=0xff => Ok(json!(marker as i8)),
+            0xc1 => Err(format!(
+                "Invalid MessagePack marker 0xc1 at byte {}",
+                self.pos - 1
+            )),
+        }
+    }
+}
+
+// ==================== Protobuf ====================
+
+struct ProtobufFormatter;
+
+impl ValueFormatter for ProtobufFormatter {
+    fn format(&self) -> ValueFormat {
+        ValueFormat::Protobuf
+    }
+
+    fn detect(&self, bytes: &[u8]) -> bool {
+        looks_binary(bytes) && decode_iothub(bytes).is_some()
+    }
+
+    fn render(&self, bytes: &[u8]) -> Result<FormattedValue, String> {
+        decode_iothub(bytes)
+            .map(FormattedValue::Tree)
+            .ok_or_else(|| "Not a known iothub protobuf message".to_string())
+    }
+}
+
+/// Decode `bytes` as `T` only if every byte belongs to a known field
+fn decode_exact<T: prost::Message + Default + PartialEq>(bytes: &[u8]) -> Option<T> {
+    let message = T::decode(bytes).ok()?;
+    (message != T::default() && message.encoded_len() == bytes.len()).then_some(message)
+}
+
+/// Try the iothub message types a value is commonly stored as.
+///
+/// The result carries the message name under `@type` and keeps the proto
+/// field names.
+fn decode_iothub(bytes: &[u8]) -> Option<Value> {
+    let (name, mut value) = if let Some(list) = decode_exact::<EventRecordList>(bytes) {
+        (
+            "EventRecordList",
+            json!({ "eventArray": list.event_array.iter().map(event_record_json).collect::<Vec<_>>() }),
+        )
+    } else if let Some(frame) = decode_exact::<DataFrame>(bytes) {
+        ("DataFrame", data_frame_json(&frame))
+    } else if let Some(event) = decode_exact::<EventRecord>(bytes) {
+        ("EventRecord", event_record_json(&event))
+    } else if let Some(req) = decode_exact::<SvrReqRecord>(bytes) {
+        (
+            "SvrReqRecord",
+            json!({
+                "reqSerialUUID": req.req_serial_uuid,
+                "reqDateTime": clock_time_json(req.req_date_time.as_ref()),
+                "timeOut": req.time_out,
+                "requester": req.requester,
+                "imr": req.imr,
+                "args": any_map_json(&req.args),
+                "isTestRequest": req.is_test_request,
+            }),
+        )
+    } else if let Some(resp) = decode_exact::<SvrRespRecord>(bytes) {
+        (
+            "SvrRespRecord",
+            json!({
+                "reqSerialUUID": resp.req_serial_uuid,
+                "respCode": resp.resp_code,
+                "respDateTime": clock_time_json(resp.resp_date_time.as_ref()),
+                "requester": resp.requester,
+                "imr": resp.imr,
+                "args": any_map_json(&resp.args),
+                "responser": resp.responser,
+            }),
+        )
+    } else {
+        return None;
+    };
+    if let Value::Object(map) = &mut value {
+        map.insert("@type".to_string(), Value::String(name.to_string()));
+    }
+    Some(value)
+}
+
+fn event_record_json(event: &EventRecord) -> Value {
+    json!({
+        "evtUUID": event.evt_uuid,
+        "type": event.r#type,
+        "tags": event.tags,
+        "src": event.src,
+        "imGlobalUUID": event.im_global_uuid,
+        "imr": event.imr,
+        "happenedTime": hi_clock_time_json(event.happened_time.as_ref()),
+        "recordTime": clock_time_json(event.record_time.as_ref()),
+        "level": event.level,
+        "code": event.code.iter().map(enum_value_json).collect::<Vec<_>>(),
+        "dictName": event.dict_name,
+        "bcrUUID": event.bcr_uuid,
+        "context": any_map_json(&event.context),
+    })
+}
+
+fn data_frame_json(frame: &DataFrame) -> Value {
+    use crate::proto::iothub::data_record::K;
+
+    let sets = frame.frame.iter().map(|set| {
+        let header = set.header.as_ref().map(|header| {
+            json!({
+                "imGlobalUUID": header.im_global_uuid,
+                "seriesType": header.series_type,
+                "windowSize": header.window_size,
+                "sourceDevice": header.source_device,
+                "t": clock_time_json(header.t.as_ref()),
+                "nanoSecond": header.nano_second,
+                "extendsData": any_map_json(&header.extends_data),
+            })
+        });
+        let data = set.data.iter().map(|record| {
+            let mut value = json!({
+                "v": record.v.as_ref().map_or(Value::Null, any_value_json),
+                "q": record.q,
+                "bcrUUID": record.bcr_uuid,
+                "deviceTime": clock_time_json(record.device_time.as_ref()),
+            });
+            if let Value::Object(map) = &mut value {
+                match &record.k {
+                    Some(K::Im2id(id)) => map.insert("im2id".to_string(), json!(id)),
+                    Some(K::Imr(imr)) => map.insert("imr".to_string(), json!(imr.path)),
+                    None => None,
+                };
+            }
+            value
+        });
+        json!({ "header": header, "data": data.collect::<Vec<_>>() })
+    });
+    json!({ "frame": sets.collect::<Vec<_>>() })
+}
+
+fn clock_time_json(clock: Option<&ClockTime>) -> Value {
+    clock.map_or(Value::Null, |clock| {
+        timestamp_json(clock.t, 0, clock.zone_info)
+    })
+}
+
+fn hi_clock_time_json(clock: Option<&HiClockTime>) -> Value {
+    clock.map_or(Value::Null, |clock| {
+        timestamp_json(clock.t, clock.nano, clock.zone_info)
+    })
+}
+
+fn timestamp_json(secs: u32, nanos: u32, zone_info: i32) -> Value {
+    let time =
+        chrono::DateTime::<chrono::Utc>::from_timestamp(i64::from(secs), nanos.min(999_999_999))
+            .map(|dt| dt.to_rfc3339());
+    json!({ "t": secs, "time": time, "zoneInfo": zone_info })
+}
+
+fn enum_value_json(value: &EnumValue) -> Value {
+    use crate::proto::iothub::enum_value::V;
+    match &value.v {
+        Some(V::Uint64V(v)) => json!(v),
+        Some(V::BoolV(v)) => json!(v),
+        Some(V::StringV(v)) => json!(v),
+        None => Value::Null,
+    }
+}
+
+fn any_map_json(map: &HashMap<String, AnyValue>) -> Value {
+    Value::Object(
+        map.iter()
+            .map(|(key, value)| (key.clone(), any_value_json(value)))
+            .collect(),
+    )
+}
+
+/// Convert an `AnyValue`, expanding embedded JSON and MessagePack payloads
+fn any_value_json(value: &AnyValue) -> Value {
+    use crate::proto::iothub::any_value::V;
+    match &value.v {
+        Some(V::DoubleV(v)) => json!(v),
+        Some(V::FloatV(v)) => json!(v),
+        Some(V::Int32V(v)) => json!(v),
+        Some(V::Uint32V(v)) => json!(v),
+        Some(V::Uint64V(v)) => json!(v),
+        Some(V::Sint32V(v)) => json!(v),
+        Some(V::Sint64V(v)) => json!(v),
+        Some(V::Fixed32V(v)) => json!(v),
+        Some(V::Fixed64V(v)) => json!(v),
+        Some(V::Sfixed32V(v)) => json!(v),
+        Some(V::Sfixed64V(v)) => json!(v),
+        Some(V::BoolV(v)) => json!(v),
+        Some(V::StringV(v)) => json!(v),
+        Some(V::BytesV(v)) => json!(BASE64.encode(v)),
+        Some(V::AnyV(any)) => decode_iothub(&any.value).unwrap_or_else(
+            || json!({ "typeUrl": any.type_url, "value": BASE64.encode(&any.value) }),
+        ),
+        Some(V::NullV(_)) | None => Value::Null,
+        Some(V::JsonV(v)) => serde_json::from_str(v).unwrap_or_else(|_| json!(v)),
+        Some(V::MsgPackV(v)) => decode_msgpack(v).unwrap_or_else(|_| json!(BASE64.encode(v))),
+    }
+}
+
+// ==================== JSON tree ====================
+
+/// One-line summary of a JSON node
+pub fn json_summary(value: &Value) -> String {
+    match value {
+        Value::Object(map) => format!("{{…}} {} keys", map.len()),
+        Value::Array(items) => format!("[…] {} items", items.len()),
+        Value::String(s) => format!("\"{}\"", s.escape_debug()),
+        other => other.to_string(),
+    }
+}
+
+/// Path of an object member below `parent`, in JSONPath notation
+pub fn json_child_path(parent: &str, key: &str) -> String {
+    let plain = key
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_alphanumeric() || c == '_');
+    if plain {
+        format!("{parent}.{key}")
+    } else {
+        format!("{parent}['{}']", key.replace('\'', "\\'"))
+    }
+}
+
+/// One step of a parsed JSONPath
+#[derive(Debug, Clone, PartialEq)]
+enum PathSegment {
+    /// `.name` or `['name']`
+    Child(String),
+    /// `[n]`, negative counts from the end
+    Index(i64),
+    /// `.*` or `[*]`
+    Wildcard,
+    /// `..name`, or `..*` when `None`
+    Descendant(Option<String>),
+}
+
+fn parse_json_path(path: &str) -> Result<Vec<PathSegment>, String> {
+    let rest = path
+        .trim()
+        .strip_prefix('$')
+        .ok_or_else(|| "JSONPath must start with $".to_string())?;
+    let chars: Vec<char> = rest.chars().collect();
+    let mut segments = Vec::new();
+    let mut ix = 0;
+
+    let read_name = |ix: &mut usize| {
+        let start = *ix;
+        while *ix < chars.len() && !matches!(chars[*ix], '.' | '[') {
+            *ix += 1;
+        }
+        chars[start..*ix].iter().collect::<String>()
+    };
+
+    while ix < chars.len() {
+        match chars[ix] {
+            '.' if chars.get(ix + 1) == Some(&'.') => {
+                ix += 2;
+                let name = read_name(&mut ix);
+                if name.is_empty() {
+                    return Err("Missing member name after ..".to_string());
+                }
+                segments.push(PathSegment::Descendant((name != "*").then_some(name)));
+            }
+            '.' => {
+                ix += 1;
+                let name = read_name(&mut ix);
+                segments.push(match name.as_str() {
+                    "" => return Err("Missing member name after .".to_string()),
+                    "*" => PathSegment::Wildcard,
+                    _ => PathSegment::Child(name),
+                });
+            }
+            '[' => {
+                let close = chars[ix..]
+                    .iter()
+                    .position(|c| *c == ']')
+                    .map(|offset| ix + offset)
+                    .ok_or_else(|| "Unclosed [ in JSONPath".to_string())?;
+                let inner: String = chars[ix + 1..close].iter().collect();
+                let inner = inner.trim();
+                let quoted = ['\'', '"'].iter().find_map(|q| {
+                    inner
+                        .strip_prefix(*q)
+                        .and_then(|s| s.strip_suffix(*q))
+                        .map(|s| s.replace(&format!("\\{q}"), &q.to_string()))
+                });
+                segments.push(if inner == "*" {
+                    PathSegment::Wildcard
+                } else if let Some(name) = quoted {
+                    PathSegment::Child(name)
+                } else {
+                    PathSegment::Index(
+                        inner
+                            .parse()
+                            .map_err(|_| format!("Invalid index [{inner}] in JSONPath"))?,
+                    )
+                });
+                ix = close + 1;
+            }
+            c => return Err(format!("Unexpected '{c}' in JSONPath")),
+        }
+    }
+    Ok(segments)
+}
+
+/// Children of a node with their paths
+fn json_children<'a>(path: &str, value: &'a Value) -> Vec<(String, &'a Value)> {
+    match value {
+        Value::Object(map) => map
+            .iter()
+            .map(|(key, child)| (json_child_path(path, key), child))
+            .collect(),
+        Value::Array(items) => items
+            .iter()
+            .enumerate()
+            .map(|(ix, child)| (format!("{path}[{ix}]"), child))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn push_descendants<'a>(path: String, value: &'a Value, out: &mut Vec<(String, &'a Value)>) {
+    let children = json_children(&path, value);
+    out.push((path, value));
+    for (child_path, child) in children {
+        push_descendants(child_path, child, out);
+    }
+}
+
+/// Evaluate a JSONPath query (`$`, `.name`, `['name']`, `[n]`, `[*]`, `..name`).
+///
+/// Returns every matching node with its normalized path.
+pub fn json_path_query<'a>(
+    root: &'a Value,
+    path: &str,
+) -> Result<Vec<(String, &'a Value)>, String> {
+    let mut nodes = vec![("$".to_string(), root)];
+    for segment in parse_json_path(path)? {
+        let mut next = Vec::new();
+        for (node_path, node) in nodes {
+            match &segment {
+                PathSegment::Child(name) => {
+                    if let Some(child) = node.get(name.as_str()) {
+                        next.push((json_child_path(&node_path, name), child));
+                    }
+                }
+                PathSegment::Index(index) => {
+                    if let Value::Array(items) = node {
+                        let len = items.len() as i64;
+                        let ix = if *index < 0 { len + index } else { *index };
+                        if (0..len).contains(&ix) {
+                            next.push((format!("{node_path}[{ix}]"), &items[ix as usize]));
+                        }
+                    }
+                }
+                PathSegment::Wildcard => next.extend(json_children(&node_path, node)),
+                PathSegment::Descendant(name) => {
+                    let mut all = Vec::new();
+                    push_descendants(node_path, node, &mut all);
+                    for (desc_path, desc) in all {
+                        match name {
+                            Some(name) => {
+                                if let Some(child) = desc.as_object().and_then(|m| m.get(name)) {
+                                    next.push((json_child_path(&desc_path, name), child));
+                                }
+                            }
+                            None => next.extend(json_children(&desc_path, desc)),
+                        }
+                    }
+                }
+            }
+        }
+        nodes = next;
+    }
+    Ok(nodes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost::Message;
+
+    #[test]
+    fn detects_formats() {
+        assert_eq!(ValueFormat::detect(br#" {"a": 1} "#), ValueFormat::Json);
+        assert_eq!(ValueFormat::detect(b"plain text"), ValueFormat::Text);
+        assert_eq!(
+            ValueFormat::detect(b"\x81\xa1a\x01"),
+            ValueFormat::MessagePack
+        );
+        assert_eq!(ValueFormat::detect(&[0xff, 0x00, 0x13]), ValueFormat::Hex);
+
+        let list = EventRecordList {
+            event_array: vec![EventRecord {
+                evt_uuid: "evt-1".to_string(),
+                level: 3,
+                ..Default::default()
+            }],
+        };
+        let bytes = list.encode_to_vec();
+        assert_eq!(ValueFormat::detect(&bytes), ValueFormat::Protobuf);
+        let Ok(FormattedValue::Tree(tree)) = ValueFormat::Protobuf.render(&bytes) else {
+            panic!("protobuf should decode");
+        };
+        assert_eq!(tree["@type"], "EventRecordList");
+        assert_eq!(tree["eventArray"][0]["evtUUID"], "evt-1");
+    }
+
+    #[test]
+    fn decodes_msgpack_and_base64() {
+        // {"id": 7, "tags": ["x", -1], "ok": true}
+        let bytes = b"\x83\xa2id\x07\xa4tags\x92\xa1x\xff\xa2ok\xc3";
+        let value = decode_msgpack(bytes).expect("valid msgpack");
+        assert_eq!(value, json!({ "id": 7, "tags": ["x", -1], "ok": true }));
+        assert!(decode_msgpack(b"\x92\x01").is_err());
+
+        let encoded = BASE64.encode(br#"{"a":[1,2]}"#);
+        assert_eq!(
+            ValueFormat::Base64.render(encoded.as_bytes()),
+            Ok(FormattedValue::Tree(json!({ "a": [1, 2] })))
+        );
+        assert!(hex_dump(b"AB\x00").starts_with("00000000  41 42 00"));
+    }
+
+    #[test]
+    fn evaluates_json_path() {
+        let root = json!({
+            "device": { "id": "d1", "points": [{ "id": 1 }, { "id": 2, "tag": "x y" }] },
+            "x y": true,
+        });
+        let paths = |query: &str| {
+            json_path_query(&root, query)
+                .expect("valid query")
+                .into_iter()
+                .map(|(path, _)| path)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(paths("$.device.points[-1].id"), ["$.device.points[1].id"]);
+        assert_eq!(paths("$['x y']"), ["$['x y']"]);
+        assert_eq!(paths("$.device.points[*].id").len(), 2);
+        assert_eq!(paths("$..id").len(), 3);
+        assert!(json_path_query(&root, "device").is_err());
+        assert_eq!(key_pattern("cmc:device:42", ":_"), "cmc:device:*");
+        assert_eq!(key_pattern("standalone", ":"), "standalone");
+    }
+}
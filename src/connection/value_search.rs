@@ -0,0 +1,177 @@
+//! Value Search
+//!
+//! Matching and excerpting for the "find in values" job of the key browser.
+
+use super::redis_config::RedisKeyType;
+use regex::{Regex, RegexBuilder};
+use std::ops::Range;
+
+/// Characters of context kept on each side of a match in an excerpt
+const EXCERPT_CONTEXT_CHARS: usize = 40;
+
+/// Matches value text against a substring or a regular expression
+#[derive(Debug, Clone)]
+pub enum ValueMatcher {
+    /// Plain substring; the needle is lowercased when case-insensitive
+    Substring {
+        needle: String,
+        case_sensitive: bool,
+    },
+    /// Regular expression
+    Regex(Regex),
+}
+
+impl ValueMatcher {
+    /// Build a matcher, rejecting empty needles and invalid expressions
+    pub fn new(needle: &str, regex: bool, case_sensitive: bool) -> Result<Self, String> {
+        if needle.is_empty() {
+            return Err("Search text is empty".to_string());
+        }
+        if regex {
+            RegexBuilder::new(needle)
+                .case_insensitive(!case_sensitive)
+                .build()
+                .map(Self::Regex)
+                .map_err(|e| e.to_string())
+        } else {
+            Ok(Self::Substring {
+                needle: if case_sensitive {
+                    needle.to_string()
+                } else {
+                    needle.chars().map(fold_char).collect()
+                },
+                case_sensitive,
+            })
+        }
+    }
+
+    /// Byte range of the first match in `text`
+    pub fn find(&self, text: &str) -> Option<Range<usize>> {
+        match self {
+            Self::Substring {
+                needle,
+                case_sensitive: true,
+            } => text.find(needle.as_str()).map(|ix| ix..ix + needle.len()),
+            Self::Substring { needle, .. } => find_ignore_case(text, needle),
+            Self::Regex(regex) => regex
+                .find(text)
+                .filter(|m| !m.is_empty())
+                .map(|m| m.range()),
+        }
+    }
+
+    /// Excerpt around the first match in `text`
+    pub fn excerpt(&self, text: &str) -> Option<String> {
+        self.find(text).map(|range| excerpt(text, range))
+    }
+}
+
+/// Lowercase a char, keeping one char per char so byte offsets can be mapped back
+fn fold_char(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+/// Case-insensitive substring search returning a byte range of `text`.
+///
+/// Compares char by char so the range stays valid even when lowercasing
+/// changes a character's byte length.
+fn find_ignore_case(text: &str, needle: &str) -> Option<Range<usize>> {
+    let needle: Vec<char> = needle.chars().collect();
+    let lowered: Vec<(usize, char)> = text
+        .char_indices()
+        .map(|(ix, c)| (ix, fold_char(c)))
+        .collect();
+    lowered
+        .windows(needle.len())
+        .position(|window| window.iter().map(|(_, c)| *c).eq(needle.iter().copied()))
+        .map(|start| {
+            let begin = lowered[start].0;
+            let end = lowered
+                .get(start + needle.len())
+                .map_or(text.len(), |(ix, _)| *ix);
+            begin..end
+        })
+}
+
+/// Single-line excerpt of `text` with some context around `range`
+pub fn excerpt(text: &str, range: Range<usize>) -> String {
+    let before: Vec<char> = text[..range.start].chars().collect();
+    let after: Vec<char> = text[range.end..].chars().collect();
+    let head_start = before.len().saturating_sub(EXCERPT_CONTEXT_CHARS);
+    let tail_end = after.len().min(EXCERPT_CONTEXT_CHARS);
+
+    let mut out = String::new();
+    if head_start > 0 {
+        out.push('…');
+    }
+    out.extend(before[head_start..].iter());
+    out.push_str(&text[range]);
+    out.extend(after[..tail_end].iter());
+    if tail_end < after.len() {
+        out.push('…');
+    }
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// One value match found by the search
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValueSearchMatch {
+    /// Key holding the value
+    pub key: String,
+    /// Type of the key
+    pub key_type: RedisKeyType,
+    /// Hash field, list index or member the match is in (`None` for strings)
+    pub field: Option<String>,
+    /// Text around the match
+    pub excerpt: String,
+}
+
+/// Result of searching one batch of keys
+#[derive(Debug, Clone, Default)]
+pub struct ValueSearchBatch {
+    /// Matches found in this batch
+    pub matches: Vec<ValueSearchMatch>,
+    /// Keys whose values were searched
+    pub scanned: usize,
+    /// SCAN cursor to continue from; 0 when the keyspace is exhausted
+    pub next_cursor: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_substring_and_regex() {
+        let text = "topic: persistent://cmc/agent/Agent-42/status";
+
+        let plain = ValueMatcher::new("agent-42", false, false).expect("valid matcher");
+        let range = plain.find(text).expect("case-insensitive match");
+        assert_eq!(&text[range], "Agent-42");
+        assert!(
+            ValueMatcher::new("agent-42", false, true)
+                .expect("valid matcher")
+                .find(text)
+                .is_none()
+        );
+
+        let regex = ValueMatcher::new(r"agent-\d+", true, false).expect("valid regex");
+        assert_eq!(
+            regex.excerpt(text).as_deref(),
+            Some("topic: persistent://cmc/agent/Agent-42/status")
+        );
+        assert!(ValueMatcher::new("(", true, true).is_err());
+        assert!(ValueMatcher::new("", false, true).is_err());
+    }
+
+    #[test]
+    fn excerpt_trims_long_context() {
+        let text = format!("{}needle{}", "a".repeat(100), "b\n".repeat(50));
+        let range = 100..106;
+        let out = excerpt(&text, range);
+        assert!(out.starts_with('…'));
+        assert!(out.ends_with('…'));
+        assert!(out.contains(&format!("{}needle", "a".repeat(EXCERPT_CONTEXT_CHARS))));
+        assert!(!out.contains('\n'));
+    }
+}
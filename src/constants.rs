@@ -38,6 +38,9 @@ pub const RETRY_MAX_DELAY_MS: u64 = 60000;
 pub const RETRY_MULTIPLIER: f64 = 2.0;
 pub const RETRY_JITTER: f64 = 0.1;
 
+/// Default delimiter characters of the key namespace tree
+pub const DEFAULT_KEY_TREE_DELIMITERS: &str = "_:.";
+
 /// Command timeout
 pub const COMMAND_TIMEOUT_SECS: u64 = 30;
 
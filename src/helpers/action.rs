@@ -6,6 +6,9 @@ use gpui::{Action, KeyBinding, Window};
 use schemars::JsonSchema;
 use serde::Deserialize;
 
+/// Key context of the Redis console's command input
+pub const CONSOLE_INPUT_CONTEXT: &str = "RedisConsole > Input";
+
 /// Menu actions (application-level)
 #[derive(Clone, Copy, PartialEq, Debug, Deserialize, JsonSchema, Action)]
 pub enum MenuAction {
@@ -63,6 +66,17 @@ pub enum CommandAction {
     Cancel,
 }
 
+/// Redis console input actions
+#[derive(Clone, Copy, PartialEq, Debug, Deserialize, JsonSchema, Action)]
+pub enum ConsoleAction {
+    /// Complete the command name
+    Complete,
+    /// Recall the previous command from history
+    HistoryPrevious,
+    /// Recall the next command from history
+    HistoryNext,
+}
+
 /// Sidebar server tab actions
 #[derive(Clone, Copy, PartialEq, Debug, Deserialize, JsonSchema, Action)]
 pub enum ServerAction {
@@ -74,6 +88,10 @@ pub enum ServerAction {
     Delete,
     /// Reconnect the selected server
     Reconnect,
+    /// Run connection diagnostics for the selected server
+    Diagnose,
+    /// Store the preset credential that connected as the server's own password
+    PromoteCredential,
 }
 
 /// Convert a keystroke string to human-readable format
@@ -187,6 +205,18 @@ pub fn new_key_bindings() -> Vec<KeyBinding> {
         // Commands
         KeyBinding::new("secondary-enter", CommandAction::Send, None),
         KeyBinding::new("escape", CommandAction::Cancel, None),
+        // Redis console (bound on the input so they win over its own bindings)
+        KeyBinding::new("tab", ConsoleAction::Complete, Some(CONSOLE_INPUT_CONTEXT)),
+        KeyBinding::new(
+            "up",
+            ConsoleAction::HistoryPrevious,
+            Some(CONSOLE_INPUT_CONTEXT),
+        ),
+        KeyBinding::new(
+            "down",
+            ConsoleAction::HistoryNext,
+            Some(CONSOLE_INPUT_CONTEXT),
+        ),
     ];
 
     #[cfg(target_os = "macos")]
@@ -60,6 +60,42 @@ fn copy_dir_files(src: &PathBuf, dst: &Path) -> Result<()> {
     Ok(())
 }
 
+/// New content written next to its target and moved over it by `commit`.
+///
+/// Staging several files first lets a caller replace all of them only once
+/// each one was written; an uncommitted file is removed on drop.
+pub struct StagedFile {
+    temp: PathBuf,
+    path: PathBuf,
+}
+
+impl StagedFile {
+    /// Write `content` to a temporary file beside `path`
+    pub fn write(path: PathBuf, content: &str) -> Result<Self> {
+        let mut temp = path.clone().into_os_string();
+        temp.push(".tmp");
+        let staged = Self {
+            temp: PathBuf::from(temp),
+            path,
+        };
+        fs::write(&staged.temp, content)?;
+        Ok(staged)
+    }
+
+    /// Replace the target with the staged content
+    pub fn commit(self) -> Result<()> {
+        fs::rename(&self.temp, &self.path)?;
+        Ok(())
+    }
+}
+
+impl Drop for StagedFile {
+    fn drop(&mut self) {
+        // Already gone after a commit
+        let _ = fs::remove_file(&self.temp);
+    }
+}
+
 /// Check if running from App Store (macOS)
 ///
 /// Detected by presence of `_MASReceipt/receipt` in app bundle.
@@ -237,3 +273,27 @@ pub fn get_app_bundle_path() -> Option<PathBuf> {
 
     Some(app_bundle.to_path_buf())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn staged_file_replaces_its_target_only_on_commit() {
+        let dir = std::env::temp_dir().join(format!("dfc-gui-staged-{}", std::process::id()));
+        fs::create_dir_all(&dir).expect("create temp dir");
+        let path = dir.join("state.toml");
+        fs::write(&path, "old").expect("write target");
+
+        let dropped = StagedFile::write(path.clone(), "discarded").expect("stage");
+        drop(dropped);
+        assert_eq!(fs::read_to_string(&path).expect("read"), "old");
+        assert!(!dir.join("state.toml.tmp").exists());
+
+        let staged = StagedFile::write(path.clone(), "new").expect("stage");
+        assert_eq!(fs::read_to_string(&path).expect("read"), "old");
+        staged.commit().expect("commit");
+        assert_eq!(fs::read_to_string(&path).expect("read"), "new");
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -9,6 +9,7 @@ mod fs;
 mod macos_menu;
 mod string;
 mod updater;
+mod vault;
 
 pub use action::*;
 pub use bounded::*;
@@ -17,3 +18,4 @@ pub use fs::*;
 pub use macos_menu::*;
 pub use string::*;
 pub use updater::*;
+pub use vault::*;
@@ -3,11 +3,14 @@
 //! This module provides utility functions for:
 //! - Case-insensitive substring matching
 //! - String comparison that prefers numeric ordering when both sides parse as u64
-//! - AES-256-GCM encryption and decryption for sensitive data (e.g., passwords)
+//! - Human-readable byte sizes
+//! - AES-256-GCM encryption and decryption for sensitive data (e.g., passwords),
+//!   keyed by the credential vault
 //! - Base64 encoding/decoding for storage and transport
 
 use std::cmp::Ordering;
 
+use super::vault_key;
 use crate::error::Error;
 use aes_gcm::{
     Aes256Gcm,
@@ -33,13 +36,67 @@ pub fn cmp_u64ish(a: &str, b: &str) -> Ordering {
     }
 }
 
-/// Master encryption key for AES-256-GCM cipher.
+/// Format a byte count with a binary unit, e.g. `1.5 MB`.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+
+    let mut value = bytes as f64;
+    let mut unit = 0usize;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{bytes} {}", UNITS[unit])
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+/// Built-in key of releases before the credential vault.
 ///
-/// WARNING: In production, this should be stored securely (e.g., keychain, env var)
-/// rather than hardcoded in the binary.
-const MASTER_KEY: &[u8; 32] = b"DfcGuiSecretKey2026GoldwindTeam!";
+/// Only decrypts entries without the `VAULT_CIPHER_PREFIX`; they are
+/// re-encrypted with the vault key on startup.
+const LEGACY_MASTER_KEY: &[u8; 32] = b"DfcGuiSecretKey2026GoldwindTeam!";
+
+/// Prefix of values encrypted with the vault key
+pub const VAULT_CIPHER_PREFIX: &str = "v2:";
+
+/// Encrypts a plaintext string using AES-256-GCM with the vault key.
+///
+/// Fails while the vault is locked.
+///
+/// # Storage Format
+/// `v2:` followed by the `encrypt_with` output
+pub fn encrypt(plain_text: &str) -> Result<String> {
+    encrypt_for_vault(&vault_key()?, plain_text)
+}
 
-/// Encrypts a plaintext string using AES-256-GCM encryption.
+/// Like `encrypt`, with a vault key that is not installed yet
+pub fn encrypt_for_vault(key: &[u8; 32], plain_text: &str) -> Result<String> {
+    Ok(format!(
+        "{VAULT_CIPHER_PREFIX}{}",
+        encrypt_with(key, plain_text)?
+    ))
+}
+
+/// Decrypts a value produced by `encrypt`, or by releases before the vault
+/// (no prefix, built-in key).
+pub fn decrypt(cipher_text: &str) -> Result<String> {
+    match cipher_text.strip_prefix(VAULT_CIPHER_PREFIX) {
+        Some(data) => decrypt_with(&vault_key()?, data),
+        None => decrypt_with(LEGACY_MASTER_KEY, cipher_text),
+    }
+}
+
+/// Whether a stored secret still needs re-encrypting with the vault key
+/// (written before the vault, or saved as plain text)
+pub fn is_legacy_cipher_text(value: &str) -> bool {
+    !value.is_empty() && !value.starts_with(VAULT_CIPHER_PREFIX)
+}
+
+/// Encrypts a plaintext string using AES-256-GCM encryption with `key`.
 ///
 /// The encrypted data is encoded as Base64 for easy storage and transport.
 /// Each encryption uses a randomly generated nonce for security.
@@ -54,13 +111,14 @@ const MASTER_KEY: &[u8; 32] = b"DfcGuiSecretKey2026GoldwindTeam!";
 /// The output Base64 string contains: `[nonce (12 bytes)][ciphertext (variable)]`
 ///
 /// # Arguments
+/// * `key` - The 256-bit key
 /// * `plain_text` - The plaintext string to encrypt
 ///
 /// # Returns
 /// A Base64-encoded string containing the nonce and ciphertext
-pub fn encrypt(plain_text: &str) -> Result<String> {
-    // Initialize AES-256-GCM cipher with master key
-    let cipher = Aes256Gcm::new(MASTER_KEY.into());
+pub fn encrypt_with(key: &[u8; 32], plain_text: &str) -> Result<String> {
+    // Initialize AES-256-GCM cipher with the key
+    let cipher = Aes256Gcm::new(key.into());
 
     // Generate a random 96-bit nonce (number used once)
     let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
@@ -82,15 +140,16 @@ pub fn encrypt(plain_text: &str) -> Result<String> {
 
 /// Decrypts a Base64-encoded ciphertext encrypted with AES-256-GCM.
 ///
-/// Expects the input to be in the format produced by `encrypt()`:
+/// Expects the input to be in the format produced by `encrypt_with()`:
 /// `[nonce (12 bytes)][ciphertext (variable)]` encoded as Base64.
 ///
 /// # Arguments
+/// * `key` - The 256-bit key
 /// * `cipher_text` - Base64-encoded string containing nonce and ciphertext
 ///
 /// # Returns
 /// The decrypted plaintext string
-pub fn decrypt(cipher_text: &str) -> Result<String> {
+pub fn decrypt_with(key: &[u8; 32], cipher_text: &str) -> Result<String> {
     // Decode from Base64
     let data = BASE64.decode(cipher_text).map_err(|e| Error::Invalid {
         message: format!("Base64 decode failed: {e}"),
@@ -103,8 +162,8 @@ pub fn decrypt(cipher_text: &str) -> Result<String> {
         });
     }
 
-    // Initialize cipher with master key
-    let cipher = Aes256Gcm::new(MASTER_KEY.into());
+    // Initialize cipher with the key
+    let cipher = Aes256Gcm::new(key.into());
 
     // Extract nonce from first 12 bytes
     let nonce_bytes = &data[0..12];
@@ -129,9 +188,11 @@ pub fn decrypt(cipher_text: &str) -> Result<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::helpers::init_test_vault;
 
     #[test]
     fn test_encrypt_decrypt() {
+        init_test_vault();
         let original = "my_secret_password";
         let encrypted = encrypt(original).expect("Encryption failed");
         let decrypted = decrypt(&encrypted).expect("Decryption failed");
@@ -140,6 +201,7 @@ mod tests {
 
     #[test]
     fn test_encrypt_produces_different_ciphertext() {
+        init_test_vault();
         let original = "test";
         let encrypted1 = encrypt(original).expect("Encryption failed");
         let encrypted2 = encrypt(original).expect("Encryption failed");
@@ -147,6 +209,19 @@ mod tests {
         assert_ne!(encrypted1, encrypted2);
     }
 
+    #[test]
+    fn test_decrypt_legacy_cipher_text() {
+        init_test_vault();
+        let legacy = encrypt_with(LEGACY_MASTER_KEY, "old_password").expect("Encryption failed");
+        assert!(is_legacy_cipher_text(&legacy));
+        assert_eq!(decrypt(&legacy).expect("Decryption failed"), "old_password");
+
+        let migrated = encrypt("old_password").expect("Encryption failed");
+        assert!(migrated.starts_with(VAULT_CIPHER_PREFIX));
+        assert!(!is_legacy_cipher_text(&migrated));
+        assert!(decrypt_with(LEGACY_MASTER_KEY, &migrated[VAULT_CIPHER_PREFIX.len()..]).is_err());
+    }
+
     #[test]
     fn test_decrypt_invalid_base64() {
         let result = decrypt("not_valid_base64!!!");
@@ -0,0 +1,433 @@
+//! Credential Vault
+//!
+//! Key that encrypts the passwords and tokens stored in the config files.
+//! By default a random key lives in `vault.key` next to the configs
+//! (readable by the owner only). With a master passphrase the key is derived
+//! with Argon2id instead, `vault.toml` holds the salt and a check value, and
+//! the vault starts locked until the passphrase is entered.
+
+use crate::error::{Error, Result};
+use aes_gcm::{
+    Aes256Gcm,
+    aead::{KeyInit, OsRng, rand_core::RngCore},
+};
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, RwLock};
+
+use super::{decrypt_with, encrypt_with, get_or_create_config_dir};
+
+/// Vault key length (AES-256)
+pub const VAULT_KEY_LEN: usize = 32;
+/// Shortest passphrase accepted when one is chosen in the UI
+pub const MIN_PASSPHRASE_LEN: usize = 8;
+
+/// Random key file (no passphrase)
+const KEY_FILE: &str = "vault.key";
+/// Passphrase settings file
+const PASSPHRASE_FILE: &str = "vault.toml";
+/// Plaintext of the check value that verifies a passphrase
+const CHECK_PLAINTEXT: &str = "dfc-gui-vault";
+const SALT_LEN: usize = 16;
+
+/// Where the vault key comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VaultMode {
+    /// Random key in `vault.key`
+    KeyFile,
+    /// Key derived from a master passphrase
+    Passphrase,
+}
+
+/// Argon2id settings of a passphrase-derived key, with a check value that
+/// tells a wrong passphrase apart. Stored as `vault.toml` and in encrypted
+/// server exports.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PassphraseKey {
+    /// Argon2 salt (Base64)
+    salt: String,
+    /// Argon2 memory cost in KiB
+    m_cost: u32,
+    /// Argon2 iterations
+    t_cost: u32,
+    /// Argon2 parallelism
+    p_cost: u32,
+    /// `CHECK_PLAINTEXT` encrypted with the derived key
+    check: String,
+}
+
+impl PassphraseKey {
+    /// Settings with a fresh salt for `passphrase`, and the derived key
+    pub fn generate(passphrase: &str) -> Result<(Self, [u8; VAULT_KEY_LEN])> {
+        if passphrase.is_empty() {
+            return Err(Error::Invalid {
+                message: "The passphrase must not be empty".to_string(),
+            });
+        }
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let mut settings = Self {
+            salt: BASE64.encode(salt),
+            m_cost: Params::DEFAULT_M_COST,
+            t_cost: Params::DEFAULT_T_COST,
+            p_cost: Params::DEFAULT_P_COST,
+            check: String::new(),
+        };
+        let key = settings.derive_key(passphrase)?;
+        settings.check = encrypt_with(&key, CHECK_PLAINTEXT)?;
+        Ok((settings, key))
+    }
+
+    /// Key derived from `passphrase`; fails on a wrong passphrase
+    pub fn unlock(&self, passphrase: &str) -> Result<[u8; VAULT_KEY_LEN]> {
+        let key = self.derive_key(passphrase)?;
+        match decrypt_with(&key, &self.check) {
+            Ok(check) if check == CHECK_PLAINTEXT => Ok(key),
+            _ => Err(Error::Invalid {
+                message: "Wrong passphrase".to_string(),
+            }),
+        }
+    }
+
+    fn derive_key(&self, passphrase: &str) -> Result<[u8; VAULT_KEY_LEN]> {
+        let salt = BASE64.decode(&self.salt).map_err(|e| Error::Invalid {
+            message: format!("Invalid vault salt: {e}"),
+        })?;
+        let params = Params::new(self.m_cost, self.t_cost, self.p_cost, Some(VAULT_KEY_LEN))
+            .map_err(|e| Error::Invalid {
+                message: format!("Invalid vault key derivation parameters: {e}"),
+            })?;
+        let mut key = [0u8; VAULT_KEY_LEN];
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+            .map_err(|e| Error::Invalid {
+                message: format!("Vault key derivation failed: {e}"),
+            })?;
+        Ok(key)
+    }
+}
+
+/// Vault key and the files it is kept in
+pub struct Vault {
+    dir: PathBuf,
+    mode: VaultMode,
+    /// `None` while locked
+    key: Option<[u8; VAULT_KEY_LEN]>,
+}
+
+impl Vault {
+    /// Open the vault in `dir`, creating a random key file on first use.
+    /// A passphrase vault opens locked.
+    pub fn open(dir: &Path) -> Result<Self> {
+        let dir = dir.to_path_buf();
+        if dir.join(PASSPHRASE_FILE).exists() {
+            return Ok(Self {
+                dir,
+                mode: VaultMode::Passphrase,
+                key: None,
+            });
+        }
+
+        let key_path = dir.join(KEY_FILE);
+        let key = if key_path.exists() {
+            let encoded = std::fs::read_to_string(&key_path)?;
+            let bytes = BASE64.decode(encoded.trim()).map_err(|e| Error::Invalid {
+                message: format!("Invalid vault key file {}: {e}", key_path.display()),
+            })?;
+            <[u8; VAULT_KEY_LEN]>::try_from(bytes.as_slice()).map_err(|_| Error::Invalid {
+                message: format!("Invalid vault key length in {}", key_path.display()),
+            })?
+        } else {
+            let key = random_key();
+            write_private(&key_path, &BASE64.encode(key))?;
+            tracing::info!(path = %key_path.display(), "Created credential vault key");
+            key
+        };
+        Ok(Self {
+            dir,
+            mode: VaultMode::KeyFile,
+            key: Some(key),
+        })
+    }
+
+    pub fn mode(&self) -> VaultMode {
+        self.mode
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.key.is_none()
+    }
+
+    /// Key for encrypting and decrypting secrets
+    pub fn key(&self) -> Result<[u8; VAULT_KEY_LEN]> {
+        self.key.ok_or_else(|| Error::Invalid {
+            message: "The credential vault is locked; unlock it in Settings".to_string(),
+        })
+    }
+
+    /// Derive the key from `passphrase`; fails on a wrong passphrase
+    pub fn unlock(&mut self, passphrase: &str) -> Result<()> {
+        if self.mode != VaultMode::Passphrase {
+            return Ok(());
+        }
+        let content = std::fs::read_to_string(self.dir.join(PASSPHRASE_FILE))?;
+        let settings: PassphraseKey = toml::from_str(&content)?;
+        self.key = Some(settings.unlock(passphrase)?);
+        Ok(())
+    }
+
+    /// Forget the key of a passphrase vault (a key file vault stays open)
+    pub fn lock(&mut self) {
+        if self.mode == VaultMode::Passphrase {
+            self.key = None;
+        }
+    }
+
+    /// Generate a key derived from `passphrase`, or a random key file key with
+    /// `None`, without touching the vault files. Secrets encrypted with the
+    /// current key must be re-encrypted with it before `install_key`.
+    pub fn new_key(&self, passphrase: Option<&str>) -> Result<NewVaultKey> {
+        self.key()?;
+        match passphrase {
+            Some(passphrase) => {
+                let (settings, key) = PassphraseKey::generate(passphrase)?;
+                Ok(NewVaultKey {
+                    settings: Some(settings),
+                    key,
+                })
+            }
+            None => Ok(NewVaultKey {
+                settings: None,
+                key: random_key(),
+            }),
+        }
+    }
+
+    /// Write a key from `new_key` to the vault files and start using it
+    pub fn install_key(&mut self, new_key: NewVaultKey) -> Result<()> {
+        self.key()?;
+        let key_path = self.dir.join(KEY_FILE);
+        let passphrase_path = self.dir.join(PASSPHRASE_FILE);
+        match new_key.settings {
+            Some(settings) => {
+                write_private(&passphrase_path, &toml::to_string_pretty(&settings)?)?;
+                if key_path.exists() {
+                    std::fs::remove_file(&key_path)?;
+                }
+                self.mode = VaultMode::Passphrase;
+            }
+            None => {
+                write_private(&key_path, &BASE64.encode(new_key.key))?;
+                if passphrase_path.exists() {
+                    std::fs::remove_file(&passphrase_path)?;
+                }
+                self.mode = VaultMode::KeyFile;
+            }
+        }
+        self.key = Some(new_key.key);
+        Ok(())
+    }
+}
+
+/// Key generated by `Vault::new_key`, not installed yet
+pub struct NewVaultKey {
+    /// Passphrase settings; `None` for a random key file
+    settings: Option<PassphraseKey>,
+    key: [u8; VAULT_KEY_LEN],
+}
+
+impl NewVaultKey {
+    pub fn key(&self) -> &[u8; VAULT_KEY_LEN] {
+        &self.key
+    }
+}
+
+fn random_key() -> [u8; VAULT_KEY_LEN] {
+    Aes256Gcm::generate_key(&mut OsRng).into()
+}
+
+/// Write `content` to `path`, readable and writable by the owner only
+pub fn write_private(path: &Path, content: &str) -> Result<()> {
+    #[cfg(unix)]
+    {
+        use std::io::Write;
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+        let mut file = std::fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(path)?;
+        // `mode` only applies to new files
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+        file.write_all(content.as_bytes())?;
+    }
+    #[cfg(not(unix))]
+    std::fs::write(path, content)?;
+    Ok(())
+}
+
+/// Vault of the running app; `None` until `init_vault`
+static VAULT: LazyLock<RwLock<Option<Vault>>> = LazyLock::new(|| RwLock::new(None));
+
+/// Open the vault in the config directory
+pub fn init_vault() -> Result<()> {
+    let vault = Vault::open(&get_or_create_config_dir()?)?;
+    tracing::info!(mode = ?vault.mode(), locked = vault.is_locked(), "Opened credential vault");
+    if let Ok(mut guard) = VAULT.write() {
+        *guard = Some(vault);
+    }
+    Ok(())
+}
+
+fn with_vault<T>(f: impl FnOnce(&mut Vault) -> Result<T>) -> Result<T> {
+    let mut guard = VAULT.write().map_err(|_| Error::Invalid {
+        message: "Credential vault unavailable".to_string(),
+    })?;
+    let vault = guard.as_mut().ok_or_else(|| Error::Invalid {
+        message: "Credential vault not initialized".to_string(),
+    })?;
+    f(vault)
+}
+
+/// Key of the app vault
+pub fn vault_key() -> Result<[u8; VAULT_KEY_LEN]> {
+    with_vault(|vault| vault.key())
+}
+
+/// Mode of the app vault; `None` before `init_vault`
+pub fn vault_mode() -> Option<VaultMode> {
+    with_vault(|vault| Ok(vault.mode())).ok()
+}
+
+pub fn vault_locked() -> bool {
+    with_vault(|vault| Ok(vault.is_locked())).unwrap_or(true)
+}
+
+pub fn unlock_vault(passphrase: &str) -> Result<()> {
+    with_vault(|vault| vault.unlock(passphrase))
+}
+
+pub fn lock_vault() {
+    let _ = with_vault(|vault| {
+        vault.lock();
+        Ok(())
+    });
+}
+
+/// See `Vault::new_key`
+pub fn new_vault_key(passphrase: Option<&str>) -> Result<NewVaultKey> {
+    with_vault(|vault| vault.new_key(passphrase))
+}
+
+/// See `Vault::install_key`
+pub fn install_vault_key(new_key: NewVaultKey) -> Result<()> {
+    with_vault(|vault| vault.install_key(new_key))
+}
+
+/// Install an unlocked vault in a temporary directory
+#[cfg(test)]
+pub(crate) fn init_test_vault() {
+    static INIT: std::sync::Once = std::sync::Once::new();
+    INIT.call_once(|| {
+        let dir = std::env::temp_dir().join(format!("dfc-gui-test-vault-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("create test vault dir");
+        let vault = Vault::open(&dir).expect("open test vault");
+        if let Ok(mut guard) = VAULT.write() {
+            *guard = Some(vault);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("dfc-gui-vault-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).expect("create temp dir");
+        dir
+    }
+
+    #[test]
+    fn key_file_vault_keeps_its_key() {
+        let dir = temp_dir("key-file");
+        let vault = Vault::open(&dir).expect("open");
+        assert_eq!(vault.mode(), VaultMode::KeyFile);
+        let key = vault.key().expect("unlocked");
+
+        let reopened = Vault::open(&dir).expect("reopen");
+        assert_eq!(reopened.key().expect("unlocked"), key);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(dir.join(KEY_FILE))
+                .expect("key file")
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn passphrase_vault_locks_and_unlocks() {
+        let dir = temp_dir("passphrase");
+        let mut vault = Vault::open(&dir).expect("open");
+        vault
+            .new_key(Some("correct horse"))
+            .and_then(|key| vault.install_key(key))
+            .expect("set");
+        assert_eq!(vault.mode(), VaultMode::Passphrase);
+        assert!(!dir.join(KEY_FILE).exists());
+        let key = vault.key().expect("unlocked");
+
+        let mut reopened = Vault::open(&dir).expect("reopen");
+        assert!(reopened.is_locked());
+        assert!(reopened.key().is_err());
+        assert!(reopened.unlock("wrong").is_err());
+        reopened.unlock("correct horse").expect("unlock");
+        assert_eq!(reopened.key().expect("unlocked"), key);
+
+        reopened.lock();
+        assert!(reopened.is_locked());
+        assert!(reopened.new_key(None).is_err());
+
+        reopened.unlock("correct horse").expect("unlock");
+        reopened
+            .new_key(None)
+            .and_then(|key| reopened.install_key(key))
+            .expect("remove passphrase");
+        assert_eq!(reopened.mode(), VaultMode::KeyFile);
+        assert!(!dir.join(PASSPHRASE_FILE).exists());
+        assert!(!Vault::open(&dir).expect("reopen").is_locked());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn new_key_changes_nothing_until_installed() {
+        let dir = temp_dir("new-key");
+        let mut vault = Vault::open(&dir).expect("open");
+        let old_key = vault.key().expect("unlocked");
+
+        let new_key = vault.new_key(Some("staple")).expect("new key");
+        assert_eq!(vault.mode(), VaultMode::KeyFile);
+        assert_eq!(vault.key().expect("unlocked"), old_key);
+        assert!(!dir.join(PASSPHRASE_FILE).exists());
+        assert_eq!(
+            Vault::open(&dir).expect("reopen").key().expect("unlocked"),
+            old_key
+        );
+
+        let installed = *new_key.key();
+        vault.install_key(new_key).expect("install");
+        assert_eq!(vault.mode(), VaultMode::Passphrase);
+        assert_eq!(vault.key().expect("unlocked"), installed);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -6,8 +6,9 @@
 
 use crate::helpers::{
     DeviceAction, MenuAction, ServerAction, WindowAction, get_or_create_log_dir,
-    handle_window_action, install_application_icon, install_native_window_menu_shortcuts,
-    is_app_store_build, is_development, new_key_bindings, supports_auto_update,
+    handle_window_action, init_vault, install_application_icon,
+    install_native_window_menu_shortcuts, is_app_store_build, is_development, new_key_bindings,
+    supports_auto_update,
 };
 use crate::services::ServiceHub;
 use crate::states::{
@@ -266,6 +267,16 @@ impl Render for DfcApp {
                             content.reconnect_server(&server_id, cx);
                         });
                     }
+                    ServerAction::Diagnose => {
+                        this.content.update(cx, |content, cx| {
+                            content.diagnose_server(window, cx, &server_id);
+                        });
+                    }
+                    ServerAction::PromoteCredential => {
+                        this.content.update(cx, |content, cx| {
+                            content.promote_credential(window, cx, &server_id);
+                        });
+                    }
                 }
             }))
     }
@@ -329,6 +340,11 @@ fn main() {
 
     let app = Application::new().with_assets(assets::Assets);
 
+    // Open the credential vault before anything decrypts stored secrets
+    if let Err(e) = init_vault() {
+        error!(error = %e, "Failed to open the credential vault");
+    }
+
     // Load or create app state
     let mut app_state = DfcAppState::try_load().unwrap_or_else(|e| {
         error!(error = %e, "Failed to load app state, using default");
@@ -337,12 +353,14 @@ fn main() {
 
     // Load server configurations
     app_state.load_servers();
+    app_state.migrate_legacy_secrets();
 
     // Create service hub
     let services = ServiceHub::with_defaults().unwrap_or_else(|e| {
         error!(error = %e, "Failed to create service hub");
         panic!("Cannot start without service hub");
     });
+    services.proxies().set_global(app_state.proxy());
 
     app.run(move |cx| {
         // Initialize GPUI components
@@ -0,0 +1,131 @@
+//! Redis Write Audit Log
+//!
+//! Appends one JSON line per write issued from the key browser, so changes made
+//! to shared servers can be traced afterwards.
+
+use crate::connection::{RedisWriteOp, RedisWritePreview};
+use crate::error::Result;
+use crate::helpers::get_or_create_data_dir;
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// File name of the audit log inside the data directory
+const AUDIT_LOG_FILE: &str = "redis-audit.jsonl";
+
+/// One recorded write
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AuditEntry {
+    /// Local time of the write (RFC 3339)
+    pub timestamp: String,
+    pub server_id: String,
+    pub server_name: String,
+    pub key: String,
+    /// Executed command line
+    pub command: String,
+    pub before: String,
+    pub after: String,
+    /// Whether the server accepted the write
+    pub success: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl AuditEntry {
+    /// Build an entry for an executed write
+    pub fn new(
+        server_id: &str,
+        server_name: &str,
+        op: &RedisWriteOp,
+        preview: &RedisWritePreview,
+        result: std::result::Result<(), String>,
+    ) -> Self {
+        Self {
+            timestamp: chrono::Local::now().to_rfc3339(),
+            server_id: server_id.to_string(),
+            server_name: server_name.to_string(),
+            key: op.key().to_string(),
+            command: op.command_line(),
+            before: preview.before.clone(),
+            after: preview.after.clone(),
+            success: result.is_ok(),
+            error: result.err(),
+        }
+    }
+}
+
+/// Append-only JSON-lines audit log
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    path: PathBuf,
+}
+
+impl AuditLog {
+    /// Audit log in the application data directory
+    pub fn open_default() -> Result<Self> {
+        Ok(Self {
+            path: get_or_create_data_dir()?.join(AUDIT_LOG_FILE),
+        })
+    }
+
+    /// Append an entry as one JSON line
+    pub fn append(&self, entry: &AuditEntry) -> Result<()> {
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        file.write_all(line.as_bytes())?;
+        Ok(())
+    }
+
+    /// Record an entry, logging instead of failing if the file is not writable
+    pub fn record(entry: &AuditEntry) {
+        let result = Self::open_default().and_then(|log| log.append(entry));
+        if let Err(e) = result {
+            tracing::error!(key = %entry.key, "Failed to write audit log: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn append_writes_one_json_line_per_entry() {
+        let path = std::env::temp_dir().join(format!(
+            "dfc-gui-audit-{}-{}.jsonl",
+            std::process::id(),
+            chrono::Local::now()
+                .timestamp_nanos_opt()
+                .unwrap_or_default()
+        ));
+        let log = AuditLog { path: path.clone() };
+        let op = RedisWriteOp::Del {
+            key: "cfg:1".to_string(),
+        };
+        let preview = RedisWritePreview {
+            before: "cfg:1".to_string(),
+            after: "(nil)".to_string(),
+        };
+
+        let ok = AuditEntry::new("s1", "Farm A", &op, &preview, Ok(()));
+        let failed = AuditEntry::new("s1", "Farm A", &op, &preview, Err("READONLY".to_string()));
+        log.append(&ok).expect("append entry");
+        log.append(&failed).expect("append entry");
+
+        let content = std::fs::read_to_string(&path).expect("read audit log");
+        let _ = std::fs::remove_file(&path);
+        let entries: Vec<AuditEntry> = content
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("parse entry"))
+            .collect();
+
+        assert_eq!(entries, vec![ok, failed]);
+        assert_eq!(entries[0].command, "DEL cfg:1");
+        assert_eq!(entries[1].error.as_deref(), Some("READONLY"));
+    }
+}
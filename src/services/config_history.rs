@@ -0,0 +1,164 @@
+//! Config Snapshot History
+//!
+//! Stores every load of the CMC iothub configs as a timestamped JSON file per
+//! server, so configuration drift on site can be traced back afterwards.
+
+use crate::connection::ConfigSnapshot;
+use crate::error::Result;
+use crate::helpers::get_or_create_data_dir;
+use std::path::PathBuf;
+
+/// Directory of the snapshot history inside the data directory
+const CONFIG_HISTORY_DIR: &str = "config-snapshots";
+/// Snapshots kept per server; older ones are pruned on save
+const CONFIG_SNAPSHOT_LIMIT: usize = 100;
+
+/// Per-server directory of config snapshots
+#[derive(Debug, Clone)]
+pub struct ConfigHistory {
+    dir: PathBuf,
+}
+
+impl ConfigHistory {
+    /// Snapshot history in the application data directory
+    pub fn open_default() -> Result<Self> {
+        Ok(Self {
+            dir: get_or_create_data_dir()?.join(CONFIG_HISTORY_DIR),
+        })
+    }
+
+    /// Snapshot IDs of a server, newest first
+    pub fn list(&self, server_id: &str) -> Result<Vec<String>> {
+        let dir = self.server_dir(server_id);
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut ids: Vec<String> = std::fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                name.strip_suffix(".json").map(str::to_string)
+            })
+            .collect();
+        // IDs are sortable local timestamps
+        ids.sort_unstable_by(|a, b| b.cmp(a));
+        Ok(ids)
+    }
+
+    /// Load one snapshot
+    pub fn load(&self, server_id: &str, id: &str) -> Result<ConfigSnapshot> {
+        let content =
+            std::fs::read_to_string(self.server_dir(server_id).join(format!("{id}.json")))?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Save a snapshot unless its configs equal the latest one; returns the
+    /// new snapshot ID
+    pub fn save(&self, snapshot: &ConfigSnapshot) -> Result<Option<String>> {
+        let ids = self.list(&snapshot.server_id)?;
+        if let Some(latest) = ids.first() {
+            let unchanged = self
+                .load(&snapshot.server_id, latest)
+                .is_ok_and(|latest| latest.configs == snapshot.configs);
+            if unchanged {
+                return Ok(None);
+            }
+        }
+
+        let dir = self.server_dir(&snapshot.server_id);
+        std::fs::create_dir_all(&dir)?;
+        let id = chrono::Local::now().format("%Y%m%dT%H%M%S%.3f").to_string();
+        std::fs::write(
+            dir.join(format!("{id}.json")),
+            serde_json::to_string(snapshot)?,
+        )?;
+
+        for old in ids.iter().skip(CONFIG_SNAPSHOT_LIMIT - 1) {
+            let _ = std::fs::remove_file(dir.join(format!("{old}.json")));
+        }
+        Ok(Some(id))
+    }
+
+    /// Save a snapshot, logging instead of failing if the directory is not writable
+    pub fn record(snapshot: &ConfigSnapshot) {
+        let result = Self::open_default().and_then(|history| history.save(snapshot));
+        match result {
+            Ok(Some(id)) => {
+                tracing::info!(server_id = %snapshot.server_id, id, "Saved config snapshot");
+            }
+            Ok(None) => {}
+            Err(e) => {
+                tracing::error!(
+                    server_id = %snapshot.server_id,
+                    "Failed to save config snapshot: {}",
+                    e
+                );
+            }
+        }
+    }
+
+    /// Readable label of a snapshot ID, e.g. `2026-10-18 09:30:12`
+    pub fn label(id: &str) -> String {
+        chrono::NaiveDateTime::parse_from_str(id, "%Y%m%dT%H%M%S%.3f")
+            .map(|time| time.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|_| id.to_string())
+    }
+
+    fn server_dir(&self, server_id: &str) -> PathBuf {
+        let name: String = server_id
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        self.dir.join(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connection::ConfigItem;
+
+    #[test]
+    fn save_skips_unchanged_configs_and_lists_newest_first() {
+        let dir = std::env::temp_dir().join(format!(
+            "dfc-gui-config-history-{}-{}",
+            std::process::id(),
+            chrono::Local::now()
+                .timestamp_nanos_opt()
+                .unwrap_or_default()
+        ));
+        let history = ConfigHistory { dir: dir.clone() };
+        let config = |url: &str| ConfigItem {
+            group_id: 0,
+            service_url: url.to_string(),
+            source: "CMC_a".to_string(),
+            details: Vec::new(),
+            topic_agents: Vec::new(),
+        };
+
+        let first = ConfigSnapshot::new("s1", "Farm A", vec![config("pulsar://a:6650")]);
+        let first_id = history.save(&first).expect("save first");
+        let unchanged = history.save(&first).expect("save unchanged");
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        let second = ConfigSnapshot::new("s1", "Farm A", vec![config("pulsar://b:6650")]);
+        let second_id = history.save(&second).expect("save second");
+
+        let ids = history.list("s1").expect("list snapshots");
+        let loaded = history.load("s1", &ids[0]).expect("load latest");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert!(first_id.is_some());
+        assert_eq!(unchanged, None);
+        assert_eq!(
+            ids,
+            vec![second_id.expect("second id"), first_id.expect("first id")]
+        );
+        assert_eq!(loaded, second);
+    }
+}
@@ -3,10 +3,11 @@
 //! Central orchestrator for all services. Handles initialization, lifecycle,
 //! and provides a unified API for the state layer.
 
+use crate::connection::WritePermit;
 use crate::error::Result;
 use crate::services::{
-    DeviceId, DeviceMeta, PulsarBus, PulsarConfig, RedisConfig, RedisRepo, RetryConfig,
-    ServiceEvent, Supervisor, generate_correlation_id,
+    DeviceId, DeviceMeta, ProxyForwards, PulsarBus, PulsarConfig, RedisConfig, RedisRepo,
+    RetryConfig, ServiceEvent, SshTunnels, Supervisor, generate_correlation_id,
 };
 use crossbeam_channel::{Receiver, Sender};
 use std::sync::Arc;
@@ -28,6 +29,10 @@ pub struct ServiceHub {
     redis: Arc<RedisRepo>,
     /// Pulsar bus for event streaming
     pulsar: Arc<PulsarBus>,
+    /// SSH tunnels of servers behind a jump host
+    ssh_tunnels: Arc<SshTunnels>,
+    /// Global proxy setting and proxy forwards
+    proxies: Arc<ProxyForwards>,
     /// Redis connection supervisor
     redis_supervisor: Arc<Supervisor>,
     /// Pulsar connection supervisor
@@ -49,12 +54,20 @@ impl ServiceHub {
             Arc::new(Supervisor::new("pulsar", config.retry.clone(), tx.clone()));
 
         // Create services
-        let redis = Arc::new(RedisRepo::new(&config.redis, tx.clone())?);
+        let proxies = Arc::new(ProxyForwards::new());
+        let ssh_tunnels = Arc::new(SshTunnels::new(proxies.clone()));
+        let redis = Arc::new(RedisRepo::new(
+            &config.redis,
+            ssh_tunnels.clone(),
+            tx.clone(),
+        )?);
         let pulsar = Arc::new(PulsarBus::new(&config.pulsar, tx.clone())?);
 
         Ok(Self {
             redis,
             pulsar,
+            ssh_tunnels,
+            proxies,
             redis_supervisor,
             pulsar_supervisor,
             tx,
@@ -108,7 +121,15 @@ impl ServiceHub {
     /// Send a command to a device
     ///
     /// Returns a correlation ID that can be used to track the command response.
-    pub fn send_command(&self, device: &DeviceId, method: &str, params: &str) -> Result<Arc<str>> {
+    /// The permit must have been issued for the connected server.
+    pub fn send_command(
+        &self,
+        permit: &WritePermit,
+        device: &DeviceId,
+        method: &str,
+        params: &str,
+    ) -> Result<Arc<str>> {
+        self.redis.check_write_permit(permit)?;
         let correlation_id = generate_correlation_id();
         self.pulsar
             .send_command(device, method, params, &correlation_id)?;
@@ -144,6 +165,16 @@ impl ServiceHub {
         &self.redis
     }
 
+    /// Get SSH tunnels reference
+    pub fn ssh_tunnels(&self) -> &Arc<SshTunnels> {
+        &self.ssh_tunnels
+    }
+
+    /// Get proxies reference
+    pub fn proxies(&self) -> &Arc<ProxyForwards> {
+        &self.proxies
+    }
+
     // ==================== Event Emission (for testing) ====================
 
     /// Emit a service event (mainly for testing)
@@ -158,6 +189,8 @@ impl Clone for ServiceHub {
         Self {
             redis: self.redis.clone(),
             pulsar: self.pulsar.clone(),
+            ssh_tunnels: self.ssh_tunnels.clone(),
+            proxies: self.proxies.clone(),
             redis_supervisor: self.redis_supervisor.clone(),
             pulsar_supervisor: self.pulsar_supervisor.clone(),
             tx: self.tx.clone(),
@@ -185,4 +218,18 @@ mod tests {
         let hub = ServiceHub::with_defaults().expect("Failed to create hub");
         assert!(!hub.events().is_empty() || hub.events().is_empty()); // Just check it works
     }
+
+    #[test]
+    fn send_command_needs_a_connected_server_matching_the_permit() {
+        let hub = ServiceHub::with_defaults().expect("Failed to create hub");
+        let permit = crate::connection::DfcServerConfig {
+            id: "server-a".to_string(),
+            safety: crate::connection::SafetyLevel::Unrestricted,
+            ..Default::default()
+        }
+        .authorize_write(None)
+        .expect("unrestricted");
+        let device = DeviceId::from("dev-1");
+        assert!(hub.send_command(&permit, &device, "Start", "{}").is_err());
+    }
 }
@@ -21,16 +21,25 @@
 //! └─────────────────────────────────────────────────────────────┘
 //! ```
 
+mod audit_log;
+mod config_history;
 mod events;
 mod hub;
+mod proxy;
 mod pulsar_bus;
 mod redis_repo;
+mod redis_tls;
 mod runtime;
+mod ssh_tunnel;
 mod supervisor;
 
+pub use audit_log::*;
+pub use config_history::*;
 pub use events::*;
 pub use hub::*;
+pub use proxy::*;
 pub use pulsar_bus::*;
 pub use redis_repo::*;
 pub use runtime::*;
+pub use ssh_tunnel::*;
 pub use supervisor::*;
@@ -0,0 +1,242 @@
+//! Proxies
+//!
+//! Local port forwards through a SOCKS5 or HTTP CONNECT proxy, for clients
+//! (fred, Pulsar) that cannot dial through a proxy themselves. A forward
+//! listens on `127.0.0.1` and opens one proxied connection per accepted
+//! connection. Forwards are kept for the whole session so reconnecting
+//! clients find their local port again.
+
+use crate::connection::{
+    DfcServerConfig, ProxyConfig, ProxyKind, http_connect_request, socks5_auth_request,
+    socks5_connect_request, socks5_greeting,
+};
+use crate::error::{Error, Result};
+use std::collections::HashMap;
+use std::sync::{Mutex, RwLock};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Timeout for reaching the target through the proxy
+const PROXY_CONNECT_TIMEOUT_SECS: u64 = 20;
+/// Upper bound of an HTTP CONNECT response header
+const HTTP_CONNECT_MAX_HEADER: usize = 8 * 1024;
+
+/// Global proxy setting and the proxy forwards of all servers
+pub struct ProxyForwards {
+    global: RwLock<Option<ProxyConfig>>,
+    /// Local port of each (proxy, remote address)
+    forwards: Mutex<HashMap<(ProxyConfig, String, u16), u16>>,
+}
+
+impl Default for ProxyForwards {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProxyForwards {
+    pub fn new() -> Self {
+        Self {
+            global: RwLock::new(None),
+            forwards: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Replace the global proxy (decrypted); `None` or an empty URL disables it
+    pub fn set_global(&self, proxy: Option<ProxyConfig>) {
+        let proxy = proxy.filter(|proxy| !proxy.url.trim().is_empty());
+        if let Ok(mut global) = self.global.write() {
+            *global = proxy;
+        }
+    }
+
+    /// Current global proxy
+    pub fn global(&self) -> Option<ProxyConfig> {
+        self.global.read().ok().and_then(|global| global.clone())
+    }
+
+    /// Proxy used to reach `host` for `server`, if any
+    pub fn proxy_for(&self, server: &DfcServerConfig, host: &str) -> Option<ProxyConfig> {
+        let global = self.global();
+        server.effective_proxy(global.as_ref(), host).cloned()
+    }
+
+    /// Local address carrying `host:port` for `server`; the address itself
+    /// when no proxy applies
+    pub async fn forward(
+        &self,
+        server: &DfcServerConfig,
+        host: &str,
+        port: u16,
+    ) -> Result<(String, u16)> {
+        let Some(proxy) = self.proxy_for(server, host) else {
+            return Ok((host.to_string(), port));
+        };
+        let key = (proxy.clone(), host.to_string(), port);
+        if let Some(local_port) = self.forwards.lock().ok().and_then(|f| f.get(&key).copied()) {
+            return Ok(("127.0.0.1".to_string(), local_port));
+        }
+
+        let host = host.to_string();
+        let local_port = super::run_in_tokio(async move {
+            // Surface proxy and auth errors to the caller right away
+            drop(connect_via(&proxy, &host, port).await?);
+            listen(proxy, host, port).await
+        })
+        .await?;
+        if let Ok(mut forwards) = self.forwards.lock() {
+            forwards.insert(key, local_port);
+        }
+        Ok(("127.0.0.1".to_string(), local_port))
+    }
+}
+
+/// Local port forwarding to `host:port` through `proxy`
+async fn listen(proxy: ProxyConfig, host: String, port: u16) -> Result<u16> {
+    let listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+    let local_port = listener.local_addr()?.port();
+    tracing::info!(
+        proxy = %proxy.url,
+        local_port,
+        remote = %format!("{host}:{port}"),
+        "Started proxy port forward"
+    );
+
+    super::spawn_named_in_tokio("proxy-forward", async move {
+        loop {
+            let mut local = match listener.accept().await {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    tracing::warn!("Proxy forward accept failed: {}", e);
+                    continue;
+                }
+            };
+            let proxy = proxy.clone();
+            let host = host.clone();
+            tokio::spawn(async move {
+                let piped = async {
+                    let mut remote = connect_via(&proxy, &host, port).await?;
+                    tokio::io::copy_bidirectional(&mut local, &mut remote).await?;
+                    Ok::<_, Error>(())
+                };
+                if let Err(e) = piped.await {
+                    tracing::warn!(
+                        remote = %format!("{host}:{port}"),
+                        "Proxied connection failed: {}",
+                        e
+                    );
+                }
+            });
+        }
+    });
+    Ok(local_port)
+}
+
+/// TCP connection to `host:port` through `proxy`
+pub async fn connect_via(proxy: &ProxyConfig, host: &str, port: u16) -> Result<TcpStream> {
+    let (kind, proxy_host, proxy_port) = proxy.endpoint()?;
+    let connect = async {
+        let mut stream = TcpStream::connect((proxy_host.as_str(), proxy_port)).await?;
+        match kind {
+            ProxyKind::Socks5 => socks5_handshake(&mut stream, proxy, host, port).await?,
+            ProxyKind::Http => http_connect(&mut stream, proxy, host, port).await?,
+        }
+        Ok::<_, Error>(stream)
+    };
+    tokio::time::timeout(Duration::from_secs(PROXY_CONNECT_TIMEOUT_SECS), connect)
+        .await
+        .unwrap_or_else(|_| {
+            Err(Error::Connection {
+                message: format!("Proxy connect to {host}:{port} timed out"),
+            })
+        })
+}
+
+fn proxy_error(message: impl Into<String>) -> Error {
+    Error::Connection {
+        message: format!("Proxy: {}", message.into()),
+    }
+}
+
+async fn socks5_handshake(
+    stream: &mut TcpStream,
+    proxy: &ProxyConfig,
+    host: &str,
+    port: u16,
+) -> Result<()> {
+    stream.write_all(&socks5_greeting(proxy)).await?;
+    let mut choice = [0u8; 2];
+    stream.read_exact(&mut choice).await?;
+    match choice {
+        [0x05, 0x00] => {}
+        [0x05, 0x02] => {
+            stream.write_all(&socks5_auth_request(proxy)?).await?;
+            let mut status = [0u8; 2];
+            stream.read_exact(&mut status).await?;
+            if status[1] != 0x00 {
+                return Err(proxy_error("SOCKS5 authentication failed"));
+            }
+        }
+        [0x05, 0xFF] => return Err(proxy_error("no acceptable SOCKS5 authentication method")),
+        _ => return Err(proxy_error("not a SOCKS5 proxy")),
+    }
+
+    stream
+        .write_all(&socks5_connect_request(host, port)?)
+        .await?;
+    let mut reply = [0u8; 4];
+    stream.read_exact(&mut reply).await?;
+    if reply[1] != 0x00 {
+        let reason = match reply[1] {
+            0x02 => "connection not allowed by ruleset",
+            0x03 => "network unreachable",
+            0x04 => "host unreachable",
+            0x05 => "connection refused",
+            0x06 => "TTL expired",
+            _ => "general failure",
+        };
+        return Err(proxy_error(format!(
+            "SOCKS5 connect to {host}:{port} failed: {reason}"
+        )));
+    }
+    // Skip the bound address
+    let address_len = match reply[3] {
+        0x01 => 4,
+        0x04 => 16,
+        0x03 => usize::from(stream.read_u8().await?),
+        _ => return Err(proxy_error("invalid SOCKS5 reply")),
+    };
+    let mut bound = vec![0u8; address_len + 2];
+    stream.read_exact(&mut bound).await?;
+    Ok(())
+}
+
+async fn http_connect(
+    stream: &mut TcpStream,
+    proxy: &ProxyConfig,
+    host: &str,
+    port: u16,
+) -> Result<()> {
+    stream
+        .write_all(http_connect_request(proxy, host, port).as_bytes())
+        .await?;
+    // Read byte by byte so no tunneled data is consumed
+    let mut header = Vec::new();
+    while !header.ends_with(b"\r\n\r\n") {
+        if header.len() >= HTTP_CONNECT_MAX_HEADER {
+            return Err(proxy_error("HTTP CONNECT response too large"));
+        }
+        header.push(stream.read_u8().await?);
+    }
+    let header = String::from_utf8_lossy(&header);
+    let status_line = header.lines().next().unwrap_or_default();
+    let status = status_line.split_whitespace().nth(1).unwrap_or_default();
+    if status.starts_with('2') {
+        Ok(())
+    } else {
+        Err(proxy_error(format!(
+            "HTTP CONNECT to {host}:{port} rejected: {status_line}"
+        )))
+    }
+}
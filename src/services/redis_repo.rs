@@ -4,8 +4,16 @@
 //! stored in Redis. Handles one-time queries and caching.
 
 use crate::connection::{
-    ConfigItem, DetailItem, DfcServerConfig, PresetCredential, REDIS_KEY_PATTERNS, RedisKeyItem,
-    RedisKeyType, RedisKeyValue, TopicAgentItem, TopicDetail,
+    CONFIG_WATCH_POLL_SECS, CONFIG_WATCH_RESYNC_SECS, CompareValue, ConfigFingerprint, ConfigItem,
+    ConfigWatchChange, ConfigWatchEvent, ConfigWatchMode, DetailItem, DfcServerConfig,
+    DiagnosticReport, KEY_COMPARE_MAX_ELEMENTS, KEY_COMPARE_MAX_KEYS, KeyComparison, KeySample,
+    PresetCredential, REDIS_KEY_PATTERNS, RedisClusterNode, RedisDashboard, RedisKeyItem,
+    RedisKeyType, RedisKeyValue, RedisReply, RedisServerStats, RedisSlowlogEntry, RedisStreamEntry,
+    RedisStreamGroup, RedisStreamInfo, RedisStreamPendingEntry, RedisStreamValue, RedisValueCursor,
+    RedisValuePage, RedisWriteOp, TopicAgentItem, TopicDetail, ValueMatcher, ValueSearchBatch,
+    ValueSearchMatch, WorkingCredential, WritePermit, config_watch_patterns, credential_attempts,
+    diff_values, fingerprint_change, is_write_command, keyspace_channel_key,
+    keyspace_notifications_enabled, parse_client_list, parse_cluster_nodes, parse_info,
 };
 use crate::error::{Error, Result};
 use crate::services::events::{DeviceId, DeviceMeta};
@@ -13,21 +21,56 @@ use crossbeam_channel::Sender;
 use fred::clients::Client as FredClient;
 use fred::prelude::*;
 use fred::types::CustomCommand;
-use fred::types::config::Config as FredConfig;
+use fred::types::Message;
+use fred::types::config::{Config as FredConfig, Server};
+use std::collections::{HashMap, HashSet};
 use std::future::Future;
-use std::sync::Arc;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, broadcast, watch};
+
+use super::{ServiceEvent, SshTunnels};
+
+/// Where and how to reach the Redis server of a config
+#[derive(Clone)]
+struct RedisTarget {
+    host: String,
+    port: u16,
+    db: u8,
+    /// Sentinel master name and Sentinel nodes
+    sentinel: Option<(String, Vec<(String, u16)>)>,
+    tls: Option<fred::types::config::TlsConfig>,
+    /// TLS server name when `host` is a local forward
+    tls_server_name: Option<String>,
+    /// Whether the connection runs through an SSH tunnel or proxy
+    tunneled: bool,
+}
 
-use super::ServiceEvent;
+impl RedisTarget {
+    fn from_server(server: &DfcServerConfig) -> Result<Self> {
+        Ok(Self {
+            host: server.host.trim().to_string(),
+            port: server.port,
+            db: server.db,
+            sentinel: server.sentinel()?,
+            tls: super::redis_tls::redis_tls_config(server)?,
+            tls_server_name: None,
+            tunneled: false,
+        })
+    }
+}
 
 struct ActiveRedisClient {
     client: FredClient,
+    /// Server the client is connected to; write permits are checked against it
+    server: DfcServerConfig,
 }
 
 impl ActiveRedisClient {
-    fn new(client: FredClient) -> Self {
-        Self { client }
+    fn new(client: FredClient, server: DfcServerConfig) -> Self {
+        Self { client, server }
     }
 }
 
@@ -61,17 +104,44 @@ pub struct RedisRepo {
     tx: Sender<ServiceEvent>,
     /// Redis client instance
     client: Arc<RwLock<Option<Arc<ActiveRedisClient>>>>,
+    /// Bumped when the IMID->IMR mapping changed on the server
+    imid2imr_revision: watch::Sender<u64>,
+    /// SSH tunnels and proxies carrying connections of routed servers
+    ssh_tunnels: Arc<SshTunnels>,
+    /// Credential that last connected, per server ID
+    working_credentials: Mutex<HashMap<String, WorkingCredential>>,
 }
 
 impl RedisRepo {
     const RETIRED_CLIENT_MAX_WAIT_SECS: u64 = 60;
+    /// Number of stream entries fetched per XRANGE/XREVRANGE page
+    pub const STREAM_PAGE_SIZE: usize = 100;
+    /// Number of pending entries listed per consumer group
+    const STREAM_PENDING_LIMIT: usize = 100;
+    /// Number of hash/list/set/zset elements fetched per page
+    pub const VALUE_PAGE_SIZE: usize = 100;
+    /// Upper bound of HSCAN/SSCAN/ZSCAN round trips spent filling one page
+    const VALUE_SCAN_MAX_ROUNDS: usize = 16;
+    /// Keys asked for per SCAN round trip when fingerprinting config keys
+    const CONFIG_WATCH_SCAN_COUNT: usize = 500;
+    /// Elements searched per collection key by the value search
+    const VALUE_SEARCH_MAX_ELEMENTS: usize = 10_000;
+    /// Elements fetched per round trip by the value search
+    const VALUE_SEARCH_CHUNK: usize = 500;
 
     /// Create a new Redis repository
-    pub fn new(config: &RedisConfig, tx: Sender<ServiceEvent>) -> Result<Self> {
+    pub fn new(
+        config: &RedisConfig,
+        ssh_tunnels: Arc<SshTunnels>,
+        tx: Sender<ServiceEvent>,
+    ) -> Result<Self> {
         Ok(Self {
             config: config.clone(),
             tx,
             client: Arc::new(RwLock::new(None)),
+            imid2imr_revision: watch::channel(0).0,
+            ssh_tunnels,
+            working_credentials: Mutex::new(HashMap::new()),
         })
     }
 
@@ -91,11 +161,15 @@ impl RedisRepo {
 
     /// Connect to a specific server configuration, trying preset credentials if needed.
     /// Auto-detects cluster mode on the first successful connection and reconnects if needed.
+    ///
+    /// `remembered` is the credential that worked last time, tried first.
+    /// Returns the credential that worked.
     pub async fn connect_to_server(
         &self,
         server: &DfcServerConfig,
         preset_credentials: &[PresetCredential],
-    ) -> Result<()> {
+        remembered: Option<&WorkingCredential>,
+    ) -> Result<WorkingCredential> {
         tracing::info!(
             "Connecting to Redis server: {} ({}:{})",
             server.name,
@@ -103,72 +177,344 @@ impl RedisRepo {
             server.port
         );
 
-        let mut last_error = None;
-
-        // Try server's own password first
-        if let Some(ref pwd) = server.password {
-            if !pwd.is_empty() {
-                match self
-                    .try_connect(&server.host, server.port, None, Some(pwd))
-                    .await
-                {
-                    Ok(client) => {
-                        return self
-                            .store_connected_client(client, &server.name, "server password")
-                            .await;
-                    }
-                    Err(e) => {
-                        tracing::debug!("Auth attempt failed (server password): {}", e);
-                        last_error = Some(e);
-                    }
+        // The app state drops credentials that went stale with the presets;
+        // forget them here as well
+        match remembered {
+            Some(remembered) => self.remember_credential(&server.id, remembered.clone()),
+            None => {
+                if let Ok(mut cache) = self.working_credentials.lock() {
+                    cache.remove(&server.id);
                 }
             }
         }
+        match self
+            .connect_with_credentials(server, preset_credentials)
+            .await
+        {
+            Ok((client, credential)) => {
+                self.store_connected_client(client, server, &credential.label())
+                    .await?;
+                Ok(credential)
+            }
+            Err(err) => {
+                tracing::error!("Failed to connect to Redis: {}", err);
+                Err(err)
+            }
+        }
+    }
+
+    fn remember_credential(&self, server_id: &str, credential: WorkingCredential) {
+        if let Ok(mut cache) = self.working_credentials.lock() {
+            cache.insert(server_id.to_string(), credential);
+        }
+    }
+
+    /// Build a client for `server`, trying its own password, then each preset
+    /// credential, then no auth, starting with the credential that worked last.
+    /// Returns the client and the credential that worked.
+    async fn connect_with_credentials(
+        &self,
+        server: &DfcServerConfig,
+        preset_credentials: &[PresetCredential],
+    ) -> Result<(FredClient, WorkingCredential)> {
+        // Stored secrets are still cipher text while the vault is locked
+        if crate::helpers::vault_locked() {
+            return Err(Error::Invalid {
+                message: "The credential vault is locked; unlock it in Settings".to_string(),
+            });
+        }
+        let mut target = RedisTarget::from_server(server)?;
+        if target.db == 0 {
+            target.db = self.config.database;
+        }
+        if self.ssh_tunnels.routes(server, &target.host) {
+            // Sentinel hands out master addresses the forward does not cover
+            if target.sentinel.is_some() {
+                return Err(Error::Invalid {
+                    message: "Redis Sentinel is not supported through an SSH tunnel or proxy"
+                        .to_string(),
+                });
+            }
+            let (host, port) = self
+                .ssh_tunnels
+                .forward(server, &target.host, target.port)
+                .await?;
+            if target.tls.is_some() {
+                target.tls_server_name = Some(target.host.clone());
+            }
+            target.host = host;
+            target.port = port;
+            target.tunneled = true;
+        }
+        let remembered = self
+            .working_credentials
+            .lock()
+            .ok()
+            .and_then(|cache| cache.get(&server.id).cloned());
+        let mut last_error = None;
 
-        // Try each preset credential
-        for cred in preset_credentials {
+        for attempt in credential_attempts(
+            server.password.as_deref(),
+            preset_credentials,
+            remembered.as_ref(),
+        ) {
             match self
-                .try_connect(
-                    &server.host,
-                    server.port,
-                    cred.username.as_deref(),
-                    Some(&cred.password),
-                )
+                .try_connect(&target, attempt.username, attempt.password)
                 .await
             {
                 Ok(client) => {
-                    return self
-                        .store_connected_client(client, &server.name, "preset credential")
-                        .await;
+                    self.remember_credential(&server.id, attempt.credential.clone());
+                    return Ok((client, attempt.credential));
                 }
                 Err(e) => {
-                    tracing::debug!("Auth attempt failed (preset credential): {}", e);
+                    tracing::debug!(
+                        "Auth attempt failed ({}): {}",
+                        attempt.credential.label(),
+                        e
+                    );
                     last_error = Some(e);
                 }
             }
         }
 
-        // No-auth fallback
-        match self
-            .try_connect(&server.host, server.port, None, None)
-            .await
-        {
-            Ok(client) => {
-                return self
-                    .store_connected_client(client, &server.name, "no auth")
-                    .await;
+        Err(last_error.unwrap_or_else(|| Error::Connection {
+            message: format!("Failed to connect to {}", server.display_name()),
+        }))
+    }
+
+    /// Check every step of connecting to `server` with a throwaway client:
+    /// DNS, TCP reachability (or the SSH tunnel / proxy forward), each
+    /// credential of the ladder, INFO and cluster detection, and the config
+    /// keys. The active client is left alone. Returns the config items read
+    /// with the first working credential, for the Pulsar checks.
+    pub async fn diagnose(
+        &self,
+        server: &DfcServerConfig,
+        preset_credentials: &[PresetCredential],
+        report: &mut DiagnosticReport,
+    ) -> Vec<ConfigItem> {
+        let mut target = match RedisTarget::from_server(server) {
+            Ok(target) => target,
+            Err(err) => {
+                report.fail("Server settings", err.to_string());
+                return Vec::new();
             }
-            Err(e) => {
-                tracing::debug!("Auth attempt failed (no auth): {}", e);
-                last_error = Some(e);
+        };
+        if target.db == 0 {
+            target.db = self.config.database;
+        }
+        let mut endpoints = match &target.sentinel {
+            Some((_, nodes)) => nodes.clone(),
+            None => vec![(target.host.clone(), target.port)],
+        };
+        report.pass(
+            "Server settings",
+            format!(
+                "{} db {}{}{}",
+                endpoints
+                    .iter()
+                    .map(|(host, port)| format!("{host}:{port}"))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                target.db,
+                if target.sentinel.is_some() {
+                    " (Sentinel)"
+                } else {
+                    ""
+                },
+                if target.tls.is_some() { " (TLS)" } else { "" },
+            ),
+        );
+
+        if self.ssh_tunnels.routes(server, &target.host) {
+            report.skip("DNS resolution", "resolved by the SSH tunnel or proxy");
+            if target.sentinel.is_some() {
+                report.fail(
+                    "SSH tunnel / proxy",
+                    "Redis Sentinel is not supported through an SSH tunnel or proxy",
+                );
+                return Vec::new();
+            }
+            match self
+                .ssh_tunnels
+                .forward(server, &target.host, target.port)
+                .await
+            {
+                Ok((host, port)) => {
+                    report.pass(
+                        "SSH tunnel / proxy",
+                        format!("{}:{} forwarded to {host}:{port}", target.host, target.port),
+                    );
+                    if target.tls.is_some() {
+                        target.tls_server_name = Some(target.host.clone());
+                    }
+                    target.host = host.clone();
+                    target.port = port;
+                    target.tunneled = true;
+                    endpoints = vec![(host, port)];
+                }
+                Err(err) => {
+                    report.fail("SSH tunnel / proxy", err.to_string());
+                    return Vec::new();
+                }
+            }
+        } else {
+            for (host, port) in &endpoints {
+                let addr = format!("{host}:{port}");
+                let resolved = super::run_in_tokio(async move {
+                    tokio::net::lookup_host(addr)
+                        .await
+                        .map(|addrs| addrs.map(|addr| addr.ip().to_string()).collect::<Vec<_>>())
+                })
+                .await;
+                report.record(format!("DNS resolution {host}"), &resolved, |ips| {
+                    ips.join(", ")
+                });
             }
         }
 
-        let err = last_error.unwrap_or_else(|| Error::Connection {
-            message: format!("Failed to connect to {}", server.display_name()),
-        });
-        tracing::error!("Failed to connect to Redis: {}", err);
-        Err(err)
+        for (host, port) in &endpoints {
+            let addr = format!("{host}:{port}");
+            let reachable = super::run_in_tokio(async move {
+                let started = std::time::Instant::now();
+                match tokio::time::timeout(
+                    Duration::from_secs(5),
+                    tokio::net::TcpStream::connect(addr),
+                )
+                .await
+                {
+                    Ok(Ok(_)) => Ok(started.elapsed()),
+                    Ok(Err(err)) => Err(err.to_string()),
+                    Err(_) => Err("timed out after 5s".to_string()),
+                }
+            })
+            .await;
+            report.record(format!("TCP {host}:{port}"), &reachable, |elapsed| {
+                format!("connected in {} ms", elapsed.as_millis())
+            });
+        }
+
+        // Default order of `connect_with_credentials`; secrets stay out of the labels
+        let attempts = credential_attempts(server.password.as_deref(), preset_credentials, None);
+
+        let mut connected = None;
+        for attempt in attempts {
+            let (username, password) = (attempt.username, attempt.password);
+            let name = format!("Auth: {}", attempt.credential.label());
+            if connected.is_some() {
+                report.skip(name, "an earlier credential worked");
+                continue;
+            }
+            match self
+                .build_and_connect(&target, username, password, false)
+                .await
+            {
+                Ok(client) => {
+                    report.pass(name, "authenticated");
+                    connected = Some((client, username, password));
+                }
+                Err(err) => report.fail(name, err.to_string()),
+            }
+        }
+        let Some((mut client, username, password)) = connected else {
+            return Vec::new();
+        };
+
+        if target.sentinel.is_none() {
+            let info = {
+                let client = client.clone();
+                super::run_in_tokio(async move {
+                    let info_cmd = CustomCommand::new_static("INFO", None, false);
+                    client
+                        .custom::<String, _>(info_cmd, vec![Value::from("server")])
+                        .await
+                        .map_err(|e| e.to_string())
+                })
+                .await
+            };
+            let fields = info.as_deref().map(parse_info).unwrap_or_default();
+            let mode = fields.get("redis_mode").cloned().unwrap_or_default();
+            report.record("INFO server", &info, |_| {
+                format!(
+                    "redis_version {}, redis_mode {}",
+                    fields.get("redis_version").map_or("?", String::as_str),
+                    if mode.is_empty() { "?" } else { &mode },
+                )
+            });
+
+            if mode == "cluster" {
+                if target.tunneled {
+                    report.fail(
+                        "Cluster detection",
+                        "Redis Cluster is not supported through an SSH tunnel or proxy",
+                    );
+                } else {
+                    match self
+                        .build_and_connect(&target, username, password, true)
+                        .await
+                    {
+                        Ok(cluster_client) => {
+                            report.pass("Cluster detection", "reconnected in cluster mode");
+                            let previous = std::mem::replace(&mut client, cluster_client);
+                            super::run_in_tokio(Self::shutdown_client(previous, "diagnostics"))
+                                .await;
+                        }
+                        Err(err) => report.fail("Cluster detection", err.to_string()),
+                    }
+                }
+            } else {
+                report.pass("Cluster detection", "standalone");
+            }
+        }
+
+        let cfgid = server
+            .cfgid
+            .as_deref()
+            .map(str::trim)
+            .filter(|cfgid| !cfgid.is_empty())
+            .map(str::to_string);
+        let configs = {
+            let client = client.clone();
+            super::run_in_tokio(Self::load_configs(client, cfgid.clone())).await
+        };
+        let configs = match configs {
+            Ok(configs) if configs.is_empty() => {
+                report.fail(
+                    "Config keys",
+                    format!(
+                        "no keys match {}{}",
+                        REDIS_KEY_PATTERNS.join(", "),
+                        cfgid
+                            .map(|cfgid| format!(" for cfgid {cfgid}"))
+                            .unwrap_or_default()
+                    ),
+                );
+                Vec::new()
+            }
+            Ok(configs) => {
+                report.pass("Config keys", format!("{} config items", configs.len()));
+                configs
+            }
+            Err(err) => {
+                report.fail("Config keys", err.to_string());
+                Vec::new()
+            }
+        };
+
+        super::run_in_tokio(Self::shutdown_client(client, "diagnostics")).await;
+        configs
+    }
+
+    /// Fail unless `permit` was issued for the server the active client is
+    /// connected to; for write paths outside this repository
+    pub fn check_write_permit(&self, permit: &WritePermit) -> Result<()> {
+        let guard = self.client.try_read().map_err(|_| Error::Connection {
+            message: "The Redis connection is switching servers".to_string(),
+        })?;
+        let client_handle = guard.as_ref().ok_or_else(|| Error::Connection {
+            message: "Not connected to Redis".to_string(),
+        })?;
+        permit.check(&client_handle.server)
     }
 
     async fn current_client(&self) -> Result<Arc<ActiveRedisClient>> {
@@ -187,6 +533,28 @@ impl RedisRepo {
         // Clone the current client handle first so reconnects can swap in a new
         // client even if an older request is still blocked on network I/O.
         let client_handle = self.current_client().await?;
+        Self::run_with_client(client_handle, f).await
+    }
+
+    /// Like `with_connected_client` for a write; fails unless `permit` was
+    /// issued for the server the client is connected to
+    async fn with_write_client<F, Fut, T>(&self, permit: &WritePermit, f: F) -> Result<T>
+    where
+        F: FnOnce(FredClient) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<T>> + Send + 'static,
+        T: Send + 'static,
+    {
+        let client_handle = self.current_client().await?;
+        permit.check(&client_handle.server)?;
+        Self::run_with_client(client_handle, f).await
+    }
+
+    async fn run_with_client<F, Fut, T>(client_handle: Arc<ActiveRedisClient>, f: F) -> Result<T>
+    where
+        F: FnOnce(FredClient) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<T>> + Send + 'static,
+        T: Send + 'static,
+    {
         super::run_in_tokio(async move {
             let _client_handle = client_handle;
             let client = _client_handle.client.clone();
@@ -256,10 +624,11 @@ impl RedisRepo {
     async fn store_connected_client(
         &self,
         client: FredClient,
-        server_name: &str,
+        server: &DfcServerConfig,
         via: &str,
     ) -> Result<()> {
-        let next_client = Arc::new(ActiveRedisClient::new(client));
+        let server_name = server.name.as_str();
+        let next_client = Arc::new(ActiveRedisClient::new(client, server.clone()));
         let retire_wait_timeout = self.retired_client_max_wait();
         let previous_client = {
             let mut guard = self.client.write().await;
@@ -297,17 +666,21 @@ impl RedisRepo {
         Ok(())
     }
 
-    /// Connect in centralized mode, detect cluster via INFO, and reconnect in cluster mode if needed
+    /// Connect in centralized (or Sentinel) mode, detect cluster via INFO, and
+    /// reconnect in cluster mode if needed
     async fn try_connect(
         &self,
-        host: &str,
-        port: u16,
+        target: &RedisTarget,
         username: Option<&str>,
         password: Option<&str>,
     ) -> Result<FredClient> {
         let client = self
-            .build_and_connect(host, port, username, password, false)
+            .build_and_connect(target, username, password, false)
             .await?;
+        // Sentinel always hands out a standalone master
+        if target.sentinel.is_some() {
+            return Ok(client);
+        }
         let info: String = {
             let client = client.clone();
             super::run_in_tokio(async move {
@@ -321,6 +694,12 @@ impl RedisRepo {
         };
 
         if info.contains("redis_mode:cluster") {
+            if target.tunneled {
+                return Err(Error::Connection {
+                    message: "Redis Cluster is not supported through an SSH tunnel or proxy"
+                        .to_string(),
+                });
+            }
             tracing::info!("Detected Redis Cluster mode, reconnecting...");
             let client = client.clone();
             super::run_in_tokio(async move {
@@ -328,7 +707,7 @@ impl RedisRepo {
             })
             .await;
             return self
-                .build_and_connect(host, port, username, password, true)
+                .build_and_connect(target, username, password, true)
                 .await;
         }
 
@@ -338,13 +717,12 @@ impl RedisRepo {
     /// Build a fred client and connect
     async fn build_and_connect(
         &self,
-        host: &str,
-        port: u16,
+        target: &RedisTarget,
         username: Option<&str>,
         password: Option<&str>,
         clustered: bool,
     ) -> Result<FredClient> {
-        let host = host.to_string();
+        let target = target.clone();
         let username = username
             .filter(|value| !value.is_empty())
             .map(str::to_string);
@@ -353,18 +731,36 @@ impl RedisRepo {
             .map(str::to_string);
 
         super::run_in_tokio(async move {
-            let server = fred::prelude::Server::new(host, port);
+            let mut server = fred::prelude::Server::new(target.host, target.port);
+            if let Some(name) = target.tls_server_name {
+                server.tls_server_name = Some(name.into());
+            }
             let mut redis_config = FredConfig::default();
-            redis_config.server = if clustered {
-                ServerConfig::Clustered {
+            redis_config.server = match target.sentinel {
+                Some((service_name, nodes)) => ServerConfig::Sentinel {
+                    hosts: nodes
+                        .into_iter()
+                        .map(|(host, port)| fred::prelude::Server::new(host, port))
+                        .collect(),
+                    service_name,
+                },
+                None if clustered => ServerConfig::Clustered {
                     hosts: vec![server],
                     policy: fred::types::config::ClusterDiscoveryPolicy::ConfigEndpoint,
-                }
-            } else {
-                ServerConfig::Centralized { server }
+                },
+                None => ServerConfig::Centralized { server },
             };
+            // Cluster nodes only have database 0
+            if clustered {
+                if target.db != 0 {
+                    tracing::warn!(db = target.db, "Ignoring database index in cluster mode");
+                }
+            } else if target.db != 0 {
+                redis_config.database = Some(target.db);
+            }
             redis_config.username = username;
             redis_config.password = password;
+            redis_config.tls = target.tls;
 
             let client = Builder::from_config(redis_config)
                 .with_connection_config(|config| {
@@ -416,110 +812,109 @@ impl RedisRepo {
 
         tracing::debug!("Fetching configs from Redis, cfgid filter: {:?}", cfgid);
 
-        self.with_connected_client(move |client| async move {
-            let mut configs = Vec::new();
-            let mut group_id = 1;
+        self.with_connected_client(move |client| Self::load_configs(client, cfgid))
+            .await
+    }
 
-            for pattern in REDIS_KEY_PATTERNS {
-                let scan_pattern = if let Some(cfg) = cfgid.as_deref() {
-                    let wrapped_cfgid = if cfg.starts_with('{') && cfg.ends_with('}') {
-                        cfg.to_string()
-                    } else {
-                        format!("{{{}}}", cfg)
-                    };
-                    pattern.replace('*', &wrapped_cfgid)
+    /// Read the configuration items matching `REDIS_KEY_PATTERNS` through `client`
+    async fn load_configs(client: FredClient, cfgid: Option<String>) -> Result<Vec<ConfigItem>> {
+        let mut configs = Vec::new();
+        let mut group_id = 1;
+
+        for pattern in REDIS_KEY_PATTERNS {
+            let scan_pattern = if let Some(cfg) = cfgid.as_deref() {
+                let wrapped_cfgid = if cfg.starts_with('{') && cfg.ends_with('}') {
+                    cfg.to_string()
                 } else {
-                    pattern.to_string()
+                    format!("{{{}}}", cfg)
                 };
+                pattern.replace('*', &wrapped_cfgid)
+            } else {
+                pattern.to_string()
+            };
 
-                tracing::debug!("Scanning with pattern: {}", scan_pattern);
+            tracing::debug!("Scanning with pattern: {}", scan_pattern);
 
-                let cmd = CustomCommand::new_static("KEYS", None, false);
-                let keys_result: Value = client
-                    .custom(cmd, vec![Value::from(scan_pattern.clone())])
-                    .await
-                    .map_err(|e: fred::error::Error| {
-                        tracing::error!("Redis KEYS failed: {}", e);
-                        Error::Connection {
-                            message: e.to_string(),
-                        }
-                    })?;
+            let cmd = CustomCommand::new_static("KEYS", None, false);
+            let keys_result: Value = client
+                .custom(cmd, vec![Value::from(scan_pattern.clone())])
+                .await
+                .map_err(|e: fred::error::Error| {
+                    tracing::error!("Redis KEYS failed: {}", e);
+                    Error::Connection {
+                        message: e.to_string(),
+                    }
+                })?;
 
-                let keys: Vec<String> = match keys_result {
-                    Value::Array(arr) => arr.into_iter().filter_map(|v| v.into_string()).collect(),
-                    _ => vec![],
-                };
+            let keys: Vec<String> = match keys_result {
+                Value::Array(arr) => arr.into_iter().filter_map(|v| v.into_string()).collect(),
+                _ => vec![],
+            };
 
-                for key in keys {
-                    let Some(config_json) = Self::get_config_json(&client, &key).await else {
-                        continue;
-                    };
+            for key in keys {
+                let Some(config_json) = Self::get_config_json(&client, &key).await else {
+                    continue;
+                };
 
-                    let raw_value = match &config_json {
-                        serde_json::Value::String(s) => s.clone(),
-                        _ => config_json.to_string(),
-                    };
+                let raw_value = match &config_json {
+                    serde_json::Value::String(s) => s.clone(),
+                    _ => config_json.to_string(),
+                };
 
-                    let details = if Self::is_output_iothub_key(&key)
-                        || Self::is_input_iothub_key(&key)
-                        || Self::is_io_iothub_key(&key)
-                    {
-                        Vec::new()
-                    } else {
-                        Self::parse_config_value(&raw_value, group_id)
-                    };
+                let details = if Self::is_output_iothub_key(&key)
+                    || Self::is_input_iothub_key(&key)
+                    || Self::is_io_iothub_key(&key)
+                {
+                    Vec::new()
+                } else {
+                    Self::parse_config_value(&raw_value, group_id)
+                };
 
-                    let service_url = Self::extract_service_url_from_json(&config_json)
-                        .unwrap_or_else(|| Self::extract_service_url(&key, &raw_value));
-                    let cfgid = Self::extract_cfgid_from_key(&key);
-
-                    let topic_agents = if let Some(ref cfg) = cfgid {
-                        let app_id = Self::fetch_app_id(&client, cfg).await;
-                        let agent_ids = Self::fetch_topic_agent_ids(&client, cfg, &app_id).await;
-
-                        if Self::is_output_iothub_key(&key) {
-                            Self::build_output_iothub_topic_agents(
-                                &config_json,
-                                &agent_ids,
-                                group_id,
-                            )
-                        } else if Self::is_input_iothub_key(&key) {
-                            Self::build_input_iothub_topic_agents(
-                                &config_json,
-                                &agent_ids,
-                                &app_id,
-                                group_id,
-                            )
-                        } else if Self::is_io_iothub_key(&key) {
-                            Self::build_io_iothub_topic_agents(
-                                &config_json,
-                                &agent_ids,
-                                &app_id,
-                                group_id,
-                            )
-                        } else {
-                            Self::build_topic_agents_from_details(&agent_ids, &details, group_id)
-                        }
+                let service_url = Self::extract_service_url_from_json(&config_json)
+                    .unwrap_or_else(|| Self::extract_service_url(&key, &raw_value));
+                let cfgid = Self::extract_cfgid_from_key(&key);
+
+                let topic_agents = if let Some(ref cfg) = cfgid {
+                    let app_id = Self::fetch_app_id(&client, cfg).await;
+                    let agent_ids = Self::fetch_topic_agent_ids(&client, cfg, &app_id).await;
+
+                    if Self::is_output_iothub_key(&key) {
+                        Self::build_output_iothub_topic_agents(&config_json, &agent_ids, group_id)
+                    } else if Self::is_input_iothub_key(&key) {
+                        Self::build_input_iothub_topic_agents(
+                            &config_json,
+                            &agent_ids,
+                            &app_id,
+                            group_id,
+                        )
+                    } else if Self::is_io_iothub_key(&key) {
+                        Self::build_io_iothub_topic_agents(
+                            &config_json,
+                            &agent_ids,
+                            &app_id,
+                            group_id,
+                        )
                     } else {
-                        Vec::new()
-                    };
+                        Self::build_topic_agents_from_details(&agent_ids, &details, group_id)
+                    }
+                } else {
+                    Vec::new()
+                };
 
-                    configs.push(ConfigItem {
-                        group_id,
-                        service_url,
-                        source: key,
-                        details,
-                        topic_agents,
-                    });
+                configs.push(ConfigItem {
+                    group_id,
+                    service_url,
+                    source: key,
+                    details,
+                    topic_agents,
+                });
 
-                    group_id += 1;
-                }
+                group_id += 1;
             }
+        }
 
-            tracing::info!("Fetched {} config items from Redis", configs.len());
-            Ok(configs)
-        })
-        .await
+        tracing::info!("Fetched {} config items from Redis", configs.len());
+        Ok(configs)
     }
 
     fn is_output_iothub_key(key: &str) -> bool {
@@ -686,53 +1081,309 @@ impl RedisRepo {
         ids
     }
 
-    /// Load InfoModel property mapping (IMID -> IMR) from Redis.
-    ///
-    /// Aligns with DFC `Loader.LoadImid2Imr` logic:
-    /// - Key: `CMC_{cfgid}_sg.infomodel.property`
-    /// - JSON is an array of objects: `{ "uuid": "...", "props": [{ "imid": 1, "uuid": "..."}, ...] }`
-    /// - `uuid` is normalized by taking substring before first `_`.
-    pub async fn fetch_imid2imr(
-        &self,
-        cfgid: &str,
-    ) -> Result<std::collections::HashMap<(String, u32), String>> {
-        let wrapped = Self::wrap_cfgid(cfgid);
-        let key = format!("CMC_{}_sg.infomodel.property", wrapped);
+    /// Receiver that changes whenever the IMID->IMR mapping should be reloaded
+    pub fn imid2imr_revision(&self) -> watch::Receiver<u64> {
+        self.imid2imr_revision.subscribe()
+    }
 
-        self.with_connected_client(move |client| async move {
-            let Some(json) = Self::get_config_json(&client, &key).await else {
-                return Ok(std::collections::HashMap::new());
-            };
+    /// Tell running topic streams to reload the IMID->IMR mapping
+    pub fn invalidate_imid2imr(&self) {
+        self.imid2imr_revision
+            .send_modify(|revision| *revision = revision.wrapping_add(1));
+    }
 
-            let map = Self::parse_imid2imr_json(&json);
-            tracing::debug!(key = %key, count = map.len(), "Loaded IMID->IMR mapping");
-            Ok(map)
+    /// Read `notify-keyspace-events` of the active server
+    pub async fn keyspace_notification_flags(&self) -> Result<String> {
+        self.with_connected_client(move |client| async move {
+            let cmd = CustomCommand::new_static("CONFIG", None, false);
+            let reply: Value = client
+                .custom(
+                    cmd,
+                    vec![Value::from("GET"), Value::from("notify-keyspace-events")],
+                )
+                .await
+                .map_err(|e: fred::error::Error| Error::Command {
+                    message: e.to_string(),
+                })?;
+            Ok(Self::value_into_pairs(reply)
+                .into_iter()
+                .next()
+                .map(|(_, flags)| Self::value_to_string_lossy(flags))
+                .unwrap_or_default())
         })
         .await
     }
 
-    fn parse_imid2imr_json(
-        json: &serde_json::Value,
-    ) -> std::collections::HashMap<(String, u32), String> {
-        let mut map: std::collections::HashMap<(String, u32), String> =
-            std::collections::HashMap::new();
-        let mut unique_imid_map: std::collections::HashMap<u32, Option<String>> =
-            std::collections::HashMap::new();
+    /// Fingerprint every key matching `patterns`.
+    ///
+    /// Keys are listed with SCAN and hashed by the server with DEBUG
+    /// DIGEST-VALUE. Where DEBUG is disabled `digest_value` is cleared and the
+    /// DUMP payload is hashed instead.
+    pub async fn config_fingerprint(
+        &self,
+        patterns: Vec<String>,
+        digest_value: Arc<AtomicBool>,
+    ) -> Result<ConfigFingerprint> {
+        self.with_connected_client(move |client| async move {
+            let mut fingerprint = ConfigFingerprint::new();
+            for pattern in patterns {
+                let mut cursor = 0u64;
+                loop {
+                    let cmd = CustomCommand::new_static("SCAN", None, false);
+                    let args = vec![
+                        Value::from(cursor.to_string()),
+                        Value::from("MATCH"),
+                        Value::from(pattern.clone()),
+                        Value::from("COUNT"),
+                        Value::from(Self::CONFIG_WATCH_SCAN_COUNT.to_string()),
+                    ];
+                    let result: Value =
+                        client.custom(cmd, args).await.map_err(|e| Error::Command {
+                            message: e.to_string(),
+                        })?;
+                    let (next_cursor, items) = Self::parse_scan_reply(result);
+                    for key in items.into_iter().filter_map(|v| v.into_string()) {
+                        if let Some(hash) = Self::key_digest(&client, &key, &digest_value).await? {
+                            fingerprint.insert(key, hash);
+                        }
+                    }
+                    cursor = next_cursor;
+                    if cursor == 0 {
+                        break;
+                    }
+                }
+            }
+            Ok(fingerprint)
+        })
+        .await
+    }
 
-        for info in Self::value_as_array(json) {
-            let Some(uuid) = Self::get_json_string(info, "uuid") else {
-                continue;
-            };
-            let uuid_base = match uuid.split('_').next() {
-                Some(v) if !v.is_empty() => v.to_string(),
-                _ => uuid,
-            };
-            if uuid_base.is_empty() {
-                continue;
+    /// Hash of one key's value; `None` once the key is gone
+    async fn key_digest(
+        client: &FredClient,
+        key: &str,
+        digest_value: &AtomicBool,
+    ) -> Result<Option<u64>> {
+        if digest_value.load(Ordering::Relaxed) {
+            let cmd = CustomCommand::new_static("DEBUG", None, false);
+            let args = vec![Value::from("DIGEST-VALUE"), Value::from(key.to_string())];
+            match client.custom::<Value, _>(cmd, args).await {
+                Ok(reply) => {
+                    let digest = match reply {
+                        Value::Array(digests) => digests.into_iter().next(),
+                        other => Some(other),
+                    }
+                    .and_then(|v| v.into_string())
+                    // A missing key digests to all zeros
+                    .filter(|digest| digest.chars().any(|c| c != '0'));
+                    return Ok(digest.map(|digest| {
+                        let mut hasher = DefaultHasher::new();
+                        digest.hash(&mut hasher);
+                        hasher.finish()
+                    }));
+                }
+                Err(e) => {
+                    tracing::debug!("DEBUG DIGEST-VALUE unavailable, hashing DUMP: {}", e);
+                    digest_value.store(false, Ordering::Relaxed);
+                }
             }
+        }
+        let cmd = CustomCommand::new_static("DUMP", None, false);
+        let payload: Value = client
+            .custom(cmd, vec![Value::from(key.to_string())])
+            .await
+            .map_err(|e| Error::Command {
+                message: e.to_string(),
+            })?;
+        Ok(payload.as_bytes().map(|bytes| {
+            let mut hasher = DefaultHasher::new();
+            bytes.hash(&mut hasher);
+            hasher.finish()
+        }))
+    }
 
-            let Some(props) = info.get("props") else {
-                continue;
+    /// Watch the config keys of the active server until `stop` is set.
+    ///
+    /// Uses keyspace notifications on a dedicated connection to `server` when the
+    /// server publishes them, and polls key fingerprints otherwise. Must run on
+    /// the tokio runtime.
+    pub async fn watch_config_keys(
+        &self,
+        server: &DfcServerConfig,
+        preset_credentials: &[PresetCredential],
+        mut stop: watch::Receiver<bool>,
+        tx: Sender<ConfigWatchEvent>,
+    ) {
+        let patterns = config_watch_patterns(server.cfgid.as_deref());
+        let notifications = match self.keyspace_notification_flags().await {
+            Ok(flags) => keyspace_notifications_enabled(&flags),
+            Err(e) => {
+                tracing::debug!("Cannot read notify-keyspace-events: {}", e);
+                false
+            }
+        };
+        let subscriber = if notifications {
+            match self
+                .connect_with_credentials(server, preset_credentials)
+                .await
+            {
+                Ok((client, _)) => Some(client),
+                Err(e) => {
+                    tracing::warn!("Config watch subscriber connect failed: {}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        let mut messages = match &subscriber {
+            Some(client) => {
+                let rx = client.message_rx();
+                let channels: Vec<String> = patterns
+                    .iter()
+                    .map(|pattern| format!("__keyspace@*__:{pattern}"))
+                    .collect();
+                match client.psubscribe(channels).await {
+                    Ok(()) => Some(rx),
+                    Err(e) => {
+                        tracing::warn!("Config watch PSUBSCRIBE failed: {}", e);
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
+
+        let mode = if messages.is_some() {
+            ConfigWatchMode::Notifications
+        } else {
+            ConfigWatchMode::Polling
+        };
+        tracing::info!(server = %server.name, ?mode, "Watching config keys");
+        let _ = tx.send(ConfigWatchEvent::Mode(mode));
+
+        let digest_value = Arc::new(AtomicBool::new(true));
+        let mut fingerprint = self
+            .config_fingerprint(patterns.clone(), digest_value.clone())
+            .await
+            .unwrap_or_default();
+        let mut poll = Self::config_watch_interval(mode);
+        loop {
+            let change = tokio::select! {
+                changed = stop.changed() => {
+                    if changed.is_err() || *stop.borrow() {
+                        break;
+                    }
+                    continue;
+                }
+                _ = poll.tick() => {
+                    match self
+                        .config_fingerprint(patterns.clone(), digest_value.clone())
+                        .await
+                    {
+                        Ok(next) => {
+                            let change = fingerprint_change(&fingerprint, &next);
+                            fingerprint = next;
+                            change
+                        }
+                        Err(e) => {
+                            tracing::warn!("Config watch poll failed: {}", e);
+                            continue;
+                        }
+                    }
+                }
+                message = Self::next_keyspace_message(&mut messages) => match message {
+                    Ok(message) => keyspace_channel_key(&message.channel)
+                        .map(ConfigWatchChange::for_key)
+                        .unwrap_or_default(),
+                    Err(broadcast::error::RecvError::Lagged(_)) => ConfigWatchChange {
+                        configs: true,
+                        infomodel: true,
+                    },
+                    Err(broadcast::error::RecvError::Closed) => {
+                        tracing::warn!("Config watch subscriber closed; falling back to polling");
+                        messages = None;
+                        poll = Self::config_watch_interval(ConfigWatchMode::Polling);
+                        let _ = tx.send(ConfigWatchEvent::Mode(ConfigWatchMode::Polling));
+                        continue;
+                    }
+                }
+            };
+            if !change.is_empty() && tx.send(ConfigWatchEvent::Changed(change)).is_err() {
+                break;
+            }
+        }
+
+        if let Some(client) = subscriber {
+            Self::shutdown_client(client, "config watch").await;
+        }
+    }
+
+    fn config_watch_interval(mode: ConfigWatchMode) -> tokio::time::Interval {
+        let secs = match mode {
+            ConfigWatchMode::Notifications => CONFIG_WATCH_RESYNC_SECS,
+            ConfigWatchMode::Polling => CONFIG_WATCH_POLL_SECS,
+        };
+        let period = Duration::from_secs(secs);
+        tokio::time::interval_at(tokio::time::Instant::now() + period, period)
+    }
+
+    async fn next_keyspace_message(
+        messages: &mut Option<broadcast::Receiver<Message>>,
+    ) -> std::result::Result<Message, broadcast::error::RecvError> {
+        match messages {
+            Some(rx) => rx.recv().await,
+            None => std::future::pending().await,
+        }
+    }
+
+    /// Load InfoModel property mapping (IMID -> IMR) from Redis.
+    ///
+    /// Aligns with DFC `Loader.LoadImid2Imr` logic:
+    /// - Key: `CMC_{cfgid}_sg.infomodel.property`
+    /// - JSON is an array of objects: `{ "uuid": "...", "props": [{ "imid": 1, "uuid": "..."}, ...] }`
+    /// - `uuid` is normalized by taking substring before first `_`.
+    pub async fn fetch_imid2imr(
+        &self,
+        cfgid: &str,
+    ) -> Result<std::collections::HashMap<(String, u32), String>> {
+        let wrapped = Self::wrap_cfgid(cfgid);
+        let key = format!("CMC_{}_sg.infomodel.property", wrapped);
+
+        self.with_connected_client(move |client| async move {
+            let Some(json) = Self::get_config_json(&client, &key).await else {
+                return Ok(std::collections::HashMap::new());
+            };
+
+            let map = Self::parse_imid2imr_json(&json);
+            tracing::debug!(key = %key, count = map.len(), "Loaded IMID->IMR mapping");
+            Ok(map)
+        })
+        .await
+    }
+
+    fn parse_imid2imr_json(
+        json: &serde_json::Value,
+    ) -> std::collections::HashMap<(String, u32), String> {
+        let mut map: std::collections::HashMap<(String, u32), String> =
+            std::collections::HashMap::new();
+        let mut unique_imid_map: std::collections::HashMap<u32, Option<String>> =
+            std::collections::HashMap::new();
+
+        for info in Self::value_as_array(json) {
+            let Some(uuid) = Self::get_json_string(info, "uuid") else {
+                continue;
+            };
+            let uuid_base = match uuid.split('_').next() {
+                Some(v) if !v.is_empty() => v.to_string(),
+                _ => uuid,
+            };
+            if uuid_base.is_empty() {
+                continue;
+            }
+
+            let Some(props) = info.get("props") else {
+                continue;
             };
             for prop in Self::value_as_array(props) {
                 let Some(imr) = Self::get_json_string(prop, "uuid") else {
@@ -1457,6 +2108,41 @@ impl RedisRepo {
         .await
     }
 
+    /// Escape glob metacharacters so a key prefix matches literally in SCAN MATCH
+    pub fn escape_glob(text: &str) -> String {
+        let mut escaped = String::with_capacity(text.len());
+        for ch in text.chars() {
+            if matches!(ch, '*' | '?' | '[' | ']' | '\\') {
+                escaped.push('\\');
+            }
+            escaped.push(ch);
+        }
+        escaped
+    }
+
+    /// Scan keys below a namespace prefix (`SCAN MATCH prefix*`).
+    ///
+    /// A MATCH scan can return few keys per round, so up to `VALUE_SCAN_MAX_ROUNDS`
+    /// rounds are spent collecting about `count` keys.
+    pub async fn scan_prefix(
+        &self,
+        prefix: &str,
+        mut cursor: u64,
+        count: usize,
+    ) -> Result<(Vec<RedisKeyItem>, u64)> {
+        let pattern = format!("{}*", Self::escape_glob(prefix));
+        let mut keys = Vec::new();
+        for _ in 0..Self::VALUE_SCAN_MAX_ROUNDS {
+            let (page, next_cursor) = self.scan_keys(&pattern, cursor, count).await?;
+            keys.extend(page);
+            cursor = next_cursor;
+            if cursor == 0 || keys.len() >= count {
+                break;
+            }
+        }
+        Ok((keys, cursor))
+    }
+
     /// Get the type of a key
     async fn get_key_type_internal(client: &FredClient, key: &str) -> RedisKeyType {
         let cmd = CustomCommand::new_static("TYPE", None, false);
@@ -1494,8 +2180,8 @@ impl RedisRepo {
         .await
     }
 
-    /// Get a string value
-    pub async fn get_string(&self, key: &str) -> Result<String> {
+    /// Get a string value as raw bytes, keeping binary payloads intact
+    pub async fn get_string_bytes(&self, key: &str) -> Result<Vec<u8>> {
         let key = key.to_string();
         self.with_connected_client(move |client| async move {
             let cmd = CustomCommand::new_static("GET", None, false);
@@ -1506,37 +2192,14 @@ impl RedisRepo {
                     message: e.to_string(),
                 })?;
 
-            result.into_string().ok_or_else(|| Error::Parse {
-                message: "Failed to parse string value".to_string(),
-            })
-        })
-        .await
-    }
-
-    /// Get a hash value (all fields)
-    pub async fn get_hash(&self, key: &str) -> Result<Vec<(String, String)>> {
-        let key = key.to_string();
-        self.with_connected_client(move |client| async move {
-            let cmd = CustomCommand::new_static("HGETALL", None, false);
-            let result: Value = client
-                .custom(cmd, vec![Value::from(key)])
-                .await
-                .map_err(|e| Error::Connection {
-                    message: e.to_string(),
-                })?;
-
             match result {
-                Value::Array(arr) => {
-                    let mut pairs = Vec::new();
-                    let mut iter = arr.into_iter();
-                    while let (Some(k), Some(v)) = (iter.next(), iter.next()) {
-                        if let (Some(key_str), Some(val_str)) = (k.into_string(), v.into_string()) {
-                            pairs.push((key_str, val_str));
-                        }
-                    }
-                    Ok(pairs)
-                }
-                _ => Ok(vec![]),
+                Value::Bytes(bytes) => Ok(bytes.to_vec()),
+                other => other
+                    .into_string()
+                    .map(String::into_bytes)
+                    .ok_or_else(|| Error::Parse {
+                        message: "Failed to parse string value".to_string(),
+                    }),
             }
         })
         .await
@@ -1569,26 +2232,6 @@ impl RedisRepo {
         .await
     }
 
-    /// Get a set value (all members)
-    pub async fn get_set(&self, key: &str) -> Result<Vec<String>> {
-        let key = key.to_string();
-        self.with_connected_client(move |client| async move {
-            let cmd = CustomCommand::new_static("SMEMBERS", None, false);
-            let result: Value = client
-                .custom(cmd, vec![Value::from(key)])
-                .await
-                .map_err(|e| Error::Connection {
-                    message: e.to_string(),
-                })?;
-
-            match result {
-                Value::Array(arr) => Ok(arr.into_iter().filter_map(|v| v.into_string()).collect()),
-                _ => Ok(vec![]),
-            }
-        })
-        .await
-    }
-
     /// Get a sorted set value (with scores, range)
     pub async fn get_zset(&self, key: &str, start: i64, stop: i64) -> Result<Vec<(String, f64)>> {
         let key = key.to_string();
@@ -1630,94 +2273,1368 @@ impl RedisRepo {
         .await
     }
 
-    /// Get key value based on its type
-    pub async fn get_key_value(&self, key: &str) -> Result<RedisKeyValue> {
-        let key_type = self.get_key_type(key).await?;
-
-        match key_type {
-            RedisKeyType::String => {
-                let value = self.get_string(key).await?;
-                Ok(RedisKeyValue::String(value))
-            }
-            RedisKeyType::Hash => {
-                let value = self.get_hash(key).await?;
-                Ok(RedisKeyValue::Hash(value))
-            }
-            RedisKeyType::List => {
-                // Get first 100 elements
-                let value = self.get_list(key, 0, 99).await?;
-                Ok(RedisKeyValue::List(value))
-            }
-            RedisKeyType::Set => {
-                let value = self.get_set(key).await?;
-                Ok(RedisKeyValue::Set(value))
-            }
-            RedisKeyType::ZSet => {
-                // Get first 100 elements with scores
-                let value = self.get_zset(key, 0, 99).await?;
-                Ok(RedisKeyValue::ZSet(value))
-            }
-            _ => Ok(RedisKeyValue::Error(format!(
-                "Unsupported key type: {:?}",
-                key_type
-            ))),
-        }
-    }
-}
+    /// Get one page of stream entries.
+    ///
+    /// `after` is the ID of the last entry of the previous page; the next page starts
+    /// right after it. With `reverse` the stream is paged newest-first via XREVRANGE.
+    pub async fn get_stream_range(
+        &self,
+        key: &str,
+        after: Option<&str>,
+        count: usize,
+        reverse: bool,
+    ) -> Result<Vec<RedisStreamEntry>> {
+        let key = key.to_string();
+        let after = after.map(str::to_string);
+        self.with_connected_client(move |client| async move {
+            let (cmd_name, start, end) = if reverse {
+                let end = after.map_or_else(|| "+".to_string(), |id| format!("({id}"));
+                ("XREVRANGE", end, "-".to_string())
+            } else {
+                let start = after.map_or_else(|| "-".to_string(), |id| format!("({id}"));
+                ("XRANGE", start, "+".to_string())
+            };
+            let cmd = CustomCommand::new_static(cmd_name, None, false);
+            let result: Value = client
+                .custom(
+                    cmd,
+                    vec![
+                        Value::from(key),
+                        Value::from(start),
+                        Value::from(end),
+                        Value::from("COUNT"),
+                        Value::from(count.to_string()),
+                    ],
+                )
+                .await
+                .map_err(|e| Error::Connection {
+                    message: e.to_string(),
+                })?;
 
-impl std::fmt::Debug for RedisRepo {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("RedisRepo")
-            .field("config", &self.config)
-            .finish()
+            Ok(Self::parse_stream_entries(result))
+        })
+        .await
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use serde_json::json;
-    use std::sync::{
-        Arc,
-        atomic::{AtomicBool, Ordering},
-    };
-    use tokio::sync::oneshot;
-    use tokio::time::{Duration, sleep};
 
-    #[test]
-    fn parse_config_string_value_handles_json_and_plain() {
-        let parsed = RedisRepo::parse_config_string_value("{\"a\":1}");
-        assert!(parsed.is_object());
+    /// Get the stream summary (XINFO STREAM)
+    pub async fn get_stream_info(&self, key: &str) -> Result<RedisStreamInfo> {
+        let key = key.to_string();
+        self.with_connected_client(move |client| async move {
+            let cmd = CustomCommand::new_static("XINFO", None, false);
+            let result: Value = client
+                .custom(cmd, vec![Value::from("STREAM"), Value::from(key)])
+                .await
+                .map_err(|e| Error::Connection {
+                    message: e.to_string(),
+                })?;
 
-        let parsed = RedisRepo::parse_config_string_value("plain");
-        assert!(parsed.is_string());
+            Ok(Self::parse_stream_info(result))
+        })
+        .await
     }
 
-    #[test]
-    fn parse_list_values_parses_each_item() {
-        let value =
-            RedisRepo::parse_list_values(vec!["{\"a\":1}".to_string(), "plain".to_string()]);
-        let arr = value.as_array().expect("array");
-        assert!(arr[0].is_object());
-        assert!(arr[1].is_string());
-    }
+    /// Get the consumer groups of a stream (XINFO GROUPS)
+    pub async fn get_stream_groups(&self, key: &str) -> Result<Vec<RedisStreamGroup>> {
+        let key = key.to_string();
+        self.with_connected_client(move |client| async move {
+            let cmd = CustomCommand::new_static("XINFO", None, false);
+            let result: Value = client
+                .custom(cmd, vec![Value::from("GROUPS"), Value::from(key)])
+                .await
+                .map_err(|e| Error::Connection {
+                    message: e.to_string(),
+                })?;
 
-    #[test]
-    fn parse_hash_pairs_parses_values() {
-        let value = RedisRepo::parse_hash_pairs(vec![
-            ("a".to_string(), "{\"x\":1}".to_string()),
-            ("b".to_string(), "plain".to_string()),
-        ]);
-        let obj = value.as_object().expect("object");
-        assert!(obj.get("a").is_some_and(serde_json::Value::is_object));
-        assert!(obj.get("b").is_some_and(serde_json::Value::is_string));
+            Ok(result
+                .into_array()
+                .into_iter()
+                .map(Self::parse_stream_group)
+                .collect())
+        })
+        .await
     }
 
-    #[test]
-    fn parse_imid2imr_json_accepts_suffixed_uuid_and_string_imid() {
-        let value = json!([
-            {
-                "uuid": "705537041061273601_template",
+    /// Get the pending entries of a consumer group (XPENDING, extended form)
+    pub async fn get_stream_pending(
+        &self,
+        key: &str,
+        group: &str,
+        count: usize,
+    ) -> Result<Vec<RedisStreamPendingEntry>> {
+        let key = key.to_string();
+        let group = group.to_string();
+        self.with_connected_client(move |client| async move {
+            let cmd = CustomCommand::new_static("XPENDING", None, false);
+            let result: Value = client
+                .custom(
+                    cmd,
+                    vec![
+                        Value::from(key),
+                        Value::from(group),
+                        Value::from("-"),
+                        Value::from("+"),
+                        Value::from(count.to_string()),
+                    ],
+                )
+                .await
+                .map_err(|e| Error::Connection {
+                    message: e.to_string(),
+                })?;
+
+            Ok(Self::parse_stream_pending(result))
+        })
+        .await
+    }
+
+    /// Load a stream's summary, first page of entries, groups and pending lists
+    pub async fn get_stream(&self, key: &str, reverse: bool) -> Result<RedisStreamValue> {
+        let info = self.get_stream_info(key).await?;
+        let entries = self
+            .get_stream_range(key, None, Self::STREAM_PAGE_SIZE, reverse)
+            .await?;
+        let groups = self.get_stream_groups(key).await?;
+
+        let mut pending = Vec::with_capacity(groups.len());
+        for group in &groups {
+            if group.pending == 0 {
+                pending.push((group.name.clone(), Vec::new()));
+                continue;
+            }
+            let entries = self
+                .get_stream_pending(key, &group.name, Self::STREAM_PENDING_LIMIT)
+                .await?;
+            pending.push((group.name.clone(), entries));
+        }
+
+        Ok(RedisStreamValue {
+            info,
+            has_more: entries.len() >= Self::STREAM_PAGE_SIZE,
+            entries,
+            reverse,
+            groups,
+            pending,
+        })
+    }
+
+    fn value_to_string_lossy(value: Value) -> String {
+        match value {
+            Value::Null => String::new(),
+            other => other.into_string().unwrap_or_default(),
+        }
+    }
+
+    fn value_to_i64(value: &Value) -> Option<i64> {
+        match value {
+            Value::Integer(v) => Some(*v),
+            other => other.as_str().and_then(|s| s.trim().parse::<i64>().ok()),
+        }
+    }
+
+    /// Split a RESP2 flat `[k1, v1, k2, v2, ...]` reply (or a RESP3 map) into pairs
+    fn value_into_pairs(value: Value) -> Vec<(String, Value)> {
+        let mut pairs = Vec::new();
+        let mut iter = value.into_array().into_iter();
+        while let (Some(k), Some(v)) = (iter.next(), iter.next()) {
+            if let Some(key) = k.into_string() {
+                pairs.push((key, v));
+            }
+        }
+        pairs
+    }
+
+    fn parse_stream_entry(value: Value) -> Option<RedisStreamEntry> {
+        let mut parts = value.into_array().into_iter();
+        let id = parts.next()?.into_string()?;
+        let fields = match parts.next() {
+            Some(Value::Null) | None => Vec::new(),
+            Some(fields) => Self::value_into_pairs(fields)
+                .into_iter()
+                .map(|(field, value)| (field, Self::value_to_string_lossy(value)))
+                .collect(),
+        };
+        Some(RedisStreamEntry { id, fields })
+    }
+
+    fn parse_stream_entries(value: Value) -> Vec<RedisStreamEntry> {
+        match value {
+            Value::Array(arr) => arr
+                .into_iter()
+                .filter_map(Self::parse_stream_entry)
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn parse_stream_info(value: Value) -> RedisStreamInfo {
+        let mut info = RedisStreamInfo::default();
+        for (field, value) in Self::value_into_pairs(value) {
+            match field.as_str() {
+                "length" => info.length = Self::value_to_i64(&value).unwrap_or_default(),
+                "groups" => info.groups = Self::value_to_i64(&value).unwrap_or_default(),
+                "last-generated-id" => info.last_generated_id = Self::value_to_string_lossy(value),
+                "first-entry" => {
+                    info.first_entry_id = Self::parse_stream_entry(value).map(|entry| entry.id)
+                }
+                "last-entry" => {
+                    info.last_entry_id = Self::parse_stream_entry(value).map(|entry| entry.id)
+                }
+                _ => {}
+            }
+        }
+        info
+    }
+
+    fn parse_stream_group(value: Value) -> RedisStreamGroup {
+        let mut group = RedisStreamGroup::default();
+        for (field, value) in Self::value_into_pairs(value) {
+            match field.as_str() {
+                "name" => group.name = Self::value_to_string_lossy(value),
+                "consumers" => group.consumers = Self::value_to_i64(&value).unwrap_or_default(),
+                "pending" => group.pending = Self::value_to_i64(&value).unwrap_or_default(),
+                "last-delivered-id" => group.last_delivered_id = Self::value_to_string_lossy(value),
+                "lag" => group.lag = Self::value_to_i64(&value),
+                _ => {}
+            }
+        }
+        group
+    }
+
+    fn parse_stream_pending(value: Value) -> Vec<RedisStreamPendingEntry> {
+        value
+            .into_array()
+            .into_iter()
+            .filter_map(|item| {
+                let mut parts = item.into_array().into_iter();
+                let id = parts.next()?.into_string()?;
+                let consumer = parts.next().map(Self::value_to_string_lossy)?;
+                let idle_ms = parts
+                    .next()
+                    .and_then(|v| Self::value_to_i64(&v))
+                    .unwrap_or_default();
+                let delivery_count = parts
+                    .next()
+                    .and_then(|v| Self::value_to_i64(&v))
+                    .unwrap_or_default();
+                Some(RedisStreamPendingEntry {
+                    id,
+                    consumer,
+                    idle_ms,
+                    delivery_count,
+                })
+            })
+            .collect()
+    }
+
+    /// Split a SCAN-family reply `[cursor, [items...]]`
+    fn parse_scan_reply(value: Value) -> (u64, Vec<Value>) {
+        match value {
+            Value::Array(mut arr) if arr.len() >= 2 => {
+                let items = arr.remove(1).into_array();
+                let cursor = arr
+                    .remove(0)
+                    .into_string()
+                    .and_then(|s| s.parse::<u64>().ok())
+                    .unwrap_or(0);
+                (cursor, items)
+            }
+            _ => (0, Vec::new()),
+        }
+    }
+
+    fn pair_strings(items: Vec<Value>) -> Vec<(String, String)> {
+        let mut pairs = Vec::with_capacity(items.len() / 2);
+        let mut iter = items.into_iter();
+        while let (Some(k), Some(v)) = (iter.next(), iter.next()) {
+            if let (Some(key_str), Some(val_str)) = (k.into_string(), v.into_string()) {
+                pairs.push((key_str, val_str));
+            }
+        }
+        pairs
+    }
+
+    fn pair_scores(items: Vec<Value>) -> Vec<(String, f64)> {
+        let mut pairs = Vec::with_capacity(items.len() / 2);
+        let mut iter = items.into_iter();
+        while let (Some(member), Some(score)) = (iter.next(), iter.next()) {
+            if let Some(member_str) = member.into_string() {
+                let score_val = score
+                    .into_string()
+                    .and_then(|s| s.parse::<f64>().ok())
+                    .unwrap_or(0.0);
+                pairs.push((member_str, score_val));
+            }
+        }
+        pairs
+    }
+
+    /// Run HSCAN/SSCAN/ZSCAN until roughly `count` items are collected or the scan ends.
+    ///
+    /// A MATCH pattern can make single rounds return nothing, so a page may take
+    /// several round trips; `VALUE_SCAN_MAX_ROUNDS` keeps one page responsive.
+    async fn scan_collection(
+        client: &FredClient,
+        cmd_name: &'static str,
+        key: &str,
+        pattern: &str,
+        mut cursor: u64,
+        count: usize,
+        values_per_item: usize,
+    ) -> Result<(Vec<Value>, u64)> {
+        let mut collected = Vec::new();
+
+        for _ in 0..Self::VALUE_SCAN_MAX_ROUNDS {
+            let cmd = CustomCommand::new_static(cmd_name, None, false);
+            let mut args = vec![
+                Value::from(key.to_string()),
+                Value::from(cursor.to_string()),
+            ];
+            if !pattern.is_empty() && pattern != "*" {
+                args.push(Value::from("MATCH"));
+                args.push(Value::from(pattern.to_string()));
+            }
+            args.push(Value::from("COUNT"));
+            args.push(Value::from(count.to_string()));
+
+            let result: Value = client.custom(cmd, args).await.map_err(|e| {
+                tracing::error!("Redis {} failed: {}", cmd_name, e);
+                Error::Connection {
+                    message: e.to_string(),
+                }
+            })?;
+
+            let (next_cursor, items) = Self::parse_scan_reply(result);
+            collected.extend(items);
+            cursor = next_cursor;
+
+            if cursor == 0 || collected.len() / values_per_item >= count {
+                break;
+            }
+        }
+
+        Ok((collected, cursor))
+    }
+
+    /// Scan hash fields (HSCAN), optionally filtered by a MATCH pattern
+    pub async fn scan_hash(
+        &self,
+        key: &str,
+        pattern: &str,
+        cursor: u64,
+        count: usize,
+    ) -> Result<(Vec<(String, String)>, u64)> {
+        let key = key.to_string();
+        let pattern = pattern.to_string();
+        self.with_connected_client(move |client| async move {
+            let (items, cursor) =
+                Self::scan_collection(&client, "HSCAN", &key, &pattern, cursor, count, 2).await?;
+            Ok((Self::pair_strings(items), cursor))
+        })
+        .await
+    }
+
+    /// Scan set members (SSCAN), optionally filtered by a MATCH pattern
+    pub async fn scan_set(
+        &self,
+        key: &str,
+        pattern: &str,
+        cursor: u64,
+        count: usize,
+    ) -> Result<(Vec<String>, u64)> {
+        let key = key.to_string();
+        let pattern = pattern.to_string();
+        self.with_connected_client(move |client| async move {
+            let (items, cursor) =
+                Self::scan_collection(&client, "SSCAN", &key, &pattern, cursor, count, 1).await?;
+            Ok((
+                items.into_iter().filter_map(|v| v.into_string()).collect(),
+                cursor,
+            ))
+        })
+        .await
+    }
+
+    /// Scan sorted set members with scores (ZSCAN), optionally filtered by a MATCH pattern
+    pub async fn scan_zset(
+        &self,
+        key: &str,
+        pattern: &str,
+        cursor: u64,
+        count: usize,
+    ) -> Result<(Vec<(String, f64)>, u64)> {
+        let key = key.to_string();
+        let pattern = pattern.to_string();
+        self.with_connected_client(move |client| async move {
+            let (items, cursor) =
+                Self::scan_collection(&client, "ZSCAN", &key, &pattern, cursor, count, 2).await?;
+            Ok((Self::pair_scores(items), cursor))
+        })
+        .await
+    }
+
+    /// Get the element count of a collection key (HLEN/LLEN/SCARD/ZCARD/XLEN)
+    pub async fn get_key_length(&self, key: &str, key_type: RedisKeyType) -> Result<Option<i64>> {
+        let cmd_name = match key_type {
+            RedisKeyType::Hash => "HLEN",
+            RedisKeyType::List => "LLEN",
+            RedisKeyType::Set => "SCARD",
+            RedisKeyType::ZSet => "ZCARD",
+            RedisKeyType::Stream => "XLEN",
+            RedisKeyType::String | RedisKeyType::Unknown => return Ok(None),
+        };
+        let key = key.to_string();
+        self.with_connected_client(move |client| async move {
+            let cmd = CustomCommand::new_static(cmd_name, None, false);
+            let result: Value = client
+                .custom(cmd, vec![Value::from(key)])
+                .await
+                .map_err(|e| Error::Connection {
+                    message: e.to_string(),
+                })?;
+            Ok(Self::value_to_i64(&result))
+        })
+        .await
+    }
+
+    /// Measure one SCAN batch of keys for the keyspace analyzer
+    pub async fn analyze_keys(&self, cursor: u64, count: usize) -> Result<(Vec<KeySample>, u64)> {
+        let (keys, next_cursor) = self.scan_keys("*", cursor, count).await?;
+        let samples = self.measure_keys(keys).await?;
+        Ok((samples, next_cursor))
+    }
+
+    /// Measure `count` random keys (RANDOMKEY) for the keyspace analyzer.
+    ///
+    /// Random picks can repeat; duplicates are dropped.
+    pub async fn sample_keys(&self, count: usize) -> Result<Vec<KeySample>> {
+        let keys = self
+            .with_connected_client(move |client| async move {
+                let mut keys: Vec<String> = Vec::with_capacity(count);
+                let mut seen: HashSet<String> = HashSet::with_capacity(count);
+                for _ in 0..count {
+                    let cmd = CustomCommand::new_static("RANDOMKEY", None, false);
+                    let result: Value =
+                        client.custom(cmd, Vec::<Value>::new()).await.map_err(|e| {
+                            Error::Connection {
+                                message: e.to_string(),
+                            }
+                        })?;
+                    // An empty database has no random key
+                    let Some(key) = result.into_string() else {
+                        break;
+                    };
+                    if seen.insert(key.clone()) {
+                        keys.push(key);
+                    }
+                }
+                let mut items = Vec::with_capacity(keys.len());
+                for key in keys {
+                    let key_type = Self::get_key_type_internal(&client, &key).await;
+                    let ttl = Self::get_key_ttl_internal(&client, &key).await;
+                    items.push(RedisKeyItem::new(key, key_type, ttl));
+                }
+                Ok(items)
+            })
+            .await?;
+        self.measure_keys(keys).await
+    }
+
+    /// Number of keys in the current database (DBSIZE)
+    pub async fn db_size(&self) -> Result<u64> {
+        self.with_connected_client(move |client| async move {
+            let cmd = CustomCommand::new_static("DBSIZE", None, false);
+            let result: Value =
+                client
+                    .custom(cmd, Vec::<Value>::new())
+                    .await
+                    .map_err(|e| Error::Connection {
+                        message: e.to_string(),
+                    })?;
+            Ok(Self::value_to_i64(&result).unwrap_or(0).max(0) as u64)
+        })
+        .await
+    }
+
+    /// Fetch MEMORY USAGE and element count of scanned keys concurrently
+    async fn measure_keys(&self, keys: Vec<RedisKeyItem>) -> Result<Vec<KeySample>> {
+        self.with_connected_client(move |client| async move {
+            let measurements = keys.into_iter().map(|item| {
+                let client = client.clone();
+                async move {
+                    let memory = Self::key_memory_internal(&client, &item.key).await;
+                    let length_cmd = match item.key_type {
+                        RedisKeyType::String => Some("STRLEN"),
+                        RedisKeyType::Hash => Some("HLEN"),
+                        RedisKeyType::List => Some("LLEN"),
+                        RedisKeyType::Set => Some("SCARD"),
+                        RedisKeyType::ZSet => Some("ZCARD"),
+                        RedisKeyType::Stream => Some("XLEN"),
+                        RedisKeyType::Unknown => None,
+                    };
+                    let elements = match length_cmd {
+                        Some(cmd_name) => {
+                            let cmd = CustomCommand::new_static(cmd_name, None, false);
+                            client
+                                .custom::<Value, _>(cmd, vec![Value::from(item.key.clone())])
+                                .await
+                                .ok()
+                                .and_then(|v| Self::value_to_i64(&v))
+                                .map(|v| v.max(0) as u64)
+                        }
+                        None => None,
+                    };
+                    KeySample {
+                        key: item.key,
+                        key_type: item.key_type,
+                        ttl: item.ttl,
+                        memory,
+                        elements,
+                    }
+                }
+            });
+            Ok(futures::future::join_all(measurements).await)
+        })
+        .await
+    }
+
+    /// MEMORY USAGE of a key; `None` if the server does not support it
+    async fn key_memory_internal(client: &FredClient, key: &str) -> Option<u64> {
+        let cmd = CustomCommand::new_static("MEMORY", None, false);
+        let result: Value = client
+            .custom(
+                cmd,
+                vec![Value::from("USAGE"), Value::from(key.to_string())],
+            )
+            .await
+            .ok()?;
+        Self::value_to_i64(&result).map(|v| v.max(0) as u64)
+    }
+
+    /// Compare keys between the active server and `target`.
+    ///
+    /// `key_or_pattern` is a single key, or a glob pattern scanned on both servers
+    /// (up to `KEY_COMPARE_MAX_KEYS` keys). The target is reached through a
+    /// short-lived client so the active connection is left untouched.
+    pub async fn compare_keys(
+        &self,
+        target: &DfcServerConfig,
+        preset_credentials: &[PresetCredential],
+        key_or_pattern: &str,
+    ) -> Result<Vec<KeyComparison>> {
+        let (peer, _) = self
+            .connect_with_credentials(target, preset_credentials)
+            .await?;
+        let key_or_pattern = key_or_pattern.to_string();
+        let peer_for_compare = peer.clone();
+        let result = self
+            .with_connected_client(move |client| async move {
+                let peer = peer_for_compare;
+                let keys = if key_or_pattern.contains(['*', '?', '[']) {
+                    let mut keys = Self::scan_key_names(&client, &key_or_pattern).await?;
+                    keys.extend(Self::scan_key_names(&peer, &key_or_pattern).await?);
+                    keys.sort();
+                    keys.dedup();
+                    keys.truncate(KEY_COMPARE_MAX_KEYS);
+                    keys
+                } else {
+                    vec![key_or_pattern]
+                };
+
+                let mut comparisons = Vec::with_capacity(keys.len());
+                for key in keys {
+                    let (source, source_truncated) =
+                        Self::load_compare_value(&client, &key).await?;
+                    let (target, target_truncated) = Self::load_compare_value(&peer, &key).await?;
+                    comparisons.push(KeyComparison {
+                        diff: diff_values(&source, &target),
+                        key,
+                        truncated: source_truncated || target_truncated,
+                    });
+                }
+                Ok(comparisons)
+            })
+            .await;
+        super::run_in_tokio(Self::shutdown_client(peer, "key compare")).await;
+        result
+    }
+
+    /// Copy a key from the active server to `target` with DUMP/RESTORE REPLACE,
+    /// keeping its TTL. Returns summaries of the replaced and the copied value.
+    /// `permit` must have been issued for `target`.
+    pub async fn copy_key_to_server(
+        &self,
+        permit: &WritePermit,
+        target: &DfcServerConfig,
+        preset_credentials: &[PresetCredential],
+        key: &str,
+    ) -> Result<(String, String)> {
+        permit.check(target)?;
+        let (peer, _) = self
+            .connect_with_credentials(target, preset_credentials)
+            .await?;
+        let key = key.to_string();
+        let peer_for_copy = peer.clone();
+        let result = self
+            .with_connected_client(move |client| async move {
+                let peer = peer_for_copy;
+                let command_error = |e: fred::error::Error| Error::Command {
+                    message: e.to_string(),
+                };
+
+                let cmd = CustomCommand::new_static("DUMP", None, false);
+                let payload: Value = client
+                    .custom(cmd, vec![Value::from(key.clone())])
+                    .await
+                    .map_err(command_error)?;
+                if payload.is_null() {
+                    return Err(Error::Invalid {
+                        message: format!("Key {key} does not exist on the source server"),
+                    });
+                }
+                let cmd = CustomCommand::new_static("PTTL", None, false);
+                let pttl: Value = client
+                    .custom(cmd, vec![Value::from(key.clone())])
+                    .await
+                    .map_err(command_error)?;
+                let pttl = Self::value_to_i64(&pttl).unwrap_or(-1).max(0);
+
+                let (source, _) = Self::load_compare_value(&client, &key).await?;
+                let (replaced, _) = Self::load_compare_value(&peer, &key).await?;
+
+                let cmd = CustomCommand::new_static("RESTORE", None, false);
+                let _: Value = peer
+                    .custom(
+                        cmd,
+                        vec![
+                            Value::from(key.clone()),
+                            Value::from(pttl.to_string()),
+                            payload,
+                            Value::from("REPLACE"),
+                        ],
+                    )
+                    .await
+                    .map_err(command_error)?;
+                Ok((replaced.summary(), source.summary()))
+            })
+            .await;
+        super::run_in_tokio(Self::shutdown_client(peer, "key copy")).await;
+        result
+    }
+
+    /// Key names matching `pattern`, up to `KEY_COMPARE_MAX_KEYS`
+    async fn scan_key_names(client: &FredClient, pattern: &str) -> Result<Vec<String>> {
+        let mut keys = Vec::new();
+        let mut cursor = 0u64;
+        loop {
+            let cmd = CustomCommand::new_static("SCAN", None, false);
+            let args = vec![
+                Value::from(cursor.to_string()),
+                Value::from("MATCH"),
+                Value::from(pattern.to_string()),
+                Value::from("COUNT"),
+                Value::from("500"),
+            ];
+            let result: Value = client
+                .custom(cmd, args)
+                .await
+                .map_err(|e| Error::Connection {
+                    message: e.to_string(),
+                })?;
+            let (next_cursor, items) = Self::parse_scan_reply(result);
+            keys.extend(items.into_iter().filter_map(|v| v.into_string()));
+            cursor = next_cursor;
+            if cursor == 0 || keys.len() >= KEY_COMPARE_MAX_KEYS {
+                break;
+            }
+        }
+        Ok(keys)
+    }
+
+    /// Load a key's whole value for comparison.
+    ///
+    /// Collections stop after `KEY_COMPARE_MAX_ELEMENTS`; the flag tells whether
+    /// the value was cut.
+    async fn load_compare_value(client: &FredClient, key: &str) -> Result<(CompareValue, bool)> {
+        let cmd = CustomCommand::new_static("TYPE", None, false);
+        let key_type: Value = client
+            .custom(cmd, vec![Value::from(key.to_string())])
+            .await
+            .map_err(|e| Error::Connection {
+                message: e.to_string(),
+            })?;
+        let key_type = key_type.into_string().unwrap_or_default();
+        if key_type == "none" {
+            return Ok((CompareValue::Missing, false));
+        }
+
+        let max = KEY_COMPARE_MAX_ELEMENTS;
+        match RedisKeyType::from_type_str(&key_type) {
+            RedisKeyType::String => {
+                let cmd = CustomCommand::new_static("GET", None, false);
+                let value: Value = client
+                    .custom(cmd, vec![Value::from(key.to_string())])
+                    .await
+                    .map_err(|e| Error::Connection {
+                        message: e.to_string(),
+                    })?;
+                let bytes = match value {
+                    Value::Bytes(bytes) => bytes.to_vec(),
+                    other => Self::value_to_string_lossy(other).into_bytes(),
+                };
+                Ok((CompareValue::String(bytes), false))
+            }
+            RedisKeyType::List => {
+                let cmd = CustomCommand::new_static("LRANGE", None, false);
+                let result: Value = client
+                    .custom(
+                        cmd,
+                        vec![
+                            Value::from(key.to_string()),
+                            Value::from("0"),
+                            Value::from(max.to_string()),
+                        ],
+                    )
+                    .await
+                    .map_err(|e| Error::Connection {
+                        message: e.to_string(),
+                    })?;
+                let mut items: Vec<String> = result
+                    .into_array()
+                    .into_iter()
+                    .map(Self::value_to_string_lossy)
+                    .collect();
+                let truncated = items.len() > max;
+                items.truncate(max);
+                Ok((CompareValue::List(items), truncated))
+            }
+            key_type @ (RedisKeyType::Hash | RedisKeyType::Set | RedisKeyType::ZSet) => {
+                let (cmd_name, values_per_item) = match key_type {
+                    RedisKeyType::Hash => ("HSCAN", 2),
+                    RedisKeyType::Set => ("SSCAN", 1),
+                    _ => ("ZSCAN", 2),
+                };
+                let mut items = Vec::new();
+                let mut cursor = 0;
+                loop {
+                    let (chunk, next_cursor) = Self::scan_collection(
+                        client,
+                        cmd_name,
+                        key,
+                        "*",
+                        cursor,
+                        Self::VALUE_SEARCH_CHUNK,
+                        values_per_item,
+                    )
+                    .await?;
+                    items.extend(chunk);
+                    cursor = next_cursor;
+                    if cursor == 0 || items.len() / values_per_item >= max {
+                        break;
+                    }
+                }
+                let truncated = cursor != 0;
+                let value = match key_type {
+                    RedisKeyType::Hash => {
+                        CompareValue::Hash(Self::pair_strings(items).into_iter().collect())
+                    }
+                    RedisKeyType::Set => CompareValue::Set(
+                        items.into_iter().filter_map(|v| v.into_string()).collect(),
+                    ),
+                    _ => CompareValue::ZSet(Self::pair_scores(items).into_iter().collect()),
+                };
+                Ok((value, truncated))
+            }
+            other => Ok((CompareValue::Other(other), false)),
+        }
+    }
+
+    /// Search the values of one SCAN batch of keys matching `pattern`.
+    ///
+    /// Strings, hash fields and values, list elements and set/sorted set members
+    /// are searched; streams are skipped. Collections are searched up to
+    /// `VALUE_SEARCH_MAX_ELEMENTS` elements per key.
+    pub async fn search_values(
+        &self,
+        pattern: &str,
+        cursor: u64,
+        count: usize,
+        matcher: Arc<ValueMatcher>,
+    ) -> Result<ValueSearchBatch> {
+        let (keys, next_cursor) = self.scan_keys(pattern, cursor, count).await?;
+        self.with_connected_client(move |client| async move {
+            let mut matches = Vec::new();
+            for item in &keys {
+                if let Err(e) = Self::search_key_value(&client, item, &matcher, &mut matches).await
+                {
+                    tracing::warn!(key = %item.key, error = %e, "Skipping key in value search");
+                }
+            }
+            Ok(ValueSearchBatch {
+                matches,
+                scanned: keys.len(),
+                next_cursor,
+            })
+        })
+        .await
+    }
+
+    /// Search the value of one key, appending a match per matching element
+    async fn search_key_value(
+        client: &FredClient,
+        item: &RedisKeyItem,
+        matcher: &ValueMatcher,
+        matches: &mut Vec<ValueSearchMatch>,
+    ) -> Result<()> {
+        let mut push = |field: Option<String>, excerpt: Option<String>| {
+            if let Some(excerpt) = excerpt {
+                matches.push(ValueSearchMatch {
+                    key: item.key.clone(),
+                    key_type: item.key_type,
+                    field,
+                    excerpt,
+                });
+            }
+        };
+
+        match item.key_type {
+            RedisKeyType::String => {
+                let cmd = CustomCommand::new_static("GET", None, false);
+                let value: Value = client
+                    .custom(cmd, vec![Value::from(item.key.clone())])
+                    .await
+                    .map_err(|e| Error::Connection {
+                        message: e.to_string(),
+                    })?;
+                let text = match value {
+                    Value::Bytes(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+                    other => Self::value_to_string_lossy(other),
+                };
+                push(None, matcher.excerpt(&text));
+            }
+            RedisKeyType::List => {
+                let chunk = Self::VALUE_SEARCH_CHUNK as i64;
+                let mut start = 0i64;
+                while (start as usize) < Self::VALUE_SEARCH_MAX_ELEMENTS {
+                    let cmd = CustomCommand::new_static("LRANGE", None, false);
+                    let result: Value = client
+                        .custom(
+                            cmd,
+                            vec![
+                                Value::from(item.key.clone()),
+                                Value::from(start.to_string()),
+                                Value::from((start + chunk - 1).to_string()),
+                            ],
+                        )
+                        .await
+                        .map_err(|e| Error::Connection {
+                            message: e.to_string(),
+                        })?;
+                    let items = result.into_array();
+                    let fetched = items.len() as i64;
+                    for (offset, element) in items.into_iter().enumerate() {
+                        let text = Self::value_to_string_lossy(element);
+                        let index = start + offset as i64;
+                        push(Some(index.to_string()), matcher.excerpt(&text));
+                    }
+                    if fetched < chunk {
+                        break;
+                    }
+                    start += chunk;
+                }
+            }
+            RedisKeyType::Hash | RedisKeyType::Set | RedisKeyType::ZSet => {
+                let (cmd_name, values_per_item) = match item.key_type {
+                    RedisKeyType::Hash => ("HSCAN", 2),
+                    RedisKeyType::Set => ("SSCAN", 1),
+                    _ => ("ZSCAN", 2),
+                };
+                let mut cursor = 0;
+                let mut searched = 0;
+                loop {
+                    let (items, next_cursor) = Self::scan_collection(
+                        client,
+                        cmd_name,
+                        &item.key,
+                        "*",
+                        cursor,
+                        Self::VALUE_SEARCH_CHUNK,
+                        values_per_item,
+                    )
+                    .await?;
+                    searched += items.len() / values_per_item;
+                    match item.key_type {
+                        RedisKeyType::Hash => {
+                            for (field, value) in Self::pair_strings(items) {
+                                let excerpt =
+                                    matcher.excerpt(&value).or_else(|| matcher.excerpt(&field));
+                                push(Some(field), excerpt);
+                            }
+                        }
+                        RedisKeyType::Set => {
+                            for member in items.into_iter().filter_map(|v| v.into_string()) {
+                                push(None, matcher.excerpt(&member));
+                            }
+                        }
+                        _ => {
+                            for (member, _) in Self::pair_scores(items) {
+                                push(None, matcher.excerpt(&member));
+                            }
+                        }
+                    }
+                    cursor = next_cursor;
+                    if cursor == 0 || searched >= Self::VALUE_SEARCH_MAX_ELEMENTS {
+                        break;
+                    }
+                }
+            }
+            RedisKeyType::Stream | RedisKeyType::Unknown => {}
+        }
+        Ok(())
+    }
+
+    /// Offset-based paging helper: a full page means more elements may follow
+    fn next_offset(offset: i64, loaded: usize, count: usize) -> RedisValueCursor {
+        if loaded >= count {
+            RedisValueCursor::Offset(offset + loaded as i64)
+        } else {
+            RedisValueCursor::Done
+        }
+    }
+
+    /// Get one page of a key's value.
+    ///
+    /// `cursor` is `None` for the first page. `pattern` is applied server-side as a
+    /// MATCH filter on hash fields and set/zset members; lists and strings ignore it.
+    /// Sorted sets are paged by rank without a pattern and scanned with one.
+    pub async fn get_value_page(
+        &self,
+        key: &str,
+        key_type: RedisKeyType,
+        pattern: &str,
+        cursor: Option<RedisValueCursor>,
+    ) -> Result<RedisValuePage> {
+        let count = Self::VALUE_PAGE_SIZE;
+        let scan_cursor = match cursor {
+            Some(RedisValueCursor::Scan(cursor)) => cursor,
+            _ => 0,
+        };
+        let offset = match cursor {
+            Some(RedisValueCursor::Offset(offset)) => offset,
+            _ => 0,
+        };
+        let total = if cursor.is_none() {
+            self.get_key_length(key, key_type).await?
+        } else {
+            None
+        };
+
+        let (value, next) = match key_type {
+            RedisKeyType::String => {
+                let value = match String::from_utf8(self.get_string_bytes(key).await?) {
+                    Ok(text) => RedisKeyValue::String(text),
+                    Err(e) => RedisKeyValue::Bytes(e.into_bytes()),
+                };
+                (value, RedisValueCursor::Done)
+            }
+            RedisKeyType::Hash => {
+                let (pairs, next) = self.scan_hash(key, pattern, scan_cursor, count).await?;
+                (
+                    RedisKeyValue::Hash(pairs),
+                    RedisValueCursor::from_scan(next),
+                )
+            }
+            RedisKeyType::Set => {
+                let (members, next) = self.scan_set(key, pattern, scan_cursor, count).await?;
+                (
+                    RedisKeyValue::Set(members),
+                    RedisValueCursor::from_scan(next),
+                )
+            }
+            RedisKeyType::ZSet if !pattern.is_empty() && pattern != "*" => {
+                let (members, next) = self.scan_zset(key, pattern, scan_cursor, count).await?;
+                (
+                    RedisKeyValue::ZSet(members),
+                    RedisValueCursor::from_scan(next),
+                )
+            }
+            RedisKeyType::ZSet => {
+                let stop = offset + count as i64 - 1;
+                let members = self.get_zset(key, offset, stop).await?;
+                let next = Self::next_offset(offset, members.len(), count);
+                (RedisKeyValue::ZSet(members), next)
+            }
+            RedisKeyType::List => {
+                let stop = offset + count as i64 - 1;
+                let items = self.get_list(key, offset, stop).await?;
+                let next = Self::next_offset(offset, items.len(), count);
+                (RedisKeyValue::List(items), next)
+            }
+            RedisKeyType::Stream => {
+                // Newest entries first; streams page themselves by entry ID
+                let stream = self.get_stream(key, true).await?;
+                (RedisKeyValue::Stream(stream), RedisValueCursor::Done)
+            }
+            RedisKeyType::Unknown => (
+                RedisKeyValue::Error(format!("Unsupported key type: {:?}", key_type)),
+                RedisValueCursor::Done,
+            ),
+        };
+
+        Ok(RedisValuePage { value, next, total })
+    }
+
+    /// Get the first page of a key's value, optionally filtered by a MATCH pattern
+    pub async fn get_key_value_page(&self, key: &str, pattern: &str) -> Result<RedisValuePage> {
+        let key_type = self.get_key_type(key).await?;
+        self.get_value_page(key, key_type, pattern, None).await
+    }
+
+    /// Execute a write issued from the key browser.
+    ///
+    /// Returns the server reply as text. A rename onto an existing key, an
+    /// EXPIRE on a missing key and a list delete whose element has changed are
+    /// reported as `Error::Command`. The permit
+    /// must have been issued for the active server.
+    pub async fn apply_write(&self, permit: &WritePermit, op: &RedisWriteOp) -> Result<String> {
+        let op = op.clone();
+        self.with_write_client(permit, move |client| async move {
+            // Route by the key; the list delete script does not start with it
+            let cmd = CustomCommand::new_static(op.command_name(), op.key(), false);
+            let args = op.args().into_iter().map(Value::from).collect();
+            let result: Value = client.custom(cmd, args).await.map_err(|e| {
+                tracing::error!("Redis {} failed: {}", op.command_name(), e);
+                Error::Command {
+                    message: e.to_string(),
+                }
+            })?;
+
+            let replied_zero = Self::value_to_i64(&result) == Some(0);
+            match &op {
+                RedisWriteOp::Rename { new_key, .. } if replied_zero => Err(Error::Command {
+                    message: format!("Key already exists: {new_key}"),
+                }),
+                RedisWriteOp::Expire { key, .. } if replied_zero => Err(Error::Command {
+                    message: format!("Key does not exist: {key}"),
+                }),
+                RedisWriteOp::LDel { key, index, .. } if Self::value_to_i64(&result) == Some(-1) => {
+                    Err(Error::Command {
+                        message: format!("List element {key}[{index}] has changed; reload the key"),
+                    })
+                }
+                _ => {
+                    tracing::info!(command = op.command_name(), key = %op.key(), "Redis write applied");
+                    Ok(Self::value_to_string_lossy(result))
+                }
+            }
+        })
+        .await
+    }
+
+    /// Convert a raw reply into the console reply tree
+    fn value_to_reply(value: Value) -> RedisReply {
+        match value {
+            Value::Null => RedisReply::Nil,
+            Value::Integer(v) => RedisReply::Integer(v),
+            Value::Double(v) => RedisReply::Double(v),
+            Value::Boolean(v) => RedisReply::Boolean(v),
+            Value::Array(items) => {
+                RedisReply::Array(items.into_iter().map(Self::value_to_reply).collect())
+            }
+            Value::Map(map) => {
+                let mut pairs: Vec<(String, RedisReply)> = map
+                    .inner()
+                    .into_iter()
+                    .map(|(k, v)| (k.as_str_lossy().to_string(), Self::value_to_reply(v)))
+                    .collect();
+                pairs.sort_by(|a, b| a.0.cmp(&b.0));
+                RedisReply::Map(pairs)
+            }
+            other => RedisReply::String(Self::value_to_string_lossy(other)),
+        }
+    }
+
+    /// Execute a raw command typed into the console.
+    ///
+    /// Error replies from the server are returned as `RedisReply::Error`; only
+    /// connection failures and timeouts surface as `Err`. Write commands need
+    /// a permit issued for the active server.
+    pub async fn execute_command(
+        &self,
+        args: Vec<String>,
+        permit: Option<&WritePermit>,
+    ) -> Result<RedisReply> {
+        let client_handle = self.current_client().await?;
+        match permit {
+            Some(permit) => permit.check(&client_handle.server)?,
+            None if is_write_command(&args) => {
+                return Err(Error::Invalid {
+                    message: "Write commands need the server's write confirmation".to_string(),
+                });
+            }
+            None => {}
+        }
+        let mut args = args.into_iter();
+        let Some(name) = args.next() else {
+            return Err(Error::Invalid {
+                message: "Empty command".to_string(),
+            });
+        };
+        let args: Vec<Value> = args.map(Value::from).collect();
+
+        Self::run_with_client(client_handle, move |client| async move {
+            let cmd = CustomCommand::new(name.to_ascii_uppercase(), None, false);
+            match client.custom::<Value, _>(cmd, args).await {
+                Ok(value) => Ok(Self::value_to_reply(value)),
+                Err(e) => match e.kind() {
+                    ErrorKind::IO | ErrorKind::Canceled => Err(Error::Connection {
+                        message: e.to_string(),
+                    }),
+                    ErrorKind::Timeout => Err(Error::Timeout {
+                        message: e.to_string(),
+                    }),
+                    _ => Ok(RedisReply::Error(e.details().to_string())),
+                },
+            }
+        })
+        .await
+    }
+
+    /// Parse a `SLOWLOG GET` reply.
+    ///
+    /// Each entry is `[id, timestamp, duration_us, [args...], client_addr, client_name]`;
+    /// the client fields are missing before Redis 4.0.
+    fn parse_slowlog(value: Value) -> Vec<RedisSlowlogEntry> {
+        value
+            .into_array()
+            .into_iter()
+            .filter_map(|entry| {
+                let mut parts = entry.into_array().into_iter();
+                let id = Self::value_to_i64(&parts.next()?)?;
+                let timestamp = Self::value_to_i64(&parts.next()?)?;
+                let duration_us = Self::value_to_i64(&parts.next()?)?;
+                let command = parts
+                    .next()?
+                    .into_array()
+                    .into_iter()
+                    .map(Self::value_to_string_lossy)
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                Some(RedisSlowlogEntry {
+                    id,
+                    timestamp,
+                    duration_us,
+                    command,
+                    client_addr: parts
+                        .next()
+                        .map(Self::value_to_string_lossy)
+                        .unwrap_or_default(),
+                    client_name: parts
+                        .next()
+                        .map(Self::value_to_string_lossy)
+                        .unwrap_or_default(),
+                })
+            })
+            .collect()
+    }
+
+    /// Load the server dashboard: INFO, SLOWLOG, CLIENT LIST and, in cluster
+    /// mode, CLUSTER NODES.
+    ///
+    /// Only INFO is required; the other commands may be disabled by ACLs or
+    /// renamed, in which case their sections stay empty. On a cluster the
+    /// commands go to every primary listed by CLUSTER NODES: stats add up, slow
+    /// commands and clients of all primaries are listed together.
+    pub async fn fetch_dashboard(&self, slowlog_len: usize) -> Result<RedisDashboard> {
+        self.with_connected_client(move |client| async move {
+            let mut cluster_nodes = if client.is_clustered() {
+                Self::fetch_cluster_nodes(&client).await
+            } else {
+                Vec::new()
+            };
+            let primaries: Vec<Server> = cluster_nodes
+                .iter()
+                .filter(|node| node.is_master() && !node.is_failing() && !node.slots.is_empty())
+                .filter_map(|node| Server::try_from(node.addr.as_str()).ok())
+                .collect();
+            let mut dashboard = if primaries.is_empty() {
+                Self::fetch_node_dashboard(&client, slowlog_len).await?
+            } else {
+                let nodes = futures::future::join_all(primaries.into_iter().map(|server| {
+                    let node = client.with_cluster_node(server.clone());
+                    async move {
+                        let dashboard = Self::fetch_node_dashboard(&node, slowlog_len).await;
+                        if let Err(e) = &dashboard {
+                            tracing::warn!(node = %server, "Cluster node dashboard failed: {}", e);
+                        }
+                        dashboard
+                    }
+                }))
+                .await;
+                Self::combine_node_dashboards(nodes, slowlog_len)?
+            };
+
+            // A cluster node reached through a standalone connection
+            if cluster_nodes.is_empty() && dashboard.stats.cluster_enabled {
+                cluster_nodes = Self::fetch_cluster_nodes(&client).await;
+            }
+            dashboard.cluster_nodes = cluster_nodes;
+            Ok(dashboard)
+        })
+        .await
+    }
+
+    /// CLUSTER NODES of the node `client` talks to; empty when it fails
+    async fn fetch_cluster_nodes(client: &FredClient) -> Vec<RedisClusterNode> {
+        let cmd = CustomCommand::new_static("CLUSTER", None, false);
+        match client
+            .custom::<String, _>(cmd, vec![Value::from("NODES")])
+            .await
+        {
+            Ok(text) => parse_cluster_nodes(&text),
+            Err(e) => {
+                tracing::warn!("CLUSTER NODES failed: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    /// INFO, SLOWLOG and CLIENT LIST of the node `client` talks to
+    async fn fetch_node_dashboard<C: ClientLike>(
+        client: &C,
+        slowlog_len: usize,
+    ) -> Result<RedisDashboard> {
+        let cmd = CustomCommand::new_static("INFO", None, false);
+        let info: String =
+            client
+                .custom(cmd, Vec::<Value>::new())
+                .await
+                .map_err(|e| Error::Command {
+                    message: e.to_string(),
+                })?;
+        let stats = RedisServerStats::from_info(&parse_info(&info));
+
+        let cmd = CustomCommand::new_static("SLOWLOG", None, false);
+        let args = vec![Value::from("GET"), Value::from(slowlog_len as i64)];
+        let slowlog = match client.custom::<Value, _>(cmd, args).await {
+            Ok(value) => Self::parse_slowlog(value),
+            Err(e) => {
+                tracing::warn!("SLOWLOG GET failed: {}", e);
+                Vec::new()
+            }
+        };
+
+        let cmd = CustomCommand::new_static("CLIENT", None, false);
+        let clients = match client
+            .custom::<String, _>(cmd, vec![Value::from("LIST")])
+            .await
+        {
+            Ok(text) => parse_client_list(&text),
+            Err(e) => {
+                tracing::warn!("CLIENT LIST failed: {}", e);
+                Vec::new()
+            }
+        };
+
+        Ok(RedisDashboard {
+            stats,
+            slowlog,
+            clients,
+            cluster_nodes: Vec::new(),
+        })
+    }
+
+    /// Dashboard of a cluster from those of its primaries; fails only when
+    /// no primary answered
+    fn combine_node_dashboards(
+        nodes: Vec<Result<RedisDashboard>>,
+        slowlog_len: usize,
+    ) -> Result<RedisDashboard> {
+        let mut first_error = None;
+        let mut stats = Vec::new();
+        let mut slowlog = Vec::new();
+        let mut clients = Vec::new();
+        for node in nodes {
+            match node {
+                Ok(node) => {
+                    stats.push(node.stats);
+                    slowlog.extend(node.slowlog);
+                    clients.extend(node.clients);
+                }
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        if stats.is_empty() {
+            return Err(first_error.unwrap_or_else(|| Error::Command {
+                message: "No cluster primary answered".to_string(),
+            }));
+        }
+        // Newest slow commands first, as SLOWLOG GET lists them per node
+        slowlog.sort_by_key(|entry| std::cmp::Reverse(entry.timestamp));
+        slowlog.truncate(slowlog_len);
+        Ok(RedisDashboard {
+            stats: RedisServerStats::combine(&stats),
+            slowlog,
+            clients,
+            cluster_nodes: Vec::new(),
+        })
+    }
+}
+
+impl std::fmt::Debug for RedisRepo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RedisRepo")
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connection::SafetyLevel;
+    use serde_json::json;
+    use std::sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    };
+    use tokio::sync::oneshot;
+    use tokio::time::{Duration, sleep};
+
+    #[test]
+    fn parse_config_string_value_handles_json_and_plain() {
+        let parsed = RedisRepo::parse_config_string_value("{\"a\":1}");
+        assert!(parsed.is_object());
+
+        let parsed = RedisRepo::parse_config_string_value("plain");
+        assert!(parsed.is_string());
+    }
+
+    #[test]
+    fn parse_list_values_parses_each_item() {
+        let value =
+            RedisRepo::parse_list_values(vec!["{\"a\":1}".to_string(), "plain".to_string()]);
+        let arr = value.as_array().expect("array");
+        assert!(arr[0].is_object());
+        assert!(arr[1].is_string());
+    }
+
+    #[test]
+    fn parse_hash_pairs_parses_values() {
+        let value = RedisRepo::parse_hash_pairs(vec![
+            ("a".to_string(), "{\"x\":1}".to_string()),
+            ("b".to_string(), "plain".to_string()),
+        ]);
+        let obj = value.as_object().expect("object");
+        assert!(obj.get("a").is_some_and(serde_json::Value::is_object));
+        assert!(obj.get("b").is_some_and(serde_json::Value::is_string));
+    }
+
+    #[test]
+    fn parse_imid2imr_json_accepts_suffixed_uuid_and_string_imid() {
+        let value = json!([
+            {
+                "uuid": "705537041061273601_template",
                 "props": [
                     { "uuid": "Turbine/WTUR/State/DataAvailable", "imid": "1" },
                     { "uuid": "Turbine/WTUR/State/IgnoredZero", "imid": 0 },
@@ -1802,15 +3719,301 @@ mod tests {
         }
     }
 
+    fn bulk(values: &[&str]) -> Value {
+        Value::Array(values.iter().map(|v| Value::from(*v)).collect())
+    }
+
+    #[test]
+    fn parse_stream_entries_reads_ids_and_field_pairs() {
+        let reply = Value::Array(vec![
+            Value::Array(vec![
+                Value::from("1718000000000-0"),
+                bulk(&["agent", "622", "topic", "prop"]),
+            ]),
+            Value::Array(vec![Value::from("1718000000001-0"), bulk(&["k", "v"])]),
+        ]);
+
+        let entries = RedisRepo::parse_stream_entries(reply);
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].id, "1718000000000-0");
+        assert_eq!(
+            entries[0].fields,
+            vec![
+                ("agent".to_string(), "622".to_string()),
+                ("topic".to_string(), "prop".to_string())
+            ]
+        );
+        assert_eq!(entries[1].fields.len(), 1);
+    }
+
+    #[test]
+    fn parse_stream_info_and_groups_read_flat_pairs() {
+        let info = RedisRepo::parse_stream_info(Value::Array(vec![
+            Value::from("length"),
+            Value::Integer(42),
+            Value::from("groups"),
+            Value::Integer(1),
+            Value::from("last-generated-id"),
+            Value::from("5-0"),
+            Value::from("first-entry"),
+            Value::Array(vec![Value::from("1-0"), bulk(&["a", "b"])]),
+            Value::from("last-entry"),
+            Value::Null,
+        ]));
+        assert_eq!(info.length, 42);
+        assert_eq!(info.groups, 1);
+        assert_eq!(info.last_generated_id, "5-0");
+        assert_eq!(info.first_entry_id.as_deref(), Some("1-0"));
+        assert_eq!(info.last_entry_id, None);
+
+        let group = RedisRepo::parse_stream_group(Value::Array(vec![
+            Value::from("name"),
+            Value::from("dfc"),
+            Value::from("consumers"),
+            Value::Integer(2),
+            Value::from("pending"),
+            Value::Integer(3),
+            Value::from("last-delivered-id"),
+            Value::from("4-0"),
+        ]));
+        assert_eq!(group.name, "dfc");
+        assert_eq!(group.consumers, 2);
+        assert_eq!(group.pending, 3);
+        assert_eq!(group.lag, None);
+    }
+
+    #[test]
+    fn parse_stream_pending_reads_extended_reply() {
+        let reply = Value::Array(vec![Value::Array(vec![
+            Value::from("1-0"),
+            Value::from("worker-1"),
+            Value::Integer(1500),
+            Value::Integer(2),
+        ])]);
+
+        let pending = RedisRepo::parse_stream_pending(reply);
+
+        assert_eq!(
+            pending,
+            vec![RedisStreamPendingEntry {
+                id: "1-0".to_string(),
+                consumer: "worker-1".to_string(),
+                idle_ms: 1500,
+                delivery_count: 2,
+            }]
+        );
+    }
+
+    #[test]
+    fn escape_glob_escapes_metacharacters() {
+        assert_eq!(RedisRepo::escape_glob("CMC_{DCC0007}_"), "CMC_{DCC0007}_");
+        assert_eq!(RedisRepo::escape_glob("a*b?[c]\\"), "a\\*b\\?\\[c\\]\\\\");
+    }
+
+    #[test]
+    fn parse_scan_reply_splits_cursor_and_items() {
+        let reply = Value::Array(vec![Value::from("17"), bulk(&["f1", "v1", "f2", "v2"])]);
+
+        let (cursor, items) = RedisRepo::parse_scan_reply(reply);
+
+        assert_eq!(cursor, 17);
+        assert_eq!(
+            RedisRepo::pair_strings(items),
+            vec![
+                ("f1".to_string(), "v1".to_string()),
+                ("f2".to_string(), "v2".to_string())
+            ]
+        );
+        assert_eq!(RedisRepo::parse_scan_reply(Value::Null), (0, Vec::new()));
+    }
+
+    #[test]
+    fn pair_scores_parses_member_score_pairs() {
+        let pairs = RedisRepo::pair_scores(vec![
+            Value::from("a"),
+            Value::from("1.5"),
+            Value::from("b"),
+            Value::from("oops"),
+        ]);
+        assert_eq!(pairs, vec![("a".to_string(), 1.5), ("b".to_string(), 0.0)]);
+    }
+
+    #[test]
+    fn value_to_reply_keeps_nested_structure() {
+        let reply = RedisRepo::value_to_reply(Value::Array(vec![
+            Value::from("a"),
+            Value::Integer(3),
+            Value::Null,
+            bulk(&["x", "y"]),
+        ]));
+        assert_eq!(
+            reply,
+            RedisReply::Array(vec![
+                RedisReply::String("a".to_string()),
+                RedisReply::Integer(3),
+                RedisReply::Nil,
+                RedisReply::Array(vec![
+                    RedisReply::String("x".to_string()),
+                    RedisReply::String("y".to_string()),
+                ]),
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_slowlog_reads_entries_with_and_without_client_fields() {
+        let reply = Value::Array(vec![
+            Value::Array(vec![
+                Value::Integer(7),
+                Value::Integer(1718000000),
+                Value::Integer(15000),
+                bulk(&["KEYS", "CMC_*"]),
+                Value::from("10.0.0.5:50000"),
+                Value::from("dfc-gui"),
+            ]),
+            Value::Array(vec![
+                Value::Integer(6),
+                Value::Integer(1717999999),
+                Value::Integer(12000),
+                bulk(&["HGETALL", "cfg"]),
+            ]),
+        ]);
+
+        let entries = RedisRepo::parse_slowlog(reply);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].command, "KEYS CMC_*");
+        assert_eq!(entries[0].duration_us, 15000);
+        assert_eq!(entries[0].client_name, "dfc-gui");
+        assert_eq!(entries[1].client_addr, "");
+    }
+
+    #[test]
+    fn cluster_dashboard_merges_the_primaries_that_answered() {
+        let node = |timestamps: &[i64], clients: usize| {
+            Ok(RedisDashboard {
+                stats: RedisServerStats {
+                    connected_clients: clients as u64,
+                    ..RedisServerStats::default()
+                },
+                slowlog: timestamps
+                    .iter()
+                    .map(|&timestamp| RedisSlowlogEntry {
+                        timestamp,
+                        ..RedisSlowlogEntry::default()
+                    })
+                    .collect(),
+                clients: vec![Default::default(); clients],
+                cluster_nodes: Vec::new(),
+            })
+        };
+        let dashboard = RedisRepo::combine_node_dashboards(
+            vec![
+                node(&[30, 10], 2),
+                Err(Error::Command {
+                    message: "node down".to_string(),
+                }),
+                node(&[20, 5], 1),
+            ],
+            3,
+        )
+        .expect("combine node dashboards");
+
+        assert_eq!(dashboard.stats.connected_clients, 3);
+        assert_eq!(dashboard.clients.len(), 3);
+        assert_eq!(
+            dashboard
+                .slowlog
+                .iter()
+                .map(|entry| entry.timestamp)
+                .collect::<Vec<_>>(),
+            vec![30, 20, 10]
+        );
+
+        let failed = RedisRepo::combine_node_dashboards(
+            vec![Err(Error::Command {
+                message: "node down".to_string(),
+            })],
+            3,
+        );
+        assert!(failed.is_err());
+    }
+
+    #[test]
+    fn next_offset_stops_on_short_page() {
+        assert_eq!(
+            RedisRepo::next_offset(100, 100, 100),
+            RedisValueCursor::Offset(200)
+        );
+        assert_eq!(RedisRepo::next_offset(100, 42, 100), RedisValueCursor::Done);
+        assert_eq!(RedisValueCursor::from_scan(0), RedisValueCursor::Done);
+        assert_eq!(RedisValueCursor::from_scan(9), RedisValueCursor::Scan(9));
+    }
+
+    #[test]
+    fn writes_need_a_permit_for_the_connected_server() {
+        crate::services::block_on(async {
+            let (tx, _rx) = crossbeam_channel::unbounded();
+            let repo = RedisRepo::new(&RedisConfig::default(), Arc::new(SshTunnels::default()), tx)
+                .expect("repo");
+            let server_a = DfcServerConfig {
+                id: "server-a".to_string(),
+                safety: SafetyLevel::Unrestricted,
+                ..Default::default()
+            };
+            let server_b = DfcServerConfig {
+                id: "server-b".to_string(),
+                safety: SafetyLevel::Unrestricted,
+                ..Default::default()
+            };
+            {
+                let mut guard = repo.client.write().await;
+                *guard = Some(Arc::new(ActiveRedisClient::new(
+                    FredClient::default(),
+                    server_b.clone(),
+                )));
+            }
+
+            let permit_a = server_a.authorize_write(None).expect("unrestricted");
+            let op = RedisWriteOp::Set {
+                key: "k".to_string(),
+                value: "v".to_string(),
+            };
+            let set = || vec!["SET".to_string(), "k".to_string(), "v".to_string()];
+            assert!(matches!(
+                repo.apply_write(&permit_a, &op).await,
+                Err(Error::Invalid { .. })
+            ));
+            assert!(matches!(
+                repo.execute_command(set(), Some(&permit_a)).await,
+                Err(Error::Invalid { .. })
+            ));
+            assert!(matches!(
+                repo.execute_command(set(), None).await,
+                Err(Error::Invalid { .. })
+            ));
+            assert!(repo.check_write_permit(&permit_a).is_err());
+
+            let permit_b = server_b.authorize_write(None).expect("unrestricted");
+            assert!(repo.check_write_permit(&permit_b).is_ok());
+        });
+    }
+
     #[test]
     fn switching_client_does_not_wait_for_inflight_request() {
         crate::services::block_on(async {
             let (tx, _rx) = crossbeam_channel::unbounded();
-            let repo = Arc::new(RedisRepo::new(&RedisConfig::default(), tx).expect("repo"));
+            let repo = Arc::new(
+                RedisRepo::new(&RedisConfig::default(), Arc::new(SshTunnels::default()), tx)
+                    .expect("repo"),
+            );
 
             {
                 let mut guard = repo.client.write().await;
-                *guard = Some(Arc::new(ActiveRedisClient::new(FredClient::default())));
+                *guard = Some(Arc::new(ActiveRedisClient::new(
+                    FredClient::default(),
+                    DfcServerConfig::default(),
+                )));
             }
 
             let (started_tx, started_rx) = oneshot::channel();
@@ -1835,7 +4038,11 @@ mod tests {
 
             let swap_task = tokio::spawn(async move {
                 let result = swap_repo
-                    .store_connected_client(FredClient::default(), "next", "test")
+                    .store_connected_client(
+                        FredClient::default(),
+                        &DfcServerConfig::default(),
+                        "test",
+                    )
                     .await;
                 swapped_flag.store(true, Ordering::SeqCst);
                 result
@@ -1857,7 +4064,10 @@ mod tests {
     #[test]
     fn retired_client_forces_shutdown_after_grace_period() {
         crate::services::block_on(async {
-            let client_handle = Arc::new(ActiveRedisClient::new(FredClient::default()));
+            let client_handle = Arc::new(ActiveRedisClient::new(
+                FredClient::default(),
+                DfcServerConfig::default(),
+            ));
             let leaked_handle = client_handle.clone();
 
             tokio::time::timeout(
@@ -0,0 +1,147 @@
+//! Redis TLS
+//!
+//! Builds the rustls client config for Redis servers behind TLS: system or
+//! custom CA roots, optional client certificate, and a skip-verify mode for
+//! lab setups with self-signed certificates.
+
+use crate::connection::DfcServerConfig;
+use crate::error::{Error, Result};
+use fred::types::config::{TlsConfig, TlsConnector};
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::WebPkiSupportedAlgorithms;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime};
+use rustls::{ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme};
+use std::io::BufReader;
+use std::sync::Arc;
+
+/// TLS settings of `server`, or `None` for plain TCP
+pub(crate) fn redis_tls_config(server: &DfcServerConfig) -> Result<Option<TlsConfig>> {
+    if !server.tls {
+        return Ok(None);
+    }
+
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let builder = ClientConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .map_err(tls_error)?;
+
+    let builder = if server.tls_skip_verify {
+        tracing::warn!(
+            server = %server.display_name(),
+            "TLS certificate verification disabled"
+        );
+        builder
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(SkipServerVerification {
+                algorithms: provider.signature_verification_algorithms,
+            }))
+    } else {
+        builder.with_root_certificates(root_store(non_empty(&server.tls_ca_file))?)
+    };
+
+    let config = match (
+        non_empty(&server.tls_cert_file),
+        non_empty(&server.tls_key_file),
+    ) {
+        (Some(cert_file), Some(key_file)) => builder
+            .with_client_auth_cert(read_certs(cert_file)?, read_key(key_file)?)
+            .map_err(tls_error)?,
+        (None, None) => builder.with_no_client_auth(),
+        _ => {
+            return Err(Error::Invalid {
+                message: "TLS client certificate and key must be set together".to_string(),
+            });
+        }
+    };
+
+    Ok(Some(TlsConnector::from(config).into()))
+}
+
+fn non_empty(value: &Option<String>) -> Option<&str> {
+    value.as_deref().map(str::trim).filter(|v| !v.is_empty())
+}
+
+fn tls_error(e: rustls::Error) -> Error {
+    Error::Connection {
+        message: format!("TLS setup failed: {e}"),
+    }
+}
+
+/// Custom CA roots, or the platform's native roots
+fn root_store(ca_file: Option<&str>) -> Result<RootCertStore> {
+    let mut roots = RootCertStore::empty();
+    match ca_file {
+        Some(ca_file) => {
+            for cert in read_certs(ca_file)? {
+                roots.add(cert).map_err(tls_error)?;
+            }
+        }
+        None => {
+            let native = rustls_native_certs::load_native_certs();
+            for e in &native.errors {
+                tracing::warn!("Failed to load native certificate: {}", e);
+            }
+            roots.add_parsable_certificates(native.certs);
+        }
+    }
+    Ok(roots)
+}
+
+fn read_certs(path: &str) -> Result<Vec<CertificateDer<'static>>> {
+    let mut reader = BufReader::new(std::fs::File::open(path)?);
+    let certs = rustls_pemfile::certs(&mut reader).collect::<std::io::Result<Vec<_>>>()?;
+    if certs.is_empty() {
+        return Err(Error::Invalid {
+            message: format!("No certificate found in {path}"),
+        });
+    }
+    Ok(certs)
+}
+
+fn read_key(path: &str) -> Result<PrivateKeyDer<'static>> {
+    let mut reader = BufReader::new(std::fs::File::open(path)?);
+    rustls_pemfile::private_key(&mut reader)?.ok_or_else(|| Error::Invalid {
+        message: format!("No private key found in {path}"),
+    })
+}
+
+/// Accepts every server certificate but still checks handshake signatures
+#[derive(Debug)]
+struct SkipServerVerification {
+    algorithms: WebPkiSupportedAlgorithms,
+}
+
+impl ServerCertVerifier for SkipServerVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> std::result::Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> std::result::Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> std::result::Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.algorithms.supported_schemes()
+    }
+}
@@ -0,0 +1,699 @@
+//! SSH Tunnels
+//!
+//! Local port forwards through the SSH tunnel of a server. Each server gets
+//! one SSH session (over its jump-host chain) shared by all of its forwards;
+//! a forward listens on `127.0.0.1` and opens a `direct-tcpip` channel per
+//! accepted connection. Dropped sessions are re-established in the background
+//! and on the next accepted connection.
+//!
+//! Host keys are checked against `~/.ssh/known_hosts` and the app's own
+//! known hosts file; changed keys are rejected. The user is asked to trust
+//! the fingerprint of an unknown host, and trusted keys are saved to the
+//! app's file only.
+//!
+//! Servers without a tunnel fall back to their proxy forwards, and the first
+//! SSH hop itself is dialed through the proxy when one applies.
+
+use super::{ProxyForwards, connect_via};
+use crate::connection::{
+    DfcServerConfig, ProxyConfig, SshHop, SshTunnelConfig, SshTunnelStatus, url_host_port,
+};
+use crate::error::{Error, Result};
+use crate::helpers::get_or_create_config_dir;
+use russh::client::{self, Handle};
+use russh::keys::known_hosts::{check_known_hosts_path, learn_known_hosts_path};
+use russh::keys::{HashAlg, PrivateKey, PrivateKeyWithHashAlg, PublicKey};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, oneshot, watch};
+
+/// Interval of the background session check and reconnect
+const SSH_RETRY_SECS: u64 = 10;
+/// SSH keepalive interval; three missed replies drop the session
+const SSH_KEEPALIVE_SECS: u64 = 30;
+/// Timeout for establishing the whole jump-host chain
+const SSH_CONNECT_TIMEOUT_SECS: u64 = 20;
+/// Known hosts file of the app inside the config directory
+const KNOWN_HOSTS_FILE: &str = "known_hosts";
+
+/// SSH tunnels of all servers
+pub struct SshTunnels {
+    tunnels: Mutex<HashMap<String, Arc<ServerTunnel>>>,
+    proxies: Arc<ProxyForwards>,
+    /// Bumped whenever a tunnel status changes
+    revision: Arc<watch::Sender<u64>>,
+    host_keys: HostKeyPrompts,
+    /// Receiving end of the host key prompts, taken by the UI
+    host_key_prompts: Mutex<Option<mpsc::UnboundedReceiver<HostKeyPrompt>>>,
+}
+
+impl Default for SshTunnels {
+    fn default() -> Self {
+        Self::new(Arc::default())
+    }
+}
+
+impl SshTunnels {
+    pub fn new(proxies: Arc<ProxyForwards>) -> Self {
+        let (prompts, host_key_prompts) = mpsc::unbounded_channel();
+        Self {
+            tunnels: Mutex::new(HashMap::new()),
+            proxies,
+            revision: Arc::new(watch::channel(0).0),
+            host_keys: HostKeyPrompts {
+                prompts,
+                pending: Arc::default(),
+                rejected: Arc::default(),
+            },
+            host_key_prompts: Mutex::new(Some(host_key_prompts)),
+        }
+    }
+
+    /// Unknown host keys waiting for the user to trust them; only the first
+    /// caller gets the receiver
+    pub fn take_host_key_prompts(&self) -> Option<mpsc::UnboundedReceiver<HostKeyPrompt>> {
+        self.host_key_prompts.lock().ok()?.take()
+    }
+
+    /// Subscribe to tunnel status changes
+    pub fn revision(&self) -> watch::Receiver<u64> {
+        self.revision.subscribe()
+    }
+
+    /// Status of a server's tunnel, `None` if it has none running
+    pub fn status(&self, server_id: &str) -> Option<SshTunnelStatus> {
+        let tunnels = self.tunnels.lock().ok()?;
+        let tunnel = tunnels.get(server_id)?;
+        tunnel.status.lock().ok().map(|status| status.clone())
+    }
+
+    /// Whether connections of `server` to `host` go through a tunnel or proxy
+    pub fn routes(&self, server: &DfcServerConfig, host: &str) -> bool {
+        server.ssh_tunnel.is_some() || self.proxies.proxy_for(server, host).is_some()
+    }
+
+    /// Local address carrying `host:port` for `server`; the address itself
+    /// when the server has neither an SSH tunnel nor a proxy
+    pub async fn forward(
+        &self,
+        server: &DfcServerConfig,
+        host: &str,
+        port: u16,
+    ) -> Result<(String, u16)> {
+        let Some(tunnel) = self.tunnel(server) else {
+            return self.proxies.forward(server, host, port).await;
+        };
+        let host = host.to_string();
+        super::run_in_tokio(async move {
+            // Surface connect and auth errors to the caller right away
+            tunnel.session().await?;
+            let local_port = tunnel.listen(host, port).await?;
+            Ok(("127.0.0.1".to_string(), local_port))
+        })
+        .await
+    }
+
+    /// `url` with its authority replaced by a local forward; unchanged when
+    /// the server has neither an SSH tunnel nor a proxy
+    pub async fn forward_url(
+        &self,
+        server: &DfcServerConfig,
+        url: &str,
+        default_port: u16,
+    ) -> Result<String> {
+        let Some((scheme, host, port)) = url_host_port(url, default_port) else {
+            // Without a host, any proxy configured for the server counts
+            if server.ssh_tunnel.is_some() || self.proxies.proxy_for(server, "").is_some() {
+                return Err(Error::Invalid {
+                    message: format!("Cannot forward URL through SSH tunnel or proxy: {url}"),
+                });
+            }
+            return Ok(url.to_string());
+        };
+        if !self.routes(server, host) {
+            return Ok(url.to_string());
+        }
+        let (local_host, local_port) = self.forward(server, host, port).await?;
+        Ok(format!("{scheme}://{local_host}:{local_port}"))
+    }
+
+    /// Stop a server's tunnel and its forwards
+    pub fn close(&self, server_id: &str) {
+        let tunnel = self
+            .tunnels
+            .lock()
+            .ok()
+            .and_then(|mut tunnels| tunnels.remove(server_id));
+        if let Some(tunnel) = tunnel {
+            tunnel.stop();
+            self.bump();
+        }
+    }
+
+    /// Running tunnel of `server`, restarted if its settings changed
+    fn tunnel(&self, server: &DfcServerConfig) -> Option<Arc<ServerTunnel>> {
+        let Some(config) = server.ssh_tunnel.clone() else {
+            self.close(&server.id);
+            return None;
+        };
+        // The first hop is dialed through the proxy when one applies
+        let proxy = config
+            .hops()
+            .ok()
+            .and_then(|hops| hops.into_iter().next())
+            .and_then(|hop| self.proxies.proxy_for(server, &hop.host));
+        let mut tunnels = self.tunnels.lock().ok()?;
+        if let Some(existing) = tunnels.get(&server.id) {
+            if existing.config == config && existing.proxy == proxy {
+                return Some(existing.clone());
+            }
+            tracing::info!(server_id = %server.id, "SSH tunnel settings changed, restarting");
+            existing.stop();
+        }
+
+        let tunnel = Arc::new(ServerTunnel {
+            server_id: server.id.clone(),
+            config,
+            proxy,
+            session: tokio::sync::Mutex::new(None),
+            forwards: Mutex::new(HashMap::new()),
+            status: Mutex::new(SshTunnelStatus::Connecting),
+            stop: watch::channel(false).0,
+            revision: self.revision.clone(),
+            host_keys: self.host_keys.clone(),
+        });
+        tunnels.insert(server.id.clone(), tunnel.clone());
+        super::spawn_named_in_tokio("ssh-tunnel-monitor", tunnel.clone().monitor());
+        Some(tunnel)
+    }
+
+    fn bump(&self) {
+        self.revision.send_modify(|revision| *revision += 1);
+    }
+}
+
+/// SSH tunnel of one server
+struct ServerTunnel {
+    server_id: String,
+    config: SshTunnelConfig,
+    /// Proxy in front of the first hop
+    proxy: Option<ProxyConfig>,
+    /// Current session; reconnected when closed
+    session: tokio::sync::Mutex<Option<Arc<SshSession>>>,
+    /// Local port of each forwarded remote address
+    forwards: Mutex<HashMap<(String, u16), u16>>,
+    status: Mutex<SshTunnelStatus>,
+    stop: watch::Sender<bool>,
+    revision: Arc<watch::Sender<u64>>,
+    host_keys: HostKeyPrompts,
+}
+
+impl ServerTunnel {
+    fn stop(&self) {
+        let _ = self.stop.send(true);
+    }
+
+    fn set_status(&self, status: SshTunnelStatus) {
+        let changed = match self.status.lock() {
+            Ok(mut current) if *current != status => {
+                *current = status;
+                true
+            }
+            _ => false,
+        };
+        if changed {
+            self.revision.send_modify(|revision| *revision += 1);
+        }
+    }
+
+    /// Open session, connecting (again) if there is none or it dropped
+    async fn session(&self) -> Result<Arc<SshSession>> {
+        let mut session = self.session.lock().await;
+        if let Some(current) = session.as_ref() {
+            if !current.is_closed() {
+                return Ok(current.clone());
+            }
+            tracing::warn!(server_id = %self.server_id, "SSH session dropped, reconnecting");
+        }
+
+        self.set_status(SshTunnelStatus::Connecting);
+        let connected = tokio::time::timeout(
+            Duration::from_secs(SSH_CONNECT_TIMEOUT_SECS),
+            SshSession::connect(&self.config, self.proxy.as_ref(), &self.host_keys),
+        )
+        .await
+        .unwrap_or_else(|_| {
+            Err(Error::Connection {
+                message: "SSH connect timed out".to_string(),
+            })
+        });
+        match connected {
+            Ok(connected) => {
+                tracing::info!(server_id = %self.server_id, "SSH tunnel connected");
+                let connected = Arc::new(connected);
+                *session = Some(connected.clone());
+                self.set_status(SshTunnelStatus::Connected);
+                Ok(connected)
+            }
+            Err(e) => {
+                tracing::error!(server_id = %self.server_id, "SSH tunnel failed: {}", e);
+                *session = None;
+                self.set_status(SshTunnelStatus::Failed(e.to_string()));
+                Err(e)
+            }
+        }
+    }
+
+    /// Local port forwarding to `host:port`, started on first use
+    async fn listen(self: &Arc<Self>, host: String, port: u16) -> Result<u16> {
+        let key = (host.clone(), port);
+        if let Some(local_port) = self.forwards.lock().ok().and_then(|f| f.get(&key).copied()) {
+            return Ok(local_port);
+        }
+
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+        let local_port = listener.local_addr()?.port();
+        if let Ok(mut forwards) = self.forwards.lock() {
+            forwards.insert(key, local_port);
+        }
+        tracing::info!(
+            server_id = %self.server_id,
+            local_port,
+            remote = %format!("{host}:{port}"),
+            "Started SSH port forward"
+        );
+
+        let tunnel = self.clone();
+        let mut stop = self.stop.subscribe();
+        super::spawn_named_in_tokio("ssh-tunnel-forward", async move {
+            loop {
+                tokio::select! {
+                    _ = stop.changed() => break,
+                    accepted = listener.accept() => {
+                        let stream = match accepted {
+                            Ok((stream, _)) => stream,
+                            Err(e) => {
+                                tracing::warn!("SSH forward accept failed: {}", e);
+                                continue;
+                            }
+                        };
+                        let tunnel = tunnel.clone();
+                        let host = host.clone();
+                        tokio::spawn(async move {
+                            if let Err(e) = tunnel.pipe(stream, &host, port).await {
+                                tracing::warn!(
+                                    remote = %format!("{host}:{port}"),
+                                    "SSH forwarded connection failed: {}",
+                                    e
+                                );
+                            }
+                        });
+                    }
+                }
+            }
+        });
+        Ok(local_port)
+    }
+
+    /// Carry one local connection over a `direct-tcpip` channel
+    async fn pipe(&self, mut local: TcpStream, host: &str, port: u16) -> Result<()> {
+        let session = self.session().await?;
+        let channel = session
+            .last()?
+            .channel_open_direct_tcpip(host, u32::from(port), "127.0.0.1", 0)
+            .await
+            .map_err(ssh_error)?;
+        let mut remote = channel.into_stream();
+        tokio::io::copy_bidirectional(&mut local, &mut remote).await?;
+        Ok(())
+    }
+
+    /// Reconnect dropped sessions in the background; close the session on stop
+    async fn monitor(self: Arc<Self>) {
+        let mut stop = self.stop.subscribe();
+        loop {
+            tokio::select! {
+                _ = stop.changed() => break,
+                _ = tokio::time::sleep(Duration::from_secs(SSH_RETRY_SECS)) => {
+                    let has_forwards = self.forwards.lock().is_ok_and(|f| !f.is_empty());
+                    if has_forwards {
+                        let _ = self.session().await;
+                    }
+                }
+            }
+        }
+
+        if let Some(session) = self.session.lock().await.take() {
+            session.disconnect().await;
+        }
+        tracing::info!(server_id = %self.server_id, "SSH tunnel closed");
+    }
+}
+
+/// Sessions of a jump-host chain; the last one reaches the targets
+struct SshSession {
+    handles: Vec<Handle<KnownHostsClient>>,
+}
+
+impl SshSession {
+    async fn connect(
+        config: &SshTunnelConfig,
+        proxy: Option<&ProxyConfig>,
+        host_keys: &HostKeyPrompts,
+    ) -> Result<Self> {
+        let hops = config.hops()?;
+        let password = config.password.as_deref().filter(|p| !p.is_empty());
+        let key = match config.key_file.as_deref().map(str::trim) {
+            Some(path) if !path.is_empty() => Some(Arc::new(
+                russh::keys::load_secret_key(path, password).map_err(|e| Error::Invalid {
+                    message: format!("Failed to load SSH key {path}: {e}"),
+                })?,
+            )),
+            _ => None,
+        };
+        let ssh_config = Arc::new(client::Config {
+            keepalive_interval: Some(Duration::from_secs(SSH_KEEPALIVE_SECS)),
+            keepalive_max: 3,
+            ..Default::default()
+        });
+
+        let mut handles: Vec<Handle<KnownHostsClient>> = Vec::new();
+        for hop in hops {
+            let handler = KnownHostsClient {
+                host: hop.host.clone(),
+                port: hop.port,
+                host_keys: host_keys.clone(),
+            };
+            let mut handle = match (handles.last(), proxy) {
+                (None, None) => {
+                    client::connect(ssh_config.clone(), (hop.host.as_str(), hop.port), handler)
+                        .await
+                        .map_err(ssh_error)?
+                }
+                (None, Some(proxy)) => {
+                    let stream = connect_via(proxy, &hop.host, hop.port).await?;
+                    client::connect_stream(ssh_config.clone(), stream, handler)
+                        .await
+                        .map_err(ssh_error)?
+                }
+                (Some(previous), _) => {
+                    let channel = previous
+                        .channel_open_direct_tcpip(
+                            hop.host.as_str(),
+                            u32::from(hop.port),
+                            "127.0.0.1",
+                            0,
+                        )
+                        .await
+                        .map_err(ssh_error)?;
+                    client::connect_stream(ssh_config.clone(), channel.into_stream(), handler)
+                        .await
+                        .map_err(ssh_error)?
+                }
+            };
+            authenticate(&mut handle, &hop, key.clone(), password).await?;
+            handles.push(handle);
+        }
+        Ok(Self { handles })
+    }
+
+    fn last(&self) -> Result<&Handle<KnownHostsClient>> {
+        self.handles.last().ok_or_else(|| Error::Connection {
+            message: "SSH session has no hosts".to_string(),
+        })
+    }
+
+    fn is_closed(&self) -> bool {
+        self.handles.iter().any(|handle| handle.is_closed())
+    }
+
+    async fn disconnect(&self) {
+        for handle in self.handles.iter().rev() {
+            let _ = handle
+                .disconnect(russh::Disconnect::ByApplication, "", "en")
+                .await;
+        }
+    }
+}
+
+async fn authenticate(
+    handle: &mut Handle<KnownHostsClient>,
+    hop: &SshHop,
+    key: Option<Arc<PrivateKey>>,
+    password: Option<&str>,
+) -> Result<()> {
+    let result = match key {
+        Some(key) => {
+            let hash = handle
+                .best_supported_rsa_hash()
+                .await
+                .map_err(ssh_error)?
+                .flatten();
+            handle
+                .authenticate_publickey(&hop.user, PrivateKeyWithHashAlg::new(key, hash))
+                .await
+        }
+        None => {
+            handle
+                .authenticate_password(&hop.user, password.unwrap_or_default())
+                .await
+        }
+    }
+    .map_err(ssh_error)?;
+
+    if result.success() {
+        Ok(())
+    } else {
+        Err(Error::Connection {
+            message: format!(
+                "SSH authentication failed for {}@{}:{}",
+                hop.user, hop.host, hop.port
+            ),
+        })
+    }
+}
+
+fn ssh_error(e: russh::Error) -> Error {
+    Error::Connection {
+        message: format!("SSH: {e}"),
+    }
+}
+
+/// Unknown SSH host key waiting for the user to trust it. Dropping the
+/// prompt unanswered rejects the key.
+pub struct HostKeyPrompt {
+    pub host: String,
+    pub port: u16,
+    /// Key algorithm, e.g. `ssh-ed25519`
+    pub algorithm: String,
+    /// SHA256 fingerprint of the key
+    pub fingerprint: String,
+    key: PublicKey,
+    known_hosts: PathBuf,
+    reply: oneshot::Sender<bool>,
+    pending: PendingHostKey,
+}
+
+impl HostKeyPrompt {
+    /// Answer the prompt; a trusted key is saved to the app's known hosts
+    /// file, so it is also known to later connects if this one timed out
+    pub fn answer(self, trust: bool) {
+        let Self {
+            host,
+            port,
+            key,
+            known_hosts,
+            reply,
+            mut pending,
+            ..
+        } = self;
+        pending.trusted = trust;
+        super::spawn_named_in_tokio("ssh-host-key", async move {
+            if trust {
+                tracing::info!(host = %host, port, "Trusting SSH host key");
+                if let Err(e) = learn_known_hosts_path(&host, port, &key, &known_hosts) {
+                    tracing::warn!("Failed to save SSH host key: {}", e);
+                }
+            }
+            let _ = reply.send(trust);
+            drop(pending);
+        });
+    }
+}
+
+/// Sends unknown host keys to the UI, one prompt per host key at a time
+#[derive(Clone)]
+struct HostKeyPrompts {
+    prompts: mpsc::UnboundedSender<HostKeyPrompt>,
+    /// Host keys with an open prompt
+    pending: Arc<Mutex<HashSet<String>>>,
+    /// Host keys the user declined; not asked about again until restart
+    rejected: Arc<Mutex<HashSet<String>>>,
+}
+
+impl HostKeyPrompts {
+    /// Ask the user to trust `key` of `host:port`; false when declined, or
+    /// when a prompt for the key is already open
+    async fn confirm(&self, host: &str, port: u16, key: &PublicKey, known_hosts: PathBuf) -> bool {
+        let fingerprint = key.fingerprint(HashAlg::Sha256).to_string();
+        let id = format!("[{host}]:{port} {fingerprint}");
+        if self
+            .rejected
+            .lock()
+            .is_ok_and(|rejected| rejected.contains(&id))
+        {
+            tracing::warn!(host, port, "SSH host key was declined");
+            return false;
+        }
+        if !self
+            .pending
+            .lock()
+            .is_ok_and(|mut pending| pending.insert(id.clone()))
+        {
+            tracing::info!(host, port, "SSH host key already awaits confirmation");
+            return false;
+        }
+
+        let (reply, answer) = oneshot::channel();
+        let prompt = HostKeyPrompt {
+            host: host.to_string(),
+            port,
+            algorithm: key.algorithm().to_string(),
+            fingerprint,
+            key: key.clone(),
+            known_hosts,
+            reply,
+            pending: PendingHostKey {
+                id,
+                trusted: false,
+                pending: self.pending.clone(),
+                rejected: self.rejected.clone(),
+            },
+        };
+        if self.prompts.send(prompt).is_err() {
+            tracing::warn!(host, port, "No one to confirm the SSH host key");
+            return false;
+        }
+        tracing::info!(host, port, "Waiting for the SSH host key to be trusted");
+        answer.await.unwrap_or(false)
+    }
+}
+
+/// Open prompt of a host key; closed on drop, which declines the key
+/// unless it was trusted
+struct PendingHostKey {
+    id: String,
+    trusted: bool,
+    pending: Arc<Mutex<HashSet<String>>>,
+    rejected: Arc<Mutex<HashSet<String>>>,
+}
+
+impl Drop for PendingHostKey {
+    fn drop(&mut self) {
+        if let Some(mut rejected) = self.rejected.lock().ok().filter(|_| !self.trusted) {
+            rejected.insert(self.id.clone());
+        }
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.remove(&self.id);
+        }
+    }
+}
+
+/// Checks host keys against `~/.ssh/known_hosts` and the app's known hosts
+/// file, and asks the user about unknown ones
+struct KnownHostsClient {
+    host: String,
+    port: u16,
+    host_keys: HostKeyPrompts,
+}
+
+impl client::Handler for KnownHostsClient {
+    type Error = russh::Error;
+
+    async fn check_server_key(
+        &mut self,
+        server_public_key: &PublicKey,
+    ) -> std::result::Result<bool, Self::Error> {
+        let known_hosts = match get_or_create_config_dir() {
+            Ok(dir) => dir.join(KNOWN_HOSTS_FILE),
+            Err(e) => {
+                tracing::error!("No known hosts file for SSH host keys: {}", e);
+                return Ok(false);
+            }
+        };
+        let known = russh::keys::check_known_hosts(&self.host, self.port, server_public_key)
+            .and_then(|known| {
+                Ok(known
+                    || check_known_hosts_path(
+                        &self.host,
+                        self.port,
+                        server_public_key,
+                        &known_hosts,
+                    )?)
+            });
+        match known {
+            Ok(true) => Ok(true),
+            Ok(false) => Ok(self
+                .host_keys
+                .confirm(&self.host, self.port, server_public_key, known_hosts)
+                .await),
+            Err(e) => {
+                tracing::error!(
+                    host = %self.host,
+                    port = self.port,
+                    "SSH host key rejected: {}",
+                    e
+                );
+                Ok(false)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOST_KEY: &str =
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAICem2ADG3ck22hyGQ5CaPeZE6WSOYCJD7a3tDsw6+Kux";
+
+    #[test]
+    fn declined_host_keys_are_not_asked_about_again() {
+        let tunnels = SshTunnels::default();
+        let mut prompts = tunnels
+            .take_host_key_prompts()
+            .expect("take host key prompts");
+        assert!(tunnels.take_host_key_prompts().is_none());
+        let key = PublicKey::from_openssh(HOST_KEY).expect("parse host key");
+        let known_hosts = std::env::temp_dir().join("dfc-known-hosts-unused");
+
+        let (trusted, ()) = futures::executor::block_on(async {
+            futures::join!(
+                tunnels
+                    .host_keys
+                    .confirm("bastion", 22, &key, known_hosts.clone()),
+                async {
+                    let prompt = prompts.recv().await.expect("receive host key prompt");
+                    assert_eq!((prompt.host.as_str(), prompt.port), ("bastion", 22));
+                    assert_eq!(prompt.algorithm, "ssh-ed25519");
+                    assert!(prompt.fingerprint.starts_with("SHA256:"));
+                    // Closing the dialog drops the prompt unanswered
+                    drop(prompt);
+                }
+            )
+        });
+        assert!(!trusted);
+
+        let trusted = futures::executor::block_on(tunnels.host_keys.confirm(
+            "bastion",
+            22,
+            &key,
+            known_hosts,
+        ));
+        assert!(!trusted);
+        assert!(prompts.try_recv().is_err());
+    }
+}
@@ -3,13 +3,23 @@
 //! Global application state including routing, theme, locale, and window bounds.
 
 use crate::connection::{
-    DfcServerConfig, EncryptedPresetCredential, PresetCredential, get_servers, save_servers,
+    DfcServerConfig, EncryptedPresetCredential, ImportCandidate, ImportSummary, PresetCredential,
+    ProxyConfig, ServerSortOrder, ValueFormat, WorkingCredential, apply_import,
+    encode_servers_for_key, get_servers, group_key, merge_preset_credentials, save_servers,
+    save_servers_blocking, servers_have_legacy_secrets, stage_encoded_servers,
 };
-use crate::error::Result;
+use crate::constants::DEFAULT_KEY_TREE_DELIMITERS;
+use crate::error::{Error, Result};
 use crate::helpers::get_or_create_config_dir;
-use crate::helpers::{decrypt, encrypt};
+use crate::helpers::{
+    StagedFile, decrypt, encrypt, encrypt_for_vault, install_vault_key, is_legacy_cipher_text,
+    new_vault_key, vault_locked,
+};
 use crate::services::ServiceHub;
-use crate::states::{ConfigState, FleetState, KeysState};
+use crate::states::{
+    ConfigState, EventHistoryConfig, FleetState, KeysState, PROP_HISTORY_MAX_ROWS,
+    remove_event_history,
+};
 use chrono::Local;
 use gpui::{Action, App, AppContext, Bounds, Context, Entity, Global, Pixels};
 use gpui_component::ThemeMode;
@@ -17,6 +27,7 @@ use locale_config::Locale;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::PathBuf;
 use std::rc::Rc;
 use tracing::{error, info};
@@ -67,6 +78,15 @@ pub enum HomeLayoutMode {
     List,
 }
 
+/// Keys browser list mode
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyViewMode {
+    #[default]
+    Flat,
+    Tree,
+}
+
 // ==================== Actions ====================
 
 /// Theme selection action
@@ -122,11 +142,38 @@ pub struct DfcAppState {
     font_size: Option<FontSize>,
     #[serde(default)]
     home_layout_mode: HomeLayoutMode,
+    /// Order of the servers on the home page
+    #[serde(default)]
+    server_sort: ServerSortOrder,
+    /// Server groups shown collapsed on the home page, by `group_key`
+    #[serde(default)]
+    collapsed_groups: BTreeSet<String>,
+    /// Keys browser list mode
+    #[serde(default)]
+    key_view_mode: KeyViewMode,
+    /// Delimiter characters of the key namespace tree
+    #[serde(default)]
+    key_tree_delimiters: Option<String>,
+    /// Value formatter chosen per key pattern
+    #[serde(default)]
+    value_formats: BTreeMap<String, ValueFormat>,
     /// Selected device ID
     selected_device: Option<String>,
     /// Preset credentials (encrypted)
     #[serde(default)]
     preset_credentials: Vec<EncryptedPresetCredential>,
+    /// Credential that last connected, per server ID
+    #[serde(default)]
+    working_credentials: BTreeMap<String, WorkingCredential>,
+    /// Global proxy for Redis, Pulsar and update checks (encrypted password)
+    #[serde(default)]
+    proxy: Option<ProxyConfig>,
+    /// On-disk event history per server and topic
+    #[serde(default)]
+    event_history: EventHistoryConfig,
+    /// Samples kept in the prop history per topic; unset uses the default
+    #[serde(default)]
+    prop_history_max_rows: Option<usize>,
     /// Version tag skipped by the user
     #[serde(default)]
     skipped_version: Option<String>,
@@ -193,6 +240,29 @@ impl DfcAppState {
         self.home_layout_mode
     }
 
+    pub fn server_sort(&self) -> ServerSortOrder {
+        self.server_sort
+    }
+
+    pub fn is_group_collapsed(&self, group: &str) -> bool {
+        self.collapsed_groups.contains(&group_key(group))
+    }
+
+    pub fn key_view_mode(&self) -> KeyViewMode {
+        self.key_view_mode
+    }
+
+    pub fn key_tree_delimiters(&self) -> &str {
+        self.key_tree_delimiters
+            .as_deref()
+            .filter(|d| !d.is_empty())
+            .unwrap_or(DEFAULT_KEY_TREE_DELIMITERS)
+    }
+
+    pub fn value_format(&self, key_pattern: &str) -> Option<ValueFormat> {
+        self.value_formats.get(key_pattern).copied()
+    }
+
     pub fn theme(&self) -> Option<ThemeMode> {
         match self.theme.as_deref() {
             Some(LIGHT_THEME_MODE) => Some(ThemeMode::Light),
@@ -231,6 +301,30 @@ impl DfcAppState {
             .collect()
     }
 
+    /// Credential that last connected to a server
+    pub fn working_credential(&self, server_id: &str) -> Option<&WorkingCredential> {
+        self.working_credentials.get(server_id)
+    }
+
+    pub fn event_history(&self) -> &EventHistoryConfig {
+        &self.event_history
+    }
+
+    pub fn prop_history_max_rows(&self) -> usize {
+        self.prop_history_max_rows
+            .unwrap_or(PROP_HISTORY_MAX_ROWS)
+            .max(1)
+    }
+
+    /// Get the global proxy (decrypted)
+    pub fn proxy(&self) -> Option<ProxyConfig> {
+        let mut proxy = self.proxy.clone()?;
+        if let Some(password) = proxy.password.as_mut() {
+            *password = decrypt(password).unwrap_or_else(|_| password.clone());
+        }
+        Some(proxy)
+    }
+
     // ==================== Setters ====================
 
     pub fn go_to(&mut self, route: Route, cx: &mut Context<Self>) {
@@ -264,6 +358,29 @@ impl DfcAppState {
         self.home_layout_mode = home_layout_mode;
     }
 
+    pub fn set_server_sort(&mut self, server_sort: ServerSortOrder) {
+        self.server_sort = server_sort;
+    }
+
+    pub fn toggle_group_collapsed(&mut self, group: &str) {
+        let key = group_key(group);
+        if !self.collapsed_groups.remove(&key) {
+            self.collapsed_groups.insert(key);
+        }
+    }
+
+    pub fn set_key_view_mode(&mut self, key_view_mode: KeyViewMode) {
+        self.key_view_mode = key_view_mode;
+    }
+
+    pub fn set_key_tree_delimiters(&mut self, delimiters: String) {
+        self.key_tree_delimiters = Some(delimiters).filter(|d| !d.is_empty());
+    }
+
+    pub fn set_value_format(&mut self, key_pattern: String, format: ValueFormat) {
+        self.value_formats.insert(key_pattern, format);
+    }
+
     pub fn set_selected_device(&mut self, device_id: Option<String>) {
         self.selected_device = device_id;
     }
@@ -276,18 +393,35 @@ impl DfcAppState {
         self.last_update_check = Some(ts);
     }
 
-    /// Set preset credentials (will be encrypted)
-    pub fn set_preset_credentials(&mut self, credentials: Vec<PresetCredential>) {
-        self.preset_credentials = credentials
-            .into_iter()
-            .filter_map(|cred| {
-                let password = encrypt(&cred.password).ok()?;
-                Some(EncryptedPresetCredential {
-                    username: cred.username,
-                    password,
-                })
-            })
-            .collect();
+    pub fn set_event_history(&mut self, event_history: EventHistoryConfig) {
+        self.event_history = event_history;
+    }
+
+    pub fn set_prop_history_max_rows(&mut self, max_rows: usize) {
+        self.prop_history_max_rows = Some(max_rows.max(1));
+    }
+
+    pub fn set_working_credential(&mut self, server_id: String, credential: WorkingCredential) {
+        self.working_credentials.insert(server_id, credential);
+    }
+
+    /// Set preset credentials (will be encrypted); fails without changing
+    /// anything while the vault is locked. Remembered working credentials
+    /// pointing at an edited, removed or moved preset are forgotten.
+    pub fn set_preset_credentials(&mut self, credentials: Vec<PresetCredential>) -> Result<()> {
+        let sealed = seal_preset_credentials(credentials.clone(), encrypt)?;
+        let previous = self.preset_credentials();
+        self.working_credentials
+            .retain(|_, credential| credential.survives_preset_change(&previous, &credentials));
+        self.preset_credentials = sealed;
+        Ok(())
+    }
+
+    /// Set the global proxy (password will be encrypted); an empty URL clears it.
+    /// Fails without changing anything while the vault is locked.
+    pub fn set_proxy(&mut self, proxy: Option<ProxyConfig>) -> Result<()> {
+        self.proxy = seal_proxy(proxy, encrypt)?;
+        Ok(())
     }
 
     // ==================== Server Management ====================
@@ -362,6 +496,7 @@ impl DfcAppState {
     /// Remove a server by ID
     pub fn remove_server(&mut self, id: &str, cx: &mut Context<Self>) {
         self.servers.retain(|s| s.id != id);
+        self.working_credentials.remove(id);
 
         // Clear selection if removed server was selected
         if self.selected_server_id.as_deref() == Some(id) {
@@ -370,23 +505,174 @@ impl DfcAppState {
 
         // Save asynchronously
         let servers = self.servers.clone();
-        cx.spawn(async move |_, _| {
+        let server_id = id.to_string();
+        cx.spawn(async move |_, cx| {
             if let Err(e) = save_servers(servers).await {
                 error!(error = %e, "Failed to save servers after removal");
             } else {
                 info!("Server removed and saved");
             }
+            let removed = cx
+                .background_spawn(async move { remove_event_history(&server_id) })
+                .await;
+            if let Err(e) = removed {
+                error!(error = %e, "Failed to delete event history of removed server");
+            }
         })
         .detach();
 
         cx.notify();
     }
 
+    /// Apply the resolutions of an import plan to the server list and save it
+    pub fn import_servers(
+        &mut self,
+        candidates: Vec<ImportCandidate>,
+        cx: &mut Context<Self>,
+    ) -> ImportSummary {
+        let summary = apply_import(&mut self.servers, candidates, &Local::now().to_rfc3339());
+
+        let servers = self.servers.clone();
+        cx.spawn(async move |_, _| {
+            if let Err(e) = save_servers(servers).await {
+                error!(error = %e, "Failed to save servers after import");
+            } else {
+                info!(
+                    added = summary.added,
+                    replaced = summary.replaced,
+                    skipped = summary.skipped,
+                    "Servers imported and saved"
+                );
+            }
+        })
+        .detach();
+
+        cx.notify();
+        summary
+    }
+
+    /// Add the imported preset credentials not known yet and save them;
+    /// returns how many were added
+    pub fn import_preset_credentials(&mut self, imported: &[PresetCredential]) -> Result<usize> {
+        let mut credentials = self.preset_credentials();
+        let added = merge_preset_credentials(&mut credentials, imported);
+        if added > 0 {
+            self.set_preset_credentials(credentials)?;
+            save_app_state(self)?;
+        }
+        Ok(added)
+    }
+
+    /// Whether the credential that last connected to a server can become
+    /// its own password (a preset credential without an ACL user). Never
+    /// while the vault is locked, as presets only show cipher text then.
+    pub fn can_promote_working_credential(&self, server_id: &str) -> bool {
+        !vault_locked()
+            && matches!(
+                self.working_credential(server_id),
+                Some(WorkingCredential::Preset { index, username: None })
+                    if self.preset_credentials.get(*index).is_some_and(|cred| cred.username.is_none())
+            )
+    }
+
+    /// Store the preset credential that last connected to a server as the
+    /// server's own password and save both
+    pub fn promote_working_credential(
+        &mut self,
+        server_id: &str,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        if vault_locked() {
+            return Err(Error::Invalid {
+                message: "The credential vault is locked; unlock it in Settings".to_string(),
+            });
+        }
+        let presets = self.preset_credentials();
+        let password = self
+            .working_credential(server_id)
+            .and_then(|credential| credential.promotable_password(&presets))
+            .map(str::to_string);
+        let (Some(password), Some(mut server)) = (password, self.server(server_id).cloned()) else {
+            return Err(Error::Invalid {
+                message: "The working credential cannot become the server password".to_string(),
+            });
+        };
+        server.password = Some(password);
+        self.upsert_server(server, cx);
+        self.set_working_credential(server_id.to_string(), WorkingCredential::ServerPassword);
+        save_app_state(self)
+    }
+
     /// Select a server
     pub fn select_server(&mut self, id: Option<String>, cx: &mut Context<Self>) {
         self.selected_server_id = id;
         cx.notify();
     }
+
+    // ==================== Credential Vault ====================
+
+    /// Re-encrypt secrets still stored with the pre-vault built-in key.
+    /// Does nothing while the vault is locked.
+    pub fn migrate_legacy_secrets(&mut self) {
+        if vault_locked() {
+            return;
+        }
+        let legacy_state = self
+            .preset_credentials
+            .iter()
+            .any(|cred| is_legacy_cipher_text(&cred.password))
+            || self
+                .proxy
+                .as_ref()
+                .and_then(|proxy| proxy.password.as_deref())
+                .is_some_and(is_legacy_cipher_text);
+        if legacy_state {
+            let sealed = self
+                .set_preset_credentials(self.preset_credentials())
+                .and_then(|()| self.set_proxy(self.proxy()))
+                .and_then(|()| save_app_state(self));
+            match sealed {
+                Ok(()) => info!("Re-encrypted app state secrets with the vault key"),
+                Err(e) => error!(error = %e, "Failed to re-encrypt app state secrets"),
+            }
+        }
+        match servers_have_legacy_secrets() {
+            Ok(true) => match save_servers_blocking(self.servers.clone()) {
+                Ok(()) => info!("Re-encrypted server secrets with the vault key"),
+                Err(e) => error!(error = %e, "Failed to re-encrypt server secrets"),
+            },
+            Ok(false) => {}
+            Err(e) => error!(error = %e, "Failed to check server secrets"),
+        }
+    }
+
+    /// Switch the vault to `passphrase` (random key file with `None`) and
+    /// rewrite every stored secret with the new key. Both re-sealed files are
+    /// written beside the current ones before the key is switched, so a
+    /// failed write leaves the vault and the stored secrets as they were.
+    pub fn change_vault_key(&mut self, passphrase: Option<&str>) -> Result<()> {
+        let new_key = new_vault_key(passphrase)?;
+        let seal = |plain: &str| encrypt_for_vault(new_key.key(), plain);
+        let mut sealed = self.clone();
+        sealed.preset_credentials = seal_preset_credentials(self.preset_credentials(), seal)?;
+        sealed.proxy = seal_proxy(self.proxy(), seal)?;
+        let app_state = StagedFile::write(get_config_path()?, &toml::to_string(&sealed)?)?;
+        let servers = encode_servers_for_key(self.servers.clone(), new_key.key())?;
+        let servers = stage_encoded_servers(&servers)?;
+
+        install_vault_key(new_key)?;
+        self.preset_credentials = sealed.preset_credentials;
+        self.proxy = sealed.proxy;
+        app_state.commit()?;
+        servers.commit()
+    }
+
+    /// Reload server secrets after the vault was unlocked or locked
+    pub fn reload_vault_secrets(&mut self, cx: &mut Context<Self>) {
+        self.load_servers();
+        self.migrate_legacy_secrets();
+        cx.notify();
+    }
 }
 
 // ==================== Global Store ====================
@@ -480,6 +766,36 @@ impl Global for DfcGlobalStore {}
 
 // ==================== Persistence ====================
 
+/// Encrypt preset credential passwords with `seal`
+fn seal_preset_credentials(
+    credentials: Vec<PresetCredential>,
+    seal: impl Fn(&str) -> Result<String>,
+) -> Result<Vec<EncryptedPresetCredential>> {
+    credentials
+        .into_iter()
+        .map(|cred| {
+            Ok(EncryptedPresetCredential {
+                password: seal(&cred.password)?,
+                username: cred.username,
+            })
+        })
+        .collect()
+}
+
+/// Encrypt the proxy password with `seal`; an empty URL clears the proxy
+fn seal_proxy(
+    proxy: Option<ProxyConfig>,
+    seal: impl Fn(&str) -> Result<String>,
+) -> Result<Option<ProxyConfig>> {
+    let Some(mut proxy) = proxy.filter(|proxy| !proxy.url.trim().is_empty()) else {
+        return Ok(None);
+    };
+    if let Some(password) = proxy.password.as_mut().filter(|p| !p.is_empty()) {
+        *password = seal(password)?;
+    }
+    Ok(Some(proxy))
+}
+
 /// Save app state to disk
 pub fn save_app_state(state: &DfcAppState) -> Result<()> {
     let path = get_config_path()?;
@@ -500,6 +500,35 @@ impl ConfigState {
         cx.notify();
     }
 
+    /// Replace the configs of a loaded server session after a live change,
+    /// keeping its agent and topic selection and the active server.
+    /// Returns false if the session is gone or being reloaded.
+    pub fn refresh_configs_for_server(
+        &mut self,
+        server_id: &str,
+        configs: Vec<ConfigItem>,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        if !self.apply_refreshed_configs(server_id, configs) {
+            return false;
+        }
+        cx.notify();
+        true
+    }
+
+    fn apply_refreshed_configs(&mut self, server_id: &str, configs: Vec<ConfigItem>) -> bool {
+        let Some(session) = self.sessions.get_mut(server_id) else {
+            return false;
+        };
+        if !session.load_state.is_loaded() || session.pending_request_id.is_some() {
+            return false;
+        }
+        let topic_sync_enabled = session.topic_sync_enabled;
+        Self::apply_configs_for_session(session, configs);
+        session.topic_sync_enabled = topic_sync_enabled;
+        true
+    }
+
     /// Set loading state
     pub fn set_loading(&mut self, cx: &mut Context<Self>) {
         self.current_session_mut().load_state = ConfigLoadState::Loading;
@@ -853,6 +882,41 @@ mod tests {
         assert_eq!(agents[0].topics[0].index, 1);
     }
 
+    #[test]
+    fn refresh_configs_keeps_selection_and_active_server() {
+        let mut state = ConfigState::new();
+        let agents = |topics: Vec<(i32, &str, bool, &str)>| {
+            vec![
+                make_config(
+                    1,
+                    vec![make_agent("A", vec![(0, "/a/x", true, "event")], 1)],
+                ),
+                make_config(2, vec![make_agent("B", topics, 2)]),
+            ]
+        };
+        ConfigState::apply_configs_for_session(
+            state.session_for_server_mut("s1"),
+            agents(vec![(0, "/b/x", true, "event"), (1, "/b/y", true, "event")]),
+        );
+        state.active_server_id = Some("s1".to_string());
+        assert!(state.apply_agent_selection(Some("B".to_string())));
+        state.current_session_mut().selected_topic_index = Some(1);
+        state.active_server_id = Some("s2".to_string());
+
+        // "/b/y" moved to the front and a topic was added
+        assert!(state.apply_refreshed_configs(
+            "s1",
+            agents(vec![(0, "/b/y", true, "event"), (1, "/b/z", true, "event")]),
+        ));
+        assert!(!state.apply_refreshed_configs("missing", Vec::new()));
+
+        assert_eq!(state.active_server_id(), Some("s2"));
+        state.active_server_id = Some("s1".to_string());
+        assert_eq!(state.selected_agent_id(), Some("B"));
+        assert_eq!(state.selected_topic_index(), Some(0));
+        assert_eq!(state.selected_agent().map(|a| a.topics.len()), Some(2));
+    }
+
     #[test]
     fn set_configs_initializes_selection() {
         let mut state = ConfigState::new();
@@ -0,0 +1,206 @@
+//! Console State
+//!
+//! Command history and transcript of the Redis console, kept per server.
+//! History survives restarts; transcripts only live for the session.
+
+use crate::connection::{RedisReply, has_credentials, parse_command_line};
+use crate::error::Result;
+use crate::helpers::get_or_create_data_dir;
+use gpui::Context;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// File name of the persisted command history inside the data directory
+const CONSOLE_HISTORY_FILE: &str = "console-history.json";
+
+/// Maximum number of remembered commands per server
+pub const CONSOLE_HISTORY_LIMIT: usize = 200;
+
+/// Maximum number of transcript entries kept per server
+const CONSOLE_TRANSCRIPT_LIMIT: usize = 100;
+
+/// One executed command and its reply
+#[derive(Debug, Clone)]
+pub struct ConsoleEntry {
+    /// Unique ID within the session, stable while older entries are dropped
+    pub id: u64,
+    /// Command line as typed
+    pub command: String,
+    pub reply: RedisReply,
+    /// Round-trip time in milliseconds (0 if rejected locally)
+    pub elapsed_ms: u64,
+}
+
+/// Push a command onto a history, skipping repeats and capping its length
+fn push_history_line(history: &mut Vec<String>, command: &str) {
+    if history.last().is_some_and(|last| last == command) {
+        return;
+    }
+    history.push(command.to_string());
+    if history.len() > CONSOLE_HISTORY_LIMIT {
+        let overflow = history.len() - CONSOLE_HISTORY_LIMIT;
+        history.drain(..overflow);
+    }
+}
+
+/// Console state shared by the console view
+pub struct ConsoleState {
+    /// Command history by server ID (oldest first)
+    histories: HashMap<String, Vec<String>>,
+    /// Session transcript by server ID (oldest first)
+    transcripts: HashMap<String, Vec<ConsoleEntry>>,
+    /// Reject write commands even on servers marked writable
+    read_only: bool,
+    /// Whether a command is in flight
+    running: bool,
+    /// ID of the next transcript entry
+    next_entry_id: u64,
+}
+
+impl ConsoleState {
+    /// Create the state, loading the persisted history
+    pub fn new() -> Self {
+        let mut histories: HashMap<String, Vec<String>> = Self::history_path()
+            .and_then(|path| {
+                if !path.exists() {
+                    return Ok(HashMap::new());
+                }
+                let content = std::fs::read_to_string(path)?;
+                Ok(serde_json::from_str(&content)?)
+            })
+            .unwrap_or_else(|e| {
+                tracing::warn!("Failed to load console history: {}", e);
+                HashMap::new()
+            });
+        // Older versions also saved lines that carry a password
+        for history in histories.values_mut() {
+            history
+                .retain(|line| !parse_command_line(line).is_ok_and(|args| has_credentials(&args)));
+        }
+
+        Self {
+            histories,
+            transcripts: HashMap::new(),
+            read_only: true,
+            running: false,
+            next_entry_id: 0,
+        }
+    }
+
+    fn history_path() -> Result<PathBuf> {
+        Ok(get_or_create_data_dir()?.join(CONSOLE_HISTORY_FILE))
+    }
+
+    fn save_history(&self) {
+        let result = Self::history_path().and_then(|path| {
+            std::fs::write(path, serde_json::to_string(&self.histories)?)?;
+            Ok(())
+        });
+        if let Err(e) = result {
+            tracing::error!("Failed to save console history: {}", e);
+        }
+    }
+
+    /// Command history of a server (oldest first)
+    pub fn history(&self, server_id: &str) -> &[String] {
+        self.histories
+            .get(server_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Transcript of a server (oldest first)
+    pub fn transcript(&self, server_id: &str) -> &[ConsoleEntry] {
+        self.transcripts
+            .get(server_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Record a command in the server's history and persist it
+    pub fn push_history(&mut self, server_id: &str, command: &str, cx: &mut Context<Self>) {
+        let history = self.histories.entry(server_id.to_string()).or_default();
+        push_history_line(history, command);
+        self.save_history();
+        cx.notify();
+    }
+
+    /// Append an executed command to the server's transcript
+    pub fn push_entry(
+        &mut self,
+        server_id: &str,
+        command: String,
+        reply: RedisReply,
+        elapsed_ms: u64,
+        cx: &mut Context<Self>,
+    ) {
+        let id = self.next_entry_id;
+        self.next_entry_id += 1;
+        let transcript = self.transcripts.entry(server_id.to_string()).or_default();
+        transcript.push(ConsoleEntry {
+            id,
+            command,
+            reply,
+            elapsed_ms,
+        });
+        if transcript.len() > CONSOLE_TRANSCRIPT_LIMIT {
+            let overflow = transcript.len() - CONSOLE_TRANSCRIPT_LIMIT;
+            transcript.drain(..overflow);
+        }
+        cx.notify();
+    }
+
+    /// Clear the server's transcript (history is kept)
+    pub fn clear_transcript(&mut self, server_id: &str, cx: &mut Context<Self>) {
+        self.transcripts.remove(server_id);
+        cx.notify();
+    }
+
+    /// Whether writes are rejected by the console toggle
+    pub fn read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Set the read-only toggle
+    pub fn set_read_only(&mut self, read_only: bool, cx: &mut Context<Self>) {
+        self.read_only = read_only;
+        cx.notify();
+    }
+
+    /// Whether a command is in flight
+    pub fn running(&self) -> bool {
+        self.running
+    }
+
+    /// Mark a command as in flight or finished
+    pub fn set_running(&mut self, running: bool, cx: &mut Context<Self>) {
+        self.running = running;
+        cx.notify();
+    }
+}
+
+impl Default for ConsoleState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_history_line_skips_repeats_and_caps_length() {
+        let mut history = Vec::new();
+        push_history_line(&mut history, "GET a");
+        push_history_line(&mut history, "GET a");
+        push_history_line(&mut history, "GET b");
+        assert_eq!(history, vec!["GET a", "GET b"]);
+
+        for ix in 0..CONSOLE_HISTORY_LIMIT {
+            push_history_line(&mut history, &format!("GET {ix}"));
+        }
+        assert_eq!(history.len(), CONSOLE_HISTORY_LIMIT);
+        assert_eq!(history[0], "GET 0");
+    }
+}
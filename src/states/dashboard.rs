@@ -0,0 +1,127 @@
+//! Dashboard State
+//!
+//! Latest server dashboard snapshot of the active Redis server and its
+//! refresh status.
+
+use crate::connection::RedisDashboard;
+use chrono::{DateTime, Local};
+use gpui::Context;
+use std::sync::Arc;
+
+/// Number of SLOWLOG entries fetched per refresh
+pub const DASHBOARD_SLOWLOG_LEN: usize = 32;
+
+/// Seconds between automatic dashboard refreshes
+pub const DASHBOARD_REFRESH_SECS: u64 = 5;
+
+/// Dashboard state of the active server
+pub struct DashboardState {
+    /// Server the snapshot belongs to
+    server_id: Option<String>,
+    /// Latest snapshot
+    dashboard: Option<RedisDashboard>,
+    /// Time the snapshot was taken
+    refreshed_at: Option<DateTime<Local>>,
+    /// Error of the last failed refresh
+    error: Option<Arc<str>>,
+    /// Whether a refresh is in flight
+    loading: bool,
+    /// Whether the dashboard refreshes periodically
+    auto_refresh: bool,
+    /// Monotonic generation for async refresh requests
+    generation: u64,
+}
+
+impl DashboardState {
+    /// Create an empty state with auto refresh enabled
+    pub fn new() -> Self {
+        Self {
+            server_id: None,
+            dashboard: None,
+            refreshed_at: None,
+            error: None,
+            loading: false,
+            auto_refresh: true,
+            generation: 0,
+        }
+    }
+
+    /// Server the snapshot belongs to
+    pub fn server_id(&self) -> Option<&str> {
+        self.server_id.as_deref()
+    }
+
+    /// Latest snapshot
+    pub fn dashboard(&self) -> Option<&RedisDashboard> {
+        self.dashboard.as_ref()
+    }
+
+    /// Time the snapshot was taken
+    pub fn refreshed_at(&self) -> Option<DateTime<Local>> {
+        self.refreshed_at
+    }
+
+    /// Error of the last failed refresh
+    pub fn error(&self) -> Option<&Arc<str>> {
+        self.error.as_ref()
+    }
+
+    /// Whether a refresh is in flight
+    pub fn is_loading(&self) -> bool {
+        self.loading
+    }
+
+    /// Whether the dashboard refreshes periodically
+    pub fn auto_refresh(&self) -> bool {
+        self.auto_refresh
+    }
+
+    /// Enable or disable periodic refresh
+    pub fn set_auto_refresh(&mut self, auto_refresh: bool, cx: &mut Context<Self>) {
+        self.auto_refresh = auto_refresh;
+        cx.notify();
+    }
+
+    /// Current request generation
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Start a refresh for a server and return its generation.
+    ///
+    /// Switching servers drops the previous server's snapshot.
+    pub fn begin_refresh(&mut self, server_id: String, cx: &mut Context<Self>) -> u64 {
+        if self.server_id.as_deref() != Some(server_id.as_str()) {
+            self.dashboard = None;
+            self.refreshed_at = None;
+            self.error = None;
+        }
+        self.server_id = Some(server_id);
+        self.loading = true;
+        self.generation += 1;
+        cx.notify();
+        self.generation
+    }
+
+    /// Store a refreshed snapshot
+    pub fn set_dashboard(&mut self, dashboard: RedisDashboard, cx: &mut Context<Self>) {
+        self.dashboard = Some(dashboard);
+        self.refreshed_at = Some(Local::now());
+        self.error = None;
+        self.loading = false;
+        cx.notify();
+    }
+
+    /// Record a failed refresh, keeping the previous snapshot visible
+    pub fn set_error(&mut self, error: impl Into<Arc<str>>, cx: &mut Context<Self>) {
+        self.error = Some(error.into());
+        self.loading = false;
+        cx.notify();
+    }
+}
+
+impl Default for DashboardState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
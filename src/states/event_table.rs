@@ -1,16 +1,101 @@
 //! Event topic table state
 //!
 //! Holds parsed rows from iothub `thing_event` topics and UI pagination state.
+//! Rows live in a session-only SQLite database, or with the event history
+//! enabled in one database file per server that survives restarts.
 
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use super::prop_table::SortDirection;
-use crate::helpers::{cmp_u64ish, split_filter_values};
+use crate::error::{Error, Result};
+use crate::helpers::{cmp_u64ish, get_or_create_data_dir, split_filter_values};
 use rusqlite::types::Value;
-use rusqlite::{Connection, params, params_from_iter};
+use rusqlite::{Connection, TransactionBehavior, params, params_from_iter};
 use rust_i18n::t;
+use serde::{Deserialize, Serialize};
 
 const EVENT_TABLE_MAX_ROWS: usize = 1_000_000;
+/// Directory of the event history databases inside the data directory
+const EVENT_HISTORY_DIR: &str = "event-history";
+
+/// Settings of the on-disk event history
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EventHistoryConfig {
+    /// Keep events on disk per server and topic
+    pub enabled: bool,
+    /// Days events are kept; 0 keeps them regardless of age
+    pub max_age_days: u32,
+    /// Events kept per server and topic
+    pub max_rows: usize,
+}
+
+impl Default for EventHistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_age_days: 30,
+            max_rows: EVENT_TABLE_MAX_ROWS,
+        }
+    }
+}
+
+impl EventHistoryConfig {
+    fn retention(&self) -> EventRetention {
+        EventRetention {
+            max_rows: self.max_rows.max(1),
+            max_age_secs: (self.max_age_days > 0).then(|| i64::from(self.max_age_days) * 86_400),
+        }
+    }
+}
+
+/// Database file holding the event history of a server
+pub fn event_history_path(server_id: &str) -> Result<PathBuf> {
+    let dir = get_or_create_data_dir()?.join(EVENT_HISTORY_DIR);
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir.join(format!("{server_id}.sqlite3")))
+}
+
+/// Delete the event history of a server, if it has one
+pub fn remove_event_history(server_id: &str) -> Result<()> {
+    let path = event_history_path(server_id)?;
+    // WAL mode keeps the recent writes next to the database file
+    for suffix in ["", "-wal", "-shm"] {
+        let mut file = path.clone().into_os_string();
+        file.push(suffix);
+        match std::fs::remove_file(&file) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(())
+}
+
+/// On-disk event history of one topic, ready to back an [`EventTableState`]
+pub struct EventHistory {
+    store: EventTableStore,
+}
+
+/// Open the event history of `topic_path` on `server_id` and drop the rows
+/// outside the retention of `config`. This touches the disk and can take
+/// a while on a large history, so run it off the UI thread.
+pub fn open_event_history(
+    server_id: &str,
+    topic_path: &str,
+    config: &EventHistoryConfig,
+) -> Result<EventHistory> {
+    let path = event_history_path(server_id)?;
+    let store =
+        EventTableStore::open_history(&path, topic_path, config.retention()).map_err(|e| {
+            Error::Invalid {
+                message: e.to_string(),
+            }
+        })?;
+    Ok(EventHistory { store })
+}
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(usize)]
@@ -319,6 +404,10 @@ impl EventTableState {
 
     pub fn reset_for_topic(&mut self, topic_path: Option<String>) {
         self.topic_path = topic_path;
+        // A history store is bound to its topic; fall back to a session-only one
+        if self.is_persistent() {
+            self.store = None;
+        }
         if !self.clear_store() {
             return;
         }
@@ -333,6 +422,34 @@ impl EventTableState {
         self.refresh_counts();
     }
 
+    /// Move the table onto the on-disk `history` of its topic. Rows received
+    /// while the history was opening are carried over; filters and sort stay.
+    /// Returns false when the table moved to another topic or already has
+    /// a history.
+    pub fn attach_history(&mut self, history: EventHistory) -> bool {
+        let store = history.store;
+        if self.is_persistent() || self.topic_path.as_deref() != Some(&*store.topic) {
+            return false;
+        }
+        if let Some(session) = &self.store {
+            let moved = session
+                .batches()
+                .and_then(|batches| batches.iter().try_for_each(|rows| store.insert_rows(rows)));
+            if let Err(e) = moved {
+                tracing::error!(error = %e, "failed to move session event rows into history");
+            }
+        }
+        self.store = Some(store);
+        self.page_index = 0;
+        self.refresh_counts();
+        true
+    }
+
+    /// Whether rows are kept in the on-disk event history
+    pub fn is_persistent(&self) -> bool {
+        self.store.as_ref().is_some_and(|store| store.persistent)
+    }
+
     pub fn mark_loading_for_topic(&mut self, topic_path: Option<String>) {
         self.topic_path = topic_path;
         self.load_state = if self.topic_path.is_some() {
@@ -344,6 +461,7 @@ impl EventTableState {
 
     /// Clear cached rows before a reconnect-driven reload while preserving the
     /// current topic binding and user-visible filters/sort settings.
+    /// The on-disk history is kept; replayed events are de-duplicated.
     pub fn prepare_for_reload(&mut self) {
         if !self.clear_store() {
             return;
@@ -441,7 +559,7 @@ impl EventTableState {
             }
         }
 
-        if let Some(store) = &self.store {
+        if let Some(store) = self.store.as_ref().filter(|store| !store.persistent) {
             if let Err(e) = store.clear() {
                 tracing::error!(error = %e, "failed to clear event table local store");
                 self.load_state = EventTableLoadState::Error(EventTableError::StoreClear {
@@ -493,10 +611,22 @@ fn format_event_store_error(key: &str, locale: &str, detail: &str) -> String {
     format!("{title}: {detail}")
 }
 
+/// Rows kept per topic
+#[derive(Clone, Copy, Debug)]
+struct EventRetention {
+    max_rows: usize,
+    /// Age in seconds after which rows are dropped
+    max_age_secs: Option<i64>,
+}
+
 #[derive(Clone)]
 struct EventTableStore {
     inner: Arc<Mutex<EventTableStoreInner>>,
-    max_rows: usize,
+    /// Topic the rows belong to; empty in a session-only store
+    topic: Arc<str>,
+    retention: EventRetention,
+    /// Whether the rows survive restarts
+    persistent: bool,
 }
 
 struct EventTableStoreInner {
@@ -512,12 +642,49 @@ impl EventTableStore {
         // Empty path creates a private temporary database, so long-running event
         // streams are not retained in a Rust Vec or SQLite's pure in-memory DB.
         let conn = Connection::open("")?;
+        Self::with_connection(
+            conn,
+            "",
+            EventRetention {
+                max_rows,
+                max_age_secs: None,
+            },
+            false,
+        )
+    }
+
+    /// Open the history database at `path`, scoped to the rows of `topic`,
+    /// and drop the rows outside `retention`
+    fn open_history(path: &Path, topic: &str, retention: EventRetention) -> rusqlite::Result<Self> {
+        let conn = Connection::open(path)?;
+        // Every topic of the server opens its own connection to the file
+        conn.busy_timeout(Duration::from_secs(5))?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        let store = Self::with_connection(conn, topic, retention, true)?;
+        {
+            let mut inner = store.lock_inner()?;
+            let tx = inner.conn.transaction()?;
+            let deleted_rows = store.apply_retention(&tx, chrono::Utc::now().timestamp())?;
+            tx.commit()?;
+            tracing::debug!(topic, deleted_rows, "opened event history");
+        }
+        Ok(store)
+    }
+
+    fn with_connection(
+        conn: Connection,
+        topic: &str,
+        retention: EventRetention,
+        persistent: bool,
+    ) -> rusqlite::Result<Self> {
         conn.create_collation("u64ish", cmp_u64ish)?;
         conn.execute_batch(
             r#"
-            CREATE TABLE event_rows (
+            CREATE TABLE IF NOT EXISTS event_rows (
                 uid INTEGER PRIMARY KEY,
+                topic TEXT NOT NULL,
                 batch_uid INTEGER NOT NULL,
+                stored_at INTEGER NOT NULL,
                 uuid TEXT NOT NULL,
                 device TEXT NOT NULL,
                 imr TEXT NOT NULL,
@@ -532,58 +699,72 @@ impl EventTableStore {
                 context TEXT NOT NULL,
                 summary TEXT NOT NULL
             );
-            CREATE INDEX event_rows_default_order_idx ON event_rows(batch_uid DESC, uid ASC);
-            CREATE INDEX event_rows_device_idx ON event_rows(device);
-            CREATE INDEX event_rows_imr_idx ON event_rows(imr);
-            CREATE INDEX event_rows_happened_time_idx ON event_rows(happened_time);
-            CREATE INDEX event_rows_record_time_idx ON event_rows(record_time);
+            CREATE INDEX IF NOT EXISTS event_rows_topic_idx ON event_rows(topic);
+            CREATE INDEX IF NOT EXISTS event_rows_default_order_idx
+                ON event_rows(topic, batch_uid DESC, uid ASC);
+            CREATE INDEX IF NOT EXISTS event_rows_stored_at_idx ON event_rows(topic, stored_at);
+            CREATE UNIQUE INDEX IF NOT EXISTS event_rows_uuid_idx
+                ON event_rows(topic, uuid) WHERE uuid <> '';
+            CREATE INDEX IF NOT EXISTS event_rows_device_idx ON event_rows(device);
+            CREATE INDEX IF NOT EXISTS event_rows_imr_idx ON event_rows(imr);
+            CREATE INDEX IF NOT EXISTS event_rows_happened_time_idx ON event_rows(happened_time);
+            CREATE INDEX IF NOT EXISTS event_rows_record_time_idx ON event_rows(record_time);
             "#,
         )?;
         Ok(Self {
             inner: Arc::new(Mutex::new(EventTableStoreInner { conn })),
-            max_rows,
+            topic: Arc::from(topic),
+            retention,
+            persistent,
         })
     }
 
     fn clear(&self) -> rusqlite::Result<()> {
         let inner = self.lock_inner()?;
-        inner.conn.execute("DELETE FROM event_rows", [])?;
+        inner
+            .conn
+            .execute("DELETE FROM event_rows WHERE topic = ?1", [&*self.topic])?;
         Ok(())
     }
 
     fn insert_rows(&self, rows: &[EventRow]) -> rusqlite::Result<()> {
+        self.insert_rows_at(rows, chrono::Utc::now().timestamp())
+    }
+
+    /// Insert a batch stored at `now` (unix seconds). Rows get store-assigned
+    /// uids, since the stream restarts its numbering every session, and rows
+    /// whose `evtUUID` is already stored are skipped.
+    fn insert_rows_at(&self, rows: &[EventRow], now: i64) -> rusqlite::Result<()> {
         let mut inner = self.lock_inner()?;
-        let tx = inner.conn.transaction()?;
+        // Other topics of the server write to the same file; take the write
+        // lock up front so the batch uid below cannot be read stale
+        let tx = inner
+            .conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)?;
         let mut inserted_rows = 0usize;
-        let batch_uid = rows
-            .iter()
-            .filter_map(|row| i64::try_from(row.uid).ok())
-            .max()
-            .unwrap_or(0);
+        let batch_uid: i64 = tx.query_row(
+            "SELECT COALESCE(MAX(batch_uid), 0) + 1 FROM event_rows WHERE topic = ?1",
+            [&*self.topic],
+            |row| row.get(0),
+        )?;
         {
             let mut stmt = tx.prepare(
                 r#"
-                INSERT OR REPLACE INTO event_rows (
-                    uid, batch_uid, uuid, device, imr, event_type, level, tags, codes,
-                    str_codes, happened_time, record_time, bcr_id, context, summary
+                INSERT INTO event_rows (
+                    topic, batch_uid, stored_at, uuid, device, imr, event_type, level,
+                    tags, codes, str_codes, happened_time, record_time, bcr_id, context, summary
                 )
-                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)
+                VALUES (
+                    ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16
+                )
+                ON CONFLICT (topic, uuid) WHERE uuid <> '' DO NOTHING
                 "#,
             )?;
             for row in rows {
-                let uid = match i64::try_from(row.uid) {
-                    Ok(uid) => uid,
-                    Err(_) => {
-                        tracing::warn!(
-                            uid = row.uid,
-                            "skipping event row because uid exceeds sqlite integer range"
-                        );
-                        continue;
-                    }
-                };
                 inserted_rows += stmt.execute(params![
-                    uid,
+                    &*self.topic,
                     batch_uid,
+                    now,
                     &row.uuid,
                     &row.device,
                     &row.imr,
@@ -600,33 +781,108 @@ impl EventTableStore {
                 ])?;
             }
         }
-        let deleted_rows = trim_event_rows_to_limit(&tx, self.max_rows)?;
+        if inserted_rows < rows.len() {
+            tracing::debug!(
+                skipped_rows = rows.len() - inserted_rows,
+                "skipped event rows already stored"
+            );
+        }
+        let deleted_rows = self.apply_retention(&tx, now)?;
         tx.commit()?;
         if deleted_rows > 0 {
             tracing::debug!(
                 inserted_rows,
                 deleted_rows,
-                max_rows = self.max_rows,
+                max_rows = self.retention.max_rows,
                 "trimmed event table local store after insert"
             );
         }
         Ok(())
     }
 
+    /// Drop the rows of the topic beyond the row limit or whose event is
+    /// older than the age limit. The age goes by the record time of the
+    /// event, then its happened time, and only without either by the time
+    /// the row was stored.
+    fn apply_retention(&self, tx: &rusqlite::Transaction<'_>, now: i64) -> rusqlite::Result<usize> {
+        let mut deleted_rows = trim_event_rows_to_limit(tx, &self.topic, self.retention.max_rows)?;
+        if let Some(max_age_secs) = self.retention.max_age_secs {
+            let cutoff = now - max_age_secs;
+            deleted_rows += tx.execute(
+                r#"
+                DELETE FROM event_rows
+                WHERE topic = ?1 AND (
+                    COALESCE(NULLIF(record_time, ''), NULLIF(happened_time, '')) < ?2
+                    OR (record_time = '' AND happened_time = '' AND stored_at < ?3)
+                )
+                "#,
+                params![&*self.topic, format_event_time(cutoff), cutoff],
+            )?;
+        }
+        Ok(deleted_rows)
+    }
+
+    /// Rows of the topic grouped by batch, oldest batch first
+    fn batches(&self) -> rusqlite::Result<Vec<Vec<EventRow>>> {
+        let inner = self.lock_inner()?;
+        let mut stmt = inner.conn.prepare(
+            r#"
+            SELECT batch_uid, uid, uuid, device, imr, event_type, level, tags, codes,
+                   str_codes, happened_time, record_time, bcr_id, context, summary
+            FROM event_rows
+            WHERE topic = ?1
+            ORDER BY batch_uid ASC, uid ASC
+            "#,
+        )?;
+        let mapped = stmt.query_map([&*self.topic], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                EventRow {
+                    uid: row.get::<_, i64>(1)?.max(0) as u64,
+                    uuid: row.get(2)?,
+                    device: row.get(3)?,
+                    imr: row.get(4)?,
+                    event_type: row.get(5)?,
+                    level: row.get(6)?,
+                    tags: row.get(7)?,
+                    codes: row.get(8)?,
+                    str_codes: row.get(9)?,
+                    happened_time: row.get(10)?,
+                    record_time: row.get(11)?,
+                    bcr_id: row.get(12)?,
+                    context: row.get(13)?,
+                    summary: row.get(14)?,
+                },
+            ))
+        })?;
+
+        let mut batches: Vec<Vec<EventRow>> = Vec::new();
+        let mut last_batch_uid = None;
+        for row in mapped {
+            let (batch_uid, row) = row?;
+            match batches.last_mut() {
+                Some(batch) if last_batch_uid == Some(batch_uid) => batch.push(row),
+                _ => batches.push(vec![row]),
+            }
+            last_batch_uid = Some(batch_uid);
+        }
+        Ok(batches)
+    }
+
     fn count_all(&self) -> rusqlite::Result<usize> {
         let inner = self.lock_inner()?;
-        let count = inner
-            .conn
-            .query_row("SELECT COUNT(*) FROM event_rows", [], |row| {
-                row.get::<_, i64>(0)
-            })?;
+        let count = inner.conn.query_row(
+            "SELECT COUNT(*) FROM event_rows WHERE topic = ?1",
+            [&*self.topic],
+            |row| row.get::<_, i64>(0),
+        )?;
         Ok(count.max(0) as usize)
     }
 
     fn count_filtered(&self, filters: &EventFilters) -> rusqlite::Result<usize> {
         let inner = self.lock_inner()?;
         let mut sql = String::from("SELECT COUNT(*) FROM event_rows");
-        let params = append_filter_clause(filters, &mut sql);
+        let params = append_filter_clause(&self.topic, filters, &mut sql);
         let count = inner
             .conn
             .query_row(&sql, params_from_iter(params.iter()), |row| {
@@ -650,7 +906,7 @@ impl EventTableStore {
             FROM event_rows
             "#,
         );
-        let mut params = append_filter_clause(filters, &mut sql);
+        let mut params = append_filter_clause(&self.topic, filters, &mut sql);
         sql.push_str(" ORDER BY ");
         sql.push_str(sort_order_clause(sort));
         sql.push_str(" LIMIT ? OFFSET ?");
@@ -689,26 +945,37 @@ impl EventTableStore {
     }
 }
 
+/// Unix seconds `secs` in the local-time format of the event time columns,
+/// which sorts the same as the time itself
+fn format_event_time(secs: i64) -> String {
+    chrono::DateTime::<chrono::Utc>::from_timestamp(secs, 0)
+        .unwrap_or_default()
+        .with_timezone(&chrono::Local)
+        .format("%Y-%m-%d %H:%M:%S%.3f")
+        .to_string()
+}
+
 fn trim_event_rows_to_limit(
     tx: &rusqlite::Transaction<'_>,
+    topic: &str,
     max_rows: usize,
 ) -> rusqlite::Result<usize> {
     let offset = max_rows.min(i64::MAX as usize) as i64;
     tx.execute(
         r#"
         DELETE FROM event_rows
-        WHERE uid <= COALESCE(
-            (SELECT uid FROM event_rows ORDER BY uid DESC LIMIT 1 OFFSET ?1),
+        WHERE topic = ?1 AND uid <= COALESCE(
+            (SELECT uid FROM event_rows WHERE topic = ?1 ORDER BY uid DESC LIMIT 1 OFFSET ?2),
             -1
         )
         "#,
-        [offset],
+        params![topic, offset],
     )
 }
 
-fn append_filter_clause(filters: &EventFilters, sql: &mut String) -> Vec<Value> {
-    let mut clauses = Vec::new();
-    let mut params = Vec::new();
+fn append_filter_clause(topic: &str, filters: &EventFilters, sql: &mut String) -> Vec<Value> {
+    let mut clauses = vec!["topic = ?".to_string()];
+    let mut params = vec![Value::Text(topic.to_string())];
 
     push_contains_filter("uuid", &filters.uuid, &mut clauses, &mut params);
     push_contains_filter("device", &filters.device, &mut clauses, &mut params);
@@ -734,10 +1001,8 @@ fn append_filter_clause(filters: &EventFilters, sql: &mut String) -> Vec<Value>
     push_contains_filter("context", &filters.context, &mut clauses, &mut params);
     push_contains_filter("summary", &filters.summary, &mut clauses, &mut params);
 
-    if !clauses.is_empty() {
-        sql.push_str(" WHERE ");
-        sql.push_str(&clauses.join(" AND "));
-    }
+    sql.push_str(" WHERE ");
+    sql.push_str(&clauses.join(" AND "));
 
     params
 }
@@ -937,6 +1202,135 @@ mod tests {
         );
     }
 
+    fn uuids(store: &EventTableStore) -> Vec<String> {
+        store
+            .query_page(&EventFilters::default(), None, 0, 10)
+            .expect("query event rows")
+            .into_iter()
+            .map(|row| row.uuid)
+            .collect()
+    }
+
+    #[test]
+    fn event_history_survives_reopen_and_skips_known_uuids() {
+        let dir = std::env::temp_dir().join(format!("dfc-event-history-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).expect("create history dir");
+        let path = dir.join("server.sqlite3");
+        // The fixture rows carry fixed event times, so keep them regardless of age
+        let retention = EventHistoryConfig {
+            max_age_days: 0,
+            ..EventHistoryConfig::default()
+        }
+        .retention();
+
+        let store =
+            EventTableStore::open_history(&path, "topic-a", retention).expect("open event history");
+        store
+            .insert_rows(&[
+                event_row(1, "2026-04-14 00:00:01.000"),
+                event_row(2, "2026-04-14 00:00:02.000"),
+            ])
+            .expect("insert first session rows");
+        drop(store);
+
+        // The next session numbers its rows from 1 again and replays uuid-2
+        let store = EventTableStore::open_history(&path, "topic-a", retention)
+            .expect("reopen event history");
+        assert_eq!(store.count_all().expect("count event rows"), 2);
+        let mut replayed = event_row(1, "2026-04-14 00:00:02.000");
+        replayed.uuid = "uuid-2".to_string();
+        let mut fresh = event_row(2, "2026-04-14 00:00:03.000");
+        fresh.uuid = "uuid-3".to_string();
+        store
+            .insert_rows(&[replayed, fresh])
+            .expect("insert second session rows");
+        assert_eq!(uuids(&store), vec!["uuid-3", "uuid-1", "uuid-2"]);
+
+        let other =
+            EventTableStore::open_history(&path, "topic-b", retention).expect("open other topic");
+        assert_eq!(other.count_all().expect("count other topic"), 0);
+
+        drop((store, other));
+        std::fs::remove_dir_all(&dir).expect("remove history dir");
+    }
+
+    #[test]
+    fn event_history_drops_rows_past_max_age() {
+        let store = EventTableStore::with_connection(
+            Connection::open("").expect("open event store"),
+            "topic",
+            EventRetention {
+                max_rows: 10,
+                max_age_secs: Some(60),
+            },
+            true,
+        )
+        .expect("create event store");
+        let now = 1_776_000_000;
+        // Stored just now, but recorded long ago: replayed from the device
+        let mut replayed = event_row(1, "");
+        replayed.record_time = format_event_time(now - 120);
+        let mut fresh = event_row(2, "");
+        fresh.record_time = format_event_time(now - 30);
+        let mut happened = event_row(3, &format_event_time(now - 90));
+        happened.record_time = String::new();
+        let mut untimed = event_row(4, "");
+        untimed.record_time = String::new();
+        store
+            .insert_rows_at(&[untimed], now - 100)
+            .expect("insert untimed row");
+        store
+            .insert_rows_at(&[replayed, fresh, happened], now)
+            .expect("insert timed rows");
+
+        assert_eq!(uuids(&store), vec!["uuid-2"]);
+    }
+
+    #[test]
+    fn history_picks_up_rows_received_while_opening() {
+        let dir = std::env::temp_dir().join(format!("dfc-event-history-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).expect("create history dir");
+        let path = dir.join("server.sqlite3");
+        // The fixture rows carry fixed event times, so keep them regardless of age
+        let retention = EventHistoryConfig {
+            max_age_days: 0,
+            ..EventHistoryConfig::default()
+        }
+        .retention();
+        let history =
+            EventTableStore::open_history(&path, "topic-a", retention).expect("open event history");
+        history
+            .insert_rows(&[event_row(1, "2026-04-14 00:00:01.000")])
+            .expect("insert earlier session row");
+
+        let mut state = EventTableState::new();
+        state.reset_for_topic(Some("topic-a".to_string()));
+        let mut known = event_row(1, "2026-04-14 00:00:01.000");
+        known.uuid = "uuid-1".to_string();
+        state.push_rows_front(vec![known, event_row(2, "2026-04-14 00:00:02.000")]);
+        state.push_rows_front(vec![event_row(3, "2026-04-14 00:00:03.000")]);
+        state.set_filter(EventSortColumn::Device, "100852277".to_string());
+
+        let other =
+            EventTableStore::open_history(&path, "topic-b", retention).expect("open other topic");
+        assert!(!state.attach_history(EventHistory { store: other }));
+        assert!(state.attach_history(EventHistory { store: history }));
+        assert!(state.is_persistent());
+        assert_eq!(state.rows_len(), 3);
+        assert_eq!(state.filters().device, "100852277");
+        assert_eq!(
+            state
+                .page_rows_owned()
+                .into_iter()
+                .map(|row| row.uuid)
+                .collect::<Vec<_>>(),
+            vec!["uuid-3", "uuid-2", "uuid-1"]
+        );
+
+        drop(state);
+        std::fs::remove_dir_all(&dir).expect("remove history dir");
+    }
+
     #[test]
     fn default_view_preserves_batch_order() {
         let mut state = EventTableState::new();
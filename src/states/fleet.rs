@@ -3,6 +3,7 @@
 //! Manages the state of all devices in the fleet. This is the single source of truth
 //! for device data including metadata, telemetry, alarms, and online status.
 
+use crate::connection::WritePermit;
 use crate::constants::{
     DEVICE_ALARMS_CAPACITY, DEVICE_EVENTS_CAPACITY, INGEST_BATCH_SIZE, INGEST_INTERVAL_MS,
 };
@@ -363,12 +364,13 @@ impl FleetState {
     pub fn send_command(
         &mut self,
         services: &ServiceHub,
+        permit: &WritePermit,
         device: &DeviceId,
         method: &str,
         params: &str,
         cx: &mut Context<Self>,
     ) {
-        match services.send_command(device, method, params) {
+        match services.send_command(permit, device, method, params) {
             Ok(correlation_id) => {
                 self.pending_commands.insert(
                     correlation_id,
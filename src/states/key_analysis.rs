@@ -0,0 +1,162 @@
+//! Key Analysis State
+//!
+//! Progress and report of the keyspace analyzer of the active server.
+
+use crate::connection::{KeySample, KeyspaceReport};
+use gpui::Context;
+use std::sync::Arc;
+
+/// Keys measured per analyzer batch
+pub const KEY_ANALYSIS_BATCH_SIZE: usize = 200;
+
+/// Random keys measured by a sampling run
+pub const KEY_ANALYSIS_SAMPLE_SIZE: usize = 10_000;
+
+/// How the analyzer walks the keyspace
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum KeyAnalysisMode {
+    /// Measure `KEY_ANALYSIS_SAMPLE_SIZE` random keys
+    #[default]
+    Sample,
+    /// SCAN and measure every key
+    Full,
+}
+
+/// Lifecycle of an analyzer run
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum KeyAnalysisStatus {
+    /// No run started yet
+    #[default]
+    Idle,
+    /// Batches are being measured
+    Running,
+    /// The run completed
+    Finished,
+    /// Cancelled by the user; the partial report is kept
+    Cancelled,
+    /// A batch failed; the partial report is kept
+    Failed(Arc<str>),
+}
+
+/// Keyspace analyzer state of the active server
+#[derive(Default)]
+pub struct KeyAnalysisState {
+    /// Server the report belongs to
+    server_id: Option<String>,
+    /// Mode of the current or last run
+    mode: KeyAnalysisMode,
+    /// Current status
+    status: KeyAnalysisStatus,
+    /// Report aggregated so far
+    report: KeyspaceReport,
+    /// DBSIZE at the start of the run
+    db_size: Option<u64>,
+    /// Monotonic generation; batches of older runs are dropped
+    generation: u64,
+}
+
+impl KeyAnalysisState {
+    /// Create an idle state
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Server the report belongs to
+    pub fn server_id(&self) -> Option<&str> {
+        self.server_id.as_deref()
+    }
+
+    /// Mode of the current or last run
+    pub fn mode(&self) -> KeyAnalysisMode {
+        self.mode
+    }
+
+    /// Current status
+    pub fn status(&self) -> &KeyAnalysisStatus {
+        &self.status
+    }
+
+    /// Whether batches are being measured
+    pub fn is_running(&self) -> bool {
+        self.status == KeyAnalysisStatus::Running
+    }
+
+    /// Report aggregated so far
+    pub fn report(&self) -> &KeyspaceReport {
+        &self.report
+    }
+
+    /// DBSIZE at the start of the run
+    pub fn db_size(&self) -> Option<u64> {
+        self.db_size
+    }
+
+    /// Current run generation
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Start a new run and return its generation
+    pub fn begin(
+        &mut self,
+        server_id: String,
+        mode: KeyAnalysisMode,
+        cx: &mut Context<Self>,
+    ) -> u64 {
+        self.server_id = Some(server_id);
+        self.mode = mode;
+        self.status = KeyAnalysisStatus::Running;
+        self.report = KeyspaceReport::default();
+        self.db_size = None;
+        self.generation += 1;
+        cx.notify();
+        self.generation
+    }
+
+    /// Record DBSIZE of the analyzed database
+    pub fn set_db_size(&mut self, db_size: u64, cx: &mut Context<Self>) {
+        self.db_size = Some(db_size);
+        cx.notify();
+    }
+
+    /// Aggregate a measured batch; `done` finishes the run
+    pub fn push_batch(
+        &mut self,
+        samples: Vec<KeySample>,
+        done: bool,
+        delimiters: &str,
+        cx: &mut Context<Self>,
+    ) {
+        for sample in samples {
+            self.report.add(sample, delimiters);
+        }
+        if done {
+            self.status = KeyAnalysisStatus::Finished;
+        }
+        cx.notify();
+    }
+
+    /// Stop the running analysis, keeping the partial report
+    pub fn cancel(&mut self, cx: &mut Context<Self>) {
+        if self.is_running() {
+            self.generation += 1;
+            self.status = KeyAnalysisStatus::Cancelled;
+            cx.notify();
+        }
+    }
+
+    /// Record a failed batch
+    pub fn set_error(&mut self, error: impl Into<Arc<str>>, cx: &mut Context<Self>) {
+        self.status = KeyAnalysisStatus::Failed(error.into());
+        cx.notify();
+    }
+
+    /// Drop the report, e.g. after switching servers
+    pub fn clear(&mut self, cx: &mut Context<Self>) {
+        *self = Self {
+            generation: self.generation + 1,
+            ..Self::default()
+        };
+        cx.notify();
+    }
+}
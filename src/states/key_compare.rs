@@ -0,0 +1,119 @@
+//! Key Compare State
+//!
+//! Results of comparing keys between the active server and another connected server.
+
+use crate::connection::KeyComparison;
+use gpui::Context;
+use std::sync::Arc;
+
+/// Lifecycle of a comparison
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum KeyCompareStatus {
+    /// No comparison started yet
+    #[default]
+    Idle,
+    /// Values are being loaded from both servers
+    Running,
+    /// The comparison completed
+    Finished,
+    /// Connecting or loading failed
+    Failed(Arc<str>),
+}
+
+/// Key comparison state of the active server
+#[derive(Default)]
+pub struct KeyCompareState {
+    /// Server the keys were read from
+    source_server_id: Option<String>,
+    /// Server the keys were compared against
+    target_server_id: Option<String>,
+    /// Current status
+    status: KeyCompareStatus,
+    /// One result per compared key
+    results: Vec<KeyComparison>,
+    /// Monotonic generation; results of older comparisons are dropped
+    generation: u64,
+}
+
+impl KeyCompareState {
+    /// Create an idle state
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Server the keys were read from
+    pub fn source_server_id(&self) -> Option<&str> {
+        self.source_server_id.as_deref()
+    }
+
+    /// Server the keys were compared against
+    pub fn target_server_id(&self) -> Option<&str> {
+        self.target_server_id.as_deref()
+    }
+
+    /// Current status
+    pub fn status(&self) -> &KeyCompareStatus {
+        &self.status
+    }
+
+    /// Whether values are being loaded
+    pub fn is_running(&self) -> bool {
+        self.status == KeyCompareStatus::Running
+    }
+
+    /// One result per compared key
+    pub fn results(&self) -> &[KeyComparison] {
+        &self.results
+    }
+
+    /// Current comparison generation
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Start a new comparison and return its generation
+    pub fn begin(
+        &mut self,
+        source_server_id: String,
+        target_server_id: String,
+        cx: &mut Context<Self>,
+    ) -> u64 {
+        self.source_server_id = Some(source_server_id);
+        self.target_server_id = Some(target_server_id);
+        self.status = KeyCompareStatus::Running;
+        self.results.clear();
+        self.generation += 1;
+        cx.notify();
+        self.generation
+    }
+
+    /// Store the results of a finished comparison
+    pub fn set_results(&mut self, results: Vec<KeyComparison>, cx: &mut Context<Self>) {
+        self.results = results;
+        self.status = KeyCompareStatus::Finished;
+        cx.notify();
+    }
+
+    /// Replace the result of one key, e.g. after copying it to the target
+    pub fn update_result(&mut self, comparison: KeyComparison, cx: &mut Context<Self>) {
+        if let Some(result) = self.results.iter_mut().find(|r| r.key == comparison.key) {
+            *result = comparison;
+            cx.notify();
+        }
+    }
+
+    /// Record a failed comparison
+    pub fn set_error(&mut self, error: impl Into<Arc<str>>, cx: &mut Context<Self>) {
+        self.status = KeyCompareStatus::Failed(error.into());
+        cx.notify();
+    }
+
+    /// Drop all results, e.g. after switching servers
+    pub fn clear(&mut self, cx: &mut Context<Self>) {
+        *self = Self {
+            generation: self.generation + 1,
+            ..Self::default()
+        };
+        cx.notify();
+    }
+}
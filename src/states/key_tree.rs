@@ -0,0 +1,205 @@
+//! Key Namespace Tree
+//!
+//! Groups Redis keys into collapsible folders by delimiter, e.g.
+//! `CMC_{DCC0007}_sg.og.output.iothub` becomes
+//! `CMC_` / `{DCC0007}_` / `sg.` / `og.` / `output.` / `iothub`.
+//! Hash tags (`{...}`) are never split, even if they contain delimiters.
+
+use crate::connection::RedisKeyItem;
+use std::collections::{BTreeMap, HashSet};
+
+/// Split a key into namespace pieces.
+///
+/// Every piece but the last keeps its trailing delimiter, so concatenating the
+/// first `n` pieces yields the folder prefix used for `SCAN MATCH prefix*`.
+pub fn split_key_namespaces<'a>(key: &'a str, delimiters: &str) -> Vec<&'a str> {
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    let mut in_hash_tag = false;
+
+    for (ix, ch) in key.char_indices() {
+        match ch {
+            '{' if !in_hash_tag => in_hash_tag = true,
+            '}' if in_hash_tag => in_hash_tag = false,
+            _ if !in_hash_tag && delimiters.contains(ch) => {
+                let end = ix + ch.len_utf8();
+                pieces.push(&key[start..end]);
+                start = end;
+            }
+            _ => {}
+        }
+    }
+    if start < key.len() {
+        pieces.push(&key[start..]);
+    }
+    pieces
+}
+
+/// Folder of the key tree
+#[derive(Debug, Default)]
+struct KeyTreeFolder {
+    /// Full prefix of the folder, including its trailing delimiter
+    prefix: String,
+    /// Number of keys below this folder (recursive)
+    count: usize,
+    folders: BTreeMap<String, KeyTreeFolder>,
+    keys: Vec<RedisKeyItem>,
+}
+
+impl KeyTreeFolder {
+    fn insert(&mut self, item: &RedisKeyItem, pieces: &[&str]) {
+        self.count += 1;
+        match pieces {
+            [] | [_] => self.keys.push(item.clone()),
+            [first, rest @ ..] => {
+                let prefix = format!("{}{}", self.prefix, first);
+                self.folders
+                    .entry((*first).to_string())
+                    .or_insert_with(|| KeyTreeFolder {
+                        prefix,
+                        ..Default::default()
+                    })
+                    .insert(item, rest);
+            }
+        }
+    }
+
+    fn push_rows(
+        &self,
+        depth: usize,
+        expanded: &HashSet<String>,
+        has_more: &dyn Fn(&str) -> bool,
+        rows: &mut Vec<KeyTreeRow>,
+    ) {
+        for (piece, folder) in &self.folders {
+            let is_expanded = expanded.contains(&folder.prefix);
+            rows.push(KeyTreeRow::Folder {
+                prefix: folder.prefix.clone(),
+                label: piece.clone(),
+                depth,
+                count: folder.count,
+                expanded: is_expanded,
+            });
+            if is_expanded {
+                folder.push_rows(depth + 1, expanded, has_more, rows);
+                if has_more(&folder.prefix) {
+                    rows.push(KeyTreeRow::LoadMore {
+                        prefix: folder.prefix.clone(),
+                        depth: depth + 1,
+                    });
+                }
+            }
+        }
+
+        let mut keys: Vec<&RedisKeyItem> = self.keys.iter().collect();
+        keys.sort_by(|a, b| a.key.cmp(&b.key));
+        for item in keys {
+            rows.push(KeyTreeRow::Key {
+                label: item.key[self.prefix.len()..].to_string(),
+                item: item.clone(),
+                depth,
+            });
+        }
+    }
+}
+
+/// Visible row of the key tree
+#[derive(Debug, Clone)]
+pub enum KeyTreeRow {
+    /// Namespace folder
+    Folder {
+        prefix: String,
+        label: String,
+        depth: usize,
+        count: usize,
+        expanded: bool,
+    },
+    /// Key leaf; `label` is the key without its folder prefix
+    Key {
+        item: RedisKeyItem,
+        label: String,
+        depth: usize,
+    },
+    /// More keys under `prefix` are available on the server
+    LoadMore { prefix: String, depth: usize },
+}
+
+/// Namespace tree over a set of keys
+#[derive(Debug, Default)]
+pub struct KeyTree {
+    root: KeyTreeFolder,
+}
+
+impl KeyTree {
+    /// Build the tree from keys using the given delimiter characters
+    pub fn build<'a>(keys: impl IntoIterator<Item = &'a RedisKeyItem>, delimiters: &str) -> Self {
+        let mut root = KeyTreeFolder::default();
+        for item in keys {
+            let pieces = split_key_namespaces(&item.key, delimiters);
+            root.insert(item, &pieces);
+        }
+        Self { root }
+    }
+
+    /// Flatten the tree into visible rows (folders first, then keys).
+    ///
+    /// `has_more` tells whether a folder's prefix scan has more keys to load.
+    pub fn rows(
+        &self,
+        expanded: &HashSet<String>,
+        has_more: &dyn Fn(&str) -> bool,
+    ) -> Vec<KeyTreeRow> {
+        let mut rows = Vec::new();
+        self.root.push_rows(0, expanded, has_more, &mut rows);
+        rows
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connection::RedisKeyType;
+
+    fn item(key: &str) -> RedisKeyItem {
+        RedisKeyItem::new(key.to_string(), RedisKeyType::String, -1)
+    }
+
+    #[test]
+    fn split_keeps_hash_tags_together() {
+        assert_eq!(
+            split_key_namespaces("CMC_{DCC0007}_sg.og.output.iothub", "_:."),
+            vec!["CMC_", "{DCC0007}_", "sg.", "og.", "output.", "iothub"]
+        );
+        assert_eq!(
+            split_key_namespaces("a:{x.y:z}:b", ":."),
+            vec!["a:", "{x.y:z}:", "b"]
+        );
+        assert_eq!(split_key_namespaces("plain", ":"), vec!["plain"]);
+        assert_eq!(split_key_namespaces("trailing:", ":"), vec!["trailing:"]);
+    }
+
+    #[test]
+    fn rows_show_counts_and_expand_lazily() {
+        let keys = [item("cfg:a:1"), item("cfg:a:2"), item("cfg:b"), item("top")];
+        let tree = KeyTree::build(keys.iter(), ":");
+
+        let collapsed = tree.rows(&HashSet::new(), &|_| false);
+        assert_eq!(collapsed.len(), 2);
+        assert!(matches!(
+            &collapsed[0],
+            KeyTreeRow::Folder { prefix, count: 3, expanded: false, .. } if prefix == "cfg:"
+        ));
+        assert!(matches!(&collapsed[1], KeyTreeRow::Key { label, .. } if label == "top"));
+
+        let expanded: HashSet<String> = ["cfg:".to_string()].into_iter().collect();
+        let rows = tree.rows(&expanded, &|prefix| prefix == "cfg:");
+        let labels: Vec<String> = rows
+            .iter()
+            .map(|row| match row {
+                KeyTreeRow::Folder { label, .. } | KeyTreeRow::Key { label, .. } => label.clone(),
+                KeyTreeRow::LoadMore { prefix, .. } => format!("more:{prefix}"),
+            })
+            .collect();
+        assert_eq!(labels, vec!["cfg:", "a:", "b", "more:cfg:", "top"]);
+    }
+}
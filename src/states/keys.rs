@@ -3,8 +3,12 @@
 //! Manages the state of Redis keys browsing, including the key list,
 //! selected key/value, connected servers, and filter patterns.
 
-use crate::connection::{ConnectedServerInfo, RedisKeyItem, RedisKeyValue};
+use crate::connection::{
+    ConnectedServerInfo, RedisKeyItem, RedisKeyValue, RedisStreamEntry, RedisValueCursor,
+    RedisValuePage,
+};
 use gpui::Context;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 /// Keys loading state
@@ -37,6 +41,8 @@ impl KeysLoadState {
 pub struct KeysState {
     /// List of Redis keys for the current server
     keys: Vec<RedisKeyItem>,
+    /// Names of the listed keys; SCAN may return a key more than once
+    key_names: HashSet<String>,
     /// Current loading state
     load_state: KeysLoadState,
     /// Currently selected key
@@ -57,6 +63,20 @@ pub struct KeysState {
     list_generation: u64,
     /// Monotonic generation for async selected-value requests.
     value_generation: u64,
+    /// Whether another page of the selected value is being fetched
+    value_page_loading: bool,
+    /// Where the next page of the selected collection value starts
+    value_cursor: RedisValueCursor,
+    /// Total element count of the selected collection value, if known
+    value_total: Option<i64>,
+    /// Server-side MATCH pattern applied to fields/members of the selected value
+    value_match: String,
+    /// Error of the last failed write on the selected key
+    write_error: Option<Arc<str>>,
+    /// Expanded folder prefixes of the namespace tree
+    expanded_prefixes: HashSet<String>,
+    /// SCAN cursor of each folder expanded via `SCAN MATCH prefix*` (0 means done)
+    prefix_cursors: HashMap<String, u64>,
 }
 
 impl KeysState {
@@ -64,6 +84,7 @@ impl KeysState {
     pub fn new() -> Self {
         Self {
             keys: Vec::new(),
+            key_names: HashSet::new(),
             load_state: KeysLoadState::Idle,
             selected_key: None,
             selected_value: RedisKeyValue::Empty,
@@ -74,6 +95,13 @@ impl KeysState {
             has_more_keys: false,
             list_generation: 0,
             value_generation: 0,
+            value_page_loading: false,
+            value_cursor: RedisValueCursor::Done,
+            value_total: None,
+            value_match: String::new(),
+            write_error: None,
+            expanded_prefixes: HashSet::new(),
+            prefix_cursors: HashMap::new(),
         }
     }
 
@@ -159,34 +187,116 @@ impl KeysState {
         self.value_generation
     }
 
+    /// Whether another page of the selected value is being fetched.
+    pub fn is_value_page_loading(&self) -> bool {
+        self.value_page_loading
+    }
+
+    /// Get the cursor of the selected value's next page.
+    pub fn value_cursor(&self) -> RedisValueCursor {
+        self.value_cursor
+    }
+
+    /// Get the total element count of the selected value, if known.
+    pub fn value_total(&self) -> Option<i64> {
+        self.value_total
+    }
+
+    /// Get the MATCH pattern applied to the selected value.
+    pub fn value_match(&self) -> &str {
+        &self.value_match
+    }
+
+    /// Get the expanded folder prefixes of the namespace tree.
+    pub fn expanded_prefixes(&self) -> &HashSet<String> {
+        &self.expanded_prefixes
+    }
+
+    /// Get the prefix scan cursor of a folder, if it was scanned.
+    pub fn prefix_cursor(&self, prefix: &str) -> Option<u64> {
+        self.prefix_cursors.get(prefix).copied()
+    }
+
+    /// Whether a folder may hold keys that are not loaded yet.
+    ///
+    /// Once the global SCAN has finished every key is loaded, so no folder has more.
+    pub fn prefix_has_more(&self, prefix: &str) -> bool {
+        self.has_more_keys && self.prefix_cursor(prefix) != Some(0)
+    }
+
+    /// Get the error of the last failed write, if any.
+    pub fn write_error(&self) -> Option<&str> {
+        self.write_error.as_deref()
+    }
+
     fn bump_list_generation(&mut self) {
         self.list_generation = self.list_generation.wrapping_add(1);
     }
 
     fn bump_value_generation(&mut self) {
         self.value_generation = self.value_generation.wrapping_add(1);
+        self.value_page_loading = false;
+        self.value_cursor = RedisValueCursor::Done;
+        self.write_error = None;
+    }
+
+    /// Add the keys not listed yet, in the order they came
+    fn extend_keys(&mut self, keys: Vec<RedisKeyItem>) {
+        for item in keys {
+            if self.key_names.insert(item.key.clone()) {
+                self.keys.push(item);
+            }
+        }
     }
 
     // ==================== Setters ====================
 
     /// Set keys list
     pub fn set_keys(&mut self, keys: Vec<RedisKeyItem>, cursor: u64, cx: &mut Context<Self>) {
-        self.keys = keys;
+        self.keys.clear();
+        self.key_names.clear();
+        self.extend_keys(keys);
+        self.prefix_cursors.clear();
         self.scan_cursor = cursor;
         self.has_more_keys = cursor != 0;
         self.load_state = KeysLoadState::Loaded;
         cx.notify();
     }
 
-    /// Append more keys (for pagination)
+    /// Append more keys (for pagination), skipping keys already listed
     pub fn append_keys(&mut self, keys: Vec<RedisKeyItem>, cursor: u64, cx: &mut Context<Self>) {
-        self.keys.extend(keys);
+        self.extend_keys(keys);
         self.scan_cursor = cursor;
         self.has_more_keys = cursor != 0;
         self.load_state = KeysLoadState::Loaded;
         cx.notify();
     }
 
+    /// Expand or collapse a namespace folder; returns whether it is now expanded
+    pub fn toggle_prefix(&mut self, prefix: &str, cx: &mut Context<Self>) -> bool {
+        let expanded = if self.expanded_prefixes.remove(prefix) {
+            false
+        } else {
+            self.expanded_prefixes.insert(prefix.to_string());
+            true
+        };
+        cx.notify();
+        expanded
+    }
+
+    /// Merge keys found by a folder's prefix scan, skipping keys already listed
+    pub fn merge_prefix_keys(
+        &mut self,
+        prefix: &str,
+        keys: Vec<RedisKeyItem>,
+        cursor: u64,
+        cx: &mut Context<Self>,
+    ) {
+        self.extend_keys(keys);
+        self.prefix_cursors.insert(prefix.to_string(), cursor);
+        cx.notify();
+    }
+
     /// Set loading state
     pub fn set_loading(&mut self, cx: &mut Context<Self>) {
         self.bump_list_generation();
@@ -206,6 +316,17 @@ impl KeysState {
         self.selected_key = key;
         // Reset value when changing selection
         self.selected_value = RedisKeyValue::Loading;
+        self.value_total = None;
+        self.value_match.clear();
+        cx.notify();
+    }
+
+    /// Reload the selected key's value from the first page with a new MATCH pattern
+    pub fn reload_value(&mut self, pattern: String, cx: &mut Context<Self>) {
+        self.bump_value_generation();
+        self.selected_value = RedisKeyValue::Loading;
+        self.value_total = None;
+        self.value_match = pattern;
         cx.notify();
     }
 
@@ -215,6 +336,89 @@ impl KeysState {
         cx.notify();
     }
 
+    /// Set the first page of the selected key's value
+    pub fn set_selected_value_page(&mut self, page: RedisValuePage, cx: &mut Context<Self>) {
+        self.selected_value = page.value;
+        self.value_cursor = page.next;
+        self.value_total = page.total;
+        self.value_page_loading = false;
+        cx.notify();
+    }
+
+    /// Append the next page of the selected collection value
+    pub fn append_value_page(&mut self, page: RedisValuePage, cx: &mut Context<Self>) {
+        let next = page.next;
+        if self.selected_value.append_page(page.value) {
+            self.value_cursor = next;
+        } else {
+            self.value_cursor = RedisValueCursor::Done;
+        }
+        self.value_page_loading = false;
+        cx.notify();
+    }
+
+    /// Mark the selected value as fetching its next page
+    pub fn set_value_page_loading(&mut self, loading: bool, cx: &mut Context<Self>) {
+        self.value_page_loading = loading;
+        cx.notify();
+    }
+
+    /// Append a page of entries to the selected stream value
+    pub fn append_stream_entries(
+        &mut self,
+        entries: Vec<RedisStreamEntry>,
+        page_size: usize,
+        cx: &mut Context<Self>,
+    ) {
+        if let RedisKeyValue::Stream(stream) = &mut self.selected_value {
+            stream.has_more = entries.len() >= page_size;
+            stream.entries.extend(entries);
+        }
+        self.value_page_loading = false;
+        cx.notify();
+    }
+
+    /// Record the outcome of a write on the selected key
+    pub fn set_write_error(&mut self, error: Option<String>, cx: &mut Context<Self>) {
+        self.write_error = error.map(Into::into);
+        cx.notify();
+    }
+
+    /// Update a key's TTL in the list after EXPIRE/PERSIST
+    pub fn update_key_ttl(&mut self, key: &str, ttl: i64, cx: &mut Context<Self>) {
+        if let Some(item) = self.keys.iter_mut().find(|item| item.key == key) {
+            item.ttl = ttl;
+            cx.notify();
+        }
+    }
+
+    /// Rename a key in the list after RENAMENX
+    pub fn rename_key(&mut self, key: &str, new_key: &str, cx: &mut Context<Self>) {
+        if let Some(item) = self.keys.iter_mut().find(|item| item.key == key) {
+            item.key = new_key.to_string();
+            self.key_names.remove(key);
+            self.key_names.insert(new_key.to_string());
+        }
+        if self.selected_key.as_deref() == Some(key) {
+            self.selected_key = Some(new_key.to_string());
+        }
+        cx.notify();
+    }
+
+    /// Remove a deleted key from the list and clear it if selected
+    pub fn remove_key(&mut self, key: &str, cx: &mut Context<Self>) {
+        self.keys.retain(|item| item.key != key);
+        self.key_names.remove(key);
+        if self.selected_key.as_deref() == Some(key) {
+            self.bump_value_generation();
+            self.selected_key = None;
+            self.selected_value = RedisKeyValue::Empty;
+            self.value_total = None;
+            self.value_match.clear();
+        }
+        cx.notify();
+    }
+
     /// Set the filter pattern
     pub fn set_filter_pattern(&mut self, pattern: String, cx: &mut Context<Self>) {
         self.filter_pattern = pattern;
@@ -266,12 +470,17 @@ impl KeysState {
         self.bump_list_generation();
         self.bump_value_generation();
         self.keys.clear();
+        self.key_names.clear();
         self.selected_key = None;
         self.selected_value = RedisKeyValue::Empty;
+        self.value_total = None;
+        self.value_match.clear();
         self.filter_pattern.clear();
         self.load_state = KeysLoadState::Idle;
         self.scan_cursor = 0;
         self.has_more_keys = false;
+        self.expanded_prefixes.clear();
+        self.prefix_cursors.clear();
         cx.notify();
     }
 
@@ -280,14 +489,19 @@ impl KeysState {
         self.bump_list_generation();
         self.bump_value_generation();
         self.keys.clear();
+        self.key_names.clear();
         self.load_state = KeysLoadState::Idle;
         self.selected_key = None;
         self.selected_value = RedisKeyValue::Empty;
+        self.value_total = None;
+        self.value_match.clear();
         self.filter_pattern.clear();
         self.connected_servers.clear();
         self.active_server_id = None;
         self.scan_cursor = 0;
         self.has_more_keys = false;
+        self.expanded_prefixes.clear();
+        self.prefix_cursors.clear();
         cx.notify();
     }
 }
@@ -297,3 +511,23 @@ impl Default for KeysState {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connection::RedisKeyType;
+
+    fn key(name: &str) -> RedisKeyItem {
+        RedisKeyItem::new(name.to_string(), RedisKeyType::String, -1)
+    }
+
+    #[test]
+    fn scan_pages_do_not_list_a_key_twice() {
+        let mut state = KeysState::new();
+        state.extend_keys(vec![key("a"), key("b"), key("a")]);
+        state.extend_keys(vec![key("b"), key("c")]);
+
+        let names: Vec<&str> = state.keys().iter().map(|item| item.key.as_str()).collect();
+        assert_eq!(names, vec!["a", "b", "c"]);
+    }
+}
@@ -9,22 +9,36 @@
 
 mod app;
 mod config;
+mod console;
+mod dashboard;
 mod event_table;
 mod fleet;
 mod i18n;
+mod key_analysis;
+mod key_compare;
+mod key_tree;
 mod keys;
+mod prop_chart;
 mod prop_table;
 mod service_table;
 mod ui_event;
 pub mod update;
+mod value_search;
 
 pub use app::*;
 pub use config::*;
+pub use console::*;
+pub use dashboard::*;
 pub use event_table::*;
 pub use fleet::*;
 pub use i18n::*;
+pub use key_analysis::*;
+pub use key_compare::*;
+pub use key_tree::*;
 pub use keys::*;
+pub use prop_chart::*;
 pub use prop_table::*;
 pub use service_table::*;
 pub use ui_event::*;
 pub use update::*;
+pub use value_search::*;
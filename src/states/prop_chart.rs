@@ -0,0 +1,605 @@
+//! Prop chart state
+//!
+//! Numeric samples of the prop points picked in the prop table, the time
+//! window shown by the chart and the per-column aggregation of dense series.
+
+use super::prop_table::PropRow;
+use chrono::{DateTime, NaiveDateTime};
+
+/// Series overlaid in one chart
+pub const PROP_CHART_MAX_SERIES: usize = 8;
+/// Most recent samples loaded per series
+pub const PROP_CHART_MAX_SAMPLES: usize = 20_000;
+/// Narrowest time window the chart zooms into, in milliseconds
+const PROP_CHART_MIN_SPAN_MS: f64 = 1_000.0;
+
+/// One numeric sample of a point
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ChartSample {
+    /// Sample time in milliseconds, as written by the device (no time zone)
+    pub time_ms: i64,
+    pub value: f64,
+    pub quality: i32,
+}
+
+impl ChartSample {
+    /// Quality 0 marks a good sample; anything else is drawn as abnormal
+    pub fn is_good(&self) -> bool {
+        self.quality == 0
+    }
+}
+
+/// Numeric value of a prop; booleans chart as 0 and 1
+pub fn chart_value(value: &str) -> Option<f64> {
+    let value = value.trim();
+    if value.eq_ignore_ascii_case("true") {
+        return Some(1.0);
+    }
+    if value.eq_ignore_ascii_case("false") {
+        return Some(0.0);
+    }
+    value.parse::<f64>().ok().filter(|value| value.is_finite())
+}
+
+/// Milliseconds of a prop time such as `2026-04-03 11:04:40.000`
+pub fn chart_time_ms(time: &str) -> Option<i64> {
+    let time = time.trim();
+    NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M:%S%.f")
+        .or_else(|_| NaiveDateTime::parse_from_str(time, "%Y-%m-%dT%H:%M:%S%.f"))
+        .map(|time| time.and_utc().timestamp_millis())
+        .ok()
+        .or_else(|| {
+            DateTime::parse_from_rfc3339(time)
+                .ok()
+                .map(|time| time.naive_local().and_utc().timestamp_millis())
+        })
+}
+
+/// Format chart milliseconds back into the prop time layout
+pub fn format_chart_time(time_ms: i64, with_date: bool) -> String {
+    let Some(time) = DateTime::from_timestamp_millis(time_ms) else {
+        return String::new();
+    };
+    let format = if with_date {
+        "%Y-%m-%d %H:%M:%S"
+    } else {
+        "%H:%M:%S%.3f"
+    };
+    time.naive_utc().format(format).to_string()
+}
+
+/// Chartable samples of `rows`, ordered by time; other rows are skipped
+pub fn chart_samples(rows: &[PropRow]) -> Vec<ChartSample> {
+    let mut samples = rows
+        .iter()
+        .filter_map(|row| {
+            Some(ChartSample {
+                time_ms: chart_time_ms(&row.time)?,
+                value: chart_value(&row.value)?,
+                quality: row.quality,
+            })
+        })
+        .collect::<Vec<_>>();
+    samples.sort_by_key(|sample| sample.time_ms);
+    samples
+}
+
+/// Time range shown on the x axis, in milliseconds
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TimeWindow {
+    pub start_ms: f64,
+    pub end_ms: f64,
+}
+
+impl TimeWindow {
+    pub fn span(&self) -> f64 {
+        self.end_ms - self.start_ms
+    }
+
+    /// Scale the span by `factor` keeping the time at `anchor` (0 = left
+    /// edge, 1 = right edge) in place
+    pub fn zoom(self, factor: f64, anchor: f64) -> Self {
+        let anchor = anchor.clamp(0.0, 1.0);
+        let pivot = self.start_ms + self.span() * anchor;
+        let span = (self.span() * factor).max(PROP_CHART_MIN_SPAN_MS);
+        Self {
+            start_ms: pivot - span * anchor,
+            end_ms: pivot + span * (1.0 - anchor),
+        }
+    }
+
+    /// Shift by `fraction` of the span; positive moves towards later times
+    pub fn pan(self, fraction: f64) -> Self {
+        let offset = self.span() * fraction;
+        Self {
+            start_ms: self.start_ms + offset,
+            end_ms: self.end_ms + offset,
+        }
+    }
+
+    /// Position of `time_ms` inside the window, 0 at the left edge
+    pub fn fraction(&self, time_ms: f64) -> f64 {
+        if self.span() <= 0.0 {
+            return 0.5;
+        }
+        (time_ms - self.start_ms) / self.span()
+    }
+}
+
+/// Samples of one column of the plot
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ChartBucket {
+    pub column: usize,
+    pub min: f64,
+    pub max: f64,
+    pub avg: f64,
+    pub count: usize,
+    /// Whether any sample in the column is not good
+    pub abnormal: bool,
+}
+
+/// Min, max and average of the samples in view
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SeriesStats {
+    pub min: f64,
+    pub max: f64,
+    pub avg: f64,
+    pub count: usize,
+}
+
+/// Samples of `samples` inside `window`, plus the nearest one on each side
+/// so lines run up to the edges
+pub fn samples_in_window(samples: &[ChartSample], window: TimeWindow) -> &[ChartSample] {
+    let start = samples.partition_point(|sample| (sample.time_ms as f64) < window.start_ms);
+    let end = samples.partition_point(|sample| (sample.time_ms as f64) <= window.end_ms);
+    &samples[start.saturating_sub(1)..(end + 1).min(samples.len())]
+}
+
+fn samples_strictly_in_window(samples: &[ChartSample], window: TimeWindow) -> &[ChartSample] {
+    let start = samples.partition_point(|sample| (sample.time_ms as f64) < window.start_ms);
+    let end = samples.partition_point(|sample| (sample.time_ms as f64) <= window.end_ms);
+    &samples[start..end]
+}
+
+/// Aggregate the samples inside `window` into `columns` equal time slices;
+/// empty slices are left out
+pub fn bucket_samples(
+    samples: &[ChartSample],
+    window: TimeWindow,
+    columns: usize,
+) -> Vec<ChartBucket> {
+    let columns = columns.max(1);
+    let mut buckets: Vec<ChartBucket> = Vec::new();
+    let mut sum = 0.0;
+    for sample in samples_strictly_in_window(samples, window) {
+        let column =
+            ((window.fraction(sample.time_ms as f64) * columns as f64) as usize).min(columns - 1);
+        match buckets.last_mut() {
+            Some(bucket) if bucket.column == column => {
+                bucket.min = bucket.min.min(sample.value);
+                bucket.max = bucket.max.max(sample.value);
+                bucket.count += 1;
+                bucket.abnormal |= !sample.is_good();
+                sum += sample.value;
+                bucket.avg = sum / bucket.count as f64;
+            }
+            _ => {
+                sum = sample.value;
+                buckets.push(ChartBucket {
+                    column,
+                    min: sample.value,
+                    max: sample.value,
+                    avg: sample.value,
+                    count: 1,
+                    abnormal: !sample.is_good(),
+                });
+            }
+        }
+    }
+    buckets
+}
+
+/// Statistics of the samples inside `window`
+pub fn series_stats(samples: &[ChartSample], window: TimeWindow) -> Option<SeriesStats> {
+    let samples = samples_strictly_in_window(samples, window);
+    if samples.is_empty() {
+        return None;
+    }
+    let mut stats = SeriesStats {
+        min: f64::INFINITY,
+        max: f64::NEG_INFINITY,
+        avg: 0.0,
+        count: samples.len(),
+    };
+    let mut sum = 0.0;
+    for sample in samples {
+        stats.min = stats.min.min(sample.value);
+        stats.max = stats.max.max(sample.value);
+        sum += sample.value;
+    }
+    stats.avg = sum / samples.len() as f64;
+    Some(stats)
+}
+
+/// A charted point and its samples
+#[derive(Clone, Debug)]
+pub struct PropChartSeries {
+    /// Latest row of the point; identifies it in the prop table
+    pub point: PropRow,
+    pub samples: Vec<ChartSample>,
+}
+
+impl PropChartSeries {
+    pub fn label(&self) -> String {
+        format!("{} · {}", self.point.device, self.point.imr)
+    }
+
+    pub fn is_point(&self, row: &PropRow) -> bool {
+        same_point(&self.point, row)
+    }
+}
+
+/// Whether two rows belong to the same prop point
+pub fn same_point(a: &PropRow, b: &PropRow) -> bool {
+    a.global_uuid == b.global_uuid && a.device == b.device && a.imr == b.imr && a.imid == b.imid
+}
+
+/// Points charted from the prop table and the part of their history in view
+#[derive(Clone, Debug, Default)]
+pub struct PropChartState {
+    series: Vec<PropChartSeries>,
+    /// Zoomed window; `None` fits all samples and follows new ones
+    window: Option<TimeWindow>,
+    /// Newest prop history id whose samples are loaded
+    loaded_history_id: i64,
+}
+
+impl PropChartState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn series(&self) -> &[PropChartSeries] {
+        &self.series
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.series.is_empty()
+    }
+
+    pub fn contains(&self, row: &PropRow) -> bool {
+        self.series.iter().any(|series| series.is_point(row))
+    }
+
+    /// Chart only `rows`, at most [`PROP_CHART_MAX_SERIES`] of them
+    pub fn set_points(&mut self, rows: Vec<PropRow>) {
+        self.series.clear();
+        for row in rows {
+            if self.series.len() >= PROP_CHART_MAX_SERIES {
+                break;
+            }
+            if !self.contains(&row) {
+                self.series.push(PropChartSeries {
+                    point: row,
+                    samples: Vec::new(),
+                });
+            }
+        }
+        self.window = None;
+    }
+
+    /// Overlay the point of `row`, or remove it when already charted.
+    /// Returns false when the series limit is reached.
+    pub fn toggle_point(&mut self, row: PropRow) -> bool {
+        if let Some(index) = self.series.iter().position(|series| series.is_point(&row)) {
+            self.series.remove(index);
+            return true;
+        }
+        if self.series.len() >= PROP_CHART_MAX_SERIES {
+            return false;
+        }
+        self.series.push(PropChartSeries {
+            point: row,
+            samples: Vec::new(),
+        });
+        true
+    }
+
+    pub fn clear(&mut self) {
+        self.series.clear();
+        self.window = None;
+        self.loaded_history_id = 0;
+    }
+
+    pub fn loaded_history_id(&self) -> i64 {
+        self.loaded_history_id
+    }
+
+    /// Reload the samples of every series from `history`, which holds
+    /// everything up to the history id `latest_history_id`
+    pub fn refresh(
+        &mut self,
+        latest_history_id: i64,
+        mut history: impl FnMut(&PropRow) -> Vec<PropRow>,
+    ) {
+        for series in &mut self.series {
+            let rows = history(&series.point);
+            if let Some(latest) = rows.last() {
+                series.point = latest.clone();
+            }
+            series.samples = chart_samples(&rows);
+        }
+        self.loaded_history_id = latest_history_id;
+    }
+
+    /// Add the samples recorded since the last load, up to the history id
+    /// `latest_history_id`; `history_after(point, id)` returns the samples of
+    /// a point recorded after `id` in arrival order. Each series keeps its
+    /// [`PROP_CHART_MAX_SAMPLES`] newest samples. Returns whether any series
+    /// changed.
+    pub fn append_new_samples(
+        &mut self,
+        latest_history_id: i64,
+        mut history_after: impl FnMut(&PropRow, i64) -> Vec<PropRow>,
+    ) -> bool {
+        if latest_history_id <= self.loaded_history_id {
+            return false;
+        }
+        let after = self.loaded_history_id;
+        self.loaded_history_id = latest_history_id;
+        let mut changed = false;
+        for series in &mut self.series {
+            let rows = history_after(&series.point, after);
+            let Some(latest) = rows.last() else {
+                continue;
+            };
+            series.point = latest.clone();
+            let samples = chart_samples(&rows);
+            if samples.is_empty() {
+                continue;
+            }
+            changed = true;
+            // Replayed backfill can be older than the samples already shown
+            let in_order = match (series.samples.last(), samples.first()) {
+                (Some(last), Some(first)) => last.time_ms <= first.time_ms,
+                _ => true,
+            };
+            series.samples.extend(samples);
+            if !in_order {
+                series.samples.sort_by_key(|sample| sample.time_ms);
+            }
+            let excess = series.samples.len().saturating_sub(PROP_CHART_MAX_SAMPLES);
+            series.samples.drain(..excess);
+        }
+        changed
+    }
+
+    /// Window spanning all samples
+    pub fn full_window(&self) -> Option<TimeWindow> {
+        let mut times = self
+            .series
+            .iter()
+            .filter_map(|series| Some((series.samples.first()?, series.samples.last()?)));
+        let (first, last) = times.next()?;
+        let (mut start, mut end) = (first.time_ms, last.time_ms);
+        for (first, last) in times {
+            start = start.min(first.time_ms);
+            end = end.max(last.time_ms);
+        }
+        let (start, end) = (start as f64, end as f64);
+        if end - start < PROP_CHART_MIN_SPAN_MS {
+            let middle = (start + end) / 2.0;
+            return Some(TimeWindow {
+                start_ms: middle - PROP_CHART_MIN_SPAN_MS / 2.0,
+                end_ms: middle + PROP_CHART_MIN_SPAN_MS / 2.0,
+            });
+        }
+        Some(TimeWindow {
+            start_ms: start,
+            end_ms: end,
+        })
+    }
+
+    /// Window in view
+    pub fn window(&self) -> Option<TimeWindow> {
+        self.window.or_else(|| self.full_window())
+    }
+
+    pub fn is_zoomed(&self) -> bool {
+        self.window.is_some()
+    }
+
+    pub fn zoom(&mut self, factor: f64, anchor: f64) {
+        let Some(window) = self.window() else {
+            return;
+        };
+        let zoomed = window.zoom(factor, anchor);
+        // Zooming out past all samples returns to following them
+        self.window = match self.full_window() {
+            Some(full) if zoomed.start_ms <= full.start_ms && zoomed.end_ms >= full.end_ms => None,
+            _ => Some(zoomed),
+        };
+    }
+
+    pub fn pan(&mut self, fraction: f64) {
+        if let Some(window) = self.window() {
+            self.window = Some(window.pan(fraction));
+        }
+    }
+
+    /// Show `window`, e.g. while dragging the plot
+    pub fn set_window(&mut self, window: TimeWindow) {
+        self.window = Some(window);
+    }
+
+    pub fn reset_zoom(&mut self) {
+        self.window = None;
+    }
+
+    /// Value range of the samples in view, padded so lines stay off the edges
+    pub fn value_range(&self, window: TimeWindow) -> Option<(f64, f64)> {
+        let mut range: Option<(f64, f64)> = None;
+        for series in &self.series {
+            for sample in samples_in_window(&series.samples, window) {
+                range = Some(match range {
+                    Some((min, max)) => (min.min(sample.value), max.max(sample.value)),
+                    None => (sample.value, sample.value),
+                });
+            }
+        }
+        let (min, max) = range?;
+        if (max - min).abs() < f64::EPSILON {
+            return Some((min - 1.0, max + 1.0));
+        }
+        let padding = (max - min) * 0.05;
+        Some((min - padding, max + padding))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(time: &str, value: &str, quality: i32) -> PropRow {
+        PropRow {
+            uid: 1,
+            global_uuid: "705537041061273601".to_string(),
+            device: "100852277".to_string(),
+            imr: "Turbine/WTUR/Power".to_string(),
+            imid: 1,
+            value: value.to_string(),
+            quality,
+            bcrid: String::new(),
+            time: time.to_string(),
+            message_time: time.to_string(),
+            summary: String::new(),
+        }
+    }
+
+    fn sample(time_ms: i64, value: f64, quality: i32) -> ChartSample {
+        ChartSample {
+            time_ms,
+            value,
+            quality,
+        }
+    }
+
+    #[test]
+    fn chart_samples_keep_numeric_rows_in_time_order() {
+        let samples = chart_samples(&[
+            row("2026-04-14 11:33:06.000", "2.5", 0),
+            row("2026-04-14 11:33:03.500", "true", 1),
+            row("2026-04-14 11:33:09.000", "running", 0),
+            row("not a time", "1", 0),
+        ]);
+
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[0].value, 1.0);
+        assert_eq!(samples[0].quality, 1);
+        assert_eq!(samples[1].time_ms - samples[0].time_ms, 2_500);
+        assert_eq!(
+            format_chart_time(samples[1].time_ms, true),
+            "2026-04-14 11:33:06"
+        );
+    }
+
+    #[test]
+    fn buckets_summarize_dense_samples_per_column() {
+        let samples = (0..100)
+            .map(|i| sample(i * 10, i as f64, if i == 42 { 3 } else { 0 }))
+            .collect::<Vec<_>>();
+        let window = TimeWindow {
+            start_ms: 0.0,
+            end_ms: 1_000.0,
+        };
+
+        let buckets = bucket_samples(&samples, window, 10);
+
+        assert_eq!(buckets.len(), 10);
+        assert_eq!(buckets[4].min, 40.0);
+        assert_eq!(buckets[4].max, 49.0);
+        assert_eq!(buckets[4].avg, 44.5);
+        assert_eq!(buckets[4].count, 10);
+        assert!(buckets[4].abnormal);
+        assert!(!buckets[5].abnormal);
+        let stats = series_stats(&samples, window).expect("samples in view");
+        assert_eq!((stats.min, stats.max, stats.count), (0.0, 99.0, 100));
+    }
+
+    #[test]
+    fn window_zoom_keeps_anchor_and_pan_shifts_by_span() {
+        let window = TimeWindow {
+            start_ms: 0.0,
+            end_ms: 10_000.0,
+        };
+
+        let zoomed = window.zoom(0.5, 0.2);
+        assert_eq!(zoomed.fraction(2_000.0), 0.2);
+        assert_eq!(zoomed.span(), 5_000.0);
+
+        let panned = zoomed.pan(0.1);
+        assert_eq!(panned.start_ms, zoomed.start_ms + 500.0);
+        assert_eq!(window.zoom(0.0001, 0.5).span(), PROP_CHART_MIN_SPAN_MS);
+    }
+
+    #[test]
+    fn chart_follows_samples_until_zoomed_and_caps_series() {
+        let mut chart = PropChartState::new();
+        let first = row("2026-04-14 11:33:00.000", "1", 0);
+        chart.set_points(vec![first.clone()]);
+        chart.refresh(2, |_| {
+            vec![
+                row("2026-04-14 11:33:00.000", "1", 0),
+                row("2026-04-14 11:34:00.000", "3", 0),
+            ]
+        });
+        let full = chart.window().expect("window");
+        assert_eq!(full.span(), 60_000.0);
+
+        chart.zoom(0.5, 1.0);
+        assert!(chart.is_zoomed());
+        chart.zoom(4.0, 0.5);
+        assert!(!chart.is_zoomed());
+
+        for imid in 2..=PROP_CHART_MAX_SERIES as i32 {
+            let mut other = first.clone();
+            other.imid = imid;
+            assert!(chart.toggle_point(other));
+        }
+        let mut extra = first.clone();
+        extra.imid = 100;
+        assert!(!chart.toggle_point(extra));
+        assert!(chart.toggle_point(first));
+        assert_eq!(chart.series().len(), PROP_CHART_MAX_SERIES - 1);
+    }
+
+    #[test]
+    fn new_samples_merge_in_time_order() {
+        let mut chart = PropChartState::new();
+        let point = row("2026-04-14 11:33:00.000", "1", 0);
+        chart.set_points(vec![point.clone()]);
+        chart.refresh(1, |_| vec![point.clone()]);
+        assert!(!chart.append_new_samples(1, |_, _| panic!("nothing new")));
+
+        let mut asked_after = None;
+        assert!(chart.append_new_samples(3, |_, after| {
+            asked_after = Some(after);
+            vec![
+                row("2026-04-14 11:34:00.000", "3", 0),
+                row("2026-04-14 11:32:00.000", "0", 0),
+            ]
+        }));
+        assert_eq!(asked_after, Some(1));
+        assert_eq!(chart.loaded_history_id(), 3);
+        let values = chart.series()[0]
+            .samples
+            .iter()
+            .map(|sample| sample.value)
+            .collect::<Vec<_>>();
+        assert_eq!(values, vec![0.0, 1.0, 3.0]);
+
+        // New rows of other points leave the series alone
+        assert!(!chart.append_new_samples(4, |_, _| Vec::new()));
+        assert_eq!(chart.loaded_history_id(), 4);
+    }
+}
@@ -1,13 +1,19 @@
 //! Prop topic table state
 //!
 //! Holds parsed rows from iothub `prop_data` topics and UI pagination state.
+//! Rows live in a private SQLite database: one table with the latest value of
+//! every point plus an append-only history of all samples.
 
-use std::cmp::Ordering;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use crate::helpers::{cmp_u64ish, split_filter_values};
-use hashlink::LinkedHashMap;
+use rusqlite::types::Value;
+use rusqlite::{Connection, params, params_from_iter};
+use rust_i18n::t;
+
+/// Samples kept in the prop history of a topic by default
+pub const PROP_HISTORY_MAX_ROWS: usize = 1_000_000;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum SortDirection {
@@ -94,51 +100,12 @@ impl PropFilters {
             PropSortColumn::Summary => self.summary = value,
         }
     }
-
-    /// Pre-lowercase non-empty needles for the hot path. Returns one slot per column.
-    fn lowered_needles(&self) -> [Vec<String>; 10] {
-        [
-            lowered_filter_values(&self.global_uuid),
-            lowered_filter_values(&self.device),
-            lowered_filter_values(&self.imr),
-            lowered_filter_values(&self.imid),
-            lowered_filter_values(&self.value),
-            lowered_filter_values(&self.quality),
-            lowered_filter_values(&self.bcrid),
-            lowered_single_filter_value(&self.time),
-            lowered_single_filter_value(&self.message_time),
-            lowered_filter_values(&self.summary),
-        ]
-    }
-}
-
-fn lowered_filter_values(value: &str) -> Vec<String> {
-    split_filter_values(value)
-        .into_iter()
-        .map(|part| part.to_lowercase())
-        .collect()
-}
-
-fn lowered_single_filter_value(value: &str) -> Vec<String> {
-    let value = value.trim();
-    if value.is_empty() {
-        Vec::new()
-    } else {
-        vec![value.to_lowercase()]
-    }
 }
 
 fn prop_column_allows_multi_filter(column: PropSortColumn) -> bool {
     !matches!(column, PropSortColumn::Time | PropSortColumn::MessageTime)
 }
 
-fn matches_any_lowered(haystack: &str, lowered_needles: &[String]) -> bool {
-    let haystack = haystack.to_lowercase();
-    lowered_needles
-        .iter()
-        .any(|needle| haystack.contains(needle))
-}
-
 /// A single row in the property data table (aligned with DFC PropHistory columns).
 #[derive(Clone, Debug)]
 pub struct PropRow {
@@ -180,34 +147,85 @@ pub enum PropTableLoadState {
     Idle,
     Loading,
     Ready,
-    Error(Arc<str>),
+    Error(PropTableError),
+}
+
+#[derive(Clone, Debug)]
+pub enum PropTableError {
+    Message(Arc<str>),
+    StoreUnavailable,
+    StoreInit { detail: Arc<str> },
+    StoreInsert { detail: Arc<str> },
+    StoreClear { detail: Arc<str> },
+    StoreCountAll { detail: Arc<str> },
+    StoreCountFiltered { detail: Arc<str> },
+}
+
+impl PropTableError {
+    pub fn localized_message(&self, locale: &str) -> String {
+        match self {
+            Self::Message(message) => message.to_string(),
+            Self::StoreUnavailable => {
+                t!("properties.prop_store_unavailable", locale = locale).into()
+            }
+            Self::StoreInit { detail } => {
+                format_prop_store_error("properties.prop_store_init_failed", locale, detail)
+            }
+            Self::StoreInsert { detail } => {
+                format_prop_store_error("properties.prop_store_insert_failed", locale, detail)
+            }
+            Self::StoreClear { detail } => {
+                format_prop_store_error("properties.prop_store_clear_failed", locale, detail)
+            }
+            Self::StoreCountAll { detail } => {
+                format_prop_store_error("properties.prop_store_count_failed", locale, detail)
+            }
+            Self::StoreCountFiltered { detail } => {
+                format_prop_store_error("properties.prop_store_filter_count_failed", locale, detail)
+            }
+        }
+    }
 }
 
 /// State for rendering prop topic data as a paginated table.
 #[derive(Clone)]
 pub struct PropTableState {
     topic_path: Option<String>,
-    rows: LinkedHashMap<PropPointKey, PropRow>,
+    store: Option<PropTableStore>,
     load_state: PropTableLoadState,
     page_size: usize,
     page_index: usize,
     sort: Option<PropSort>,
     filters: PropFilters,
-    /// Keys into `rows` after filtering and sorting, in display order.
-    visible_keys: Vec<PropPointKey>,
+    total_rows: usize,
+    visible_rows: usize,
 }
 
 impl PropTableState {
     pub fn new() -> Self {
+        let (store, load_state) = match PropTableStore::new() {
+            Ok(store) => (Some(store), PropTableLoadState::Idle),
+            Err(e) => {
+                tracing::error!("failed to create prop table store: {e}");
+                (
+                    None,
+                    PropTableLoadState::Error(PropTableError::StoreInit {
+                        detail: Arc::from(e.to_string()),
+                    }),
+                )
+            }
+        };
+
         Self {
             topic_path: None,
-            rows: LinkedHashMap::new(),
-            load_state: PropTableLoadState::Idle,
+            store,
+            load_state,
             page_size: 20,
             page_index: 0,
             sort: None,
             filters: PropFilters::default(),
-            visible_keys: Vec::new(),
+            total_rows: 0,
+            visible_rows: 0,
         }
     }
 
@@ -220,14 +238,32 @@ impl PropTableState {
     }
 
     pub fn rows_len(&self) -> usize {
-        self.rows.len()
+        self.total_rows
     }
 
     pub fn visible_len(&self) -> usize {
-        if self.is_identity_view() {
-            self.rows.len()
-        } else {
-            self.visible_keys.len()
+        self.visible_rows
+    }
+
+    /// Samples kept in the history of all points
+    #[cfg(test)]
+    pub fn history_len(&self) -> usize {
+        let Some(store) = &self.store else {
+            return 0;
+        };
+        match store.count_history() {
+            Ok(count) => count,
+            Err(e) => {
+                tracing::error!(error = %e, "failed to count prop history rows");
+                0
+            }
+        }
+    }
+
+    /// Cap the samples kept in the history; the oldest are dropped first
+    pub fn set_history_limit(&mut self, max_rows: usize) {
+        if let Some(store) = &self.store {
+            store.set_history_limit(max_rows);
         }
     }
 
@@ -251,10 +287,6 @@ impl PropTableState {
         !self.filters.is_empty()
     }
 
-    fn is_identity_view(&self) -> bool {
-        self.sort.is_none() && self.filters.is_empty()
-    }
-
     pub fn toggle_sort(&mut self, column: PropSortColumn) {
         self.sort = match self.sort {
             None => Some(PropSort {
@@ -275,7 +307,6 @@ impl PropTableState {
         };
 
         self.page_index = 0;
-        self.rebuild_visible_indices();
     }
 
     pub fn set_filter(&mut self, column: PropSortColumn, value: String) {
@@ -296,7 +327,7 @@ impl PropTableState {
         }
         self.filters.set(column, value);
         self.page_index = 0;
-        self.rebuild_visible_indices();
+        self.refresh_counts();
     }
 
     pub fn clear_filters(&mut self) {
@@ -305,7 +336,7 @@ impl PropTableState {
         }
         self.filters = PropFilters::default();
         self.page_index = 0;
-        self.rebuild_visible_indices();
+        self.refresh_counts();
     }
 
     pub fn total_pages(&self) -> usize {
@@ -313,7 +344,7 @@ impl PropTableState {
             return 1;
         }
         let total = self.visible_len();
-        let pages = (total + self.page_size - 1) / self.page_size;
+        let pages = total.div_ceil(self.page_size);
         pages.max(1)
     }
 
@@ -328,16 +359,18 @@ impl PropTableState {
 
     pub fn reset_for_topic(&mut self, topic_path: Option<String>) {
         self.topic_path = topic_path;
-        self.rows.clear();
+        if !self.clear_store(true) {
+            return;
+        }
         self.page_index = 0;
         self.sort = None;
         self.filters = PropFilters::default();
-        self.visible_keys.clear();
         self.load_state = if self.topic_path.is_some() {
             PropTableLoadState::Loading
         } else {
             PropTableLoadState::Idle
         };
+        self.refresh_counts();
     }
 
     pub fn mark_loading_for_topic(&mut self, topic_path: Option<String>) {
@@ -351,19 +384,22 @@ impl PropTableState {
 
     /// Clear cached rows before a reconnect-driven reload while preserving the
     /// current topic binding and user-visible filters/sort settings.
+    /// The point history is kept; replayed samples are de-duplicated.
     pub fn prepare_for_reload(&mut self) {
-        self.rows.clear();
+        if !self.clear_store(false) {
+            return;
+        }
         self.page_index = 0;
-        self.visible_keys.clear();
         self.load_state = if self.topic_path.is_some() {
             PropTableLoadState::Loading
         } else {
             PropTableLoadState::Idle
         };
+        self.refresh_counts();
     }
 
     pub fn set_error(&mut self, message: impl Into<Arc<str>>) {
-        self.load_state = PropTableLoadState::Error(message.into());
+        self.load_state = PropTableLoadState::Error(PropTableError::Message(message.into()));
     }
 
     pub fn mark_ready(&mut self) {
@@ -372,49 +408,25 @@ impl PropTableState {
         }
     }
 
-    pub fn push_rows_front(&mut self, mut batch: Vec<PropRow>) {
+    pub fn push_rows_front(&mut self, batch: Vec<PropRow>) {
         if batch.is_empty() {
             return;
         }
 
-        let mut replaced_rows = 0usize;
-        let mut incoming_positions: HashMap<PropPointKey, usize> =
-            HashMap::with_capacity(batch.len());
-        let mut replacement_batch = Vec::with_capacity(batch.len());
-        for row in batch.drain(..) {
-            let key = PropPointKey::from(&row);
-            if let Some(&incoming_idx) = incoming_positions.get(&key) {
-                replacement_batch[incoming_idx] = row;
-                continue;
-            }
-
-            incoming_positions.insert(key, replacement_batch.len());
-            replacement_batch.push(row);
-        }
-
-        if replacement_batch.is_empty() {
-            self.mark_ready();
+        let Some(store) = &self.store else {
+            tracing::error!("prop rows arrived before local store was available");
+            self.load_state = PropTableLoadState::Error(PropTableError::StoreUnavailable);
+            return;
+        };
+        if let Err(e) = store.insert_rows(&batch) {
+            tracing::error!(error = %e, "failed to insert prop rows into local store");
+            self.load_state = PropTableLoadState::Error(PropTableError::StoreInsert {
+                detail: Arc::from(e.to_string()),
+            });
             return;
         }
 
-        // Keep per-message ordering stable: the first element in `batch` remains
-        // before later ones after pushing to the front.
-        for row in replacement_batch.into_iter().rev() {
-            let key = PropPointKey::from(&row);
-            if self.rows.replace(key.clone(), row).is_some() {
-                replaced_rows += 1;
-            }
-            let _ = self.rows.to_front(&key);
-        }
-
-        if replaced_rows > 0 {
-            tracing::debug!(
-                replaced_rows,
-                "replaced existing prop rows with latest point values"
-            );
-        }
-
-        self.rebuild_visible_indices();
+        self.refresh_counts();
 
         // If user is on a later page, keep their position bounded.
         self.page_index = self.page_index.min(self.total_pages().saturating_sub(1));
@@ -442,74 +454,649 @@ impl PropTableState {
             return Vec::new();
         }
 
-        if self.is_identity_view() {
-            return self
-                .rows
-                .values()
-                .skip(start)
-                .take(count)
-                .cloned()
-                .collect();
+        let Some(store) = &self.store else {
+            return Vec::new();
+        };
+        match store.query_page(&self.filters, self.sort, start, count) {
+            Ok(rows) => rows,
+            Err(e) => {
+                tracing::error!(error = %e, "failed to query prop rows from local store");
+                Vec::new()
+            }
+        }
+    }
+
+    /// Up to `limit` most recent samples of the point `row` belongs to,
+    /// oldest first
+    pub fn point_history(&self, row: &PropRow, limit: usize) -> Vec<PropRow> {
+        let Some(store) = &self.store else {
+            return Vec::new();
+        };
+        match store.query_point_history(&PropPointKey::from(row), limit) {
+            Ok(rows) => rows,
+            Err(e) => {
+                tracing::error!(error = %e, "failed to query prop point history");
+                Vec::new()
+            }
         }
+    }
 
-        self.visible_keys
-            .iter()
-            .skip(start)
-            .take(count)
-            .filter_map(|key| self.rows.get(key).cloned())
-            .collect()
+    /// Id of the newest sample in the history, 0 while it is empty; ids only
+    /// grow until the history is cleared
+    pub fn latest_history_id(&self) -> i64 {
+        let Some(store) = &self.store else {
+            return 0;
+        };
+        match store.latest_history_id() {
+            Ok(id) => id,
+            Err(e) => {
+                tracing::error!(error = %e, "failed to query the latest prop history id");
+                0
+            }
+        }
     }
 
-    fn rebuild_visible_indices(&mut self) {
-        self.visible_keys.clear();
+    /// Up to `limit` most recent samples of the point `row` belongs to that
+    /// were recorded after the history id `after`, in arrival order
+    pub fn point_history_after(&self, row: &PropRow, after: i64, limit: usize) -> Vec<PropRow> {
+        let Some(store) = &self.store else {
+            return Vec::new();
+        };
+        match store.query_point_history_after(&PropPointKey::from(row), after, limit) {
+            Ok(rows) => rows,
+            Err(e) => {
+                tracing::error!(error = %e, "failed to query new prop point samples");
+                Vec::new()
+            }
+        }
+    }
 
-        // Identity view (no sort, no filters) bypasses visible_keys entirely
-        // — page_rows_owned reads from `rows` directly.
-        if self.is_identity_view() {
-            return;
+    /// Latest rows of up to `limit` points sharing the IMR of `row` across
+    /// devices, ordered by device
+    pub fn points_with_imr(&self, row: &PropRow, limit: usize) -> Vec<PropRow> {
+        let Some(store) = &self.store else {
+            return Vec::new();
+        };
+        match store.query_points_with_imr(row, limit) {
+            Ok(rows) => rows,
+            Err(e) => {
+                tracing::error!(error = %e, "failed to query prop points by IMR");
+                Vec::new()
+            }
         }
+    }
 
-        if self.filters.is_empty() {
-            self.visible_keys.extend(self.rows.keys().cloned());
-        } else {
-            let needles = self.filters.lowered_needles();
-            for (key, row) in &self.rows {
-                if row_matches_lowered(row, &needles) {
-                    self.visible_keys.push(key.clone());
+    fn clear_store(&mut self, with_history: bool) -> bool {
+        if self.store.is_none() {
+            match PropTableStore::new() {
+                Ok(store) => self.store = Some(store),
+                Err(e) => {
+                    tracing::error!("failed to recreate prop table store: {e}");
+                    self.load_state = PropTableLoadState::Error(PropTableError::StoreInit {
+                        detail: Arc::from(e.to_string()),
+                    });
+                    return false;
                 }
             }
         }
 
-        let Some(sort) = self.sort else {
+        if let Some(Err(e)) = self.store.as_ref().map(|store| store.clear(with_history)) {
+            tracing::error!(error = %e, "failed to clear prop table local store");
+            self.load_state = PropTableLoadState::Error(PropTableError::StoreClear {
+                detail: Arc::from(e.to_string()),
+            });
+            return false;
+        }
+        self.total_rows = 0;
+        self.visible_rows = 0;
+        true
+    }
+
+    fn refresh_counts(&mut self) {
+        let Some(store) = &self.store else {
+            self.total_rows = 0;
+            self.visible_rows = 0;
             return;
         };
 
-        let direction = sort.direction;
-        let column = sort.column;
-
-        self.visible_keys.sort_by(|ka, kb| {
-            let a = match self.rows.get(ka) {
-                Some(v) => v,
-                None => return Ordering::Equal,
-            };
-            let b = match self.rows.get(kb) {
-                Some(v) => v,
-                None => return Ordering::Equal,
-            };
-
-            let ord = compare_prop_rows(a, b, column);
-            let ord = match direction {
-                SortDirection::Asc => ord,
-                SortDirection::Desc => ord.reverse(),
-            };
-
-            // Deterministic tie-breaker: newer (larger uid) first.
-            if ord == Ordering::Equal {
-                b.uid.cmp(&a.uid)
-            } else {
-                ord
+        let total_rows = match store.count_all() {
+            Ok(count) => count,
+            Err(e) => {
+                tracing::error!(error = %e, "failed to count prop rows");
+                self.load_state = PropTableLoadState::Error(PropTableError::StoreCountAll {
+                    detail: Arc::from(e.to_string()),
+                });
+                return;
+            }
+        };
+        let visible_rows = match store.count_filtered(&self.filters) {
+            Ok(count) => count,
+            Err(e) => {
+                tracing::error!(error = %e, "failed to count filtered prop rows");
+                self.load_state = PropTableLoadState::Error(PropTableError::StoreCountFiltered {
+                    detail: Arc::from(e.to_string()),
+                });
+                return;
             }
-        });
+        };
+
+        self.total_rows = total_rows;
+        self.visible_rows = visible_rows;
+    }
+}
+
+impl Default for PropTableState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn format_prop_store_error(key: &str, locale: &str, detail: &str) -> String {
+    let title = t!(key, locale = locale).to_string();
+    format!("{title}: {detail}")
+}
+
+#[derive(Clone)]
+struct PropTableStore {
+    inner: Arc<Mutex<PropTableStoreInner>>,
+}
+
+struct PropTableStoreInner {
+    conn: Connection,
+    history_max_rows: usize,
+}
+
+impl PropTableStore {
+    fn new() -> rusqlite::Result<Self> {
+        Self::new_with_history_limit(PROP_HISTORY_MAX_ROWS)
+    }
+
+    fn new_with_history_limit(history_max_rows: usize) -> rusqlite::Result<Self> {
+        // Empty path creates a private temporary database, like the event table
+        let conn = Connection::open("")?;
+        conn.create_collation("u64ish", cmp_u64ish)?;
+        // `prop_latest` holds one row per point with the newest sample;
+        // `prop_history` appends every sample, keyed by point and sample time
+        conn.execute_batch(
+            r#"
+            CREATE TABLE prop_latest (
+                point_id INTEGER PRIMARY KEY,
+                global_uuid TEXT NOT NULL,
+                device TEXT NOT NULL,
+                imr TEXT NOT NULL,
+                imid INTEGER NOT NULL,
+                uid INTEGER NOT NULL,
+                batch_uid INTEGER NOT NULL,
+                position INTEGER NOT NULL,
+                value TEXT NOT NULL,
+                quality INTEGER NOT NULL,
+                bcrid TEXT NOT NULL,
+                time TEXT NOT NULL,
+                message_time TEXT NOT NULL,
+                summary TEXT NOT NULL,
+                UNIQUE (global_uuid, device, imr, imid)
+            );
+            CREATE INDEX prop_latest_default_order_idx
+                ON prop_latest(batch_uid DESC, position ASC);
+            CREATE INDEX prop_latest_device_idx ON prop_latest(device);
+            CREATE INDEX prop_latest_imr_idx ON prop_latest(imr);
+            CREATE TABLE prop_history (
+                history_id INTEGER PRIMARY KEY,
+                global_uuid TEXT NOT NULL,
+                device TEXT NOT NULL,
+                imr TEXT NOT NULL,
+                imid INTEGER NOT NULL,
+                uid INTEGER NOT NULL,
+                value TEXT NOT NULL,
+                quality INTEGER NOT NULL,
+                bcrid TEXT NOT NULL,
+                time TEXT NOT NULL,
+                message_time TEXT NOT NULL,
+                summary TEXT NOT NULL,
+                UNIQUE (global_uuid, device, imr, imid, time)
+            );
+            -- Index entries end with the rowid, so a point's samples are
+            -- ordered by history_id here
+            CREATE INDEX prop_history_point_idx
+                ON prop_history(global_uuid, device, imr, imid);
+            "#,
+        )?;
+        Ok(Self {
+            inner: Arc::new(Mutex::new(PropTableStoreInner {
+                conn,
+                history_max_rows: history_max_rows.max(1),
+            })),
+        })
+    }
+
+    fn set_history_limit(&self, max_rows: usize) {
+        if let Ok(mut inner) = self.inner.lock() {
+            inner.history_max_rows = max_rows.max(1);
+        }
+    }
+
+    fn clear(&self, with_history: bool) -> rusqlite::Result<()> {
+        let inner = self.lock_inner()?;
+        inner.conn.execute("DELETE FROM prop_latest", [])?;
+        if with_history {
+            inner.conn.execute("DELETE FROM prop_history", [])?;
+        }
+        Ok(())
+    }
+
+    /// Store a batch in arrival order. A point sent twice in one batch keeps
+    /// the position of its first sample and the value of its last one, while
+    /// the history records both; samples already in the history are skipped.
+    fn insert_rows(&self, rows: &[PropRow]) -> rusqlite::Result<()> {
+        let mut latest_positions: HashMap<PropPointKey, usize> = HashMap::with_capacity(rows.len());
+        let mut latest_rows: Vec<&PropRow> = Vec::with_capacity(rows.len());
+        for row in rows {
+            let key = PropPointKey::from(row);
+            if let Some(&position) = latest_positions.get(&key) {
+                latest_rows[position] = row;
+                continue;
+            }
+            latest_positions.insert(key, latest_rows.len());
+            latest_rows.push(row);
+        }
+
+        let mut inner = self.lock_inner()?;
+        let history_max_rows = inner.history_max_rows;
+        let tx = inner.conn.transaction()?;
+        let batch_uid: i64 = tx.query_row(
+            "SELECT COALESCE(MAX(batch_uid), 0) + 1 FROM prop_latest",
+            [],
+            |row| row.get(0),
+        )?;
+        let mut replaced_rows = 0usize;
+        {
+            let mut exists = tx.prepare(
+                r#"
+                SELECT 1 FROM prop_latest
+                WHERE global_uuid = ?1 AND device = ?2 AND imr = ?3 AND imid = ?4
+                "#,
+            )?;
+            let mut upsert = tx.prepare(
+                r#"
+                INSERT INTO prop_latest (
+                    global_uuid, device, imr, imid, uid, batch_uid, position,
+                    value, quality, bcrid, time, message_time, summary
+                )
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
+                ON CONFLICT (global_uuid, device, imr, imid) DO UPDATE SET
+                    uid = excluded.uid,
+                    batch_uid = excluded.batch_uid,
+                    position = excluded.position,
+                    value = excluded.value,
+                    quality = excluded.quality,
+                    bcrid = excluded.bcrid,
+                    time = excluded.time,
+                    message_time = excluded.message_time,
+                    summary = excluded.summary
+                "#,
+            )?;
+            for (position, row) in latest_rows.iter().enumerate() {
+                if exists.exists(params![&row.global_uuid, &row.device, &row.imr, row.imid])? {
+                    replaced_rows += 1;
+                }
+                upsert.execute(params![
+                    &row.global_uuid,
+                    &row.device,
+                    &row.imr,
+                    row.imid,
+                    row.uid.min(i64::MAX as u64) as i64,
+                    batch_uid,
+                    position as i64,
+                    &row.value,
+                    row.quality,
+                    &row.bcrid,
+                    &row.time,
+                    &row.message_time,
+                    &row.summary,
+                ])?;
+            }
+
+            let mut append = tx.prepare(
+                r#"
+                INSERT OR IGNORE INTO prop_history (
+                    global_uuid, device, imr, imid, uid, value, quality, bcrid,
+                    time, message_time, summary
+                )
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+                "#,
+            )?;
+            for row in rows {
+                append.execute(params![
+                    &row.global_uuid,
+                    &row.device,
+                    &row.imr,
+                    row.imid,
+                    row.uid.min(i64::MAX as u64) as i64,
+                    &row.value,
+                    row.quality,
+                    &row.bcrid,
+                    &row.time,
+                    &row.message_time,
+                    &row.summary,
+                ])?;
+            }
+        }
+        let deleted_rows = trim_prop_history_to_limit(&tx, history_max_rows)?;
+        tx.commit()?;
+
+        if replaced_rows > 0 {
+            tracing::debug!(
+                replaced_rows,
+                "replaced existing prop rows with latest point values"
+            );
+        }
+        if deleted_rows > 0 {
+            tracing::debug!(
+                deleted_rows,
+                max_rows = history_max_rows,
+                "trimmed prop history after insert"
+            );
+        }
+        Ok(())
+    }
+
+    fn count_all(&self) -> rusqlite::Result<usize> {
+        let inner = self.lock_inner()?;
+        let count = inner
+            .conn
+            .query_row("SELECT COUNT(*) FROM prop_latest", [], |row| {
+                row.get::<_, i64>(0)
+            })?;
+        Ok(count.max(0) as usize)
+    }
+
+    #[cfg(test)]
+    fn count_history(&self) -> rusqlite::Result<usize> {
+        let inner = self.lock_inner()?;
+        let count = inner
+            .conn
+            .query_row("SELECT COUNT(*) FROM prop_history", [], |row| {
+                row.get::<_, i64>(0)
+            })?;
+        Ok(count.max(0) as usize)
+    }
+
+    fn count_filtered(&self, filters: &PropFilters) -> rusqlite::Result<usize> {
+        let inner = self.lock_inner()?;
+        let mut sql = String::from("SELECT COUNT(*) FROM prop_latest");
+        let params = append_filter_clause(filters, &mut sql);
+        let count = inner
+            .conn
+            .query_row(&sql, params_from_iter(params.iter()), |row| {
+                row.get::<_, i64>(0)
+            })?;
+        Ok(count.max(0) as usize)
+    }
+
+    fn query_page(
+        &self,
+        filters: &PropFilters,
+        sort: Option<PropSort>,
+        start: usize,
+        count: usize,
+    ) -> rusqlite::Result<Vec<PropRow>> {
+        let inner = self.lock_inner()?;
+        let mut sql = String::from(
+            r#"
+            SELECT uid, global_uuid, device, imr, imid, value, quality, bcrid,
+                   time, message_time, summary
+            FROM prop_latest
+            "#,
+        );
+        let mut params = append_filter_clause(filters, &mut sql);
+        sql.push_str(" ORDER BY ");
+        sql.push_str(sort_order_clause(sort));
+        sql.push_str(" LIMIT ? OFFSET ?");
+        params.push(Value::Integer(count.min(i64::MAX as usize) as i64));
+        params.push(Value::Integer(start.min(i64::MAX as usize) as i64));
+
+        let mut stmt = inner.conn.prepare(&sql)?;
+        let mapped = stmt.query_map(params_from_iter(params.iter()), prop_row_from_sql)?;
+        mapped.collect()
+    }
+
+    fn query_point_history(
+        &self,
+        key: &PropPointKey,
+        limit: usize,
+    ) -> rusqlite::Result<Vec<PropRow>> {
+        let inner = self.lock_inner()?;
+        let mut stmt = inner.conn.prepare(
+            r#"
+            SELECT uid, global_uuid, device, imr, imid, value, quality, bcrid,
+                   time, message_time, summary
+            FROM prop_history
+            WHERE global_uuid = ?1 AND device = ?2 AND imr = ?3 AND imid = ?4
+            ORDER BY time DESC, history_id DESC
+            LIMIT ?5
+            "#,
+        )?;
+        let mapped = stmt.query_map(
+            params![
+                &key.global_uuid,
+                &key.device,
+                &key.imr,
+                key.imid,
+                limit.min(i64::MAX as usize) as i64,
+            ],
+            prop_row_from_sql,
+        )?;
+        let mut rows = mapped.collect::<rusqlite::Result<Vec<_>>>()?;
+        rows.reverse();
+        Ok(rows)
+    }
+
+    fn latest_history_id(&self) -> rusqlite::Result<i64> {
+        let inner = self.lock_inner()?;
+        inner.conn.query_row(
+            "SELECT COALESCE(MAX(history_id), 0) FROM prop_history",
+            [],
+            |row| row.get(0),
+        )
+    }
+
+    fn query_point_history_after(
+        &self,
+        key: &PropPointKey,
+        after: i64,
+        limit: usize,
+    ) -> rusqlite::Result<Vec<PropRow>> {
+        let inner = self.lock_inner()?;
+        let mut stmt = inner.conn.prepare(
+            r#"
+            SELECT uid, global_uuid, device, imr, imid, value, quality, bcrid,
+                   time, message_time, summary
+            FROM prop_history
+            WHERE global_uuid = ?1 AND device = ?2 AND imr = ?3 AND imid = ?4
+              AND history_id > ?5
+            ORDER BY history_id DESC
+            LIMIT ?6
+            "#,
+        )?;
+        let mapped = stmt.query_map(
+            params![
+                &key.global_uuid,
+                &key.device,
+                &key.imr,
+                key.imid,
+                after,
+                limit.min(i64::MAX as usize) as i64,
+            ],
+            prop_row_from_sql,
+        )?;
+        let mut rows = mapped.collect::<rusqlite::Result<Vec<_>>>()?;
+        rows.reverse();
+        Ok(rows)
+    }
+
+    fn query_points_with_imr(&self, row: &PropRow, limit: usize) -> rusqlite::Result<Vec<PropRow>> {
+        let inner = self.lock_inner()?;
+        let mut stmt = inner.conn.prepare(
+            r#"
+            SELECT uid, global_uuid, device, imr, imid, value, quality, bcrid,
+                   time, message_time, summary
+            FROM prop_latest
+            WHERE imr = ?1 AND imid = ?2
+            ORDER BY device COLLATE u64ish ASC, global_uuid COLLATE u64ish ASC
+            LIMIT ?3
+            "#,
+        )?;
+        let mapped = stmt.query_map(
+            params![&row.imr, row.imid, limit.min(i64::MAX as usize) as i64],
+            prop_row_from_sql,
+        )?;
+        mapped.collect()
+    }
+
+    fn lock_inner(&self) -> rusqlite::Result<std::sync::MutexGuard<'_, PropTableStoreInner>> {
+        self.inner.lock().map_err(|_| rusqlite::Error::InvalidQuery)
+    }
+}
+
+fn prop_row_from_sql(row: &rusqlite::Row<'_>) -> rusqlite::Result<PropRow> {
+    Ok(PropRow {
+        uid: row.get::<_, i64>(0)?.max(0) as u64,
+        global_uuid: row.get(1)?,
+        device: row.get(2)?,
+        imr: row.get(3)?,
+        imid: row.get(4)?,
+        value: row.get(5)?,
+        quality: row.get(6)?,
+        bcrid: row.get(7)?,
+        time: row.get(8)?,
+        message_time: row.get(9)?,
+        summary: row.get(10)?,
+    })
+}
+
+fn trim_prop_history_to_limit(
+    tx: &rusqlite::Transaction<'_>,
+    max_rows: usize,
+) -> rusqlite::Result<usize> {
+    let offset = max_rows.min(i64::MAX as usize) as i64;
+    tx.execute(
+        r#"
+        DELETE FROM prop_history
+        WHERE history_id <= COALESCE(
+            (SELECT history_id FROM prop_history ORDER BY history_id DESC LIMIT 1 OFFSET ?1),
+            -1
+        )
+        "#,
+        [offset],
+    )
+}
+
+fn append_filter_clause(filters: &PropFilters, sql: &mut String) -> Vec<Value> {
+    let mut clauses = Vec::new();
+    let mut params = Vec::new();
+
+    push_contains_filter(
+        "global_uuid",
+        &filters.global_uuid,
+        &mut clauses,
+        &mut params,
+    );
+    push_contains_filter("device", &filters.device, &mut clauses, &mut params);
+    push_contains_filter("imr", &filters.imr, &mut clauses, &mut params);
+    push_contains_filter("imid", &filters.imid, &mut clauses, &mut params);
+    push_contains_filter("value", &filters.value, &mut clauses, &mut params);
+    push_contains_filter("quality", &filters.quality, &mut clauses, &mut params);
+    push_contains_filter("bcrid", &filters.bcrid, &mut clauses, &mut params);
+    push_prefix_filter("time", &filters.time, &mut clauses, &mut params);
+    push_prefix_filter(
+        "message_time",
+        &filters.message_time,
+        &mut clauses,
+        &mut params,
+    );
+    push_contains_filter("summary", &filters.summary, &mut clauses, &mut params);
+
+    if !clauses.is_empty() {
+        sql.push_str(" WHERE ");
+        sql.push_str(&clauses.join(" AND "));
+    }
+
+    params
+}
+
+fn push_contains_filter(
+    column: &'static str,
+    value: &str,
+    clauses: &mut Vec<String>,
+    params: &mut Vec<Value>,
+) {
+    let values = split_filter_values(value);
+    if values.is_empty() {
+        return;
+    }
+    clauses.push(or_clause(values.len(), || {
+        format!("instr(lower({column}), ?) > 0")
+    }));
+    params.extend(
+        values
+            .into_iter()
+            .map(|value| Value::Text(value.to_lowercase())),
+    );
+}
+
+fn push_prefix_filter(
+    column: &'static str,
+    value: &str,
+    clauses: &mut Vec<String>,
+    params: &mut Vec<Value>,
+) {
+    let value = value.trim();
+    if value.is_empty() {
+        return;
+    }
+    clauses.push(format!("substr({column}, 1, length(?)) = ?"));
+    params.push(Value::Text(value.to_string()));
+    params.push(Value::Text(value.to_string()));
+}
+
+fn or_clause(count: usize, mut clause: impl FnMut() -> String) -> String {
+    if count == 1 {
+        return clause();
+    }
+    let clauses = (0..count).map(|_| clause()).collect::<Vec<_>>();
+    format!("({})", clauses.join(" OR "))
+}
+
+fn sort_order_clause(sort: Option<PropSort>) -> &'static str {
+    let Some(sort) = sort else {
+        return "batch_uid DESC, position ASC";
+    };
+
+    match (sort.column, sort.direction) {
+        (PropSortColumn::GlobalUuid, SortDirection::Asc) => {
+            "global_uuid COLLATE u64ish ASC, uid DESC"
+        }
+        (PropSortColumn::GlobalUuid, SortDirection::Desc) => {
+            "global_uuid COLLATE u64ish DESC, uid DESC"
+        }
+        (PropSortColumn::Device, SortDirection::Asc) => "device COLLATE u64ish ASC, uid DESC",
+        (PropSortColumn::Device, SortDirection::Desc) => "device COLLATE u64ish DESC, uid DESC",
+        (PropSortColumn::Imr, SortDirection::Asc) => "imr ASC, uid DESC",
+        (PropSortColumn::Imr, SortDirection::Desc) => "imr DESC, uid DESC",
+        (PropSortColumn::Imid, SortDirection::Asc) => "imid ASC, uid DESC",
+        (PropSortColumn::Imid, SortDirection::Desc) => "imid DESC, uid DESC",
+        (PropSortColumn::Value, SortDirection::Asc) => "value ASC, uid DESC",
+        (PropSortColumn::Value, SortDirection::Desc) => "value DESC, uid DESC",
+        (PropSortColumn::Quality, SortDirection::Asc) => "quality ASC, uid DESC",
+        (PropSortColumn::Quality, SortDirection::Desc) => "quality DESC, uid DESC",
+        (PropSortColumn::Bcrid, SortDirection::Asc) => "bcrid ASC, uid DESC",
+        (PropSortColumn::Bcrid, SortDirection::Desc) => "bcrid DESC, uid DESC",
+        (PropSortColumn::Time, SortDirection::Asc) => "time ASC, uid DESC",
+        (PropSortColumn::Time, SortDirection::Desc) => "time DESC, uid DESC",
+        (PropSortColumn::MessageTime, SortDirection::Asc) => "message_time ASC, uid DESC",
+        (PropSortColumn::MessageTime, SortDirection::Desc) => "message_time DESC, uid DESC",
+        (PropSortColumn::Summary, SortDirection::Asc) => "summary ASC, uid DESC",
+        (PropSortColumn::Summary, SortDirection::Desc) => "summary DESC, uid DESC",
     }
 }
 
@@ -686,83 +1273,161 @@ mod tests {
             vec!["100", "300"]
         );
     }
-}
 
-fn row_matches_lowered(row: &PropRow, needles: &[Vec<String>; 10]) -> bool {
-    if !needles[0].is_empty() {
-        if !matches_any_lowered(&row.global_uuid, &needles[0]) {
-            return false;
-        }
-    }
-    if !needles[1].is_empty() {
-        if !matches_any_lowered(&row.device, &needles[1]) {
-            return false;
-        }
-    }
-    if !needles[2].is_empty() {
-        if !matches_any_lowered(&row.imr, &needles[2]) {
-            return false;
-        }
+    fn sample(uid: u64, time: &str, value: &str) -> PropRow {
+        let mut row = prop_row(uid, time);
+        row.time = time.to_string();
+        row.value = value.to_string();
+        row
     }
-    if !needles[3].is_empty() {
-        if !matches_any_lowered(&row.imid.to_string(), &needles[3]) {
-            return false;
-        }
-    }
-    if !needles[4].is_empty() {
-        if !matches_any_lowered(&row.value, &needles[4]) {
-            return false;
-        }
-    }
-    if !needles[5].is_empty() {
-        if !matches_any_lowered(&row.quality.to_string(), &needles[5]) {
-            return false;
-        }
-    }
-    if !needles[6].is_empty() {
-        if !matches_any_lowered(&row.bcrid, &needles[6]) {
-            return false;
-        }
+
+    #[test]
+    fn point_history_keeps_every_sample_oldest_first() {
+        let mut state = PropTableState::new();
+        state.reset_for_topic(Some("persistent://topic".to_string()));
+
+        state.push_rows_front(vec![
+            sample(1, "2026-04-14 11:33:03.000", "1.5"),
+            sample(2, "2026-04-14 11:33:06.000", "2.5"),
+        ]);
+        // A replayed sample is not recorded twice, an older backfilled one is
+        // ordered by its sample time
+        state.push_rows_front(vec![
+            sample(3, "2026-04-14 11:33:06.000", "2.5"),
+            sample(4, "2026-04-14 11:33:00.000", "0.5"),
+        ]);
+
+        assert_eq!(state.rows_len(), 1);
+        assert_eq!(state.history_len(), 3);
+        let row = state.page_rows_owned().remove(0);
+        let values = state
+            .point_history(&row, 10)
+            .into_iter()
+            .map(|row| row.value)
+            .collect::<Vec<_>>();
+        assert_eq!(values, vec!["0.5", "1.5", "2.5"]);
+        assert_eq!(state.point_history(&row, 1)[0].value, "2.5");
     }
-    if !needles[7].is_empty() {
-        if !matches_any_day_filter(&row.time, &needles[7]) {
-            return false;
-        }
+
+    #[test]
+    fn point_history_after_returns_only_new_samples_of_the_point() {
+        let mut state = PropTableState::new();
+        state.reset_for_topic(Some("persistent://topic".to_string()));
+        assert_eq!(state.latest_history_id(), 0);
+
+        state.push_rows_front(vec![sample(1, "2026-04-14 11:33:03.000", "1")]);
+        let seen = state.latest_history_id();
+        let row = state.page_rows_owned().remove(0);
+        assert!(state.point_history_after(&row, seen, 10).is_empty());
+
+        let mut other = sample(2, "2026-04-14 11:33:04.000", "9");
+        other.imid = 2;
+        state.push_rows_front(vec![
+            other,
+            sample(3, "2026-04-14 11:33:06.000", "2"),
+            sample(4, "2026-04-14 11:33:00.000", "0"),
+        ]);
+        assert!(state.latest_history_id() > seen);
+        let values = state
+            .point_history_after(&row, seen, 10)
+            .into_iter()
+            .map(|row| row.value)
+            .collect::<Vec<_>>();
+        assert_eq!(values, vec!["2", "0"]);
+        assert_eq!(state.point_history_after(&row, seen, 1)[0].value, "0");
     }
-    if !needles[8].is_empty() {
-        if !matches_any_day_filter(&row.message_time, &needles[8]) {
-            return false;
-        }
+
+    #[test]
+    fn history_limit_drops_oldest_samples() {
+        let mut state = PropTableState::new();
+        state.reset_for_topic(Some("persistent://topic".to_string()));
+        state.set_history_limit(2);
+
+        state.push_rows_front(vec![
+            sample(1, "2026-04-14 11:33:03.000", "1"),
+            sample(2, "2026-04-14 11:33:06.000", "2"),
+            sample(3, "2026-04-14 11:33:09.000", "3"),
+        ]);
+
+        assert_eq!(state.history_len(), 2);
+        let row = state.page_rows_owned().remove(0);
+        assert_eq!(row.value, "3");
+        let values = state
+            .point_history(&row, 10)
+            .into_iter()
+            .map(|row| row.value)
+            .collect::<Vec<_>>();
+        assert_eq!(values, vec!["2", "3"]);
     }
-    if !needles[9].is_empty() {
-        if !matches_any_lowered(&row.summary, &needles[9]) {
-            return false;
-        }
+
+    #[test]
+    fn prepare_for_reload_keeps_point_history() {
+        let mut state = PropTableState::new();
+        state.reset_for_topic(Some("persistent://topic".to_string()));
+        state.push_rows_front(vec![sample(1, "2026-04-14 11:33:03.000", "1")]);
+
+        state.prepare_for_reload();
+        assert_eq!(state.history_len(), 1);
+
+        state.reset_for_topic(Some("persistent://topic".to_string()));
+        assert_eq!(state.history_len(), 0);
     }
-    true
-}
 
-fn matches_any_day_filter(timestamp: &str, days: &[String]) -> bool {
-    days.iter().any(|day| timestamp.starts_with(day))
-}
+    #[test]
+    fn default_view_puts_latest_batch_first_and_keeps_batch_order() {
+        let mut state = PropTableState::new();
+        state.reset_for_topic(Some("persistent://topic".to_string()));
 
-impl Default for PropTableState {
-    fn default() -> Self {
-        Self::new()
+        let mut rows = Vec::new();
+        for (uid, imid) in [(1, 1), (2, 2), (3, 3)] {
+            let mut row = prop_row(uid, "2026-04-14 11:33:03.000");
+            row.imid = imid;
+            rows.push(row);
+        }
+        state.push_rows_front(rows);
+        let mut updated = prop_row(4, "2026-04-14 11:33:06.000");
+        updated.imid = 2;
+        updated.time = "2026-04-03 11:04:50.000".to_string();
+        state.push_rows_front(vec![updated]);
+
+        let imids = |state: &PropTableState| {
+            state
+                .page_rows_owned()
+                .iter()
+                .map(|row| row.imid)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(imids(&state), vec![2, 1, 3]);
+
+        state.toggle_sort(PropSortColumn::Imid);
+        assert_eq!(imids(&state), vec![1, 2, 3]);
+        state.toggle_sort(PropSortColumn::Imid);
+        assert_eq!(imids(&state), vec![3, 2, 1]);
     }
-}
 
-fn compare_prop_rows(a: &PropRow, b: &PropRow, column: PropSortColumn) -> Ordering {
-    match column {
-        PropSortColumn::GlobalUuid => cmp_u64ish(&a.global_uuid, &b.global_uuid),
-        PropSortColumn::Device => cmp_u64ish(&a.device, &b.device),
-        PropSortColumn::Imr => a.imr.cmp(&b.imr),
-        PropSortColumn::Imid => a.imid.cmp(&b.imid),
-        PropSortColumn::Value => a.value.cmp(&b.value),
-        PropSortColumn::Quality => a.quality.cmp(&b.quality),
-        PropSortColumn::Bcrid => a.bcrid.cmp(&b.bcrid),
-        PropSortColumn::Time => a.time.cmp(&b.time),
-        PropSortColumn::MessageTime => a.message_time.cmp(&b.message_time),
-        PropSortColumn::Summary => a.summary.cmp(&b.summary),
+    #[test]
+    fn points_with_imr_lists_the_point_on_every_device() {
+        let mut state = PropTableState::new();
+        state.reset_for_topic(Some("persistent://topic".to_string()));
+
+        let mut rows = Vec::new();
+        for (uid, device) in [(1, "300"), (2, "20"), (3, "100")] {
+            let mut row = prop_row(uid, "2026-04-14 11:33:03.000");
+            row.device = device.to_string();
+            rows.push(row);
+        }
+        let mut other = prop_row(4, "2026-04-14 11:33:03.000");
+        other.imr = "Turbine/WTUR/State/Other".to_string();
+        rows.push(other);
+        state.push_rows_front(rows);
+
+        let probe = prop_row(5, "2026-04-14 11:33:03.000");
+        let devices = state
+            .points_with_imr(&probe, 10)
+            .into_iter()
+            .map(|row| row.device)
+            .collect::<Vec<_>>();
+        assert_eq!(devices, vec!["20", "100", "300"]);
+        assert_eq!(state.points_with_imr(&probe, 2).len(), 2);
     }
 }
@@ -1,5 +1,6 @@
 //! Application auto-update state and workflows.
 
+use crate::connection::{ProxyConfig, ProxyKind};
 use crate::error::Error;
 use gpui::{App, Entity, Global};
 use tracing::{debug, error, info};
@@ -123,11 +124,55 @@ pub fn current_version() -> &'static str {
     CURRENT_VERSION
 }
 
-fn fetch_latest_release() -> Result<ReleaseInfo> {
-    let client = reqwest::blocking::Client::builder()
+/// HTTP client for GitHub, through the global proxy when one is set
+fn http_client(
+    timeout: std::time::Duration,
+    proxy: Option<&ProxyConfig>,
+) -> Result<reqwest::blocking::Client> {
+    let mut builder = reqwest::blocking::Client::builder()
         .user_agent("DFC-GUI")
-        .timeout(std::time::Duration::from_secs(30))
-        .build()?;
+        .timeout(timeout);
+    if let Some(proxy) = proxy {
+        let (kind, host, port) = proxy.endpoint()?;
+        let scheme = match kind {
+            ProxyKind::Socks5 => "socks5h",
+            ProxyKind::Http => "http",
+        };
+        let host = if host.contains(':') {
+            format!("[{host}]")
+        } else {
+            host
+        };
+        let mut url = reqwest::Url::parse(&format!("{scheme}://{host}:{port}")).map_err(|err| {
+            Error::Invalid {
+                message: format!("Invalid proxy URL \"{}\": {err}", proxy.url),
+            }
+        })?;
+        if let Some(username) = proxy.username.as_deref().filter(|u| !u.is_empty()) {
+            // Credentials in the URL are sent as SOCKS5 auth or Basic auth
+            let _ = url.set_username(username);
+            let _ = url.set_password(proxy.password.as_deref());
+        }
+        let bypass = proxy
+            .bypass
+            .as_deref()
+            .unwrap_or_default()
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| match entry.strip_prefix('*') {
+                Some(domain) if !domain.is_empty() => domain,
+                _ => entry,
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        builder = builder
+            .proxy(reqwest::Proxy::all(url)?.no_proxy(reqwest::NoProxy::from_string(&bypass)));
+    }
+    Ok(builder.build()?)
+}
+
+fn fetch_latest_release(proxy: Option<&ProxyConfig>) -> Result<ReleaseInfo> {
+    let client = http_client(std::time::Duration::from_secs(30), proxy)?;
     let response = client
         .get(GITHUB_RELEASES_LATEST_URL)
         .send()?
@@ -281,14 +326,12 @@ fn html_to_text(fragment: &str) -> String {
 fn download_file(
     url: &str,
     path: &std::path::Path,
+    proxy: Option<&ProxyConfig>,
     tx: futures::channel::mpsc::UnboundedSender<DownloadMsg>,
 ) -> Result<u64> {
     use std::io::{Read, Write};
 
-    let client = reqwest::blocking::Client::builder()
-        .user_agent("DFC-GUI")
-        .timeout(std::time::Duration::from_secs(600))
-        .build()?;
+    let client = http_client(std::time::Duration::from_secs(600), proxy)?;
     let mut response = client.get(url).send()?.error_for_status()?;
     let mut file = std::fs::File::create(path)?;
     let mut downloaded = 0u64;
@@ -349,6 +392,7 @@ pub fn check_for_updates(manual: bool, cx: &App) {
         }
     }
 
+    let proxy = cx.global::<super::DfcGlobalStore>().read(cx).proxy();
     cx.spawn(async move |cx| {
         let _ = state_entity.update(cx, |state, cx| {
             state.status = UpdateStatus::Checking;
@@ -361,7 +405,7 @@ pub fn check_for_updates(manual: bool, cx: &App) {
 
         let (tx, rx) = futures::channel::oneshot::channel();
         std::thread::spawn(move || {
-            let _ = tx.send(fetch_latest_release());
+            let _ = tx.send(fetch_latest_release(proxy.as_ref()));
         });
 
         let result = rx.await.unwrap_or_else(|_| {
@@ -466,6 +510,7 @@ pub fn download_update(cx: &App) {
     };
 
     let url = asset.download_url.clone();
+    let proxy = cx.global::<super::DfcGlobalStore>().read(cx).proxy();
     let file_name = asset.name.clone();
     let total_size = asset.size;
 
@@ -493,7 +538,7 @@ pub fn download_update(cx: &App) {
         let download_path_clone = download_path.clone();
 
         std::thread::spawn(move || {
-            let result = download_file(&url, &download_path_clone, tx.clone());
+            let result = download_file(&url, &download_path_clone, proxy.as_ref(), tx.clone());
             match result {
                 Ok(written) => {
                     let _ = tx.unbounded_send(DownloadMsg::Complete { written });
@@ -0,0 +1,136 @@
+//! Value Search State
+//!
+//! Progress and matches of the "find in values" job of the key browser.
+
+use crate::connection::{ValueSearchBatch, ValueSearchMatch};
+use gpui::Context;
+use std::sync::Arc;
+
+/// Keys fetched per SCAN batch of the value search
+pub const VALUE_SEARCH_BATCH_SIZE: usize = 100;
+
+/// Matches kept before the search stops on its own
+pub const VALUE_SEARCH_MAX_MATCHES: usize = 1000;
+
+/// Lifecycle of a value search
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum ValueSearchStatus {
+    /// No search started yet
+    #[default]
+    Idle,
+    /// Batches are being fetched
+    Running,
+    /// The keyspace was scanned completely
+    Finished,
+    /// Stopped after `VALUE_SEARCH_MAX_MATCHES` matches
+    Truncated,
+    /// Cancelled by the user
+    Cancelled,
+    /// A batch failed
+    Failed(Arc<str>),
+}
+
+/// Value search state of the active server
+#[derive(Default)]
+pub struct ValueSearchState {
+    /// Server the matches belong to
+    server_id: Option<String>,
+    /// Current status
+    status: ValueSearchStatus,
+    /// Keys searched so far
+    scanned: usize,
+    /// Matches found so far
+    matches: Vec<ValueSearchMatch>,
+    /// Monotonic generation; batches of older searches are dropped
+    generation: u64,
+}
+
+impl ValueSearchState {
+    /// Create an idle state
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Server the matches belong to
+    pub fn server_id(&self) -> Option<&str> {
+        self.server_id.as_deref()
+    }
+
+    /// Current status
+    pub fn status(&self) -> &ValueSearchStatus {
+        &self.status
+    }
+
+    /// Whether batches are being fetched
+    pub fn is_running(&self) -> bool {
+        self.status == ValueSearchStatus::Running
+    }
+
+    /// Keys searched so far
+    pub fn scanned(&self) -> usize {
+        self.scanned
+    }
+
+    /// Matches found so far
+    pub fn matches(&self) -> &[ValueSearchMatch] {
+        &self.matches
+    }
+
+    /// Current search generation
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Start a new search and return its generation
+    pub fn begin(&mut self, server_id: String, cx: &mut Context<Self>) -> u64 {
+        self.server_id = Some(server_id);
+        self.status = ValueSearchStatus::Running;
+        self.scanned = 0;
+        self.matches.clear();
+        self.generation += 1;
+        cx.notify();
+        self.generation
+    }
+
+    /// Append a batch and return whether the search should continue
+    pub fn push_batch(&mut self, batch: ValueSearchBatch, cx: &mut Context<Self>) -> bool {
+        self.scanned += batch.scanned;
+        let room = VALUE_SEARCH_MAX_MATCHES.saturating_sub(self.matches.len());
+        let truncated = batch.matches.len() > room;
+        self.matches.extend(batch.matches.into_iter().take(room));
+        self.status = if truncated || self.matches.len() >= VALUE_SEARCH_MAX_MATCHES {
+            ValueSearchStatus::Truncated
+        } else if batch.next_cursor == 0 {
+            ValueSearchStatus::Finished
+        } else {
+            ValueSearchStatus::Running
+        };
+        cx.notify();
+        self.is_running()
+    }
+
+    /// Stop the running search, keeping the matches found so far
+    pub fn cancel(&mut self, cx: &mut Context<Self>) {
+        if self.is_running() {
+            self.generation += 1;
+            self.status = ValueSearchStatus::Cancelled;
+            cx.notify();
+        }
+    }
+
+    /// Record a failed batch
+    pub fn set_error(&mut self, error: impl Into<Arc<str>>, cx: &mut Context<Self>) {
+        self.status = ValueSearchStatus::Failed(error.into());
+        cx.notify();
+    }
+
+    /// Drop all matches, e.g. after switching servers
+    pub fn clear(&mut self, cx: &mut Context<Self>) {
+        self.generation += 1;
+        self.server_id = None;
+        self.status = ValueSearchStatus::Idle;
+        self.scanned = 0;
+        self.matches.clear();
+        cx.notify();
+    }
+}
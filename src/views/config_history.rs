@@ -0,0 +1,428 @@
+//! Config History View
+//!
+//! Snapshot history of the CMC iothub configs of the active config server:
+//! - One snapshot per load whose configs differ from the previous one
+//! - Pick two snapshots (A = older, B = newer) and diff them
+//! - Added or removed configs, agents and topics, visibility flags and service URLs
+
+use crate::connection::{ConfigChange, ConfigChangeKind, diff_configs};
+use crate::services::ConfigHistory;
+use crate::states::{ConfigState, DfcGlobalStore};
+use crate::views::render_simple_table;
+use gpui::{App, Context, Entity, EventEmitter, Subscription, Task, Window, div, prelude::*};
+use gpui_component::{
+    ActiveTheme, IconName, Selectable, Sizable,
+    button::{Button, ButtonVariants},
+    h_flex,
+    label::Label,
+    scroll::ScrollableElement,
+    v_flex,
+};
+use rust_i18n::t;
+
+/// Rows shown in the diff table
+const DIFF_ROWS: usize = 500;
+
+/// Events emitted by the config history view
+#[derive(Debug, Clone)]
+pub enum ConfigHistoryEvent {
+    /// Return to the agent list
+    Close,
+}
+
+/// Config snapshot history view component
+pub struct ConfigHistoryView {
+    /// Config state entity (provides the active config server)
+    config_state: Entity<ConfigState>,
+    /// Server the snapshot list belongs to
+    server_id: Option<String>,
+    /// Snapshot IDs, newest first
+    snapshot_ids: Vec<String>,
+    /// Older snapshot of the diff
+    base_id: Option<String>,
+    /// Newer snapshot of the diff
+    compare_id: Option<String>,
+    /// Differences between the two selected snapshots
+    changes: Vec<ConfigChange>,
+    /// Listing or loading error
+    error: Option<String>,
+    /// Whether the config state was loading when last observed
+    was_loading: bool,
+    /// Running snapshot listing
+    list_task: Option<Task<()>>,
+    /// Running snapshot load and diff
+    diff_task: Option<Task<()>>,
+    /// Subscriptions
+    _subscriptions: Vec<Subscription>,
+}
+
+impl EventEmitter<ConfigHistoryEvent> for ConfigHistoryView {}
+
+impl ConfigHistoryView {
+    /// Create a new config history view
+    pub fn new(
+        config_state: Entity<ConfigState>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let mut subscriptions = Vec::new();
+
+        // A finished load may have saved a new snapshot, or the active server changed
+        subscriptions.push(cx.observe(&config_state, |this, model, cx| {
+            let state = model.read(cx);
+            let active = state.active_server_id().map(str::to_string);
+            let loading = state.is_loading();
+            let load_finished = this.was_loading && !loading;
+            this.was_loading = loading;
+            if active != this.server_id || load_finished {
+                this.reload(cx);
+            }
+        }));
+
+        let mut this = Self {
+            config_state,
+            server_id: None,
+            snapshot_ids: Vec::new(),
+            base_id: None,
+            compare_id: None,
+            changes: Vec::new(),
+            error: None,
+            was_loading: false,
+            list_task: None,
+            diff_task: None,
+            _subscriptions: subscriptions,
+        };
+        this.reload(cx);
+        this
+    }
+
+    /// Get the locale string
+    fn locale(&self, cx: &App) -> String {
+        cx.global::<DfcGlobalStore>().read(cx).locale().to_string()
+    }
+
+    /// List the snapshots of the active server and diff the latest two
+    pub fn reload(&mut self, cx: &mut Context<Self>) {
+        let server_id = self
+            .config_state
+            .read(cx)
+            .active_server_id()
+            .map(str::to_string);
+        if server_id != self.server_id {
+            self.base_id = None;
+            self.compare_id = None;
+            self.changes.clear();
+        }
+        self.server_id = server_id.clone();
+        let Some(server_id) = server_id else {
+            self.snapshot_ids.clear();
+            cx.notify();
+            return;
+        };
+
+        let task = cx.spawn(async move |handle, cx| {
+            let result = cx
+                .background_spawn(async move {
+                    ConfigHistory::open_default().and_then(|history| history.list(&server_id))
+                })
+                .await;
+            let _ = handle.update(cx, |this, cx| {
+                match result {
+                    Ok(ids) => {
+                        let keep = |id: &Option<String>| {
+                            id.as_ref().filter(|id| ids.contains(id)).cloned()
+                        };
+                        this.base_id = keep(&this.base_id).or_else(|| ids.get(1).cloned());
+                        this.compare_id = keep(&this.compare_id).or_else(|| ids.first().cloned());
+                        this.snapshot_ids = ids;
+                        this.error = None;
+                    }
+                    Err(e) => {
+                        tracing::error!("Failed to list config snapshots: {}", e);
+                        this.snapshot_ids.clear();
+                        this.error = Some(e.to_string());
+                    }
+                }
+                this.load_diff(cx);
+            });
+        });
+        self.list_task = Some(task);
+    }
+
+    /// Load the two selected snapshots and diff them
+    fn load_diff(&mut self, cx: &mut Context<Self>) {
+        let (Some(server_id), Some(base_id), Some(compare_id)) = (
+            self.server_id.clone(),
+            self.base_id.clone(),
+            self.compare_id.clone(),
+        ) else {
+            self.changes.clear();
+            cx.notify();
+            return;
+        };
+
+        let task = cx.spawn(async move |handle, cx| {
+            let result = cx
+                .background_spawn(async move {
+                    let history = ConfigHistory::open_default()?;
+                    let base = history.load(&server_id, &base_id)?;
+                    let compare = history.load(&server_id, &compare_id)?;
+                    Ok::<_, crate::error::Error>(diff_configs(&base.configs, &compare.configs))
+                })
+                .await;
+            let _ = handle.update(cx, |this, cx| {
+                match result {
+                    Ok(changes) => {
+                        this.changes = changes;
+                        this.error = None;
+                    }
+                    Err(e) => {
+                        tracing::error!("Failed to load config snapshots: {}", e);
+                        this.changes.clear();
+                        this.error = Some(e.to_string());
+                    }
+                }
+                cx.notify();
+            });
+        });
+        self.diff_task = Some(task);
+    }
+
+    /// Pick the older (A) or newer (B) snapshot of the diff
+    fn select_snapshot(&mut self, id: String, is_base: bool, cx: &mut Context<Self>) {
+        if is_base {
+            self.base_id = Some(id);
+        } else {
+            self.compare_id = Some(id);
+        }
+        self.load_diff(cx);
+    }
+
+    /// Render title, refresh and close buttons
+    fn render_toolbar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let locale = self.locale(cx);
+        h_flex()
+            .w_full()
+            .gap_2()
+            .items_center()
+            .child(Label::new(t!("config_history.title", locale = &locale).to_string()).text_sm())
+            .child(div().flex_1())
+            .child(
+                Button::new("config-history-refresh")
+                    .ghost()
+                    .small()
+                    .icon(IconName::Redo)
+                    .tooltip(t!("config_history.refresh", locale = &locale).to_string())
+                    .on_click(cx.listener(|this, _, _, cx| this.reload(cx))),
+            )
+            .child(
+                Button::new("config-history-close")
+                    .ghost()
+                    .small()
+                    .icon(IconName::Close)
+                    .tooltip(t!("config_history.close", locale = &locale).to_string())
+                    .on_click(cx.listener(|_, _, _, cx| cx.emit(ConfigHistoryEvent::Close))),
+            )
+    }
+
+    /// Render the snapshot list with A/B selection buttons
+    fn render_snapshots(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let border = cx.theme().border;
+        let rows: Vec<_> = self
+            .snapshot_ids
+            .iter()
+            .enumerate()
+            .map(|(ix, id)| {
+                let base_id = id.clone();
+                let compare_id = id.clone();
+                h_flex()
+                    .w_full()
+                    .gap_1()
+                    .px_2()
+                    .py_1()
+                    .items_center()
+                    .border_b_1()
+                    .border_color(border)
+                    .child(
+                        Button::new(("config-history-base", ix))
+                            .ghost()
+                            .xsmall()
+                            .label("A")
+                            .selected(self.base_id.as_ref() == Some(id))
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.select_snapshot(base_id.clone(), true, cx);
+                            })),
+                    )
+                    .child(
+                        Button::new(("config-history-compare", ix))
+                            .ghost()
+                            .xsmall()
+                            .label("B")
+                            .selected(self.compare_id.as_ref() == Some(id))
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.select_snapshot(compare_id.clone(), false, cx);
+                            })),
+                    )
+                    .child(Label::new(ConfigHistory::label(id)).text_sm())
+            })
+            .collect();
+
+        div()
+            .id("config-history-snapshots")
+            .w(gpui::px(240.0))
+            .h_full()
+            .border_r_1()
+            .border_color(border)
+            .children(rows)
+            .overflow_y_scrollbar()
+    }
+
+    /// Render the diff of the selected snapshots
+    fn render_diff(&self, cx: &App) -> impl IntoElement {
+        let locale = self.locale(cx);
+        let muted_fg = cx.theme().muted_foreground;
+
+        if self.snapshot_ids.len() < 2 {
+            return Label::new(t!("config_history.need_two", locale = &locale).to_string())
+                .text_sm()
+                .text_color(muted_fg)
+                .into_any_element();
+        }
+        if self.base_id == self.compare_id {
+            return Label::new(t!("config_history.same_snapshot", locale = &locale).to_string())
+                .text_sm()
+                .text_color(muted_fg)
+                .into_any_element();
+        }
+        if self.changes.is_empty() {
+            return Label::new(t!("config_history.no_changes", locale = &locale).to_string())
+                .text_sm()
+                .text_color(cx.theme().success)
+                .into_any_element();
+        }
+
+        let columns = [
+            (
+                t!("config_history.change", locale = &locale).to_string(),
+                Some(80.0),
+            ),
+            (
+                t!("config_history.subject", locale = &locale).to_string(),
+                Some(110.0),
+            ),
+            (
+                t!("config_history.config", locale = &locale).to_string(),
+                None,
+            ),
+            (
+                t!("config_history.name", locale = &locale).to_string(),
+                None,
+            ),
+            (
+                t!("config_history.before", locale = &locale).to_string(),
+                Some(160.0),
+            ),
+            (
+                t!("config_history.after", locale = &locale).to_string(),
+                Some(160.0),
+            ),
+        ];
+        let rows = self
+            .changes
+            .iter()
+            .take(DIFF_ROWS)
+            .map(|change| {
+                let kind = match change.kind {
+                    ConfigChangeKind::Added => "config_history.added",
+                    ConfigChangeKind::Removed => "config_history.removed",
+                    ConfigChangeKind::Changed => "config_history.changed",
+                };
+                vec![
+                    t!(kind, locale = &locale).to_string(),
+                    t!(change.subject.label_key(), locale = &locale).to_string(),
+                    change.source.clone(),
+                    change.name.clone(),
+                    change.before.clone().unwrap_or_default(),
+                    change.after.clone().unwrap_or_default(),
+                ]
+            })
+            .collect();
+
+        v_flex()
+            .w_full()
+            .gap_1()
+            .child(
+                Label::new(
+                    t!(
+                        "config_history.summary",
+                        count = self.changes.len(),
+                        locale = &locale
+                    )
+                    .to_string(),
+                )
+                .text_xs()
+                .text_color(muted_fg),
+            )
+            .child(render_simple_table(
+                "config-history-diff",
+                &columns,
+                rows,
+                cx,
+            ))
+            .into_any_element()
+    }
+}
+
+impl Render for ConfigHistoryView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let locale = self.locale(cx);
+        let toolbar = self.render_toolbar(cx).into_any_element();
+
+        let body = if self.snapshot_ids.is_empty() {
+            div()
+                .flex_1()
+                .flex()
+                .items_center()
+                .justify_center()
+                .child(
+                    Label::new(t!("config_history.empty", locale = &locale).to_string())
+                        .text_color(cx.theme().muted_foreground),
+                )
+                .into_any_element()
+        } else {
+            let snapshots = self.render_snapshots(cx).into_any_element();
+            h_flex()
+                .flex_1()
+                .w_full()
+                .min_h(gpui::px(0.0))
+                .items_start()
+                .child(snapshots)
+                .child(
+                    div()
+                        .id("config-history-diff-scroll")
+                        .flex_1()
+                        .h_full()
+                        .px_3()
+                        .child(self.render_diff(cx))
+                        .overflow_y_scrollbar(),
+                )
+                .into_any_element()
+        };
+
+        v_flex()
+            .size_full()
+            .p_3()
+            .gap_2()
+            .child(toolbar)
+            .when_some(self.error.clone(), |this, error| {
+                this.child(
+                    Label::new(
+                        t!("config_history.failed", error = error, locale = &locale).to_string(),
+                    )
+                    .text_xs()
+                    .text_color(cx.theme().danger),
+                )
+            })
+            .child(body)
+    }
+}
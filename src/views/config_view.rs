@@ -4,20 +4,26 @@
 //! - Left panel: TopicAgentId list
 //! - Right panel: Topic tabs for selected TopicAgentId
 
+use super::config_history::{ConfigHistoryEvent, ConfigHistoryView};
+use super::prop_chart::{PropChartEvent, PropChartView};
 use super::service_panel::{
-    self, CUSTOM_TYPE_INDEX, REQUEST_TYPES, ServicePublishRequest, ServiceStreamEvent,
-    build_pulsar_client_with_fallbacks, normalize_pulsar_service_url,
+    self, CUSTOM_TYPE_INDEX, PulsarConnectOptions, REQUEST_TYPES, ServicePublishRequest,
+    ServiceStreamEvent, build_pulsar_client_with_fallbacks, normalize_pulsar_service_url,
     pulsar_service_url_candidates, run_service_topic_stream,
 };
+use super::write_guard::guard_write;
 use crate::assets::CustomIconName;
-use crate::connection::{ConfigItem, ConfigLoadState, ConnectedServerInfo, TopicAgentItem};
+use crate::connection::{
+    ConfigItem, ConfigLoadState, ConnectedServerInfo, TopicAgentItem, WritePermit,
+};
 use crate::helpers::{count_filter_values, split_filter_values};
 use crate::services::spawn_named_in_tokio;
 use crate::states::{
-    AgentQueryMode, AgentSearchSession, ConfigState, DfcAppState, DfcGlobalStore, EventRow,
-    EventSortColumn, EventTableLoadState, EventTableState, KeysState, PropRow, PropSortColumn,
-    PropTableLoadState, PropTableState, ServiceRequestRow, ServiceTableLoadState,
-    ServiceTableState, SortDirection,
+    AgentQueryMode, AgentSearchSession, ConfigState, DfcAppState, DfcGlobalStore, EventHistory,
+    EventRow, EventSortColumn, EventTableLoadState, EventTableState, KeysState,
+    PROP_CHART_MAX_SERIES, PropRow, PropSortColumn, PropTableLoadState, PropTableState,
+    ServiceRequestRow, ServiceTableLoadState, ServiceTableState, SortDirection, chart_value,
+    open_event_history,
 };
 use chrono::Local;
 use crossbeam_channel::{Receiver, Sender};
@@ -54,6 +60,8 @@ use tokio::sync::watch;
 
 /// Width of the left agent list panel
 const AGENT_LIST_WIDTH: f32 = 320.0;
+/// Height of the trend chart below the prop table
+const PROP_CHART_HEIGHT: f32 = 320.0;
 /// Height of the top bar for left/right panels (keeps alignment)
 const PANEL_TOPBAR_HEIGHT: f32 = 48.0;
 const TOPIC_FEEDBACK_TICK_MS: u64 = 120;
@@ -352,6 +360,18 @@ pub struct ServiceFormState {
     pub error_message: Option<String>,
 }
 
+/// Validated service request form, held while the safety level is confirmed.
+#[derive(Clone)]
+struct ServiceRequestDraft {
+    devices: Vec<String>,
+    imr: String,
+    requester: String,
+    args: std::collections::HashMap<String, crate::proto::iothub::AnyValue>,
+    args_summary: String,
+    timeout_ms: u32,
+    is_test: bool,
+}
+
 /// Per-column filter input states for the prop topic table.
 struct PropFilterInputs {
     global_uuid: Entity<InputState>,
@@ -559,6 +579,10 @@ pub struct ConfigView {
     prop_table_horizontal_scroll_handle: ScrollHandle,
     /// Runtime-resizable prop table column widths
     prop_column_widths: TableColumnWidths<PROP_COLUMN_COUNT>,
+    /// Trend chart of the prop points picked in the table
+    prop_chart: Entity<PropChartView>,
+    /// Server and topic the charted points belong to
+    prop_chart_source: Option<(String, String)>,
     /// Event topic table state (for `thing_event` topics)
     event_table_state: Entity<EventTableState>,
     /// Per-column filter inputs for the event topic table
@@ -609,6 +633,10 @@ pub struct ConfigView {
     last_agent_tabs_signature: AgentTabsSignature,
     agent_tabs_scrollbar_visible: bool,
     switch_feedback_until: Option<Instant>,
+    /// Config snapshot history and diff
+    config_history_view: Entity<ConfigHistoryView>,
+    /// Show the snapshot history instead of the agent panels
+    show_config_history: bool,
     /// Subscriptions
     _subscriptions: Vec<Subscription>,
 }
@@ -637,6 +665,7 @@ impl ConfigView {
 
         // Prop topic table state
         let prop_table_state = cx.new(|_| PropTableState::new());
+        let prop_chart = cx.new(|_| PropChartView::new());
         let prop_row_uid = Arc::new(AtomicU64::new(1));
         let event_table_state = cx.new(|_| EventTableState::new());
         let event_row_uid = Arc::new(AtomicU64::new(1));
@@ -836,8 +865,24 @@ impl ConfigView {
             } else {
                 this.persist_visible_prop_state_for_current_server(cx);
             }
+            this.refresh_prop_chart(cx);
             cx.notify();
         }));
+        subscriptions.push(cx.observe(&prop_chart, |_, _, cx| cx.notify()));
+        subscriptions.push(
+            cx.subscribe(
+                &prop_chart,
+                |this, _, event: &PropChartEvent, cx| match event {
+                    PropChartEvent::CompareDevices(row) => {
+                        let table = this.prop_table_state.read(cx).clone();
+                        let rows = table.points_with_imr(row, PROP_CHART_MAX_SERIES);
+                        this.prop_chart.update(cx, |chart, cx| {
+                            chart.set_points(rows, &table, cx);
+                        });
+                    }
+                },
+            ),
+        );
         subscriptions.push(cx.observe(&event_table_state, |this, _model, cx| {
             if this.suppress_event_state_persist {
                 this.suppress_event_state_persist = false;
@@ -954,6 +999,18 @@ impl ConfigView {
             this.update_agent_tabs_scrollbar_visibility(cx);
         }));
 
+        let config_history_view =
+            cx.new(|cx| ConfigHistoryView::new(config_state.clone(), window, cx));
+        subscriptions.push(cx.subscribe(
+            &config_history_view,
+            |this, _view, event, cx| match event {
+                ConfigHistoryEvent::Close => {
+                    this.show_config_history = false;
+                    cx.notify();
+                }
+            },
+        ));
+
         Self {
             app_state,
             config_state,
@@ -969,6 +1026,8 @@ impl ConfigView {
             prop_table_scroll_handle: ScrollHandle::default(),
             prop_table_horizontal_scroll_handle: ScrollHandle::default(),
             prop_column_widths: TableColumnWidths::new(PROP_DEFAULT_COLUMN_WIDTHS),
+            prop_chart,
+            prop_chart_source: None,
             event_table_state,
             event_filter_inputs,
             event_extra_filter_inputs,
@@ -1004,6 +1063,8 @@ impl ConfigView {
             last_agent_tabs_signature: (None, None, Vec::new()),
             agent_tabs_scrollbar_visible: false,
             switch_feedback_until: None,
+            config_history_view,
+            show_config_history: false,
             _subscriptions: subscriptions,
         }
     }
@@ -1710,7 +1771,15 @@ impl ConfigView {
         &mut self,
         server_id: &str,
         topic_path: &str,
+        cx: &mut Context<Self>,
     ) -> &mut EventTopicRuntime {
+        let exists = self
+            .server_topic_runtimes
+            .get(server_id)
+            .is_some_and(|runtime| runtime.event_topics.contains_key(topic_path));
+        if !exists {
+            self.load_event_history(server_id, topic_path, cx);
+        }
         let runtime = self.current_runtime_mut(server_id);
         runtime
             .event_topics
@@ -1725,6 +1794,74 @@ impl ConfigView {
             })
     }
 
+    /// Open the on-disk event history of the topic in the background when it
+    /// is enabled; the table keeps collecting rows until it is ready
+    fn load_event_history(&self, server_id: &str, topic_path: &str, cx: &mut Context<Self>) {
+        let event_history = cx
+            .global::<DfcGlobalStore>()
+            .read(cx)
+            .event_history()
+            .clone();
+        if !event_history.enabled {
+            return;
+        }
+        let server_id = server_id.to_string();
+        let topic_path = topic_path.to_string();
+        cx.spawn(async move |handle, cx| {
+            let history = cx
+                .background_spawn({
+                    let server_id = server_id.clone();
+                    let topic_path = topic_path.clone();
+                    async move { open_event_history(&server_id, &topic_path, &event_history) }
+                })
+                .await;
+            let history = match history {
+                Ok(history) => history,
+                Err(e) => {
+                    tracing::error!(
+                        server_id,
+                        topic = %topic_path,
+                        "failed to open event history: {e}"
+                    );
+                    return;
+                }
+            };
+            let _ = handle.update(cx, |this, cx| {
+                this.attach_event_history(&server_id, &topic_path, history, cx);
+            });
+        })
+        .detach();
+    }
+
+    /// Move the event table of the topic onto its opened history, both the
+    /// one of the background stream and the visible one
+    fn attach_event_history(
+        &mut self,
+        server_id: &str,
+        topic_path: &str,
+        history: EventHistory,
+        cx: &mut Context<Self>,
+    ) {
+        let is_visible = self.current_server_id(cx).as_deref() == Some(server_id)
+            && self.current_selected_topic_path_raw(cx).as_deref() == Some(topic_path);
+        if let Some(topic_runtime) = self
+            .server_topic_runtimes
+            .get_mut(server_id)
+            .and_then(|runtime| runtime.event_topics.get_mut(topic_path))
+        {
+            if topic_runtime.state.attach_history(history) && is_visible {
+                let snapshot = topic_runtime.state.clone();
+                self.replace_visible_event_state(snapshot, cx);
+            }
+        } else if is_visible {
+            self.event_table_state.update(cx, |state, cx| {
+                if state.attach_history(history) {
+                    cx.notify();
+                }
+            });
+        }
+    }
+
     fn service_topic_runtime_mut(
         &mut self,
         server_id: &str,
@@ -1974,7 +2111,7 @@ impl ConfigView {
         }
 
         let snapshot = self.event_table_state.read(cx).clone();
-        let topic_runtime = self.event_topic_runtime_mut(&server_id, &topic_path);
+        let topic_runtime = self.event_topic_runtime_mut(&server_id, &topic_path, cx);
         topic_runtime.state = snapshot;
     }
 
@@ -2209,6 +2346,7 @@ impl ConfigView {
             }
             snapshot
         } else {
+            self.load_event_history(server_id, topic_path, cx);
             let mut snapshot = EventTableState::new();
             snapshot.reset_for_topic(Some(topic_path.to_string()));
             if let Some(device_filter) = device_filter {
@@ -2245,6 +2383,39 @@ impl ConfigView {
         self.service_publish_tx = publish_tx;
     }
 
+    /// Chart the point of `row`; the secondary modifier overlays it on the
+    /// points already charted
+    fn chart_prop_row(&mut self, row: PropRow, overlay: bool, cx: &mut Context<Self>) {
+        let table = self.prop_table_state.read(cx).clone();
+        let source = self
+            .current_server_id(cx)
+            .zip(table.topic_path().map(str::to_string));
+        let overlay = overlay && self.prop_chart_source == source;
+        self.prop_chart_source = source;
+        self.prop_chart.update(cx, |chart, cx| {
+            chart.show_point(row, overlay, &table, cx);
+        });
+    }
+
+    /// Follow new samples of the charted points, or drop the chart once the
+    /// table shows another server or topic
+    fn refresh_prop_chart(&mut self, cx: &mut Context<Self>) {
+        if self.prop_chart.read(cx).is_empty() {
+            return;
+        }
+        let table = self.prop_table_state.read(cx).clone();
+        let source = self
+            .current_server_id(cx)
+            .zip(table.topic_path().map(str::to_string));
+        if self.prop_chart_source != source {
+            self.prop_chart_source = None;
+            self.prop_chart.update(cx, |chart, cx| chart.clear(cx));
+            return;
+        }
+        self.prop_chart
+            .update(cx, |chart, cx| chart.follow(&table, cx));
+    }
+
     fn replace_visible_prop_state(&mut self, snapshot: PropTableState, cx: &mut Context<Self>) {
         self.suppress_prop_state_persist = true;
         self.prop_table_state.update(cx, |state, cx| {
@@ -2645,7 +2816,7 @@ impl ConfigView {
         service_url: String,
         cfgid: String,
         topic_path: String,
-        token: Option<String>,
+        pulsar_options: PulsarConnectOptions,
         cx: &mut Context<Self>,
     ) {
         let same_topic_running = self
@@ -2669,7 +2840,7 @@ impl ConfigView {
             service_url,
             cfgid,
             topic_path,
-            token,
+            pulsar_options,
             cx,
         );
     }
@@ -2679,7 +2850,7 @@ impl ConfigView {
         server_id: &str,
         service_url: String,
         topic_path: String,
-        token: Option<String>,
+        pulsar_options: PulsarConnectOptions,
         cx: &mut Context<Self>,
     ) {
         let same_topic_running = self
@@ -2692,13 +2863,19 @@ impl ConfigView {
             return;
         }
 
-        let topic_runtime = self.event_topic_runtime_mut(server_id, &topic_path);
+        let topic_runtime = self.event_topic_runtime_mut(server_id, &topic_path, cx);
         topic_runtime
             .state
             .mark_loading_for_topic(Some(topic_path.clone()));
         tracing::info!(server_id, topic = %topic_path, "starting event topic stream");
 
-        self.start_event_stream(server_id.to_string(), service_url, topic_path, token, cx);
+        self.start_event_stream(
+            server_id.to_string(),
+            service_url,
+            topic_path,
+            pulsar_options,
+            cx,
+        );
     }
 
     fn ensure_service_stream_for_server(
@@ -2708,7 +2885,7 @@ impl ConfigView {
         topic_path: String,
         request_topic: String,
         response_topic: String,
-        token: Option<String>,
+        pulsar_options: PulsarConnectOptions,
         cx: &mut Context<Self>,
     ) {
         let same_topic_running = self
@@ -2734,7 +2911,7 @@ impl ConfigView {
             topic_path,
             request_topic,
             response_topic,
-            token,
+            pulsar_options,
             cx,
         );
     }
@@ -2773,12 +2950,10 @@ impl ConfigView {
             return;
         };
 
-        let token = {
-            let app = cx.global::<DfcGlobalStore>().read(cx);
-            let server = app.server(&server_id);
-            server
-                .and_then(|s| s.pulsar_token.clone())
-                .filter(|t| !t.trim().is_empty())
+        let pulsar_options = {
+            let store = cx.global::<DfcGlobalStore>();
+            let server = store.read(cx).server(&server_id);
+            PulsarConnectOptions::for_server(server, store.services().ssh_tunnels())
         };
 
         if let Some(topic_path) = selected_topic_path
@@ -2811,7 +2986,7 @@ impl ConfigView {
                 service_url,
                 cfgid,
                 topic_path.clone(),
-                token,
+                pulsar_options,
                 cx,
             );
             self.load_visible_prop_state_for_server(&server_id, &topic_path, window, cx);
@@ -2828,7 +3003,8 @@ impl ConfigView {
                 match find_topic_service_url(config_state.configs(), &topic_path) {
                     Some(service_url) => service_url,
                     None => {
-                        let topic_runtime = self.event_topic_runtime_mut(&server_id, &topic_path);
+                        let topic_runtime =
+                            self.event_topic_runtime_mut(&server_id, &topic_path, cx);
                         topic_runtime
                             .state
                             .set_error("无法定位该 Topic 对应的 service_url");
@@ -2848,7 +3024,7 @@ impl ConfigView {
                 &server_id,
                 service_url,
                 topic_path.clone(),
-                token,
+                pulsar_options,
                 cx,
             );
             self.load_visible_event_state_for_server(&server_id, &topic_path, window, cx);
@@ -2889,7 +3065,7 @@ impl ConfigView {
                 topic_path.clone(),
                 request_topic,
                 response_topic,
-                token,
+                pulsar_options,
                 cx,
             );
             self.load_visible_service_state_for_server(&server_id, &topic_path, cx);
@@ -2905,7 +3081,7 @@ impl ConfigView {
         service_url: String,
         cfgid: String,
         topic_path: String,
-        token: Option<String>,
+        pulsar_options: PulsarConnectOptions,
         cx: &mut Context<Self>,
     ) {
         let (tx, rx): (Sender<PropStreamEvent>, Receiver<PropStreamEvent>) =
@@ -2924,7 +3100,7 @@ impl ConfigView {
             run_prop_topic_stream(
                 service_url,
                 stream_topic_path,
-                token,
+                pulsar_options,
                 cfgid,
                 redis,
                 stop_rx,
@@ -3018,12 +3194,17 @@ impl ConfigView {
                             == Some(runtime_server_id.as_str())
                             && this.current_selected_topic_path_raw(cx).as_deref()
                                 == Some(topic_path.as_str());
+                        let history_limit = cx
+                            .global::<DfcGlobalStore>()
+                            .read(cx)
+                            .prop_history_max_rows();
                         let mut snapshot = None;
                         if let Some(topic_runtime) = this
                             .server_topic_runtimes
                             .get_mut(&runtime_server_id)
                             .and_then(|runtime| runtime.prop_topics.get_mut(topic_path.as_str()))
                         {
+                            topic_runtime.state.set_history_limit(history_limit);
                             topic_runtime.state.push_rows_front(rows);
                             if is_visible {
                                 snapshot = Some(topic_runtime.state.clone());
@@ -3086,13 +3267,13 @@ impl ConfigView {
         server_id: String,
         service_url: String,
         topic_path: String,
-        token: Option<String>,
+        pulsar_options: PulsarConnectOptions,
         cx: &mut Context<Self>,
     ) {
         let (tx, rx): (Sender<EventStreamEvent>, Receiver<EventStreamEvent>) =
             crossbeam_channel::unbounded();
         let (stop_tx, stop_rx) = watch::channel(false);
-        self.event_topic_runtime_mut(&server_id, &topic_path)
+        self.event_topic_runtime_mut(&server_id, &topic_path, cx)
             .stream_stop = Some(stop_tx);
 
         let uid = self.event_row_uid.clone();
@@ -3101,7 +3282,15 @@ impl ConfigView {
         let runtime_topic_path = topic_path.clone();
 
         spawn_named_in_tokio("event-topic-stream", async move {
-            run_event_topic_stream(service_url, stream_topic_path, token, stop_rx, tx, uid).await;
+            run_event_topic_stream(
+                service_url,
+                stream_topic_path,
+                pulsar_options,
+                stop_rx,
+                tx,
+                uid,
+            )
+            .await;
         });
 
         let task = cx.spawn(async move |handle, cx| {
@@ -3247,7 +3436,7 @@ impl ConfigView {
             }
         });
 
-        self.event_topic_runtime_mut(&server_id, &runtime_topic_path)
+        self.event_topic_runtime_mut(&server_id, &runtime_topic_path, cx)
             .ingest_task = Some(task);
     }
 
@@ -3258,7 +3447,7 @@ impl ConfigView {
         topic_path: String,
         request_topic: String,
         response_topic: String,
-        token: Option<String>,
+        pulsar_options: PulsarConnectOptions,
         cx: &mut Context<Self>,
     ) {
         let (event_tx, event_rx): (Sender<ServiceStreamEvent>, Receiver<ServiceStreamEvent>) =
@@ -3279,12 +3468,14 @@ impl ConfigView {
         let runtime_server_id = server_id.clone();
         let runtime_topic_path = topic_path.clone();
 
+        let stream_server_id = server_id.clone();
         spawn_named_in_tokio("service-topic-stream", async move {
             run_service_topic_stream(
+                stream_server_id,
                 service_url,
                 request_topic,
                 response_topic,
-                token,
+                pulsar_options,
                 stop_rx,
                 publish_rx,
                 event_tx,
@@ -3415,7 +3606,7 @@ impl ConfigView {
             .ingest_task = Some(task);
     }
 
-    fn on_submit_service_request(&mut self, cx: &mut Context<Self>) {
+    fn on_submit_service_request(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         let devices_raw = self.service_form.devices_input.read(cx).value().to_string();
         let devices: Vec<String> = devices_raw
             .split('\n')
@@ -3506,10 +3697,63 @@ impl ConfigView {
         self.service_form.error_message = None;
 
         let is_test = self.service_form.is_test;
+
+        if self.service_publish_tx.is_none() {
+            self.service_form.error_message =
+                Some("服务流尚未就绪,请先选中 service Topic".to_string());
+            cx.notify();
+            return;
+        }
+        let Some(server) = self
+            .current_server_id(cx)
+            .and_then(|server_id| self.app_state.read(cx).server(&server_id).cloned())
+        else {
+            return;
+        };
+
+        // Service requests go out only with a permit from the server's safety level
+        let action = format!("{imr} → {}", devices.join(", "));
+        let draft = ServiceRequestDraft {
+            devices,
+            imr,
+            requester,
+            args: parsed_args,
+            args_summary,
+            timeout_ms,
+            is_test,
+        };
+        let view = cx.entity();
+        guard_write(&server, action, window, cx, move |result, _, cx| {
+            let draft = draft.clone();
+            view.update(cx, |this, cx| match result {
+                Ok(permit) => this.publish_service_request(permit, draft, cx),
+                Err(e) => {
+                    this.service_form.error_message = Some(e.to_string());
+                    cx.notify();
+                }
+            });
+        });
+    }
+
+    /// Queue a validated service request for every device
+    fn publish_service_request(
+        &mut self,
+        permit: WritePermit,
+        draft: ServiceRequestDraft,
+        cx: &mut Context<Self>,
+    ) {
+        let ServiceRequestDraft {
+            devices,
+            imr,
+            requester,
+            args: parsed_args,
+            args_summary,
+            timeout_ms,
+            is_test,
+        } = draft;
         let now_local = chrono::Local::now()
             .format("%Y-%m-%d %H:%M:%S%.3f")
             .to_string();
-
         let Some(publish_tx) = self.service_publish_tx.clone() else {
             self.service_form.error_message =
                 Some("服务流尚未就绪,请先选中 service Topic".to_string());
@@ -3553,6 +3797,7 @@ impl ConfigView {
             if let Err(e) = publish_tx.send(ServicePublishRequest {
                 device: device.clone(),
                 record,
+                permit: permit.clone(),
             }) {
                 self.service_form.error_message = Some(format!("发送请求队列失败 ({device}): {e}"));
                 cx.notify();
@@ -4132,6 +4377,17 @@ impl ConfigView {
             .suffix(search_btn)
             .cleanable(true);
 
+        let history_btn = Button::new("config-history-btn")
+            .ghost()
+            .icon(IconName::Calendar)
+            .tooltip(t!("config_history.open", locale = &locale).to_string())
+            .on_click(cx.listener(|this, _, _, cx| {
+                this.show_config_history = true;
+                this.config_history_view
+                    .update(cx, |view, cx| view.reload(cx));
+                cx.notify();
+            }));
+
         v_flex()
             .flex_none()
             .w(px(AGENT_LIST_WIDTH))
@@ -4147,7 +4403,9 @@ impl ConfigView {
                     .items_center()
                     .border_b_1()
                     .border_color(border_color)
-                    .child(keyword_input),
+                    .gap_1()
+                    .child(keyword_input)
+                    .child(history_btn),
             )
             // Agent list
             .child(
@@ -4388,13 +4646,18 @@ impl ConfigView {
 
         match &load_state {
             PropTableLoadState::Error(msg) => {
+                let locale = self.locale(cx);
+                let error_detail = msg.localized_message(&locale);
                 return div()
                     .flex_1()
                     .h_0()
                     .min_w(px(0.0))
                     .min_h(px(0.0))
                     .p_4()
-                    .child(Label::new(format!("加载失败: {msg}")).text_color(cx.theme().danger))
+                    .child(
+                        Label::new(format!("加载失败: {error_detail}"))
+                            .text_color(cx.theme().danger),
+                    )
                     .into_any_element();
             }
             _ => {}
@@ -4402,11 +4665,23 @@ impl ConfigView {
 
         let page_rows = self.prop_table_state.read(cx).page_rows_owned();
         let table_width = self.prop_column_widths.total();
+        let (chart_open, charted_rows) = {
+            let prop_chart = self.prop_chart.read(cx);
+            (
+                !prop_chart.is_empty(),
+                page_rows
+                    .iter()
+                    .map(|row| prop_chart.contains(row))
+                    .collect::<Vec<_>>(),
+            )
+        };
 
         // Build rows
         let mut rows = Vec::new();
         for (idx, row) in page_rows.iter().enumerate() {
-            let bg = if idx % 2 == 0 {
+            let bg = if charted_rows[idx] {
+                cx.theme().accent
+            } else if idx % 2 == 0 {
                 if cx.theme().is_dark() {
                     cx.theme().background.lighten(0.3)
                 } else {
@@ -4423,6 +4698,15 @@ impl ConfigView {
                     .bg(bg)
                     .border_b_1()
                     .border_color(border)
+                    .when(chart_value(&row.value).is_some(), |this| {
+                        let row = row.clone();
+                        this.cursor_pointer().on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(move |this, event: &MouseDownEvent, _, cx| {
+                                this.chart_prop_row(row.clone(), event.modifiers.secondary(), cx);
+                            }),
+                        )
+                    })
                     .child(self.render_prop_cell(
                         TableCellId::new(row.uid, "prop-global-uuid"),
                         self.prop_column_width(PropSortColumn::GlobalUuid),
@@ -4652,6 +4936,16 @@ impl ConfigView {
                             )),
                     ),
             )
+            .when(chart_open, |this| {
+                this.child(
+                    div()
+                        .w_full()
+                        .h(px(PROP_CHART_HEIGHT))
+                        .flex_none()
+                        .pt_3()
+                        .child(self.prop_chart.clone()),
+                )
+            })
             .into_any_element()
     }
 
@@ -5149,8 +5443,8 @@ impl ConfigView {
                         Button::new("svc-submit")
                             .primary()
                             .label("发起请求")
-                            .on_click(cx.listener(|this, _, _, cx| {
-                                this.on_submit_service_request(cx);
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.on_submit_service_request(window, cx);
                             })),
                     )
                     .child(
@@ -6592,6 +6886,13 @@ impl ConfigView {
 
     /// Render the main content based on state
     fn render_content(&self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        if self.show_config_history {
+            return div()
+                .size_full()
+                .child(self.config_history_view.clone())
+                .into_any_element();
+        }
+
         let load_state = self.config_state.read(cx).load_state().clone();
         let (has_configs, has_topic_agents) = {
             let config_state = self.config_state.read(cx);
@@ -6967,14 +7268,15 @@ fn parse_event_rows_from_payload(payload: &[u8], uid: &AtomicU64) -> (Vec<EventR
 async fn run_prop_topic_stream(
     service_url: String,
     topic_path: String,
-    token: Option<String>,
+    pulsar_options: PulsarConnectOptions,
     cfgid: String,
     redis: Arc<crate::services::RedisRepo>,
     mut stop: watch::Receiver<bool>,
     tx: Sender<PropStreamEvent>,
     uid: Arc<AtomicU64>,
 ) {
-    let imid2imr = match redis.fetch_imid2imr(&cfgid).await {
+    let mut imid2imr_revision = redis.imid2imr_revision();
+    let mut imid2imr = match redis.fetch_imid2imr(&cfgid).await {
         Ok(map) => map,
         Err(e) => {
             tracing::warn!("Failed to load IMID->IMR mapping: {}", e);
@@ -7017,7 +7319,7 @@ async fn run_prop_topic_stream(
         }
 
         let (client, connected_service_url): (pulsar::Pulsar<_>, String) =
-            match build_pulsar_client_with_fallbacks(&service_urls, token.as_deref()).await {
+            match build_pulsar_client_with_fallbacks(&service_urls, &pulsar_options).await {
                 Ok(client) => client,
                 Err(e) => {
                     let _ = tx.send(PropStreamEvent::Error(format!("Pulsar 连接失败: {e}")));
@@ -7146,6 +7448,19 @@ async fn run_prop_topic_stream(
                         "prop topic consumer heartbeat"
                     );
                 }
+                Ok(()) = imid2imr_revision.changed() => {
+                    match redis.fetch_imid2imr(&cfgid).await {
+                        Ok(map) => {
+                            tracing::info!(
+                                topic = %topic_path,
+                                count = map.len(),
+                                "Reloaded IMID->IMR mapping after config change"
+                            );
+                            imid2imr = map;
+                        }
+                        Err(e) => tracing::warn!("Failed to reload IMID->IMR mapping: {}", e),
+                    }
+                }
                 msg = consumer.next() => {
                     match msg {
                         Some(Ok(message)) => {
@@ -7201,7 +7516,7 @@ async fn run_prop_topic_stream(
 async fn run_event_topic_stream(
     service_url: String,
     topic_path: String,
-    token: Option<String>,
+    pulsar_options: PulsarConnectOptions,
     mut stop: watch::Receiver<bool>,
     tx: Sender<EventStreamEvent>,
     uid: Arc<AtomicU64>,
@@ -7241,7 +7556,7 @@ async fn run_event_topic_stream(
         }
 
         let (client, connected_service_url): (pulsar::Pulsar<_>, String) =
-            match build_pulsar_client_with_fallbacks(&service_urls, token.as_deref()).await {
+            match build_pulsar_client_with_fallbacks(&service_urls, &pulsar_options).await {
                 Ok(client) => client,
                 Err(e) => {
                     let _ = tx.send(EventStreamEvent::Error(format!("Pulsar 连接失败: {e}")));
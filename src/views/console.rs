@@ -0,0 +1,540 @@
+//! Redis Console View
+//!
+//! Raw command console for the active Redis server:
+//! - Command input with tab completion and per-server history (Up/Down)
+//! - Transcript with replies rendered as collapsible trees, newest first
+//! - Read-only toggle; writes need it off and a permit from the server's
+//!   safety level
+
+use super::write_guard::guard_write;
+use crate::connection::{
+    RedisReply, SafetyLevel, WritePermit, common_prefix, complete_command, has_credentials,
+    is_unsupported_command, is_write_command, parse_command_line,
+};
+use crate::helpers::ConsoleAction;
+use crate::states::{ConsoleEntry, ConsoleState, DfcGlobalStore, KeysState};
+use gpui::{
+    AnyElement, App, Context, Entity, SharedString, Subscription, Window, div, prelude::*, px,
+};
+use gpui_component::{
+    ActiveTheme, Disableable, Icon, IconName, Sizable,
+    button::{Button, ButtonVariants},
+    checkbox::Checkbox,
+    h_flex,
+    input::{Input, InputEvent, InputState},
+    label::Label,
+    scroll::ScrollableElement,
+    v_flex,
+};
+use rust_i18n::t;
+use std::collections::HashSet;
+use std::time::Instant;
+
+/// Indentation per reply tree level in pixels
+const REPLY_INDENT: f32 = 16.0;
+
+/// Redis console view component
+pub struct ConsoleView {
+    /// Keys state entity (provides the active server)
+    keys_state: Entity<KeysState>,
+    /// Console history and transcript
+    console_state: Entity<ConsoleState>,
+    /// Command input state
+    input_state: Entity<InputState>,
+    /// History position while browsing with Up/Down (`None` while typing)
+    history_cursor: Option<usize>,
+    /// Command names offered by the last ambiguous completion
+    completions: Vec<&'static str>,
+    /// Collapsed reply nodes, keyed by `entry_id/child/...`
+    collapsed: HashSet<String>,
+    /// Subscriptions
+    _subscriptions: Vec<Subscription>,
+}
+
+impl ConsoleView {
+    /// Create a new console view
+    pub fn new(keys_state: Entity<KeysState>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let console_state = cx.new(|_| ConsoleState::new());
+        let mut subscriptions = Vec::new();
+
+        subscriptions.push(cx.observe(&console_state, |_this, _model, cx| {
+            cx.notify();
+        }));
+
+        // Switching servers invalidates the history position
+        subscriptions.push(cx.observe(&keys_state, |this, _model, cx| {
+            this.history_cursor = None;
+            cx.notify();
+        }));
+
+        let input_state = cx.new(|cx| {
+            let locale = cx.global::<DfcGlobalStore>().read(cx).locale().to_string();
+            let placeholder = t!("console.placeholder", locale = &locale).to_string();
+            InputState::new(window, cx).placeholder(placeholder)
+        });
+
+        subscriptions.push(cx.subscribe_in(
+            &input_state,
+            window,
+            |this, _state, event, window, cx| match event {
+                InputEvent::PressEnter { .. } => this.execute(window, cx),
+                InputEvent::Change if !this.completions.is_empty() => {
+                    this.completions.clear();
+                    cx.notify();
+                }
+                _ => {}
+            },
+        ));
+
+        Self {
+            keys_state,
+            console_state,
+            input_state,
+            history_cursor: None,
+            completions: Vec::new(),
+            collapsed: HashSet::new(),
+            _subscriptions: subscriptions,
+        }
+    }
+
+    /// Get the locale string
+    fn locale(&self, cx: &App) -> String {
+        cx.global::<DfcGlobalStore>().read(cx).locale().to_string()
+    }
+
+    fn active_server_id(&self, cx: &App) -> Option<String> {
+        self.keys_state
+            .read(cx)
+            .active_server_id()
+            .map(str::to_string)
+    }
+
+    /// Whether the server's safety level allows writes at all
+    fn server_writable(&self, server_id: &str, cx: &App) -> bool {
+        cx.global::<DfcGlobalStore>()
+            .read(cx)
+            .server(server_id)
+            .is_some_and(|server| server.safety != SafetyLevel::ReadOnly)
+    }
+
+    fn set_input(&mut self, value: String, window: &mut Window, cx: &mut Context<Self>) {
+        self.input_state.update(cx, |state, cx| {
+            state.set_value(value, window, cx);
+        });
+    }
+
+    /// Record a command that was rejected before reaching the server
+    fn reject(
+        &mut self,
+        server_id: &str,
+        command: String,
+        message: String,
+        cx: &mut Context<Self>,
+    ) {
+        self.console_state.update(cx, |state, cx| {
+            state.push_entry(server_id, command, RedisReply::Error(message), 0, cx);
+        });
+    }
+
+    /// Execute the command line in the input
+    fn execute(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let line = self.input_state.read(cx).value().trim().to_string();
+        if line.is_empty() || self.console_state.read(cx).running() {
+            return;
+        }
+        let Some(server_id) = self.active_server_id(cx) else {
+            return;
+        };
+        let locale = self.locale(cx);
+
+        self.set_input(String::new(), window, cx);
+        self.history_cursor = None;
+        self.completions.clear();
+
+        let args = match parse_command_line(&line) {
+            Ok(args) if args.is_empty() => return,
+            Ok(args) => args,
+            Err(message) => {
+                self.reject(&server_id, line, message, cx);
+                return;
+            }
+        };
+        // The history is saved to disk, so lines with a password stay out of it
+        if !has_credentials(&args) {
+            self.console_state.update(cx, |state, cx| {
+                state.push_history(&server_id, &line, cx);
+            });
+        }
+
+        if is_unsupported_command(&args) {
+            let message = t!(
+                "console.unsupported",
+                command = args[0].to_ascii_uppercase(),
+                locale = &locale
+            )
+            .to_string();
+            self.reject(&server_id, line, message, cx);
+            return;
+        }
+
+        if !is_write_command(&args) {
+            self.run(server_id, line, args, None, cx);
+            return;
+        }
+
+        let message = if !self.server_writable(&server_id, cx) {
+            Some(t!("console.server_read_only", locale = &locale).to_string())
+        } else if self.console_state.read(cx).read_only() {
+            Some(t!("console.read_only_rejected", locale = &locale).to_string())
+        } else {
+            None
+        };
+        if let Some(message) = message {
+            self.reject(&server_id, line, message, cx);
+            return;
+        }
+        let Some(server) = cx
+            .global::<DfcGlobalStore>()
+            .read(cx)
+            .server(&server_id)
+            .cloned()
+        else {
+            return;
+        };
+        let view = cx.entity();
+        guard_write(&server, line.clone(), window, cx, move |result, _, cx| {
+            let server_id = server_id.clone();
+            let line = line.clone();
+            let args = args.clone();
+            view.update(cx, |this, cx| match result {
+                Ok(permit) => this.run(server_id, line, args, Some(permit), cx),
+                Err(e) => this.reject(&server_id, line, e.to_string(), cx),
+            });
+        });
+    }
+
+    /// Send a parsed command to the server; write commands carry their permit
+    fn run(
+        &mut self,
+        server_id: String,
+        line: String,
+        args: Vec<String>,
+        permit: Option<WritePermit>,
+        cx: &mut Context<Self>,
+    ) {
+        self.console_state.update(cx, |state, cx| {
+            state.set_running(true, cx);
+        });
+
+        let store = cx.global::<DfcGlobalStore>().clone();
+        let console_state = self.console_state.clone();
+        cx.spawn(async move |_, cx| {
+            let started = Instant::now();
+            let result = store
+                .services()
+                .redis()
+                .execute_command(args, permit.as_ref())
+                .await;
+            let elapsed_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+            let reply = result.unwrap_or_else(|e| {
+                tracing::error!("Console command failed: {}", e);
+                RedisReply::Error(e.to_string())
+            });
+            let _ = console_state.update(cx, |state, cx| {
+                state.push_entry(&server_id, line, reply, elapsed_ms, cx);
+                state.set_running(false, cx);
+            });
+        })
+        .detach();
+    }
+
+    /// Complete the command name typed so far
+    fn complete(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let text = self.input_state.read(cx).value().to_string();
+        let prefix = text.trim_start();
+        // Only the command name (first word) is completed
+        if prefix.is_empty() || prefix.contains(char::is_whitespace) {
+            return;
+        }
+
+        let candidates = complete_command(prefix);
+        match candidates.as_slice() {
+            [] => {}
+            [only] => {
+                self.set_input(format!("{only} "), window, cx);
+                self.completions.clear();
+            }
+            _ => {
+                let common = common_prefix(&candidates);
+                if common.len() > prefix.len() {
+                    self.set_input(common.to_string(), window, cx);
+                }
+                // Set after the input change, which clears the list
+                self.completions = candidates;
+            }
+        }
+        cx.notify();
+    }
+
+    /// Move through the server's command history
+    fn recall_history(&mut self, previous: bool, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(server_id) = self.active_server_id(cx) else {
+            return;
+        };
+        let history = self.console_state.read(cx).history(&server_id);
+        if history.is_empty() {
+            return;
+        }
+
+        let cursor = match (self.history_cursor, previous) {
+            (None, true) => Some(history.len() - 1),
+            (None, false) => return,
+            (Some(ix), true) => Some(ix.saturating_sub(1)),
+            (Some(ix), false) if ix + 1 < history.len() => Some(ix + 1),
+            (Some(_), false) => None,
+        };
+        let value = cursor
+            .and_then(|ix| history.get(ix))
+            .cloned()
+            .unwrap_or_default();
+        self.history_cursor = cursor;
+        self.set_input(value, window, cx);
+    }
+
+    fn toggle_node(&mut self, path: String, cx: &mut Context<Self>) {
+        if !self.collapsed.remove(&path) {
+            self.collapsed.insert(path);
+        }
+        cx.notify();
+    }
+
+    /// Render a reply node and, if expanded, its children.
+    ///
+    /// `path` is `entry_id` followed by `/child` per level.
+    fn push_reply_rows(
+        &self,
+        path: String,
+        label: Option<String>,
+        reply: &RedisReply,
+        rows: &mut Vec<AnyElement>,
+        cx: &mut Context<Self>,
+    ) {
+        let depth = path.matches('/').count();
+        let nested = reply.is_nested();
+        let expanded = nested && !self.collapsed.contains(&path);
+        let color = match reply {
+            RedisReply::Error(_) => cx.theme().danger,
+            RedisReply::Nil => cx.theme().muted_foreground,
+            RedisReply::Integer(_) | RedisReply::Double(_) | RedisReply::Boolean(_) => {
+                cx.theme().info
+            }
+            RedisReply::String(_) => cx.theme().foreground,
+            RedisReply::Array(_) | RedisReply::Map(_) => cx.theme().muted_foreground,
+        };
+
+        let mut row = h_flex()
+            .id(SharedString::from(format!("console-node-{path}")))
+            .w_full()
+            .pl(px(depth as f32 * REPLY_INDENT))
+            .gap_1()
+            .items_start()
+            .child(div().w(px(12.0)).flex_shrink_0().when(nested, |this| {
+                this.child(
+                    Icon::new(if expanded {
+                        IconName::ChevronDown
+                    } else {
+                        IconName::ChevronRight
+                    })
+                    .size_3()
+                    .text_color(cx.theme().muted_foreground),
+                )
+            }))
+            .when_some(label, |this, label| {
+                this.child(
+                    Label::new(label)
+                        .text_sm()
+                        .text_color(cx.theme().muted_foreground),
+                )
+            })
+            .child(
+                Label::new(reply.summary())
+                    .text_sm()
+                    .text_color(color)
+                    .flex_1(),
+            );
+        if nested {
+            let toggle_path = path.clone();
+            row = row
+                .cursor_pointer()
+                .hover(|this| this.bg(cx.theme().accent.opacity(0.5)))
+                .on_click(cx.listener(move |this, _, _, cx| {
+                    this.toggle_node(toggle_path.clone(), cx);
+                }));
+        }
+        rows.push(row.into_any_element());
+
+        if !expanded {
+            return;
+        }
+        match reply {
+            RedisReply::Array(items) => {
+                for (ix, item) in items.iter().enumerate() {
+                    let label = format!("{})", ix + 1);
+                    self.push_reply_rows(format!("{path}/{ix}"), Some(label), item, rows, cx);
+                }
+            }
+            RedisReply::Map(pairs) => {
+                for (ix, (key, value)) in pairs.iter().enumerate() {
+                    let label = format!("{key}:");
+                    self.push_reply_rows(format!("{path}/{ix}"), Some(label), value, rows, cx);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Render one transcript entry: prompt line followed by the reply tree
+    fn render_entry(&self, entry: &ConsoleEntry, cx: &mut Context<Self>) -> impl IntoElement {
+        let mut rows = Vec::new();
+        self.push_reply_rows(entry.id.to_string(), None, &entry.reply, &mut rows, cx);
+
+        v_flex()
+            .w_full()
+            .gap_1()
+            .py_2()
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .child(
+                h_flex()
+                    .w_full()
+                    .gap_2()
+                    .child(
+                        Label::new(format!("> {}", entry.command))
+                            .text_sm()
+                            .text_color(cx.theme().primary)
+                            .flex_1(),
+                    )
+                    .child(
+                        Label::new(format!("{} ms", entry.elapsed_ms))
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground),
+                    ),
+            )
+            .children(rows)
+    }
+
+    /// Render the toolbar with the read-only toggle and clear button
+    fn render_toolbar(&self, server_id: &str, cx: &mut Context<Self>) -> impl IntoElement {
+        let locale = self.locale(cx);
+        let writable = self.server_writable(server_id, cx);
+        let read_only = !writable || self.console_state.read(cx).read_only();
+        let clear_server_id = server_id.to_string();
+
+        let read_only_toggle = Checkbox::new("console-read-only")
+            .label(t!("console.read_only", locale = &locale).to_string())
+            .checked(read_only)
+            .disabled(!writable)
+            .on_click(cx.listener(|this, checked: &bool, _, cx| {
+                this.console_state.update(cx, |state, cx| {
+                    state.set_read_only(*checked, cx);
+                });
+            }));
+
+        h_flex()
+            .w_full()
+            .gap_2()
+            .items_center()
+            .child(read_only_toggle)
+            .when(!writable, |this| {
+                this.child(
+                    Label::new(t!("console.server_read_only", locale = &locale).to_string())
+                        .text_xs()
+                        .text_color(cx.theme().muted_foreground),
+                )
+            })
+            .child(div().flex_1())
+            .child(
+                Button::new("console-clear")
+                    .ghost()
+                    .xsmall()
+                    .icon(IconName::CircleX)
+                    .label(t!("console.clear", locale = &locale).to_string())
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        this.collapsed.clear();
+                        this.console_state.update(cx, |state, cx| {
+                            state.clear_transcript(&clear_server_id, cx);
+                        });
+                    })),
+            )
+    }
+}
+
+impl Render for ConsoleView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let locale = self.locale(cx);
+        let Some(server_id) = self.active_server_id(cx) else {
+            return div()
+                .size_full()
+                .flex()
+                .items_center()
+                .justify_center()
+                .child(
+                    Label::new(t!("console.no_server", locale = &locale).to_string())
+                        .text_color(cx.theme().muted_foreground),
+                )
+                .into_any_element();
+        };
+
+        let running = self.console_state.read(cx).running();
+        let entries: Vec<ConsoleEntry> = self
+            .console_state
+            .read(cx)
+            .transcript(&server_id)
+            .iter()
+            .rev()
+            .cloned()
+            .collect();
+        let entry_elements: Vec<AnyElement> = entries
+            .iter()
+            .map(|entry| self.render_entry(entry, cx).into_any_element())
+            .collect();
+
+        v_flex()
+            .size_full()
+            .p_3()
+            .gap_2()
+            .child(self.render_toolbar(&server_id, cx))
+            .child(
+                div()
+                    .key_context("RedisConsole")
+                    .on_action(cx.listener(
+                        |this, action: &ConsoleAction, window, cx| match action {
+                            ConsoleAction::Complete => this.complete(window, cx),
+                            ConsoleAction::HistoryPrevious => this.recall_history(true, window, cx),
+                            ConsoleAction::HistoryNext => this.recall_history(false, window, cx),
+                        },
+                    ))
+                    .child(
+                        Input::new(&self.input_state)
+                            .prefix(Label::new(">").text_color(cx.theme().muted_foreground))
+                            .disabled(running),
+                    ),
+            )
+            .when(!self.completions.is_empty(), |this| {
+                this.child(
+                    Label::new(self.completions.join("  "))
+                        .text_xs()
+                        .text_color(cx.theme().muted_foreground),
+                )
+            })
+            .child(
+                div()
+                    .id("console-transcript")
+                    .flex_1()
+                    .w_full()
+                    .child(v_flex().w_full().children(entry_elements))
+                    .overflow_y_scrollbar(),
+            )
+            .into_any_element()
+    }
+}
@@ -2,10 +2,20 @@
 //!
 //! Routes to different views based on the current application route.
 
+use super::pulsar_security_form::PulsarSecurityInputs;
+use super::server_diagnostics::open_server_diagnostics;
+use super::server_transfer::{ServerImportEvent, open_server_export, open_server_import};
+use super::vault_settings::{VaultSettingsEvent, VaultSettingsView};
+use super::write_guard::{render_badge, render_safety_badge};
 use crate::assets::CustomIconName;
-use crate::connection::{DfcServerConfig, credentials_to_text, text_to_credentials};
+use crate::connection::{
+    ConfigSnapshot, ConfigWatchChange, ConfigWatchEvent, DfcServerConfig, ProxyConfig, SafetyLevel,
+    ServerEnvironment, ServerSortOrder, SshTunnelConfig, WorkingCredential, collect_tags,
+    credentials_to_text, group_servers, parse_tags, sort_servers, text_to_credentials,
+};
 use crate::constants::DEFAULT_PULSAR_TOKEN;
-use crate::helpers::DeviceAction;
+use crate::helpers::{DeviceAction, vault_key, vault_locked};
+use crate::services::{ConfigHistory, HostKeyPrompt, spawn_named_in_tokio};
 use crate::states::{
     ConfigState, DfcAppState, DfcGlobalStore, FleetState, HomeLayoutMode, KeysState, Route,
     UIEvent, i18n_common, i18n_format, i18n_servers, i18n_settings, i18n_sidebar,
@@ -13,26 +23,32 @@ use crate::states::{
 };
 use crate::views::{ConfigView, KeysBrowserView};
 use gpui::{
-    App, Context, Entity, FocusHandle, SharedString, Subscription, Window, div, prelude::*, px,
+    App, Context, Entity, FocusHandle, SharedString, Subscription, Task, Window, div, prelude::*,
+    px,
 };
 use gpui_component::{
     ActiveTheme, Colorize, Icon, IconName, Selectable, Sizable, WindowExt,
     button::{Button, ButtonVariants},
+    checkbox::Checkbox,
     form::{field, v_form},
     h_flex,
     input::{Input, InputEvent, InputState, NumberInput, NumberInputEvent, StepAction},
     label::Label,
-    menu::{ContextMenuExt, PopupMenuItem},
+    menu::{ContextMenuExt, DropdownMenu, PopupMenuItem},
     scroll::ScrollableElement,
     v_flex,
 };
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::sync::watch;
 
 /// Width of the keyword search input
 const KEYWORD_INPUT_WIDTH: f32 = 200.0;
+/// Config watch changes arriving within this window trigger one reload
+const CONFIG_WATCH_DEBOUNCE_MS: u64 = 500;
 
 /// Default Redis port
 const DEFAULT_REDIS_PORT: u16 = 6379;
@@ -70,22 +86,65 @@ pub struct DfcContent {
 
     // Server form input states
     name_state: Entity<InputState>,
+    group_state: Entity<InputState>,
+    tags_state: Entity<InputState>,
+    /// Environment label of the edited server
+    environment: Rc<Cell<Option<ServerEnvironment>>>,
     host_state: Entity<InputState>,
     port_state: Entity<InputState>,
     password_state: Entity<InputState>,
     cfgid_state: Entity<InputState>,
     device_filter_state: Entity<InputState>,
     pulsar_token_state: Entity<InputState>,
+    pulsar_security: PulsarSecurityInputs,
+    /// Safety level of the edited server
+    safety: Rc<Cell<SafetyLevel>>,
+    db_state: Entity<InputState>,
+    sentinel_master_state: Entity<InputState>,
+    sentinel_nodes_state: Entity<InputState>,
+    /// Whether the edited server is reached over TLS
+    tls: Rc<Cell<bool>>,
+    /// Whether TLS certificate verification is skipped
+    tls_skip_verify: Rc<Cell<bool>>,
+    tls_ca_file_state: Entity<InputState>,
+    tls_cert_file_state: Entity<InputState>,
+    tls_key_file_state: Entity<InputState>,
+    ssh_host_state: Entity<InputState>,
+    ssh_password_state: Entity<InputState>,
+    ssh_key_file_state: Entity<InputState>,
+    ssh_jump_hosts_state: Entity<InputState>,
+    proxy_url_state: Entity<InputState>,
+    proxy_username_state: Entity<InputState>,
+    proxy_password_state: Entity<InputState>,
+    proxy_bypass_state: Entity<InputState>,
+    /// Whether the edited server ignores the global proxy
+    proxy_direct: Rc<Cell<bool>>,
     /// Current server ID being edited (empty for new)
     editing_server_id: String,
 
     // Settings form input states
     preset_credentials_state: Entity<InputState>,
+    key_tree_delimiters_state: Entity<InputState>,
+    event_history_max_age_state: Entity<InputState>,
+    event_history_max_rows_state: Entity<InputState>,
+    prop_history_max_rows_state: Entity<InputState>,
+    global_proxy_url_state: Entity<InputState>,
+    global_proxy_username_state: Entity<InputState>,
+    global_proxy_password_state: Entity<InputState>,
+    global_proxy_bypass_state: Entity<InputState>,
+    /// Credential vault status and passphrase management
+    vault_settings: Entity<VaultSettingsView>,
 
     /// Focus handle for keyboard shortcuts
     focus_handle: FocusHandle,
     /// Monotonic request ID used to ignore stale reconnect completions.
     reconnect_request_id: Arc<AtomicU64>,
+    /// Stops the live config watch of the active Redis client
+    config_watch_stop: Option<watch::Sender<bool>>,
+    /// Reloads configs reported stale by the config watch
+    config_watch_task: Option<Task<()>>,
+    /// Asks the user to trust unknown SSH host keys
+    _host_key_task: Option<Task<()>>,
 
     /// Subscriptions
     _subscriptions: Vec<Subscription>,
@@ -101,6 +160,7 @@ impl DfcContent {
         let app_state = store.app_state();
         let config_state = store.config_state();
         let keys_state = store.keys_state();
+        let host_key_prompts = store.services().ssh_tunnels().take_host_key_prompts();
 
         // Create ConfigView
         let config_view = cx.new(|cx| {
@@ -172,6 +232,20 @@ impl DfcContent {
             cx.notify();
         }));
 
+        // Ask about unknown SSH host keys as the tunnels run into them
+        let host_key_task = host_key_prompts.map(|mut prompts| {
+            cx.spawn_in(window, async move |this, cx| {
+                while let Some(prompt) = prompts.recv().await {
+                    let confirmed = this.update_in(cx, |this, window, cx| {
+                        this.confirm_host_key(prompt, window, cx);
+                    });
+                    if confirmed.is_err() {
+                        break;
+                    }
+                }
+            })
+        });
+
         // Initialize keyword search input
         let keyword_state = cx.new(|cx| {
             InputState::new(window, cx)
@@ -191,6 +265,12 @@ impl DfcContent {
         let name_state = cx.new(|cx| {
             InputState::new(window, cx).placeholder(i18n_servers(cx, "name_placeholder"))
         });
+        let group_state = cx.new(|cx| {
+            InputState::new(window, cx).placeholder(i18n_servers(cx, "group_placeholder"))
+        });
+        let tags_state = cx.new(|cx| {
+            InputState::new(window, cx).placeholder(i18n_servers(cx, "tags_placeholder"))
+        });
         let host_state = cx.new(|cx| {
             InputState::new(window, cx).placeholder(i18n_servers(cx, "host_placeholder"))
         });
@@ -213,6 +293,50 @@ impl DfcContent {
                 .placeholder(i18n_servers(cx, "pulsar_token_placeholder"))
                 .auto_grow(2, 10)
         });
+        let db_state = cx
+            .new(|cx| InputState::new(window, cx).placeholder(i18n_servers(cx, "db_placeholder")));
+        let sentinel_master_state = cx.new(|cx| {
+            InputState::new(window, cx).placeholder(i18n_servers(cx, "sentinel_master_placeholder"))
+        });
+        let sentinel_nodes_state = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder(i18n_servers(cx, "sentinel_nodes_placeholder"))
+                .auto_grow(1, 5)
+        });
+        let tls_ca_file_state = cx.new(|cx| {
+            InputState::new(window, cx).placeholder(i18n_servers(cx, "tls_ca_file_placeholder"))
+        });
+        let tls_cert_file_state = cx.new(|cx| {
+            InputState::new(window, cx).placeholder(i18n_servers(cx, "tls_cert_file_placeholder"))
+        });
+        let tls_key_file_state = cx.new(|cx| {
+            InputState::new(window, cx).placeholder(i18n_servers(cx, "tls_key_file_placeholder"))
+        });
+        let ssh_host_state = cx.new(|cx| {
+            InputState::new(window, cx).placeholder(i18n_servers(cx, "ssh_host_placeholder"))
+        });
+        let ssh_password_state = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder(i18n_servers(cx, "ssh_password_placeholder"))
+                .masked(true)
+        });
+        let ssh_key_file_state = cx.new(|cx| {
+            InputState::new(window, cx).placeholder(i18n_servers(cx, "ssh_key_file_placeholder"))
+        });
+        let ssh_jump_hosts_state = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder(i18n_servers(cx, "ssh_jump_hosts_placeholder"))
+                .auto_grow(1, 5)
+        });
+        let pulsar_security = PulsarSecurityInputs::new(window, cx);
+        let proxy_url_state = cx.new(|cx| {
+            InputState::new(window, cx).placeholder(i18n_servers(cx, "proxy_url_placeholder"))
+        });
+        let proxy_username_state = cx.new(|cx| InputState::new(window, cx));
+        let proxy_password_state = cx.new(|cx| InputState::new(window, cx).masked(true));
+        let proxy_bypass_state = cx.new(|cx| {
+            InputState::new(window, cx).placeholder(i18n_servers(cx, "proxy_bypass_placeholder"))
+        });
 
         // Initialize preset credentials input with existing data
         let existing_credentials = app_state.read(cx).preset_credentials();
@@ -229,16 +353,142 @@ impl DfcContent {
         // Subscribe to preset credentials input for auto-save on blur
         subscriptions.push(
             cx.subscribe(&preset_credentials_state, |this, state, event, cx| {
-                if matches!(event, InputEvent::Blur) {
+                // While locked the input holds cipher text; never save it back
+                if matches!(event, InputEvent::Blur) && !vault_locked() {
                     let text = state.read(cx).value();
                     let credentials = text_to_credentials(&text);
                     update_app_state_and_save(cx, "set_preset_credentials", move |state, _| {
-                        state.set_preset_credentials(credentials.clone());
+                        if let Err(e) = state.set_preset_credentials(credentials.clone()) {
+                            tracing::error!(error = %e, "Failed to encrypt preset credentials");
+                        }
                     });
                 }
             }),
         );
 
+        // Initialize global proxy inputs with existing data
+        let existing_proxy = app_state.read(cx).proxy().unwrap_or_default();
+        let global_proxy_url_state = cx.new(|cx| {
+            let mut state =
+                InputState::new(window, cx).placeholder(i18n_servers(cx, "proxy_url_placeholder"));
+            state.set_value(existing_proxy.url.clone(), window, cx);
+            state
+        });
+        let global_proxy_username_state = cx.new(|cx| {
+            let mut state = InputState::new(window, cx);
+            state.set_value(
+                existing_proxy.username.clone().unwrap_or_default(),
+                window,
+                cx,
+            );
+            state
+        });
+        let global_proxy_password_state = cx.new(|cx| {
+            let mut state = InputState::new(window, cx).masked(true);
+            state.set_value(
+                existing_proxy.password.clone().unwrap_or_default(),
+                window,
+                cx,
+            );
+            state
+        });
+        let global_proxy_bypass_state = cx.new(|cx| {
+            let mut state = InputState::new(window, cx)
+                .placeholder(i18n_servers(cx, "proxy_bypass_placeholder"));
+            state.set_value(
+                existing_proxy.bypass.clone().unwrap_or_default(),
+                window,
+                cx,
+            );
+            state
+        });
+
+        // Subscribe to global proxy inputs for auto-save on blur
+        for state in [
+            &global_proxy_url_state,
+            &global_proxy_username_state,
+            &global_proxy_password_state,
+            &global_proxy_bypass_state,
+        ] {
+            subscriptions.push(cx.subscribe(state, |this, _, event, cx| {
+                if matches!(event, InputEvent::Blur) {
+                    this.save_global_proxy(cx);
+                }
+            }));
+        }
+
+        // Reload the stored secrets whenever the vault is unlocked, locked or re-keyed
+        let vault_settings = cx.new(|cx| VaultSettingsView::new(app_state.clone(), window, cx));
+        subscriptions.push(cx.subscribe_in(
+            &vault_settings,
+            window,
+            |this, _, event, window, cx| match event {
+                VaultSettingsEvent::Changed => this.reload_vault_secrets(window, cx),
+            },
+        ));
+
+        // Initialize key tree delimiters input
+        let existing_delimiters = app_state.read(cx).key_tree_delimiters().to_string();
+        let key_tree_delimiters_state = cx.new(|cx| {
+            let mut state = InputState::new(window, cx)
+                .placeholder(i18n_settings(cx, "key_tree_delimiters_placeholder"));
+            state.set_value(existing_delimiters, window, cx);
+            state
+        });
+
+        // Subscribe to key tree delimiters input for auto-save on blur
+        subscriptions.push(
+            cx.subscribe(&key_tree_delimiters_state, |_this, state, event, cx| {
+                if matches!(event, InputEvent::Blur) {
+                    let delimiters: String = state
+                        .read(cx)
+                        .value()
+                        .chars()
+                        .filter(|c| !c.is_whitespace())
+                        .collect();
+                    update_app_state_and_save(cx, "set_key_tree_delimiters", move |state, _| {
+                        state.set_key_tree_delimiters(delimiters.clone());
+                    });
+                }
+            }),
+        );
+
+        // Initialize event history retention inputs, saved on blur
+        let event_history = app_state.read(cx).event_history().clone();
+        let event_history_max_age_state = cx.new(|cx| {
+            let mut state = InputState::new(window, cx)
+                .placeholder(i18n_settings(cx, "event_history_max_age_placeholder"));
+            state.set_value(event_history.max_age_days.to_string(), window, cx);
+            state
+        });
+        let event_history_max_rows_state = cx.new(|cx| {
+            let mut state = InputState::new(window, cx);
+            state.set_value(event_history.max_rows.to_string(), window, cx);
+            state
+        });
+        for state in [&event_history_max_age_state, &event_history_max_rows_state] {
+            subscriptions.push(cx.subscribe(state, |this, _, event, cx| {
+                if matches!(event, InputEvent::Blur) {
+                    this.save_event_history(cx);
+                }
+            }));
+        }
+
+        // Initialize the prop history limit input, saved on blur
+        let prop_history_max_rows = app_state.read(cx).prop_history_max_rows();
+        let prop_history_max_rows_state = cx.new(|cx| {
+            let mut state = InputState::new(window, cx);
+            state.set_value(prop_history_max_rows.to_string(), window, cx);
+            state
+        });
+        subscriptions.push(
+            cx.subscribe(&prop_history_max_rows_state, |this, _, event, cx| {
+                if matches!(event, InputEvent::Blur) {
+                    this.save_prop_history_max_rows(cx);
+                }
+            }),
+        );
+
         // Subscribe to port input for stepping
         subscriptions.push(cx.subscribe_in(
             &port_state,
@@ -260,6 +510,23 @@ impl DfcContent {
             },
         ));
 
+        // Subscribe to database input for stepping
+        subscriptions.push(cx.subscribe_in(
+            &db_state,
+            window,
+            |_this, state, event, window, cx| {
+                let NumberInputEvent::Step(action) = event;
+                let current_val = state.read(cx).value().parse::<u8>().unwrap_or_default();
+                let new_val = match action {
+                    StepAction::Increment => current_val.saturating_add(1),
+                    StepAction::Decrement => current_val.saturating_sub(1),
+                };
+                state.update(cx, |input, cx| {
+                    input.set_value(new_val.to_string(), window, cx);
+                });
+            },
+        ));
+
         Self {
             current_route,
             current_home_layout_mode,
@@ -272,16 +539,50 @@ impl DfcContent {
             keyword_state,
             filter_keyword: SharedString::default(),
             name_state,
+            group_state,
+            tags_state,
+            environment: Rc::new(Cell::new(None)),
             host_state,
             port_state,
             password_state,
             cfgid_state,
             device_filter_state,
             pulsar_token_state,
+            pulsar_security,
+            safety: Rc::new(Cell::new(SafetyLevel::default())),
+            db_state,
+            sentinel_master_state,
+            sentinel_nodes_state,
+            tls: Rc::new(Cell::new(false)),
+            tls_skip_verify: Rc::new(Cell::new(false)),
+            tls_ca_file_state,
+            tls_cert_file_state,
+            tls_key_file_state,
+            ssh_host_state,
+            ssh_password_state,
+            ssh_key_file_state,
+            ssh_jump_hosts_state,
+            proxy_url_state,
+            proxy_username_state,
+            proxy_password_state,
+            proxy_bypass_state,
+            proxy_direct: Rc::new(Cell::new(false)),
             editing_server_id: String::new(),
             preset_credentials_state,
+            key_tree_delimiters_state,
+            event_history_max_age_state,
+            event_history_max_rows_state,
+            prop_history_max_rows_state,
+            global_proxy_url_state,
+            global_proxy_username_state,
+            global_proxy_password_state,
+            global_proxy_bypass_state,
+            vault_settings,
             focus_handle,
             reconnect_request_id: Arc::new(AtomicU64::new(0)),
+            config_watch_stop: None,
+            config_watch_task: None,
+            _host_key_task: host_key_task,
             _subscriptions: subscriptions,
         }
     }
@@ -321,19 +622,30 @@ impl DfcContent {
         self.focus_search(window, cx);
     }
 
-    /// Check if a server matches the current filter keyword
+    /// Keyword, `#tag`, `env:` and `group:` filtering of the server list
     fn server_matches_filter(&self, server: &DfcServerConfig) -> bool {
-        if self.filter_keyword.is_empty() {
-            return true;
+        server.matches_filter(&self.filter_keyword)
+    }
+
+    /// Add or remove a `#tag` term in the filter input
+    fn toggle_tag_filter(&mut self, tag: &str, window: &mut Window, cx: &mut Context<Self>) {
+        let term = format!("#{}", tag.to_lowercase());
+        let mut terms: Vec<String> = self
+            .filter_keyword
+            .split_whitespace()
+            .map(str::to_string)
+            .collect();
+        if terms.iter().any(|t| t.eq_ignore_ascii_case(&term)) {
+            terms.retain(|t| !t.eq_ignore_ascii_case(&term));
+        } else {
+            terms.push(term);
         }
-        let keyword = self.filter_keyword.to_lowercase();
-        let name_matches = server.name.to_lowercase().contains(&keyword);
-        let host_matches = server.host.to_lowercase().contains(&keyword);
-        let cfgid_matches = server
-            .cfgid
-            .as_ref()
-            .is_some_and(|c| c.to_lowercase().contains(&keyword));
-        name_matches || host_matches || cfgid_matches
+        let filter = terms.join(" ");
+        self.keyword_state.update(cx, |state, cx| {
+            state.set_value(filter.clone(), window, cx);
+        });
+        self.filter_keyword = filter.into();
+        cx.notify();
     }
 
     fn server_updated_at(&self, server: &DfcServerConfig) -> String {
@@ -356,6 +668,13 @@ impl DfcContent {
         self.name_state.update(cx, |state, cx| {
             state.set_value(server.name.clone(), window, cx);
         });
+        self.group_state.update(cx, |state, cx| {
+            state.set_value(server.group.clone().unwrap_or_default(), window, cx);
+        });
+        self.tags_state.update(cx, |state, cx| {
+            state.set_value(server.tags.join(", "), window, cx);
+        });
+        self.environment.set(server.environment);
         self.host_state.update(cx, |state, cx| {
             state.set_value(server.host.clone(), window, cx);
         });
@@ -380,6 +699,64 @@ impl DfcContent {
         self.pulsar_token_state.update(cx, |state, cx| {
             state.set_value(server.pulsar_token.clone().unwrap_or_default(), window, cx);
         });
+        self.safety.set(server.safety);
+        self.db_state.update(cx, |state, cx| {
+            state.set_value(server.db.to_string(), window, cx);
+        });
+        self.sentinel_master_state.update(cx, |state, cx| {
+            state.set_value(
+                server.sentinel_master.clone().unwrap_or_default(),
+                window,
+                cx,
+            );
+        });
+        self.sentinel_nodes_state.update(cx, |state, cx| {
+            state.set_value(
+                server.sentinel_nodes.clone().unwrap_or_default(),
+                window,
+                cx,
+            );
+        });
+        self.tls.set(server.tls);
+        self.tls_skip_verify.set(server.tls_skip_verify);
+        self.tls_ca_file_state.update(cx, |state, cx| {
+            state.set_value(server.tls_ca_file.clone().unwrap_or_default(), window, cx);
+        });
+        self.tls_cert_file_state.update(cx, |state, cx| {
+            state.set_value(server.tls_cert_file.clone().unwrap_or_default(), window, cx);
+        });
+        self.tls_key_file_state.update(cx, |state, cx| {
+            state.set_value(server.tls_key_file.clone().unwrap_or_default(), window, cx);
+        });
+        let ssh = server.ssh_tunnel.clone().unwrap_or_default();
+        self.ssh_host_state.update(cx, |state, cx| {
+            state.set_value(ssh.host, window, cx);
+        });
+        self.ssh_password_state.update(cx, |state, cx| {
+            state.set_value(ssh.password.unwrap_or_default(), window, cx);
+        });
+        self.ssh_key_file_state.update(cx, |state, cx| {
+            state.set_value(ssh.key_file.unwrap_or_default(), window, cx);
+        });
+        self.ssh_jump_hosts_state.update(cx, |state, cx| {
+            state.set_value(ssh.jump_hosts.unwrap_or_default(), window, cx);
+        });
+        let proxy = server.proxy.clone().unwrap_or_default();
+        self.proxy_url_state.update(cx, |state, cx| {
+            state.set_value(proxy.url, window, cx);
+        });
+        self.proxy_username_state.update(cx, |state, cx| {
+            state.set_value(proxy.username.unwrap_or_default(), window, cx);
+        });
+        self.proxy_password_state.update(cx, |state, cx| {
+            state.set_value(proxy.password.unwrap_or_default(), window, cx);
+        });
+        self.proxy_bypass_state.update(cx, |state, cx| {
+            state.set_value(proxy.bypass.unwrap_or_default(), window, cx);
+        });
+        self.proxy_direct.set(server.proxy_direct);
+        self.pulsar_security
+            .fill(server.pulsar_security.as_ref(), window, cx);
     }
 
     /// Fill input fields for copying an existing server as a new item
@@ -417,6 +794,34 @@ impl DfcContent {
         self.pulsar_token_state.update(cx, |state, cx| {
             state.set_value(DEFAULT_PULSAR_TOKEN.to_string(), window, cx);
         });
+        self.safety.set(SafetyLevel::default());
+        self.environment.set(None);
+        for state in [
+            &self.group_state,
+            &self.tags_state,
+            &self.db_state,
+            &self.sentinel_master_state,
+            &self.sentinel_nodes_state,
+            &self.tls_ca_file_state,
+            &self.tls_cert_file_state,
+            &self.tls_key_file_state,
+            &self.ssh_host_state,
+            &self.ssh_password_state,
+            &self.ssh_key_file_state,
+            &self.ssh_jump_hosts_state,
+            &self.proxy_url_state,
+            &self.proxy_username_state,
+            &self.proxy_password_state,
+            &self.proxy_bypass_state,
+        ] {
+            state.update(cx, |state, cx| {
+                state.set_value(String::new(), window, cx);
+            });
+        }
+        self.tls.set(false);
+        self.tls_skip_verify.set(false);
+        self.proxy_direct.set(false);
+        self.pulsar_security.clear(window, cx);
     }
 
     /// Remove server with confirmation dialog
@@ -450,6 +855,102 @@ impl DfcContent {
         });
     }
 
+    /// Ask whether to trust the unknown host key of an SSH tunnel hop;
+    /// closing the dialog declines it
+    fn confirm_host_key(
+        &mut self,
+        prompt: HostKeyPrompt,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let message = i18n_format(
+            cx,
+            "servers.host_key_prompt",
+            &[
+                ("host", &format!("{}:{}", prompt.host, prompt.port)),
+                ("algorithm", &prompt.algorithm),
+                ("fingerprint", &prompt.fingerprint),
+            ],
+        );
+        let prompt = Rc::new(RefCell::new(Some(prompt)));
+
+        window.open_dialog(cx, move |dialog, _, cx| {
+            let prompt = prompt.clone();
+
+            dialog
+                .confirm()
+                .title(i18n_servers(cx, "host_key_title"))
+                .child(message.clone())
+                .on_ok(move |_, window, cx| {
+                    if let Some(prompt) = prompt.borrow_mut().take() {
+                        prompt.answer(true);
+                    }
+                    window.close_dialog(cx);
+                    true
+                })
+        });
+    }
+
+    /// Store the preset credential that connected to a server as its own
+    /// password, after confirmation
+    pub fn promote_credential(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        server_id: &str,
+    ) {
+        let Some(server_name) = self
+            .app_state
+            .read(cx)
+            .server(server_id)
+            .map(|s| s.confirmation_name())
+        else {
+            return;
+        };
+
+        let app_state = self.app_state.clone();
+        let server_id = server_id.to_string();
+
+        window.open_dialog(cx, move |dialog, _, cx| {
+            let message = i18n_format(
+                cx,
+                "servers.promote_credential_prompt",
+                &[("server", &server_name)],
+            );
+            let app_state = app_state.clone();
+            let server_id = server_id.clone();
+
+            dialog
+                .confirm()
+                .title(i18n_servers(cx, "promote_credential"))
+                .child(message)
+                .on_ok(move |_, window, cx| {
+                    let result = app_state.update(cx, |state, cx| {
+                        state.promote_working_credential(&server_id, cx)
+                    });
+                    if let Err(e) = result {
+                        tracing::error!(server_id, error = %e, "Failed to promote credential");
+                    }
+                    window.close_dialog(cx);
+                    true
+                })
+        });
+    }
+
+    /// Open the connection diagnostics of a server
+    pub fn diagnose_server(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        server_id: &str,
+    ) {
+        let Some(server) = self.app_state.read(cx).server(server_id).cloned() else {
+            return;
+        };
+        let credentials = self.app_state.read(cx).preset_credentials();
+        open_server_diagnostics(server, credentials, window, cx);
+    }
+
     /// Open add/edit server dialog
     pub fn open_server_dialog(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         let app_state = self.app_state.clone();
@@ -460,6 +961,28 @@ impl DfcContent {
         let cfgid_state = self.cfgid_state.clone();
         let device_filter_state = self.device_filter_state.clone();
         let pulsar_token_state = self.pulsar_token_state.clone();
+        let pulsar_security = self.pulsar_security.clone();
+        let safety = self.safety.clone();
+        let group_state = self.group_state.clone();
+        let tags_state = self.tags_state.clone();
+        let environment = self.environment.clone();
+        let db_state = self.db_state.clone();
+        let sentinel_master_state = self.sentinel_master_state.clone();
+        let sentinel_nodes_state = self.sentinel_nodes_state.clone();
+        let tls = self.tls.clone();
+        let tls_skip_verify = self.tls_skip_verify.clone();
+        let tls_ca_file_state = self.tls_ca_file_state.clone();
+        let tls_cert_file_state = self.tls_cert_file_state.clone();
+        let tls_key_file_state = self.tls_key_file_state.clone();
+        let ssh_host_state = self.ssh_host_state.clone();
+        let ssh_password_state = self.ssh_password_state.clone();
+        let ssh_key_file_state = self.ssh_key_file_state.clone();
+        let ssh_jump_hosts_state = self.ssh_jump_hosts_state.clone();
+        let proxy_url_state = self.proxy_url_state.clone();
+        let proxy_username_state = self.proxy_username_state.clone();
+        let proxy_password_state = self.proxy_password_state.clone();
+        let proxy_bypass_state = self.proxy_bypass_state.clone();
+        let proxy_direct = self.proxy_direct.clone();
         let config_state = self.config_state.clone();
         let server_id = self.editing_server_id.clone();
         let is_new = server_id.is_empty();
@@ -472,6 +995,30 @@ impl DfcContent {
         let cfgid_state_clone = cfgid_state.clone();
         let device_filter_state_clone = device_filter_state.clone();
         let pulsar_token_state_clone = pulsar_token_state.clone();
+        let pulsar_security_clone = pulsar_security.clone();
+        let safety_clone = safety.clone();
+        let group_state_clone = group_state.clone();
+        let tags_state_clone = tags_state.clone();
+        let environment_clone = environment.clone();
+        let db_state_clone = db_state.clone();
+        let sentinel_master_state_clone = sentinel_master_state.clone();
+        let sentinel_nodes_state_clone = sentinel_nodes_state.clone();
+        let tls_clone = tls.clone();
+        let tls_skip_verify_clone = tls_skip_verify.clone();
+        let tls_ca_file_state_clone = tls_ca_file_state.clone();
+        let tls_cert_file_state_clone = tls_cert_file_state.clone();
+        let tls_key_file_state_clone = tls_key_file_state.clone();
+        let ssh_host_state_clone = ssh_host_state.clone();
+        let ssh_password_state_clone = ssh_password_state.clone();
+        let ssh_key_file_state_clone = ssh_key_file_state.clone();
+        let ssh_jump_hosts_state_clone = ssh_jump_hosts_state.clone();
+        let proxy_states_clone = [
+            proxy_url_state.clone(),
+            proxy_username_state.clone(),
+            proxy_password_state.clone(),
+            proxy_bypass_state.clone(),
+        ];
+        let proxy_direct_clone = proxy_direct.clone();
         let config_state_clone = config_state.clone();
         let app_state_clone = app_state.clone();
         let server_id_clone = server_id.clone();
@@ -517,6 +1064,26 @@ impl DfcContent {
                 Some(pulsar_token_val.to_string())
             };
 
+            let optional_value = |state: &Entity<InputState>, cx: &App| {
+                let value = state.read(cx).value();
+                let value = value.trim();
+                (!value.is_empty()).then(|| value.to_string())
+            };
+            let db = db_state_clone
+                .read(cx)
+                .value()
+                .trim()
+                .parse::<u8>()
+                .unwrap_or_default();
+
+            let ssh_tunnel =
+                optional_value(&ssh_host_state_clone, cx).map(|host| SshTunnelConfig {
+                    host,
+                    password: optional_value(&ssh_password_state_clone, cx),
+                    key_file: optional_value(&ssh_key_file_state_clone, cx),
+                    jump_hosts: optional_value(&ssh_jump_hosts_state_clone, cx),
+                });
+
             let candidate = DfcServerConfig {
                 id: server_id_clone.clone(),
                 name: name.to_string(),
@@ -527,8 +1094,51 @@ impl DfcContent {
                 device_filter,
                 pulsar_token,
                 updated_at: None,
+                safety: safety_clone.get(),
+                db,
+                tls: tls_clone.get(),
+                tls_ca_file: optional_value(&tls_ca_file_state_clone, cx),
+                tls_cert_file: optional_value(&tls_cert_file_state_clone, cx),
+                tls_key_file: optional_value(&tls_key_file_state_clone, cx),
+                tls_skip_verify: tls_skip_verify_clone.get(),
+                sentinel_master: optional_value(&sentinel_master_state_clone, cx),
+                sentinel_nodes: optional_value(&sentinel_nodes_state_clone, cx),
+                proxy_direct: proxy_direct_clone.get(),
+                ssh_tunnel,
+                proxy: proxy_from_inputs(&proxy_states_clone, cx),
+                pulsar_security: pulsar_security_clone.value(cx),
+                group: optional_value(&group_state_clone, cx),
+                tags: parse_tags(&tags_state_clone.read(cx).value()),
+                environment: environment_clone.get(),
             };
 
+            // Secrets cannot be encrypted for saving while the vault is locked
+            let validation = vault_key()
+                .and_then(|_| candidate.sentinel())
+                .and_then(|_| {
+                    candidate
+                        .ssh_tunnel
+                        .as_ref()
+                        .map_or(Ok(Vec::new()), |ssh| ssh.hops())
+                })
+                .and_then(|_| {
+                    candidate
+                        .proxy
+                        .as_ref()
+                        .map_or(Ok(()), |proxy| proxy.endpoint().map(|_| ()))
+                });
+            if let Err(e) = validation {
+                let message = e.to_string();
+                window.open_dialog(cx, move |dialog, _, cx| {
+                    dialog
+                        .confirm()
+                        .title(i18n_servers(cx, "invalid_title"))
+                        .child(message.clone())
+                        .on_ok(move |_, _, _| true)
+                });
+                return false;
+            }
+
             if server_id_clone.is_empty() {
                 let duplicate_server = app_state_clone
                     .read(cx)
@@ -607,6 +1217,63 @@ impl DfcContent {
             let cfgid_label = i18n_servers(cx, "cfgid");
             let device_filter_label = i18n_servers(cx, "device_filter");
             let pulsar_token_label = i18n_servers(cx, "pulsar_token");
+            let safety_field_label = i18n_servers(cx, "safety");
+            let safety_buttons: Vec<_> = SafetyLevel::ALL
+                .into_iter()
+                .enumerate()
+                .map(|(index, level)| {
+                    let safety = safety.clone();
+                    Button::new(("server-safety", index))
+                        .ghost()
+                        .xsmall()
+                        .label(i18n_servers(cx, &format!("safety_{}", level.as_str())))
+                        .selected(safety.get() == level)
+                        .on_click(move |_, window, _| {
+                            safety.set(level);
+                            window.refresh();
+                        })
+                })
+                .collect();
+            let group_label = i18n_servers(cx, "group");
+            let tags_label = i18n_servers(cx, "tags");
+            let environment_label = i18n_servers(cx, "environment");
+            let environment_buttons: Vec<_> = std::iter::once(None)
+                .chain(ServerEnvironment::ALL.map(Some))
+                .enumerate()
+                .map(|(index, option)| {
+                    let label = match option {
+                        Some(env) => i18n_servers(cx, &format!("env_{}", env.as_str())),
+                        None => i18n_servers(cx, "env_none"),
+                    };
+                    let environment = environment.clone();
+                    Button::new(("server-environment", index))
+                        .ghost()
+                        .xsmall()
+                        .label(label)
+                        .selected(environment.get() == option)
+                        .on_click(move |_, window, _| {
+                            environment.set(option);
+                            window.refresh();
+                        })
+                })
+                .collect();
+            let db_label = i18n_servers(cx, "db");
+            let sentinel_master_label = i18n_servers(cx, "sentinel_master");
+            let sentinel_nodes_label = i18n_servers(cx, "sentinel_nodes");
+            let tls_label = i18n_servers(cx, "tls");
+            let tls_skip_verify_label = i18n_servers(cx, "tls_skip_verify");
+            let tls_ca_file_label = i18n_servers(cx, "tls_ca_file");
+            let tls_cert_file_label = i18n_servers(cx, "tls_cert_file");
+            let tls_key_file_label = i18n_servers(cx, "tls_key_file");
+            let ssh_host_label = i18n_servers(cx, "ssh_host");
+            let ssh_password_label = i18n_servers(cx, "ssh_password");
+            let ssh_key_file_label = i18n_servers(cx, "ssh_key_file");
+            let ssh_jump_hosts_label = i18n_servers(cx, "ssh_jump_hosts");
+            let proxy_url_label = i18n_servers(cx, "proxy_url");
+            let proxy_username_label = i18n_servers(cx, "proxy_username");
+            let proxy_password_label = i18n_servers(cx, "proxy_password");
+            let proxy_bypass_label = i18n_servers(cx, "proxy_bypass");
+            let proxy_direct_label = i18n_servers(cx, "proxy_direct");
 
             dialog
                 .title(title)
@@ -614,6 +1281,13 @@ impl DfcContent {
                 .child({
                     let form = v_form()
                         .child(field().label(name_label).child(Input::new(&name_state)))
+                        .child(field().label(group_label).child(Input::new(&group_state)))
+                        .child(field().label(tags_label).child(Input::new(&tags_state)))
+                        .child(
+                            field()
+                                .label(environment_label)
+                                .child(h_flex().gap_1().children(environment_buttons)),
+                        )
                         .child(field().label(host_label).child(Input::new(&host_state)))
                         .child(
                             field()
@@ -636,6 +1310,123 @@ impl DfcContent {
                                 .label(pulsar_token_label)
                                 .child(Input::new(&pulsar_token_state)),
                         );
+                    let form = pulsar_security
+                        .render(form, cx)
+                        .child(
+                            field()
+                                .label(safety_field_label)
+                                .child(h_flex().gap_1().children(safety_buttons)),
+                        )
+                        .child(field().label(db_label).child(NumberInput::new(&db_state)))
+                        .child(
+                            field()
+                                .label(sentinel_master_label)
+                                .child(Input::new(&sentinel_master_state)),
+                        )
+                        .child(
+                            field()
+                                .label(sentinel_nodes_label)
+                                .child(Input::new(&sentinel_nodes_state)),
+                        )
+                        .child(
+                            field().child(
+                                Checkbox::new("server-tls")
+                                    .label(tls_label)
+                                    .checked(tls.get())
+                                    .on_click({
+                                        let tls = tls.clone();
+                                        move |checked: &bool, window, _| {
+                                            tls.set(*checked);
+                                            window.refresh();
+                                        }
+                                    }),
+                            ),
+                        )
+                        .when(tls.get(), |form| {
+                            form.child(
+                                field()
+                                    .label(tls_ca_file_label)
+                                    .child(Input::new(&tls_ca_file_state)),
+                            )
+                            .child(
+                                field()
+                                    .label(tls_cert_file_label)
+                                    .child(Input::new(&tls_cert_file_state)),
+                            )
+                            .child(
+                                field()
+                                    .label(tls_key_file_label)
+                                    .child(Input::new(&tls_key_file_state)),
+                            )
+                            .child(
+                                field().child(
+                                    Checkbox::new("server-tls-skip-verify")
+                                        .label(tls_skip_verify_label)
+                                        .checked(tls_skip_verify.get())
+                                        .on_click({
+                                            let tls_skip_verify = tls_skip_verify.clone();
+                                            move |checked: &bool, window, _| {
+                                                tls_skip_verify.set(*checked);
+                                                window.refresh();
+                                            }
+                                        }),
+                                ),
+                            )
+                        })
+                        .child(
+                            field()
+                                .label(ssh_host_label)
+                                .child(Input::new(&ssh_host_state)),
+                        )
+                        .child(
+                            field()
+                                .label(ssh_password_label)
+                                .child(Input::new(&ssh_password_state).mask_toggle()),
+                        )
+                        .child(
+                            field()
+                                .label(ssh_key_file_label)
+                                .child(Input::new(&ssh_key_file_state)),
+                        )
+                        .child(
+                            field()
+                                .label(ssh_jump_hosts_label)
+                                .child(Input::new(&ssh_jump_hosts_state)),
+                        )
+                        .child(
+                            field()
+                                .label(proxy_url_label)
+                                .child(Input::new(&proxy_url_state)),
+                        )
+                        .child(
+                            field()
+                                .label(proxy_username_label)
+                                .child(Input::new(&proxy_username_state)),
+                        )
+                        .child(
+                            field()
+                                .label(proxy_password_label)
+                                .child(Input::new(&proxy_password_state).mask_toggle()),
+                        )
+                        .child(
+                            field()
+                                .label(proxy_bypass_label)
+                                .child(Input::new(&proxy_bypass_state)),
+                        )
+                        .child(
+                            field().child(
+                                Checkbox::new("server-proxy-direct")
+                                    .label(proxy_direct_label)
+                                    .checked(proxy_direct.get())
+                                    .on_click({
+                                        let proxy_direct = proxy_direct.clone();
+                                        move |checked: &bool, window, _| {
+                                            proxy_direct.set(*checked);
+                                            window.refresh();
+                                        }
+                                    }),
+                            ),
+                        );
 
                     div()
                         .id("server-dialog-content")
@@ -677,12 +1468,99 @@ impl DfcContent {
         });
     }
 
+    /// Stop the live config watch, e.g. before the active client is replaced
+    fn stop_config_watch(&mut self) {
+        if let Some(stop) = self.config_watch_stop.take() {
+            let _ = stop.send(true);
+        }
+        self.config_watch_task = None;
+    }
+
+    /// Watch the CMC keys of the connected server and reload its configs and
+    /// IMID->IMR mapping in place when they change
+    fn start_config_watch(&mut self, server: DfcServerConfig, cx: &mut Context<Self>) {
+        self.stop_config_watch();
+        let credentials = self.app_state.read(cx).preset_credentials();
+        let redis = cx.global::<DfcGlobalStore>().services().redis().clone();
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let (stop_tx, stop_rx) = watch::channel(false);
+        self.config_watch_stop = Some(stop_tx);
+
+        let watch_redis = redis.clone();
+        let watch_server = server.clone();
+        spawn_named_in_tokio("config-watch", async move {
+            watch_redis
+                .watch_config_keys(&watch_server, &credentials, stop_rx, tx)
+                .await;
+        });
+
+        let config_state = self.config_state.clone();
+        let task = cx.spawn(async move |_, cx| {
+            loop {
+                cx.background_executor()
+                    .timer(Duration::from_millis(CONFIG_WATCH_DEBOUNCE_MS))
+                    .await;
+
+                let mut change = ConfigWatchChange::default();
+                loop {
+                    match rx.try_recv() {
+                        Ok(ConfigWatchEvent::Changed(next)) => change.merge(next),
+                        Ok(ConfigWatchEvent::Mode(mode)) => {
+                            tracing::debug!(server_id = %server.id, ?mode, "Config watch mode");
+                        }
+                        Err(crossbeam_channel::TryRecvError::Empty) => break,
+                        Err(crossbeam_channel::TryRecvError::Disconnected) => return,
+                    }
+                }
+
+                if change.infomodel {
+                    tracing::info!(server_id = %server.id, "IMID->IMR mapping changed");
+                    redis.invalidate_imid2imr();
+                }
+                if !change.configs {
+                    continue;
+                }
+
+                match redis.fetch_configs(server.cfgid.as_deref()).await {
+                    Ok(configs) => {
+                        let snapshot =
+                            ConfigSnapshot::new(&server.id, &server.name, configs.clone());
+                        cx.background_spawn(async move { ConfigHistory::record(&snapshot) })
+                            .await;
+                        let config_count = configs.len();
+                        let Ok(refreshed) = config_state.update(cx, |state, cx| {
+                            state.refresh_configs_for_server(&server.id, configs, cx)
+                        }) else {
+                            return;
+                        };
+                        tracing::info!(
+                            server_id = %server.id,
+                            config_count,
+                            refreshed,
+                            "Reloaded configs after live change"
+                        );
+                    }
+                    Err(e) => {
+                        tracing::warn!(server_id = %server.id, "Live config reload failed: {}", e);
+                    }
+                }
+            }
+        });
+        self.config_watch_task = Some(task);
+    }
+
     /// Reconnect a server and refresh its config list.
     pub fn reconnect_server(&mut self, server_id: &str, cx: &mut Context<Self>) {
         let Some(server) = self.app_state.read(cx).server(server_id).cloned() else {
             return;
         };
+        self.stop_config_watch();
         let credentials = self.app_state.read(cx).preset_credentials();
+        let remembered = self
+            .app_state
+            .read(cx)
+            .working_credential(server_id)
+            .cloned();
 
         let server_id = server_id.to_string();
 
@@ -715,49 +1593,65 @@ impl DfcContent {
             });
         });
 
-        cx.spawn(async move |_, cx| {
+        cx.spawn(async move |this, cx| {
             let redis = store.services().redis();
             let cfgid = server.cfgid.as_deref();
 
-            if let Err(e) = redis.connect_to_server(&server, &credentials).await {
-                let Ok(selected_server_matches) = app_state.update(cx, |state, _| {
-                    state.selected_server_id() == Some(server_id.as_str())
-                }) else {
-                    return;
-                };
-                if reconnect_request_id.load(Ordering::Acquire) != request_id
-                    || !selected_server_matches
-                {
-                    tracing::info!(
-                        server_id,
-                        request_id,
-                        error = %e,
-                        "Ignoring stale reconnect error after server selection changed"
-                    );
-                    let _ = config_state.update(cx, |state, cx| {
-                        state.restore_stale_loading_for_server_request(&server_id, request_id, cx);
-                    });
-                    return;
-                }
-                tracing::error!("Failed to connect to Redis: {}", e);
-                let _ = config_state.update(cx, |state, cx| {
-                    if reconnect_request_id.load(Ordering::Acquire) == request_id {
-                        state.set_error_for_server_request(
-                            &server_id,
-                            request_id,
-                            e.to_string(),
-                            cx,
-                        );
-                    } else {
+            let credential = match redis
+                .connect_to_server(&server, &credentials, remembered.as_ref())
+                .await
+            {
+                Ok(credential) => credential,
+                Err(e) => {
+                    let Ok(selected_server_matches) = app_state.update(cx, |state, _| {
+                        state.selected_server_id() == Some(server_id.as_str())
+                    }) else {
+                        return;
+                    };
+                    if reconnect_request_id.load(Ordering::Acquire) != request_id
+                        || !selected_server_matches
+                    {
                         tracing::info!(
-                                server_id,
+                            server_id,
                             request_id,
                             error = %e,
-                            "Ignoring stale reconnect error during state write"
+                            "Ignoring stale reconnect error after server selection changed"
                         );
+                        let _ = config_state.update(cx, |state, cx| {
+                            state.restore_stale_loading_for_server_request(
+                                &server_id, request_id, cx,
+                            );
+                        });
+                        return;
                     }
+                    tracing::error!("Failed to connect to Redis: {}", e);
+                    let _ = config_state.update(cx, |state, cx| {
+                        if reconnect_request_id.load(Ordering::Acquire) == request_id {
+                            state.set_error_for_server_request(
+                                &server_id,
+                                request_id,
+                                e.to_string(),
+                                cx,
+                            );
+                        } else {
+                            tracing::info!(
+                                    server_id,
+                                request_id,
+                                error = %e,
+                                "Ignoring stale reconnect error during state write"
+                            );
+                        }
+                    });
+                    return;
+                }
+            };
+            if remembered.as_ref() != Some(&credential) {
+                let server_id = server_id.clone();
+                let _ = cx.update(|cx| {
+                    update_app_state_and_save(cx, "set_working_credential", move |state, _| {
+                        state.set_working_credential(server_id.clone(), credential.clone());
+                    });
                 });
-                return;
             }
 
             match redis.fetch_configs(cfgid).await {
@@ -784,9 +1678,13 @@ impl DfcContent {
                         return;
                     }
                     tracing::info!("Fetched {} configs", configs.len());
-                    let _ = config_state.update(cx, |state, cx| {
+                    let snapshot = ConfigSnapshot::new(&server.id, &server.name, configs.clone());
+                    cx.background_spawn(async move { ConfigHistory::record(&snapshot) })
+                        .await;
+                    let applied = config_state.update(cx, |state, cx| {
                         if reconnect_request_id.load(Ordering::Acquire) == request_id {
                             state.set_configs_for_server(&server_id, configs, cx);
+                            true
                         } else {
                             tracing::info!(
                                 server_id,
@@ -794,8 +1692,13 @@ impl DfcContent {
                                 config_count = configs.len(),
                                 "Ignoring stale reconnect configs during state write"
                             );
+                            false
                         }
                     });
+                    if matches!(applied, Ok(true)) {
+                        let server = server.clone();
+                        let _ = this.update(cx, |this, cx| this.start_config_watch(server, cx));
+                    }
                 }
                 Err(e) => {
                     let Ok(selected_server_matches) = app_state.update(cx, |state, _| {
@@ -915,6 +1818,70 @@ impl DfcContent {
                 });
             });
 
+        let server_sort = self.app_state.read(cx).server_sort();
+        let sort_btn =
+            Button::new("home-server-sort")
+                .ghost()
+                .small()
+                .outline()
+                .icon(IconName::ChevronsUpDown)
+                .label(i18n_servers(cx, &format!("sort_{}", server_sort.as_str())))
+                .tooltip(i18n_servers(cx, "sort_tooltip"))
+                .dropdown_menu(move |menu, _, cx| {
+                    ServerSortOrder::ALL.into_iter().fold(menu, |menu, order| {
+                        let item = PopupMenuItem::new(i18n_servers(
+                            cx,
+                            &format!("sort_{}", order.as_str()),
+                        ))
+                        .on_click(move |_, _, cx| {
+                            update_app_state_and_save(cx, "set_server_sort", move |state, _| {
+                                state.set_server_sort(order);
+                            });
+                        });
+                        menu.item(if order == server_sort {
+                            item.icon(Icon::new(IconName::Check))
+                        } else {
+                            item
+                        })
+                    })
+                });
+
+        // Quick tag filters
+        let active_terms: Vec<String> = self
+            .filter_keyword
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .collect();
+        let tag_buttons: Vec<_> = collect_tags(self.app_state.read(cx).servers())
+            .into_iter()
+            .enumerate()
+            .map(|(index, tag)| {
+                let active = active_terms.contains(&format!("#{}", tag.to_lowercase()));
+                Button::new(("home-tag-filter", index))
+                    .ghost()
+                    .xsmall()
+                    .label(format!("#{tag}"))
+                    .selected(active)
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.toggle_tag_filter(&tag, window, cx);
+                    }))
+            })
+            .collect();
+
+        let import_btn = Button::new("home-import-servers")
+            .ghost()
+            .small()
+            .outline()
+            .label(i18n_servers(cx, "import"))
+            .on_click(cx.listener(|this, _, window, cx| this.import_servers(window, cx)));
+
+        let export_btn = Button::new("home-export-servers")
+            .ghost()
+            .small()
+            .outline()
+            .label(i18n_servers(cx, "export"))
+            .on_click(|_, window, cx| open_server_export(window, cx));
+
         h_flex()
             .w_full()
             .justify_end()
@@ -922,11 +1889,38 @@ impl DfcContent {
             .pt_4()
             .pb_2()
             .gap_2()
+            .child(
+                h_flex()
+                    .flex_1()
+                    .min_w(px(0.0))
+                    .gap_1()
+                    .flex_wrap()
+                    .children(tag_buttons),
+            )
+            .child(sort_btn)
+            .child(import_btn)
+            .child(export_btn)
             .child(grid_btn)
             .child(list_btn)
             .into_any_element()
     }
 
+    /// Open the server import dialog
+    fn import_servers(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let view = open_server_import(window, cx);
+        cx.subscribe_in(&view, window, |this, _, event, window, cx| match event {
+            ServerImportEvent::Imported => {
+                // Imported preset credentials belong in the settings input
+                let credentials = this.app_state.read(cx).preset_credentials();
+                this.preset_credentials_state.update(cx, |input, cx| {
+                    input.set_value(credentials_to_text(&credentials), window, cx);
+                });
+                cx.notify();
+            }
+        })
+        .detach();
+    }
+
     /// Render a single server card
     fn render_server_card(
         &self,
@@ -941,6 +1935,8 @@ impl DfcContent {
         let menu_server_id_for_edit = server.id.clone();
         let menu_server_id_for_copy = server.id.clone();
         let menu_server_id_for_delete = server.id.clone();
+        let menu_server_id_for_diagnose = server.id.clone();
+        let menu_server_id_for_promote = server.id.clone();
         let store = cx.global::<DfcGlobalStore>().clone();
         let copy_button_store = store.clone();
         let copy_button_server_id = server.id.clone();
@@ -950,6 +1946,12 @@ impl DfcContent {
         let edit_label = i18n_common(cx, "edit");
         let duplicate_label = i18n_common(cx, "duplicate");
         let delete_label = i18n_common(cx, "delete");
+        let diagnose_label = i18n_servers(cx, "diagnose");
+        let promote_label = i18n_servers(cx, "promote_credential");
+        let can_promote = self
+            .app_state
+            .read(cx)
+            .can_promote_working_credential(&server.id);
 
         let edit_btn = Button::new(("server-edit", index))
             .ghost()
@@ -1003,26 +2005,61 @@ impl DfcContent {
                 let sid_edit = menu_server_id_for_edit.clone();
                 let sid_copy = menu_server_id_for_copy.clone();
                 let sid_delete = menu_server_id_for_delete.clone();
-
+                let store_for_diagnose = store.clone();
+                let sid_diagnose = menu_server_id_for_diagnose.clone();
+                let store_for_promote = store.clone();
+                let sid_promote = menu_server_id_for_promote.clone();
+
+                let menu = menu
+                    .item(
+                        PopupMenuItem::new(edit_label.clone())
+                            .icon(Icon::from(CustomIconName::FilePenLine))
+                            .on_click(move |_, window, cx| {
+                                store_for_edit.set_pending_server(sid_edit.clone());
+                                window.dispatch_action(
+                                    Box::new(crate::helpers::ServerAction::Edit),
+                                    cx,
+                                );
+                            }),
+                    )
+                    .item(
+                        PopupMenuItem::new(duplicate_label.clone())
+                            .icon(Icon::from(CustomIconName::FilePlusCorner))
+                            .on_click(move |_, window, cx| {
+                                store_for_copy.set_pending_server(sid_copy.clone());
+                                window.dispatch_action(
+                                    Box::new(crate::helpers::ServerAction::Copy),
+                                    cx,
+                                );
+                            }),
+                    )
+                    .item(
+                        PopupMenuItem::new(diagnose_label.clone())
+                            .icon(Icon::new(IconName::Info))
+                            .on_click(move |_, window, cx| {
+                                store_for_diagnose.set_pending_server(sid_diagnose.clone());
+                                window.dispatch_action(
+                                    Box::new(crate::helpers::ServerAction::Diagnose),
+                                    cx,
+                                );
+                            }),
+                    );
+                let menu = if can_promote {
+                    menu.item(
+                        PopupMenuItem::new(promote_label.clone())
+                            .icon(Icon::new(IconName::Check))
+                            .on_click(move |_, window, cx| {
+                                store_for_promote.set_pending_server(sid_promote.clone());
+                                window.dispatch_action(
+                                    Box::new(crate::helpers::ServerAction::PromoteCredential),
+                                    cx,
+                                );
+                            }),
+                    )
+                } else {
+                    menu
+                };
                 menu.item(
-                    PopupMenuItem::new(edit_label.clone())
-                        .icon(Icon::from(CustomIconName::FilePenLine))
-                        .on_click(move |_, window, cx| {
-                            store_for_edit.set_pending_server(sid_edit.clone());
-                            window
-                                .dispatch_action(Box::new(crate::helpers::ServerAction::Edit), cx);
-                        }),
-                )
-                .item(
-                    PopupMenuItem::new(duplicate_label.clone())
-                        .icon(Icon::from(CustomIconName::FilePlusCorner))
-                        .on_click(move |_, window, cx| {
-                            store_for_copy.set_pending_server(sid_copy.clone());
-                            window
-                                .dispatch_action(Box::new(crate::helpers::ServerAction::Copy), cx);
-                        }),
-                )
-                .item(
                     PopupMenuItem::new(delete_label.clone())
                         .icon(Icon::from(CustomIconName::FileXCorner))
                         .on_click(move |_, window, cx| {
@@ -1059,6 +2096,9 @@ impl DfcContent {
                                     .child(delete_btn),
                             ),
                     )
+                    .when_some(render_server_labels(server, cx), |this, labels| {
+                        this.child(labels)
+                    })
                     .when(!updated_at.is_empty(), |this| {
                         this.child(
                             Label::new(updated_at)
@@ -1083,6 +2123,8 @@ impl DfcContent {
         let menu_server_id_for_edit = server.id.clone();
         let menu_server_id_for_copy = server.id.clone();
         let menu_server_id_for_delete = server.id.clone();
+        let menu_server_id_for_diagnose = server.id.clone();
+        let menu_server_id_for_promote = server.id.clone();
         let store = cx.global::<DfcGlobalStore>().clone();
         let copy_button_store = store.clone();
         let copy_button_server_id = server.id.clone();
@@ -1092,6 +2134,12 @@ impl DfcContent {
         let edit_label = i18n_common(cx, "edit");
         let copy_label = i18n_common(cx, "copy");
         let delete_label = i18n_common(cx, "delete");
+        let diagnose_label = i18n_servers(cx, "diagnose");
+        let promote_label = i18n_servers(cx, "promote_credential");
+        let can_promote = self
+            .app_state
+            .read(cx)
+            .can_promote_working_credential(&server.id);
 
         let edit_btn = Button::new(("server-list-edit", index))
             .ghost()
@@ -1146,26 +2194,61 @@ impl DfcContent {
                 let sid_edit = menu_server_id_for_edit.clone();
                 let sid_copy = menu_server_id_for_copy.clone();
                 let sid_delete = menu_server_id_for_delete.clone();
-
+                let store_for_diagnose = store.clone();
+                let sid_diagnose = menu_server_id_for_diagnose.clone();
+                let store_for_promote = store.clone();
+                let sid_promote = menu_server_id_for_promote.clone();
+
+                let menu = menu
+                    .item(
+                        PopupMenuItem::new(edit_label.clone())
+                            .icon(Icon::from(CustomIconName::FilePenLine))
+                            .on_click(move |_, window, cx| {
+                                store_for_edit.set_pending_server(sid_edit.clone());
+                                window.dispatch_action(
+                                    Box::new(crate::helpers::ServerAction::Edit),
+                                    cx,
+                                );
+                            }),
+                    )
+                    .item(
+                        PopupMenuItem::new(copy_label.clone())
+                            .icon(Icon::from(CustomIconName::FilePlusCorner))
+                            .on_click(move |_, window, cx| {
+                                store_for_copy.set_pending_server(sid_copy.clone());
+                                window.dispatch_action(
+                                    Box::new(crate::helpers::ServerAction::Copy),
+                                    cx,
+                                );
+                            }),
+                    )
+                    .item(
+                        PopupMenuItem::new(diagnose_label.clone())
+                            .icon(Icon::new(IconName::Info))
+                            .on_click(move |_, window, cx| {
+                                store_for_diagnose.set_pending_server(sid_diagnose.clone());
+                                window.dispatch_action(
+                                    Box::new(crate::helpers::ServerAction::Diagnose),
+                                    cx,
+                                );
+                            }),
+                    );
+                let menu = if can_promote {
+                    menu.item(
+                        PopupMenuItem::new(promote_label.clone())
+                            .icon(Icon::new(IconName::Check))
+                            .on_click(move |_, window, cx| {
+                                store_for_promote.set_pending_server(sid_promote.clone());
+                                window.dispatch_action(
+                                    Box::new(crate::helpers::ServerAction::PromoteCredential),
+                                    cx,
+                                );
+                            }),
+                    )
+                } else {
+                    menu
+                };
                 menu.item(
-                    PopupMenuItem::new(edit_label.clone())
-                        .icon(Icon::from(CustomIconName::FilePenLine))
-                        .on_click(move |_, window, cx| {
-                            store_for_edit.set_pending_server(sid_edit.clone());
-                            window
-                                .dispatch_action(Box::new(crate::helpers::ServerAction::Edit), cx);
-                        }),
-                )
-                .item(
-                    PopupMenuItem::new(copy_label.clone())
-                        .icon(Icon::from(CustomIconName::FilePlusCorner))
-                        .on_click(move |_, window, cx| {
-                            store_for_copy.set_pending_server(sid_copy.clone());
-                            window
-                                .dispatch_action(Box::new(crate::helpers::ServerAction::Copy), cx);
-                        }),
-                )
-                .item(
                     PopupMenuItem::new(delete_label.clone())
                         .icon(Icon::from(CustomIconName::FileXCorner))
                         .on_click(move |_, window, cx| {
@@ -1194,7 +2277,10 @@ impl DfcContent {
                                     .flex_1()
                                     .min_w(px(0.0))
                                     .child(Label::new(title).text_sm().text_ellipsis()),
-                            ),
+                            )
+                            .when_some(render_server_labels(server, cx), |this, labels| {
+                                this.child(labels)
+                            }),
                     )
                     .child(
                         h_flex()
@@ -1220,6 +2306,7 @@ impl DfcContent {
     fn render_server_grid(
         &self,
         window: &mut Window,
+        first_index: usize,
         servers: &[DfcServerConfig],
         bg: gpui::Hsla,
         cx: &mut Context<Self>,
@@ -1234,7 +2321,7 @@ impl DfcContent {
 
         let mut children = Vec::new();
         for (index, server) in servers.iter().enumerate() {
-            children.push(self.render_server_card(index, server, bg, cx));
+            children.push(self.render_server_card(first_index + index, server, bg, cx));
         }
 
         div()
@@ -1249,18 +2336,83 @@ impl DfcContent {
 
     fn render_server_list(
         &self,
+        first_index: usize,
         servers: &[DfcServerConfig],
         bg: gpui::Hsla,
         cx: &mut Context<Self>,
     ) -> gpui::AnyElement {
         let mut rows = Vec::new();
         for (index, server) in servers.iter().enumerate() {
-            rows.push(self.render_server_list_row(index, server, bg, cx));
+            rows.push(self.render_server_list_row(first_index + index, server, bg, cx));
         }
 
         v_flex().p_2().gap_2().children(rows).into_any_element()
     }
 
+    /// Render servers in the selected layout
+    fn render_server_section(
+        &self,
+        window: &mut Window,
+        first_index: usize,
+        servers: &[DfcServerConfig],
+        bg: gpui::Hsla,
+        cx: &mut Context<Self>,
+    ) -> gpui::AnyElement {
+        match self.app_state.read(cx).home_layout_mode() {
+            HomeLayoutMode::Grid => self.render_server_grid(window, first_index, servers, bg, cx),
+            HomeLayoutMode::List => self.render_server_list(first_index, servers, bg, cx),
+        }
+    }
+
+    /// Render the collapsible header of a server group (`None` for ungrouped servers)
+    fn render_group_header(
+        &self,
+        index: usize,
+        group: Option<&str>,
+        count: usize,
+        cx: &mut Context<Self>,
+    ) -> gpui::AnyElement {
+        let key = group.unwrap_or_default().to_string();
+        let collapsed = self.app_state.read(cx).is_group_collapsed(&key);
+        let name = group
+            .map(str::to_string)
+            .unwrap_or_else(|| i18n_servers(cx, "ungrouped").to_string());
+
+        h_flex()
+            .id(("server-group", index))
+            .px_4()
+            .pt_3()
+            .pb_1()
+            .gap_2()
+            .items_center()
+            .cursor_pointer()
+            .child(
+                Icon::new(if collapsed {
+                    IconName::ChevronRight
+                } else {
+                    IconName::ChevronDown
+                })
+                .size_4(),
+            )
+            .child(
+                Label::new(name)
+                    .text_sm()
+                    .font_weight(gpui::FontWeight::SEMIBOLD),
+            )
+            .child(
+                Label::new(count.to_string())
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground),
+            )
+            .on_click(move |_, _, cx| {
+                let key = key.clone();
+                update_app_state_and_save(cx, "toggle_group_collapsed", move |state, _| {
+                    state.toggle_group_collapsed(&key);
+                });
+            })
+            .into_any_element()
+    }
+
     /// Render the home view with server cards, config view, or keys browser
     fn render_home(&self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         // Priority 1: If we have connected servers with keys loaded - show KeysBrowserView
@@ -1281,7 +2433,7 @@ impl DfcContent {
         };
 
         // Build server cards - collect first to avoid borrow issues
-        let servers: Vec<_> = self
+        let mut servers: Vec<_> = self
             .app_state
             .read(cx)
             .servers()
@@ -1289,9 +2441,41 @@ impl DfcContent {
             .filter(|s| self.server_matches_filter(s))
             .cloned()
             .collect();
-        let content = match self.app_state.read(cx).home_layout_mode() {
-            HomeLayoutMode::Grid => self.render_server_grid(window, &servers, bg, cx),
-            HomeLayoutMode::List => self.render_server_list(&servers, bg, cx),
+        sort_servers(&mut servers, self.app_state.read(cx).server_sort());
+        let groups = group_servers(servers);
+        let content = if groups.iter().all(|(group, _)| group.is_none()) {
+            let servers = groups
+                .into_iter()
+                .next()
+                .map(|(_, servers)| servers)
+                .unwrap_or_default();
+            self.render_server_section(window, 0, &servers, bg, cx)
+        } else {
+            let mut first_index = 0;
+            let mut sections = Vec::new();
+            for (group_index, (group, servers)) in groups.into_iter().enumerate() {
+                sections.push(self.render_group_header(
+                    group_index,
+                    group.as_deref(),
+                    servers.len(),
+                    cx,
+                ));
+                if !self
+                    .app_state
+                    .read(cx)
+                    .is_group_collapsed(group.as_deref().unwrap_or_default())
+                {
+                    sections.push(self.render_server_section(
+                        window,
+                        first_index,
+                        &servers,
+                        bg,
+                        cx,
+                    ));
+                }
+                first_index += servers.len();
+            }
+            v_flex().w_full().children(sections).into_any_element()
         };
 
         v_flex()
@@ -1350,20 +2534,284 @@ impl DfcContent {
     /// Render the settings view
     fn render_settings(&self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let preset_credentials_label = i18n_settings(cx, "preset_credentials");
+        let key_tree_delimiters_label = i18n_settings(cx, "key_tree_delimiters");
+        let event_history_label = i18n_settings(cx, "event_history");
+        let event_history_enabled_label = i18n_settings(cx, "event_history_enabled");
+        let event_history_max_age_label = i18n_settings(cx, "event_history_max_age");
+        let event_history_max_rows_label = i18n_settings(cx, "event_history_max_rows");
+        let event_history = self.app_state.read(cx).event_history().clone();
+        let prop_history_max_rows_label = i18n_settings(cx, "prop_history_max_rows");
+        let proxy_url_label = i18n_settings(cx, "proxy_url");
+        let proxy_username_label = i18n_servers(cx, "proxy_username");
+        let proxy_password_label = i18n_servers(cx, "proxy_password");
+        let proxy_bypass_label = i18n_servers(cx, "proxy_bypass");
+
+        // Secret fields only show cipher text until the vault is unlocked
+        let locked = vault_locked();
 
         v_flex()
             .size_full()
             .p_4()
             .gap_4()
             .child(Label::new(i18n_common(cx, "settings")).text_xl())
+            .child(self.vault_settings.clone())
             .child(
-                v_form().child(
-                    field()
-                        .label(preset_credentials_label)
-                        .child(Input::new(&self.preset_credentials_state)),
-                ),
+                v_form()
+                    .when(!locked, |form| {
+                        form.child(
+                            field()
+                                .label(preset_credentials_label)
+                                .child(Input::new(&self.preset_credentials_state)),
+                        )
+                    })
+                    .child(
+                        field()
+                            .label(key_tree_delimiters_label)
+                            .child(Input::new(&self.key_tree_delimiters_state)),
+                    )
+                    .child(
+                        field().label(event_history_label).child(
+                            Checkbox::new("settings-event-history")
+                                .label(event_history_enabled_label)
+                                .checked(event_history.enabled)
+                                .on_click(move |checked: &bool, _, cx| {
+                                    let checked = *checked;
+                                    update_app_state_and_save(
+                                        cx,
+                                        "set_event_history",
+                                        move |state, _| {
+                                            let mut event_history = state.event_history().clone();
+                                            event_history.enabled = checked;
+                                            state.set_event_history(event_history);
+                                        },
+                                    );
+                                }),
+                        ),
+                    )
+                    .when(event_history.enabled, |form| {
+                        form.child(
+                            field()
+                                .label(event_history_max_age_label)
+                                .child(Input::new(&self.event_history_max_age_state)),
+                        )
+                        .child(
+                            field()
+                                .label(event_history_max_rows_label)
+                                .child(Input::new(&self.event_history_max_rows_state)),
+                        )
+                    })
+                    .child(
+                        field()
+                            .label(prop_history_max_rows_label)
+                            .child(Input::new(&self.prop_history_max_rows_state)),
+                    )
+                    .when(!locked, |form| {
+                        form.child(
+                            field()
+                                .label(proxy_url_label)
+                                .child(Input::new(&self.global_proxy_url_state)),
+                        )
+                        .child(
+                            field()
+                                .label(proxy_username_label)
+                                .child(Input::new(&self.global_proxy_username_state)),
+                        )
+                        .child(
+                            field()
+                                .label(proxy_password_label)
+                                .child(Input::new(&self.global_proxy_password_state).mask_toggle()),
+                        )
+                        .child(
+                            field()
+                                .label(proxy_bypass_label)
+                                .child(Input::new(&self.global_proxy_bypass_state)),
+                        )
+                    }),
             )
     }
+
+    /// Reload servers, preset credentials and the global proxy after a vault change
+    fn reload_vault_secrets(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.app_state
+            .update(cx, |state, cx| state.reload_vault_secrets(cx));
+        let credentials = self.app_state.read(cx).preset_credentials();
+        let proxy = self.app_state.read(cx).proxy();
+        cx.global::<DfcGlobalStore>()
+            .services()
+            .proxies()
+            .set_global(proxy.clone());
+
+        let proxy = proxy.unwrap_or_default();
+        let values = [
+            (
+                &self.preset_credentials_state,
+                credentials_to_text(&credentials),
+            ),
+            (&self.global_proxy_url_state, proxy.url),
+            (
+                &self.global_proxy_username_state,
+                proxy.username.unwrap_or_default(),
+            ),
+            (
+                &self.global_proxy_password_state,
+                proxy.password.unwrap_or_default(),
+            ),
+            (
+                &self.global_proxy_bypass_state,
+                proxy.bypass.unwrap_or_default(),
+            ),
+        ];
+        for (state, value) in values {
+            state.update(cx, |input, cx| input.set_value(value, window, cx));
+        }
+        cx.notify();
+    }
+
+    /// Persist the event history retention; applies to topics opened afterwards.
+    /// Values that are not numbers keep the previous setting.
+    fn save_event_history(&mut self, cx: &mut Context<Self>) {
+        let mut event_history = self.app_state.read(cx).event_history().clone();
+        let max_age = self.event_history_max_age_state.read(cx).value();
+        if let Ok(days) = max_age.trim().parse() {
+            event_history.max_age_days = days;
+        }
+        let max_rows = self.event_history_max_rows_state.read(cx).value();
+        if let Ok(rows) = max_rows.trim().parse::<usize>() {
+            event_history.max_rows = rows.max(1);
+        }
+        update_app_state_and_save(cx, "set_event_history", move |state, _| {
+            state.set_event_history(event_history.clone());
+        });
+    }
+
+    /// Persist the prop history limit; open prop tables pick it up on their
+    /// next update. Values that are not numbers keep the previous setting.
+    fn save_prop_history_max_rows(&mut self, cx: &mut Context<Self>) {
+        let max_rows = self.prop_history_max_rows_state.read(cx).value();
+        let Ok(max_rows) = max_rows.trim().parse::<usize>() else {
+            return;
+        };
+        update_app_state_and_save(cx, "set_prop_history_max_rows", move |state, _| {
+            state.set_prop_history_max_rows(max_rows);
+        });
+    }
+
+    /// Persist the global proxy and apply it to new connections
+    fn save_global_proxy(&mut self, cx: &mut Context<Self>) {
+        // While locked the password input holds cipher text; never save it back
+        if vault_locked() {
+            return;
+        }
+        let proxy = proxy_from_inputs(
+            &[
+                self.global_proxy_url_state.clone(),
+                self.global_proxy_username_state.clone(),
+                self.global_proxy_password_state.clone(),
+                self.global_proxy_bypass_state.clone(),
+            ],
+            cx,
+        );
+        if let Some(Err(e)) = proxy.as_ref().map(ProxyConfig::endpoint) {
+            tracing::warn!("Invalid global proxy: {}", e);
+        }
+        cx.global::<DfcGlobalStore>()
+            .services()
+            .proxies()
+            .set_global(proxy.clone());
+        update_app_state_and_save(cx, "set_proxy", move |state, _| {
+            if let Err(e) = state.set_proxy(proxy.clone()) {
+                tracing::error!(error = %e, "Failed to encrypt the global proxy password");
+            }
+        });
+    }
+}
+
+/// Environment colour of the server badges
+fn environment_color(environment: ServerEnvironment, cx: &App) -> gpui::Hsla {
+    match environment {
+        ServerEnvironment::Dev => cx.theme().success,
+        ServerEnvironment::Test => cx.theme().warning,
+        ServerEnvironment::Prod => cx.theme().danger,
+    }
+}
+
+/// Environment and safety badges and tags of a server card or list row;
+/// `None` without any of them
+fn render_server_labels(server: &DfcServerConfig, cx: &App) -> Option<gpui::AnyElement> {
+    let connected_via = render_connected_via(server, cx);
+    if server.environment.is_none()
+        && server.tags.is_empty()
+        && !server.safety.is_protected()
+        && connected_via.is_none()
+    {
+        return None;
+    }
+    let tags: Vec<_> = server
+        .tags
+        .iter()
+        .map(|tag| {
+            Label::new(format!("#{tag}"))
+                .text_xs()
+                .text_color(cx.theme().muted_foreground)
+        })
+        .collect();
+    Some(
+        h_flex()
+            .gap_1()
+            .flex_wrap()
+            .items_center()
+            .when_some(server.environment, |this, environment| {
+                this.child(render_badge(
+                    i18n_servers(cx, &format!("env_{}", environment.as_str())),
+                    environment_color(environment, cx),
+                ))
+            })
+            .when_some(render_safety_badge(server.safety, cx), |this, badge| {
+                this.child(badge)
+            })
+            .children(tags)
+            .children(connected_via)
+            .into_any_element(),
+    )
+}
+
+/// "via preset credential #N" when a preset credential connected last time
+fn render_connected_via(server: &DfcServerConfig, cx: &App) -> Option<Label> {
+    let store = cx.global::<DfcGlobalStore>();
+    let WorkingCredential::Preset { index, username } =
+        store.read(cx).working_credential(&server.id)?
+    else {
+        return None;
+    };
+    let number = (index + 1).to_string();
+    let text = match username.as_deref().filter(|user| !user.is_empty()) {
+        Some(user) => i18n_format(
+            cx,
+            "servers.connected_via_user",
+            &[("index", &number), ("user", user)],
+        ),
+        None => i18n_format(cx, "servers.connected_via", &[("index", &number)]),
+    };
+    Some(
+        Label::new(text)
+            .text_xs()
+            .text_color(cx.theme().muted_foreground),
+    )
+}
+
+/// Proxy from the URL, username, password and bypass inputs; `None` without a URL
+fn proxy_from_inputs(states: &[Entity<InputState>; 4], cx: &App) -> Option<ProxyConfig> {
+    let [url, username, password, bypass] = states.each_ref().map(|state| {
+        let value = state.read(cx).value();
+        let value = value.trim();
+        (!value.is_empty()).then(|| value.to_string())
+    });
+    Some(ProxyConfig {
+        url: url?,
+        username,
+        password,
+        bypass,
+    })
 }
 
 impl Render for DfcContent {
@@ -0,0 +1,579 @@
+//! Keyspace Analyzer View
+//!
+//! Background big-key and keyspace analysis of the active Redis server:
+//! - Sample random keys or SCAN the whole keyspace in batches, with cancel
+//! - Largest keys by memory and element count
+//! - Key count and memory per prefix and per type, TTL histogram
+//! - CSV export of the report
+
+use crate::connection::{KeySample, TTL_BUCKET_COUNT, ttl_bucket_label};
+use crate::helpers::format_bytes;
+use crate::states::{
+    DfcGlobalStore, KEY_ANALYSIS_BATCH_SIZE, KEY_ANALYSIS_SAMPLE_SIZE, KeyAnalysisMode,
+    KeyAnalysisState, KeyAnalysisStatus, KeysState,
+};
+use crate::views::render_simple_table;
+use chrono::Local;
+use gpui::{App, Context, Entity, Subscription, Task, Window, div, prelude::*};
+use gpui_component::{
+    ActiveTheme, Disableable, IconName, Selectable, Sizable,
+    button::{Button, ButtonVariants},
+    h_flex,
+    label::Label,
+    scroll::ScrollableElement,
+    v_flex,
+};
+use home::home_dir;
+use rust_i18n::t;
+use std::path::PathBuf;
+
+/// Rows shown per table; the CSV export contains everything
+const TABLE_ROWS: usize = 20;
+
+/// Keyspace analyzer view component
+pub struct KeyAnalyzerView {
+    /// Keys state entity (provides the active server)
+    keys_state: Entity<KeysState>,
+    /// Analyzer progress and report
+    analysis_state: Entity<KeyAnalysisState>,
+    /// Mode used by the next run
+    mode: KeyAnalysisMode,
+    /// Result of the last CSV export: written path or error
+    export_result: Option<Result<String, String>>,
+    /// Running analysis loop; dropping it cancels the run
+    analysis_task: Option<Task<()>>,
+    /// Subscriptions
+    _subscriptions: Vec<Subscription>,
+}
+
+impl KeyAnalyzerView {
+    /// Create a new analyzer view
+    pub fn new(
+        keys_state: Entity<KeysState>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let analysis_state = cx.new(|_| KeyAnalysisState::new());
+        let mut subscriptions = Vec::new();
+
+        subscriptions.push(cx.observe(&analysis_state, |_this, _model, cx| {
+            cx.notify();
+        }));
+
+        // A report of another server is meaningless after switching
+        subscriptions.push(cx.observe(&keys_state, |this, model, cx| {
+            let active = model.read(cx).active_server_id().map(str::to_string);
+            let report_server = this.analysis_state.read(cx).server_id().map(str::to_string);
+            if report_server.is_some() && report_server != active {
+                this.analysis_task = None;
+                this.export_result = None;
+                this.analysis_state.update(cx, |state, cx| state.clear(cx));
+            }
+        }));
+
+        Self {
+            keys_state,
+            analysis_state,
+            mode: KeyAnalysisMode::default(),
+            export_result: None,
+            analysis_task: None,
+            _subscriptions: subscriptions,
+        }
+    }
+
+    /// Get the locale string
+    fn locale(&self, cx: &App) -> String {
+        cx.global::<DfcGlobalStore>().read(cx).locale().to_string()
+    }
+
+    /// Start an analysis run, replacing any running one
+    fn start(&mut self, cx: &mut Context<Self>) {
+        let Some(server_id) = self
+            .keys_state
+            .read(cx)
+            .active_server_id()
+            .map(str::to_string)
+        else {
+            return;
+        };
+        let mode = self.mode;
+        let delimiters = cx
+            .global::<DfcGlobalStore>()
+            .read(cx)
+            .key_tree_delimiters()
+            .to_string();
+        self.export_result = None;
+
+        let generation = self
+            .analysis_state
+            .update(cx, |state, cx| state.begin(server_id.clone(), mode, cx));
+        let store = cx.global::<DfcGlobalStore>().clone();
+        let analysis_state = self.analysis_state.clone();
+        let task = cx.spawn(async move |handle, cx| {
+            let redis = store.services().redis();
+            if let Ok(db_size) = redis.db_size().await {
+                let _ = analysis_state.update(cx, |state, cx| {
+                    if state.generation() == generation {
+                        state.set_db_size(db_size, cx);
+                    }
+                });
+            }
+
+            let mut cursor = 0;
+            let mut sampled = 0;
+            loop {
+                let result = match mode {
+                    KeyAnalysisMode::Sample => {
+                        let count = KEY_ANALYSIS_BATCH_SIZE.min(KEY_ANALYSIS_SAMPLE_SIZE - sampled);
+                        sampled += count;
+                        redis.sample_keys(count).await.map(|samples| {
+                            // An empty batch means the database is empty
+                            let done = sampled >= KEY_ANALYSIS_SAMPLE_SIZE || samples.is_empty();
+                            (samples, done)
+                        })
+                    }
+                    KeyAnalysisMode::Full => {
+                        redis
+                            .analyze_keys(cursor, KEY_ANALYSIS_BATCH_SIZE)
+                            .await
+                            .map(|(samples, next_cursor)| {
+                                cursor = next_cursor;
+                                (samples, next_cursor == 0)
+                            })
+                    }
+                };
+                let keep_running = analysis_state
+                    .update(cx, |state, cx| {
+                        if state.generation() != generation {
+                            tracing::info!(server_id = %server_id, "Ignoring stale key analysis batch");
+                            return false;
+                        }
+                        match result {
+                            Ok((samples, done)) => {
+                                state.push_batch(samples, done, &delimiters, cx);
+                                !done
+                            }
+                            Err(e) => {
+                                tracing::error!("Key analysis failed: {}", e);
+                                state.set_error(e.to_string(), cx);
+                                false
+                            }
+                        }
+                    })
+                    .unwrap_or(false);
+                if !keep_running {
+                    break;
+                }
+            }
+
+            let _ = handle.update(cx, |this, cx| {
+                if this.analysis_state.read(cx).generation() == generation {
+                    this.analysis_task = None;
+                }
+            });
+        });
+        self.analysis_task = Some(task);
+    }
+
+    /// Cancel the running analysis
+    fn cancel(&mut self, cx: &mut Context<Self>) {
+        self.analysis_task = None;
+        self.analysis_state.update(cx, |state, cx| state.cancel(cx));
+    }
+
+    /// Ask for a file name and write the report as CSV
+    fn export_csv(&mut self, cx: &mut Context<Self>) {
+        let csv = self.analysis_state.read(cx).report().to_csv();
+        let server_name: String = self
+            .keys_state
+            .read(cx)
+            .active_server()
+            .map(|server| server.server_name.as_str())
+            .unwrap_or("redis")
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '-' })
+            .collect();
+        let file_name = format!(
+            "keyspace-{server_name}-{}.csv",
+            Local::now().format("%Y%m%d-%H%M%S")
+        );
+        let directory = home_dir().unwrap_or_else(|| PathBuf::from("."));
+        let path_rx = cx.prompt_for_new_path(&directory, Some(&file_name));
+
+        cx.spawn(async move |handle, cx| {
+            let Ok(Ok(Some(path))) = path_rx.await else {
+                return;
+            };
+            let result = std::fs::write(&path, csv)
+                .map(|_| path.display().to_string())
+                .map_err(|e| e.to_string());
+            if let Err(e) = &result {
+                tracing::error!("Failed to export keyspace report: {}", e);
+            }
+            let _ = handle.update(cx, |this, cx| {
+                this.export_result = Some(result);
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    /// Render mode selector, start/cancel and export buttons
+    fn render_toolbar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let locale = self.locale(cx);
+        let state = self.analysis_state.read(cx);
+        let running = state.is_running();
+        let can_export = !running && state.report().analyzed > 0;
+
+        let mode_button = |id: &'static str, label: String, mode: KeyAnalysisMode| {
+            Button::new(id)
+                .ghost()
+                .xsmall()
+                .label(label)
+                .selected(self.mode == mode)
+                .disabled(running)
+                .on_click(cx.listener(move |this, _, _, cx| {
+                    this.mode = mode;
+                    cx.notify();
+                }))
+        };
+        let sample_button = mode_button(
+            "key-analysis-sample",
+            t!(
+                "key_analysis.mode_sample",
+                count = KEY_ANALYSIS_SAMPLE_SIZE,
+                locale = &locale
+            )
+            .to_string(),
+            KeyAnalysisMode::Sample,
+        );
+        let full_button = mode_button(
+            "key-analysis-full",
+            t!("key_analysis.mode_full", locale = &locale).to_string(),
+            KeyAnalysisMode::Full,
+        );
+
+        let action = if running {
+            Button::new("key-analysis-cancel")
+                .small()
+                .icon(IconName::CircleX)
+                .label(t!("key_analysis.cancel", locale = &locale).to_string())
+                .on_click(cx.listener(|this, _, _, cx| this.cancel(cx)))
+        } else {
+            Button::new("key-analysis-start")
+                .small()
+                .primary()
+                .label(t!("key_analysis.start", locale = &locale).to_string())
+                .on_click(cx.listener(|this, _, _, cx| this.start(cx)))
+        };
+
+        h_flex()
+            .w_full()
+            .gap_2()
+            .items_center()
+            .child(sample_button)
+            .child(full_button)
+            .child(div().flex_1())
+            .child(
+                Button::new("key-analysis-export")
+                    .small()
+                    .ghost()
+                    .label(t!("key_analysis.export_csv", locale = &locale).to_string())
+                    .disabled(!can_export)
+                    .on_click(cx.listener(|this, _, _, cx| this.export_csv(cx))),
+            )
+            .child(action)
+    }
+
+    /// Render progress, status and export result
+    fn render_status(&self, cx: &App) -> impl IntoElement {
+        let locale = self.locale(cx);
+        let state = self.analysis_state.read(cx);
+        let analyzed = state.report().analyzed;
+        let progress = match (state.mode(), state.db_size()) {
+            (KeyAnalysisMode::Sample, db_size) => t!(
+                "key_analysis.progress_sample",
+                analyzed = analyzed,
+                db_size = db_size
+                    .map(|s| s.to_string())
+                    .unwrap_or_else(|| "?".to_string()),
+                locale = &locale
+            ),
+            (KeyAnalysisMode::Full, Some(db_size)) => t!(
+                "key_analysis.progress_full",
+                analyzed = analyzed,
+                db_size = db_size,
+                locale = &locale
+            ),
+            (KeyAnalysisMode::Full, None) => t!(
+                "key_analysis.progress_full",
+                analyzed = analyzed,
+                db_size = "?",
+                locale = &locale
+            ),
+        }
+        .to_string();
+        let (status, color) = match state.status() {
+            KeyAnalysisStatus::Idle => (
+                t!("key_analysis.idle", locale = &locale).to_string(),
+                cx.theme().muted_foreground,
+            ),
+            KeyAnalysisStatus::Running => (
+                t!("key_analysis.running", locale = &locale).to_string(),
+                cx.theme().muted_foreground,
+            ),
+            KeyAnalysisStatus::Finished => (
+                t!("key_analysis.finished", locale = &locale).to_string(),
+                cx.theme().success,
+            ),
+            KeyAnalysisStatus::Cancelled => (
+                t!("key_analysis.cancelled", locale = &locale).to_string(),
+                cx.theme().warning,
+            ),
+            KeyAnalysisStatus::Failed(error) => (
+                t!("key_analysis.failed", error = error, locale = &locale).to_string(),
+                cx.theme().danger,
+            ),
+        };
+        let idle = *state.status() == KeyAnalysisStatus::Idle;
+        let export = self.export_result.as_ref().map(|result| match result {
+            Ok(path) => (
+                t!("key_analysis.exported", path = path, locale = &locale).to_string(),
+                cx.theme().success,
+            ),
+            Err(error) => (
+                t!(
+                    "key_analysis.export_failed",
+                    error = error,
+                    locale = &locale
+                )
+                .to_string(),
+                cx.theme().danger,
+            ),
+        });
+
+        v_flex()
+            .gap_1()
+            .child(
+                h_flex()
+                    .gap_2()
+                    .when(!idle, |this| {
+                        this.child(
+                            Label::new(progress)
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground),
+                        )
+                    })
+                    .child(Label::new(status).text_xs().text_color(color)),
+            )
+            .when_some(export, |this, (message, color)| {
+                this.child(Label::new(message).text_xs().text_color(color))
+            })
+    }
+
+    /// Render a titled section
+    fn render_section(&self, title: String, body: impl IntoElement, cx: &App) -> impl IntoElement {
+        v_flex()
+            .w_full()
+            .gap_1()
+            .child(
+                Label::new(title)
+                    .text_sm()
+                    .text_color(cx.theme().muted_foreground),
+            )
+            .child(body)
+    }
+
+    /// Render a "largest keys" table
+    fn render_top_keys(
+        &self,
+        id: &'static str,
+        title: String,
+        samples: &[KeySample],
+        cx: &App,
+    ) -> impl IntoElement {
+        let locale = self.locale(cx);
+        let columns = [
+            (t!("keys.key", locale = &locale).to_string(), None),
+            (t!("keys.type", locale = &locale).to_string(), Some(70.0)),
+            (
+                t!("key_analysis.memory", locale = &locale).to_string(),
+                Some(100.0),
+            ),
+            (
+                t!("key_analysis.elements", locale = &locale).to_string(),
+                Some(100.0),
+            ),
+            (t!("keys.ttl", locale = &locale).to_string(), Some(90.0)),
+        ];
+        let rows = samples
+            .iter()
+            .take(TABLE_ROWS)
+            .map(|sample| {
+                vec![
+                    sample.key.clone(),
+                    sample.key_type.short_name().to_string(),
+                    sample.memory.map(format_bytes).unwrap_or_default(),
+                    sample.elements.map(|e| e.to_string()).unwrap_or_default(),
+                    if sample.ttl < 0 {
+                        "-".to_string()
+                    } else {
+                        format!("{}s", sample.ttl)
+                    },
+                ]
+            })
+            .collect();
+        self.render_section(title, render_simple_table(id, &columns, rows, cx), cx)
+    }
+
+    /// Render the report tables
+    fn render_report(&self, cx: &App) -> impl IntoElement {
+        let locale = self.locale(cx);
+        let report = self.analysis_state.read(cx).report();
+        let keys_label = t!("key_analysis.keys", locale = &locale).to_string();
+        let memory_label = t!("key_analysis.memory", locale = &locale).to_string();
+
+        let group_columns = |name: String| {
+            [
+                (name, None),
+                (keys_label.clone(), Some(100.0)),
+                (memory_label.clone(), Some(100.0)),
+            ]
+        };
+
+        let type_rows = report
+            .types()
+            .into_iter()
+            .map(|(key_type, stats)| {
+                vec![
+                    key_type.short_name().to_string(),
+                    stats.keys.to_string(),
+                    format_bytes(stats.memory),
+                ]
+            })
+            .collect();
+        let prefix_rows = report
+            .prefixes()
+            .into_iter()
+            .take(TABLE_ROWS)
+            .map(|(prefix, stats)| {
+                vec![
+                    if prefix.is_empty() {
+                        t!("key_analysis.no_prefix", locale = &locale).to_string()
+                    } else {
+                        prefix.to_string()
+                    },
+                    stats.keys.to_string(),
+                    format_bytes(stats.memory),
+                ]
+            })
+            .collect();
+        let mut ttl_rows = vec![vec![
+            t!("keys.no_expiry", locale = &locale).to_string(),
+            report.no_expiry.to_string(),
+        ]];
+        ttl_rows.extend(
+            (0..TTL_BUCKET_COUNT)
+                .map(|ix| vec![ttl_bucket_label(ix), report.ttl_buckets[ix].to_string()]),
+        );
+
+        v_flex()
+            .w_full()
+            .gap_4()
+            .child(self.render_top_keys(
+                "key-analysis-top-memory",
+                t!("key_analysis.top_memory", locale = &locale).to_string(),
+                report.top_by_memory(),
+                cx,
+            ))
+            .child(self.render_top_keys(
+                "key-analysis-top-elements",
+                t!("key_analysis.top_elements", locale = &locale).to_string(),
+                report.top_by_elements(),
+                cx,
+            ))
+            .child(
+                h_flex()
+                    .w_full()
+                    .gap_4()
+                    .items_start()
+                    .child(div().flex_1().child(self.render_section(
+                        t!("key_analysis.by_prefix", locale = &locale).to_string(),
+                        render_simple_table(
+                            "key-analysis-prefixes",
+                            &group_columns(t!("key_analysis.prefix", locale = &locale).to_string()),
+                            prefix_rows,
+                            cx,
+                        ),
+                        cx,
+                    )))
+                    .child(
+                        v_flex()
+                            .w(gpui::px(280.0))
+                            .gap_4()
+                            .child(self.render_section(
+                                t!("key_analysis.by_type", locale = &locale).to_string(),
+                                render_simple_table(
+                                    "key-analysis-types",
+                                    &group_columns(t!("keys.type", locale = &locale).to_string()),
+                                    type_rows,
+                                    cx,
+                                ),
+                                cx,
+                            ))
+                            .child(self.render_section(
+                                t!("key_analysis.ttl_histogram", locale = &locale).to_string(),
+                                render_simple_table(
+                                    "key-analysis-ttl",
+                                    &[
+                                        (t!("keys.ttl", locale = &locale).to_string(), None),
+                                        (keys_label.clone(), Some(100.0)),
+                                    ],
+                                    ttl_rows,
+                                    cx,
+                                ),
+                                cx,
+                            )),
+                    ),
+            )
+    }
+}
+
+impl Render for KeyAnalyzerView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        if self.keys_state.read(cx).active_server_id().is_none() {
+            let locale = self.locale(cx);
+            return div()
+                .size_full()
+                .flex()
+                .items_center()
+                .justify_center()
+                .child(
+                    Label::new(t!("console.no_server", locale = &locale).to_string())
+                        .text_color(cx.theme().muted_foreground),
+                )
+                .into_any_element();
+        }
+
+        let has_report = self.analysis_state.read(cx).report().analyzed > 0;
+        let toolbar = self.render_toolbar(cx).into_any_element();
+
+        v_flex()
+            .size_full()
+            .p_3()
+            .gap_2()
+            .child(toolbar)
+            .child(self.render_status(cx))
+            .when(has_report, |this| {
+                this.child(
+                    div()
+                        .id("key-analysis-report")
+                        .flex_1()
+                        .w_full()
+                        .child(self.render_report(cx))
+                        .overflow_y_scrollbar(),
+                )
+            })
+            .into_any_element()
+    }
+}
@@ -0,0 +1,617 @@
+//! Key Compare View
+//!
+//! Compares a key, or the keys matching a pattern, between the active server
+//! and another connected server:
+//! - Structural diff per JSON path, hash field, set member and list index
+//! - Copy a key to the target server after confirmation (the target's
+//!   safety level must allow the write)
+
+use super::write_guard::guard_write;
+use crate::connection::{DiffKind, KeyComparison, KeyDiff, SafetyLevel, WritePermit};
+use crate::services::{AuditEntry, AuditLog};
+use crate::states::{DfcGlobalStore, KeyCompareState, KeyCompareStatus, KeysState};
+use crate::views::render_simple_table;
+use gpui::{AnyElement, App, Context, Entity, SharedString, Subscription, Window, div, prelude::*};
+use gpui_component::{
+    ActiveTheme, Disableable, IconName, Selectable, Sizable, WindowExt,
+    button::{Button, ButtonVariants},
+    h_flex,
+    input::{Input, InputEvent, InputState},
+    label::Label,
+    scroll::ScrollableElement,
+    v_flex,
+};
+use rust_i18n::t;
+
+/// Diff entries listed per key
+const DIFF_ROWS: usize = 100;
+
+/// Key compare view component
+pub struct KeyCompareView {
+    /// Keys state entity (provides the active server and selected key)
+    keys_state: Entity<KeysState>,
+    /// Comparison results
+    compare_state: Entity<KeyCompareState>,
+    /// Key or pattern input
+    key_state: Entity<InputState>,
+    /// Server to compare against
+    target_server_id: Option<String>,
+    /// Result of the last copy: message or error
+    copy_result: Option<Result<String, String>>,
+    /// Subscriptions
+    _subscriptions: Vec<Subscription>,
+}
+
+impl KeyCompareView {
+    /// Create a new key compare view
+    pub fn new(keys_state: Entity<KeysState>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let compare_state = cx.new(|_| KeyCompareState::new());
+        let mut subscriptions = Vec::new();
+
+        subscriptions.push(cx.observe(&compare_state, |_this, _model, cx| {
+            cx.notify();
+        }));
+
+        // Results are tied to the source server; forget targets that were closed
+        subscriptions.push(cx.observe(&keys_state, |this, model, cx| {
+            let keys_state = model.read(cx);
+            let active = keys_state.active_server_id().map(str::to_string);
+            let target_connected = this.target_server_id.as_deref().is_some_and(|target| {
+                Some(target) != active.as_deref()
+                    && keys_state
+                        .connected_servers()
+                        .iter()
+                        .any(|s| s.server_id == target)
+            });
+            if !target_connected {
+                this.target_server_id = None;
+            }
+            let source = this
+                .compare_state
+                .read(cx)
+                .source_server_id()
+                .map(str::to_string);
+            if source.is_some() && (source != active || !target_connected) {
+                this.copy_result = None;
+                this.compare_state.update(cx, |state, cx| state.clear(cx));
+            }
+            cx.notify();
+        }));
+
+        let locale = cx.global::<DfcGlobalStore>().read(cx).locale().to_string();
+        let key_state = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder(t!("key_compare.key_placeholder", locale = &locale).to_string())
+        });
+        subscriptions.push(cx.subscribe(&key_state, |this, _state, event, cx| {
+            if let InputEvent::PressEnter { .. } = event {
+                this.compare(cx);
+            }
+        }));
+
+        Self {
+            keys_state,
+            compare_state,
+            key_state,
+            target_server_id: None,
+            copy_result: None,
+            _subscriptions: subscriptions,
+        }
+    }
+
+    /// Get the locale string
+    fn locale(&self, cx: &App) -> String {
+        cx.global::<DfcGlobalStore>().read(cx).locale().to_string()
+    }
+
+    /// Display name of a connected server
+    fn server_name(&self, server_id: &str, cx: &App) -> String {
+        self.keys_state
+            .read(cx)
+            .connected_servers()
+            .iter()
+            .find(|s| s.server_id == server_id)
+            .map(|s| s.server_name.clone())
+            .unwrap_or_else(|| server_id.to_string())
+    }
+
+    /// Compare the entered key or pattern (the selected key if empty)
+    fn compare(&mut self, cx: &mut Context<Self>) {
+        let keys_state = self.keys_state.read(cx);
+        let Some(source_id) = keys_state.active_server_id().map(str::to_string) else {
+            return;
+        };
+        let Some(target_id) = self.target_server_id.clone() else {
+            return;
+        };
+        let key_or_pattern = match self.key_state.read(cx).value().trim() {
+            "" => keys_state.selected_key().unwrap_or_default().to_string(),
+            input => input.to_string(),
+        };
+        if key_or_pattern.is_empty() {
+            return;
+        }
+        let app_state = cx.global::<DfcGlobalStore>().read(cx);
+        let Some(target) = app_state.server(&target_id).cloned() else {
+            return;
+        };
+        let credentials = app_state.preset_credentials();
+        self.copy_result = None;
+
+        let generation = self.compare_state.update(cx, |state, cx| {
+            state.begin(source_id.clone(), target_id.clone(), cx)
+        });
+        let store = cx.global::<DfcGlobalStore>().clone();
+        let compare_state = self.compare_state.clone();
+        cx.spawn(async move |_, cx| {
+            let redis = store.services().redis();
+            let result = redis
+                .compare_keys(&target, &credentials, &key_or_pattern)
+                .await;
+            let _ = compare_state.update(cx, |state, cx| {
+                if state.generation() != generation {
+                    tracing::info!(source_id, target_id, "Ignoring stale key comparison");
+                    return;
+                }
+                match result {
+                    Ok(results) => state.set_results(results, cx),
+                    Err(e) => {
+                        tracing::error!("Key comparison failed: {}", e);
+                        state.set_error(e.to_string(), cx);
+                    }
+                }
+            });
+        })
+        .detach();
+    }
+
+    /// Ask for confirmation, then overwrite the key on the target server
+    fn confirm_copy(&mut self, key: String, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(target_id) = self.compare_state.read(cx).target_server_id() else {
+            return;
+        };
+        let locale = self.locale(cx);
+        let title = t!("key_compare.copy_title", locale = &locale).to_string();
+        let message = t!(
+            "key_compare.copy_confirm",
+            key = &key,
+            target = self.server_name(target_id, cx),
+            locale = &locale
+        )
+        .to_string();
+        let view = cx.entity();
+
+        window.open_dialog(cx, move |dialog, _, _| {
+            let view = view.clone();
+            let key = key.clone();
+            dialog
+                .confirm()
+                .title(title.clone())
+                .child(Label::new(message.clone()).text_sm())
+                .on_ok(move |_, window, cx| {
+                    let view = view.clone();
+                    let key = key.clone();
+                    // Ask for the server name once this dialog has closed
+                    window.defer(cx, move |window, cx| {
+                        view.update(cx, |this, cx| this.authorize_copy(key, window, cx));
+                    });
+                    true
+                })
+        });
+    }
+
+    /// Copy a confirmed key once the safety level of the target allows it
+    fn authorize_copy(&mut self, key: String, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(target_id) = self.compare_state.read(cx).target_server_id() else {
+            return;
+        };
+        let Some(target) = cx
+            .global::<DfcGlobalStore>()
+            .read(cx)
+            .server(target_id)
+            .cloned()
+        else {
+            return;
+        };
+        let view = cx.entity();
+        guard_write(
+            &target,
+            format!("RESTORE {key} REPLACE"),
+            window,
+            cx,
+            move |result, _, cx| {
+                view.update(cx, |this, cx| match result {
+                    Ok(permit) => this.copy_to_target(permit, key.clone(), cx),
+                    Err(e) => {
+                        this.copy_result = Some(Err(e.to_string()));
+                        cx.notify();
+                    }
+                });
+            },
+        );
+    }
+
+    /// Copy a confirmed key, record it in the audit log and re-compare it
+    fn copy_to_target(&mut self, permit: WritePermit, key: String, cx: &mut Context<Self>) {
+        let compare_state = self.compare_state.read(cx);
+        let (Some(source_id), Some(target_id)) = (
+            compare_state.source_server_id().map(str::to_string),
+            compare_state.target_server_id().map(str::to_string),
+        ) else {
+            return;
+        };
+        let generation = compare_state.generation();
+        let app_state = cx.global::<DfcGlobalStore>().read(cx);
+        let Some(target) = app_state.server(&target_id).cloned() else {
+            return;
+        };
+        let credentials = app_state.preset_credentials();
+        let source_name = self.server_name(&source_id, cx);
+
+        let store = cx.global::<DfcGlobalStore>().clone();
+        cx.spawn(async move |this, cx| {
+            let redis = store.services().redis();
+            let result = redis
+                .copy_key_to_server(&permit, &target, &credentials, &key)
+                .await;
+            let (before, after) = result.as_ref().cloned().unwrap_or_default();
+            AuditLog::record(&AuditEntry {
+                timestamp: chrono::Local::now().to_rfc3339(),
+                server_id: target.id.clone(),
+                server_name: target.name.clone(),
+                key: key.clone(),
+                command: format!("RESTORE {key} <DUMP from {source_name}> REPLACE"),
+                before,
+                after,
+                success: result.is_ok(),
+                error: result.as_ref().err().map(|e| e.to_string()),
+            });
+
+            let refreshed = match &result {
+                Ok(_) => redis.compare_keys(&target, &credentials, &key).await.ok(),
+                Err(_) => None,
+            };
+            let _ = this.update(cx, |this, cx| {
+                if this.compare_state.read(cx).generation() != generation {
+                    return;
+                }
+                this.copy_result = Some(match result {
+                    Ok(_) => Ok(key.clone()),
+                    Err(e) => {
+                        tracing::error!("Copying key to {} failed: {}", target.name, e);
+                        Err(e.to_string())
+                    }
+                });
+                if let Some(comparison) = refreshed.and_then(|mut r| r.pop()) {
+                    this.compare_state.update(cx, |state, cx| {
+                        state.update_result(comparison, cx);
+                    });
+                }
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    /// Render target server buttons, key input and compare button
+    fn render_toolbar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let locale = self.locale(cx);
+        let keys_state = self.keys_state.read(cx);
+        let active = keys_state.active_server_id();
+        let targets: Vec<(usize, String, String)> = keys_state
+            .connected_servers()
+            .iter()
+            .filter(|s| Some(s.server_id.as_str()) != active)
+            .enumerate()
+            .map(|(ix, s)| (ix, s.server_id.clone(), s.server_name.clone()))
+            .collect();
+        let running = self.compare_state.read(cx).is_running();
+
+        let target_row = if targets.is_empty() {
+            h_flex().child(
+                Label::new(t!("key_compare.no_target", locale = &locale).to_string())
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground),
+            )
+        } else {
+            h_flex()
+                .gap_1()
+                .items_center()
+                .flex_wrap()
+                .child(
+                    Label::new(t!("key_compare.target", locale = &locale).to_string())
+                        .text_sm()
+                        .text_color(cx.theme().muted_foreground),
+                )
+                .children(targets.into_iter().map(|(ix, server_id, server_name)| {
+                    let selected = self.target_server_id.as_deref() == Some(server_id.as_str());
+                    Button::new(("key-compare-target", ix))
+                        .ghost()
+                        .xsmall()
+                        .label(server_name)
+                        .selected(selected)
+                        .on_click(cx.listener(move |this, _, _, cx| {
+                            this.target_server_id = Some(server_id.clone());
+                            cx.notify();
+                        }))
+                }))
+        };
+
+        v_flex().w_full().gap_2().child(target_row).child(
+            h_flex()
+                .w_full()
+                .gap_2()
+                .child(div().flex_1().child(Input::new(&self.key_state).small()))
+                .child(
+                    Button::new("key-compare-start")
+                        .small()
+                        .primary()
+                        .icon(IconName::Search)
+                        .label(t!("key_compare.compare", locale = &locale).to_string())
+                        .disabled(running || self.target_server_id.is_none())
+                        .on_click(cx.listener(|this, _, _, cx| this.compare(cx))),
+                ),
+        )
+    }
+
+    /// Render the status line
+    fn render_status(&self, cx: &App) -> impl IntoElement {
+        let locale = self.locale(cx);
+        let state = self.compare_state.read(cx);
+        let (status, color) = match state.status() {
+            KeyCompareStatus::Idle => (None, cx.theme().muted_foreground),
+            KeyCompareStatus::Running => (
+                Some(t!("key_compare.running", locale = &locale).to_string()),
+                cx.theme().muted_foreground,
+            ),
+            KeyCompareStatus::Finished => {
+                let different = state
+                    .results()
+                    .iter()
+                    .filter(|r| r.diff.is_different())
+                    .count();
+                (
+                    Some(
+                        t!(
+                            "key_compare.summary",
+                            count = state.results().len(),
+                            different = different,
+                            locale = &locale
+                        )
+                        .to_string(),
+                    ),
+                    if different == 0 {
+                        cx.theme().success
+                    } else {
+                        cx.theme().warning
+                    },
+                )
+            }
+            KeyCompareStatus::Failed(error) => (
+                Some(t!("key_compare.failed", error = error, locale = &locale).to_string()),
+                cx.theme().danger,
+            ),
+        };
+        let copy = self.copy_result.as_ref().map(|result| match result {
+            Ok(key) => (
+                t!("key_compare.copied", key = key, locale = &locale).to_string(),
+                cx.theme().success,
+            ),
+            Err(error) => (
+                t!("key_compare.copy_failed", error = error, locale = &locale).to_string(),
+                cx.theme().danger,
+            ),
+        });
+
+        h_flex()
+            .gap_2()
+            .when_some(status, |this, status| {
+                this.child(Label::new(status).text_xs().text_color(color))
+            })
+            .when_some(copy, |this, (message, color)| {
+                this.child(Label::new(message).text_xs().text_color(color))
+            })
+    }
+
+    /// Render the result of one key with its diff entries
+    fn render_result(&self, ix: usize, cx: &mut Context<Self>) -> AnyElement {
+        let locale = self.locale(cx);
+        let state = self.compare_state.read(cx);
+        let Some(KeyComparison {
+            key,
+            diff,
+            truncated,
+        }) = state.results().get(ix).cloned()
+        else {
+            return div().into_any_element();
+        };
+        let target_writable = state.target_server_id().is_some_and(|target| {
+            cx.global::<DfcGlobalStore>()
+                .read(cx)
+                .server(target)
+                .is_some_and(|server| server.safety != SafetyLevel::ReadOnly)
+        });
+
+        let (badge, color) = match &diff {
+            KeyDiff::Equal => (
+                t!("key_compare.equal", locale = &locale).to_string(),
+                cx.theme().success,
+            ),
+            KeyDiff::OnlyInSource => (
+                t!("key_compare.only_in_source", locale = &locale).to_string(),
+                cx.theme().warning,
+            ),
+            KeyDiff::OnlyInTarget => (
+                t!("key_compare.only_in_target", locale = &locale).to_string(),
+                cx.theme().warning,
+            ),
+            KeyDiff::TypeMismatch { source, target } => (
+                t!(
+                    "key_compare.type_mismatch",
+                    source = source.short_name(),
+                    target = target.short_name(),
+                    locale = &locale
+                )
+                .to_string(),
+                cx.theme().danger,
+            ),
+            KeyDiff::Changed(entries) => (
+                t!(
+                    "key_compare.changed",
+                    count = entries.len(),
+                    locale = &locale
+                )
+                .to_string(),
+                cx.theme().danger,
+            ),
+            KeyDiff::NotCompared(key_type) => (
+                t!(
+                    "key_compare.not_compared",
+                    key_type = key_type.short_name(),
+                    locale = &locale
+                )
+                .to_string(),
+                cx.theme().muted_foreground,
+            ),
+        };
+        // A key missing on the source cannot be copied
+        let can_copy = diff.is_different() && diff != KeyDiff::OnlyInTarget;
+
+        let entries = match diff {
+            KeyDiff::Changed(entries) => entries,
+            _ => Vec::new(),
+        };
+        let hidden = entries.len().saturating_sub(DIFF_ROWS);
+        let rows: Vec<Vec<String>> = entries
+            .into_iter()
+            .take(DIFF_ROWS)
+            .map(|entry| {
+                let kind = match entry.kind {
+                    DiffKind::OnlyInSource => "-",
+                    DiffKind::OnlyInTarget => "+",
+                    DiffKind::Changed => "~",
+                };
+                vec![
+                    kind.to_string(),
+                    entry.path,
+                    entry.source.unwrap_or_default(),
+                    entry.target.unwrap_or_default(),
+                ]
+            })
+            .collect();
+        let columns = [
+            (String::new(), Some(28.0)),
+            (
+                t!("key_compare.path", locale = &locale).to_string(),
+                Some(200.0),
+            ),
+            (t!("key_compare.source", locale = &locale).to_string(), None),
+            (
+                t!("key_compare.target_value", locale = &locale).to_string(),
+                None,
+            ),
+        ];
+        let copy_key = key.clone();
+
+        v_flex()
+            .w_full()
+            .gap_1()
+            .pb_2()
+            .child(
+                h_flex()
+                    .w_full()
+                    .gap_2()
+                    .items_center()
+                    .child(
+                        Label::new(key)
+                            .text_sm()
+                            .text_color(cx.theme().foreground)
+                            .flex_1(),
+                    )
+                    .when(truncated, |this| {
+                        this.child(
+                            Label::new(t!("key_compare.truncated", locale = &locale).to_string())
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground),
+                        )
+                    })
+                    .child(Label::new(badge).text_xs().text_color(color))
+                    .when(can_copy, |this| {
+                        this.child(
+                            Button::new(("key-compare-copy", ix))
+                                .ghost()
+                                .xsmall()
+                                .label(t!("key_compare.copy", locale = &locale).to_string())
+                                .disabled(!target_writable)
+                                .when(!target_writable, |this| {
+                                    this.tooltip(
+                                        t!("key_compare.target_read_only", locale = &locale)
+                                            .to_string(),
+                                    )
+                                })
+                                .on_click(cx.listener(move |this, _, window, cx| {
+                                    this.confirm_copy(copy_key.clone(), window, cx);
+                                })),
+                        )
+                    }),
+            )
+            .when(!rows.is_empty(), |this| {
+                this.child(render_simple_table(
+                    SharedString::from(format!("key-compare-diff-{ix}")),
+                    &columns,
+                    rows,
+                    cx,
+                ))
+            })
+            .when(hidden > 0, |this| {
+                this.child(
+                    Label::new(
+                        t!("key_compare.more", count = hidden, locale = &locale).to_string(),
+                    )
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground),
+                )
+            })
+            .into_any_element()
+    }
+}
+
+impl Render for KeyCompareView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        if self.keys_state.read(cx).active_server_id().is_none() {
+            let locale = self.locale(cx);
+            return div()
+                .size_full()
+                .flex()
+                .items_center()
+                .justify_center()
+                .child(
+                    Label::new(t!("console.no_server", locale = &locale).to_string())
+                        .text_color(cx.theme().muted_foreground),
+                )
+                .into_any_element();
+        }
+
+        let result_count = self.compare_state.read(cx).results().len();
+        let rows: Vec<AnyElement> = (0..result_count)
+            .map(|ix| self.render_result(ix, cx))
+            .collect();
+        let toolbar = self.render_toolbar(cx).into_any_element();
+
+        v_flex()
+            .size_full()
+            .p_3()
+            .gap_2()
+            .child(toolbar)
+            .child(self.render_status(cx))
+            .child(
+                div()
+                    .id("key-compare-results")
+                    .flex_1()
+                    .w_full()
+                    .child(v_flex().w_full().gap_2().children(rows))
+                    .overflow_y_scrollbar(),
+            )
+            .into_any_element()
+    }
+}
@@ -4,18 +4,237 @@
 //! - Left: Search input + Keys list with type badges
 //! - Right: Selected key's value display
 
-use crate::connection::{RedisKeyItem, RedisKeyType, RedisKeyValue};
-use crate::states::{DfcGlobalStore, KeysState};
-use gpui::{App, Context, Entity, Subscription, Window, div, prelude::*, px};
+use super::write_guard::guard_write;
+use crate::assets::CustomIconName;
+use crate::connection::{
+    RedisKeyItem, RedisKeyType, RedisKeyValue, RedisStreamValue, RedisWriteOp, RedisWritePreview,
+    WritePermit,
+};
+use crate::services::{AuditEntry, AuditLog, RedisRepo};
+use crate::states::{
+    DfcGlobalStore, KeyTree, KeyTreeRow, KeyViewMode, KeysState, update_app_state_and_save,
+};
+use crate::views::{
+    ConsoleView, KeyAnalyzerView, KeyCompareView, ServerDashboardView, ValueSearchEvent,
+    ValueSearchView, ValueViewerView,
+};
+use gpui::{App, Context, Entity, SharedString, Subscription, Window, div, prelude::*, px};
 use gpui_component::{
-    ActiveTheme, Colorize, Icon, IconName, Sizable,
+    ActiveTheme, Colorize, Disableable, Icon, IconName, Selectable, Sizable, WindowExt,
     button::{Button, ButtonVariants},
+    form::{field, v_form},
     h_flex,
     input::{Input, InputEvent, InputState},
     label::Label,
     v_flex,
 };
 use rust_i18n::t;
+use std::rc::Rc;
+
+/// Indentation per namespace tree level in pixels
+const TREE_INDENT: f32 = 14.0;
+
+/// Panel shown to the right of the keys list
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum RightPanel {
+    /// Value of the selected key
+    #[default]
+    Value,
+    /// Raw command console
+    Console,
+    /// Server dashboard
+    Dashboard,
+    /// Find in values
+    Search,
+    /// Keyspace analyzer
+    Analyzer,
+    /// Compare keys with another server
+    Compare,
+}
+
+/// Value edit opened from the key browser; each maps to one `RedisWriteOp`
+#[derive(Debug, Clone, PartialEq)]
+enum ValueEdit {
+    /// SET the whole string
+    String,
+    /// HSET a new or existing field
+    HashField,
+    /// LPUSH a new element
+    ListPush,
+    /// LSET the element at an index
+    ListSet(i64),
+    /// SADD a member
+    SetAdd,
+    /// ZADD a member with a score
+    ZSetMember,
+    /// EXPIRE the key
+    Expire,
+    /// RENAMENX the key
+    Rename,
+}
+
+impl ValueEdit {
+    /// Locale key of the dialog title
+    fn title_key(&self) -> &'static str {
+        match self {
+            Self::String => "keys.edit_string_title",
+            Self::HashField => "keys.hash_field_title",
+            Self::ListPush => "keys.list_push_title",
+            Self::ListSet(_) => "keys.list_set_title",
+            Self::SetAdd => "keys.set_add_title",
+            Self::ZSetMember => "keys.zset_member_title",
+            Self::Expire => "keys.expire_title",
+            Self::Rename => "keys.rename_title",
+        }
+    }
+
+    /// Locale key of the first input, for edits that take two inputs
+    fn field_label_key(&self) -> Option<&'static str> {
+        match self {
+            Self::HashField => Some("keys.field"),
+            Self::ZSetMember => Some("keys.member"),
+            _ => None,
+        }
+    }
+
+    /// Locale key of the value input
+    fn value_label_key(&self) -> &'static str {
+        match self {
+            Self::SetAdd => "keys.member",
+            Self::ZSetMember => "keys.score",
+            Self::Expire => "keys.ttl_seconds",
+            Self::Rename => "keys.new_key",
+            _ => "keys.value",
+        }
+    }
+
+    /// Build the write from the dialog inputs, or `None` if they are invalid
+    fn build_op(&self, key: &str, field: &str, value: &str) -> Option<RedisWriteOp> {
+        let key = key.to_string();
+        let op = match self {
+            Self::String => RedisWriteOp::Set {
+                key,
+                value: value.to_string(),
+            },
+            Self::HashField if !field.is_empty() => RedisWriteOp::HSet {
+                key,
+                field: field.to_string(),
+                value: value.to_string(),
+            },
+            Self::ListPush => RedisWriteOp::LPush {
+                key,
+                value: value.to_string(),
+            },
+            Self::ListSet(index) => RedisWriteOp::LSet {
+                key,
+                index: *index,
+                value: value.to_string(),
+            },
+            Self::SetAdd if !value.is_empty() => RedisWriteOp::SAdd {
+                key,
+                member: value.to_string(),
+            },
+            Self::ZSetMember if !field.is_empty() => {
+                let score = value.trim().parse::<f64>().ok().filter(|s| s.is_finite())?;
+                RedisWriteOp::ZAdd {
+                    key,
+                    member: field.to_string(),
+                    score,
+                }
+            }
+            Self::Expire => {
+                let seconds = value.trim().parse::<i64>().ok().filter(|s| *s > 0)?;
+                RedisWriteOp::Expire { key, seconds }
+            }
+            Self::Rename => {
+                let new_key = value.trim();
+                if new_key.is_empty() || new_key == key {
+                    return None;
+                }
+                RedisWriteOp::Rename {
+                    key,
+                    new_key: new_key.to_string(),
+                }
+            }
+            Self::HashField | Self::SetAdd | Self::ZSetMember => return None,
+        };
+        Some(op)
+    }
+}
+
+/// Render a read-only table with a header row (shared by the value and dashboard panels).
+///
+/// The table copies what it needs, so it borrows neither `columns` nor `cx`.
+pub(crate) fn render_simple_table<I: Into<gpui::ElementId>>(
+    id: I,
+    columns: &[(String, Option<f32>)],
+    rows: Vec<Vec<String>>,
+    cx: &App,
+) -> impl IntoElement + use<I> {
+    let header_bg = cx.theme().secondary;
+    let border_color = cx.theme().border;
+    let muted_fg = cx.theme().muted_foreground;
+
+    let cell = |width: Option<f32>, is_last: bool| {
+        let cell = div().px_2().py_1().border_color(border_color);
+        let cell = match width {
+            Some(width) => cell.w(px(width)),
+            None => cell.flex_1(),
+        };
+        if is_last { cell } else { cell.border_r_1() }
+    };
+
+    let header = h_flex()
+        .w_full()
+        .bg(header_bg)
+        .border_b_1()
+        .border_color(border_color)
+        .children(columns.iter().enumerate().map(|(ix, (title, width))| {
+            cell(*width, ix + 1 == columns.len())
+                .child(Label::new(title.clone()).text_sm().text_color(muted_fg))
+        }));
+
+    let mut body = Vec::with_capacity(rows.len());
+    for (index, row) in rows.into_iter().enumerate() {
+        let bg = if index % 2 == 0 {
+            if cx.theme().is_dark() {
+                cx.theme().background.lighten(0.3)
+            } else {
+                cx.theme().background.darken(0.01)
+            }
+        } else {
+            cx.theme().background
+        };
+        body.push(
+            h_flex()
+                .w_full()
+                .bg(bg)
+                .border_b_1()
+                .border_color(border_color)
+                .children(row.into_iter().zip(columns.iter()).enumerate().map(
+                    |(ix, (value, (_, width)))| {
+                        cell(*width, ix + 1 == columns.len())
+                            .child(Label::new(value).text_sm().text_ellipsis())
+                    },
+                )),
+        );
+    }
+
+    div()
+        .w_full()
+        .rounded_md()
+        .border_1()
+        .border_color(border_color)
+        .overflow_hidden()
+        .child(header)
+        .child(
+            div()
+                .id(id)
+                .max_h(px(400.0))
+                .overflow_y_scroll()
+                .children(body),
+        )
+}
 
 /// Keys browser view component
 pub struct KeysBrowserView {
@@ -23,6 +242,26 @@ pub struct KeysBrowserView {
     keys_state: Entity<KeysState>,
     /// Search input state
     search_state: Entity<InputState>,
+    /// MATCH pattern input for fields/members of the selected value
+    value_match_state: Entity<InputState>,
+    /// Field/member input of the edit dialog
+    edit_field_state: Entity<InputState>,
+    /// Value input of the edit dialog
+    edit_value_state: Entity<InputState>,
+    /// Formatted viewer of string values
+    value_viewer: Entity<ValueViewerView>,
+    /// Raw command console shown in place of the value panel
+    console_view: Entity<ConsoleView>,
+    /// Server dashboard shown in place of the value panel
+    dashboard_view: Entity<ServerDashboardView>,
+    /// Find-in-values job shown in place of the value panel
+    value_search_view: Entity<ValueSearchView>,
+    /// Keyspace analyzer shown in place of the value panel
+    key_analyzer_view: Entity<KeyAnalyzerView>,
+    /// Cross-server key comparison shown in place of the value panel
+    key_compare_view: Entity<KeyCompareView>,
+    /// Panel shown to the right of the keys list
+    right_panel: RightPanel,
     /// Subscriptions
     _subscriptions: Vec<Subscription>,
 }
@@ -57,9 +296,75 @@ impl KeysBrowserView {
             }
         }));
 
+        // Create value MATCH input; applied server-side on Enter
+        let value_match_state = cx.new(|cx| {
+            let locale = cx.global::<DfcGlobalStore>().read(cx).locale().to_string();
+            let placeholder = t!("keys.value_match_placeholder", locale = &locale).to_string();
+            InputState::new(window, cx)
+                .clean_on_escape()
+                .placeholder(placeholder)
+        });
+
+        subscriptions.push(cx.subscribe(&value_match_state, |this, state, event, cx| {
+            let pattern = state.read(cx).value().trim().to_string();
+            // Clearing the input drops the filter right away
+            let cleared = matches!(event, InputEvent::Change)
+                && pattern.is_empty()
+                && !this.keys_state.read(cx).value_match().is_empty();
+            if matches!(event, InputEvent::PressEnter { .. }) || cleared {
+                if this.keys_state.read(cx).selected_key().is_none() {
+                    return;
+                }
+                this.keys_state.update(cx, |state, cx| {
+                    state.reload_value(pattern, cx);
+                });
+                this.fetch_value_page(cx);
+            }
+        }));
+
+        // Inputs shared by the value edit dialogs
+        let edit_field_state = cx.new(|cx| InputState::new(window, cx));
+        let edit_value_state =
+            cx.new(|cx| InputState::new(window, cx).multi_line(true).auto_grow(2, 8));
+
+        let value_viewer = cx.new(|cx| ValueViewerView::new(keys_state.clone(), window, cx));
+        let console_view = cx.new(|cx| ConsoleView::new(keys_state.clone(), window, cx));
+        let dashboard_view = cx.new(|cx| ServerDashboardView::new(keys_state.clone(), window, cx));
+        let value_search_view = cx.new(|cx| ValueSearchView::new(keys_state.clone(), window, cx));
+        let key_analyzer_view = cx.new(|cx| KeyAnalyzerView::new(keys_state.clone(), window, cx));
+        let key_compare_view = cx.new(|cx| KeyCompareView::new(keys_state.clone(), window, cx));
+
+        // Opening a search match shows its key in the value panel
+        subscriptions.push(cx.subscribe_in(
+            &value_search_view,
+            window,
+            |this, _view, event, window, cx| match event {
+                ValueSearchEvent::OpenKey(key) => {
+                    this.keys_state.update(cx, |state, cx| {
+                        state.select_key(Some(key.clone()), cx);
+                    });
+                    this.value_match_state.update(cx, |state, cx| {
+                        state.set_value("", window, cx);
+                    });
+                    this.fetch_value_page(cx);
+                    this.set_right_panel(RightPanel::Value, cx);
+                }
+            },
+        ));
+
         Self {
             keys_state,
             search_state,
+            value_match_state,
+            edit_field_state,
+            edit_value_state,
+            value_viewer,
+            console_view,
+            dashboard_view,
+            value_search_view,
+            key_analyzer_view,
+            key_compare_view,
+            right_panel: RightPanel::default(),
             _subscriptions: subscriptions,
         }
     }
@@ -97,6 +402,8 @@ impl KeysBrowserView {
         &self,
         index: usize,
         key_item: &RedisKeyItem,
+        label: &str,
+        depth: usize,
         is_selected: bool,
         cx: &mut Context<Self>,
     ) -> gpui::Stateful<gpui::Div> {
@@ -125,6 +432,9 @@ impl KeysBrowserView {
             .id(("key-item", index))
             .w_full()
             .px_2()
+            .when(depth > 0, |this| {
+                this.pl(px(8.0 + depth as f32 * TREE_INDENT))
+            })
             .py_1()
             .bg(bg)
             .cursor_pointer()
@@ -136,89 +446,775 @@ impl KeysBrowserView {
                     .items_center()
                     .child(self.render_type_badge(key_item.key_type, cx))
                     .child(
-                        Label::new(key_item.key.clone())
+                        Label::new(label.to_string())
                             .text_sm()
                             .text_color(text_color)
                             .text_ellipsis()
                             .flex_1(),
                     ),
             )
-            .on_click(cx.listener(move |this, _, _, cx| {
-                let key_clone = key.clone();
-                let active_server_id = this
-                    .keys_state
-                    .read(cx)
-                    .active_server_id()
-                    .map(str::to_string);
-                let value_generation = this.keys_state.update(cx, |state, cx| {
-                    state.select_key(Some(key_clone.clone()), cx);
-                    state.value_generation()
+            .on_click(cx.listener(move |this, _, window, cx| {
+                this.keys_state.update(cx, |state, cx| {
+                    state.select_key(Some(key.clone()), cx);
+                });
+                this.value_match_state.update(cx, |state, cx| {
+                    state.set_value("", window, cx);
                 });
+                this.fetch_value_page(cx);
+            }))
+    }
 
-                // Fetch the key value
-                let store = cx.global::<DfcGlobalStore>().clone();
-                let keys_state = this.keys_state.clone();
-                let key_for_fetch = key_clone.clone();
-
-                cx.spawn(async move |_, cx| {
-                    let redis = store.services().redis();
-                    match redis.get_key_value(&key_for_fetch).await {
-                        Ok(value) => {
-                            let _ = keys_state.update(cx, |state, cx| {
-                                if state.active_server_id() == active_server_id.as_deref()
-                                    && state.selected_key() == Some(key_for_fetch.as_str())
-                                    && state.value_generation() == value_generation
-                                {
-                                    state.set_selected_value(value, cx);
-                                } else {
-                                    tracing::info!(
-                                        server_id = ?active_server_id.as_deref(),
-                                        key = %key_for_fetch,
-                                        value_generation,
-                                        "Ignoring stale key value response after active selection changed"
-                                    );
-                                }
-                            });
-                        }
-                        Err(e) => {
-                            tracing::error!("Failed to get key value: {}", e);
-                            let _ = keys_state.update(cx, |state, cx| {
-                                if state.active_server_id() == active_server_id.as_deref()
-                                    && state.selected_key() == Some(key_for_fetch.as_str())
-                                    && state.value_generation() == value_generation
-                                {
-                                    state.set_selected_value(
-                                        RedisKeyValue::Error(e.to_string()),
-                                        cx,
-                                    );
-                                } else {
-                                    tracing::info!(
-                                        server_id = ?active_server_id.as_deref(),
-                                        key = %key_for_fetch,
-                                        value_generation,
-                                        error = %e,
-                                        "Ignoring stale key value error after active selection changed"
-                                    );
-                                }
-                            });
+    /// Fetch the first page of the selected key's value using the current MATCH pattern
+    fn fetch_value_page(&mut self, cx: &mut Context<Self>) {
+        let keys_state = self.keys_state.read(cx);
+        let Some(key) = keys_state.selected_key().map(str::to_string) else {
+            return;
+        };
+        let pattern = keys_state.value_match().to_string();
+        let active_server_id = keys_state.active_server_id().map(str::to_string);
+        let value_generation = keys_state.value_generation();
+
+        let store = cx.global::<DfcGlobalStore>().clone();
+        let keys_state = self.keys_state.clone();
+        cx.spawn(async move |_, cx| {
+            let redis = store.services().redis();
+            let result = redis.get_key_value_page(&key, &pattern).await;
+            let _ = keys_state.update(cx, |state, cx| {
+                if state.active_server_id() != active_server_id.as_deref()
+                    || state.selected_key() != Some(key.as_str())
+                    || state.value_generation() != value_generation
+                {
+                    tracing::info!(
+                        server_id = ?active_server_id.as_deref(),
+                        key = %key,
+                        value_generation,
+                        "Ignoring stale key value response after active selection changed"
+                    );
+                    return;
+                }
+                match result {
+                    Ok(page) => state.set_selected_value_page(page, cx),
+                    Err(e) => {
+                        tracing::error!("Failed to get key value: {}", e);
+                        state.set_selected_value(RedisKeyValue::Error(e.to_string()), cx);
+                    }
+                }
+            });
+        })
+        .detach();
+    }
+
+    /// Fetch the next page of the selected hash/list/set/zset
+    fn load_more_value_page(&mut self, cx: &mut Context<Self>) {
+        let keys_state = self.keys_state.read(cx);
+        let Some(key) = keys_state.selected_key().map(str::to_string) else {
+            return;
+        };
+        let Some(key_type) = keys_state.selected_value().key_type() else {
+            return;
+        };
+        let cursor = keys_state.value_cursor();
+        if keys_state.is_value_page_loading() || !cursor.has_more() {
+            return;
+        }
+        let pattern = keys_state.value_match().to_string();
+        let active_server_id = keys_state.active_server_id().map(str::to_string);
+        let value_generation = keys_state.value_generation();
+
+        self.keys_state.update(cx, |state, cx| {
+            state.set_value_page_loading(true, cx);
+        });
+
+        let store = cx.global::<DfcGlobalStore>().clone();
+        let keys_state = self.keys_state.clone();
+        cx.spawn(async move |_, cx| {
+            let redis = store.services().redis();
+            let result = redis
+                .get_value_page(&key, key_type, &pattern, Some(cursor))
+                .await;
+            let _ = keys_state.update(cx, |state, cx| {
+                if state.active_server_id() != active_server_id.as_deref()
+                    || state.selected_key() != Some(key.as_str())
+                    || state.value_generation() != value_generation
+                {
+                    tracing::info!(
+                        key = %key,
+                        value_generation,
+                        "Ignoring stale value page after selection changed"
+                    );
+                    return;
+                }
+                match result {
+                    Ok(page) => state.append_value_page(page, cx),
+                    Err(e) => {
+                        tracing::error!("Failed to load value page: {}", e);
+                        state.set_value_page_loading(false, cx);
+                    }
+                }
+            });
+        })
+        .detach();
+    }
+
+    /// TTL of the selected key as shown in the key list
+    fn selected_key_ttl(&self, cx: &App) -> i64 {
+        let keys_state = self.keys_state.read(cx);
+        keys_state
+            .selected_key()
+            .and_then(|key| keys_state.keys().iter().find(|item| item.key == key))
+            .map(|item| item.ttl)
+            .unwrap_or(-1)
+    }
+
+    /// Open the input dialog of a value edit; submitting leads to the write confirmation
+    fn open_edit_dialog(
+        &mut self,
+        edit: ValueEdit,
+        field_value: String,
+        value: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(key) = self.keys_state.read(cx).selected_key().map(str::to_string) else {
+            return;
+        };
+        self.edit_field_state.update(cx, |state, cx| {
+            state.set_value(field_value, window, cx);
+        });
+        self.edit_value_state.update(cx, |state, cx| {
+            state.set_value(value, window, cx);
+        });
+
+        let view = cx.entity();
+        let field_state = self.edit_field_state.clone();
+        let value_state = self.edit_value_state.clone();
+        let locale = self.locale(cx);
+        let title = t!(edit.title_key(), locale = &locale).to_string();
+        let field_label = edit
+            .field_label_key()
+            .map(|label| t!(label, locale = &locale).to_string());
+        let value_label = t!(edit.value_label_key(), locale = &locale).to_string();
+
+        let field_state_clone = field_state.clone();
+        let value_state_clone = value_state.clone();
+        let handle_submit = Rc::new(move |window: &mut Window, cx: &mut App| {
+            let field_value = field_state_clone.read(cx).value().to_string();
+            let value = value_state_clone.read(cx).value().to_string();
+            let Some(op) = edit.build_op(&key, &field_value, &value) else {
+                return false;
+            };
+            // Open the confirmation once this dialog has closed
+            let view = view.clone();
+            window.defer(cx, move |window, cx| {
+                view.update(cx, |this, cx| {
+                    this.confirm_write(op, window, cx);
+                });
+            });
+            true
+        });
+
+        window.open_dialog(cx, move |dialog, _, _| {
+            let handle = handle_submit.clone();
+            let mut form = v_form();
+            if let Some(label) = field_label.clone() {
+                form = form.child(field().label(label).child(Input::new(&field_state)));
+            }
+            let form = form.child(
+                field()
+                    .label(value_label.clone())
+                    .child(Input::new(&value_state)),
+            );
+
+            dialog
+                .confirm()
+                .title(title.clone())
+                .overlay(true)
+                .child(form)
+                .on_ok(move |_, window, cx| handle(window, cx))
+        });
+    }
+
+    /// Show the before/after diff of a write and apply it once confirmed
+    fn confirm_write(&mut self, op: RedisWriteOp, window: &mut Window, cx: &mut Context<Self>) {
+        let ttl = self.selected_key_ttl(cx);
+        let keys_state = self.keys_state.read(cx);
+        let preview = op.preview(keys_state.selected_value(), ttl);
+        // Elements outside the loaded page are unknown to the preview
+        let partial = keys_state.value_cursor().has_more() || !keys_state.value_match().is_empty();
+        let command_line = op.command_line();
+        let view = cx.entity();
+        let locale = self.locale(cx);
+        let title = t!("keys.confirm_write_title", locale = &locale).to_string();
+        let before_label = t!("keys.before", locale = &locale).to_string();
+        let after_label = t!("keys.after", locale = &locale).to_string();
+        let partial_note = t!("keys.preview_loaded_page", locale = &locale).to_string();
+
+        window.open_dialog(cx, move |dialog, _, cx| {
+            let diff_block = |label: &str, text: &str, color: gpui::Hsla| {
+                v_flex()
+                    .gap_1()
+                    .child(
+                        Label::new(label.to_string())
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground),
+                    )
+                    .child(
+                        div()
+                            .w_full()
+                            .p_2()
+                            .rounded_md()
+                            .border_1()
+                            .border_color(color)
+                            .bg(color.opacity(0.1))
+                            .child(Label::new(text.to_string()).text_sm()),
+                    )
+            };
+            let content = v_flex()
+                .gap_3()
+                .child(
+                    div()
+                        .w_full()
+                        .p_2()
+                        .rounded_md()
+                        .bg(cx.theme().secondary)
+                        .child(Label::new(command_line.clone()).text_sm()),
+                )
+                .child(diff_block(
+                    &before_label,
+                    &preview.before,
+                    cx.theme().danger,
+                ))
+                .child(diff_block(&after_label, &preview.after, cx.theme().success))
+                .when(partial, |this| {
+                    this.child(
+                        Label::new(partial_note.clone())
+                            .text_xs()
+                            .text_color(cx.theme().warning),
+                    )
+                });
+
+            let view = view.clone();
+            let op = op.clone();
+            let preview = preview.clone();
+            dialog
+                .confirm()
+                .title(title.clone())
+                .child(content)
+                .on_ok(move |_, window, cx| {
+                    let view = view.clone();
+                    let op = op.clone();
+                    let preview = preview.clone();
+                    // Ask for the server name once this dialog has closed
+                    window.defer(cx, move |window, cx| {
+                        view.update(cx, |this, cx| {
+                            this.authorize_write(op, preview, window, cx);
+                        });
+                    });
+                    true
+                })
+        });
+    }
+
+    /// Apply a confirmed write once the safety level of the server allows it
+    fn authorize_write(
+        &mut self,
+        op: RedisWriteOp,
+        preview: RedisWritePreview,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(server_id) = self.keys_state.read(cx).active_server_id() else {
+            return;
+        };
+        let Some(server) = cx
+            .global::<DfcGlobalStore>()
+            .read(cx)
+            .server(server_id)
+            .cloned()
+        else {
+            return;
+        };
+        let view = cx.entity();
+        guard_write(
+            &server,
+            op.command_line(),
+            window,
+            cx,
+            move |result, _, cx| {
+                view.update(cx, |this, cx| match result {
+                    Ok(permit) => this.apply_write(permit, op.clone(), preview.clone(), cx),
+                    Err(e) => this.keys_state.update(cx, |state, cx| {
+                        state.set_write_error(Some(e.to_string()), cx);
+                    }),
+                });
+            },
+        );
+    }
+
+    /// Execute a confirmed write, record it in the audit log and refresh the view
+    fn apply_write(
+        &mut self,
+        permit: WritePermit,
+        op: RedisWriteOp,
+        preview: RedisWritePreview,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(server) = self.keys_state.read(cx).active_server().cloned() else {
+            return;
+        };
+
+        let store = cx.global::<DfcGlobalStore>().clone();
+        cx.spawn(async move |this, cx| {
+            let redis = store.services().redis();
+            let result = redis.apply_write(&permit, &op).await;
+            AuditLog::record(&AuditEntry::new(
+                &server.server_id,
+                &server.server_name,
+                &op,
+                &preview,
+                result.as_ref().map(|_| ()).map_err(|e| e.to_string()),
+            ));
+
+            let _ = this.update(cx, |this, cx| {
+                if this.keys_state.read(cx).active_server_id() != Some(server.server_id.as_str()) {
+                    return;
+                }
+                match result {
+                    Ok(_) => this.after_write(&op, cx),
+                    Err(e) => {
+                        tracing::error!("Redis write failed: {}", e);
+                        this.keys_state.update(cx, |state, cx| {
+                            state.set_write_error(Some(e.to_string()), cx);
+                        });
+                    }
+                }
+            });
+        })
+        .detach();
+    }
+
+    /// Reflect a successful write in the key list and selected value
+    fn after_write(&mut self, op: &RedisWriteOp, cx: &mut Context<Self>) {
+        let selected = self.keys_state.read(cx).selected_key() == Some(op.key());
+        match op {
+            RedisWriteOp::Del { key } => {
+                self.keys_state.update(cx, |state, cx| {
+                    state.remove_key(key, cx);
+                });
+            }
+            RedisWriteOp::Expire { key, seconds } => {
+                self.keys_state.update(cx, |state, cx| {
+                    state.update_key_ttl(key, *seconds, cx);
+                    state.set_write_error(None, cx);
+                });
+            }
+            RedisWriteOp::Persist { key } => {
+                self.keys_state.update(cx, |state, cx| {
+                    state.update_key_ttl(key, -1, cx);
+                    state.set_write_error(None, cx);
+                });
+            }
+            RedisWriteOp::Rename { key, new_key } => {
+                self.keys_state.update(cx, |state, cx| {
+                    state.rename_key(key, new_key, cx);
+                    state.set_write_error(None, cx);
+                });
+            }
+            _ if selected => {
+                self.keys_state.update(cx, |state, cx| {
+                    let pattern = state.value_match().to_string();
+                    state.reload_value(pattern, cx);
+                });
+                self.fetch_value_page(cx);
+            }
+            _ => {}
+        }
+    }
+
+    /// Render edit/delete buttons at the end of a value row
+    fn render_row_actions(
+        &self,
+        ids: (&'static str, &'static str),
+        index: usize,
+        edit: Option<(ValueEdit, String, String)>,
+        delete: Option<RedisWriteOp>,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let locale = self.locale(cx);
+        let edit_tooltip = t!("keys.edit", locale = &locale).to_string();
+        let delete_tooltip = t!("keys.delete", locale = &locale).to_string();
+
+        h_flex()
+            .w(px(64.0))
+            .px_1()
+            .gap_1()
+            .justify_end()
+            .when_some(edit, |this, (edit, field_value, value)| {
+                this.child(
+                    Button::new((ids.0, index))
+                        .ghost()
+                        .xsmall()
+                        .tooltip(edit_tooltip)
+                        .icon(Icon::from(CustomIconName::FilePenLine))
+                        .on_click(cx.listener(move |this, _, window, cx| {
+                            this.open_edit_dialog(
+                                edit.clone(),
+                                field_value.clone(),
+                                value.clone(),
+                                window,
+                                cx,
+                            );
+                        })),
+                )
+            })
+            .when_some(delete, |this, op| {
+                this.child(
+                    Button::new((ids.1, index))
+                        .ghost()
+                        .xsmall()
+                        .tooltip(delete_tooltip)
+                        .icon(Icon::from(CustomIconName::FileXCorner))
+                        .on_click(cx.listener(move |this, _, window, cx| {
+                            this.confirm_write(op.clone(), window, cx);
+                        })),
+                )
+            })
+    }
+
+    /// Render TTL/rename/delete buttons for the selected key
+    fn render_key_actions(&self, item: &RedisKeyItem, cx: &mut Context<Self>) -> impl IntoElement {
+        let locale = self.locale(cx);
+        let key = item.key.clone();
+        let persist_op = RedisWriteOp::Persist { key: key.clone() };
+        let delete_op = RedisWriteOp::Del { key: key.clone() };
+        let ttl_value = if item.ttl > 0 {
+            item.ttl.to_string()
+        } else {
+            String::new()
+        };
+
+        h_flex()
+            .gap_1()
+            .child(
+                Button::new("key-set-ttl")
+                    .ghost()
+                    .xsmall()
+                    .label(t!("keys.set_ttl", locale = &locale).to_string())
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.open_edit_dialog(
+                            ValueEdit::Expire,
+                            String::new(),
+                            ttl_value.clone(),
+                            window,
+                            cx,
+                        );
+                    })),
+            )
+            .when(item.ttl >= 0, |this| {
+                this.child(
+                    Button::new("key-persist")
+                        .ghost()
+                        .xsmall()
+                        .label(t!("keys.persist", locale = &locale).to_string())
+                        .on_click(cx.listener(move |this, _, window, cx| {
+                            this.confirm_write(persist_op.clone(), window, cx);
+                        })),
+                )
+            })
+            .child(
+                Button::new("key-rename")
+                    .ghost()
+                    .xsmall()
+                    .label(t!("keys.rename", locale = &locale).to_string())
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.open_edit_dialog(
+                            ValueEdit::Rename,
+                            String::new(),
+                            key.clone(),
+                            window,
+                            cx,
+                        );
+                    })),
+            )
+            .child(
+                Button::new("key-delete")
+                    .ghost()
+                    .xsmall()
+                    .icon(Icon::from(CustomIconName::FileXCorner))
+                    .label(t!("keys.delete", locale = &locale).to_string())
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.confirm_write(delete_op.clone(), window, cx);
+                    })),
+            )
+    }
+
+    /// Render the MATCH filter and loaded/total counter above a collection value
+    fn render_value_paging_bar(
+        &self,
+        show_match: bool,
+        add: ValueEdit,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let locale = self.locale(cx);
+        let add_label = t!("keys.add", locale = &locale).to_string();
+        let keys_state = self.keys_state.read(cx);
+        let loaded = keys_state.selected_value().loaded_len();
+        let progress = keys_state.value_total().map(|total| {
+            t!(
+                "keys.loaded_of_total",
+                loaded = loaded,
+                total = total,
+                locale = &locale
+            )
+            .to_string()
+        });
+
+        h_flex()
+            .w_full()
+            .gap_2()
+            .items_center()
+            .when(show_match, |this| {
+                this.child(
+                    div()
+                        .flex_1()
+                        .child(Input::new(&self.value_match_state).small().cleanable(true)),
+                )
+            })
+            .when(!show_match, |this| this.child(div().flex_1()))
+            .when_some(progress, |this, progress| {
+                this.child(
+                    Label::new(progress)
+                        .text_sm()
+                        .text_color(cx.theme().muted_foreground),
+                )
+            })
+            .child(
+                Button::new("value-add")
+                    .small()
+                    .icon(Icon::from(CustomIconName::FilePlusCorner))
+                    .label(add_label)
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.open_edit_dialog(
+                            add.clone(),
+                            String::new(),
+                            String::new(),
+                            window,
+                            cx,
+                        );
+                    })),
+            )
+    }
+
+    /// Render the "load more" button below a collection value
+    fn render_value_load_more(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let locale = self.locale(cx);
+        let keys_state = self.keys_state.read(cx);
+        let has_more = keys_state.value_cursor().has_more();
+        let page_loading = keys_state.is_value_page_loading();
+
+        div().when(has_more, |this| {
+            let label = if page_loading {
+                t!("keys.loading", locale = &locale).to_string()
+            } else {
+                t!("keys.load_more", locale = &locale).to_string()
+            };
+            this.w_full().child(
+                Button::new("value-load-more")
+                    .ghost()
+                    .w_full()
+                    .label(SharedString::from(label))
+                    .disabled(page_loading)
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.load_more_value_page(cx);
+                    })),
+            )
+        })
+    }
+
+    /// Expand or collapse a namespace folder, scanning its prefix on first expansion
+    fn toggle_folder(&mut self, prefix: String, cx: &mut Context<Self>) {
+        let expanded = self
+            .keys_state
+            .update(cx, |state, cx| state.toggle_prefix(&prefix, cx));
+        let keys_state = self.keys_state.read(cx);
+        if expanded
+            && keys_state.prefix_has_more(&prefix)
+            && keys_state.prefix_cursor(&prefix).is_none()
+        {
+            self.load_prefix_keys(prefix, cx);
+        }
+    }
+
+    /// Load keys below a folder with `SCAN MATCH prefix*`
+    fn load_prefix_keys(&mut self, prefix: String, cx: &mut Context<Self>) {
+        let keys_state = self.keys_state.read(cx);
+        let cursor = keys_state.prefix_cursor(&prefix).unwrap_or(0);
+        let active_server_id = keys_state.active_server_id().map(str::to_string);
+        let list_generation = keys_state.list_generation();
+
+        let store = cx.global::<DfcGlobalStore>().clone();
+        let keys_state = self.keys_state.clone();
+        cx.spawn(async move |_, cx| {
+            let redis = store.services().redis();
+            match redis.scan_prefix(&prefix, cursor, 100).await {
+                Ok((keys, next_cursor)) => {
+                    let _ = keys_state.update(cx, |state, cx| {
+                        if state.active_server_id() == active_server_id.as_deref()
+                            && state.list_generation() == list_generation
+                        {
+                            state.merge_prefix_keys(&prefix, keys, next_cursor, cx);
+                        } else {
+                            tracing::info!(
+                                prefix = %prefix,
+                                list_generation,
+                                "Ignoring stale prefix scan response after key list changed"
+                            );
                         }
+                    });
+                }
+                Err(e) => {
+                    tracing::error!("Failed to scan keys under {}: {}", prefix, e);
+                }
+            }
+        })
+        .detach();
+    }
+
+    /// Render the namespace tree rows
+    fn render_tree_rows(
+        &self,
+        rows: Vec<KeyTreeRow>,
+        selected_key: Option<&str>,
+        cx: &mut Context<Self>,
+    ) -> Vec<gpui::AnyElement> {
+        let locale = self.locale(cx);
+        let mut elements = Vec::with_capacity(rows.len());
+        for (index, row) in rows.into_iter().enumerate() {
+            let element = match row {
+                KeyTreeRow::Folder {
+                    prefix,
+                    label,
+                    depth,
+                    count,
+                    expanded,
+                } => {
+                    let chevron = if expanded {
+                        IconName::ChevronDown
+                    } else {
+                        IconName::ChevronRight
+                    };
+                    div()
+                        .id(("key-folder", index))
+                        .w_full()
+                        .pl(px(8.0 + depth as f32 * TREE_INDENT))
+                        .pr_2()
+                        .py_1()
+                        .cursor_pointer()
+                        .hover(|this| this.bg(cx.theme().accent.opacity(0.5)))
+                        .child(
+                            h_flex()
+                                .w_full()
+                                .gap_1()
+                                .items_center()
+                                .child(
+                                    Icon::new(chevron)
+                                        .size_3()
+                                        .text_color(cx.theme().muted_foreground),
+                                )
+                                .child(Label::new(label).text_sm().text_ellipsis().flex_1())
+                                .child(
+                                    Label::new(count.to_string())
+                                        .text_xs()
+                                        .text_color(cx.theme().muted_foreground),
+                                ),
+                        )
+                        .on_click(cx.listener(move |this, _, _, cx| {
+                            this.toggle_folder(prefix.clone(), cx);
+                        }))
+                        .into_any_element()
+                }
+                KeyTreeRow::Key { item, label, depth } => {
+                    let is_selected = selected_key == Some(item.key.as_str());
+                    self.render_key_item(index, &item, &label, depth, is_selected, cx)
+                        .into_any_element()
+                }
+                KeyTreeRow::LoadMore { prefix, depth } => div()
+                    .w_full()
+                    .pl(px(8.0 + depth as f32 * TREE_INDENT))
+                    .child(
+                        Button::new(("key-folder-more", index))
+                            .ghost()
+                            .xsmall()
+                            .label(t!("keys.load_more", locale = &locale).to_string())
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.load_prefix_keys(prefix.clone(), cx);
+                            })),
+                    )
+                    .into_any_element(),
+            };
+            elements.push(element);
+        }
+        elements
+    }
+
+    /// Render the flat/tree mode toggle of the keys list
+    fn render_view_mode_toggle(
+        &self,
+        view_mode: KeyViewMode,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let locale = self.locale(cx);
+        let mode_button = |id: &'static str, label: String, mode: KeyViewMode| {
+            Button::new(id)
+                .ghost()
+                .xsmall()
+                .label(label)
+                .selected(view_mode == mode)
+                .on_click(move |_, _, cx| {
+                    if view_mode == mode {
+                        return;
                     }
+                    update_app_state_and_save(cx, "set_key_view_mode", move |state, _| {
+                        state.set_key_view_mode(mode);
+                    });
                 })
-                .detach();
-            }))
+        };
+
+        h_flex()
+            .gap_1()
+            .child(mode_button(
+                "keys-view-flat",
+                t!("keys.view_flat", locale = &locale).to_string(),
+                KeyViewMode::Flat,
+            ))
+            .child(mode_button(
+                "keys-view-tree",
+                t!("keys.view_tree", locale = &locale).to_string(),
+                KeyViewMode::Tree,
+            ))
     }
 
     /// Render the keys list panel
     fn render_keys_list(&self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let locale = self.locale(cx);
+        let app_state = cx.global::<DfcGlobalStore>().read(cx);
+        let view_mode = app_state.key_view_mode();
+        let delimiters = app_state.key_tree_delimiters().to_string();
         let keys_state = self.keys_state.read(cx);
         let selected_key = keys_state.selected_key().map(|s| s.to_string());
         let is_loading = keys_state.is_loading();
         let has_more = keys_state.has_more_keys();
 
         // Collect key data before mutable borrow
-        let key_data: Vec<_> = keys_state
-            .filtered_keys()
+        let filtered_keys = keys_state.filtered_keys();
+        let keys_count = filtered_keys.len();
+        let tree_rows = (view_mode == KeyViewMode::Tree).then(|| {
+            KeyTree::build(filtered_keys.iter().copied(), &delimiters)
+                .rows(keys_state.expanded_prefixes(), &|prefix| {
+                    keys_state.prefix_has_more(prefix)
+                })
+        });
+        let key_data: Vec<_> = filtered_keys
             .iter()
             .enumerate()
             .map(|(index, key_item)| {
@@ -227,18 +1223,22 @@ impl KeysBrowserView {
             })
             .collect();
 
-        let keys_count = key_data.len();
-
         let search_btn = Button::new("keys-search-btn")
             .ghost()
             .small()
             .icon(IconName::Search);
 
         // Build key items
-        let mut key_items = Vec::new();
-        for (index, key_item, is_selected) in key_data {
-            key_items.push(self.render_key_item(index, &key_item, is_selected, cx));
-        }
+        let key_items = match tree_rows {
+            Some(rows) => self.render_tree_rows(rows, selected_key.as_deref(), cx),
+            None => key_data
+                .into_iter()
+                .map(|(index, key_item, is_selected)| {
+                    self.render_key_item(index, &key_item, &key_item.key, 0, is_selected, cx)
+                        .into_any_element()
+                })
+                .collect(),
+        };
         let count_label = t!("keys.items", count = keys_count, locale = &locale).to_string();
 
         v_flex()
@@ -270,9 +1270,15 @@ impl KeysBrowserView {
                     .border_b_1()
                     .border_color(cx.theme().border)
                     .child(
-                        Label::new(count_label)
-                            .text_xs()
-                            .text_color(cx.theme().muted_foreground),
+                        h_flex()
+                            .justify_between()
+                            .items_center()
+                            .child(
+                                Label::new(count_label)
+                                    .text_xs()
+                                    .text_color(cx.theme().muted_foreground),
+                            )
+                            .child(self.render_view_mode_toggle(view_mode, cx)),
                     ),
             )
             // Keys list
@@ -360,25 +1366,46 @@ impl KeysBrowserView {
     }
 
     /// Render string value
-    fn render_string_value(&self, value: &str, cx: &mut Context<Self>) -> impl IntoElement {
+    ///
+    /// Binary values are shown but cannot be edited as text.
+    fn render_string_value(&self, value: Option<&str>, cx: &mut Context<Self>) -> impl IntoElement {
         let locale = self.locale(cx);
         let value_label = t!("keys.value", locale = &locale).to_string();
+        let edit_label = t!("keys.edit", locale = &locale).to_string();
+        let current = value.map(str::to_string);
 
         v_flex()
             .gap_2()
             .child(
-                Label::new(value_label)
-                    .text_sm()
-                    .text_color(cx.theme().muted_foreground),
-            )
-            .child(
-                div()
-                    .w_full()
-                    .p_2()
-                    .rounded_md()
-                    .bg(cx.theme().secondary)
-                    .child(Label::new(value.to_string()).text_sm()),
+                h_flex()
+                    .justify_between()
+                    .items_center()
+                    .child(
+                        Label::new(value_label)
+                            .text_sm()
+                            .text_color(cx.theme().muted_foreground),
+                    )
+                    .child(
+                        Button::new("string-edit")
+                            .small()
+                            .icon(Icon::from(CustomIconName::FilePenLine))
+                            .label(edit_label)
+                            .disabled(current.is_none())
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                let Some(current) = current.clone() else {
+                                    return;
+                                };
+                                this.open_edit_dialog(
+                                    ValueEdit::String,
+                                    String::new(),
+                                    current,
+                                    window,
+                                    cx,
+                                );
+                            })),
+                    ),
             )
+            .child(self.value_viewer.clone())
     }
 
     /// Render hash value
@@ -392,6 +1419,12 @@ impl KeysBrowserView {
         let value_label = t!("keys.value", locale = &locale).to_string();
         let count_label = t!("keys.hash_fields", count = pairs.len(), locale = &locale).to_string();
 
+        let key = self
+            .keys_state
+            .read(cx)
+            .selected_key()
+            .unwrap_or_default()
+            .to_string();
         let header_bg = cx.theme().secondary;
         let border_color = cx.theme().border;
 
@@ -429,7 +1462,17 @@ impl KeysBrowserView {
                             .px_2()
                             .py_1()
                             .child(Label::new(value.clone()).text_sm().text_ellipsis()),
-                    ),
+                    )
+                    .child(self.render_row_actions(
+                        ("hash-edit", "hash-delete"),
+                        index,
+                        Some((ValueEdit::HashField, field.clone(), value.clone())),
+                        Some(RedisWriteOp::HDel {
+                            key: key.clone(),
+                            field: field.clone(),
+                        }),
+                        cx,
+                    )),
             );
         }
 
@@ -473,7 +1516,8 @@ impl KeysBrowserView {
                                         .text_sm()
                                         .text_color(cx.theme().muted_foreground),
                                 ),
-                            ),
+                            )
+                            .child(div().w(px(64.0))),
                     )
                     // Table body
                     .child(
@@ -494,6 +1538,12 @@ impl KeysBrowserView {
         let count_label =
             t!("keys.list_elements", count = items.len(), locale = &locale).to_string();
 
+        let key = self
+            .keys_state
+            .read(cx)
+            .selected_key()
+            .unwrap_or_default()
+            .to_string();
         let header_bg = cx.theme().secondary;
         let border_color = cx.theme().border;
 
@@ -535,7 +1585,22 @@ impl KeysBrowserView {
                             .px_2()
                             .py_1()
                             .child(Label::new(value.clone()).text_sm().text_ellipsis()),
-                    ),
+                    )
+                    .child(self.render_row_actions(
+                        ("list-edit", "list-delete"),
+                        index,
+                        Some((
+                            ValueEdit::ListSet(index as i64),
+                            String::new(),
+                            value.clone(),
+                        )),
+                        Some(RedisWriteOp::list_delete(
+                            key.clone(),
+                            index as i64,
+                            value.clone(),
+                        )),
+                        cx,
+                    )),
             );
         }
 
@@ -579,7 +1644,8 @@ impl KeysBrowserView {
                                         .text_sm()
                                         .text_color(cx.theme().muted_foreground),
                                 ),
-                            ),
+                            )
+                            .child(div().w(px(64.0))),
                     )
                     // Table body
                     .child(
@@ -599,6 +1665,12 @@ impl KeysBrowserView {
         let count_label =
             t!("keys.set_members", count = members.len(), locale = &locale).to_string();
 
+        let key = self
+            .keys_state
+            .read(cx)
+            .selected_key()
+            .unwrap_or_default()
+            .to_string();
         let header_bg = cx.theme().secondary;
         let border_color = cx.theme().border;
 
@@ -616,14 +1688,28 @@ impl KeysBrowserView {
             };
 
             rows.push(
-                div()
+                h_flex()
                     .w_full()
                     .bg(bg)
-                    .px_2()
-                    .py_1()
                     .border_b_1()
                     .border_color(border_color)
-                    .child(Label::new(member.clone()).text_sm().text_ellipsis()),
+                    .child(
+                        div()
+                            .flex_1()
+                            .px_2()
+                            .py_1()
+                            .child(Label::new(member.clone()).text_sm().text_ellipsis()),
+                    )
+                    .child(self.render_row_actions(
+                        ("set-edit", "set-delete"),
+                        index,
+                        None,
+                        Some(RedisWriteOp::SRem {
+                            key: key.clone(),
+                            member: member.clone(),
+                        }),
+                        cx,
+                    )),
             );
         }
 
@@ -679,6 +1765,12 @@ impl KeysBrowserView {
         let count_label =
             t!("keys.zset_members", count = members.len(), locale = &locale).to_string();
 
+        let key = self
+            .keys_state
+            .read(cx)
+            .selected_key()
+            .unwrap_or_default()
+            .to_string();
         let header_bg = cx.theme().secondary;
         let border_color = cx.theme().border;
 
@@ -720,7 +1812,17 @@ impl KeysBrowserView {
                             .px_2()
                             .py_1()
                             .child(Label::new(member.clone()).text_sm().text_ellipsis()),
-                    ),
+                    )
+                    .child(self.render_row_actions(
+                        ("zset-edit", "zset-delete"),
+                        index,
+                        Some((ValueEdit::ZSetMember, member.clone(), score.to_string())),
+                        Some(RedisWriteOp::ZRem {
+                            key: key.clone(),
+                            member: member.clone(),
+                        }),
+                        cx,
+                    )),
             );
         }
 
@@ -764,7 +1866,8 @@ impl KeysBrowserView {
                                         .text_sm()
                                         .text_color(cx.theme().muted_foreground),
                                 ),
-                            ),
+                            )
+                            .child(div().w(px(64.0))),
                     )
                     // Table body
                     .child(
@@ -777,12 +1880,344 @@ impl KeysBrowserView {
             )
     }
 
+    /// Fetch the next page of the selected stream
+    fn load_more_stream_entries(&mut self, cx: &mut Context<Self>) {
+        let keys_state = self.keys_state.read(cx);
+        let Some(key) = keys_state.selected_key().map(str::to_string) else {
+            return;
+        };
+        let RedisKeyValue::Stream(stream) = keys_state.selected_value() else {
+            return;
+        };
+        if keys_state.is_value_page_loading() || !stream.has_more {
+            return;
+        }
+        let after = stream.last_entry_id().map(str::to_string);
+        let reverse = stream.reverse;
+        let active_server_id = keys_state.active_server_id().map(str::to_string);
+        let value_generation = keys_state.value_generation();
+
+        self.keys_state.update(cx, |state, cx| {
+            state.set_value_page_loading(true, cx);
+        });
+
+        let store = cx.global::<DfcGlobalStore>().clone();
+        let keys_state = self.keys_state.clone();
+        cx.spawn(async move |_, cx| {
+            let redis = store.services().redis();
+            let result = redis
+                .get_stream_range(&key, after.as_deref(), RedisRepo::STREAM_PAGE_SIZE, reverse)
+                .await;
+            let _ = keys_state.update(cx, |state, cx| {
+                if state.active_server_id() != active_server_id.as_deref()
+                    || state.selected_key() != Some(key.as_str())
+                    || state.value_generation() != value_generation
+                {
+                    tracing::info!(
+                        key = %key,
+                        value_generation,
+                        "Ignoring stale stream page after selection changed"
+                    );
+                    return;
+                }
+                match result {
+                    Ok(entries) => {
+                        state.append_stream_entries(entries, RedisRepo::STREAM_PAGE_SIZE, cx)
+                    }
+                    Err(e) => {
+                        tracing::error!("Failed to load stream entries: {}", e);
+                        state.set_value_page_loading(false, cx);
+                    }
+                }
+            });
+        })
+        .detach();
+    }
+
+    /// Reload the selected stream in the given paging direction
+    fn reload_stream(&mut self, reverse: bool, cx: &mut Context<Self>) {
+        let Some(key) = self.keys_state.read(cx).selected_key().map(str::to_string) else {
+            return;
+        };
+        let active_server_id = self
+            .keys_state
+            .read(cx)
+            .active_server_id()
+            .map(str::to_string);
+        let value_generation = self.keys_state.update(cx, |state, cx| {
+            state.select_key(Some(key.clone()), cx);
+            state.value_generation()
+        });
+
+        let store = cx.global::<DfcGlobalStore>().clone();
+        let keys_state = self.keys_state.clone();
+        cx.spawn(async move |_, cx| {
+            let redis = store.services().redis();
+            let value = match redis.get_stream(&key, reverse).await {
+                Ok(stream) => RedisKeyValue::Stream(stream),
+                Err(e) => {
+                    tracing::error!("Failed to load stream: {}", e);
+                    RedisKeyValue::Error(e.to_string())
+                }
+            };
+            let _ = keys_state.update(cx, |state, cx| {
+                if state.active_server_id() == active_server_id.as_deref()
+                    && state.selected_key() == Some(key.as_str())
+                    && state.value_generation() == value_generation
+                {
+                    state.set_selected_value(value, cx);
+                }
+            });
+        })
+        .detach();
+    }
+
+    /// Render stream value: summary, entries, consumer groups and pending lists
+    fn render_stream_value(
+        &self,
+        stream: &RedisStreamValue,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let locale = self.locale(cx);
+        let muted_fg = cx.theme().muted_foreground;
+        let page_loading = self.keys_state.read(cx).is_value_page_loading();
+        let reverse = stream.reverse;
+
+        let summary = [
+            (
+                t!("keys.stream_length", locale = &locale).to_string(),
+                stream.info.length.to_string(),
+            ),
+            (
+                t!("keys.stream_groups", locale = &locale).to_string(),
+                stream.info.groups.to_string(),
+            ),
+            (
+                t!("keys.stream_last_generated_id", locale = &locale).to_string(),
+                stream.info.last_generated_id.clone(),
+            ),
+            (
+                t!("keys.stream_first_entry", locale = &locale).to_string(),
+                stream
+                    .info
+                    .first_entry_id
+                    .clone()
+                    .unwrap_or_else(|| "-".into()),
+            ),
+            (
+                t!("keys.stream_last_entry", locale = &locale).to_string(),
+                stream
+                    .info
+                    .last_entry_id
+                    .clone()
+                    .unwrap_or_else(|| "-".into()),
+            ),
+        ];
+
+        let direction_toggle = h_flex()
+            .gap_1()
+            .child(
+                Button::new("stream-newest-first")
+                    .ghost()
+                    .small()
+                    .selected(reverse)
+                    .label(t!("keys.stream_newest_first", locale = &locale).to_string())
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        if !reverse {
+                            this.reload_stream(true, cx);
+                        }
+                    })),
+            )
+            .child(
+                Button::new("stream-oldest-first")
+                    .ghost()
+                    .small()
+                    .selected(!reverse)
+                    .label(t!("keys.stream_oldest_first", locale = &locale).to_string())
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        if reverse {
+                            this.reload_stream(false, cx);
+                        }
+                    })),
+            );
+
+        let entry_rows = stream
+            .entries
+            .iter()
+            .map(|entry| {
+                let fields = entry
+                    .fields
+                    .iter()
+                    .map(|(field, value)| format!("{field}={value}"))
+                    .collect::<Vec<_>>()
+                    .join("  ");
+                vec![entry.id.clone(), fields]
+            })
+            .collect();
+        let entry_columns = [
+            (
+                t!("keys.stream_entry_id", locale = &locale).to_string(),
+                Some(200.0),
+            ),
+            (t!("keys.stream_fields", locale = &locale).to_string(), None),
+        ];
+        let entries_table =
+            render_simple_table("stream-entries-scroll", &entry_columns, entry_rows, cx);
+
+        let group_rows = stream
+            .groups
+            .iter()
+            .map(|group| {
+                vec![
+                    group.name.clone(),
+                    group.consumers.to_string(),
+                    group.pending.to_string(),
+                    group.last_delivered_id.clone(),
+                    group
+                        .lag
+                        .map_or_else(|| "-".to_string(), |lag| lag.to_string()),
+                ]
+            })
+            .collect();
+        let group_columns = [
+            (
+                t!("keys.stream_group_name", locale = &locale).to_string(),
+                None,
+            ),
+            (
+                t!("keys.stream_consumers", locale = &locale).to_string(),
+                Some(90.0),
+            ),
+            (
+                t!("keys.stream_pending", locale = &locale).to_string(),
+                Some(90.0),
+            ),
+            (
+                t!("keys.stream_last_delivered_id", locale = &locale).to_string(),
+                Some(200.0),
+            ),
+            (
+                t!("keys.stream_lag", locale = &locale).to_string(),
+                Some(70.0),
+            ),
+        ];
+        let groups_table =
+            render_simple_table("stream-groups-scroll", &group_columns, group_rows, cx);
+
+        let pending_columns = [
+            (
+                t!("keys.stream_entry_id", locale = &locale).to_string(),
+                Some(200.0),
+            ),
+            (
+                t!("keys.stream_consumer", locale = &locale).to_string(),
+                None,
+            ),
+            (
+                t!("keys.stream_idle_ms", locale = &locale).to_string(),
+                Some(110.0),
+            ),
+            (
+                t!("keys.stream_deliveries", locale = &locale).to_string(),
+                Some(90.0),
+            ),
+        ];
+        let pending_tables: Vec<_> = stream
+            .pending
+            .iter()
+            .filter(|(_, entries)| !entries.is_empty())
+            .enumerate()
+            .map(|(group_ix, (group, entries))| {
+                let title =
+                    t!("keys.stream_pending_of", group = group, locale = &locale).to_string();
+                let rows = entries
+                    .iter()
+                    .map(|entry| {
+                        vec![
+                            entry.id.clone(),
+                            entry.consumer.clone(),
+                            entry.idle_ms.to_string(),
+                            entry.delivery_count.to_string(),
+                        ]
+                    })
+                    .collect();
+                v_flex()
+                    .gap_1()
+                    .child(Label::new(title).text_sm().text_color(muted_fg))
+                    .child(render_simple_table(
+                        ("stream-pending-scroll", group_ix),
+                        &pending_columns,
+                        rows,
+                        cx,
+                    ))
+            })
+            .collect();
+
+        let entries_label = t!(
+            "keys.stream_entries",
+            count = stream.entries.len(),
+            locale = &locale
+        )
+        .to_string();
+        let groups_label = t!(
+            "keys.stream_consumer_groups",
+            count = stream.groups.len(),
+            locale = &locale
+        )
+        .to_string();
+
+        v_flex()
+            .gap_3()
+            .child(
+                h_flex()
+                    .gap_4()
+                    .flex_wrap()
+                    .children(summary.into_iter().map(|(label, value)| {
+                        h_flex()
+                            .gap_1()
+                            .child(Label::new(label).text_xs().text_color(muted_fg))
+                            .child(Label::new(value).text_xs())
+                    })),
+            )
+            .child(
+                h_flex()
+                    .justify_between()
+                    .items_center()
+                    .child(Label::new(entries_label).text_sm().text_color(muted_fg))
+                    .child(direction_toggle),
+            )
+            .child(entries_table)
+            .when(stream.has_more, |this| {
+                let label = if page_loading {
+                    t!("keys.loading", locale = &locale).to_string()
+                } else {
+                    t!("keys.load_more", locale = &locale).to_string()
+                };
+                this.child(
+                    Button::new("stream-load-more")
+                        .ghost()
+                        .w_full()
+                        .label(SharedString::from(label))
+                        .disabled(page_loading)
+                        .on_click(cx.listener(|this, _, _, cx| {
+                            this.load_more_stream_entries(cx);
+                        })),
+                )
+            })
+            .child(Label::new(groups_label).text_sm().text_color(muted_fg))
+            .when(!stream.groups.is_empty(), |this| this.child(groups_table))
+            .children(pending_tables)
+    }
+
     /// Render the value panel
     fn render_value_panel(&self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let locale = self.locale(cx);
         let keys_state = self.keys_state.read(cx);
         let selected_key = keys_state.selected_key().map(|s| s.to_string());
         let selected_value = keys_state.selected_value().clone();
+        let write_error = keys_state
+            .write_error()
+            .map(|error| t!("keys.write_failed", error = error, locale = &locale).to_string());
 
         // Find the key item for type and TTL info
         let key_item = selected_key
@@ -847,19 +2282,37 @@ impl KeysBrowserView {
                     .into_any_element()
             }
             (Some(_), RedisKeyValue::String(value)) => {
-                self.render_string_value(value, cx).into_any_element()
+                self.render_string_value(Some(value), cx).into_any_element()
             }
-            (Some(_), RedisKeyValue::Hash(pairs)) => {
-                self.render_hash_value(pairs, cx).into_any_element()
+            (Some(_), RedisKeyValue::Bytes(_)) => {
+                self.render_string_value(None, cx).into_any_element()
             }
-            (Some(_), RedisKeyValue::List(items)) => {
-                self.render_list_value(items, cx).into_any_element()
-            }
-            (Some(_), RedisKeyValue::Set(members)) => {
-                self.render_set_value(members, cx).into_any_element()
-            }
-            (Some(_), RedisKeyValue::ZSet(members)) => {
-                self.render_zset_value(members, cx).into_any_element()
+            (Some(_), RedisKeyValue::Hash(pairs)) => v_flex()
+                .gap_2()
+                .child(self.render_value_paging_bar(true, ValueEdit::HashField, cx))
+                .child(self.render_hash_value(pairs, cx))
+                .child(self.render_value_load_more(cx))
+                .into_any_element(),
+            (Some(_), RedisKeyValue::List(items)) => v_flex()
+                .gap_2()
+                .child(self.render_value_paging_bar(false, ValueEdit::ListPush, cx))
+                .child(self.render_list_value(items, cx))
+                .child(self.render_value_load_more(cx))
+                .into_any_element(),
+            (Some(_), RedisKeyValue::Set(members)) => v_flex()
+                .gap_2()
+                .child(self.render_value_paging_bar(true, ValueEdit::SetAdd, cx))
+                .child(self.render_set_value(members, cx))
+                .child(self.render_value_load_more(cx))
+                .into_any_element(),
+            (Some(_), RedisKeyValue::ZSet(members)) => v_flex()
+                .gap_2()
+                .child(self.render_value_paging_bar(true, ValueEdit::ZSetMember, cx))
+                .child(self.render_zset_value(members, cx))
+                .child(self.render_value_load_more(cx))
+                .into_any_element(),
+            (Some(_), RedisKeyValue::Stream(stream)) => {
+                self.render_stream_value(stream, cx).into_any_element()
             }
             (Some(_), RedisKeyValue::Empty) => {
                 let select_key_label = t!("keys.select_key", locale = &locale).to_string();
@@ -899,9 +2352,19 @@ impl KeysBrowserView {
                         .gap_1()
                         .child(
                             h_flex()
-                                .gap_2()
-                                .child(Label::new(key_label.clone()).text_sm().text_color(muted_fg))
-                                .child(Label::new(item.key.clone()).text_sm()),
+                                .justify_between()
+                                .items_center()
+                                .child(
+                                    h_flex()
+                                        .gap_2()
+                                        .child(
+                                            Label::new(key_label.clone())
+                                                .text_sm()
+                                                .text_color(muted_fg),
+                                        )
+                                        .child(Label::new(item.key.clone()).text_sm()),
+                                )
+                                .child(self.render_key_actions(&item, cx)),
                         )
                         .child(
                             h_flex()
@@ -938,6 +2401,17 @@ impl KeysBrowserView {
             .bg(bg_color)
             // Key info header
             .when_some(header, |this, h| this.child(h))
+            // Last failed write
+            .when_some(write_error, |this, error| {
+                this.child(
+                    div()
+                        .w_full()
+                        .px_4()
+                        .py_2()
+                        .bg(cx.theme().danger.opacity(0.1))
+                        .child(Label::new(error).text_sm().text_color(cx.theme().danger)),
+                )
+            })
             // Value content
             .child(
                 div()
@@ -951,6 +2425,15 @@ impl KeysBrowserView {
 }
 
 impl KeysBrowserView {
+    /// Switch the right panel; the dashboard only refreshes while shown
+    fn set_right_panel(&mut self, panel: RightPanel, cx: &mut Context<Self>) {
+        self.right_panel = panel;
+        self.dashboard_view.update(cx, |view, cx| {
+            view.set_active(panel == RightPanel::Dashboard, cx);
+        });
+        cx.notify();
+    }
+
     /// Render the header with back button and server info
     fn render_header(&self, cx: &mut Context<Self>) -> impl IntoElement {
         let locale = self.locale(cx);
@@ -975,6 +2458,18 @@ impl KeysBrowserView {
                 });
             }));
 
+        let right_panel = self.right_panel;
+        let panel_button = |id: &'static str, label: String, panel: RightPanel| {
+            Button::new(id)
+                .ghost()
+                .small()
+                .label(label)
+                .selected(right_panel == panel)
+                .on_click(cx.listener(move |this, _, _, cx| {
+                    this.set_right_panel(panel, cx);
+                }))
+        };
+
         h_flex()
             .w_full()
             .p_2()
@@ -986,18 +2481,121 @@ impl KeysBrowserView {
             .child(
                 Label::new(server_name)
                     .text_lg()
-                    .text_color(cx.theme().foreground),
+                    .text_color(cx.theme().foreground)
+                    .flex_1(),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(panel_button(
+                        "keys-panel-value",
+                        t!("keys.value", locale = &locale).to_string(),
+                        RightPanel::Value,
+                    ))
+                    .child(panel_button(
+                        "keys-panel-console",
+                        t!("console.title", locale = &locale).to_string(),
+                        RightPanel::Console,
+                    ))
+                    .child(panel_button(
+                        "keys-panel-search",
+                        t!("value_search.title", locale = &locale).to_string(),
+                        RightPanel::Search,
+                    ))
+                    .child(panel_button(
+                        "keys-panel-analyzer",
+                        t!("key_analysis.title", locale = &locale).to_string(),
+                        RightPanel::Analyzer,
+                    ))
+                    .child(panel_button(
+                        "keys-panel-compare",
+                        t!("key_compare.title", locale = &locale).to_string(),
+                        RightPanel::Compare,
+                    ))
+                    .child(panel_button(
+                        "keys-panel-dashboard",
+                        t!("dashboard.title", locale = &locale).to_string(),
+                        RightPanel::Dashboard,
+                    )),
             )
     }
 }
 
 impl Render for KeysBrowserView {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let right_panel = match self.right_panel {
+            RightPanel::Value => self.render_value_panel(window, cx).into_any_element(),
+            RightPanel::Console => div()
+                .flex_1()
+                .h_full()
+                .child(self.console_view.clone())
+                .into_any_element(),
+            RightPanel::Dashboard => div()
+                .flex_1()
+                .h_full()
+                .child(self.dashboard_view.clone())
+                .into_any_element(),
+            RightPanel::Search => div()
+                .flex_1()
+                .h_full()
+                .child(self.value_search_view.clone())
+                .into_any_element(),
+            RightPanel::Analyzer => div()
+                .flex_1()
+                .h_full()
+                .child(self.key_analyzer_view.clone())
+                .into_any_element(),
+            RightPanel::Compare => div()
+                .flex_1()
+                .h_full()
+                .child(self.key_compare_view.clone())
+                .into_any_element(),
+        };
+
         v_flex().size_full().child(self.render_header(cx)).child(
             h_flex()
                 .flex_1()
                 .child(self.render_keys_list(window, cx))
-                .child(self.render_value_panel(window, cx)),
+                .child(right_panel),
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn value_edit_rejects_invalid_inputs() {
+        assert_eq!(ValueEdit::HashField.build_op("k", "", "v"), None);
+        assert_eq!(ValueEdit::ZSetMember.build_op("k", "m", "abc"), None);
+        assert_eq!(ValueEdit::Expire.build_op("k", "", "0"), None);
+        assert_eq!(ValueEdit::Rename.build_op("k", "", " k "), None);
+    }
+
+    #[test]
+    fn value_edit_builds_write_ops() {
+        assert_eq!(
+            ValueEdit::ZSetMember.build_op("k", "m", " 2.5 "),
+            Some(RedisWriteOp::ZAdd {
+                key: "k".to_string(),
+                member: "m".to_string(),
+                score: 2.5,
+            })
+        );
+        assert_eq!(
+            ValueEdit::Expire.build_op("k", "", "60\n"),
+            Some(RedisWriteOp::Expire {
+                key: "k".to_string(),
+                seconds: 60,
+            })
+        );
+        assert_eq!(
+            ValueEdit::Rename.build_op("k", "", "k2"),
+            Some(RedisWriteOp::Rename {
+                key: "k".to_string(),
+                new_key: "k2".to_string(),
+            })
+        );
+    }
+}
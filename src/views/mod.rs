@@ -21,18 +21,37 @@
 //! ```
 
 mod about_dialog;
+mod config_history;
 mod config_view;
+mod console;
 mod content;
+mod key_analyzer;
+mod key_compare;
 mod keys_browser;
+mod prop_chart;
+mod pulsar_security_form;
+mod server_dashboard;
+mod server_diagnostics;
+mod server_transfer;
 mod service_panel;
 mod sidebar;
 mod title_bar;
 mod update_dialog;
+mod value_search;
+mod value_viewer;
+mod vault_settings;
+mod write_guard;
 
 pub use about_dialog::*;
 pub use config_view::*;
+pub use console::*;
 pub use content::*;
+pub use key_analyzer::*;
+pub use key_compare::*;
 pub use keys_browser::*;
+pub use server_dashboard::*;
 pub use sidebar::*;
 pub use title_bar::*;
 pub use update_dialog::*;
+pub use value_search::*;
+pub use value_viewer::*;
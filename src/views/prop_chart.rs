@@ -0,0 +1,688 @@
+//! Prop Chart View
+//!
+//! Trend of the prop points picked in the prop table:
+//! - Values over time from the point history (live stream plus replay backfill)
+//! - Several points overlaid, e.g. one IMR across devices or several IMRs of a device
+//! - Wheel zooms around the cursor, dragging pans, Fit shows all samples again
+//! - Dense series draw a min/max band around the average line
+//! - Samples with a quality other than good are marked
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use crate::states::{
+    ChartBucket, ChartSample, DfcGlobalStore, PROP_CHART_MAX_SAMPLES, PROP_CHART_MAX_SERIES,
+    PropChartState, PropRow, PropTableState, TimeWindow, bucket_samples, format_chart_time,
+    samples_in_window, series_stats,
+};
+use gpui::{
+    App, Bounds, ContentMask, Context, Corners, EventEmitter, Hsla, MouseButton, MouseDownEvent,
+    MouseMoveEvent, MouseUpEvent, PathBuilder, Pixels, ScrollWheelEvent, Window, canvas, div, fill,
+    point, prelude::*, px, size,
+};
+use gpui_component::{
+    ActiveTheme, Disableable, IconName, Sizable,
+    button::{Button, ButtonVariants},
+    h_flex,
+    label::Label,
+    v_flex,
+};
+use rust_i18n::t;
+
+/// Plot width assumed before the first paint
+const DEFAULT_PLOT_WIDTH: f32 = 600.0;
+/// Width of one aggregated column of a dense series
+const BUCKET_WIDTH: f32 = 2.0;
+/// Raw samples are marked only when at least this far apart on average
+const MARKER_SPACING: f32 = 8.0;
+/// Zoom step of one wheel notch or button press
+const ZOOM_STEP: f64 = 0.8;
+
+/// Events emitted by the prop chart view
+#[derive(Debug, Clone)]
+pub enum PropChartEvent {
+    /// Chart the IMR of this point on every device
+    CompareDevices(PropRow),
+}
+
+/// What is painted for one series
+enum PlotData {
+    /// Samples few enough to draw one by one
+    Raw(Vec<ChartSample>),
+    /// Columns of a dense series
+    Buckets(Vec<ChartBucket>),
+}
+
+struct PlotSeries {
+    color: Hsla,
+    data: PlotData,
+}
+
+/// Everything the canvas needs to paint a frame
+struct ChartPlot {
+    window: TimeWindow,
+    range: (f64, f64),
+    columns: usize,
+    series: Vec<PlotSeries>,
+    grid: Hsla,
+    abnormal: Hsla,
+}
+
+/// Chart of prop point values over time
+pub struct PropChartView {
+    chart: PropChartState,
+    /// Plot area of the last paint, maps the cursor onto the time axis
+    plot_bounds: Rc<Cell<Option<Bounds<Pixels>>>>,
+    /// Cursor x and window when a drag started
+    drag: Option<(f32, TimeWindow)>,
+    /// Shown when a point could not be overlaid
+    notice: Option<String>,
+}
+
+impl EventEmitter<PropChartEvent> for PropChartView {}
+
+impl PropChartView {
+    pub fn new() -> Self {
+        Self {
+            chart: PropChartState::new(),
+            plot_bounds: Rc::new(Cell::new(None)),
+            drag: None,
+            notice: None,
+        }
+    }
+
+    fn locale(&self, cx: &App) -> String {
+        cx.global::<DfcGlobalStore>().read(cx).locale().to_string()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chart.is_empty()
+    }
+
+    pub fn contains(&self, row: &PropRow) -> bool {
+        self.chart.contains(row)
+    }
+
+    /// Chart the point of `row`; with `overlay` it is added to (or removed
+    /// from) the points already charted
+    pub fn show_point(
+        &mut self,
+        row: PropRow,
+        overlay: bool,
+        table: &PropTableState,
+        cx: &mut Context<Self>,
+    ) {
+        self.notice = None;
+        if !overlay {
+            self.chart.set_points(vec![row]);
+        } else if !self.chart.toggle_point(row) {
+            let locale = self.locale(cx);
+            self.notice = Some(
+                t!(
+                    "prop_chart.series_limit",
+                    count = PROP_CHART_MAX_SERIES,
+                    locale = &locale
+                )
+                .to_string(),
+            );
+        }
+        self.refresh(table, cx);
+    }
+
+    /// Chart exactly `rows`
+    pub fn set_points(
+        &mut self,
+        rows: Vec<PropRow>,
+        table: &PropTableState,
+        cx: &mut Context<Self>,
+    ) {
+        self.notice = None;
+        self.chart.set_points(rows);
+        self.refresh(table, cx);
+    }
+
+    /// Reload the samples of the charted points from the table history
+    pub fn refresh(&mut self, table: &PropTableState, cx: &mut Context<Self>) {
+        if self.chart.is_empty() {
+            return;
+        }
+        self.chart.refresh(table.latest_history_id(), |row| {
+            table.point_history(row, PROP_CHART_MAX_SAMPLES)
+        });
+        cx.notify();
+    }
+
+    /// Add the samples the table recorded since the last load; does nothing
+    /// unless new rows arrived, so paging, sorting and filtering stay cheap
+    pub fn follow(&mut self, table: &PropTableState, cx: &mut Context<Self>) {
+        if self.chart.is_empty() {
+            return;
+        }
+        let latest = table.latest_history_id();
+        if latest < self.chart.loaded_history_id() {
+            // The history was cleared and ids started over
+            self.refresh(table, cx);
+            return;
+        }
+        let changed = self.chart.append_new_samples(latest, |row, after| {
+            table.point_history_after(row, after, PROP_CHART_MAX_SAMPLES)
+        });
+        if changed {
+            cx.notify();
+        }
+    }
+
+    pub fn clear(&mut self, cx: &mut Context<Self>) {
+        self.chart.clear();
+        self.drag = None;
+        self.notice = None;
+        cx.notify();
+    }
+
+    /// Position of `x` across the plot, 0 at the left edge
+    fn plot_fraction(&self, x: Pixels) -> Option<f64> {
+        let bounds = self.plot_bounds.get()?;
+        let width = f32::from(bounds.size.width);
+        if width <= 0.0 {
+            return None;
+        }
+        Some(f64::from(f32::from(x - bounds.origin.x) / width))
+    }
+
+    fn handle_scroll(
+        &mut self,
+        event: &ScrollWheelEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let delta = event.delta.pixel_delta(window.line_height());
+        if delta.x.abs() > delta.y.abs() {
+            // Sideways scrolling pans
+            let width = self
+                .plot_bounds
+                .get()
+                .map(|bounds| f32::from(bounds.size.width))
+                .unwrap_or(DEFAULT_PLOT_WIDTH)
+                .max(1.0);
+            self.chart.pan(f64::from(-f32::from(delta.x) / width));
+        } else if delta.y != px(0.0) {
+            let anchor = self.plot_fraction(event.position.x).unwrap_or(0.5);
+            let factor = if delta.y > px(0.0) {
+                ZOOM_STEP
+            } else {
+                1.0 / ZOOM_STEP
+            };
+            self.chart.zoom(factor, anchor);
+        } else {
+            return;
+        }
+        cx.stop_propagation();
+        cx.notify();
+    }
+
+    fn start_drag(&mut self, event: &MouseDownEvent, cx: &mut Context<Self>) {
+        if let Some(window) = self.chart.window() {
+            self.drag = Some((f32::from(event.position.x), window));
+            cx.stop_propagation();
+        }
+    }
+
+    fn drag_to(&mut self, event: &MouseMoveEvent, cx: &mut Context<Self>) {
+        let Some((start_x, start_window)) = self.drag else {
+            return;
+        };
+        if event.pressed_button != Some(MouseButton::Left) {
+            self.drag = None;
+            return;
+        }
+        let width = self
+            .plot_bounds
+            .get()
+            .map(|bounds| f32::from(bounds.size.width))
+            .unwrap_or(DEFAULT_PLOT_WIDTH)
+            .max(1.0);
+        let moved = f32::from(event.position.x) - start_x;
+        self.chart
+            .set_window(start_window.pan(f64::from(-moved / width)));
+        cx.notify();
+    }
+
+    fn end_drag(&mut self, _: &MouseUpEvent, cx: &mut Context<Self>) {
+        if self.drag.take().is_some() {
+            cx.notify();
+        }
+    }
+
+    fn series_color(index: usize, cx: &App) -> Hsla {
+        let theme = cx.theme();
+        let palette = [
+            theme.chart_1,
+            theme.chart_2,
+            theme.chart_3,
+            theme.chart_4,
+            theme.chart_5,
+            theme.primary,
+            theme.info,
+            theme.success,
+        ];
+        palette[index % palette.len()]
+    }
+
+    /// Frame for the canvas: raw samples or per-column buckets of each series
+    fn build_plot(&self, window: TimeWindow, range: (f64, f64), cx: &App) -> ChartPlot {
+        let width = self
+            .plot_bounds
+            .get()
+            .map(|bounds| f32::from(bounds.size.width))
+            .unwrap_or(DEFAULT_PLOT_WIDTH);
+        let columns = ((width / BUCKET_WIDTH) as usize).max(1);
+        let series = self
+            .chart
+            .series()
+            .iter()
+            .enumerate()
+            .map(|(index, series)| {
+                let visible = samples_in_window(&series.samples, window);
+                let data = if visible.len() > columns {
+                    PlotData::Buckets(bucket_samples(&series.samples, window, columns))
+                } else {
+                    PlotData::Raw(visible.to_vec())
+                };
+                PlotSeries {
+                    color: Self::series_color(index, cx),
+                    data,
+                }
+            })
+            .collect();
+        ChartPlot {
+            window,
+            range,
+            columns,
+            series,
+            grid: cx.theme().border,
+            abnormal: cx.theme().danger,
+        }
+    }
+
+    fn render_toolbar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let locale = self.locale(cx);
+        let compare_point = self
+            .chart
+            .series()
+            .first()
+            .map(|series| series.point.clone());
+
+        h_flex()
+            .w_full()
+            .gap_2()
+            .items_center()
+            .child(Label::new(t!("prop_chart.title", locale = &locale).to_string()).text_sm())
+            .when_some(self.notice.clone(), |this, notice| {
+                this.child(Label::new(notice).text_xs().text_color(cx.theme().warning))
+            })
+            .child(div().flex_1())
+            .when_some(compare_point, |this, point| {
+                this.child(
+                    Button::new("prop-chart-compare-devices")
+                        .small()
+                        .ghost()
+                        .label(t!("prop_chart.compare_devices", locale = &locale).to_string())
+                        .on_click(cx.listener(move |_, _, _, cx| {
+                            cx.emit(PropChartEvent::CompareDevices(point.clone()));
+                        })),
+                )
+            })
+            .child(
+                Button::new("prop-chart-zoom-in")
+                    .small()
+                    .ghost()
+                    .icon(IconName::Plus)
+                    .tooltip(t!("prop_chart.zoom_in", locale = &locale).to_string())
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.chart.zoom(ZOOM_STEP, 0.5);
+                        cx.notify();
+                    })),
+            )
+            .child(
+                Button::new("prop-chart-zoom-out")
+                    .small()
+                    .ghost()
+                    .icon(IconName::Minus)
+                    .tooltip(t!("prop_chart.zoom_out", locale = &locale).to_string())
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.chart.zoom(1.0 / ZOOM_STEP, 0.5);
+                        cx.notify();
+                    })),
+            )
+            .child(
+                Button::new("prop-chart-fit")
+                    .small()
+                    .ghost()
+                    .label(t!("prop_chart.fit", locale = &locale).to_string())
+                    .disabled(!self.chart.is_zoomed())
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.chart.reset_zoom();
+                        cx.notify();
+                    })),
+            )
+            .child(
+                Button::new("prop-chart-close")
+                    .small()
+                    .ghost()
+                    .icon(IconName::Close)
+                    .tooltip(t!("prop_chart.close", locale = &locale).to_string())
+                    .on_click(cx.listener(|this, _, _, cx| this.clear(cx))),
+            )
+    }
+
+    /// Color, name and statistics in view of every series
+    fn render_legend(&self, window: Option<TimeWindow>, cx: &App) -> impl IntoElement {
+        let locale = self.locale(cx);
+        let muted = cx.theme().muted_foreground;
+        h_flex()
+            .w_full()
+            .flex_wrap()
+            .gap_x_4()
+            .gap_y_1()
+            .children(
+                self.chart
+                    .series()
+                    .iter()
+                    .enumerate()
+                    .map(|(index, series)| {
+                        let stats = window.and_then(|window| series_stats(&series.samples, window));
+                        let detail = match stats {
+                            Some(stats) => t!(
+                                "prop_chart.stats",
+                                min = format_chart_value(stats.min),
+                                avg = format_chart_value(stats.avg),
+                                max = format_chart_value(stats.max),
+                                count = stats.count,
+                                locale = &locale
+                            )
+                            .to_string(),
+                            None => t!("prop_chart.no_samples", locale = &locale).to_string(),
+                        };
+                        h_flex()
+                            .gap_1()
+                            .items_center()
+                            .child(
+                                div()
+                                    .size(px(8.0))
+                                    .rounded_full()
+                                    .bg(Self::series_color(index, cx)),
+                            )
+                            .child(Label::new(series.label()).text_xs())
+                            .child(Label::new(detail).text_xs().text_color(muted))
+                    }),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .items_center()
+                    .child(div().size(px(7.0)).bg(cx.theme().danger))
+                    .child(
+                        Label::new(t!("prop_chart.abnormal_quality", locale = &locale).to_string())
+                            .text_xs()
+                            .text_color(muted),
+                    ),
+            )
+    }
+
+    fn render_plot(
+        &self,
+        window: TimeWindow,
+        range: (f64, f64),
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let muted = cx.theme().muted_foreground;
+        let plot = self.build_plot(window, range, cx);
+        let plot_bounds = self.plot_bounds.clone();
+        let with_date = window.span() >= 86_400_000.0;
+
+        v_flex()
+            .w_full()
+            .flex_1()
+            .min_h(px(0.0))
+            .child(
+                h_flex()
+                    .w_full()
+                    .flex_1()
+                    .min_h(px(0.0))
+                    .child(
+                        v_flex()
+                            .w(px(72.0))
+                            .h_full()
+                            .pr_2()
+                            .items_end()
+                            .justify_between()
+                            .child(
+                                Label::new(format_chart_value(range.1))
+                                    .text_xs()
+                                    .text_color(muted),
+                            )
+                            .child(
+                                Label::new(format_chart_value(range.0))
+                                    .text_xs()
+                                    .text_color(muted),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .id("prop-chart-plot")
+                            .flex_1()
+                            .h_full()
+                            .min_w(px(0.0))
+                            .border_1()
+                            .border_color(cx.theme().border)
+                            .cursor_grab()
+                            .on_scroll_wheel(cx.listener(Self::handle_scroll))
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|this, event: &MouseDownEvent, _, cx| {
+                                    this.start_drag(event, cx);
+                                }),
+                            )
+                            .on_mouse_move(cx.listener(|this, event: &MouseMoveEvent, _, cx| {
+                                this.drag_to(event, cx);
+                            }))
+                            .on_mouse_up(
+                                MouseButton::Left,
+                                cx.listener(|this, event: &MouseUpEvent, _, cx| {
+                                    this.end_drag(event, cx);
+                                }),
+                            )
+                            .on_mouse_up_out(
+                                MouseButton::Left,
+                                cx.listener(|this, event: &MouseUpEvent, _, cx| {
+                                    this.end_drag(event, cx);
+                                }),
+                            )
+                            .child(
+                                canvas(
+                                    move |bounds, _, _| {
+                                        plot_bounds.set(Some(bounds));
+                                    },
+                                    move |bounds, _, window, _| paint_plot(bounds, plot, window),
+                                )
+                                .size_full(),
+                            ),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .w_full()
+                    .pl(px(72.0))
+                    .justify_between()
+                    .child(
+                        Label::new(format_chart_time(window.start_ms as i64, with_date))
+                            .text_xs()
+                            .text_color(muted),
+                    )
+                    .child(
+                        Label::new(format_chart_time(window.end_ms as i64, with_date))
+                            .text_xs()
+                            .text_color(muted),
+                    ),
+            )
+    }
+}
+
+impl Default for PropChartView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Render for PropChartView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let locale = self.locale(cx);
+        let window = self.chart.window();
+        let range = window.and_then(|window| self.chart.value_range(window));
+
+        let body = match (window, range) {
+            (Some(window), Some(range)) => self.render_plot(window, range, cx).into_any_element(),
+            _ => div()
+                .flex_1()
+                .flex()
+                .items_center()
+                .justify_center()
+                .child(
+                    Label::new(t!("prop_chart.no_numeric", locale = &locale).to_string())
+                        .text_sm()
+                        .text_color(cx.theme().muted_foreground),
+                )
+                .into_any_element(),
+        };
+
+        v_flex()
+            .size_full()
+            .gap_2()
+            .child(self.render_toolbar(cx))
+            .child(self.render_legend(window, cx))
+            .child(body)
+            .child(
+                Label::new(t!("prop_chart.hint", locale = &locale).to_string())
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground),
+            )
+    }
+}
+
+/// Compact value label for axes and statistics
+fn format_chart_value(value: f64) -> String {
+    if value.abs() >= 1e6 || (value != 0.0 && value.abs() < 1e-3) {
+        format!("{value:.3e}")
+    } else {
+        let text = format!("{value:.3}");
+        text.trim_end_matches('0').trim_end_matches('.').to_string()
+    }
+}
+
+fn paint_plot(bounds: Bounds<Pixels>, plot: ChartPlot, window: &mut Window) {
+    let left = f32::from(bounds.origin.x);
+    let top = f32::from(bounds.origin.y);
+    let width = f32::from(bounds.size.width);
+    let height = f32::from(bounds.size.height);
+    let (min, max) = plot.range;
+    let x_at_fraction = |fraction: f64| left + width * fraction as f32;
+    let y_at = |value: f64| top + height * (1.0 - ((value - min) / (max - min)) as f32);
+    let column_width = width / plot.columns as f32;
+    let x_at_column = |column: usize| left + column_width * (column as f32 + 0.5);
+
+    window.with_content_mask(Some(ContentMask { bounds }), |window| {
+        for step in 1..4 {
+            let y = top + height * step as f32 / 4.0;
+            window.paint_quad(fill(
+                Bounds::new(point(px(left), px(y)), size(px(width), px(1.0))),
+                plot.grid,
+            ));
+        }
+
+        for series in &plot.series {
+            match &series.data {
+                PlotData::Raw(samples) => {
+                    let points = samples
+                        .iter()
+                        .map(|sample| {
+                            (
+                                x_at_fraction(plot.window.fraction(sample.time_ms as f64)),
+                                y_at(sample.value),
+                            )
+                        })
+                        .collect::<Vec<_>>();
+                    paint_line(&points, series.color, window);
+                    let mark_all = samples.len() as f32 * MARKER_SPACING <= width;
+                    for (sample, (x, y)) in samples.iter().zip(points) {
+                        if !sample.is_good() {
+                            paint_marker(x, y, 7.0, plot.abnormal, false, window);
+                        } else if mark_all {
+                            paint_marker(x, y, 5.0, series.color, true, window);
+                        }
+                    }
+                }
+                PlotData::Buckets(buckets) => {
+                    let band = series.color.opacity(0.25);
+                    for bucket in buckets {
+                        let x = left + column_width * bucket.column as f32;
+                        let y_top = y_at(bucket.max);
+                        let y_bottom = y_at(bucket.min);
+                        window.paint_quad(fill(
+                            Bounds::new(
+                                point(px(x), px(y_top)),
+                                size(px(column_width), px((y_bottom - y_top).max(1.0))),
+                            ),
+                            band,
+                        ));
+                    }
+                    let points = buckets
+                        .iter()
+                        .map(|bucket| (x_at_column(bucket.column), y_at(bucket.avg)))
+                        .collect::<Vec<_>>();
+                    paint_line(&points, series.color, window);
+                    for (bucket, (x, y)) in buckets.iter().zip(points) {
+                        if bucket.abnormal {
+                            paint_marker(x, y, 7.0, plot.abnormal, false, window);
+                        }
+                    }
+                }
+            }
+        }
+    });
+}
+
+fn paint_line(points: &[(f32, f32)], color: Hsla, window: &mut Window) {
+    let Some(&(x, y)) = points.first() else {
+        return;
+    };
+    if points.len() == 1 {
+        paint_marker(x, y, 5.0, color, true, window);
+        return;
+    }
+    let mut builder = PathBuilder::stroke(px(1.5));
+    builder.move_to(point(px(x), px(y)));
+    for &(x, y) in &points[1..] {
+        builder.line_to(point(px(x), px(y)));
+    }
+    match builder.build() {
+        Ok(path) => window.paint_path(path, color),
+        Err(e) => tracing::debug!("failed to build prop chart line: {e:?}"),
+    }
+}
+
+/// Sample marker centered on `(x, y)`; round for good samples, square otherwise
+fn paint_marker(x: f32, y: f32, diameter: f32, color: Hsla, round: bool, window: &mut Window) {
+    let radius = diameter / 2.0;
+    let quad = fill(
+        Bounds::new(
+            point(px(x - radius), px(y - radius)),
+            size(px(diameter), px(diameter)),
+        ),
+        color,
+    );
+    window.paint_quad(if round {
+        quad.corner_radii(Corners::all(px(radius)))
+    } else {
+        quad
+    });
+}
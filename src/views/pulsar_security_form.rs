@@ -0,0 +1,224 @@
+//! Pulsar security fields of the server dialog
+//!
+//! Authentication mode selector with the inputs of the selected mode, and
+//! the TLS options used for `pulsar+ssl://` service URLs.
+
+use crate::connection::{PulsarAuthMode, PulsarSecurityConfig};
+use crate::states::i18n_servers;
+use gpui::{App, AppContext, Entity, SharedString, Window, prelude::*};
+use gpui_component::{
+    Selectable, Sizable,
+    button::{Button, ButtonVariants},
+    checkbox::Checkbox,
+    form::{Form, field},
+    h_flex,
+    input::{Input, InputState},
+};
+use std::cell::Cell;
+use std::rc::Rc;
+
+/// Inputs of a server's Pulsar authentication and TLS settings
+#[derive(Clone)]
+pub(crate) struct PulsarSecurityInputs {
+    auth_mode: Rc<Cell<PulsarAuthMode>>,
+    token_file: Entity<InputState>,
+    token_env: Entity<InputState>,
+    username: Entity<InputState>,
+    password: Entity<InputState>,
+    oauth2_issuer_url: Entity<InputState>,
+    oauth2_client_id: Entity<InputState>,
+    oauth2_client_secret: Entity<InputState>,
+    oauth2_credentials_file: Entity<InputState>,
+    oauth2_audience: Entity<InputState>,
+    oauth2_scope: Entity<InputState>,
+    tls_ca_file: Entity<InputState>,
+    tls_skip_hostname_verification: Rc<Cell<bool>>,
+    tls_allow_insecure: Rc<Cell<bool>>,
+}
+
+impl PulsarSecurityInputs {
+    pub fn new(window: &mut Window, cx: &mut App) -> Self {
+        let mut input = |placeholder: Option<&'static str>, masked: bool| {
+            cx.new(|cx| {
+                let mut state = InputState::new(window, cx).masked(masked);
+                if let Some(key) = placeholder {
+                    state = state.placeholder(i18n_servers(cx, key));
+                }
+                state
+            })
+        };
+        Self {
+            auth_mode: Rc::new(Cell::new(PulsarAuthMode::default())),
+            token_file: input(Some("pulsar_token_file_placeholder"), false),
+            token_env: input(Some("pulsar_token_env_placeholder"), false),
+            username: input(None, false),
+            password: input(None, true),
+            oauth2_issuer_url: input(Some("pulsar_oauth2_issuer_url_placeholder"), false),
+            oauth2_client_id: input(None, false),
+            oauth2_client_secret: input(None, true),
+            oauth2_credentials_file: input(
+                Some("pulsar_oauth2_credentials_file_placeholder"),
+                false,
+            ),
+            oauth2_audience: input(None, false),
+            oauth2_scope: input(None, false),
+            tls_ca_file: input(Some("pulsar_tls_ca_file_placeholder"), false),
+            tls_skip_hostname_verification: Rc::new(Cell::new(false)),
+            tls_allow_insecure: Rc::new(Cell::new(false)),
+        }
+    }
+
+    /// Show the settings of a server
+    pub fn fill(&self, security: Option<&PulsarSecurityConfig>, window: &mut Window, cx: &mut App) {
+        let security = security.cloned().unwrap_or_default();
+        self.auth_mode.set(security.auth_mode);
+        self.tls_skip_hostname_verification
+            .set(security.tls_skip_hostname_verification);
+        self.tls_allow_insecure.set(security.tls_allow_insecure);
+        for (state, value) in [
+            (&self.token_file, security.token_file),
+            (&self.token_env, security.token_env),
+            (&self.username, security.username),
+            (&self.password, security.password),
+            (&self.oauth2_issuer_url, security.oauth2_issuer_url),
+            (&self.oauth2_client_id, security.oauth2_client_id),
+            (&self.oauth2_client_secret, security.oauth2_client_secret),
+            (
+                &self.oauth2_credentials_file,
+                security.oauth2_credentials_file,
+            ),
+            (&self.oauth2_audience, security.oauth2_audience),
+            (&self.oauth2_scope, security.oauth2_scope),
+            (&self.tls_ca_file, security.tls_ca_file),
+        ] {
+            state.update(cx, |state, cx| {
+                state.set_value(value.unwrap_or_default(), window, cx);
+            });
+        }
+    }
+
+    /// Reset to the defaults of a new server
+    pub fn clear(&self, window: &mut Window, cx: &mut App) {
+        self.fill(None, window, cx);
+    }
+
+    /// Settings as entered; `None` when everything is at its default
+    pub fn value(&self, cx: &App) -> Option<PulsarSecurityConfig> {
+        let text = |state: &Entity<InputState>| {
+            let value = state.read(cx).value();
+            let value = value.trim();
+            (!value.is_empty()).then(|| value.to_string())
+        };
+        let security = PulsarSecurityConfig {
+            auth_mode: self.auth_mode.get(),
+            token_file: text(&self.token_file),
+            token_env: text(&self.token_env),
+            username: text(&self.username),
+            password: text(&self.password),
+            oauth2_issuer_url: text(&self.oauth2_issuer_url),
+            oauth2_client_id: text(&self.oauth2_client_id),
+            oauth2_client_secret: text(&self.oauth2_client_secret),
+            oauth2_credentials_file: text(&self.oauth2_credentials_file),
+            oauth2_audience: text(&self.oauth2_audience),
+            oauth2_scope: text(&self.oauth2_scope),
+            tls_ca_file: text(&self.tls_ca_file),
+            tls_skip_hostname_verification: self.tls_skip_hostname_verification.get(),
+            tls_allow_insecure: self.tls_allow_insecure.get(),
+        };
+        (security != PulsarSecurityConfig::default()).then_some(security)
+    }
+
+    /// Append the mode selector, the inputs of the selected mode and the TLS
+    /// options to `form`
+    pub fn render(&self, form: Form, cx: &App) -> Form {
+        let auth_mode = self.auth_mode.get();
+        let mode_buttons = h_flex().gap_1().children(PulsarAuthMode::ALL.map(|mode| {
+            let selected_mode = self.auth_mode.clone();
+            Button::new(SharedString::from(format!("pulsar-auth-{}", mode.key())))
+                .ghost()
+                .xsmall()
+                .label(i18n_servers(cx, &format!("pulsar_auth_{}", mode.key())))
+                .selected(auth_mode == mode)
+                .on_click(move |_, window, _| {
+                    selected_mode.set(mode);
+                    window.refresh();
+                })
+        }));
+        let input_field = |label: &str, state: &Entity<InputState>, masked: bool| {
+            let input = Input::new(state);
+            let input = if masked { input.mask_toggle() } else { input };
+            field().label(i18n_servers(cx, label)).child(input)
+        };
+        let checkbox_field = |id: &'static str, label: &str, value: &Rc<Cell<bool>>| {
+            let value = value.clone();
+            field().child(
+                Checkbox::new(id)
+                    .label(i18n_servers(cx, label))
+                    .checked(value.get())
+                    .on_click(move |checked: &bool, window, _| {
+                        value.set(*checked);
+                        window.refresh();
+                    }),
+            )
+        };
+
+        form.child(
+            field()
+                .label(i18n_servers(cx, "pulsar_auth"))
+                .child(mode_buttons),
+        )
+        .when(auth_mode == PulsarAuthMode::TokenFile, |form| {
+            form.child(input_field("pulsar_token_file", &self.token_file, false))
+        })
+        .when(auth_mode == PulsarAuthMode::TokenEnv, |form| {
+            form.child(input_field("pulsar_token_env", &self.token_env, false))
+        })
+        .when(auth_mode == PulsarAuthMode::Basic, |form| {
+            form.child(input_field("pulsar_username", &self.username, false))
+                .child(input_field("pulsar_password", &self.password, true))
+        })
+        .when(auth_mode == PulsarAuthMode::OAuth2, |form| {
+            form.child(input_field(
+                "pulsar_oauth2_issuer_url",
+                &self.oauth2_issuer_url,
+                false,
+            ))
+            .child(input_field(
+                "pulsar_oauth2_client_id",
+                &self.oauth2_client_id,
+                false,
+            ))
+            .child(input_field(
+                "pulsar_oauth2_client_secret",
+                &self.oauth2_client_secret,
+                true,
+            ))
+            .child(input_field(
+                "pulsar_oauth2_credentials_file",
+                &self.oauth2_credentials_file,
+                false,
+            ))
+            .child(input_field(
+                "pulsar_oauth2_audience",
+                &self.oauth2_audience,
+                false,
+            ))
+            .child(input_field(
+                "pulsar_oauth2_scope",
+                &self.oauth2_scope,
+                false,
+            ))
+        })
+        .child(input_field("pulsar_tls_ca_file", &self.tls_ca_file, false))
+        .child(checkbox_field(
+            "pulsar-tls-skip-hostname-verification",
+            "pulsar_tls_skip_hostname_verification",
+            &self.tls_skip_hostname_verification,
+        ))
+        .child(checkbox_field(
+            "pulsar-tls-allow-insecure",
+            "pulsar_tls_allow_insecure",
+            &self.tls_allow_insecure,
+        ))
+    }
+}
@@ -0,0 +1,708 @@
+//! Server Dashboard View
+//!
+//! Health overview of the active Redis server, refreshed periodically while
+//! visible:
+//! - Memory, ops/sec, clients, hit rate, uptime and replication role
+//! - Keyspace per DB, replicas, cluster node map and slot coverage
+//! - SLOWLOG entries and CLIENT LIST
+
+use crate::connection::{CLUSTER_SLOT_COUNT, RedisDashboard, cluster_slot_coverage};
+use crate::states::{
+    DASHBOARD_REFRESH_SECS, DASHBOARD_SLOWLOG_LEN, DashboardState, DfcGlobalStore, KeysState,
+};
+use crate::views::render_simple_table;
+use gpui::{App, Context, Entity, Subscription, Task, Window, div, prelude::*, px};
+use gpui_component::{
+    ActiveTheme, Disableable, IconName, Sizable, button::Button, checkbox::Checkbox, h_flex,
+    label::Label, scroll::ScrollableElement, v_flex,
+};
+use rust_i18n::t;
+use std::time::Duration;
+
+/// Width of a stat card in pixels
+const STAT_CARD_WIDTH: f32 = 180.0;
+
+/// Format seconds as a short duration, e.g. `3d 4h` or `12m 5s`
+fn format_uptime(secs: u64) -> String {
+    let (days, hours, minutes) = (secs / 86_400, secs / 3_600 % 24, secs / 60 % 60);
+    if days > 0 {
+        format!("{days}d {hours}h")
+    } else if hours > 0 {
+        format!("{hours}h {minutes}m")
+    } else {
+        format!("{minutes}m {}s", secs % 60)
+    }
+}
+
+/// Server dashboard view component
+pub struct ServerDashboardView {
+    /// Keys state entity (provides the active server)
+    keys_state: Entity<KeysState>,
+    /// Latest snapshot and refresh status
+    dashboard_state: Entity<DashboardState>,
+    /// Whether the dashboard is currently shown
+    active: bool,
+    /// Periodic refresh loop; dropping it stops the loop
+    refresh_task: Option<Task<()>>,
+    /// Subscriptions
+    _subscriptions: Vec<Subscription>,
+}
+
+impl ServerDashboardView {
+    /// Create a new dashboard view
+    pub fn new(
+        keys_state: Entity<KeysState>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let dashboard_state = cx.new(|_| DashboardState::new());
+        let mut subscriptions = Vec::new();
+
+        subscriptions.push(cx.observe(&dashboard_state, |_this, _model, cx| {
+            cx.notify();
+        }));
+
+        // Reload right away when the active server changes while visible
+        subscriptions.push(cx.observe(&keys_state, |this, model, cx| {
+            if !this.active {
+                return;
+            }
+            let active_server_id = model.read(cx).active_server_id().map(str::to_string);
+            if active_server_id.as_deref() != this.dashboard_state.read(cx).server_id() {
+                this.refresh(cx);
+            }
+        }));
+
+        Self {
+            keys_state,
+            dashboard_state,
+            active: false,
+            refresh_task: None,
+            _subscriptions: subscriptions,
+        }
+    }
+
+    /// Get the locale string
+    fn locale(&self, cx: &App) -> String {
+        cx.global::<DfcGlobalStore>().read(cx).locale().to_string()
+    }
+
+    /// Show or hide the dashboard; it only refreshes while shown
+    pub fn set_active(&mut self, active: bool, cx: &mut Context<Self>) {
+        if self.active == active {
+            return;
+        }
+        self.active = active;
+        if active {
+            self.refresh(cx);
+            self.ensure_refresh_task(cx);
+        } else {
+            self.refresh_task = None;
+        }
+    }
+
+    /// Fetch a new snapshot of the active server
+    fn refresh(&mut self, cx: &mut Context<Self>) {
+        let Some(server_id) = self
+            .keys_state
+            .read(cx)
+            .active_server_id()
+            .map(str::to_string)
+        else {
+            return;
+        };
+        let generation = self
+            .dashboard_state
+            .update(cx, |state, cx| state.begin_refresh(server_id.clone(), cx));
+
+        let store = cx.global::<DfcGlobalStore>().clone();
+        let dashboard_state = self.dashboard_state.clone();
+        cx.spawn(async move |_, cx| {
+            let redis = store.services().redis();
+            let result = redis.fetch_dashboard(DASHBOARD_SLOWLOG_LEN).await;
+            let _ = dashboard_state.update(cx, |state, cx| {
+                if state.generation() != generation {
+                    tracing::info!(server_id = %server_id, "Ignoring stale dashboard response");
+                    return;
+                }
+                match result {
+                    Ok(dashboard) => state.set_dashboard(dashboard, cx),
+                    Err(e) => {
+                        tracing::error!("Failed to load server dashboard: {}", e);
+                        state.set_error(e.to_string(), cx);
+                    }
+                }
+            });
+        })
+        .detach();
+    }
+
+    /// Start the periodic refresh loop if it is enabled and not running
+    fn ensure_refresh_task(&mut self, cx: &mut Context<Self>) {
+        if self.refresh_task.is_some() || !self.dashboard_state.read(cx).auto_refresh() {
+            return;
+        }
+
+        let task = cx.spawn(async move |handle, cx| {
+            loop {
+                cx.background_executor()
+                    .timer(Duration::from_secs(DASHBOARD_REFRESH_SECS))
+                    .await;
+
+                let Ok(keep_running) = handle.update(cx, |this, cx| {
+                    if !this.active || !this.dashboard_state.read(cx).auto_refresh() {
+                        return false;
+                    }
+                    if !this.dashboard_state.read(cx).is_loading() {
+                        this.refresh(cx);
+                    }
+                    true
+                }) else {
+                    break;
+                };
+
+                if !keep_running {
+                    break;
+                }
+            }
+
+            let _ = handle.update(cx, |this, _| {
+                this.refresh_task = None;
+            });
+        });
+        self.refresh_task = Some(task);
+    }
+
+    fn set_auto_refresh(&mut self, auto_refresh: bool, cx: &mut Context<Self>) {
+        self.dashboard_state.update(cx, |state, cx| {
+            state.set_auto_refresh(auto_refresh, cx);
+        });
+        if auto_refresh {
+            self.ensure_refresh_task(cx);
+        } else {
+            self.refresh_task = None;
+        }
+    }
+
+    /// Render the toolbar with refresh controls
+    fn render_toolbar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let locale = self.locale(cx);
+        let state = self.dashboard_state.read(cx);
+        let loading = state.is_loading();
+        let auto_refresh = state.auto_refresh();
+        let refreshed_label = state.refreshed_at().map(|at| {
+            t!(
+                "dashboard.refreshed_at",
+                time = at.format("%H:%M:%S").to_string(),
+                locale = &locale
+            )
+            .to_string()
+        });
+
+        h_flex()
+            .w_full()
+            .gap_3()
+            .items_center()
+            .child(
+                Label::new(t!("dashboard.title", locale = &locale).to_string())
+                    .text_lg()
+                    .flex_1(),
+            )
+            .when_some(refreshed_label, |this, label| {
+                this.child(
+                    Label::new(label)
+                        .text_xs()
+                        .text_color(cx.theme().muted_foreground),
+                )
+            })
+            .child(
+                Checkbox::new("dashboard-auto-refresh")
+                    .label(
+                        t!(
+                            "dashboard.auto_refresh",
+                            seconds = DASHBOARD_REFRESH_SECS,
+                            locale = &locale
+                        )
+                        .to_string(),
+                    )
+                    .checked(auto_refresh)
+                    .on_click(cx.listener(|this, checked: &bool, _, cx| {
+                        this.set_auto_refresh(*checked, cx);
+                    })),
+            )
+            .child(
+                Button::new("dashboard-refresh")
+                    .small()
+                    .icon(if loading {
+                        IconName::Loader
+                    } else {
+                        IconName::Redo
+                    })
+                    .label(t!("dashboard.refresh", locale = &locale).to_string())
+                    .disabled(loading)
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.refresh(cx);
+                    })),
+            )
+    }
+
+    /// Render one stat card
+    fn render_stat(
+        &self,
+        title: String,
+        value: String,
+        detail: Option<String>,
+        cx: &App,
+    ) -> impl IntoElement {
+        v_flex()
+            .w(px(STAT_CARD_WIDTH))
+            .p_3()
+            .gap_1()
+            .rounded_md()
+            .border_1()
+            .border_color(cx.theme().border)
+            .bg(cx.theme().secondary)
+            .child(
+                Label::new(title)
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground),
+            )
+            .child(Label::new(value).text_lg())
+            .when_some(detail, |this, detail| {
+                this.child(
+                    Label::new(detail)
+                        .text_xs()
+                        .text_color(cx.theme().muted_foreground),
+                )
+            })
+    }
+
+    /// Render a titled section
+    fn render_section(
+        &self,
+        title: String,
+        content: impl IntoElement,
+        cx: &App,
+    ) -> impl IntoElement {
+        v_flex()
+            .w_full()
+            .gap_2()
+            .child(
+                Label::new(title)
+                    .text_sm()
+                    .text_color(cx.theme().muted_foreground),
+            )
+            .child(content)
+    }
+
+    /// Render the overview stat cards
+    fn render_overview(&self, dashboard: &RedisDashboard, cx: &App) -> impl IntoElement {
+        let locale = self.locale(cx);
+        let stats = &dashboard.stats;
+        let none = "--".to_string();
+
+        let memory_detail = stats.memory_usage().map(|usage| {
+            t!(
+                "dashboard.memory_of_max",
+                usage = format!("{usage:.1}"),
+                max = stats.maxmemory_human.clone(),
+                locale = &locale
+            )
+            .to_string()
+        });
+        let role_detail = match (&stats.master, &stats.master_link_status) {
+            (Some(master), Some(status)) => Some(format!("{master} ({status})")),
+            (Some(master), None) => Some(master.clone()),
+            _ => (!stats.replicas.is_empty()).then(|| {
+                t!(
+                    "dashboard.replica_count",
+                    count = stats.replicas.len(),
+                    locale = &locale
+                )
+                .to_string()
+            }),
+        };
+
+        h_flex()
+            .w_full()
+            .flex_wrap()
+            .gap_3()
+            .child(self.render_stat(
+                t!("dashboard.version", locale = &locale).to_string(),
+                stats.version.clone(),
+                Some(stats.mode.clone()).filter(|mode| !mode.is_empty()),
+                cx,
+            ))
+            .child(self.render_stat(
+                t!("dashboard.uptime", locale = &locale).to_string(),
+                format_uptime(stats.uptime_secs),
+                None,
+                cx,
+            ))
+            .child(self.render_stat(
+                t!("dashboard.memory", locale = &locale).to_string(),
+                stats.used_memory_human.clone(),
+                memory_detail,
+                cx,
+            ))
+            .child(self.render_stat(
+                t!("dashboard.memory_peak", locale = &locale).to_string(),
+                stats.used_memory_peak_human.clone(),
+                None,
+                cx,
+            ))
+            .child(self.render_stat(
+                t!("dashboard.ops_per_sec", locale = &locale).to_string(),
+                stats.ops_per_sec.to_string(),
+                None,
+                cx,
+            ))
+            .child(
+                self.render_stat(
+                    t!("dashboard.clients", locale = &locale).to_string(),
+                    stats.connected_clients.to_string(),
+                    Some(
+                        t!(
+                            "dashboard.blocked_clients",
+                            count = stats.blocked_clients,
+                            locale = &locale
+                        )
+                        .to_string(),
+                    ),
+                    cx,
+                ),
+            )
+            .child(
+                self.render_stat(
+                    t!("dashboard.hit_rate", locale = &locale).to_string(),
+                    stats
+                        .hit_rate()
+                        .map(|rate| format!("{rate:.1}%"))
+                        .unwrap_or_else(|| none.clone()),
+                    None,
+                    cx,
+                ),
+            )
+            .child(self.render_stat(
+                t!("dashboard.role", locale = &locale).to_string(),
+                if stats.role.is_empty() {
+                    none
+                } else {
+                    stats.role.clone()
+                },
+                role_detail,
+                cx,
+            ))
+    }
+
+    /// Render the cluster node map with slot coverage
+    fn render_cluster(&self, dashboard: &RedisDashboard, cx: &App) -> impl IntoElement {
+        let locale = self.locale(cx);
+        let covered = cluster_slot_coverage(&dashboard.cluster_nodes);
+        let coverage_color = if covered == CLUSTER_SLOT_COUNT {
+            cx.theme().success
+        } else {
+            cx.theme().danger
+        };
+
+        let rows = dashboard
+            .cluster_nodes
+            .iter()
+            .map(|node| {
+                vec![
+                    node.addr.clone(),
+                    node.flags.clone(),
+                    node.slots_label(),
+                    node.slot_count().to_string(),
+                    node.link_state.clone(),
+                ]
+            })
+            .collect();
+
+        v_flex()
+            .w_full()
+            .gap_2()
+            .child(
+                Label::new(
+                    t!(
+                        "dashboard.slot_coverage",
+                        covered = covered,
+                        total = CLUSTER_SLOT_COUNT,
+                        locale = &locale
+                    )
+                    .to_string(),
+                )
+                .text_sm()
+                .text_color(coverage_color),
+            )
+            .child(render_simple_table(
+                "dashboard-cluster-scroll",
+                &[
+                    (
+                        t!("dashboard.address", locale = &locale).to_string(),
+                        Some(160.0),
+                    ),
+                    (
+                        t!("dashboard.flags", locale = &locale).to_string(),
+                        Some(160.0),
+                    ),
+                    (t!("dashboard.slots", locale = &locale).to_string(), None),
+                    (
+                        t!("dashboard.slot_count", locale = &locale).to_string(),
+                        Some(80.0),
+                    ),
+                    (
+                        t!("dashboard.link", locale = &locale).to_string(),
+                        Some(110.0),
+                    ),
+                ],
+                rows,
+                cx,
+            ))
+    }
+
+    /// Render all sections of a snapshot
+    fn render_dashboard(&self, dashboard: &RedisDashboard, cx: &App) -> impl IntoElement {
+        let locale = self.locale(cx);
+        let stats = &dashboard.stats;
+
+        let keyspace_rows = stats
+            .keyspace
+            .iter()
+            .map(|db| {
+                vec![
+                    format!("db{}", db.db),
+                    db.keys.to_string(),
+                    db.expires.to_string(),
+                    format!("{}s", db.avg_ttl_ms / 1000),
+                ]
+            })
+            .collect();
+        let keyspace_columns = [
+            (t!("dashboard.db", locale = &locale).to_string(), Some(80.0)),
+            (t!("dashboard.keys", locale = &locale).to_string(), None),
+            (t!("dashboard.expires", locale = &locale).to_string(), None),
+            (t!("dashboard.avg_ttl", locale = &locale).to_string(), None),
+        ];
+        let keyspace = render_simple_table(
+            "dashboard-keyspace-scroll",
+            &keyspace_columns,
+            keyspace_rows,
+            cx,
+        );
+
+        let slowlog_rows = dashboard
+            .slowlog
+            .iter()
+            .map(|entry| {
+                let time = chrono::DateTime::from_timestamp(entry.timestamp, 0)
+                    .map(|at| {
+                        at.with_timezone(&chrono::Local)
+                            .format("%m-%d %H:%M:%S")
+                            .to_string()
+                    })
+                    .unwrap_or_else(|| entry.timestamp.to_string());
+                let client = if entry.client_name.is_empty() {
+                    entry.client_addr.clone()
+                } else {
+                    format!("{} ({})", entry.client_addr, entry.client_name)
+                };
+                vec![
+                    entry.id.to_string(),
+                    time,
+                    format!("{:.2} ms", entry.duration_us as f64 / 1000.0),
+                    entry.command.clone(),
+                    client,
+                ]
+            })
+            .collect();
+        let slowlog_columns = [
+            ("#".to_string(), Some(60.0)),
+            (
+                t!("dashboard.time", locale = &locale).to_string(),
+                Some(130.0),
+            ),
+            (
+                t!("dashboard.duration", locale = &locale).to_string(),
+                Some(100.0),
+            ),
+            (t!("dashboard.command", locale = &locale).to_string(), None),
+            (
+                t!("dashboard.client", locale = &locale).to_string(),
+                Some(200.0),
+            ),
+        ];
+        let slowlog = render_simple_table(
+            "dashboard-slowlog-scroll",
+            &slowlog_columns,
+            slowlog_rows,
+            cx,
+        );
+
+        let client_rows = dashboard
+            .clients
+            .iter()
+            .map(|client| {
+                vec![
+                    client.id.clone(),
+                    client.addr.clone(),
+                    client.name.clone(),
+                    format_uptime(client.age_secs),
+                    format_uptime(client.idle_secs),
+                    client.db.clone(),
+                    client.cmd.clone(),
+                ]
+            })
+            .collect();
+        let clients_columns = [
+            ("ID".to_string(), Some(60.0)),
+            (
+                t!("dashboard.address", locale = &locale).to_string(),
+                Some(170.0),
+            ),
+            (t!("dashboard.name", locale = &locale).to_string(), None),
+            (
+                t!("dashboard.age", locale = &locale).to_string(),
+                Some(90.0),
+            ),
+            (
+                t!("dashboard.idle", locale = &locale).to_string(),
+                Some(90.0),
+            ),
+            (t!("dashboard.db", locale = &locale).to_string(), Some(50.0)),
+            (
+                t!("dashboard.last_command", locale = &locale).to_string(),
+                None,
+            ),
+        ];
+        let clients = render_simple_table(
+            "dashboard-clients-scroll",
+            &clients_columns,
+            client_rows,
+            cx,
+        );
+
+        let replicas = (!stats.replicas.is_empty()).then(|| {
+            v_flex().gap_1().children(
+                stats
+                    .replicas
+                    .iter()
+                    .map(|replica| Label::new(replica.clone()).text_sm()),
+            )
+        });
+
+        v_flex()
+            .w_full()
+            .gap_4()
+            .child(self.render_overview(dashboard, cx))
+            .child(self.render_section(
+                t!("dashboard.keyspace", locale = &locale).to_string(),
+                keyspace,
+                cx,
+            ))
+            .when_some(replicas, |this, replicas| {
+                this.child(self.render_section(
+                    t!("dashboard.replicas", locale = &locale).to_string(),
+                    replicas,
+                    cx,
+                ))
+            })
+            .when(!dashboard.cluster_nodes.is_empty(), |this| {
+                this.child(self.render_section(
+                    t!("dashboard.cluster", locale = &locale).to_string(),
+                    self.render_cluster(dashboard, cx),
+                    cx,
+                ))
+            })
+            .child(
+                self.render_section(
+                    t!(
+                        "dashboard.slowlog",
+                        count = dashboard.slowlog.len(),
+                        locale = &locale
+                    )
+                    .to_string(),
+                    slowlog,
+                    cx,
+                ),
+            )
+            .child(
+                self.render_section(
+                    t!(
+                        "dashboard.client_list",
+                        count = dashboard.clients.len(),
+                        locale = &locale
+                    )
+                    .to_string(),
+                    clients,
+                    cx,
+                ),
+            )
+    }
+}
+
+impl Render for ServerDashboardView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let locale = self.locale(cx);
+        let state = self.dashboard_state.read(cx);
+        let dashboard = state.dashboard().cloned();
+        let loading = state.is_loading();
+        let error = state
+            .error()
+            .map(|error| t!("dashboard.load_failed", error = error, locale = &locale).to_string());
+
+        let content = match dashboard {
+            Some(dashboard) => self.render_dashboard(&dashboard, cx).into_any_element(),
+            None => {
+                let message = if loading {
+                    t!("keys.loading", locale = &locale).to_string()
+                } else {
+                    t!("dashboard.empty", locale = &locale).to_string()
+                };
+                div()
+                    .w_full()
+                    .py_8()
+                    .flex()
+                    .justify_center()
+                    .child(Label::new(message).text_color(cx.theme().muted_foreground))
+                    .into_any_element()
+            }
+        };
+
+        v_flex()
+            .size_full()
+            .p_3()
+            .gap_3()
+            .child(self.render_toolbar(cx))
+            .when_some(error, |this, error| {
+                this.child(
+                    div()
+                        .w_full()
+                        .p_2()
+                        .rounded_md()
+                        .bg(cx.theme().danger.opacity(0.1))
+                        .child(Label::new(error).text_sm().text_color(cx.theme().danger)),
+                )
+            })
+            .child(
+                div()
+                    .id("dashboard-content")
+                    .flex_1()
+                    .w_full()
+                    .child(content)
+                    .overflow_y_scrollbar(),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_uptime_picks_two_largest_units() {
+        assert_eq!(format_uptime(59), "0m 59s");
+        assert_eq!(format_uptime(3_725), "1h 2m");
+        assert_eq!(format_uptime(3 * 86_400 + 4 * 3_600 + 5), "3d 4h");
+    }
+}
@@ -0,0 +1,241 @@
+//! Connection Diagnostics Dialog
+//!
+//! Runs the diagnostics of a server entry (Redis steps, then the Pulsar
+//! steps for the configs found) and lists one pass/fail row per step, with
+//! a button copying the shareable text report.
+
+use super::service_panel::{PulsarConnectOptions, diagnose_pulsar};
+use crate::connection::{
+    DfcServerConfig, DiagnosticReport, DiagnosticStatus, PresetCredential, PulsarCredentials,
+};
+use crate::services::run_in_tokio;
+use crate::states::DfcGlobalStore;
+use gpui::{App, ClipboardItem, Context, Task, Window, div, prelude::*, px};
+use gpui_component::{
+    ActiveTheme, IconName, Sizable, WindowExt, button::Button, h_flex, label::Label,
+    scroll::ScrollableElement, v_flex,
+};
+use rust_i18n::t;
+
+/// Open the diagnostics dialog of `server` and start the run
+pub(crate) fn open_server_diagnostics(
+    server: DfcServerConfig,
+    preset_credentials: Vec<PresetCredential>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let locale = cx.global::<DfcGlobalStore>().read(cx).locale().to_string();
+    let title = t!(
+        "diagnostics.title",
+        server = server.display_name(),
+        locale = &locale
+    )
+    .to_string();
+    let view = cx.new(|cx| ServerDiagnosticsView::new(server, preset_credentials, cx));
+    window.open_dialog(cx, move |dialog, _, _| {
+        dialog
+            .title(title.clone())
+            .width(px(720.))
+            .child(view.clone())
+    });
+}
+
+/// Secrets of `server` that must not appear in a shared report
+fn report_secrets(
+    server: &DfcServerConfig,
+    preset_credentials: &[PresetCredential],
+    store: &DfcGlobalStore,
+) -> Vec<String> {
+    let security = server.pulsar_security.clone().unwrap_or_default();
+    // Token files and variables are read the way a connect reads them
+    let resolved_token = match security.credentials(server.pulsar_token.as_deref()) {
+        Ok(PulsarCredentials::Token(token)) => Some(token),
+        _ => None,
+    };
+    [
+        server.password.clone(),
+        server.pulsar_token.clone(),
+        resolved_token,
+        security.password,
+        security.oauth2_client_secret,
+        server
+            .ssh_tunnel
+            .as_ref()
+            .and_then(|ssh| ssh.password.clone()),
+        server
+            .proxy
+            .as_ref()
+            .and_then(|proxy| proxy.password.clone()),
+        store
+            .services()
+            .proxies()
+            .global()
+            .and_then(|proxy| proxy.password),
+    ]
+    .into_iter()
+    .flatten()
+    .chain(preset_credentials.iter().map(|cred| cred.password.clone()))
+    .collect()
+}
+
+/// Progress and result of one diagnostics run
+pub(crate) struct ServerDiagnosticsView {
+    server_name: String,
+    /// `None` while the run is in progress
+    report: Option<DiagnosticReport>,
+    /// Whether the report was copied to the clipboard
+    copied: bool,
+    _task: Task<()>,
+}
+
+impl ServerDiagnosticsView {
+    fn new(
+        server: DfcServerConfig,
+        preset_credentials: Vec<PresetCredential>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let store = cx.global::<DfcGlobalStore>().clone();
+        let server_name = server.display_name();
+        let started_at = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        let task = cx.spawn(async move |this, cx| {
+            let services = store.services();
+            let mut report = DiagnosticReport::new(server.display_name(), started_at);
+            let configs = services
+                .redis()
+                .diagnose(&server, &preset_credentials, &mut report)
+                .await;
+            let options = PulsarConnectOptions::for_server(Some(&server), services.ssh_tunnels());
+            let mut report = run_in_tokio(diagnose_pulsar(configs, options, report)).await;
+            report.redact(&report_secrets(&server, &preset_credentials, &store));
+            tracing::info!(
+                server = %server.name,
+                steps = report.steps.len(),
+                failed = report.failures(),
+                "Connection diagnostics finished"
+            );
+            let _ = this.update(cx, |this, cx| {
+                this.report = Some(report);
+                cx.notify();
+            });
+        });
+        Self {
+            server_name,
+            report: None,
+            copied: false,
+            _task: task,
+        }
+    }
+}
+
+impl Render for ServerDiagnosticsView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let locale = cx.global::<DfcGlobalStore>().read(cx).locale().to_string();
+        let Some(report) = &self.report else {
+            return Label::new(
+                t!(
+                    "diagnostics.running",
+                    server = &self.server_name,
+                    locale = &locale
+                )
+                .to_string(),
+            )
+            .text_sm()
+            .text_color(cx.theme().muted_foreground)
+            .into_any_element();
+        };
+
+        let failures = report.failures();
+        let summary = if failures == 0 {
+            t!(
+                "diagnostics.all_passed",
+                count = report.steps.len(),
+                locale = &locale
+            )
+        } else {
+            t!(
+                "diagnostics.failed",
+                failed = failures,
+                count = report.steps.len(),
+                locale = &locale
+            )
+        };
+        let copy_label = if self.copied {
+            t!("diagnostics.copied", locale = &locale)
+        } else {
+            t!("diagnostics.copy", locale = &locale)
+        };
+        let text = report.to_text();
+        let rows: Vec<_> = report
+            .steps
+            .iter()
+            .map(|step| {
+                let color = match step.status {
+                    DiagnosticStatus::Pass => cx.theme().success,
+                    DiagnosticStatus::Fail => cx.theme().danger,
+                    DiagnosticStatus::Skip => cx.theme().muted_foreground,
+                };
+                h_flex()
+                    .gap_2()
+                    .items_start()
+                    .child(
+                        div().w(px(40.)).flex_none().child(
+                            Label::new(step.status.tag())
+                                .text_xs()
+                                .font_weight(gpui::FontWeight::SEMIBOLD)
+                                .text_color(color),
+                        ),
+                    )
+                    .child(
+                        v_flex()
+                            .flex_1()
+                            .min_w_0()
+                            .child(Label::new(step.name.clone()).text_sm())
+                            .when(!step.detail.is_empty(), |this| {
+                                this.child(
+                                    Label::new(step.detail.clone())
+                                        .text_xs()
+                                        .text_color(cx.theme().muted_foreground),
+                                )
+                            }),
+                    )
+            })
+            .collect();
+
+        v_flex()
+            .w_full()
+            .gap_2()
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(
+                        Label::new(summary.to_string())
+                            .text_sm()
+                            .text_color(if failures == 0 {
+                                cx.theme().success
+                            } else {
+                                cx.theme().danger
+                            }),
+                    )
+                    .child(
+                        Button::new("diagnostics-copy")
+                            .small()
+                            .icon(IconName::Copy)
+                            .label(copy_label.to_string())
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                cx.write_to_clipboard(ClipboardItem::new_string(text.clone()));
+                                this.copied = true;
+                                cx.notify();
+                            })),
+                    ),
+            )
+            .child(
+                div()
+                    .id("diagnostics-steps")
+                    .w_full()
+                    .max_h(px(420.))
+                    .child(v_flex().w_full().gap_1().children(rows))
+                    .overflow_y_scrollbar(),
+            )
+            .into_any_element()
+    }
+}
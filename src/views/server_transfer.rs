@@ -0,0 +1,680 @@
+//! Server Import and Export Dialogs
+//!
+//! - Export: pick servers, choose how secrets are written (left out, plain
+//!   text or encrypted with an export passphrase), optionally add the preset
+//!   credentials, and save a TOML file
+//! - Import: open a file, decrypt it if needed, choose per server whether a
+//!   duplicate is skipped, replaced or kept next to the existing one
+
+use crate::connection::{
+    ExportSecrets, ImportCandidate, ImportResolution, ServerExport, plan_import,
+};
+use crate::helpers::{MIN_PASSPHRASE_LEN, vault_key, write_private};
+use crate::states::{DfcAppState, DfcGlobalStore};
+use chrono::Local;
+use gpui::{
+    App, Context, Entity, EventEmitter, PathPromptOptions, Task, Window, div, prelude::*, px,
+};
+use gpui_component::{
+    ActiveTheme, Selectable, Sizable, WindowExt,
+    button::{Button, ButtonVariants},
+    checkbox::Checkbox,
+    h_flex,
+    input::{Input, InputState},
+    label::Label,
+    scroll::ScrollableElement,
+    v_flex,
+};
+use home::home_dir;
+use rust_i18n::t;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+/// Open the export dialog with every server selected
+pub(crate) fn open_server_export(window: &mut Window, cx: &mut App) {
+    let store = cx.global::<DfcGlobalStore>().clone();
+    let locale = store.read(cx).locale().to_string();
+    let app_state = store.app_state();
+    let view = cx.new(|cx| ServerExportView::new(app_state, window, cx));
+    let title = t!("server_transfer.export_title", locale = &locale).to_string();
+    window.open_dialog(cx, move |dialog, _, _| {
+        dialog
+            .title(title.clone())
+            .width(px(640.))
+            .child(view.clone())
+    });
+}
+
+/// Open the import dialog; the returned view emits `ServerImportEvent`
+pub(crate) fn open_server_import(window: &mut Window, cx: &mut App) -> Entity<ServerImportView> {
+    let store = cx.global::<DfcGlobalStore>().clone();
+    let locale = store.read(cx).locale().to_string();
+    let app_state = store.app_state();
+    let view = cx.new(|cx| ServerImportView::new(app_state, window, cx));
+    let title = t!("server_transfer.import_title", locale = &locale).to_string();
+    let dialog_view = view.clone();
+    window.open_dialog(cx, move |dialog, _, _| {
+        dialog
+            .title(title.clone())
+            .width(px(720.))
+            .child(dialog_view.clone())
+    });
+    view
+}
+
+fn locale(cx: &App) -> String {
+    cx.global::<DfcGlobalStore>().read(cx).locale().to_string()
+}
+
+/// Export options and the result of the last export
+pub(crate) struct ServerExportView {
+    app_state: Entity<DfcAppState>,
+    /// IDs of the servers to export
+    selected: HashSet<String>,
+    secrets: ExportSecrets,
+    include_presets: bool,
+    passphrase_state: Entity<InputState>,
+    confirm_state: Entity<InputState>,
+    /// Saved path, or the error of the last export
+    result: Option<Result<String, String>>,
+}
+
+impl ServerExportView {
+    fn new(app_state: Entity<DfcAppState>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let selected = app_state
+            .read(cx)
+            .servers()
+            .iter()
+            .map(|server| server.id.clone())
+            .collect();
+        Self {
+            app_state,
+            selected,
+            secrets: ExportSecrets::Omit,
+            include_presets: false,
+            passphrase_state: cx.new(|cx| InputState::new(window, cx).masked(true)),
+            confirm_state: cx.new(|cx| InputState::new(window, cx).masked(true)),
+            result: None,
+        }
+    }
+
+    /// Export file contents, or the reason it cannot be written
+    fn build(&self, cx: &App) -> Result<String, String> {
+        let locale = locale(cx);
+        let state = self.app_state.read(cx);
+        let servers: Vec<_> = state
+            .servers()
+            .iter()
+            .filter(|server| self.selected.contains(&server.id))
+            .cloned()
+            .collect();
+        if servers.is_empty() {
+            return Err(t!("server_transfer.nothing_selected", locale = &locale).to_string());
+        }
+        // In-memory secrets are only decrypted while the vault is unlocked
+        if self.secrets != ExportSecrets::Omit {
+            vault_key().map_err(|e| e.to_string())?;
+        }
+        let passphrase = self.passphrase_state.read(cx).value().to_string();
+        if self.secrets == ExportSecrets::Encrypted {
+            if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+                return Err(t!(
+                    "vault.too_short",
+                    min = MIN_PASSPHRASE_LEN,
+                    locale = &locale
+                )
+                .to_string());
+            }
+            if passphrase != self.confirm_state.read(cx).value().as_ref() {
+                return Err(t!("vault.mismatch", locale = &locale).to_string());
+            }
+        }
+        let presets = if self.include_presets && self.secrets != ExportSecrets::Omit {
+            state.preset_credentials()
+        } else {
+            Vec::new()
+        };
+        ServerExport::new(
+            &servers,
+            &presets,
+            self.secrets,
+            Some(passphrase.as_str()),
+            Local::now().to_rfc3339(),
+        )
+        .and_then(|export| export.to_toml())
+        .map_err(|e| e.to_string())
+    }
+
+    /// Ask for a file name and write the export
+    fn export(&mut self, cx: &mut Context<Self>) {
+        let content = match self.build(cx) {
+            Ok(content) => content,
+            Err(e) => {
+                self.result = Some(Err(e));
+                cx.notify();
+                return;
+            }
+        };
+        let file_name = format!("dfc-servers-{}.toml", Local::now().format("%Y%m%d-%H%M%S"));
+        let directory = home_dir().unwrap_or_else(|| PathBuf::from("."));
+        let path_rx = cx.prompt_for_new_path(&directory, Some(&file_name));
+
+        cx.spawn(async move |handle, cx| {
+            let Ok(Ok(Some(path))) = path_rx.await else {
+                return;
+            };
+            // The export may hold secrets, so only the owner gets to read it
+            let result = write_private(&path, &content)
+                .map(|_| path.display().to_string())
+                .map_err(|e| e.to_string());
+            match &result {
+                Ok(path) => tracing::info!(path = %path, "Exported server configurations"),
+                Err(e) => tracing::error!("Failed to export server configurations: {}", e),
+            }
+            let _ = handle.update(cx, |this, cx| {
+                this.result = Some(result);
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+}
+
+impl Render for ServerExportView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let locale = locale(cx);
+        let servers: Vec<_> = self.app_state.read(cx).servers().to_vec();
+        let all_selected = servers
+            .iter()
+            .all(|server| self.selected.contains(&server.id));
+
+        let rows: Vec<_> = servers
+            .iter()
+            .enumerate()
+            .map(|(index, server)| {
+                let id = server.id.clone();
+                Checkbox::new(("export-server", index))
+                    .label(server.display_name())
+                    .checked(self.selected.contains(&server.id))
+                    .on_click(cx.listener(move |this, checked: &bool, _, cx| {
+                        if *checked {
+                            this.selected.insert(id.clone());
+                        } else {
+                            this.selected.remove(&id);
+                        }
+                        cx.notify();
+                    }))
+            })
+            .collect();
+
+        let secrets_button = |id: &'static str, key: &str, secrets: ExportSecrets| {
+            Button::new(id)
+                .ghost()
+                .xsmall()
+                .label(t!(key, locale = &locale).to_string())
+                .selected(self.secrets == secrets)
+                .on_click(cx.listener(move |this, _, _, cx| {
+                    this.secrets = secrets;
+                    cx.notify();
+                }))
+        };
+
+        v_flex()
+            .w_full()
+            .gap_3()
+            .child(
+                Checkbox::new("export-select-all")
+                    .label(t!("server_transfer.select_all", locale = &locale).to_string())
+                    .checked(all_selected)
+                    .on_click(cx.listener(|this, checked: &bool, _, cx| {
+                        this.selected = if *checked {
+                            this.app_state
+                                .read(cx)
+                                .servers()
+                                .iter()
+                                .map(|server| server.id.clone())
+                                .collect()
+                        } else {
+                            HashSet::new()
+                        };
+                        cx.notify();
+                    })),
+            )
+            .child(
+                div()
+                    .id("export-servers")
+                    .w_full()
+                    .max_h(px(240.))
+                    .child(v_flex().w_full().gap_1().children(rows))
+                    .overflow_y_scrollbar(),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        Label::new(t!("server_transfer.secrets", locale = &locale).to_string())
+                            .text_sm(),
+                    )
+                    .child(secrets_button(
+                        "export-secrets-omit",
+                        "server_transfer.secrets_omit",
+                        ExportSecrets::Omit,
+                    ))
+                    .child(secrets_button(
+                        "export-secrets-plain",
+                        "server_transfer.secrets_plain",
+                        ExportSecrets::Plain,
+                    ))
+                    .child(secrets_button(
+                        "export-secrets-encrypted",
+                        "server_transfer.secrets_encrypted",
+                        ExportSecrets::Encrypted,
+                    )),
+            )
+            .when(self.secrets == ExportSecrets::Plain, |this| {
+                this.child(
+                    Label::new(t!("server_transfer.plain_warning", locale = &locale).to_string())
+                        .text_xs()
+                        .text_color(cx.theme().warning),
+                )
+            })
+            .when(self.secrets == ExportSecrets::Encrypted, |this| {
+                this.child(
+                    h_flex()
+                        .gap_2()
+                        .child(
+                            v_flex()
+                                .flex_1()
+                                .gap_1()
+                                .child(
+                                    Label::new(
+                                        t!("server_transfer.passphrase", locale = &locale)
+                                            .to_string(),
+                                    )
+                                    .text_xs(),
+                                )
+                                .child(Input::new(&self.passphrase_state).mask_toggle()),
+                        )
+                        .child(
+                            v_flex()
+                                .flex_1()
+                                .gap_1()
+                                .child(
+                                    Label::new(
+                                        t!("vault.confirm_passphrase", locale = &locale)
+                                            .to_string(),
+                                    )
+                                    .text_xs(),
+                                )
+                                .child(Input::new(&self.confirm_state).mask_toggle()),
+                        ),
+                )
+            })
+            .when(self.secrets != ExportSecrets::Omit, |this| {
+                this.child(
+                    Checkbox::new("export-presets")
+                        .label(t!("server_transfer.include_presets", locale = &locale).to_string())
+                        .checked(self.include_presets)
+                        .on_click(cx.listener(|this, checked: &bool, _, cx| {
+                            this.include_presets = *checked;
+                            cx.notify();
+                        })),
+                )
+            })
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        Button::new("export-servers-save")
+                            .small()
+                            .primary()
+                            .label(
+                                t!(
+                                    "server_transfer.export_count",
+                                    count = self.selected.len(),
+                                    locale = &locale
+                                )
+                                .to_string(),
+                            )
+                            .on_click(cx.listener(|this, _, _, cx| this.export(cx))),
+                    )
+                    .when_some(self.result.clone(), |this, result| {
+                        let (message, color) = match result {
+                            Ok(path) => (
+                                t!("server_transfer.exported", path = path, locale = &locale)
+                                    .to_string(),
+                                cx.theme().success,
+                            ),
+                            Err(e) => (e, cx.theme().danger),
+                        };
+                        this.child(Label::new(message).text_xs().text_color(color))
+                    }),
+            )
+    }
+}
+
+/// Events emitted by the import dialog
+#[derive(Debug, Clone)]
+pub enum ServerImportEvent {
+    /// Servers or preset credentials were imported
+    Imported,
+}
+
+/// Import file, conflict resolutions and the result of the import
+pub(crate) struct ServerImportView {
+    app_state: Entity<DfcAppState>,
+    /// Opened file
+    path: Option<String>,
+    /// Parsed file, secrets still encrypted until the passphrase is entered
+    export: Option<ServerExport>,
+    /// Servers of the file with their resolutions; empty until decrypted
+    candidates: Vec<ImportCandidate>,
+    import_presets: bool,
+    passphrase_state: Entity<InputState>,
+    /// Error of the last step
+    error: Option<String>,
+    /// Outcome of the finished import
+    summary: Option<String>,
+    _task: Option<Task<()>>,
+}
+
+impl EventEmitter<ServerImportEvent> for ServerImportView {}
+
+impl ServerImportView {
+    fn new(app_state: Entity<DfcAppState>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        Self {
+            app_state,
+            path: None,
+            export: None,
+            candidates: Vec::new(),
+            import_presets: true,
+            passphrase_state: cx.new(|cx| InputState::new(window, cx).masked(true)),
+            error: None,
+            summary: None,
+            _task: None,
+        }
+    }
+
+    /// Ask for an export file and parse it
+    fn choose_file(&mut self, cx: &mut Context<Self>) {
+        let paths_rx = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: None,
+        });
+        self._task = Some(cx.spawn(async move |handle, cx| {
+            let Ok(Ok(Some(paths))) = paths_rx.await else {
+                return;
+            };
+            let Some(path) = paths.into_iter().next() else {
+                return;
+            };
+            let parsed = std::fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|content| ServerExport::parse(&content).map_err(|e| e.to_string()));
+            let _ = handle.update(cx, |this, cx| {
+                this.path = Some(path.display().to_string());
+                this.summary = None;
+                this.candidates.clear();
+                match parsed {
+                    Ok(export) => {
+                        this.error = None;
+                        this.export = Some(export);
+                        this.plan(cx);
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed to read server import file: {}", e);
+                        this.export = None;
+                        this.error = Some(e);
+                    }
+                }
+                cx.notify();
+            });
+        }));
+    }
+
+    /// Match the servers of a readable file against the existing ones
+    fn plan(&mut self, cx: &mut Context<Self>) {
+        let Some(export) = self.export.as_ref() else {
+            return;
+        };
+        if export.needs_passphrase() {
+            return;
+        }
+        self.candidates = plan_import(self.app_state.read(cx).servers(), export);
+        cx.notify();
+    }
+
+    fn decrypt(&mut self, cx: &mut Context<Self>) {
+        let passphrase = self.passphrase_state.read(cx).value().to_string();
+        let Some(export) = self.export.as_mut() else {
+            return;
+        };
+        match export.decrypt(&passphrase) {
+            Ok(()) => {
+                self.error = None;
+                self.plan(cx);
+            }
+            Err(e) => self.error = Some(e.to_string()),
+        }
+        cx.notify();
+    }
+
+    fn import(&mut self, cx: &mut Context<Self>) {
+        let locale = locale(cx);
+        // Saving encrypts the secrets with the vault key
+        if let Err(e) = vault_key() {
+            self.error = Some(e.to_string());
+            cx.notify();
+            return;
+        }
+        let Some(export) = self.export.take() else {
+            return;
+        };
+        let candidates = std::mem::take(&mut self.candidates);
+        let summary = self
+            .app_state
+            .update(cx, |state, cx| state.import_servers(candidates, cx));
+        let presets = if self.import_presets {
+            self.app_state.update(cx, |state, _| {
+                state.import_preset_credentials(&export.preset_credentials)
+            })
+        } else {
+            Ok(0)
+        };
+        let presets = presets.unwrap_or_else(|e| {
+            tracing::error!(error = %e, "Failed to import preset credentials");
+            0
+        });
+        self.summary = Some(
+            t!(
+                "server_transfer.imported",
+                added = summary.added,
+                replaced = summary.replaced,
+                skipped = summary.skipped,
+                presets = presets,
+                locale = &locale
+            )
+            .to_string(),
+        );
+        self.error = None;
+        cx.emit(ServerImportEvent::Imported);
+        cx.notify();
+    }
+
+    fn render_candidate(
+        &self,
+        index: usize,
+        candidate: &ImportCandidate,
+        locale: &str,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let duplicate_name = candidate.duplicate_of.as_ref().map(|id| {
+            self.app_state
+                .read(cx)
+                .server(id)
+                .map(|server| server.display_name())
+                .unwrap_or_else(|| id.clone())
+        });
+        let options: &[(&str, ImportResolution)] = if duplicate_name.is_some() {
+            &[
+                ("server_transfer.skip", ImportResolution::Skip),
+                ("server_transfer.replace", ImportResolution::Replace),
+                ("server_transfer.keep_both", ImportResolution::Add),
+            ]
+        } else {
+            &[
+                ("server_transfer.add", ImportResolution::Add),
+                ("server_transfer.skip", ImportResolution::Skip),
+            ]
+        };
+        let buttons: Vec<_> = options
+            .iter()
+            .enumerate()
+            .map(|(option, (key, resolution))| {
+                let resolution = *resolution;
+                Button::new(("import-resolution", index * 4 + option))
+                    .ghost()
+                    .xsmall()
+                    .label(t!(*key, locale = locale).to_string())
+                    .selected(candidate.resolution == resolution)
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        if let Some(candidate) = this.candidates.get_mut(index) {
+                            candidate.resolution = resolution;
+                        }
+                        cx.notify();
+                    }))
+            })
+            .collect();
+
+        h_flex()
+            .w_full()
+            .gap_2()
+            .justify_between()
+            .child(
+                v_flex()
+                    .flex_1()
+                    .min_w_0()
+                    .child(Label::new(candidate.server.display_name()).text_sm())
+                    .when_some(duplicate_name, |this, name| {
+                        this.child(
+                            Label::new(
+                                t!(
+                                    "server_transfer.duplicate_of",
+                                    server = name,
+                                    locale = locale
+                                )
+                                .to_string(),
+                            )
+                            .text_xs()
+                            .text_color(cx.theme().warning),
+                        )
+                    }),
+            )
+            .child(h_flex().gap_1().children(buttons))
+    }
+}
+
+impl Render for ServerImportView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let locale = locale(cx);
+        let needs_passphrase = self
+            .export
+            .as_ref()
+            .is_some_and(ServerExport::needs_passphrase);
+        let preset_count = self
+            .export
+            .as_ref()
+            .map_or(0, |export| export.preset_credentials.len());
+        // Rows are type-erased right away so they hold no borrow of `cx`
+        let mut rows = Vec::with_capacity(self.candidates.len());
+        for (index, candidate) in self.candidates.iter().enumerate() {
+            let row = self.render_candidate(index, candidate, &locale, cx);
+            rows.push(row.into_any_element());
+        }
+        let can_import = !needs_passphrase && !self.candidates.is_empty();
+
+        v_flex()
+            .w_full()
+            .gap_3()
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        Button::new("import-choose-file")
+                            .small()
+                            .label(t!("server_transfer.choose_file", locale = &locale).to_string())
+                            .on_click(cx.listener(|this, _, _, cx| this.choose_file(cx))),
+                    )
+                    .when_some(self.path.clone(), |this, path| {
+                        this.child(
+                            Label::new(path)
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground),
+                        )
+                    }),
+            )
+            .when(needs_passphrase, |this| {
+                this.child(
+                    h_flex()
+                        .gap_2()
+                        .child(
+                            v_flex()
+                                .flex_1()
+                                .child(Input::new(&self.passphrase_state).mask_toggle()),
+                        )
+                        .child(
+                            Button::new("import-decrypt")
+                                .small()
+                                .primary()
+                                .label(t!("server_transfer.decrypt", locale = &locale).to_string())
+                                .on_click(cx.listener(|this, _, _, cx| this.decrypt(cx))),
+                        ),
+                )
+            })
+            .when(!rows.is_empty(), |this| {
+                this.child(
+                    div()
+                        .id("import-servers")
+                        .w_full()
+                        .max_h(px(320.))
+                        .child(v_flex().w_full().gap_2().children(rows))
+                        .overflow_y_scrollbar(),
+                )
+            })
+            .when(!needs_passphrase && preset_count > 0, |this| {
+                this.child(
+                    Checkbox::new("import-presets")
+                        .label(
+                            t!(
+                                "server_transfer.import_presets",
+                                count = preset_count,
+                                locale = &locale
+                            )
+                            .to_string(),
+                        )
+                        .checked(self.import_presets)
+                        .on_click(cx.listener(|this, checked: &bool, _, cx| {
+                            this.import_presets = *checked;
+                            cx.notify();
+                        })),
+                )
+            })
+            .when(can_import, |this| {
+                this.child(
+                    Button::new("import-servers-apply")
+                        .small()
+                        .primary()
+                        .label(t!("server_transfer.import", locale = &locale).to_string())
+                        .on_click(cx.listener(|this, _, _, cx| this.import(cx))),
+                )
+            })
+            .when_some(self.error.clone(), |this, error| {
+                this.child(Label::new(error).text_xs().text_color(cx.theme().danger))
+            })
+            .when_some(self.summary.clone(), |this, summary| {
+                this.child(Label::new(summary).text_xs().text_color(cx.theme().success))
+            })
+    }
+}
@@ -4,6 +4,8 @@
 //! sends `SvrReqRecord` payloads to `thing_service-BZ-REQUEST-<id>` and
 //! consumes `SvrRespRecord` payloads from `thing_service-BZ-RESPONSE-<id>`.
 
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Duration;
@@ -12,10 +14,16 @@ use chrono::Local;
 use crossbeam_channel::{Receiver, Sender};
 use futures::StreamExt;
 use prost::Message;
+use pulsar::authentication::oauth2::{OAuth2Authentication, OAuth2Params};
 use tokio::sync::watch;
 
 use super::config_view::{decode_framed_iothub_message, format_clock_time};
+use crate::connection::{
+    ConfigItem, DfcServerConfig, DiagnosticReport, PulsarCredentials, PulsarSecurityConfig,
+    WritePermit, is_pulsar_tls_url,
+};
 use crate::proto::iothub::{AnyValue, EventRecord, EventRecordList, SvrReqRecord, SvrRespRecord};
+use crate::services::SshTunnels;
 use crate::states::ServiceResponseRow;
 
 /// Context key for an `SvrReqRecord` carried inside an `EventRecord`.
@@ -162,23 +170,130 @@ fn push_unique_pulsar_candidate(candidates: &mut Vec<String>, candidate: String)
     }
 }
 
+/// Default Pulsar binary protocol port
+const PULSAR_DEFAULT_PORT: u16 = 6650;
+/// Default Pulsar binary protocol port over TLS
+const PULSAR_TLS_DEFAULT_PORT: u16 = 6651;
+
+/// Per-server settings for building Pulsar clients
+#[derive(Clone, Default)]
+pub struct PulsarConnectOptions {
+    /// Static JWT (`DfcServerConfig::pulsar_token`)
+    pub token: Option<String>,
+    /// Authentication mode and TLS options
+    pub security: PulsarSecurityConfig,
+    /// Server whose SSH tunnel or proxy carries the broker connections
+    pub routes: Option<(Arc<SshTunnels>, DfcServerConfig)>,
+}
+
+impl PulsarConnectOptions {
+    /// Options of `server`; defaults when the server is unknown
+    pub fn for_server(server: Option<&DfcServerConfig>, ssh_tunnels: &Arc<SshTunnels>) -> Self {
+        let Some(server) = server else {
+            return Self::default();
+        };
+        Self {
+            token: server.pulsar_token.clone().filter(|t| !t.trim().is_empty()),
+            security: server.pulsar_security.clone().unwrap_or_default(),
+            routes: Some((ssh_tunnels.clone(), server.clone())),
+        }
+    }
+}
+
+/// Try each service URL in turn. Through an SSH tunnel or proxy, each
+/// candidate is replaced by a local forward; brokers must then advertise an
+/// address the forward reaches (a Pulsar proxy, or the service URL itself).
+/// `pulsar+ssl://` candidates keep TLS; over a forward the broker name no
+/// longer matches the connected host, so only the certificate chain is
+/// verified.
 pub(super) async fn build_pulsar_client_with_fallbacks(
     service_urls: &[String],
-    token: Option<&str>,
+    options: &PulsarConnectOptions,
 ) -> Result<(pulsar::Pulsar<pulsar::TokioExecutor>, String), String> {
     if service_urls.is_empty() {
         return Err("无法解析 Pulsar service URL".to_string());
     }
 
+    let credentials = options
+        .security
+        .credentials(options.token.as_deref())
+        .map_err(|err| err.to_string())?;
+    let security = &options.security;
     let mut errors = Vec::new();
 
     for service_url in service_urls {
-        let mut builder = pulsar::Pulsar::builder(service_url.clone(), pulsar::TokioExecutor);
-        if let Some(token) = token {
-            builder = builder.with_auth(pulsar::Authentication {
+        let tls = is_pulsar_tls_url(service_url);
+        let default_port = if tls {
+            PULSAR_TLS_DEFAULT_PORT
+        } else {
+            PULSAR_DEFAULT_PORT
+        };
+        let connect_url = match &options.routes {
+            Some((ssh_tunnels, server)) => {
+                match ssh_tunnels
+                    .forward_url(server, service_url, default_port)
+                    .await
+                {
+                    Ok(url) => url,
+                    Err(err) => {
+                        errors.push(format!("{service_url}: {err}"));
+                        continue;
+                    }
+                }
+            }
+            None => service_url.clone(),
+        };
+        let forwarded = connect_url != *service_url;
+        let mut builder = pulsar::Pulsar::builder(connect_url, pulsar::TokioExecutor);
+        builder = match &credentials {
+            PulsarCredentials::None => builder,
+            PulsarCredentials::Token(token) => builder.with_auth(pulsar::Authentication {
                 name: "token".to_string(),
                 data: token.as_bytes().to_vec(),
-            });
+            }),
+            PulsarCredentials::Basic { username, password } => {
+                builder.with_auth(pulsar::Authentication {
+                    name: "basic".to_string(),
+                    data: format!("{username}:{password}").into_bytes(),
+                })
+            }
+            PulsarCredentials::OAuth2 {
+                issuer_url,
+                credentials_url,
+                audience,
+                scope,
+            } => {
+                builder.with_auth_provider(OAuth2Authentication::client_credentials(OAuth2Params {
+                    issuer_url: issuer_url.clone(),
+                    credentials_url: credentials_url.clone(),
+                    audience: audience.clone(),
+                    scope: scope.clone(),
+                }))
+            }
+        };
+        if tls {
+            if let Some(ca_file) = security
+                .tls_ca_file
+                .as_deref()
+                .map(str::trim)
+                .filter(|path| !path.is_empty())
+            {
+                builder = match builder.with_certificate_chain_file(ca_file) {
+                    Ok(builder) => builder,
+                    Err(err) => {
+                        errors.push(format!("{service_url}: {ca_file}: {err}"));
+                        continue;
+                    }
+                };
+            }
+            if security.tls_allow_insecure {
+                tracing::warn!(service_url = %service_url, "Pulsar TLS certificate verification disabled");
+            }
+            builder = builder
+                .with_allow_insecure_connection(security.tls_allow_insecure)
+                .with_tls_hostname_verification_enabled(
+                    !security.tls_skip_hostname_verification && !forwarded,
+                );
         }
 
         match builder.build().await {
@@ -197,6 +312,171 @@ pub(super) async fn build_pulsar_client_with_fallbacks(
     Err(errors.join(" | "))
 }
 
+/// Topics checked per diagnostics run; the rest are reported as skipped
+const DIAGNOSTIC_TOPIC_LIMIT: usize = 20;
+
+/// Distinct service URLs of `configs` with the topics configured under each
+fn diagnostic_pulsar_targets(configs: &[ConfigItem]) -> Vec<(String, Vec<String>)> {
+    let mut targets: Vec<(String, Vec<String>)> = Vec::new();
+    for config in configs {
+        let service_url = config.service_url.trim();
+        if service_url.is_empty() {
+            continue;
+        }
+        let index = match targets.iter().position(|(url, _)| url == service_url) {
+            Some(index) => index,
+            None => {
+                targets.push((service_url.to_string(), Vec::new()));
+                targets.len() - 1
+            }
+        };
+        let topics = &mut targets[index].1;
+        let paths = config.details.iter().map(|detail| &detail.path).chain(
+            config
+                .topic_agents
+                .iter()
+                .flat_map(|agent| agent.topics.iter().map(|topic| &topic.path)),
+        );
+        for path in paths {
+            if path.contains("://") && !topics.contains(path) {
+                topics.push(path.clone());
+            }
+        }
+    }
+    targets
+}
+
+/// Pulsar steps of a connection diagnostics run: credentials, the service
+/// URL candidates of each config, a client per candidate (broker lookup),
+/// and whether the configured topics exist and have an owning broker.
+/// Runs in the tokio runtime.
+pub(super) async fn diagnose_pulsar(
+    configs: Vec<ConfigItem>,
+    options: PulsarConnectOptions,
+    mut report: DiagnosticReport,
+) -> DiagnosticReport {
+    use pulsar::message::proto::command_get_topics_of_namespace::Mode;
+
+    let targets = diagnostic_pulsar_targets(&configs);
+    if targets.is_empty() {
+        report.skip("Pulsar service URL", "no config item has a service URL");
+        return report;
+    }
+    let auth_mode = options.security.auth_mode.key();
+    let credentials = options.security.credentials(options.token.as_deref());
+    report.record(
+        "Pulsar credentials",
+        &credentials,
+        |credentials| match credentials {
+            PulsarCredentials::None => "no authentication".to_string(),
+            _ => format!("{auth_mode} resolved"),
+        },
+    );
+    if credentials.is_err() {
+        return report;
+    }
+
+    let mut checked_topics = 0;
+    for (raw_url, topics) in targets {
+        let candidates = pulsar_service_url_candidates(&raw_url);
+        if candidates.is_empty() {
+            report.fail(
+                format!("Pulsar service URL {raw_url}"),
+                "no usable broker address",
+            );
+            continue;
+        }
+        report.pass(
+            format!("Pulsar service URL {raw_url}"),
+            format!("candidates: {}", candidates.join(", ")),
+        );
+
+        let mut client = None;
+        for candidate in &candidates {
+            let name = format!("Pulsar broker {candidate}");
+            if client.is_some() {
+                report.skip(name, "an earlier candidate connected");
+                continue;
+            }
+            match build_pulsar_client_with_fallbacks(std::slice::from_ref(candidate), &options)
+                .await
+            {
+                Ok((connected, _)) => {
+                    report.pass(name, "connected");
+                    client = Some(connected);
+                }
+                Err(err) => report.fail(name, err),
+            }
+        }
+        let Some(client) = client else {
+            continue;
+        };
+
+        let mut namespaces: HashMap<(String, bool), Result<Vec<String>, String>> = HashMap::new();
+        for topic in topics {
+            if checked_topics == DIAGNOSTIC_TOPIC_LIMIT {
+                report.skip(
+                    format!("Topic {topic}"),
+                    format!("only the first {DIAGNOSTIC_TOPIC_LIMIT} topics are checked"),
+                );
+                continue;
+            }
+            checked_topics += 1;
+
+            let Some(namespace) = topic.split("://").nth(1).and_then(|rest| {
+                let mut parts = rest.splitn(3, '/');
+                Some(format!("{}/{}", parts.next()?, parts.next()?))
+            }) else {
+                report.fail(
+                    format!("Topic {topic}"),
+                    "not a tenant/namespace/topic path",
+                );
+                continue;
+            };
+            let persistent = !topic.starts_with("non-persistent://");
+            let listing = match namespaces.entry((namespace.clone(), persistent)) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let mode = if persistent {
+                        Mode::Persistent
+                    } else {
+                        Mode::NonPersistent
+                    };
+                    entry.insert(
+                        client
+                            .get_topics_of_namespace(namespace, mode)
+                            .await
+                            .map_err(|e| e.to_string()),
+                    )
+                }
+            };
+            let partition_prefix = format!("{topic}-partition-");
+            let exists = listing.as_ref().map(|topics| {
+                topics
+                    .iter()
+                    .any(|t| *t == topic || t.starts_with(&partition_prefix))
+            });
+            match exists {
+                Ok(true) => match client.lookup_topic(topic.clone()).await {
+                    Ok(address) => report.pass(
+                        format!("Topic {topic}"),
+                        format!("exists, owned by {}", address.broker_url),
+                    ),
+                    Err(err) => {
+                        report.fail(format!("Topic {topic}"), format!("lookup failed: {err}"))
+                    }
+                },
+                Ok(false) => report.fail(format!("Topic {topic}"), "not found on the broker"),
+                Err(err) => report.fail(
+                    format!("Topic {topic}"),
+                    format!("listing the namespace failed: {err}"),
+                ),
+            }
+        }
+    }
+    report
+}
+
 /// Events emitted by the background stream loop into the GPUI side.
 #[derive(Debug)]
 pub enum ServiceStreamEvent {
@@ -209,6 +489,8 @@ pub enum ServiceStreamEvent {
 pub struct ServicePublishRequest {
     pub device: String,
     pub record: SvrReqRecord,
+    /// Issued by the safety level of the server the request is sent to
+    pub permit: WritePermit,
 }
 
 /// Build the Pulsar payload for one service request.
@@ -346,11 +628,14 @@ pub fn json_value_to_any_value(value: &serde_json::Value) -> AnyValue {
     AnyValue { v: Some(v) }
 }
 
+/// Stream service responses of a topic on `server_id` and publish queued
+/// requests; requests whose permit was issued for another server are rejected
 pub async fn run_service_topic_stream(
+    server_id: String,
     service_url: String,
     request_topic: String,
     response_topic: String,
-    token: Option<String>,
+    pulsar_options: PulsarConnectOptions,
     mut stop: watch::Receiver<bool>,
     publish_rx: Receiver<ServicePublishRequest>,
     tx: Sender<ServiceStreamEvent>,
@@ -365,7 +650,7 @@ pub async fn run_service_topic_stream(
     }
 
     let (client, connected_service_url): (pulsar::Pulsar<_>, String) =
-        match build_pulsar_client_with_fallbacks(&service_urls, token.as_deref()).await {
+        match build_pulsar_client_with_fallbacks(&service_urls, &pulsar_options).await {
             Ok(client) => client,
             Err(e) => {
                 let _ = tx.send(ServiceStreamEvent::Error(format!("Pulsar 连接失败: {e}")));
@@ -425,6 +710,19 @@ pub async fn run_service_topic_stream(
         }
 
         while let Ok(req) = publish_rx.try_recv() {
+            if let Err(e) = req.permit.check_server_id(&server_id) {
+                let _ = tx.send(ServiceStreamEvent::Error(format!(
+                    "发送请求失败 ({}): {e}",
+                    req.device
+                )));
+                tracing::warn!(
+                    server_id = %server_id,
+                    permit_server_id = %req.permit.server_id(),
+                    device = %req.device,
+                    "rejected service request authorized for another server"
+                );
+                continue;
+            }
             let payload = build_service_request_payload(&req.device, &req.record);
             if let Err(e) = producer.send_non_blocking(payload).await {
                 let _ = tx.send(ServiceStreamEvent::Error(format!(
@@ -432,6 +730,7 @@ pub async fn run_service_topic_stream(
                     req.device
                 )));
                 tracing::error!(
+                    server_id = %server_id,
                     device = %req.device,
                     req_uuid = %req.record.req_serial_uuid,
                     imr = %req.record.imr,
@@ -439,6 +738,7 @@ pub async fn run_service_topic_stream(
                 );
             } else {
                 tracing::debug!(
+                    server_id = %server_id,
                     device = %req.device,
                     req_uuid = %req.record.req_serial_uuid,
                     imr = %req.record.imr,
@@ -579,11 +879,79 @@ mod tests {
         );
     }
 
+    #[test]
+    fn pulsar_service_url_candidates_keep_tls_scheme() {
+        assert_eq!(
+            pulsar_service_url_candidates("pulsar+ssl://host.orb.internal:6651,10.10.4.101:6651"),
+            vec![
+                "pulsar+ssl://host.orb.internal:6651".to_string(),
+                "pulsar+ssl://10.10.4.101:6651".to_string(),
+                "pulsar+ssl://127.0.0.1:6651".to_string(),
+                "pulsar+ssl://localhost:6651".to_string(),
+            ]
+        );
+    }
+
     #[test]
     fn pulsar_service_url_candidates_reject_bus_style_addresses() {
         assert!(pulsar_service_url_candidates("10.10.4.101:15000;10.10.4.102:15000").is_empty());
     }
 
+    #[test]
+    fn diagnostic_pulsar_targets_group_topics_by_service_url() {
+        use crate::connection::{DetailItem, TopicAgentItem, TopicDetail};
+
+        let config = |service_url: &str, detail: &str, agent_topic: &str| ConfigItem {
+            group_id: 1,
+            service_url: service_url.to_string(),
+            source: "CMC_a_sg.bus".to_string(),
+            details: vec![DetailItem {
+                index: 0,
+                path: detail.to_string(),
+                visibility: true,
+                group_id: 1,
+            }],
+            topic_agents: vec![TopicAgentItem {
+                agent_id: "agent".to_string(),
+                topics: vec![TopicDetail {
+                    index: 0,
+                    path: agent_topic.to_string(),
+                    visibility: true,
+                    topic_type: "prop".to_string(),
+                }],
+                group_id: 1,
+            }],
+        };
+        let targets = diagnostic_pulsar_targets(&[
+            config(
+                "pulsar://broker:6650",
+                "persistent://public/default/a",
+                "persistent://public/default/b",
+            ),
+            config(
+                " pulsar://broker:6650 ",
+                "bus-only",
+                "persistent://public/default/a",
+            ),
+            config(
+                "",
+                "persistent://public/default/c",
+                "persistent://public/default/c",
+            ),
+        ]);
+
+        assert_eq!(
+            targets,
+            vec![(
+                "pulsar://broker:6650".to_string(),
+                vec![
+                    "persistent://public/default/a".to_string(),
+                    "persistent://public/default/b".to_string(),
+                ],
+            )]
+        );
+    }
+
     #[test]
     fn parse_response_extracts_svr_resp() {
         let resp = SvrRespRecord {
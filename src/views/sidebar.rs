@@ -2,13 +2,15 @@
 //!
 //! Fixed-width navigation sidebar with route switching and connected servers.
 
+use super::write_guard::{safety_color, safety_label};
 use crate::assets::CustomIconName;
+use crate::connection::SshTunnelStatus;
 use crate::constants::SIDEBAR_WIDTH;
 use crate::helpers::ServerAction;
 use crate::states::{
     ConfigState, DfcAppState, DfcGlobalStore, KeysState, Route, i18n_common, i18n_sidebar,
 };
-use gpui::{AnyElement, Context, Entity, Subscription, Window, div, prelude::*, px};
+use gpui::{AnyElement, Context, Entity, Subscription, Task, Window, div, prelude::*, px};
 use gpui_component::{
     ActiveTheme, Icon, IconName,
     button::{Button, ButtonVariants},
@@ -30,6 +32,8 @@ pub struct DfcSidebar {
     keys_state: Entity<KeysState>,
     /// Subscriptions
     _subscriptions: Vec<Subscription>,
+    /// Re-renders when an SSH tunnel status changes
+    _tunnel_status_task: Task<()>,
 }
 
 impl DfcSidebar {
@@ -55,6 +59,7 @@ impl DfcSidebar {
         let config_state = store.config_state();
         let keys_state = store.keys_state();
         let current_route = store.read(cx).route();
+        let mut tunnel_revision = store.services().ssh_tunnels().revision();
 
         // Subscribe to route changes
         subscriptions.push(cx.observe(&app_state, |this, model, cx| {
@@ -77,12 +82,22 @@ impl DfcSidebar {
             cx.notify();
         }));
 
+        // Re-render on SSH tunnel status changes
+        let tunnel_status_task = cx.spawn(async move |this, cx| {
+            while tunnel_revision.changed().await.is_ok() {
+                if this.update(cx, |_, cx| cx.notify()).is_err() {
+                    break;
+                }
+            }
+        });
+
         Self {
             current_route,
             app_state,
             config_state,
             keys_state,
             _subscriptions: subscriptions,
+            _tunnel_status_task: tunnel_status_task,
         }
     }
 
@@ -237,23 +252,58 @@ impl DfcSidebar {
         let list_active = cx.theme().list_active;
         let list_active_border = cx.theme().list_active_border;
         let store = cx.global::<DfcGlobalStore>().clone();
+        let tunnel_status = store.services().ssh_tunnels().status(&server_id);
+        let safety = store
+            .read(cx)
+            .server(&server_id)
+            .map(|server| server.safety)
+            .filter(|safety| safety.is_protected());
         let server_id_for_edit = server_id.clone();
-        let server_id_for_reconnect = server_id;
+        let server_id_for_reconnect = server_id.clone();
+        let server_id_for_diagnose = server_id;
         let edit_label = i18n_common(cx, "edit");
         let reconnect_label = i18n_sidebar(cx, "reconnect");
+        let diagnose_label = i18n_sidebar(cx, "diagnose");
         let close_label = i18n_common(cx, "close");
 
+        let (tooltip, tunnel_color) = match &tunnel_status {
+            None => (server_name.clone(), None),
+            Some(SshTunnelStatus::Connected) => (
+                format!("{server_name}\n{}", i18n_sidebar(cx, "ssh_connected")),
+                Some(cx.theme().success),
+            ),
+            Some(SshTunnelStatus::Connecting) => (
+                format!("{server_name}\n{}", i18n_sidebar(cx, "ssh_connecting")),
+                Some(cx.theme().warning),
+            ),
+            Some(SshTunnelStatus::Failed(error)) => (
+                format!("{server_name}\n{}: {error}", i18n_sidebar(cx, "ssh_failed")),
+                Some(cx.theme().danger),
+            ),
+        };
+
+        let (tooltip, safety_color) = match safety {
+            Some(level) => (
+                format!("{tooltip}\n{}", safety_label(level, cx)),
+                Some(safety_color(level, cx)),
+            ),
+            None => (tooltip, None),
+        };
+
         let btn = Button::new(btn_id)
             .ghost()
             .w_full()
             .h(px(56.0))
-            .tooltip(server_name.clone())
+            .tooltip(tooltip)
             .child(
                 v_flex()
                     .items_center()
                     .justify_center()
                     .gap_1()
-                    .child(Icon::from(CustomIconName::DatabaseZap))
+                    .child(
+                        Icon::from(CustomIconName::DatabaseZap)
+                            .when_some(safety_color, |icon, color| icon.text_color(color)),
+                    )
                     .child(
                         Label::new(server_name)
                             .text_xs()
@@ -270,12 +320,39 @@ impl DfcSidebar {
                     .border_r_2()
                     .border_color(list_active_border)
             })
+            .relative()
             .child(btn)
+            // Protected servers carry a bar in the colour of their safety level
+            .when_some(safety_color, |this, color| {
+                this.child(
+                    div()
+                        .absolute()
+                        .top(px(8.0))
+                        .bottom(px(8.0))
+                        .left_0()
+                        .w(px(3.0))
+                        .rounded_r_sm()
+                        .bg(color),
+                )
+            })
+            .when_some(tunnel_color, |this, color| {
+                this.child(
+                    div()
+                        .absolute()
+                        .top(px(6.0))
+                        .right(px(10.0))
+                        .size(px(6.0))
+                        .rounded_full()
+                        .bg(color),
+                )
+            })
             .context_menu(move |menu, _, _| {
                 let store_for_edit = store.clone();
                 let store_for_reconnect = store.clone();
                 let sid_edit = server_id_for_edit.clone();
                 let sid_reconnect = server_id_for_reconnect.clone();
+                let store_for_diagnose = store.clone();
+                let sid_diagnose = server_id_for_diagnose.clone();
                 let on_close = on_close.clone();
 
                 menu.item(
@@ -294,6 +371,14 @@ impl DfcSidebar {
                             window.dispatch_action(Box::new(ServerAction::Reconnect), cx);
                         }),
                 )
+                .item(
+                    PopupMenuItem::new(diagnose_label.clone())
+                        .icon(Icon::new(IconName::Info))
+                        .on_click(move |_, window, cx| {
+                            store_for_diagnose.set_pending_server(sid_diagnose.clone());
+                            window.dispatch_action(Box::new(ServerAction::Diagnose), cx);
+                        }),
+                )
                 .item(
                     PopupMenuItem::new(close_label.clone())
                         .icon(Icon::new(IconName::Close))
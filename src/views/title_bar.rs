@@ -1,14 +1,16 @@
 //! Title Bar Component
 //!
-//! Custom title bar with settings menu and branding.
+//! Custom title bar with settings menu, branding and the safety level of the
+//! active server.
 
+use super::write_guard::render_safety_badge;
 use crate::assets::CustomIconName;
 use crate::helpers::MenuAction;
 use crate::states::{
     DfcGlobalStore, FontSize, FontSizeAction, LocaleAction, SettingsAction, ThemeAction,
     i18n_sidebar, i18n_update,
 };
-use gpui::{App, Context, Corner, Window, prelude::*};
+use gpui::{App, Context, Corner, Subscription, Window, prelude::*};
 use gpui_component::{
     Icon, IconName, Sizable, ThemeMode, TitleBar,
     button::{Button, ButtonVariants},
@@ -20,12 +22,44 @@ use gpui_component::{
 const GITHUB_REPOSITORY_URL: &str = "https://github.com/cyenxchen/dfc-gui";
 
 /// Title bar component
-pub struct DfcTitleBar;
+pub struct DfcTitleBar {
+    /// Re-render when the active server changes
+    _subscriptions: Vec<Subscription>,
+}
 
 impl DfcTitleBar {
     /// Create a new title bar
-    pub fn new(_window: &mut Window, _cx: &mut Context<Self>) -> Self {
-        Self
+    pub fn new(_window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let store = cx.global::<DfcGlobalStore>();
+        let app_state = store.app_state();
+        let keys_state = store.keys_state();
+        let subscriptions = vec![
+            cx.observe(&app_state, |_, _, cx| cx.notify()),
+            cx.observe(&keys_state, |_, _, cx| cx.notify()),
+        ];
+        Self {
+            _subscriptions: subscriptions,
+        }
+    }
+
+    /// Name and safety badge of the active server (selected in the config
+    /// view, or active in the keys browser); nothing when writes to it are
+    /// unrestricted
+    fn render_active_server(cx: &App) -> Option<impl IntoElement> {
+        let store = cx.global::<DfcGlobalStore>();
+        let server_id = store
+            .read(cx)
+            .selected_server_id()
+            .or_else(|| store.keys_state().read(cx).active_server_id())?;
+        let server = store.read(cx).server(server_id)?;
+        let badge = render_safety_badge(server.safety, cx)?;
+        Some(
+            h_flex()
+                .gap_2()
+                .items_center()
+                .child(Label::new(server.confirmation_name()).text_sm())
+                .child(badge),
+        )
     }
 
     /// Render the settings dropdown menu
@@ -121,7 +155,8 @@ impl Render for DfcTitleBar {
                                 cx.open_url(GITHUB_REPOSITORY_URL);
                             }),
                     )
-                    .child(Label::new("DFC-GUI").text_sm()),
+                    .child(Label::new("DFC-GUI").text_sm())
+                    .children(Self::render_active_server(cx)),
             )
             // Right side - settings and info
             .child(
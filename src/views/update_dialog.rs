@@ -1,6 +1,6 @@
 //! Standalone update dialog window.
 
-use crate::helpers::{WindowAction, handle_window_action};
+use crate::helpers::{WindowAction, format_bytes, handle_window_action};
 use crate::states::i18n_update;
 use crate::states::update::{
     DfcUpdateState, DfcUpdateStore, ReleaseInfo, UpdateStatus, check_for_updates, current_version,
@@ -77,12 +77,13 @@ impl UpdateDialog {
             );
         }
 
-        let mut layout = v_flex().gap_3().size_full().px_4().pb_4().pt(px(16.0));
-
-        #[cfg(target_os = "macos")]
-        {
-            layout = layout.pt(px(28.0));
-        }
+        // The macOS title bar overlaps the content
+        let top = if cfg!(target_os = "macos") {
+            28.0
+        } else {
+            16.0
+        };
+        let layout = v_flex().gap_3().size_full().px_4().pb_4().pt(px(top));
 
         let mut layout = layout
             .child(
@@ -480,20 +481,3 @@ pub fn open_update_dialog(cx: &mut App) {
         });
     }
 }
-
-fn format_bytes(bytes: u64) -> String {
-    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
-
-    let mut value = bytes as f64;
-    let mut unit = 0usize;
-    while value >= 1024.0 && unit < UNITS.len() - 1 {
-        value /= 1024.0;
-        unit += 1;
-    }
-
-    if unit == 0 {
-        format!("{bytes} {}", UNITS[unit])
-    } else {
-        format!("{value:.1} {}", UNITS[unit])
-    }
-}
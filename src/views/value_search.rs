@@ -0,0 +1,401 @@
+//! Value Search View
+//!
+//! "Find in values" job of the key browser:
+//! - Key pattern and search text (substring or regex, optionally case-sensitive)
+//! - Keys are scanned and their values searched in batches; matches stream in
+//! - Cancel keeps the matches found so far; clicking a match opens its key
+
+use crate::connection::ValueMatcher;
+use crate::states::{
+    DfcGlobalStore, KeysState, VALUE_SEARCH_BATCH_SIZE, ValueSearchState, ValueSearchStatus,
+};
+use gpui::{
+    AnyElement, App, Context, Entity, EventEmitter, SharedString, Subscription, Task, Window, div,
+    prelude::*, px,
+};
+use gpui_component::{
+    ActiveTheme, Icon, IconName, Sizable,
+    button::{Button, ButtonVariants},
+    checkbox::Checkbox,
+    h_flex,
+    input::{Input, InputEvent, InputState},
+    label::Label,
+    scroll::ScrollableElement,
+    v_flex,
+};
+use rust_i18n::t;
+use std::sync::Arc;
+
+/// Events emitted by the value search view
+#[derive(Debug, Clone)]
+pub enum ValueSearchEvent {
+    /// Show the value of a matched key
+    OpenKey(String),
+}
+
+/// Value search view component
+pub struct ValueSearchView {
+    /// Keys state entity (provides the active server)
+    keys_state: Entity<KeysState>,
+    /// Search progress and matches
+    search_state: Entity<ValueSearchState>,
+    /// Key pattern input (SCAN MATCH)
+    pattern_state: Entity<InputState>,
+    /// Search text input
+    needle_state: Entity<InputState>,
+    /// Treat the search text as a regular expression
+    regex: bool,
+    /// Match case
+    case_sensitive: bool,
+    /// Invalid search text message
+    input_error: Option<String>,
+    /// Running search loop; dropping it cancels the search
+    search_task: Option<Task<()>>,
+    /// Subscriptions
+    _subscriptions: Vec<Subscription>,
+}
+
+impl EventEmitter<ValueSearchEvent> for ValueSearchView {}
+
+impl ValueSearchView {
+    /// Create a new value search view
+    pub fn new(keys_state: Entity<KeysState>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let search_state = cx.new(|_| ValueSearchState::new());
+        let mut subscriptions = Vec::new();
+
+        subscriptions.push(cx.observe(&search_state, |_this, _model, cx| {
+            cx.notify();
+        }));
+
+        // Matches of another server are meaningless after switching
+        subscriptions.push(cx.observe(&keys_state, |this, model, cx| {
+            let active = model.read(cx).active_server_id().map(str::to_string);
+            let search_server = this.search_state.read(cx).server_id().map(str::to_string);
+            if search_server.is_some() && search_server != active {
+                this.search_task = None;
+                this.search_state.update(cx, |state, cx| state.clear(cx));
+            }
+        }));
+
+        let locale = cx.global::<DfcGlobalStore>().read(cx).locale().to_string();
+        let pattern_state = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder(t!("value_search.pattern_placeholder", locale = &locale).to_string())
+        });
+        let needle_state = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder(t!("value_search.needle_placeholder", locale = &locale).to_string())
+        });
+
+        for input in [&pattern_state, &needle_state] {
+            subscriptions.push(cx.subscribe(input, |this, _state, event, cx| match event {
+                InputEvent::PressEnter { .. } => this.start(cx),
+                InputEvent::Change if this.input_error.is_some() => {
+                    this.input_error = None;
+                    cx.notify();
+                }
+                _ => {}
+            }));
+        }
+
+        Self {
+            keys_state,
+            search_state,
+            pattern_state,
+            needle_state,
+            regex: false,
+            case_sensitive: false,
+            input_error: None,
+            search_task: None,
+            _subscriptions: subscriptions,
+        }
+    }
+
+    /// Get the locale string
+    fn locale(&self, cx: &App) -> String {
+        cx.global::<DfcGlobalStore>().read(cx).locale().to_string()
+    }
+
+    /// Start a search, replacing any running one
+    fn start(&mut self, cx: &mut Context<Self>) {
+        let Some(server_id) = self
+            .keys_state
+            .read(cx)
+            .active_server_id()
+            .map(str::to_string)
+        else {
+            return;
+        };
+        let needle = self.needle_state.read(cx).value().to_string();
+        let matcher = match ValueMatcher::new(&needle, self.regex, self.case_sensitive) {
+            Ok(matcher) => Arc::new(matcher),
+            Err(e) => {
+                self.input_error = Some(e);
+                cx.notify();
+                return;
+            }
+        };
+        let pattern = match self.pattern_state.read(cx).value().trim() {
+            "" => "*".to_string(),
+            pattern => pattern.to_string(),
+        };
+        self.input_error = None;
+
+        let generation = self
+            .search_state
+            .update(cx, |state, cx| state.begin(server_id.clone(), cx));
+        let store = cx.global::<DfcGlobalStore>().clone();
+        let search_state = self.search_state.clone();
+        let task = cx.spawn(async move |handle, cx| {
+            let redis = store.services().redis();
+            let mut cursor = 0;
+            loop {
+                let result = redis
+                    .search_values(&pattern, cursor, VALUE_SEARCH_BATCH_SIZE, matcher.clone())
+                    .await;
+                let keep_running = search_state
+                    .update(cx, |state, cx| {
+                        if state.generation() != generation {
+                            tracing::info!(server_id = %server_id, "Ignoring stale value search batch");
+                            return false;
+                        }
+                        match result {
+                            Ok(batch) => {
+                                cursor = batch.next_cursor;
+                                state.push_batch(batch, cx)
+                            }
+                            Err(e) => {
+                                tracing::error!("Value search failed: {}", e);
+                                state.set_error(e.to_string(), cx);
+                                false
+                            }
+                        }
+                    })
+                    .unwrap_or(false);
+                if !keep_running {
+                    break;
+                }
+            }
+
+            let _ = handle.update(cx, |this, cx| {
+                if this.search_state.read(cx).generation() == generation {
+                    this.search_task = None;
+                }
+            });
+        });
+        self.search_task = Some(task);
+    }
+
+    /// Cancel the running search
+    fn cancel(&mut self, cx: &mut Context<Self>) {
+        self.search_task = None;
+        self.search_state.update(cx, |state, cx| state.cancel(cx));
+    }
+
+    /// Render the query inputs and start/cancel button
+    fn render_toolbar(&self, running: bool, cx: &mut Context<Self>) -> impl IntoElement {
+        let locale = self.locale(cx);
+
+        let action = if running {
+            Button::new("value-search-cancel")
+                .small()
+                .icon(IconName::CircleX)
+                .label(t!("value_search.cancel", locale = &locale).to_string())
+                .on_click(cx.listener(|this, _, _, cx| this.cancel(cx)))
+        } else {
+            Button::new("value-search-start")
+                .small()
+                .primary()
+                .icon(IconName::Search)
+                .label(t!("value_search.start", locale = &locale).to_string())
+                .on_click(cx.listener(|this, _, _, cx| this.start(cx)))
+        };
+
+        v_flex()
+            .w_full()
+            .gap_2()
+            .child(
+                h_flex()
+                    .w_full()
+                    .gap_2()
+                    .child(
+                        div()
+                            .w(px(200.0))
+                            .child(Input::new(&self.pattern_state).small()),
+                    )
+                    .child(
+                        div().flex_1().child(
+                            Input::new(&self.needle_state).small().prefix(
+                                Icon::new(IconName::Search)
+                                    .small()
+                                    .text_color(cx.theme().muted_foreground),
+                            ),
+                        ),
+                    )
+                    .child(action),
+            )
+            .child(
+                h_flex()
+                    .gap_4()
+                    .items_center()
+                    .child(
+                        Checkbox::new("value-search-regex")
+                            .label(t!("value_search.regex", locale = &locale).to_string())
+                            .checked(self.regex)
+                            .on_click(cx.listener(|this, checked: &bool, _, cx| {
+                                this.regex = *checked;
+                                cx.notify();
+                            })),
+                    )
+                    .child(
+                        Checkbox::new("value-search-case")
+                            .label(t!("value_search.case_sensitive", locale = &locale).to_string())
+                            .checked(self.case_sensitive)
+                            .on_click(cx.listener(|this, checked: &bool, _, cx| {
+                                this.case_sensitive = *checked;
+                                cx.notify();
+                            })),
+                    )
+                    .when_some(self.input_error.clone(), |this, error| {
+                        this.child(Label::new(error).text_xs().text_color(cx.theme().danger))
+                    }),
+            )
+    }
+
+    /// Render the progress line
+    fn render_status(&self, cx: &App) -> impl IntoElement {
+        let locale = self.locale(cx);
+        let state = self.search_state.read(cx);
+        let progress = t!(
+            "value_search.progress",
+            scanned = state.scanned(),
+            count = state.matches().len(),
+            locale = &locale
+        )
+        .to_string();
+        let (status, color) = match state.status() {
+            ValueSearchStatus::Idle => (None, cx.theme().muted_foreground),
+            ValueSearchStatus::Running => (
+                Some(t!("value_search.running", locale = &locale).to_string()),
+                cx.theme().muted_foreground,
+            ),
+            ValueSearchStatus::Finished => (
+                Some(t!("value_search.finished", locale = &locale).to_string()),
+                cx.theme().success,
+            ),
+            ValueSearchStatus::Truncated => (
+                Some(
+                    t!(
+                        "value_search.truncated",
+                        count = state.matches().len(),
+                        locale = &locale
+                    )
+                    .to_string(),
+                ),
+                cx.theme().warning,
+            ),
+            ValueSearchStatus::Cancelled => (
+                Some(t!("value_search.cancelled", locale = &locale).to_string()),
+                cx.theme().muted_foreground,
+            ),
+            ValueSearchStatus::Failed(error) => (
+                Some(t!("value_search.failed", error = error, locale = &locale).to_string()),
+                cx.theme().danger,
+            ),
+        };
+        let idle = *state.status() == ValueSearchStatus::Idle;
+
+        h_flex()
+            .gap_2()
+            .when(!idle, |this| {
+                this.child(
+                    Label::new(progress)
+                        .text_xs()
+                        .text_color(cx.theme().muted_foreground),
+                )
+            })
+            .when_some(status, |this, status| {
+                this.child(Label::new(status).text_xs().text_color(color))
+            })
+    }
+
+    /// Render one match row
+    fn render_match(&self, ix: usize, cx: &mut Context<Self>) -> AnyElement {
+        let state = self.search_state.read(cx);
+        let Some(item) = state.matches().get(ix).cloned() else {
+            return div().into_any_element();
+        };
+        let open_key = item.key.clone();
+
+        v_flex()
+            .id(SharedString::from(format!("value-search-match-{ix}")))
+            .w_full()
+            .px_2()
+            .py_1()
+            .gap_1()
+            .rounded_md()
+            .cursor_pointer()
+            .hover(|this| this.bg(cx.theme().accent.opacity(0.5)))
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        Label::new(item.key)
+                            .text_sm()
+                            .text_color(cx.theme().foreground),
+                    )
+                    .when_some(item.field, |this, field| {
+                        this.child(Label::new(field).text_sm().text_color(cx.theme().info))
+                    }),
+            )
+            .child(
+                Label::new(item.excerpt)
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground),
+            )
+            .on_click(cx.listener(move |_this, _, _, cx| {
+                cx.emit(ValueSearchEvent::OpenKey(open_key.clone()));
+            }))
+            .into_any_element()
+    }
+}
+
+impl Render for ValueSearchView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        if self.keys_state.read(cx).active_server_id().is_none() {
+            let locale = self.locale(cx);
+            return div()
+                .size_full()
+                .flex()
+                .items_center()
+                .justify_center()
+                .child(
+                    Label::new(t!("console.no_server", locale = &locale).to_string())
+                        .text_color(cx.theme().muted_foreground),
+                )
+                .into_any_element();
+        }
+
+        let running = self.search_state.read(cx).is_running();
+        let match_count = self.search_state.read(cx).matches().len();
+        let rows: Vec<AnyElement> = (0..match_count)
+            .map(|ix| self.render_match(ix, cx))
+            .collect();
+
+        v_flex()
+            .size_full()
+            .p_3()
+            .gap_2()
+            .child(self.render_toolbar(running, cx))
+            .child(self.render_status(cx))
+            .child(
+                div()
+                    .id("value-search-results")
+                    .flex_1()
+                    .w_full()
+                    .child(v_flex().w_full().children(rows))
+                    .overflow_y_scrollbar(),
+            )
+            .into_any_element()
+    }
+}
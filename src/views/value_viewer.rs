@@ -0,0 +1,367 @@
+//! String Value Viewer
+//!
+//! Formatted display of the selected string value:
+//! - Format selector; auto-detected unless a format is remembered for the key pattern
+//! - JSON, protobuf and MessagePack values as a collapsible tree with JSONPath search
+//! - Text, hex dump and base64 payloads as plain text
+
+use crate::connection::{
+    FormattedValue, RedisKeyValue, ValueFormat, json_child_path, json_path_query, json_summary,
+    key_pattern,
+};
+use crate::states::{DfcGlobalStore, KeysState, update_app_state_and_save};
+use gpui::{
+    AnyElement, App, Context, Entity, SharedString, Subscription, Window, div, prelude::*, px,
+};
+use gpui_component::{
+    ActiveTheme, Icon, IconName, Selectable, Sizable,
+    button::{Button, ButtonVariants},
+    h_flex,
+    input::{Input, InputEvent, InputState},
+    label::Label,
+    v_flex,
+};
+use rust_i18n::t;
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// Indentation per JSON tree level in pixels
+const TREE_INDENT: f32 = 16.0;
+
+/// Formatted value of one key, reused until the key, bytes or format change
+struct FormatCache {
+    key: String,
+    format: ValueFormat,
+    source: Vec<u8>,
+    result: Result<FormattedValue, String>,
+}
+
+/// String value viewer component
+pub struct ValueViewerView {
+    /// Keys state entity (provides the selected key and value)
+    keys_state: Entity<KeysState>,
+    /// JSONPath query input
+    json_path_state: Entity<InputState>,
+    /// Applied JSONPath query (empty shows the whole tree)
+    json_path: String,
+    /// Collapsed tree nodes, keyed by JSONPath
+    collapsed: HashSet<String>,
+    /// Last formatted value
+    cache: Option<FormatCache>,
+    /// Subscriptions
+    _subscriptions: Vec<Subscription>,
+}
+
+impl ValueViewerView {
+    /// Create a new value viewer
+    pub fn new(keys_state: Entity<KeysState>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let mut subscriptions = Vec::new();
+
+        subscriptions.push(cx.observe(&keys_state, |_this, _model, cx| {
+            cx.notify();
+        }));
+
+        let json_path_state = cx.new(|cx| {
+            let locale = cx.global::<DfcGlobalStore>().read(cx).locale().to_string();
+            let placeholder =
+                t!("value_format.json_path_placeholder", locale = &locale).to_string();
+            InputState::new(window, cx)
+                .clean_on_escape()
+                .placeholder(placeholder)
+        });
+
+        subscriptions.push(cx.subscribe(&json_path_state, |this, state, event, cx| {
+            let query = state.read(cx).value().trim().to_string();
+            // Clearing the input shows the whole tree again
+            let cleared = matches!(event, InputEvent::Change) && query.is_empty();
+            if matches!(event, InputEvent::PressEnter { .. }) || cleared {
+                this.json_path = query;
+                cx.notify();
+            }
+        }));
+
+        Self {
+            keys_state,
+            json_path_state,
+            json_path: String::new(),
+            collapsed: HashSet::new(),
+            cache: None,
+            _subscriptions: subscriptions,
+        }
+    }
+
+    /// Get the locale string
+    fn locale(&self, cx: &App) -> String {
+        cx.global::<DfcGlobalStore>().read(cx).locale().to_string()
+    }
+
+    /// Key pattern the selected key's format is remembered under
+    fn pattern(key: &str, cx: &App) -> String {
+        let delimiters = cx
+            .global::<DfcGlobalStore>()
+            .read(cx)
+            .key_tree_delimiters()
+            .to_string();
+        key_pattern(key, &delimiters)
+    }
+
+    /// Remember a format for the selected key's pattern
+    fn choose_format(&mut self, format: ValueFormat, cx: &mut Context<Self>) {
+        let Some(key) = self.keys_state.read(cx).selected_key().map(str::to_string) else {
+            return;
+        };
+        let pattern = Self::pattern(&key, cx);
+        update_app_state_and_save(cx, "set_value_format", move |state, _| {
+            state.set_value_format(pattern.clone(), format);
+        });
+        self.cache = None;
+        cx.notify();
+    }
+
+    /// Format the selected value, reusing the cached result when unchanged
+    fn refresh_cache(&mut self, key: &str, bytes: &[u8], cx: &App) {
+        let remembered = cx
+            .global::<DfcGlobalStore>()
+            .read(cx)
+            .value_format(&Self::pattern(key, cx));
+        let same_key = self.cache.as_ref().is_some_and(|cache| cache.key == key);
+        let unchanged = self.cache.as_ref().is_some_and(|cache| {
+            same_key
+                && cache.source == bytes
+                && remembered.is_none_or(|format| format == cache.format)
+        });
+        if unchanged {
+            return;
+        }
+
+        if !same_key {
+            self.collapsed.clear();
+        }
+        let format = remembered.unwrap_or_else(|| ValueFormat::detect(bytes));
+        self.cache = Some(FormatCache {
+            key: key.to_string(),
+            format,
+            source: bytes.to_vec(),
+            result: format.render(bytes),
+        });
+    }
+
+    fn toggle_node(&mut self, path: String, cx: &mut Context<Self>) {
+        if !self.collapsed.remove(&path) {
+            self.collapsed.insert(path);
+        }
+        cx.notify();
+    }
+
+    /// Render the format selector
+    fn render_toolbar(&self, current: ValueFormat, cx: &mut Context<Self>) -> impl IntoElement {
+        let locale = self.locale(cx);
+        h_flex()
+            .gap_1()
+            .flex_wrap()
+            .items_center()
+            .child(
+                Label::new(t!("value_format.format", locale = &locale).to_string())
+                    .text_sm()
+                    .text_color(cx.theme().muted_foreground),
+            )
+            .children(ValueFormat::ALL.into_iter().map(|format| {
+                Button::new(SharedString::from(format!("value-format-{format:?}")))
+                    .ghost()
+                    .xsmall()
+                    .selected(format == current)
+                    .label(t!(format.label_key(), locale = &locale).to_string())
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        this.choose_format(format, cx);
+                    }))
+            }))
+    }
+
+    /// Render a JSON node and, if expanded, its children
+    fn push_tree_rows(
+        &self,
+        path: String,
+        label: Option<String>,
+        value: &Value,
+        depth: usize,
+        rows: &mut Vec<AnyElement>,
+        cx: &mut Context<Self>,
+    ) {
+        let nested = matches!(value, Value::Object(map) if !map.is_empty())
+            || matches!(value, Value::Array(items) if !items.is_empty());
+        let expanded = nested && !self.collapsed.contains(&path);
+        let color = match value {
+            Value::Null => cx.theme().muted_foreground,
+            Value::Bool(_) | Value::Number(_) => cx.theme().info,
+            Value::String(_) => cx.theme().success,
+            Value::Array(_) | Value::Object(_) => cx.theme().muted_foreground,
+        };
+
+        let mut row = h_flex()
+            .id(SharedString::from(format!("json-node-{path}")))
+            .w_full()
+            .pl(px(depth as f32 * TREE_INDENT))
+            .gap_1()
+            .items_start()
+            .child(div().w(px(12.0)).flex_shrink_0().when(nested, |this| {
+                this.child(
+                    Icon::new(if expanded {
+                        IconName::ChevronDown
+                    } else {
+                        IconName::ChevronRight
+                    })
+                    .size_3()
+                    .text_color(cx.theme().muted_foreground),
+                )
+            }))
+            .when_some(label, |this, label| {
+                this.child(
+                    Label::new(label)
+                        .text_sm()
+                        .text_color(cx.theme().muted_foreground),
+                )
+            })
+            .child(
+                Label::new(json_summary(value))
+                    .text_sm()
+                    .text_color(color)
+                    .flex_1(),
+            );
+        if nested {
+            let toggle_path = path.clone();
+            row = row
+                .cursor_pointer()
+                .hover(|this| this.bg(cx.theme().accent.opacity(0.5)))
+                .on_click(cx.listener(move |this, _, _, cx| {
+                    this.toggle_node(toggle_path.clone(), cx);
+                }));
+        }
+        rows.push(row.into_any_element());
+
+        if !expanded {
+            return;
+        }
+        match value {
+            Value::Object(map) => {
+                for (key, child) in map {
+                    let child_path = json_child_path(&path, key);
+                    let label = format!("{key}:");
+                    self.push_tree_rows(child_path, Some(label), child, depth + 1, rows, cx);
+                }
+            }
+            Value::Array(items) => {
+                for (ix, child) in items.iter().enumerate() {
+                    let label = format!("[{ix}]");
+                    let child_path = format!("{path}[{ix}]");
+                    self.push_tree_rows(child_path, Some(label), child, depth + 1, rows, cx);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Render a tree, or the JSONPath matches when a query is applied
+    fn render_tree(&self, root: &Value, cx: &mut Context<Self>) -> AnyElement {
+        let locale = self.locale(cx);
+        let mut rows = Vec::new();
+        let mut status = None;
+
+        if self.json_path.is_empty() {
+            self.push_tree_rows("$".to_string(), None, root, 0, &mut rows, cx);
+        } else {
+            match json_path_query(root, &self.json_path) {
+                Ok(matches) => {
+                    status = Some((
+                        t!(
+                            "value_format.matches",
+                            count = matches.len(),
+                            locale = &locale
+                        )
+                        .to_string(),
+                        cx.theme().muted_foreground,
+                    ));
+                    for (path, value) in matches {
+                        self.push_tree_rows(path.clone(), Some(path), value, 0, &mut rows, cx);
+                    }
+                }
+                Err(e) => status = Some((e, cx.theme().danger)),
+            }
+        }
+
+        v_flex()
+            .w_full()
+            .gap_2()
+            .child(
+                Input::new(&self.json_path_state).small().prefix(
+                    Icon::new(IconName::Search)
+                        .small()
+                        .text_color(cx.theme().muted_foreground),
+                ),
+            )
+            .when_some(status, |this, (message, color)| {
+                this.child(Label::new(message).text_xs().text_color(color))
+            })
+            .child(
+                v_flex()
+                    .w_full()
+                    .p_2()
+                    .rounded_md()
+                    .bg(cx.theme().secondary)
+                    .children(rows),
+            )
+            .into_any_element()
+    }
+}
+
+impl Render for ValueViewerView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let keys_state = self.keys_state.read(cx);
+        let key = keys_state.selected_key().unwrap_or_default();
+        let bytes: &[u8] = match keys_state.selected_value() {
+            RedisKeyValue::String(value) => value.as_bytes(),
+            RedisKeyValue::Bytes(value) => value,
+            _ => return div().into_any_element(),
+        };
+        self.refresh_cache(key, bytes, cx);
+        let Some(cache) = self.cache.take() else {
+            return div().into_any_element();
+        };
+
+        let toolbar = self.render_toolbar(cache.format, cx).into_any_element();
+        let body = match &cache.result {
+            Ok(FormattedValue::Tree(root)) => self.render_tree(root, cx),
+            Ok(FormattedValue::Text(text)) => div()
+                .w_full()
+                .p_2()
+                .rounded_md()
+                .bg(cx.theme().secondary)
+                .font_family(cx.theme().mono_font_family.clone())
+                .child(Label::new(text.clone()).text_sm())
+                .into_any_element(),
+            Err(e) => {
+                let locale = self.locale(cx);
+                let format = t!(cache.format.label_key(), locale = &locale);
+                Label::new(
+                    t!(
+                        "value_format.decode_failed",
+                        format = format,
+                        error = e,
+                        locale = &locale
+                    )
+                    .to_string(),
+                )
+                .text_sm()
+                .text_color(cx.theme().danger)
+                .into_any_element()
+            }
+        };
+        self.cache = Some(cache);
+
+        v_flex()
+            .w_full()
+            .gap_2()
+            .child(toolbar)
+            .child(body)
+            .into_any_element()
+    }
+}
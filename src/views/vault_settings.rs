@@ -0,0 +1,264 @@
+//! Credential Vault Settings
+//!
+//! Settings section of the vault holding the key of the stored secrets:
+//! - Unlock a passphrase vault for the session, or lock it again
+//! - Set, change or remove the master passphrase (secrets are re-encrypted)
+
+use crate::helpers::{
+    MIN_PASSPHRASE_LEN, VaultMode, lock_vault, unlock_vault, vault_locked, vault_mode,
+};
+use crate::states::{DfcAppState, DfcGlobalStore};
+use gpui::{App, Context, Entity, EventEmitter, Window, prelude::*};
+use gpui_component::{
+    ActiveTheme, Sizable,
+    button::{Button, ButtonVariants},
+    h_flex,
+    input::{Input, InputState},
+    label::Label,
+    v_flex,
+};
+use rust_i18n::t;
+
+/// Events emitted by the vault settings view
+#[derive(Debug, Clone)]
+pub enum VaultSettingsEvent {
+    /// The vault was unlocked, locked or re-keyed; stored secrets must be reloaded
+    Changed,
+}
+
+/// Vault status with unlock and passphrase management
+pub struct VaultSettingsView {
+    app_state: Entity<DfcAppState>,
+    /// Passphrase to unlock with, or the new passphrase
+    passphrase_state: Entity<InputState>,
+    /// Repeated new passphrase
+    confirm_state: Entity<InputState>,
+    /// Result of the last action, and whether it failed
+    message: Option<(String, bool)>,
+}
+
+impl EventEmitter<VaultSettingsEvent> for VaultSettingsView {}
+
+impl VaultSettingsView {
+    /// Create a new vault settings view
+    pub fn new(
+        app_state: Entity<DfcAppState>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let passphrase_state = cx.new(|cx| InputState::new(window, cx).masked(true));
+        let confirm_state = cx.new(|cx| InputState::new(window, cx).masked(true));
+        Self {
+            app_state,
+            passphrase_state,
+            confirm_state,
+            message: None,
+        }
+    }
+
+    /// Get the locale string
+    fn locale(&self, cx: &App) -> String {
+        cx.global::<DfcGlobalStore>().read(cx).locale().to_string()
+    }
+
+    /// Empty both inputs
+    fn clear_inputs(&self, window: &mut Window, cx: &mut Context<Self>) {
+        for state in [&self.passphrase_state, &self.confirm_state] {
+            state.update(cx, |input, cx| input.set_value("", window, cx));
+        }
+    }
+
+    /// Show the outcome of an action and reload secrets after a success
+    fn finish(
+        &mut self,
+        result: crate::error::Result<()>,
+        success_key: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let locale = self.locale(cx);
+        self.message = Some(match result {
+            Ok(()) => {
+                self.clear_inputs(window, cx);
+                cx.emit(VaultSettingsEvent::Changed);
+                (t!(success_key, locale = &locale).to_string(), false)
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, "Credential vault action failed");
+                (e.to_string(), true)
+            }
+        });
+        cx.notify();
+    }
+
+    fn unlock(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let passphrase = self.passphrase_state.read(cx).value().to_string();
+        self.finish(unlock_vault(&passphrase), "vault.unlocked", window, cx);
+    }
+
+    fn lock(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        lock_vault();
+        self.finish(Ok(()), "vault.locked_done", window, cx);
+    }
+
+    /// Switch to the passphrase in the inputs, or back to a key file with `remove`
+    fn change_passphrase(&mut self, remove: bool, window: &mut Window, cx: &mut Context<Self>) {
+        let locale = self.locale(cx);
+        let passphrase = self.passphrase_state.read(cx).value().to_string();
+        let confirm = self.confirm_state.read(cx).value().to_string();
+        if !remove {
+            let problem = if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+                Some(t!(
+                    "vault.too_short",
+                    min = MIN_PASSPHRASE_LEN,
+                    locale = &locale
+                ))
+            } else if passphrase != confirm {
+                Some(t!("vault.mismatch", locale = &locale))
+            } else {
+                None
+            };
+            if let Some(problem) = problem {
+                self.message = Some((problem.to_string(), true));
+                cx.notify();
+                return;
+            }
+        }
+        let new_passphrase = (!remove).then_some(passphrase.as_str());
+        let result = self
+            .app_state
+            .update(cx, |state, _| state.change_vault_key(new_passphrase));
+        let success_key = if remove {
+            "vault.removed"
+        } else {
+            "vault.changed"
+        };
+        self.finish(result, success_key, window, cx);
+    }
+}
+
+impl Render for VaultSettingsView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let locale = self.locale(cx);
+        let locked = vault_locked();
+        let mode = vault_mode();
+        let status = match (mode, locked) {
+            (None, _) => t!("vault.status_unavailable", locale = &locale),
+            (Some(_), true) => t!("vault.status_locked", locale = &locale),
+            (Some(VaultMode::Passphrase), false) => t!("vault.status_passphrase", locale = &locale),
+            (Some(VaultMode::KeyFile), false) => t!("vault.status_key_file", locale = &locale),
+        };
+        let has_passphrase = mode == Some(VaultMode::Passphrase);
+
+        let actions = if locked {
+            h_flex()
+                .gap_2()
+                .child(
+                    v_flex()
+                        .flex_1()
+                        .child(Input::new(&self.passphrase_state).mask_toggle()),
+                )
+                .child(
+                    Button::new("vault-unlock")
+                        .small()
+                        .primary()
+                        .label(t!("vault.unlock", locale = &locale).to_string())
+                        .on_click(cx.listener(|this, _, window, cx| this.unlock(window, cx))),
+                )
+                .into_any_element()
+        } else {
+            let set_label = if has_passphrase {
+                t!("vault.change_passphrase", locale = &locale)
+            } else {
+                t!("vault.set_passphrase", locale = &locale)
+            };
+            v_flex()
+                .gap_2()
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(
+                            v_flex()
+                                .flex_1()
+                                .gap_1()
+                                .child(
+                                    Label::new(
+                                        t!("vault.new_passphrase", locale = &locale).to_string(),
+                                    )
+                                    .text_xs(),
+                                )
+                                .child(Input::new(&self.passphrase_state).mask_toggle()),
+                        )
+                        .child(
+                            v_flex()
+                                .flex_1()
+                                .gap_1()
+                                .child(
+                                    Label::new(
+                                        t!("vault.confirm_passphrase", locale = &locale)
+                                            .to_string(),
+                                    )
+                                    .text_xs(),
+                                )
+                                .child(Input::new(&self.confirm_state).mask_toggle()),
+                        ),
+                )
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(
+                            Button::new("vault-set-passphrase")
+                                .small()
+                                .primary()
+                                .label(set_label.to_string())
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.change_passphrase(false, window, cx)
+                                })),
+                        )
+                        .when(has_passphrase, |this| {
+                            this.child(
+                                Button::new("vault-remove-passphrase")
+                                    .small()
+                                    .label(
+                                        t!("vault.remove_passphrase", locale = &locale).to_string(),
+                                    )
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.change_passphrase(true, window, cx)
+                                    })),
+                            )
+                            .child(
+                                Button::new("vault-lock")
+                                    .small()
+                                    .label(t!("vault.lock", locale = &locale).to_string())
+                                    .on_click(
+                                        cx.listener(|this, _, window, cx| this.lock(window, cx)),
+                                    ),
+                            )
+                        }),
+                )
+                .into_any_element()
+        };
+
+        v_flex()
+            .w_full()
+            .gap_2()
+            .child(Label::new(t!("vault.title", locale = &locale).to_string()).text_sm())
+            .child(
+                Label::new(status.to_string())
+                    .text_xs()
+                    .text_color(if locked {
+                        cx.theme().danger
+                    } else {
+                        cx.theme().muted_foreground
+                    }),
+            )
+            .child(actions)
+            .when_some(self.message.clone(), |this, (message, failed)| {
+                this.child(Label::new(message).text_xs().text_color(if failed {
+                    cx.theme().danger
+                } else {
+                    cx.theme().success
+                }))
+            })
+    }
+}
@@ -0,0 +1,169 @@
+//! Write Guard
+//!
+//! Applies the safety level of a server before a write or publish:
+//! - Read-only servers reject the write straight away
+//! - Confirm-every-write servers ask for the server name to be typed
+//! - Unrestricted servers get their permit without a prompt
+
+use crate::connection::{DfcServerConfig, SafetyLevel, WritePermit};
+use crate::error::Result;
+use crate::states::DfcGlobalStore;
+use gpui::{App, Context, Entity, SharedString, Window, div, prelude::*, px};
+use gpui_component::{
+    ActiveTheme, WindowExt,
+    input::{Input, InputState},
+    label::Label,
+    v_flex,
+};
+use rust_i18n::t;
+use std::rc::Rc;
+
+/// Ask for a write permit on `server` and pass the outcome to `on_result`.
+///
+/// `action` describes the write (a command line or a service request) in the
+/// confirmation prompt. Cancelling the prompt calls nothing.
+pub(crate) fn guard_write<F>(
+    server: &DfcServerConfig,
+    action: String,
+    window: &mut Window,
+    cx: &mut App,
+    on_result: F,
+) where
+    F: Fn(Result<WritePermit>, &mut Window, &mut App) + 'static,
+{
+    if !server.needs_write_confirmation() {
+        on_result(server.authorize_write(None), window, cx);
+        return;
+    }
+
+    let locale = cx.global::<DfcGlobalStore>().read(cx).locale().to_string();
+    let title = t!("write_guard.title", locale = &locale).to_string();
+    let view = cx.new(|cx| WriteConfirmView::new(server.confirmation_name(), action, window, cx));
+    let server = server.clone();
+    let on_result = Rc::new(on_result);
+    window.open_dialog(cx, move |dialog, _, _| {
+        let view = view.clone();
+        let server = server.clone();
+        let on_result = on_result.clone();
+        dialog
+            .confirm()
+            .title(title.clone())
+            .width(px(480.))
+            .child(view.clone())
+            .on_ok(move |_, window, cx| {
+                let typed = view.read(cx).typed(cx);
+                match server.authorize_write(Some(&typed)) {
+                    Ok(permit) => {
+                        on_result(Ok(permit), window, cx);
+                        true
+                    }
+                    Err(e) => {
+                        view.update(cx, |this, cx| {
+                            this.error = Some(e.to_string());
+                            cx.notify();
+                        });
+                        false
+                    }
+                }
+            })
+    });
+}
+
+/// Colour of a safety level badge
+pub(crate) fn safety_color(level: SafetyLevel, cx: &App) -> gpui::Hsla {
+    match level {
+        SafetyLevel::ReadOnly => cx.theme().info,
+        SafetyLevel::ConfirmWrites => cx.theme().danger,
+        SafetyLevel::Unrestricted => cx.theme().muted_foreground,
+    }
+}
+
+/// Short translated name of a safety level
+pub(crate) fn safety_label(level: SafetyLevel, cx: &App) -> SharedString {
+    let locale = cx.global::<DfcGlobalStore>().read(cx).locale().to_string();
+    t!(
+        format!("write_guard.level_{}", level.as_str()),
+        locale = &locale
+    )
+    .into()
+}
+
+/// Outlined badge of a server card, list row or header
+pub(crate) fn render_badge(label: SharedString, color: gpui::Hsla) -> gpui::Div {
+    div()
+        .px_1()
+        .rounded_sm()
+        .border_1()
+        .border_color(color)
+        .child(Label::new(label).text_xs().text_color(color))
+}
+
+/// Badge of a protected server; `None` when writes are unrestricted
+pub(crate) fn render_safety_badge(level: SafetyLevel, cx: &App) -> Option<gpui::Div> {
+    level
+        .is_protected()
+        .then(|| render_badge(safety_label(level, cx), safety_color(level, cx)))
+}
+
+/// Prompt for the server name before a protected write
+struct WriteConfirmView {
+    /// Name the user has to type
+    expected: String,
+    action: String,
+    input_state: Entity<InputState>,
+    /// Mismatch message after a failed confirmation
+    error: Option<String>,
+}
+
+impl WriteConfirmView {
+    fn new(expected: String, action: String, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let input_state = cx.new(|cx| InputState::new(window, cx).placeholder(expected.clone()));
+        Self {
+            expected,
+            action,
+            input_state,
+            error: None,
+        }
+    }
+
+    fn typed(&self, cx: &App) -> String {
+        self.input_state.read(cx).value().to_string()
+    }
+}
+
+impl Render for WriteConfirmView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let locale = cx.global::<DfcGlobalStore>().read(cx).locale().to_string();
+        v_flex()
+            .w_full()
+            .gap_3()
+            .child(
+                Label::new(t!("write_guard.protected", locale = &locale).to_string())
+                    .text_sm()
+                    .text_color(cx.theme().danger),
+            )
+            .child(
+                v_flex()
+                    .w_full()
+                    .p_2()
+                    .rounded_md()
+                    .bg(cx.theme().secondary)
+                    .child(Label::new(self.action.clone()).text_sm()),
+            )
+            .child(
+                Label::new(
+                    t!(
+                        "write_guard.type_name",
+                        name = &self.expected,
+                        locale = &locale
+                    )
+                    .to_string(),
+                )
+                .text_sm(),
+            )
+            .child(Input::new(&self.input_state))
+            .when_some(self.error.clone(), |this, error| {
+                this.child(Label::new(error).text_xs().text_color(cx.theme().danger))
+            })
+    }
+}